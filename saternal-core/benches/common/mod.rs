@@ -0,0 +1,95 @@
+//! vtebench-style workloads shared by the benchmarks
+//!
+//! Each one stresses a different part of the pipeline, after the
+//! benchmarks in alacritty/vtebench: plain text, heavy SGR use, wide
+//! characters, scrolling, and cursor motion. Output is deterministic and
+//! contains no BEL, which the PTY benchmark uses as its end marker.
+
+/// Roughly how many bytes each workload produces
+const WORKLOAD_SIZE: usize = 256 * 1024;
+
+/// Named workloads
+pub fn workloads(cols: usize, rows: usize) -> Vec<(&'static str, Vec<u8>)> {
+    vec![
+        ("dense_cells", dense_cells(cols)),
+        ("sgr_colors", sgr_colors(cols)),
+        ("unicode", unicode(cols)),
+        ("scrolling", scrolling()),
+        ("cursor_motion", cursor_motion(cols, rows)),
+    ]
+}
+
+/// Every cell filled with printable ASCII, line after line
+fn dense_cells(cols: usize) -> Vec<u8> {
+    let mut out = Vec::with_capacity(WORKLOAD_SIZE);
+    let mut c = b'!';
+    while out.len() < WORKLOAD_SIZE {
+        for _ in 0..cols {
+            out.push(c);
+            c = if c == b'~' { b'!' } else { c + 1 };
+        }
+        out.extend_from_slice(b"\r\n");
+    }
+    out
+}
+
+/// A color change (indexed, truecolor, bold, underline) before every character
+fn sgr_colors(cols: usize) -> Vec<u8> {
+    let mut out = Vec::with_capacity(WORKLOAD_SIZE);
+    let mut n: usize = 0;
+    while out.len() < WORKLOAD_SIZE {
+        for col in 0..cols {
+            n += 1;
+            let sgr = match n % 4 {
+                0 => format!("\x1b[38;5;{}m", n % 256),
+                1 => format!("\x1b[38;2;{};{};{}m", n % 256, (n * 7) % 256, (n * 13) % 256),
+                2 => format!("\x1b[1;48;5;{}m", (n * 3) % 256),
+                _ => "\x1b[0;4m".to_string(),
+            };
+            out.extend_from_slice(sgr.as_bytes());
+            out.push(b'a' + (col % 26) as u8);
+        }
+        out.extend_from_slice(b"\x1b[0m\r\n");
+    }
+    out
+}
+
+/// Wide and multi-byte characters
+fn unicode(cols: usize) -> Vec<u8> {
+    const SAMPLES: &[&str] = &["漢", "字", "é", "ß", "→", "░", "λ", "한", "▓", "ü"];
+    let mut out = Vec::with_capacity(WORKLOAD_SIZE);
+    while out.len() < WORKLOAD_SIZE {
+        for i in 0..cols / 2 {
+            out.extend_from_slice(SAMPLES[i % SAMPLES.len()].as_bytes());
+        }
+        out.extend_from_slice(b"\r\n");
+    }
+    out
+}
+
+/// Short lines that scroll the screen on every newline
+fn scrolling() -> Vec<u8> {
+    let mut out = Vec::with_capacity(WORKLOAD_SIZE);
+    let mut line = 0;
+    while out.len() < WORKLOAD_SIZE {
+        out.extend_from_slice(format!("line {}\r\n", line).as_bytes());
+        line += 1;
+    }
+    out
+}
+
+/// Characters written at pseudo-random positions (CUP)
+fn cursor_motion(cols: usize, rows: usize) -> Vec<u8> {
+    let mut out = Vec::with_capacity(WORKLOAD_SIZE);
+    let mut seed: u32 = 0x2545_f491;
+    while out.len() < WORKLOAD_SIZE {
+        // xorshift32
+        seed ^= seed << 13;
+        seed ^= seed >> 17;
+        seed ^= seed << 5;
+        let row = seed as usize % rows + 1;
+        let col = (seed >> 16) as usize % cols + 1;
+        out.extend_from_slice(format!("\x1b[{};{}H{}", row, col, (b'A' + (seed % 26) as u8) as char).as_bytes());
+    }
+    out
+}
@@ -0,0 +1,54 @@
+//! PTY throughput: a shell cats each workload and the parser thread takes it
+//! all in, end to end through the same Terminal the app uses
+//!
+//! A BEL printed after the workload marks the end of each run.
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use saternal_core::Terminal;
+use std::time::{Duration, Instant};
+
+mod common;
+
+const COLS: usize = 120;
+const ROWS: usize = 40;
+
+/// Give up on a run after this long (the shell died or never printed the marker)
+const RUN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Send `command` and wait until the bell after it rings
+fn run(terminal: &mut Terminal, command: &str) {
+    terminal.write_input(format!("{}; printf '\\a'\n", command).as_bytes()).unwrap();
+    let deadline = Instant::now() + RUN_TIMEOUT;
+    while !terminal.take_bell() {
+        assert!(Instant::now() < deadline, "no bell after `{}`", command);
+        std::thread::yield_now();
+    }
+    terminal.process_output().unwrap();
+}
+
+fn pty_throughput(c: &mut Criterion) {
+    let dir = std::env::temp_dir().join(format!("saternal-bench-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let mut terminal = Terminal::new(COLS, ROWS, Some("/bin/sh".to_string())).unwrap();
+    // Wait for the shell, and keep echoed commands out of the measurement
+    run(&mut terminal, "stty -echo");
+
+    let mut group = c.benchmark_group("pty_throughput");
+    for (name, workload) in common::workloads(COLS, ROWS) {
+        let path = dir.join(name);
+        std::fs::write(&path, &workload).unwrap();
+        let command = format!("cat '{}'", path.display());
+        group.throughput(Throughput::Bytes(workload.len() as u64));
+        group.bench_function(name, |b| b.iter(|| run(&mut terminal, &command)));
+    }
+    group.finish();
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(20);
+    targets = pty_throughput
+}
+criterion_main!(benches);
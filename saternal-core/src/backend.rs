@@ -0,0 +1,110 @@
+/// What a frontend draws the terminal engine with
+///
+/// Panes, terminals, selection, and search live in this crate and need no GPU.
+/// `saternal-renderer` implements this trait on wgpu; a CPU or skia renderer,
+/// or a test double that records what it was asked to draw, can stand in.
+use crate::geometry::Padding;
+use crate::pane::PaneNode;
+use crate::theme::ColorPalette;
+use anyhow::Result;
+
+pub trait RendererBackend {
+    /// Draw every pane in the tree as one frame
+    fn render_panes(&mut self, pane_tree: &PaneNode) -> Result<()>;
+
+    /// Frame size in physical pixels
+    fn size(&self) -> (u32, u32);
+
+    /// Resize the frame (physical pixels)
+    fn resize(&mut self, width: u32, height: u32);
+
+    /// Cell (width, height, baseline offset) in physical pixels
+    fn cell_metrics(&self) -> (f32, f32, f32);
+
+    /// Space between each pane's edges and its grid
+    fn padding(&self) -> Padding;
+
+    /// Change the logical font size (points, before the scale factor)
+    fn set_font_size(&mut self, font_size: f32) -> Result<()>;
+
+    /// Display scale factor the cells are currently sized for
+    fn scale_factor(&self) -> f64;
+
+    /// Follow the display's scale factor (1.0, 2.0, ...)
+    fn set_scale_factor(&mut self, scale_factor: f64) -> Result<()>;
+
+    /// Colors for chrome drawn outside the panes (panes use their terminal's palette)
+    fn set_palette(&mut self, palette: ColorPalette);
+
+    /// Columns and rows the whole frame holds at the current cell size
+    fn grid_size(&self) -> (usize, usize) {
+        let (width, height) = self.size();
+        let (cell_width, cell_height, _) = self.cell_metrics();
+        self.padding().grid_size(width, height, cell_width, cell_height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fixed-size backend that draws nothing
+    struct NullBackend {
+        size: (u32, u32),
+        font_size: f32,
+        scale_factor: f64,
+    }
+
+    impl RendererBackend for NullBackend {
+        fn render_panes(&mut self, _pane_tree: &PaneNode) -> Result<()> {
+            Ok(())
+        }
+
+        fn size(&self) -> (u32, u32) {
+            self.size
+        }
+
+        fn resize(&mut self, width: u32, height: u32) {
+            self.size = (width, height);
+        }
+
+        fn cell_metrics(&self) -> (f32, f32, f32) {
+            let scale = self.scale_factor as f32;
+            (self.font_size * 0.6 * scale, self.font_size * 1.25 * scale, self.font_size * scale)
+        }
+
+        fn padding(&self) -> Padding {
+            Padding { top: 5.0, left: 10.0, right: 10.0, bottom: 10.0 }
+        }
+
+        fn set_font_size(&mut self, font_size: f32) -> Result<()> {
+            self.font_size = font_size;
+            Ok(())
+        }
+
+        fn scale_factor(&self) -> f64 {
+            self.scale_factor
+        }
+
+        fn set_scale_factor(&mut self, scale_factor: f64) -> Result<()> {
+            self.scale_factor = scale_factor;
+            Ok(())
+        }
+
+        fn set_palette(&mut self, _palette: ColorPalette) {}
+    }
+
+    #[test]
+    fn test_grid_size_follows_font_and_scale() {
+        let mut backend = NullBackend { size: (820, 415), font_size: 10.0, scale_factor: 1.0 };
+        // 6x12.5 cells inside 10px side and 5/10px top/bottom padding
+        assert_eq!(backend.grid_size(), (133, 32));
+
+        backend.set_scale_factor(2.0).unwrap();
+        backend.resize(1640, 830);
+        assert_eq!(backend.grid_size(), (135, 32));
+
+        backend.set_font_size(20.0).unwrap();
+        assert_eq!(backend.grid_size(), (67, 16));
+    }
+}
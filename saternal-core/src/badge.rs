@@ -0,0 +1,123 @@
+/// Pane badges: a large translucent label in the corner of a pane
+///
+/// Programs set a pane's badge with iTerm2's escape,
+/// `ESC ] 1337 ; SetBadgeFormat = <base64 text> BEL`; an empty payload
+/// removes it. The default badge comes from `appearance.badge`, where
+/// `{hostname}` and `{user}` are filled in.
+use base64::Engine;
+
+const BADGE_PREFIX: &[u8] = b"\x1b]1337;SetBadgeFormat=";
+/// Longest payload kept while waiting for its terminator
+const MAX_BADGE_PAYLOAD: usize = 4096;
+
+/// Finds SetBadgeFormat sequences in PTY output, including ones split across reads
+#[derive(Debug, Default)]
+pub struct BadgeScanner {
+    carry: Vec<u8>,
+}
+
+impl BadgeScanner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Scan a chunk of output; returns the badges it sets, in order
+    /// ("" removes the badge)
+    pub fn scan(&mut self, bytes: &[u8]) -> Vec<String> {
+        let mut window = std::mem::take(&mut self.carry);
+        window.extend_from_slice(bytes);
+
+        let mut badges = Vec::new();
+        let mut i = 0;
+        while let Some(offset) = find(&window[i..], BADGE_PREFIX) {
+            let start = i + offset + BADGE_PREFIX.len();
+            let Some((end, terminator_len)) = terminator(&window[start..]) else {
+                // Wait for the rest, unless this can't be a badge anymore
+                if window.len() - start <= MAX_BADGE_PAYLOAD {
+                    self.carry = window[i + offset..].to_vec();
+                }
+                return badges;
+            };
+            if let Some(badge) = decode(&window[start..start + end]) {
+                badges.push(badge);
+            }
+            i = start + end + terminator_len;
+        }
+
+        // Keep a possible partial prefix at the end for the next read
+        let tail_start = window.len().saturating_sub(BADGE_PREFIX.len()).max(i);
+        if let Some(start) = (tail_start..window.len()).find(|&j| BADGE_PREFIX.starts_with(&window[j..])) {
+            self.carry = window[start..].to_vec();
+        }
+        badges
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+/// Position and length of the OSC terminator (BEL or ESC \)
+fn terminator(bytes: &[u8]) -> Option<(usize, usize)> {
+    bytes.iter().enumerate().find_map(|(i, &b)| match b {
+        0x07 => Some((i, 1)),
+        0x1b if bytes.get(i + 1) == Some(&b'\\') => Some((i, 2)),
+        _ => None,
+    })
+}
+
+fn decode(payload: &[u8]) -> Option<String> {
+    let bytes = base64::engine::general_purpose::STANDARD.decode(payload).ok()?;
+    let text = String::from_utf8(bytes).ok()?;
+    Some(text.trim().to_string())
+}
+
+/// Fill in `{hostname}` and `{user}` in a configured badge
+pub fn expand_badge(format: &str, hostname: &str, user: &str) -> String {
+    format.replace("{hostname}", hostname).replace("{user}", user)
+}
+
+/// Short host name of this machine (without the domain)
+#[cfg(unix)]
+pub fn hostname() -> String {
+    let mut buf = [0u8; 256];
+    let ok = unsafe { libc::gethostname(buf.as_mut_ptr().cast(), buf.len()) } == 0;
+    if !ok {
+        return String::new();
+    }
+    let end = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    let name = String::from_utf8_lossy(&buf[..end]);
+    name.split('.').next().unwrap_or_default().to_string()
+}
+
+/// NetBIOS name of this machine, which never has a domain
+#[cfg(windows)]
+pub fn hostname() -> String {
+    std::env::var("COMPUTERNAME").unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scans_badges_split_across_reads() {
+        let mut scanner = BadgeScanner::new();
+        // "prod" in base64, terminated by BEL, then cleared with ESC \
+        assert_eq!(
+            scanner.scan(b"ls\r\n\x1b]1337;SetBadgeFormat=cHJvZA==\x07\x1b]1337;SetBadgeFormat=\x1b\\$ "),
+            vec!["prod".to_string(), String::new()]
+        );
+        assert!(scanner.scan(b"\x1b]1337;SetBad").is_empty());
+        assert!(scanner.scan(b"geFormat=ZGV2").is_empty());
+        assert_eq!(scanner.scan(b"\x07"), vec!["dev".to_string()]);
+        // Not base64: ignored
+        assert!(scanner.scan(b"\x1b]1337;SetBadgeFormat=!!\x07").is_empty());
+    }
+
+    #[test]
+    fn test_expand_badge() {
+        assert_eq!(expand_badge("{user}@{hostname}", "box", "sam"), "sam@box");
+        assert_eq!(expand_badge("PROD", "box", "sam"), "PROD");
+    }
+}
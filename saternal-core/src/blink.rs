@@ -0,0 +1,204 @@
+/// Blinking text (SGR 5/6)
+///
+/// The emulator drops the blink attribute, so the scanner finds SGR
+/// sequences that turn it on or off and the terminal sets a spare cell flag
+/// on the cursor template right after each one is parsed. Cells printed
+/// from then on carry the flag; SGR 0, RIS, and erased cells clear it the
+/// same way they clear bold or underline.
+use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::index::{Column, Line};
+use alacritty_terminal::term::cell::Flags;
+use alacritty_terminal::term::Term;
+use std::time::{Duration, Instant};
+
+/// Cell flag for blinking text (a bit the emulator leaves unused)
+pub const BLINK: Flags = Flags::from_bits_retain(1 << 15);
+
+/// Where the scanner is inside an escape sequence
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScanState {
+    Text,
+    Escape,
+    /// CSI parameters; `plain` is false once a private marker or intermediate shows up
+    Csi { plain: bool },
+}
+
+/// Finds SGR sequences that change the blink attribute in PTY output
+#[derive(Debug)]
+pub struct BlinkScanner {
+    state: ScanState,
+    params: Vec<u8>,
+}
+
+impl Default for BlinkScanner {
+    fn default() -> Self {
+        Self { state: ScanState::Text, params: Vec::new() }
+    }
+}
+
+impl BlinkScanner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Scan a chunk of output; sequences may be split across chunks
+    ///
+    /// Returns `(offset, blinking)` pairs, where `offset` is just past the
+    /// sequence's final byte and `blinking` is the attribute after it.
+    pub fn scan(&mut self, bytes: &[u8]) -> Vec<(usize, bool)> {
+        let mut changes = Vec::new();
+        for (i, &byte) in bytes.iter().enumerate() {
+            self.state = match (self.state, byte) {
+                (_, 0x1b) => ScanState::Escape,
+                (ScanState::Escape, b'[') => {
+                    self.params.clear();
+                    ScanState::Csi { plain: true }
+                }
+                (ScanState::Csi { plain }, b'0'..=b'9' | b';' | b':') => {
+                    self.params.push(byte);
+                    ScanState::Csi { plain }
+                }
+                // Private markers (`?`, `>`) and intermediates make it something other than SGR
+                (ScanState::Csi { .. }, 0x20..=0x2f | 0x3c..=0x3f) => ScanState::Csi { plain: false },
+                (ScanState::Csi { plain }, b'm') => {
+                    if plain {
+                        if let Some(blinking) = sgr_blink(&self.params) {
+                            changes.push((i + 1, blinking));
+                        }
+                    }
+                    ScanState::Text
+                }
+                // Another final byte, or CAN/SUB cancelling the sequence
+                (ScanState::Csi { .. }, 0x40..=0x7e | 0x18 | 0x1a) => ScanState::Text,
+                (ScanState::Csi { plain }, _) => ScanState::Csi { plain },
+                _ => ScanState::Text,
+            };
+        }
+        changes
+    }
+}
+
+/// Blink attribute after an SGR sequence with these parameters, if it touches it
+fn sgr_blink(params: &[u8]) -> Option<bool> {
+    let params: Vec<&[u8]> = params.split(|&b| b == b';').collect();
+    let mut blinking = None;
+    let mut i = 0;
+    while i < params.len() {
+        let param = params[i];
+        i += 1;
+        // Colon sub-parameters (38:2::r:g:b, 4:3) carry their own arguments
+        if param.contains(&b':') {
+            continue;
+        }
+        match std::str::from_utf8(param).ok().and_then(|p| p.parse::<u16>().ok()).unwrap_or(0) {
+            0 | 25 => blinking = Some(false),
+            5 | 6 => blinking = Some(true),
+            // Extended colors: 5;n (indexed) or 2;r;g;b
+            38 | 48 | 58 => match params.get(i).copied() {
+                Some(b"5") => i += 2,
+                Some(b"2") => i += 4,
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+    blinking
+}
+
+/// Whether any cell on screen (scrolled back `scroll_offset` lines) is blinking
+pub fn has_blinking_text<T>(term: &Term<T>, scroll_offset: usize) -> bool {
+    let grid = term.grid();
+    let scroll_offset = scroll_offset.min(grid.history_size()) as i32;
+    (0..term.screen_lines() as i32).any(|row| {
+        let line = Line(row - scroll_offset);
+        (0..term.columns()).any(|col| grid[line][Column(col)].flags.contains(BLINK))
+    })
+}
+
+/// Blink phase shared by blinking text: on for one interval, off for the next
+#[derive(Debug, Clone, Copy)]
+pub struct BlinkClock {
+    start: Instant,
+    interval: Duration,
+    enabled: bool,
+}
+
+impl BlinkClock {
+    pub fn new(interval_ms: u64, enabled: bool) -> Self {
+        Self {
+            start: Instant::now(),
+            interval: Duration::from_millis(interval_ms.max(1)),
+            enabled,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Time since the clock started
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    /// Blinking text is drawn (always, when blinking is disabled)
+    pub fn is_visible(&self) -> bool {
+        !self.enabled || (self.start.elapsed().as_millis() / self.interval.as_millis()).is_multiple_of(2)
+    }
+
+    /// When the phase next flips
+    pub fn next_toggle(&self) -> Instant {
+        let interval = self.interval.as_millis();
+        let phases = self.start.elapsed().as_millis() / interval + 1;
+        self.start + Duration::from_millis((phases * interval) as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_finds_blink_changes() {
+        let mut scanner = BlinkScanner::new();
+        let out = b"\x1b[1;5mhot\x1b[25m \x1b[38;5;5mcolor\x1b[?25l\x1b[5;0mx\x1b[48:2::1:5:6m";
+        assert_eq!(scanner.scan(out), vec![(6, true), (14, false), (41, false)]);
+    }
+
+    #[test]
+    fn test_sequence_split_across_reads() {
+        let mut scanner = BlinkScanner::new();
+        assert!(scanner.scan(b"a\x1b[").is_empty());
+        assert_eq!(scanner.scan(b"6mb"), vec![(2, true)]);
+        assert_eq!(scanner.scan(b"\x1b[m"), vec![(3, false)]);
+    }
+
+    #[test]
+    fn test_template_flag_marks_printed_cells() {
+        use alacritty_terminal::event::VoidListener;
+        use alacritty_terminal::term::{test::TermSize, Config};
+        use alacritty_terminal::vte::ansi::Processor;
+
+        let mut term = Term::new(Config::default(), &TermSize::new(10, 2), VoidListener);
+        let mut processor: Processor = Processor::new();
+        term.grid_mut().cursor.template.flags.insert(BLINK);
+        processor.advance(&mut term, b"ab\x1b[1mc\x1b[0md");
+
+        let blinking: Vec<bool> =
+            (0..4).map(|col| term.grid()[Line(0)][Column(col)].flags.contains(BLINK)).collect();
+        assert_eq!(blinking, vec![true, true, true, false]);
+        assert!(has_blinking_text(&term, 0));
+    }
+
+    #[test]
+    fn test_disabled_clock_is_always_visible() {
+        let clock = BlinkClock::new(1, false);
+        std::thread::sleep(Duration::from_millis(3));
+        assert!(clock.is_visible());
+        assert!(clock.next_toggle() > Instant::now());
+    }
+}
@@ -0,0 +1,215 @@
+/// History of text copied from Saternal, with a picker to paste older entries
+///
+/// Entries live in memory, most recent first. Persistence is opt-in
+/// (`clipboard.persist_history`) since copies often contain secrets.
+use crate::history::write_private;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+
+/// Default number of remembered copies
+pub const DEFAULT_CLIPBOARD_HISTORY_SIZE: usize = 20;
+
+/// On-disk format (TOML keeps multi-line entries intact)
+#[derive(Serialize, Deserialize)]
+struct StoredHistory {
+    entries: Vec<String>,
+}
+
+/// Recent copies, most recent first, plus the picker's selection
+#[derive(Debug)]
+pub struct ClipboardHistory {
+    entries: VecDeque<String>,
+    capacity: usize,
+    path: Option<PathBuf>,
+    /// Index of the highlighted entry while the picker is open
+    picker: Option<usize>,
+}
+
+impl Default for ClipboardHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_CLIPBOARD_HISTORY_SIZE)
+    }
+}
+
+impl ClipboardHistory {
+    /// In-memory history holding up to `capacity` entries
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            capacity: capacity.max(1),
+            path: None,
+            picker: None,
+        }
+    }
+
+    /// Default persistence file (~/.config/saternal/clipboard_history.toml)
+    pub fn default_path() -> Option<PathBuf> {
+        std::env::var_os("HOME").map(|home| {
+            let mut path = PathBuf::from(home);
+            path.push(".config");
+            path.push("saternal");
+            path.push("clipboard_history.toml");
+            path
+        })
+    }
+
+    /// Load a persisted history (a missing file is an empty history)
+    pub fn load(path: impl Into<PathBuf>, capacity: usize) -> anyhow::Result<Self> {
+        let path = path.into();
+        let mut history = Self::new(capacity);
+        if path.exists() {
+            let stored: StoredHistory = toml::from_str(&std::fs::read_to_string(&path)?)?;
+            // Stored most recent first - push oldest first to keep the order
+            for entry in stored.entries.iter().rev() {
+                history.push(entry);
+            }
+        }
+        history.path = Some(path);
+        Ok(history)
+    }
+
+    /// Write the history back to the file it was loaded from (no-op when in memory only)
+    pub fn save(&self) -> anyhow::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let stored = StoredHistory {
+            entries: self.entries.iter().cloned().collect(),
+        };
+        // Owner-only, like command history
+        write_private(path, toml::to_string(&stored)?.as_bytes())
+    }
+
+    /// Record a copy, moving repeats to the front
+    /// Returns true if the history changed
+    pub fn push(&mut self, text: &str) -> bool {
+        if text.trim().is_empty() || self.entries.front().map(String::as_str) == Some(text) {
+            return false;
+        }
+        self.entries.retain(|entry| entry != text);
+        self.entries.push_front(text.to_string());
+        self.entries.truncate(self.capacity);
+        true
+    }
+
+    /// Entry by recency (0 = most recent)
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.entries.get(index).map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Open the picker on the entry before the current clipboard (falls back to the latest)
+    /// Returns false if there is nothing to pick
+    pub fn open_picker(&mut self) -> bool {
+        if self.entries.is_empty() {
+            return false;
+        }
+        self.picker = Some(if self.entries.len() > 1 { 1 } else { 0 });
+        true
+    }
+
+    pub fn close_picker(&mut self) {
+        self.picker = None;
+    }
+
+    pub fn is_picking(&self) -> bool {
+        self.picker.is_some()
+    }
+
+    /// Highlight the next older entry (wraps around)
+    pub fn picker_next(&mut self) {
+        if let Some(index) = &mut self.picker {
+            *index = (*index + 1) % self.entries.len();
+        }
+    }
+
+    /// Highlight the next newer entry (wraps around)
+    pub fn picker_prev(&mut self) {
+        if let Some(index) = &mut self.picker {
+            *index = (*index + self.entries.len() - 1) % self.entries.len();
+        }
+    }
+
+    /// Highlighted entry and its index while the picker is open
+    pub fn picked(&self) -> Option<(usize, &str)> {
+        let index = self.picker?;
+        self.get(index).map(|entry| (index, entry))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_dedups_and_caps() {
+        let mut history = ClipboardHistory::new(3);
+        for text in ["a", "b", "c", "a", "d"] {
+            history.push(text);
+        }
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.get(0), Some("d"));
+        assert_eq!(history.get(1), Some("a"));
+        assert_eq!(history.get(2), Some("c"));
+        assert!(!history.push("d"));
+        assert!(!history.push("  \n"));
+    }
+
+    #[test]
+    fn test_picker_starts_on_previous_copy_and_wraps() {
+        let mut history = ClipboardHistory::new(10);
+        assert!(!history.open_picker());
+
+        history.push("old");
+        history.push("new");
+        assert!(history.open_picker());
+        assert_eq!(history.picked(), Some((1, "old")));
+
+        history.picker_next();
+        assert_eq!(history.picked(), Some((0, "new")));
+        history.picker_prev();
+        assert_eq!(history.picked(), Some((1, "old")));
+
+        history.close_picker();
+        assert!(history.picked().is_none());
+    }
+
+    #[test]
+    fn test_persistence_round_trip() {
+        let path = std::env::temp_dir().join(format!("saternal-clipboard-{}.toml", std::process::id()));
+        let mut history = ClipboardHistory::load(&path, 10).unwrap();
+        history.push("first");
+        history.push("multi\nline");
+        history.save().unwrap();
+
+        let loaded = ClipboardHistory::load(&path, 10).unwrap();
+        assert_eq!(loaded.get(0), Some("multi\nline"));
+        assert_eq!(loaded.get(1), Some("first"));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_saved_history_is_private_to_the_user() {
+        use std::os::unix::fs::PermissionsExt;
+        let path = std::env::temp_dir().join(format!("saternal-clipboard-private-{}.toml", std::process::id()));
+        // Left world-readable by an older version
+        std::fs::write(&path, "entries = [\"old\"]\n").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+        let mut history = ClipboardHistory::load(&path, 10).unwrap();
+        history.push("hunter2");
+        history.save().unwrap();
+        assert_eq!(path.metadata().unwrap().permissions().mode() & 0o777, 0o600);
+        assert_eq!(ClipboardHistory::load(&path, 10).unwrap().get(0), Some("hunter2"));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
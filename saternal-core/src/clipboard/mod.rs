@@ -1,6 +1,10 @@
 /// Clipboard integration for copy/paste support
+mod history;
+pub mod paste;
 mod platform;
 
+pub use history::{ClipboardHistory, DEFAULT_CLIPBOARD_HISTORY_SIZE};
+pub use paste::PasteTransform;
 pub use platform::Clipboard;
 
 /// Check if text should use bracketed paste mode
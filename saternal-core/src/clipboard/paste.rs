@@ -0,0 +1,163 @@
+/// Paste cleanup: rewrites for text copied from shell examples
+///
+/// Docs and READMEs show commands as `$ cargo build \` with the rest of the
+/// command on indented lines below. Pasted as is, the shell tries to run `$`
+/// and prints a continuation prompt for every line. Each transform fixes one
+/// of these, and `clean_up` runs the enabled ones in order.
+use crate::secure_input::PASTE_CONFIRM_WINDOW;
+
+/// Prompt markers that start a line of a copied shell example
+/// `#` is left alone: in most examples it starts a comment, not a root prompt
+const PROMPTS: [&str; 3] = ["$ ", "% ", "❯ "];
+
+/// One rewrite in the paste cleanup pipeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasteTransform {
+    /// Drop the `$ ` / `% ` / `❯ ` prompt at the start of each command line
+    StripPrompts,
+    /// Join lines ending in `\` with the line after them
+    JoinContinuations,
+}
+
+impl PasteTransform {
+    /// The rewritten text and how many lines were changed
+    fn apply(self, text: &str) -> (String, usize) {
+        match self {
+            Self::StripPrompts => strip_prompts(text),
+            Self::JoinContinuations => join_continuations(text),
+        }
+    }
+}
+
+/// Pasted text after the cleanup pipeline ran over it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PasteCleanup {
+    pub text: String,
+    /// Prompts removed
+    pub prompts: usize,
+    /// Continuation lines joined onto the line before
+    pub joined: usize,
+}
+
+/// Run `transforms` over `text` in order; None when none of them changed anything
+pub fn clean_up(text: &str, transforms: &[PasteTransform]) -> Option<PasteCleanup> {
+    let mut cleanup = PasteCleanup { text: text.to_string(), prompts: 0, joined: 0 };
+    for &transform in transforms {
+        let (rewritten, changed) = transform.apply(&cleanup.text);
+        cleanup.text = rewritten;
+        match transform {
+            PasteTransform::StripPrompts => cleanup.prompts += changed,
+            PasteTransform::JoinContinuations => cleanup.joined += changed,
+        }
+    }
+    (cleanup.prompts + cleanup.joined > 0).then_some(cleanup)
+}
+
+/// Offer printed when a paste that could be cleaned up is held back
+pub fn cleanup_offer(cleanup: &PasteCleanup) -> String {
+    let mut changes = Vec::new();
+    if cleanup.prompts > 0 {
+        changes.push(format!("strip {} prompt{}", cleanup.prompts, plural(cleanup.prompts)));
+    }
+    if cleanup.joined > 0 {
+        changes.push(format!("join {} continued line{}", cleanup.joined, plural(cleanup.joined)));
+    }
+    format!(
+        "Paste looks like a shell example. Paste again within {}s to {}, or Cmd+Alt+V to paste it as is.",
+        PASTE_CONFIRM_WINDOW.as_secs(),
+        changes.join(" and ")
+    )
+}
+
+fn plural(count: usize) -> &'static str {
+    if count == 1 { "" } else { "s" }
+}
+
+/// Bytes taken by the indentation and prompt of a command line
+fn prompt_len(line: &str) -> Option<usize> {
+    let rest = line.trim_start();
+    let indent = line.len() - rest.len();
+    PROMPTS
+        .iter()
+        .find(|prompt| rest.starts_with(*prompt) && !rest[prompt.len()..].trim().is_empty())
+        .map(|prompt| indent + prompt.len())
+}
+
+/// Only text whose first line is a prompted command counts as an example;
+/// lines without a prompt (output, continuations) are kept as they are
+fn strip_prompts(text: &str) -> (String, usize) {
+    let first = text.lines().find(|line| !line.trim().is_empty());
+    if first.and_then(prompt_len).is_none() {
+        return (text.to_string(), 0);
+    }
+    let mut stripped = 0;
+    let text = text
+        .split_inclusive('\n')
+        .map(|line| match prompt_len(line) {
+            Some(len) => {
+                stripped += 1;
+                &line[len..]
+            }
+            None => line,
+        })
+        .collect();
+    (text, stripped)
+}
+
+/// A line ending in an odd number of backslashes continues on the next one
+/// (an even number is escaped backslashes). The last line never continues.
+fn join_continuations(text: &str) -> (String, usize) {
+    let mut joined = 0;
+    let mut out = String::with_capacity(text.len());
+    let mut continuing = false;
+    let mut lines = text.split_inclusive('\n').peekable();
+    while let Some(line) = lines.next() {
+        let line = if continuing { line.trim_start_matches([' ', '\t']) } else { line };
+        let body = line.trim_end();
+        let backslashes = body.len() - body.trim_end_matches('\\').len();
+        if backslashes % 2 == 1 && line.ends_with('\n') && lines.peek().is_some() {
+            out.push_str(body[..body.len() - 1].trim_end());
+            out.push(' ');
+            joined += 1;
+            continuing = true;
+        } else {
+            out.push_str(line);
+            continuing = false;
+        }
+    }
+    (out, joined)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [PasteTransform; 2] = [PasteTransform::StripPrompts, PasteTransform::JoinContinuations];
+
+    #[test]
+    fn test_strips_prompts_and_joins_continuations() {
+        let example = "$ cargo build \\\n    --release\n  $ ./target/release/app\n";
+        let cleanup = clean_up(example, &ALL).unwrap();
+        assert_eq!(cleanup.text, "cargo build --release\n./target/release/app\n");
+        assert_eq!((cleanup.prompts, cleanup.joined), (2, 1));
+        assert_eq!(
+            cleanup_offer(&cleanup),
+            "Paste looks like a shell example. Paste again within 3s to strip 2 prompts and join 1 continued line, or Cmd+Alt+V to paste it as is."
+        );
+
+        assert_eq!(clean_up("% ls\n❯ pwd", &ALL).unwrap().text, "ls\npwd");
+        let joined_only = clean_up(example, &[PasteTransform::JoinContinuations]).unwrap();
+        assert_eq!(joined_only.text, "$ cargo build --release\n  $ ./target/release/app\n");
+    }
+
+    #[test]
+    fn test_leaves_ordinary_text_alone() {
+        assert_eq!(clean_up("echo $HOME\n$ not a prompt here", &ALL), None);
+        assert_eq!(clean_up("# install\n$ make", &ALL), None);
+        assert_eq!(clean_up("$ \nls", &ALL), None);
+        // Escaped backslashes and a trailing one on the last line are kept
+        assert_eq!(clean_up("echo a\\\\\nb", &ALL), None);
+        assert_eq!(clean_up("echo a \\", &ALL), None);
+        assert_eq!(clean_up("$ ls", &[]), None);
+    }
+}
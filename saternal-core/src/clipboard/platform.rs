@@ -1,4 +1,5 @@
 /// Platform-specific clipboard implementation using arboard
+use crate::platform::ClipboardProvider;
 use anyhow::Result;
 
 /// Cross-platform clipboard
@@ -12,15 +13,17 @@ impl Clipboard {
         let ctx = arboard::Clipboard::new()?;
         Ok(Self { ctx })
     }
+}
 
+impl ClipboardProvider for Clipboard {
     /// Set clipboard text content
-    pub fn set_text(&mut self, text: &str) -> Result<()> {
+    fn set_text(&mut self, text: &str) -> Result<()> {
         self.ctx.set_text(text)?;
         Ok(())
     }
 
     /// Get clipboard text content
-    pub fn get_text(&mut self) -> Result<String> {
+    fn get_text(&mut self) -> Result<String> {
         let text = self.ctx.get_text()?;
         Ok(text)
     }
@@ -0,0 +1,89 @@
+/// Confirm-on-close for panes and windows with running programs
+///
+/// Closing something whose pane runs a program other than the shell (vim,
+/// ssh, a build) first shows a warning; repeating the close within
+/// CLOSE_CONFIRM_WINDOW goes through.
+use crate::process::ProcessInfo;
+use std::time::{Duration, Instant};
+
+/// How long a warned close stays armed
+pub const CLOSE_CONFIRM_WINDOW: Duration = Duration::from_secs(3);
+
+/// What a close request would end
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloseTarget {
+    Pane { tab: usize, pane: usize },
+    Window,
+}
+
+#[derive(Debug, Default)]
+pub struct CloseGuard {
+    armed: Option<(CloseTarget, Instant)>,
+}
+
+impl CloseGuard {
+    /// Whether the close can go ahead: nothing is running, or this repeats a
+    /// warned close of the same target in time. Otherwise arms the target.
+    pub fn check(&mut self, target: CloseTarget, busy: bool, now: Instant) -> bool {
+        if !busy {
+            return true;
+        }
+        if let Some((armed, since)) = self.armed.take() {
+            if armed == target && now.duration_since(since) <= CLOSE_CONFIRM_WINDOW {
+                return true;
+            }
+        }
+        self.armed = Some((target, now));
+        false
+    }
+}
+
+/// Warning listing the programs a close would kill
+pub fn close_warning(target: CloseTarget, processes: &[ProcessInfo]) -> String {
+    let names: Vec<String> = processes
+        .iter()
+        .map(|process| format!("{} (pid {})", process.name, process.pid))
+        .collect();
+    let (what, again) = match target {
+        CloseTarget::Pane { .. } => ("pane", "Ctrl+W"),
+        CloseTarget::Window => ("window", "close"),
+    };
+    format!(
+        "Still running: {}. {} again within {}s to close the {} anyway.",
+        names.join(", "),
+        again,
+        CLOSE_CONFIRM_WINDOW.as_secs(),
+        what
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_busy_close_needs_a_second_request() {
+        let mut guard = CloseGuard::default();
+        let pane = CloseTarget::Pane { tab: 0, pane: 1 };
+        let now = Instant::now();
+
+        assert!(guard.check(pane, false, now));
+        assert!(!guard.check(pane, true, now));
+        assert!(guard.check(pane, true, now + Duration::from_secs(1)));
+
+        // Confirmation is spent once used
+        assert!(!guard.check(pane, true, now + Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn test_confirmation_expires_and_is_per_target() {
+        let mut guard = CloseGuard::default();
+        let now = Instant::now();
+
+        assert!(!guard.check(CloseTarget::Window, true, now));
+        assert!(!guard.check(CloseTarget::Window, true, now + CLOSE_CONFIRM_WINDOW * 2));
+
+        assert!(!guard.check(CloseTarget::Pane { tab: 0, pane: 0 }, true, now));
+        assert!(!guard.check(CloseTarget::Pane { tab: 0, pane: 1 }, true, now));
+    }
+}
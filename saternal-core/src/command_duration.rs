@@ -0,0 +1,110 @@
+/// Run times shown at the right end of finished commands' prompt lines
+///
+/// Nothing is written into the grid. Each mark remembers where its command
+/// was typed, follows that line as output scrolls it into history, and is
+/// drawn over the line only while the line still holds the command.
+use alacritty_terminal::grid::{Dimensions, Grid};
+use alacritty_terminal::index::{Column, Line, Point};
+use alacritty_terminal::term::cell::{Cell, Flags};
+use std::time::Duration;
+
+use crate::shell_integration::format_duration;
+
+/// Commands quicker than this get no annotation
+pub const MIN_ANNOTATED_DURATION: Duration = Duration::from_secs(1);
+
+/// Marks kept per terminal; older commands lose their annotation
+pub(crate) const MAX_DURATION_MARKS: usize = 256;
+
+/// Where a finished command was typed, and its run time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DurationMark {
+    /// Grid line the command started on (negative = history), as of `history_size`
+    line: i32,
+    /// Column where the command started (OSC 133;B)
+    column: usize,
+    /// Scrollback length when the mark was recorded
+    history_size: usize,
+    command: String,
+    label: String,
+}
+
+impl DurationMark {
+    pub(crate) fn new(line: i32, column: usize, history_size: usize, command: String, duration: Duration) -> Self {
+        Self { line, column, history_size, command, label: format_duration(duration) }
+    }
+
+    /// Formatted run time, e.g. "2m 13s"
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Grid line to annotate now, or None when the command's line has left the
+    /// grid, was overwritten, or has no room left of the label
+    ///
+    /// Once the scrollback is full, lines rotate out without the history growing,
+    /// so the position can drift; checking the text keeps that from showing.
+    pub fn locate(&self, grid: &Grid<Cell>) -> Option<Line> {
+        let history_size = grid.history_size();
+        // Shrinking history means it was cleared
+        let scrolled = history_size.checked_sub(self.history_size)?;
+        let line = self.line - scrolled as i32;
+        if line < -(history_size as i32) || line >= grid.screen_lines() as i32 {
+            return None;
+        }
+
+        let columns = grid.columns();
+        let mut text = String::new();
+        for col in self.column.min(columns)..columns {
+            let cell = &grid[Point::new(Line(line), Column(col))];
+            if !cell.flags.contains(Flags::WIDE_CHAR_SPACER) {
+                text.push(if cell.c == '\0' { ' ' } else { cell.c });
+            }
+        }
+        let typed = text.trim();
+        let wrapped = grid[Point::new(Line(line), Column(columns - 1))].flags.contains(Flags::WRAPLINE);
+        let holds_command = !typed.is_empty()
+            && if wrapped { self.command.starts_with(typed) } else { self.command == typed };
+        let used = self.column + text.trim_end().chars().count();
+        (holds_command && used + 1 + self.label.chars().count() <= columns).then_some(Line(line))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alacritty_terminal::event::VoidListener;
+    use alacritty_terminal::term::test::TermSize;
+    use alacritty_terminal::term::{Config, Term};
+    use alacritty_terminal::vte::ansi::Processor;
+
+    fn term(columns: usize, lines: usize, output: &[u8]) -> Term<VoidListener> {
+        let mut term = Term::new(Config::default(), &TermSize::new(columns, lines), VoidListener);
+        let mut processor: Processor = Processor::new();
+        processor.advance(&mut term, output);
+        term
+    }
+
+    #[test]
+    fn test_mark_follows_its_line_into_history() {
+        let mark = DurationMark::new(0, 2, 0, "make".into(), Duration::from_secs(133));
+        assert_eq!(mark.label(), "2m 13s");
+        assert_eq!(mark.locate(term(20, 3, b"$ make\r\n").grid()), Some(Line(0)));
+
+        let term = term(20, 3, b"$ make\r\nbuilding\r\nlinking\r\ndone\r\n");
+        assert_eq!(mark.locate(term.grid()), Some(Line(-2)));
+    }
+
+    #[test]
+    fn test_mark_hides_when_the_line_changes() {
+        let mark = DurationMark::new(0, 2, 0, "make".into(), Duration::from_secs(5));
+        assert_eq!(mark.locate(term(20, 3, b"$ cargo\r\n").grid()), None);
+        assert_eq!(mark.locate(term(20, 3, b"$ make -j\r\n").grid()), None);
+        // History cleared since the mark was recorded
+        let mark = DurationMark::new(-1, 2, 4, "make".into(), Duration::from_secs(5));
+        assert_eq!(mark.locate(term(20, 3, b"$ make\r\n").grid()), None);
+        // No room for the label after a long command
+        let mark = DurationMark::new(0, 2, 0, "make all-the-things".into(), Duration::from_secs(5));
+        assert_eq!(mark.locate(term(22, 3, b"$ make all-the-things\r\n").grid()), None);
+    }
+}
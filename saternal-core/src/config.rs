@@ -1,8 +1,22 @@
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use anyhow::Context;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-use crate::renderer::cursor::CursorConfig;
-use crate::renderer::theme::ColorPalette;
+use crate::clipboard::{PasteTransform, DEFAULT_CLIPBOARD_HISTORY_SIZE};
+use crate::constants::{DEFAULT_SCROLL_DECELERATION, DEFAULT_SCROLL_MULTIPLIER};
+use crate::cursor::CursorConfig;
+use crate::theme::ColorPalette;
+use crate::wallpaper::WallpaperMode;
+use crate::font::Antialias;
+use crate::geometry::Padding;
+use crate::layout::LayoutNode;
+use crate::pane_log::LogFormat;
+use crate::search::SearchOptions;
+use crate::selection::{SelectionStyle, DEFAULT_WORD_CHARS};
+use crate::trigger::TriggerConfig;
+use crate::update::UpdateConfig;
 
 /// Configuration for Saternal
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -11,16 +25,179 @@ pub struct Config {
     pub hotkey: HotkeyConfig,
     pub appearance: AppearanceConfig,
     pub terminal: TerminalConfig,
+    #[serde(default)]
+    pub privacy: PrivacyConfig,
+    /// Default search options (regex, case sensitivity, whole word)
+    #[serde(default)]
+    pub search: SearchOptions,
+    #[serde(default)]
+    pub bell: BellConfig,
+    /// Alerts when a long command finishes out of sight
+    #[serde(default)]
+    pub command_alerts: CommandAlertConfig,
+    #[serde(default)]
+    pub clipboard: ClipboardConfig,
+    #[serde(default)]
+    pub selection: SelectionConfig,
+    #[serde(default)]
+    pub scrolling: ScrollingConfig,
+    #[serde(default)]
+    pub panes: PanesConfig,
+    /// GPU adapter choice (applies to windows opened after a change)
+    #[serde(default)]
+    pub renderer: RendererConfig,
+    #[serde(default)]
+    pub logging: LoggingConfig,
+    #[serde(default)]
+    pub updates: UpdateConfig,
+    /// Regexes matched against each line of output (highlight, notify, run)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub triggers: Vec<TriggerConfig>,
+    /// Named pane layouts opened with the `layout <name>` command
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub layouts: BTreeMap<String, LayoutNode>,
+    /// Named text blocks inserted with the Cmd+; picker or `snippet <name>`;
+    /// `{{name}}` and `{{name:default}}` placeholders are filled in first
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub snippets: BTreeMap<String, String>,
+}
+
+/// How the terminal window is presented
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WindowMode {
+    /// Borderless window that slides down from the top of the screen
+    #[default]
+    Dropdown,
+    /// Regular titled, resizable window
+    Normal,
+    /// Regular window in native macOS fullscreen
+    Fullscreen,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WindowConfig {
+    /// dropdown, normal, or fullscreen
+    #[serde(default)]
+    pub mode: WindowMode,
     /// Window width as percentage of screen width (0.0-1.0)
     pub width_percentage: f64,
     /// Window height as percentage of screen height (0.0-1.0)
     pub height_percentage: f64,
+    /// Heights (shares of the screen) Cmd+Shift+H cycles the dropdown through
+    #[serde(default = "default_height_presets")]
+    pub height_presets: Vec<f64>,
     /// Animation duration in milliseconds
     pub animation_duration_ms: u32,
+    /// Whole-window opacity, text included (0.1-1.0)
+    #[serde(default = "default_window_opacity")]
+    pub opacity: f64,
+    /// Window opacity in click-through glass mode (toggled with Cmd+Shift+`)
+    #[serde(default = "default_glass_opacity")]
+    pub glass_opacity: f64,
+    /// Pixels between each pane's edges and its text
+    #[serde(default)]
+    pub padding: Padding,
+    /// Shrink the dropdown to a whole number of rows so the last one isn't cut off
+    #[serde(default = "default_snap_to_cells")]
+    pub snap_to_cells: bool,
+    /// Use the top padding at the bottom too
+    #[serde(default)]
+    pub symmetric_padding: bool,
+    /// Hide the dropdown when another app takes focus (Cmd+Shift+P pins it open)
+    #[serde(default = "default_hide_on_focus_loss")]
+    pub hide_on_focus_loss: bool,
+    /// Show the dropdown on every Space at once; off moves it to the current one when shown (macOS)
+    #[serde(default = "default_can_join_all_spaces")]
+    pub can_join_all_spaces: bool,
+    /// Let the dropdown open over apps in native fullscreen (macOS)
+    #[serde(default = "default_appear_over_fullscreen")]
+    pub appear_over_fullscreen: bool,
+    /// Round the window's corners by this many pixels (0 = square)
+    #[serde(default)]
+    pub corner_radius: f32,
+    /// Accent outline around the whole window, in pixels (0 = none)
+    #[serde(default)]
+    pub border_width: f32,
+    /// RGBA of the window outline
+    #[serde(default = "default_window_border_color")]
+    pub border_color: [f32; 4],
+}
+
+impl WindowConfig {
+    /// Padding the grid is laid out with (symmetric_padding applied)
+    pub fn effective_padding(&self) -> Padding {
+        if self.symmetric_padding {
+            Padding { bottom: self.padding.top, ..self.padding }
+        } else {
+            self.padding
+        }
+    }
+
+    /// The preset after the current height, wrapping around to the smallest
+    /// None when no preset is a valid share of the screen
+    pub fn next_height_preset(&self) -> Option<f64> {
+        let mut presets: Vec<f64> = self
+            .height_presets
+            .iter()
+            .copied()
+            .filter(|share| (MIN_HEIGHT_PERCENTAGE..=1.0).contains(share))
+            .collect();
+        presets.sort_by(f64::total_cmp);
+        // A height dragged to within a hair of a preset counts as that preset
+        let current = self.height_percentage + 0.005;
+        presets.iter().copied().find(|&share| share > current).or(presets.first().copied())
+    }
+
+    /// Keep the height the dropdown was dragged to, as its share of the screen
+    pub fn set_dropdown_height(&mut self, height: f64, screen_height: f64) {
+        self.height_percentage = (height / screen_height).clamp(MIN_HEIGHT_PERCENTAGE, 1.0);
+    }
+
+    /// Which corners corner_radius rounds, as (top, bottom): the dropdown hangs
+    /// from the top of the screen and a fullscreen window has no corners
+    pub fn rounded_corners(&self) -> (bool, bool) {
+        match self.mode {
+            WindowMode::Dropdown => (false, true),
+            WindowMode::Normal => (true, true),
+            WindowMode::Fullscreen => (false, false),
+        }
+    }
+}
+
+/// Smallest share of the screen the dropdown can be resized to
+pub const MIN_HEIGHT_PERCENTAGE: f64 = 0.1;
+
+fn default_height_presets() -> Vec<f64> {
+    vec![0.3, 0.5, 0.9]
+}
+
+fn default_snap_to_cells() -> bool {
+    true
+}
+
+fn default_hide_on_focus_loss() -> bool {
+    true
+}
+
+fn default_can_join_all_spaces() -> bool {
+    true
+}
+
+fn default_appear_over_fullscreen() -> bool {
+    true
+}
+
+fn default_window_border_color() -> [f32; 4] {
+    [0.29, 0.56, 0.89, 0.8]  // The focused pane border's blue
+}
+
+fn default_window_opacity() -> f64 {
+    1.0
+}
+
+fn default_glass_opacity() -> f64 {
+    0.6
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,6 +206,15 @@ pub struct HotkeyConfig {
     pub toggle: String,
 }
 
+/// Color space of the rendered output
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ColorSpace {
+    #[default]
+    Srgb,
+    DisplayP3,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppearanceConfig {
     /// Color palette for theming
@@ -38,13 +224,52 @@ pub struct AppearanceConfig {
     pub font_family: String,
     /// Font size in points
     pub font_size: f32,
+    /// Cell height multiplier (1.0 = font default, 0.5-3.0)
+    #[serde(default = "default_spacing")]
+    pub line_height: f32,
+    /// Cell width multiplier (1.0 = font default, 0.5-3.0)
+    #[serde(default = "default_spacing")]
+    pub letter_spacing: f32,
+    /// Glyph antialiasing: "grayscale" or "subpixel" (RGB LCD panels)
+    #[serde(default)]
+    pub antialias: Antialias,
+    /// Round the font size and cell width to whole physical pixels and draw glyphs on
+    /// pixel boundaries; sharper text at non-integer scale factors, slightly different spacing
+    #[serde(default)]
+    pub pixel_snap: bool,
+    /// Present frames without waiting for vsync and draw right after each keypress and its
+    /// echo; lower typing latency at the cost of possible tearing and more GPU work
+    #[serde(default)]
+    pub low_latency: bool,
+    /// Color space the window's pixels are in: srgb (colors shown exactly) or display-p3
+    /// (palette colors taken as Display P3, more saturated on wide-gamut screens). macOS only
+    #[serde(default)]
+    pub color_space: ColorSpace,
     /// Background opacity (0.0-1.0)
     pub opacity: f32,
+    /// How much darker unfocused panes are drawn (0.0 = off, 0.2 = 20% darker, max 0.8)
+    #[serde(default)]
+    pub inactive_pane_dim: f32,
+    /// Show a title strip above each pane with its index, process, and directory
+    #[serde(default)]
+    pub pane_headers: bool,
+    /// Large translucent label in the corner of each pane; `{hostname}` and `{user}` are filled in
+    #[serde(default)]
+    pub badge: Option<String>,
+    /// Badge panes running ssh with the remote host, unless they set a badge of their own
+    #[serde(default = "default_ssh_badge")]
+    pub ssh_badge: bool,
+    /// Show how long a finished command ran (1s or more) at the end of its prompt line
+    #[serde(default = "default_command_durations")]
+    pub command_durations: bool,
     /// Enable background blur
     pub blur: bool,
     /// Cursor configuration
     #[serde(default)]
     pub cursor: CursorConfig,
+    /// Let the cursor and blinking text (SGR 5) blink; text blinks at cursor.blink_interval_ms
+    #[serde(default = "default_blinking")]
+    pub blinking: bool,
     /// DPI scale override (None = auto-detect from system)
     /// Useful for edge cases like VNC, VMs, or unusual display setups
     #[serde(default)]
@@ -55,19 +280,64 @@ pub struct AppearanceConfig {
     /// Wallpaper opacity (0.0-1.0, default: 0.3 for readability)
     #[serde(default = "default_wallpaper_opacity")]
     pub wallpaper_opacity: f32,
+    /// How the wallpaper is fitted: fill, fit, stretch, tile, or center
+    #[serde(default)]
+    pub wallpaper_mode: WallpaperMode,
     /// Blur strength (0.0 = no blur, 2.0 = default, 10.0 = heavy blur)
     #[serde(default = "default_blur_strength")]
     pub blur_strength: f32,
+    /// WGSL file defining `fn effect(uv: vec2<f32>) -> vec4<f32>`, run over each
+    /// finished frame (CRT curvature, scanlines); it can read `source`, `post.time`,
+    /// and `post.resolution`
+    #[serde(default)]
+    pub post_process: Option<String>,
+    /// Redraw continuously while the window is shown, so effects using `post.time` move
+    #[serde(default)]
+    pub post_process_animate: bool,
+}
+
+impl AppearanceConfig {
+    /// Color space to render in: only macOS tags the window's surface with one,
+    /// so elsewhere the output stays sRGB
+    pub fn output_color_space(&self) -> ColorSpace {
+        if cfg!(target_os = "macos") { self.color_space } else { ColorSpace::Srgb }
+    }
+
+    /// WGSL of the post-process shader, None when there isn't one
+    pub fn post_process_source(&self) -> anyhow::Result<Option<String>> {
+        let Some(path) = self.post_process.as_deref() else {
+            return Ok(None);
+        };
+        let source = std::fs::read_to_string(crate::layout::expand_home(path))
+            .with_context(|| format!("reading post-process shader {}", path))?;
+        Ok(Some(source))
+    }
 }
 
 fn default_wallpaper_opacity() -> f32 {
     0.3
 }
 
+fn default_blinking() -> bool {
+    true
+}
+
+fn default_command_durations() -> bool {
+    true
+}
+
+fn default_ssh_badge() -> bool {
+    true
+}
+
 fn default_blur_strength() -> f32 {
     2.0
 }
 
+fn default_spacing() -> f32 {
+    1.0
+}
+
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TerminalConfig {
@@ -77,15 +347,296 @@ pub struct TerminalConfig {
     pub scrollback_lines: usize,
     /// Enable ligatures
     pub ligatures: bool,
+    /// Command sent to each new tab once its shell shows a prompt (layouts set their own per pane)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub startup_command: Option<String>,
+    /// Ask before closing a pane or window that is running a program other than the shell
+    #[serde(default = "default_confirm_close")]
+    pub confirm_close: bool,
+    /// Ask before pasting several lines into a password prompt (echo off)
+    #[serde(default = "default_confirm_password_paste")]
+    pub confirm_password_paste: bool,
+    /// Draw typed characters before the shell echoes them (for slow SSH sessions)
+    #[serde(default)]
+    pub local_echo: bool,
+    /// Suggest the rest of a history command as it's typed (faded; Right Arrow takes it)
+    #[serde(default = "default_autosuggestions")]
+    pub autosuggestions: bool,
+    /// Keep command history across restarts in ~/.config/saternal/history (owner-only)
+    #[serde(default = "default_persist_history")]
+    pub persist_history: bool,
+}
+
+fn default_confirm_close() -> bool {
+    true
+}
+
+fn default_confirm_password_paste() -> bool {
+    true
+}
+
+fn default_autosuggestions() -> bool {
+    true
+}
+
+fn default_persist_history() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrivacyConfig {
+    /// Hide pane content when the screen locks or the screensaver starts
+    pub lock_on_screen_lock: bool,
+}
+
+/// What happens when a pane receives BEL
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BellMode {
+    Off,
+    /// Brief flash over the pane
+    #[default]
+    Visual,
+    /// System alert sound
+    Audible,
+    Both,
+}
+
+impl BellMode {
+    pub fn is_visual(self) -> bool {
+        matches!(self, BellMode::Visual | BellMode::Both)
+    }
+
+    pub fn is_audible(self) -> bool {
+        matches!(self, BellMode::Audible | BellMode::Both)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BellConfig {
+    /// off, visual, audible, or both
+    #[serde(default)]
+    pub mode: BellMode,
+}
+
+/// Seconds a command runs before its finish bounces the Dock icon
+const DEFAULT_BOUNCE_AFTER_SECS: u64 = 30;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CommandAlertConfig {
+    /// Bounce the Dock icon when a command that ran at least this many seconds
+    /// finishes while the dropdown is hidden (0 = never; needs shell integration)
+    pub bounce_after: u64,
+    /// Post a notification with the command and how long it took, for commands
+    /// that ran at least this many seconds (0 = never)
+    pub notify_after: u64,
+}
+
+impl CommandAlertConfig {
+    /// Whether a command that ran for `duration` bounces the Dock icon
+    pub fn bounces(&self, duration: Duration) -> bool {
+        self.bounce_after > 0 && duration.as_secs() >= self.bounce_after
+    }
+
+    /// Whether a command that ran for `duration` posts a notification
+    pub fn notifies(&self, duration: Duration) -> bool {
+        self.notify_after > 0 && duration.as_secs() >= self.notify_after
+    }
+}
+
+impl Default for CommandAlertConfig {
+    fn default() -> Self {
+        Self {
+            bounce_after: DEFAULT_BOUNCE_AFTER_SECS,
+            notify_after: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClipboardConfig {
+    /// Number of recent copies offered by the Cmd+Shift+V picker
+    pub history_size: usize,
+    /// Keep clipboard history across restarts (off by default - copies may hold secrets)
+    pub persist_history: bool,
+    /// Offer to drop the `$ ` / `% ` / `❯ ` prompts from pasted shell examples
+    pub strip_prompts: bool,
+    /// Offer to join pasted lines ending in `\` into one command
+    pub join_continuations: bool,
+    /// Hold a paste that could be cleaned up until it's repeated (false cleans it up straight away)
+    pub confirm_cleanup: bool,
+}
+
+impl ClipboardConfig {
+    /// Enabled paste cleanup transforms, in the order they run
+    pub fn paste_transforms(&self) -> Vec<PasteTransform> {
+        let mut transforms = Vec::new();
+        if self.strip_prompts {
+            transforms.push(PasteTransform::StripPrompts);
+        }
+        if self.join_continuations {
+            transforms.push(PasteTransform::JoinContinuations);
+        }
+        transforms
+    }
+}
+
+impl Default for ClipboardConfig {
+    fn default() -> Self {
+        Self {
+            history_size: DEFAULT_CLIPBOARD_HISTORY_SIZE,
+            persist_history: false,
+            strip_prompts: true,
+            join_continuations: true,
+            confirm_cleanup: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SelectionConfig {
+    /// Characters besides letters and digits that double-click selects as part of a word
+    pub word_chars: String,
+    /// highlight (tint with the palette's selection_bg) or invert (swap text and background)
+    pub style: SelectionStyle,
+}
+
+impl Default for SelectionConfig {
+    fn default() -> Self {
+        Self {
+            word_chars: DEFAULT_WORD_CHARS.to_string(),
+            style: SelectionStyle::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScrollingConfig {
+    /// Lines scrolled per mouse wheel notch (trackpads scroll by their exact pixel distance)
+    pub multiplier: f32,
+    /// Keep scrolling after a trackpad flick, slowing down gradually
+    pub momentum: bool,
+    /// How quickly momentum scrolling slows down (higher stops sooner)
+    pub deceleration: f32,
+}
+
+impl Default for ScrollingConfig {
+    fn default() -> Self {
+        Self {
+            multiplier: DEFAULT_SCROLL_MULTIPLIER,
+            momentum: false,
+            deceleration: DEFAULT_SCROLL_DECELERATION,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PanesConfig {
+    /// Outline drawn around each pane once a tab is split
+    pub border: PaneBorderConfig,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PaneBorderConfig {
+    /// Draw the outlines at all (off leaves split panes seamless)
+    pub enabled: bool,
+    /// Outline width in pixels, drawn inside each pane's edge
+    pub thickness: u32,
+    /// RGBA outline of the focused pane
+    pub active_color: [f32; 4],
+    /// RGBA outline of the other panes
+    pub inactive_color: [f32; 4],
+}
+
+impl Default for PaneBorderConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            thickness: 2,
+            active_color: [0.29, 0.56, 0.89, 0.6],   // #4A90E2 blue with 60% opacity
+            inactive_color: [0.24, 0.24, 0.24, 0.4], // #3C3C3C gray with 40% opacity
+        }
+    }
+}
+
+/// Which GPU wgpu asks for when there is more than one
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PowerPreference {
+    /// Integrated GPU: less battery and heat
+    Low,
+    /// Discrete GPU
+    #[default]
+    High,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RendererConfig {
+    pub power_preference: PowerPreference,
+    /// Use the first adapter whose name contains this (case-insensitive)
+    /// instead of the power preference's pick
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub adapter: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LoggingConfig {
+    /// Log every new pane to its own file in `directory`
+    pub auto: bool,
+    pub directory: String,
+    /// plain (text with timestamps) or raw (escape sequences kept)
+    pub format: LogFormat,
+    /// After a crash, offer the report on the next launch
+    pub crash_dialog: bool,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            auto: false,
+            directory: "~/Library/Logs/Saternal".to_string(),
+            format: LogFormat::default(),
+            crash_dialog: true,
+        }
+    }
+}
+
+impl Default for PrivacyConfig {
+    fn default() -> Self {
+        Self {
+            lock_on_screen_lock: true,
+        }
+    }
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             window: WindowConfig {
+                mode: WindowMode::default(),
                 width_percentage: 1.0,
                 height_percentage: 0.5,
+                height_presets: default_height_presets(),
                 animation_duration_ms: 180,
+                opacity: default_window_opacity(),
+                glass_opacity: default_glass_opacity(),
+                padding: Padding::default(),
+                snap_to_cells: default_snap_to_cells(),
+                symmetric_padding: false,
+                hide_on_focus_loss: default_hide_on_focus_loss(),
+                can_join_all_spaces: default_can_join_all_spaces(),
+                appear_over_fullscreen: default_appear_over_fullscreen(),
+                corner_radius: 0.0,
+                border_width: 0.0,
+                border_color: default_window_border_color(),
             },
             hotkey: HotkeyConfig {
                 toggle: "cmd+`".to_string(),
@@ -94,57 +645,166 @@ impl Default for Config {
                 palette: ColorPalette::default(),
                 font_family: "JetBrains Mono".to_string(),
                 font_size: 14.0,
+                line_height: 1.0,
+                letter_spacing: 1.0,
+                antialias: Antialias::default(),
+                pixel_snap: false,
+                low_latency: false,
+                color_space: ColorSpace::default(),
+                inactive_pane_dim: 0.0,
+                pane_headers: false,
+                badge: None,
+                ssh_badge: default_ssh_badge(),
+                command_durations: default_command_durations(),
                 opacity: 0.98,  // Increased from 0.95 for better visibility
                 blur: true,
                 cursor: CursorConfig::default(),
+                blinking: default_blinking(),
                 dpi_scale_override: None,
                 wallpaper_path: None,
                 wallpaper_opacity: 0.3,
+                wallpaper_mode: WallpaperMode::default(),
                 blur_strength: 2.0,
+                post_process: None,
+                post_process_animate: false,
             },
             terminal: TerminalConfig {
-                shell: std::env::var("SHELL").unwrap_or_else(|_| "/bin/zsh".to_string()),
+                // Windows has no $SHELL
+                shell: std::env::var("SHELL")
+                    .unwrap_or_else(|_| if cfg!(windows) { "powershell.exe" } else { "/bin/zsh" }.to_string()),
                 scrollback_lines: 10_000,
                 ligatures: true,
+                startup_command: None,
+                confirm_close: true,
+                confirm_password_paste: true,
+                local_echo: false,
+                autosuggestions: default_autosuggestions(),
+                persist_history: default_persist_history(),
             },
+            privacy: PrivacyConfig::default(),
+            search: SearchOptions::default(),
+            bell: BellConfig::default(),
+            command_alerts: CommandAlertConfig::default(),
+            clipboard: ClipboardConfig::default(),
+            selection: SelectionConfig::default(),
+            scrolling: ScrollingConfig::default(),
+            panes: PanesConfig::default(),
+            renderer: RendererConfig::default(),
+            logging: LoggingConfig::default(),
+            updates: UpdateConfig::default(),
+            triggers: Vec::new(),
+            layouts: BTreeMap::new(),
+            snippets: BTreeMap::new(),
         }
     }
 }
 
+/// Environment variable pointing at a config file to use instead of the standard locations
+pub const CONFIG_PATH_ENV: &str = "SATERNAL_CONFIG";
+
+/// Commented default config.toml (`@SHELL@` stands for the user's shell)
+const DEFAULT_CONFIG_TEMPLATE: &str = include_str!("default_config.toml");
+
+/// Standard config locations, most preferred first
+fn config_candidates(
+    home: Option<&Path>,
+    xdg_config_home: Option<&Path>,
+    appdata: Option<&Path>,
+) -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+    if let Some(xdg) = xdg_config_home.filter(|dir| dir.is_absolute()) {
+        candidates.push(xdg.join("saternal").join("config.toml"));
+    }
+    if let Some(home) = home {
+        candidates.push(home.join(".config").join("saternal").join("config.toml"));
+        candidates.push(home.join("Library").join("Application Support").join("saternal").join("config.toml"));
+    }
+    // Windows sessions usually set neither HOME nor XDG_CONFIG_HOME
+    if let Some(appdata) = appdata.filter(|dir| dir.is_absolute()) {
+        candidates.push(appdata.join("saternal").join("config.toml"));
+    }
+    candidates.dedup();
+    candidates
+}
+
+/// The override if set, else the first candidate that exists, else the first candidate
+fn resolve_config_path(
+    env_override: Option<PathBuf>,
+    candidates: &[PathBuf],
+    exists: impl Fn(&Path) -> bool,
+) -> Option<PathBuf> {
+    env_override
+        .or_else(|| candidates.iter().find(|path| exists(path)).cloned())
+        .or_else(|| candidates.first().cloned())
+}
+
 impl Config {
+    /// Path of the config file in use: $SATERNAL_CONFIG, then
+    /// $XDG_CONFIG_HOME or ~/.config, then ~/Library/Application Support,
+    /// then %APPDATA%; errors when none of those is set
+    pub fn default_path() -> anyhow::Result<PathBuf> {
+        let env_override = std::env::var_os(CONFIG_PATH_ENV)
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from);
+        let home = std::env::var_os("HOME").map(PathBuf::from);
+        let xdg = std::env::var_os("XDG_CONFIG_HOME").map(PathBuf::from);
+        let appdata = std::env::var_os("APPDATA").map(PathBuf::from);
+        let candidates = config_candidates(home.as_deref(), xdg.as_deref(), appdata.as_deref());
+        resolve_config_path(env_override, &candidates, Path::exists).with_context(|| {
+            format!("No config directory: set HOME, APPDATA, or {}", CONFIG_PATH_ENV)
+        })
+    }
+
+    /// Commented default config file, with every setting explained
+    pub fn default_toml() -> String {
+        DEFAULT_CONFIG_TEMPLATE.replace("@SHELL@", &Config::default().terminal.shell)
+    }
+
+    /// Write the commented default config to `path` (default: default_path())
+    /// An existing file is kept as config.toml.bak; returns the backup's path
+    pub fn write_default(path: Option<PathBuf>) -> anyhow::Result<Option<PathBuf>> {
+        let config_path = match path {
+            Some(path) => path,
+            None => Self::default_path()?,
+        };
+        if let Some(parent) = config_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let backup = if config_path.exists() {
+            let backup = config_path.with_extension("toml.bak");
+            std::fs::rename(&config_path, &backup)?;
+            Some(backup)
+        } else {
+            None
+        };
+        std::fs::write(&config_path, Self::default_toml())?;
+        Ok(backup)
+    }
+
     /// Load configuration from file, or create default if not exists
     pub fn load(path: Option<PathBuf>) -> anyhow::Result<Self> {
-        let config_path = path.unwrap_or_else(|| {
-            let mut p = dirs::config_dir().expect("No config directory");
-            p.push("saternal");
-            p.push("config.toml");
-            p
-        });
+        let config_path = match path {
+            Some(path) => path,
+            None => Self::default_path()?,
+        };
 
         if config_path.exists() {
             let contents = std::fs::read_to_string(&config_path)?;
             let config: Config = toml::from_str(&contents)?;
             Ok(config)
         } else {
-            // Create default config
-            let config = Config::default();
-            if let Some(parent) = config_path.parent() {
-                std::fs::create_dir_all(parent)?;
-            }
-            let contents = toml::to_string_pretty(&config)?;
-            std::fs::write(&config_path, contents)?;
-            Ok(config)
+            // Create the commented default config
+            Self::write_default(Some(config_path))?;
+            Ok(Config::default())
         }
     }
 
     /// Save configuration to file
     pub fn save(&self, path: Option<PathBuf>) -> anyhow::Result<()> {
-        let config_path = path.unwrap_or_else(|| {
-            let mut p = dirs::config_dir().expect("No config directory");
-            p.push("saternal");
-            p.push("config.toml");
-            p
-        });
+        let config_path = match path {
+            Some(path) => path,
+            None => Self::default_path()?,
+        };
 
         if let Some(parent) = config_path.parent() {
             std::fs::create_dir_all(parent)?;
@@ -156,19 +816,106 @@ impl Config {
     }
 }
 
-// Helper function to get home directory
-fn dirs() -> Option<PathBuf> {
-    std::env::var_os("HOME").map(PathBuf::from)
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-mod dirs {
-    use std::path::PathBuf;
+    #[test]
+    fn test_default_template_matches_defaults() {
+        let parsed: Config = toml::from_str(&Config::default_toml()).unwrap();
+        assert_eq!(
+            toml::to_string_pretty(&parsed).unwrap(),
+            toml::to_string_pretty(&Config::default()).unwrap()
+        );
+    }
 
-    pub fn config_dir() -> Option<PathBuf> {
-        std::env::var_os("HOME").map(|home| {
-            let mut path = PathBuf::from(home);
-            path.push(".config");
-            path
-        })
+    #[test]
+    fn test_padding_defaults_per_side() {
+        let window: WindowConfig = toml::from_str(
+            "width_percentage = 1.0\nheight_percentage = 0.5\nanimation_duration_ms = 180\n\
+             padding = { top = 20, left = 16 }",
+        )
+        .unwrap();
+        let defaults = Padding::default();
+        assert_eq!(window.padding, Padding { top: 20.0, left: 16.0, ..defaults });
+    }
+
+    #[test]
+    fn test_pane_border_fills_in_defaults() {
+        let panes: PanesConfig = toml::from_str("border = { thickness = 1, enabled = false }").unwrap();
+        let defaults = PaneBorderConfig::default();
+        assert_eq!(panes.border, PaneBorderConfig { enabled: false, thickness: 1, ..defaults });
+    }
+
+    #[test]
+    fn test_color_space_names() {
+        let parse = |name: &str| toml::from_str::<Config>(&Config::default_toml().replace("color_space = \"srgb\"", name));
+        assert_eq!(parse("color_space = \"display-p3\"").unwrap().appearance.color_space, ColorSpace::DisplayP3);
+        assert_eq!(parse("").unwrap().appearance.color_space, ColorSpace::Srgb);
+        assert!(parse("color_space = \"p3\"").is_err());
+    }
+
+    #[test]
+    fn test_renderer_power_preference() {
+        let renderer: RendererConfig = toml::from_str("power_preference = \"low\"").unwrap();
+        assert_eq!(renderer, RendererConfig { power_preference: PowerPreference::Low, adapter: None });
+        assert!(toml::from_str::<RendererConfig>("power_preference = \"medium\"").is_err());
+    }
+
+    #[test]
+    fn test_height_presets_cycle_and_wrap() {
+        let mut window = Config::default().window;
+        window.height_presets = vec![0.9, 0.3, 0.5, 2.0];
+        assert_eq!(window.next_height_preset(), Some(0.9));
+        window.height_percentage = 0.9;
+        assert_eq!(window.next_height_preset(), Some(0.3));
+
+        // A dragged height just short of a preset moves past it
+        window.set_dropdown_height(499.0, 1000.0);
+        assert_eq!(window.next_height_preset(), Some(0.9));
+        window.set_dropdown_height(20.0, 1000.0);
+        assert_eq!(window.height_percentage, MIN_HEIGHT_PERCENTAGE);
+
+        window.height_presets.clear();
+        assert_eq!(window.next_height_preset(), None);
+    }
+
+    #[test]
+    fn test_config_path_resolution() {
+        let home = Path::new("/Users/sam");
+        let candidates = config_candidates(Some(home), None, None);
+        assert_eq!(candidates[0], home.join(".config/saternal/config.toml"));
+        assert_eq!(candidates[1], home.join("Library/Application Support/saternal/config.toml"));
+
+        // Nothing exists yet: create in ~/.config
+        assert_eq!(resolve_config_path(None, &candidates, |_| false), Some(candidates[0].clone()));
+        // Only the macOS location exists
+        let found = resolve_config_path(None, &candidates, |path| path == candidates[1]);
+        assert_eq!(found, Some(candidates[1].clone()));
+        // The override always wins
+        let custom = PathBuf::from("/tmp/custom.toml");
+        assert_eq!(resolve_config_path(Some(custom.clone()), &candidates, |_| true), Some(custom));
+
+        // XDG_CONFIG_HOME comes first; relative values are ignored per the spec
+        let xdg = config_candidates(Some(home), Some(Path::new("/xdg")), None);
+        assert_eq!(xdg[0], PathBuf::from("/xdg/saternal/config.toml"));
+        assert_eq!(config_candidates(Some(home), Some(Path::new("rel")), None), candidates);
+    }
+
+    #[test]
+    fn test_config_path_without_home() {
+        // A plain Windows session: only APPDATA is set
+        let appdata = if cfg!(windows) {
+            Path::new(r"C:\Users\sam\AppData\Roaming")
+        } else {
+            Path::new("/Users/sam/AppData/Roaming")
+        };
+        let candidates = config_candidates(None, None, Some(appdata));
+        assert_eq!(candidates, vec![appdata.join("saternal").join("config.toml")]);
+        assert_eq!(resolve_config_path(None, &candidates, |_| false), Some(candidates[0].clone()));
+
+        // Nothing set at all: no path rather than a panic
+        assert!(config_candidates(None, None, None).is_empty());
+        assert_eq!(resolve_config_path(None, &[], |_| true), None);
     }
 }
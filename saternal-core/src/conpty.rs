@@ -0,0 +1,263 @@
+/// Windows pseudo console (ConPTY) for a pane's shell
+///
+/// Stands in for `alacritty_terminal::tty::Pty` on Windows with the calls
+/// `Terminal` makes on it. alacritty's own Windows backend keeps the output
+/// pipe behind a poller it drives itself; here the pipe is a plain handle, so
+/// `pty_reader` can read it on a thread like the PTY fd on Unix.
+use alacritty_terminal::event::{OnResize, WindowSize};
+use log::warn;
+use std::collections::HashMap;
+use std::ffi::{c_void, OsStr};
+use std::fs::File;
+use std::io;
+use std::os::windows::ffi::OsStrExt;
+use std::os::windows::io::{AsRawHandle, FromRawHandle, OwnedHandle};
+use std::path::Path;
+use windows_sys::Win32::Foundation::{CloseHandle, HANDLE, S_OK};
+use windows_sys::Win32::System::Console::{
+    ClosePseudoConsole, CreatePseudoConsole, ResizePseudoConsole, COORD, HPCON,
+};
+use windows_sys::Win32::System::Pipes::CreatePipe;
+use windows_sys::Win32::System::Threading::{
+    CreateProcessW, DeleteProcThreadAttributeList, InitializeProcThreadAttributeList, TerminateProcess,
+    UpdateProcThreadAttribute, CREATE_UNICODE_ENVIRONMENT, EXTENDED_STARTUPINFO_PRESENT,
+    LPPROC_THREAD_ATTRIBUTE_LIST, PROCESS_INFORMATION, PROC_THREAD_ATTRIBUTE_PSEUDOCONSOLE,
+    STARTF_USESTDHANDLES, STARTUPINFOEXW,
+};
+
+/// Shell started when none is configured
+const DEFAULT_SHELL: &str = "powershell.exe";
+
+/// The shell process attached to a pseudo console
+pub struct Child {
+    process: OwnedHandle,
+    pid: u32,
+}
+
+impl Child {
+    pub fn id(&self) -> u32 {
+        self.pid
+    }
+
+    /// End the process right away (the shell gets no chance to clean up)
+    pub fn kill(&self) -> io::Result<()> {
+        if unsafe { TerminateProcess(self.process.as_raw_handle() as HANDLE, 1) } == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
+
+/// A pseudo console with a shell attached
+pub struct Pty {
+    console: HPCON,
+    /// Our end of the console's input: keys and pastes go here
+    input: File,
+    /// Our end of the console's output, VT sequences included
+    output: File,
+    child: Child,
+}
+
+impl Pty {
+    pub fn child(&self) -> &Child {
+        &self.child
+    }
+
+    /// Output pipe (blocking reads)
+    pub fn file(&self) -> &File {
+        &self.output
+    }
+
+    /// Input pipe
+    pub fn writer(&mut self) -> &mut File {
+        &mut self.input
+    }
+}
+
+impl OnResize for Pty {
+    fn on_resize(&mut self, window_size: WindowSize) {
+        let result = unsafe { ResizePseudoConsole(self.console, coord(window_size)) };
+        if result != S_OK {
+            warn!("Failed to resize the pseudo console: {}", io::Error::from_raw_os_error(result));
+        }
+    }
+}
+
+impl Drop for Pty {
+    fn drop(&mut self) {
+        // Ends the shell and every console program it started, like a hangup.
+        // The pipes close after this, so a reader blocked on output wakes up at EOF.
+        unsafe { ClosePseudoConsole(self.console) };
+    }
+}
+
+/// Start `shell` (default: PowerShell) on a new pseudo console
+/// `env` is added to this process's environment for the shell
+pub fn new(shell: Option<&str>, cwd: Option<&Path>, env: &HashMap<String, String>, window_size: WindowSize) -> io::Result<Pty> {
+    let (console_input, input) = pipe()?;
+    let (output, console_output) = pipe()?;
+
+    let mut console: HPCON = 0;
+    let result = unsafe {
+        CreatePseudoConsole(
+            coord(window_size),
+            console_input.as_raw_handle() as HANDLE,
+            console_output.as_raw_handle() as HANDLE,
+            0,
+            &mut console,
+        )
+    };
+    if result != S_OK {
+        return Err(io::Error::from_raw_os_error(result));
+    }
+
+    let child = match spawn(console, shell.unwrap_or(DEFAULT_SHELL), cwd, env) {
+        Ok(child) => child,
+        Err(e) => {
+            unsafe { ClosePseudoConsole(console) };
+            return Err(e);
+        }
+    };
+
+    // The console keeps its own copies of its ends of the pipes
+    drop(console_input);
+    drop(console_output);
+
+    Ok(Pty { console, input: File::from(input), output: File::from(output), child })
+}
+
+/// Create `shell` attached to `console`
+fn spawn(console: HPCON, shell: &str, cwd: Option<&Path>, env: &HashMap<String, String>) -> io::Result<Child> {
+    let mut size = 0;
+    // Fails, but reports the size the list needs
+    unsafe { InitializeProcThreadAttributeList(std::ptr::null_mut(), 1, 0, &mut size) };
+    let mut attributes = vec![0u8; size];
+    let list = attributes.as_mut_ptr() as LPPROC_THREAD_ATTRIBUTE_LIST;
+    if unsafe { InitializeProcThreadAttributeList(list, 1, 0, &mut size) } == 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let result = (|| {
+        let updated = unsafe {
+            UpdateProcThreadAttribute(
+                list,
+                0,
+                PROC_THREAD_ATTRIBUTE_PSEUDOCONSOLE as usize,
+                console as *const c_void,
+                std::mem::size_of::<HPCON>(),
+                std::ptr::null_mut(),
+                std::ptr::null(),
+            )
+        };
+        if updated == 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let mut startup: STARTUPINFOEXW = unsafe { std::mem::zeroed() };
+        startup.StartupInfo.cb = std::mem::size_of::<STARTUPINFOEXW>() as u32;
+        // Null std handles, or a child started from a console would write to that one instead
+        startup.StartupInfo.dwFlags = STARTF_USESTDHANDLES;
+        startup.lpAttributeList = list;
+
+        let mut command_line = wide(&quote(shell));
+        let environment = environment_block(env);
+        let cwd = cwd.map(|cwd| wide(cwd.as_os_str()));
+        let mut info: PROCESS_INFORMATION = unsafe { std::mem::zeroed() };
+        let created = unsafe {
+            CreateProcessW(
+                std::ptr::null(),
+                command_line.as_mut_ptr(),
+                std::ptr::null(),
+                std::ptr::null(),
+                0,
+                EXTENDED_STARTUPINFO_PRESENT | CREATE_UNICODE_ENVIRONMENT,
+                environment.as_ptr() as *const c_void,
+                cwd.as_ref().map_or(std::ptr::null(), |cwd| cwd.as_ptr()),
+                &startup.StartupInfo,
+                &mut info,
+            )
+        };
+        if created == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        unsafe { CloseHandle(info.hThread) };
+
+        Ok(Child {
+            process: unsafe { OwnedHandle::from_raw_handle(info.hProcess as _) },
+            pid: info.dwProcessId,
+        })
+    })();
+
+    unsafe { DeleteProcThreadAttributeList(list) };
+    result
+}
+
+/// An anonymous pipe as (read end, write end)
+fn pipe() -> io::Result<(OwnedHandle, OwnedHandle)> {
+    let mut read: HANDLE = 0;
+    let mut write: HANDLE = 0;
+    if unsafe { CreatePipe(&mut read, &mut write, std::ptr::null(), 0) } == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    unsafe { Ok((OwnedHandle::from_raw_handle(read as _), OwnedHandle::from_raw_handle(write as _))) }
+}
+
+fn coord(window_size: WindowSize) -> COORD {
+    COORD { X: window_size.num_cols as i16, Y: window_size.num_lines as i16 }
+}
+
+/// Quote a program path with spaces for the command line
+fn quote(program: &str) -> String {
+    if program.contains(' ') && !program.starts_with('"') {
+        format!("\"{}\"", program)
+    } else {
+        program.to_string()
+    }
+}
+
+/// Null-terminated UTF-16
+fn wide(text: impl AsRef<OsStr>) -> Vec<u16> {
+    text.as_ref().encode_wide().chain(Some(0)).collect()
+}
+
+/// This process's environment with `extra` on top, as `NAME=value\0...\0\0`
+fn environment_block(extra: &HashMap<String, String>) -> Vec<u16> {
+    let mut vars: Vec<(String, String)> = std::env::vars()
+        .filter(|(name, _)| !extra.keys().any(|key| key.eq_ignore_ascii_case(name)))
+        .chain(extra.iter().map(|(name, value)| (name.clone(), value.clone())))
+        .collect();
+    // Windows expects the block sorted by name, ignoring case
+    vars.sort_by_key(|(name, _)| name.to_uppercase());
+
+    let mut block = Vec::new();
+    for (name, value) in vars {
+        block.extend(OsStr::new(&format!("{}={}", name, value)).encode_wide());
+        block.push(0);
+    }
+    if block.is_empty() {
+        block.push(0);
+    }
+    block.push(0);
+    block
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(quote("cmd.exe"), "cmd.exe");
+        assert_eq!(quote(r"C:\Program Files\PowerShell\7\pwsh.exe"), r#""C:\Program Files\PowerShell\7\pwsh.exe""#);
+    }
+
+    #[test]
+    fn test_environment_block_overrides() {
+        let extra = HashMap::from([("TERM".to_string(), "xterm-256color".to_string())]);
+        let block = String::from_utf16(&environment_block(&extra)).unwrap();
+        assert!(block.ends_with("\0\0"));
+        let vars: Vec<&str> = block.trim_end_matches('\0').split('\0').collect();
+        assert_eq!(vars.iter().filter(|var| var.to_uppercase().starts_with("TERM=")).count(), 1);
+        assert!(vars.contains(&"TERM=xterm-256color"));
+    }
+}
@@ -1,12 +1,7 @@
-/// Layout padding constants shared across rendering and terminal sizing
-///
-/// These values define the margins around terminal content and must be synchronized
-/// between:
-/// - Terminal size calculations (to determine PTY dimensions)
-/// - Text rasterization (to position glyphs on screen)
-///
-/// Changing these values will affect both the visual layout and the calculated
-/// terminal dimensions (cols/rows).
+//! Default layout padding around terminal content
+//!
+//! These are the defaults for `window.padding`; the values in effect live in
+//! `geometry::Padding`, which terminal sizing and every renderer share.
 
 /// Left padding in pixels
 pub const PADDING_LEFT: f32 = 10.0;
@@ -23,3 +18,9 @@ pub const PADDING_BOTTOM: f32 = 10.0;
 /// Minimum cell dimension to prevent division by zero
 /// Used as a fallback when cell dimensions are invalid
 pub const MIN_CELL_DIMENSION: f32 = 1.0;
+
+/// Default lines scrolled per mouse wheel notch
+pub const DEFAULT_SCROLL_MULTIPLIER: f32 = 3.0;
+
+/// Default exponential decay rate of trackpad scroll coasting speed (per second)
+pub const DEFAULT_SCROLL_DECELERATION: f32 = 4.0;
@@ -0,0 +1,181 @@
+/// Crash reports, and hanging up on the shells when Saternal goes down
+///
+/// Release builds abort on panic, so no destructor runs and each pane's shell
+/// would be left running with nothing on the other end of its PTY. The panic
+/// hook sends them SIGHUP (as closing a pane does), writes a report with the
+/// panic, backtrace, and GPU in use, and leaves a marker so the next launch
+/// can point at it.
+use parking_lot::Mutex;
+use std::backtrace::Backtrace;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::pane_log::local_timestamp;
+use crate::platform::{user_dir, UserDir};
+
+/// Names the newest report that hasn't been shown yet
+const PENDING_MARKER: &str = "pending";
+
+/// Shell processes running in panes
+static CHILDREN: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+
+/// The GPU adapter the renderer ended up on
+static GPU_INFO: Mutex<Option<String>> = Mutex::new(None);
+
+/// Track a shell so a crash hangs up on it
+pub fn register_child(pid: u32) {
+    CHILDREN.lock().push(pid);
+}
+
+pub fn unregister_child(pid: u32) {
+    CHILDREN.lock().retain(|&child| child != pid);
+}
+
+/// Record the GPU adapter for crash reports
+pub fn set_gpu_info(info: String) {
+    *GPU_INFO.lock() = Some(info);
+}
+
+/// `Crashes` in the per-user state directory (`~/Library/Logs/Saternal` on macOS)
+pub fn report_dir() -> Option<PathBuf> {
+    user_dir(UserDir::State).map(|dir| dir.join("Crashes"))
+}
+
+/// Hang up on the shells and write a report to `dir` (when set) on any panic
+///
+/// The previous hook (the one that prints the message) still runs afterwards.
+pub fn install_panic_hook(dir: Option<PathBuf>) {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        // With unwinding, only a panic on the main thread takes the app down
+        if cfg!(panic = "abort") || std::thread::current().name() == Some("main") {
+            hang_up_children();
+        }
+
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("Box<dyn Any>");
+        let location = info.location().map(|location| location.to_string());
+        let gpu = GPU_INFO.try_lock().and_then(|info| info.clone());
+        let report = format_report(
+            message,
+            location.as_deref(),
+            gpu.as_deref(),
+            &Backtrace::force_capture(),
+            SystemTime::now(),
+        );
+        match dir.as_deref().map(|dir| write_report(dir, &report)) {
+            Some(Ok(path)) => eprintln!("Crash report written to {}", path.display()),
+            Some(Err(e)) => eprintln!("Failed to write crash report: {}", e),
+            None => {}
+        }
+
+        previous(info);
+    }));
+}
+
+/// SIGHUP every registered shell
+#[cfg(unix)]
+fn hang_up_children() {
+    // The lock is never held across anything that can panic, but don't bet the cleanup on it
+    let Some(children) = CHILDREN.try_lock() else {
+        return;
+    };
+    for &pid in children.iter() {
+        unsafe {
+            libc::kill(pid as libc::pid_t, libc::SIGHUP);
+        }
+    }
+}
+
+/// Pseudo consoles close with this process and end their shells on their own
+#[cfg(windows)]
+fn hang_up_children() {}
+
+fn format_report(
+    message: &str,
+    location: Option<&str>,
+    gpu: Option<&str>,
+    backtrace: &Backtrace,
+    time: SystemTime,
+) -> String {
+    format!(
+        "Saternal {} crashed at {}\n\
+         OS: {} {}\n\
+         GPU: {}\n\
+         Thread: {}\n\
+         Panic: {}\n\
+         Location: {}\n\
+         \n\
+         Backtrace:\n{}\n",
+        env!("CARGO_PKG_VERSION"),
+        local_timestamp(time),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        gpu.unwrap_or("not initialized"),
+        std::thread::current().name().unwrap_or("<unnamed>"),
+        message,
+        location.unwrap_or("unknown"),
+        backtrace
+    )
+}
+
+/// Save `report` as `crash-<time>.log` in `dir` and mark it pending
+fn write_report(dir: &Path, report: &str) -> io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let stamp = local_timestamp(SystemTime::now()).replace([' ', ':'], "-");
+    let name = format!("crash-{}-{}.log", stamp, std::process::id());
+    let path = dir.join(&name);
+    fs::write(&path, report)?;
+    fs::write(dir.join(PENDING_MARKER), &name)?;
+    Ok(path)
+}
+
+/// The report left by the last crash, if it hasn't been shown yet
+///
+/// Clears the marker, so each report comes up once.
+pub fn take_pending_report(dir: &Path) -> Option<PathBuf> {
+    let marker = dir.join(PENDING_MARKER);
+    let name = fs::read_to_string(&marker).ok()?;
+    let _ = fs::remove_file(&marker);
+    let path = dir.join(name.trim());
+    path.is_file().then_some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_contents() {
+        let report = format_report(
+            "index out of bounds",
+            Some("src/terminal.rs:12:5"),
+            Some("Apple M2 (Metal)"),
+            &Backtrace::disabled(),
+            SystemTime::now(),
+        );
+        assert!(report.contains("Panic: index out of bounds"));
+        assert!(report.contains("Location: src/terminal.rs:12:5"));
+        assert!(report.contains("GPU: Apple M2 (Metal)"));
+        assert!(report.starts_with(&format!("Saternal {} crashed", env!("CARGO_PKG_VERSION"))));
+    }
+
+    #[test]
+    fn test_pending_report_comes_up_once() {
+        let dir = std::env::temp_dir().join(format!("saternal-crash-test-{}", std::process::id()));
+        assert_eq!(take_pending_report(&dir), None);
+
+        let path = write_report(&dir, "Saternal crashed").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "Saternal crashed");
+        assert_eq!(take_pending_report(&dir), Some(path));
+        assert_eq!(take_pending_report(&dir), None);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
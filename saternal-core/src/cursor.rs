@@ -0,0 +1,74 @@
+/// Cursor appearance settings (`appearance.cursor` in config.toml)
+use serde::{Deserialize, Serialize};
+
+/// Cursor style types
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CursorStyle {
+    Block,
+    Beam,
+    Underline,
+}
+
+impl Default for CursorStyle {
+    fn default() -> Self {
+        Self::Block
+    }
+}
+
+/// Cursor configuration
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct CursorConfig {
+    /// Cursor style (block, beam, underline)
+    pub style: CursorStyle,
+    /// Enable blinking
+    pub blink: bool,
+    /// Blink interval in milliseconds
+    pub blink_interval_ms: u64,
+    /// Stop blinking (cursor stays solid) after this many seconds without a keypress (0 = never)
+    #[serde(default = "default_blink_timeout_secs")]
+    pub blink_timeout_secs: u64,
+    /// Cursor color (RGBA, values 0.0-1.0)
+    #[serde(default = "default_cursor_color")]
+    pub color: [f32; 4],
+    /// Force show cursor even when applications request to hide it
+    /// Useful for TUI apps that don't properly manage cursor visibility
+    #[serde(default)]
+    pub force_show: bool,
+    /// Draw the character under a block cursor in reverse (the cell's background color)
+    #[serde(default)]
+    pub reverse_text: bool,
+    /// Color for the character under a block cursor instead of the background (with reverse_text)
+    #[serde(default)]
+    pub text_color: Option<[f32; 4]>,
+    /// Outline the cursor in unfocused panes and while the window is in the background
+    #[serde(default = "default_hollow_when_unfocused")]
+    pub hollow_when_unfocused: bool,
+}
+
+fn default_cursor_color() -> [f32; 4] {
+    [1.0, 1.0, 1.0, 0.8] // White with 80% opacity
+}
+
+fn default_hollow_when_unfocused() -> bool {
+    true
+}
+
+fn default_blink_timeout_secs() -> u64 {
+    5
+}
+
+impl Default for CursorConfig {
+    fn default() -> Self {
+        Self {
+            style: CursorStyle::Block,
+            blink: true,
+            blink_interval_ms: 530, // Standard terminal blink rate
+            blink_timeout_secs: default_blink_timeout_secs(),
+            color: default_cursor_color(),
+            force_show: false, // Respect application hide commands by default
+            reverse_text: false,
+            text_color: None,
+            hollow_when_unfocused: default_hollow_when_unfocused(),
+        }
+    }
+}
@@ -0,0 +1,58 @@
+//! Styled local output printed into a pane by Saternal itself
+//!
+//! Feedback lines are fed straight into the terminal emulator, so they show up
+//! inline with shell output but never reach the shell's input.
+
+/// Kind of feedback, which decides the line style
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedbackKind {
+    /// Plain text (help output, listings)
+    Info,
+    /// Operation succeeded
+    Success,
+    /// Something needs attention but nothing failed
+    Warning,
+    /// Operation failed
+    Error,
+}
+
+impl FeedbackKind {
+    /// SGR parameters for this kind
+    fn sgr(self) -> &'static str {
+        match self {
+            FeedbackKind::Info => "2",
+            FeedbackKind::Success => "32",
+            FeedbackKind::Warning => "33",
+            FeedbackKind::Error => "31",
+        }
+    }
+}
+
+/// Format text as terminal bytes: starts on a fresh line, every line styled,
+/// and the cursor left at the start of the line after the message
+pub fn format_feedback(kind: FeedbackKind, text: &str) -> String {
+    let mut out = String::from("\r\n");
+    for line in text.trim_end_matches('\n').split('\n') {
+        out.push_str(&format!("\x1b[{}m{}\x1b[0m\r\n", kind.sgr(), line.trim_end_matches('\r')));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_line_is_styled_on_its_own_line() {
+        assert_eq!(
+            format_feedback(FeedbackKind::Error, "✗ nope"),
+            "\r\n\x1b[31m✗ nope\x1b[0m\r\n"
+        );
+    }
+
+    #[test]
+    fn test_each_line_is_styled_and_uses_crlf() {
+        let out = format_feedback(FeedbackKind::Info, "a\nb\n");
+        assert_eq!(out, "\r\n\x1b[2ma\x1b[0m\r\n\x1b[2mb\x1b[0m\r\n");
+    }
+}
@@ -1,15 +1,52 @@
 use anyhow::Result;
 use fontdue::{Font, FontSettings};
-use log::info;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Monospace font compiled into the binary, used when nothing else loads
+/// (DejaVu Sans Mono, Bitstream Vera license - see assets/fonts/LICENSE-DejaVu.txt)
+const BUNDLED_FONT: &[u8] = include_bytes!("../assets/fonts/DejaVuSansMono.ttf");
+const BUNDLED_FONT_NAME: &str = "DejaVu Sans Mono (bundled)";
+
+/// System monospace fonts tried after the configured family
+const SYSTEM_FALLBACK_FONTS: &[&str] = &[
+    "/System/Library/Fonts/Monaco.ttf",
+    "/System/Library/Fonts/Menlo.ttc",
+    "/Library/Fonts/SF-Mono-Regular.otf",
+];
+
+/// How glyph edges are antialiased
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Antialias {
+    /// Single coverage value per pixel
+    #[default]
+    Grayscale,
+    /// Separate R/G/B coverage for horizontal-RGB LCD panels (sharper on non-retina displays)
+    Subpixel,
+}
+
+/// Allowed range for the line-height and letter-spacing multipliers
+pub const MIN_SPACING: f32 = 0.5;
+pub const MAX_SPACING: f32 = 3.0;
 
 /// Manages font loading and glyph rasterization
 pub struct FontManager {
     font: Font,
     configured_font_size: f32,      // Logical size from config
     current_scale_factor: f64,       // Current DPI scale (1.0, 2.0, etc.)
-    /// Cache of rasterized glyphs: (char, size) -> (width, height, bitmap)
+    /// Cache of rasterized glyphs: (char, effective size bits) -> (width, height, bitmap)
     glyph_cache: HashMap<(char, u32), (usize, usize, Vec<u8>)>,
+    /// Set when the configured family couldn't be loaded
+    fallback_warning: Option<String>,
+    /// Cell height multiplier (1.0 = font's natural line height)
+    line_height: f32,
+    /// Cell width multiplier (1.0 = font's advance width)
+    letter_spacing: f32,
+    /// Round the font size and cell width to whole physical pixels
+    pixel_snap: bool,
 }
 
 impl FontManager {
@@ -22,59 +59,144 @@ impl FontManager {
     pub fn new_with_scale(font_family: &str, font_size: f32, scale_factor: f64) -> Result<Self> {
         info!("Loading font: {} at size {} (scale: {}x)", font_family, font_size, scale_factor);
 
-        // For now, load a default monospace font
-        // In production, we'd search system fonts
-        let font_data = Self::load_default_font()?;
+        if let Some(font) = Self::load_family(font_family) {
+            return Ok(Self::with_font(font, font_size, scale_factor, None));
+        }
 
-        let font = Font::from_bytes(font_data, FontSettings::default())
-            .map_err(|e| anyhow::anyhow!("Failed to load font: {}", e))?;
+        let (font, fallback_name) = Self::load_fallback_font()?;
+        let warning = format!(
+            "Font \"{}\" not found, using {} instead",
+            font_family, fallback_name
+        );
+        warn!("{}", warning);
+        Ok(Self::with_font(font, font_size, scale_factor, Some(warning)))
+    }
 
-        Ok(Self {
+    /// Use the bundled font regardless of what is installed (the same glyphs on every machine)
+    pub fn bundled(font_size: f32) -> Result<Self> {
+        let font = parse_font(BUNDLED_FONT.to_vec())
+            .ok_or_else(|| anyhow::anyhow!("Failed to load bundled font"))?;
+        Ok(Self::with_font(font, font_size, 1.0, None))
+    }
+
+    fn with_font(font: Font, font_size: f32, scale_factor: f64, fallback_warning: Option<String>) -> Self {
+        Self {
             font,
             configured_font_size: font_size,
             current_scale_factor: scale_factor,
             glyph_cache: HashMap::new(),
-        })
-    }
-
-    /// Load default monospace font
-    fn load_default_font() -> Result<Vec<u8>> {
-        // Try to load JetBrains Mono or fallback to system fonts
-        let font_paths = vec![
-            "/System/Library/Fonts/Monaco.ttf",
-            "/System/Library/Fonts/Menlo.ttc",
-            "/Library/Fonts/SF-Mono-Regular.otf",
-        ];
-
-        for path in font_paths {
-            if let Ok(data) = std::fs::read(path) {
-                info!("Loaded font from: {}", path);
-                return Ok(data);
+            fallback_warning,
+            line_height: 1.0,
+            letter_spacing: 1.0,
+            pixel_snap: false,
+        }
+    }
+
+    /// Search the font directories for the configured family
+    fn load_family(font_family: &str) -> Option<Font> {
+        let candidates = family_file_candidates(font_family);
+        for dir in font_directories() {
+            for name in &candidates {
+                let path = dir.join(name);
+                if let Some(font) = std::fs::read(&path).ok().and_then(parse_font) {
+                    info!("Loaded font from: {}", path.display());
+                    return Some(font);
+                }
+            }
+        }
+        None
+    }
+
+    /// Load a system monospace font, or the bundled font as the last resort
+    fn load_fallback_font() -> Result<(Font, String)> {
+        for path in SYSTEM_FALLBACK_FONTS {
+            if let Some(font) = std::fs::read(path).ok().and_then(parse_font) {
+                info!("Loaded fallback font from: {}", path);
+                let name = std::path::Path::new(path)
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| path.to_string());
+                return Ok((font, name));
             }
         }
 
-        anyhow::bail!("Could not find any monospace font")
+        info!("Using bundled fallback font");
+        let font = parse_font(BUNDLED_FONT.to_vec())
+            .ok_or_else(|| anyhow::anyhow!("Failed to load bundled font"))?;
+        Ok((font, BUNDLED_FONT_NAME.to_string()))
+    }
+
+    /// Warning to show when the configured font was replaced by a fallback
+    pub fn fallback_warning(&self) -> Option<&str> {
+        self.fallback_warning.as_deref()
     }
 
-    /// Get effective font size (logical size * DPI scale)
+    /// Get effective font size (logical size * DPI scale), in whole pixels with pixel snapping
     pub fn effective_font_size(&self) -> f32 {
-        (self.configured_font_size * self.current_scale_factor as f32)
+        self.effective_font_size_at(self.current_scale_factor)
+    }
+
+    /// Effective font size on a display with the given scale factor
+    pub fn effective_font_size_at(&self, scale_factor: f64) -> f32 {
+        self.snapped(self.configured_font_size * scale_factor as f32)
     }
 
-    /// Update DPI scale factor and clear cache if changed
+    /// Effective font size of a pane zoomed to `font_scale` times the configured size
+    pub fn zoomed_font_size(&self, font_scale: f32) -> f32 {
+        self.snapped(self.configured_font_size * font_scale * self.current_scale_factor as f32)
+    }
+
+    /// A copy of this font at `font_scale` times the size, with its own glyph cache
+    /// (for drawing zoomed panes; its cell_metrics() match the zoomed font size)
+    pub fn zoomed(&self, font_scale: f32) -> Self {
+        let mut zoomed = Self::with_font(
+            self.font.clone(),
+            self.configured_font_size * font_scale,
+            self.current_scale_factor,
+            None,
+        );
+        zoomed.set_spacing(self.line_height, self.letter_spacing);
+        zoomed.pixel_snap = self.pixel_snap;
+        zoomed
+    }
+
+    fn snapped(&self, size: f32) -> f32 {
+        if self.pixel_snap {
+            size.round().max(1.0)
+        } else {
+            size
+        }
+    }
+
+    /// Rasterize at a whole-pixel size into whole-pixel cells, so glyphs stay
+    /// sharp at non-integer scale factors (e.g. 1.5x) instead of landing between pixels
+    pub fn set_pixel_snap(&mut self, pixel_snap: bool) {
+        if self.pixel_snap != pixel_snap {
+            self.pixel_snap = pixel_snap;
+            self.glyph_cache.clear();
+        }
+    }
+
+    pub fn pixel_snap(&self) -> bool {
+        self.pixel_snap
+    }
+
+    /// Update DPI scale factor
+    ///
+    /// The glyph cache is keyed by effective size, so moving back to a display
+    /// seen before reuses its glyphs.
     pub fn update_scale_factor(&mut self, scale_factor: f64) {
         if (self.current_scale_factor - scale_factor).abs() > 0.001 {
             info!("DPI scale factor changed: {:.2}x -> {:.2}x", 
                   self.current_scale_factor, scale_factor);
             self.current_scale_factor = scale_factor;
-            self.glyph_cache.clear();
         }
     }
 
     /// Get or rasterize a glyph
     pub fn get_glyph(&mut self, ch: char) -> Result<&(usize, usize, Vec<u8>)> {
         let effective_size = self.effective_font_size();
-        let size_key = effective_size.round() as u32;
+        let size_key = effective_size.to_bits();
 
         if !self.glyph_cache.contains_key(&(ch, size_key)) {
             let (metrics, bitmap) = self.font.rasterize(ch, effective_size);
@@ -113,6 +235,40 @@ impl FontManager {
         self.glyph_cache.clear();
     }
 
+    /// Set the line-height and letter-spacing multipliers (clamped to 0.5-3.0)
+    pub fn set_spacing(&mut self, line_height: f32, letter_spacing: f32) {
+        self.line_height = line_height.clamp(MIN_SPACING, MAX_SPACING);
+        self.letter_spacing = letter_spacing.clamp(MIN_SPACING, MAX_SPACING);
+    }
+
+    /// Current (line_height, letter_spacing) multipliers
+    pub fn spacing(&self) -> (f32, f32) {
+        (self.line_height, self.letter_spacing)
+    }
+
+    /// Cell (width, height, baseline offset) in pixels at the effective font size
+    pub fn cell_metrics(&self) -> (f32, f32, f32) {
+        self.cell_metrics_at(self.effective_font_size())
+    }
+
+    /// Cell (width, height, baseline offset) in pixels at a given font size
+    ///
+    /// Width comes from the 'M' advance and height from the font's line metrics,
+    /// both scaled by the spacing multipliers. Extra line height is split above
+    /// and below the text so glyphs stay vertically centered.
+    pub fn cell_metrics_at(&self, size: f32) -> (f32, f32, f32) {
+        let line_metrics = self.font.horizontal_line_metrics(size).unwrap();
+        let natural_height = line_metrics.ascent - line_metrics.descent + line_metrics.line_gap;
+        let mut cell_width = self.font.metrics('M', size).advance_width * self.letter_spacing;
+        if self.pixel_snap {
+            cell_width = cell_width.round().max(1.0);
+        }
+        let cell_height = (natural_height * self.line_height).ceil();
+        let extra = ((cell_height - natural_height.ceil()) / 2.0).floor();
+        let baseline_offset = line_metrics.ascent.ceil() + extra;
+        (cell_width, cell_height, baseline_offset)
+    }
+
     /// Get current scale factor
     pub fn scale_factor(&self) -> f64 {
         self.current_scale_factor
@@ -132,4 +288,134 @@ impl FontManager {
     pub fn rasterize(&self, ch: char) -> (fontdue::Metrics, Vec<u8>) {
         self.font.rasterize(ch, self.effective_font_size())
     }
+
+    /// Rasterize a glyph with per-subpixel coverage (returns metrics and RGB bitmap, 3 bytes per pixel)
+    pub fn rasterize_subpixel(&self, ch: char) -> (fontdue::Metrics, Vec<u8>) {
+        self.font.rasterize_subpixel(ch, self.effective_font_size())
+    }
+}
+
+fn parse_font(data: Vec<u8>) -> Option<Font> {
+    Font::from_bytes(data, FontSettings::default()).ok()
+}
+
+/// Directories searched for user-configured font families
+fn font_directories() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(home) = std::env::var_os("HOME") {
+        dirs.push(PathBuf::from(home).join("Library/Fonts"));
+    }
+    dirs.push(PathBuf::from("/Library/Fonts"));
+    dirs.push(PathBuf::from("/System/Library/Fonts"));
+    dirs
+}
+
+/// File names a family is commonly installed under
+/// e.g. "JetBrains Mono" -> JetBrainsMono-Regular.ttf, JetBrains Mono.ttf, ...
+fn family_file_candidates(font_family: &str) -> Vec<String> {
+    let compact: String = font_family.split_whitespace().collect();
+    let mut bases = vec![compact.as_str(), font_family.trim()];
+    bases.dedup();
+
+    let mut candidates = Vec::new();
+    for base in bases.into_iter().filter(|b| !b.is_empty()) {
+        for ext in ["ttf", "otf", "ttc"] {
+            candidates.push(format!("{}-Regular.{}", base, ext));
+            candidates.push(format!("{}.{}", base, ext));
+        }
+    }
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_font_parses_and_is_monospace() {
+        let font = parse_font(BUNDLED_FONT.to_vec()).expect("bundled font should parse");
+        let m = font.metrics('M', 14.0).advance_width;
+        let i = font.metrics('i', 14.0).advance_width;
+        assert_eq!(m, i);
+    }
+
+    #[test]
+    fn test_missing_family_falls_back_with_warning() {
+        let fm = FontManager::new("Definitely Not A Real Font", 14.0).unwrap();
+        let warning = fm.fallback_warning().unwrap();
+        assert!(warning.contains("Definitely Not A Real Font"));
+    }
+
+    #[test]
+    fn test_subpixel_bitmap_has_rgb_coverage() {
+        let fm = FontManager::new("Definitely Not A Real Font", 14.0).unwrap();
+        let (gray_metrics, gray) = fm.rasterize('A');
+        let (metrics, rgb) = fm.rasterize_subpixel('A');
+        assert_eq!((metrics.width, metrics.height), (gray_metrics.width, gray_metrics.height));
+        assert_eq!(rgb.len(), gray.len() * 3);
+    }
+
+    #[test]
+    fn test_spacing_scales_cell_metrics() {
+        let mut fm = FontManager::new("Definitely Not A Real Font", 14.0).unwrap();
+        let (width, height, baseline) = fm.cell_metrics();
+
+        fm.set_spacing(1.5, 1.2);
+        let (spaced_width, spaced_height, spaced_baseline) = fm.cell_metrics();
+        assert!((spaced_width - width * 1.2).abs() < 0.01);
+        assert!(spaced_height >= (height * 1.5).floor());
+        assert!(spaced_baseline > baseline);
+
+        fm.set_spacing(10.0, 0.0);
+        assert_eq!(fm.spacing(), (MAX_SPACING, MIN_SPACING));
+    }
+
+    #[test]
+    fn test_pixel_snap_rounds_size_and_cell_width() {
+        let mut fm = FontManager::new_with_scale("Definitely Not A Real Font", 13.0, 1.25).unwrap();
+        assert!((fm.effective_font_size() - 16.25).abs() < 0.001);
+
+        fm.set_pixel_snap(true);
+        assert_eq!(fm.effective_font_size(), 16.0);
+        let (width, height, baseline) = fm.cell_metrics();
+        assert_eq!(width, width.round());
+        assert_eq!(height, height.round());
+        assert_eq!(baseline, baseline.round());
+    }
+
+    #[test]
+    fn test_zoomed_copy_matches_zoomed_metrics() {
+        let mut fm = FontManager::new_with_scale("Definitely Not A Real Font", 13.0, 1.25).unwrap();
+        fm.set_spacing(1.2, 1.0);
+        fm.set_pixel_snap(true);
+        let zoomed = fm.zoomed(1.5);
+        assert_eq!(zoomed.effective_font_size(), fm.zoomed_font_size(1.5));
+        assert_eq!(zoomed.cell_metrics(), fm.cell_metrics_at(fm.zoomed_font_size(1.5)));
+        assert_eq!(zoomed.spacing(), fm.spacing());
+        assert_eq!(fm.zoomed_font_size(1.0), fm.effective_font_size());
+    }
+
+    #[test]
+    fn test_glyphs_cached_per_scale_factor() {
+        let mut fm = FontManager::new_with_scale("Definitely Not A Real Font", 13.0, 2.0).unwrap();
+        assert_eq!(fm.effective_font_size_at(1.0), 13.0);
+        let retina = fm.get_glyph('M').unwrap().clone();
+
+        fm.update_scale_factor(1.0);
+        assert_eq!(fm.effective_font_size(), 13.0);
+        let external = fm.get_glyph('M').unwrap().clone();
+        assert!(external.1 < retina.1);
+
+        // Both sizes stay cached across the move
+        fm.update_scale_factor(2.0);
+        assert_eq!(fm.glyph_cache.len(), 2);
+        assert_eq!(fm.get_glyph('M').unwrap(), &retina);
+    }
+
+    #[test]
+    fn test_family_file_candidates() {
+        let candidates = family_file_candidates("JetBrains Mono");
+        assert!(candidates.contains(&"JetBrainsMono-Regular.ttf".to_string()));
+        assert!(candidates.contains(&"JetBrains Mono.ttf".to_string()));
+    }
 }
@@ -13,6 +13,66 @@
 /// - GPU-friendly f32 math throughout
 
 use alacritty_terminal::index::{Column, Line, Point};
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{MIN_CELL_DIMENSION, PADDING_BOTTOM, PADDING_LEFT, PADDING_RIGHT, PADDING_TOP};
+
+/// Space in pixels between a pane's edges and its grid (`window.padding` in config.toml)
+///
+/// Everything that places or counts cells takes it from the renderer, so the
+/// PTY size, glyphs, cursor, selection, and mouse hit-testing stay in step.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Padding {
+    pub top: f32,
+    pub left: f32,
+    pub right: f32,
+    pub bottom: f32,
+}
+
+impl Default for Padding {
+    fn default() -> Self {
+        Self {
+            top: PADDING_TOP,
+            left: PADDING_LEFT,
+            right: PADDING_RIGHT,
+            bottom: PADDING_BOTTOM,
+        }
+    }
+}
+
+impl Padding {
+    /// Negative and non-finite values become 0
+    pub fn sanitized(self) -> Self {
+        let clean = |value: f32| if value.is_finite() { value.max(0.0) } else { 0.0 };
+        Self {
+            top: clean(self.top),
+            left: clean(self.left),
+            right: clean(self.right),
+            bottom: clean(self.bottom),
+        }
+    }
+
+    /// Columns and rows of `cell_width` x `cell_height` that fit in `width` x `height`
+    /// pixels inside this padding (at least 1 x 1)
+    pub fn grid_size(&self, width: u32, height: u32, cell_width: f32, cell_height: f32) -> (usize, usize) {
+        let cell_width = cell_width.max(MIN_CELL_DIMENSION);
+        let cell_height = cell_height.max(MIN_CELL_DIMENSION);
+        let available_width = (width as f32 - self.left - self.right).max(0.0);
+        let available_height = (height as f32 - self.top - self.bottom).max(0.0);
+        let cols = (available_width / cell_width).floor() as usize;
+        let rows = (available_height / cell_height).floor() as usize;
+        (cols.max(1), rows.max(1))
+    }
+
+    /// Tallest height up to `height` that holds a whole number of `cell_height` rows
+    /// inside this padding (at least one row), so the last row isn't cut off
+    pub fn snapped_height(&self, height: u32, cell_height: f32) -> u32 {
+        let cell_height = cell_height.max(MIN_CELL_DIMENSION);
+        let (_, rows) = self.grid_size(1, height, MIN_CELL_DIMENSION, cell_height);
+        (self.top + rows as f32 * cell_height + self.bottom).ceil() as u32
+    }
+}
 
 /// Terminal geometry configuration for coordinate conversions
 #[derive(Debug, Clone)]
@@ -308,6 +368,30 @@ mod tests {
         assert!(ndc_y < 1.0);  // Not at very top (due to padding)
     }
 
+    #[test]
+    fn test_padding_grid_size_and_snapping() {
+        let padding = Padding { top: 5.0, left: 10.0, right: 10.0, bottom: 10.0 };
+        assert_eq!(padding.grid_size(800, 600, 10.0, 20.0), (78, 29));
+
+        let wide = Padding { top: 40.0, left: 100.0, right: 100.0, bottom: 0.0 };
+        assert_eq!(wide.grid_size(800, 600, 10.0, 20.0), (60, 28));
+
+        // Padding wider than the window still leaves one cell
+        assert_eq!(wide.grid_size(150, 30, 10.0, 20.0), (1, 1));
+
+        // Snapping drops the partial row and keeps the padding
+        let snapped = padding.snapped_height(610, 20.0);
+        assert_eq!(snapped, 5 + 29 * 20 + 10);
+        assert_eq!(padding.grid_size(800, snapped, 10.0, 20.0).1, 29);
+        assert_eq!(padding.snapped_height(snapped, 20.0), snapped);
+        let fractional = padding.snapped_height(600, 17.5);
+        assert_eq!(fractional, 593);
+        assert_eq!(padding.grid_size(800, fractional, 10.0, 17.5).1, 33);
+
+        let negative = Padding { top: -5.0, left: f32::NAN, right: 3.0, bottom: 1.0 }.sanitized();
+        assert_eq!(negative, Padding { top: 0.0, left: 0.0, right: 3.0, bottom: 1.0 });
+    }
+
     #[test]
     fn test_is_within_grid() {
         let geom = test_geometry();
@@ -0,0 +1,338 @@
+/// Command history shared across panes and sessions, with fuzzy search
+///
+/// Commands are captured from OSC 133 shell integration marks (see
+/// `shell_integration`) and persisted one per line, most recent last.
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Maximum number of remembered commands
+pub const MAX_HISTORY_ENTRIES: usize = 1000;
+
+/// Maximum number of results shown by history search
+const MAX_SEARCH_RESULTS: usize = 50;
+
+/// Deduplicated command history, most recent last
+#[derive(Debug, Default)]
+pub struct CommandHistory {
+    entries: Vec<String>,
+    path: Option<PathBuf>,
+}
+
+impl CommandHistory {
+    /// Default history file (~/.config/saternal/history)
+    pub fn default_path() -> Option<PathBuf> {
+        std::env::var_os("HOME").map(|home| {
+            let mut path = PathBuf::from(home);
+            path.push(".config");
+            path.push("saternal");
+            path.push("history");
+            path
+        })
+    }
+
+    /// Load history from a file (a missing file is an empty history)
+    pub fn load(path: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let path = path.into();
+        let mut history = Self {
+            entries: Vec::new(),
+            path: None,
+        };
+        if path.exists() {
+            for line in std::fs::read_to_string(&path)?.lines() {
+                history.push(line);
+            }
+        }
+        history.path = Some(path);
+        Ok(history)
+    }
+
+    /// Record a command, moving repeats to the most recent position
+    /// Returns true if the history changed
+    pub fn push(&mut self, command: &str) -> bool {
+        let command = command.trim();
+        if command.is_empty() || command.contains('\n') {
+            return false;
+        }
+        if self.entries.last().map(String::as_str) == Some(command) {
+            return false;
+        }
+
+        self.entries.retain(|entry| entry != command);
+        self.entries.push(command.to_string());
+        if self.entries.len() > MAX_HISTORY_ENTRIES {
+            let excess = self.entries.len() - MAX_HISTORY_ENTRIES;
+            self.entries.drain(..excess);
+        }
+        true
+    }
+
+    /// Write the history back to the file it was loaded from
+    pub fn save(&self) -> anyhow::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        write_lines(path, &self.entries)
+    }
+
+    /// Commands matching the query, best match first
+    ///
+    /// Matching is fuzzy (query characters in order, case-insensitive);
+    /// tighter and more recent matches rank higher.
+    pub fn search(&self, query: &str) -> Vec<&str> {
+        let query: Vec<char> = query.to_lowercase().chars().collect();
+
+        let mut scored: Vec<(usize, usize, &str)> = self
+            .entries
+            .iter()
+            .enumerate()
+            .rev()
+            .filter_map(|(age, entry)| fuzzy_score(&query, entry).map(|score| (score, age, entry.as_str())))
+            .collect();
+
+        // Lower score = tighter match; ties go to the most recent entry
+        scored.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)));
+        scored.into_iter().take(MAX_SEARCH_RESULTS).map(|(_, _, entry)| entry).collect()
+    }
+
+    /// Most recent command that starts with `typed` and goes on past it (autosuggestion)
+    pub fn suggest(&self, typed: &str) -> Option<&str> {
+        if typed.trim().is_empty() {
+            return None;
+        }
+        self.entries
+            .iter()
+            .rev()
+            .find(|entry| entry.len() > typed.len() && entry.starts_with(typed))
+            .map(String::as_str)
+    }
+
+    /// Number of remembered commands
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if no commands are remembered
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Write the file readable by the owner only; commands can carry tokens and passwords
+fn write_lines(path: &Path, lines: &[String]) -> anyhow::Result<()> {
+    let mut contents = lines.join("\n");
+    contents.push('\n');
+    write_private(path, contents.as_bytes())
+}
+
+/// Replace `path` with `contents`, readable and writable by the owner only
+/// (creating its directory if needed)
+pub(crate) fn write_private(path: &Path, contents: &[u8]) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    // The mode only applies to new files; tighten one written before
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    }
+    file.write_all(contents)?;
+    Ok(())
+}
+
+/// Span of the shortest in-order match of `query` in `text` (None if no match)
+pub(crate) fn fuzzy_score(query: &[char], text: &str) -> Option<usize> {
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut best = None;
+    for start in 0..text.len() {
+        if text[start] != query[0] {
+            continue;
+        }
+        let mut qi = 1;
+        let mut end = start;
+        for (ti, &c) in text.iter().enumerate().skip(start + 1) {
+            if qi == query.len() {
+                break;
+            }
+            if c == query[qi] {
+                qi += 1;
+                end = ti;
+            }
+        }
+        if qi == query.len() {
+            let span = end - start;
+            best = Some(best.map_or(span, |b: usize| b.min(span)));
+        }
+    }
+    best
+}
+
+/// State of the Cmd+R history search overlay
+#[derive(Debug, Default)]
+pub struct HistorySearch {
+    active: bool,
+    query: String,
+    results: Vec<String>,
+    selected: usize,
+}
+
+impl HistorySearch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open the search with an empty query (most recent commands first)
+    pub fn activate(&mut self, history: &CommandHistory) {
+        self.active = true;
+        self.query.clear();
+        self.refresh(history);
+    }
+
+    /// Close the search
+    pub fn deactivate(&mut self) {
+        self.active = false;
+        self.query.clear();
+        self.results.clear();
+        self.selected = 0;
+    }
+
+    /// Check if the search is open
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Append typed text to the query
+    pub fn insert_text(&mut self, text: &str, history: &CommandHistory) {
+        self.query.push_str(text);
+        self.refresh(history);
+    }
+
+    /// Delete the last query character
+    pub fn delete_backward(&mut self, history: &CommandHistory) {
+        self.query.pop();
+        self.refresh(history);
+    }
+
+    /// Move the selection to the next (older / worse) result
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.results.len() {
+            self.selected += 1;
+        }
+    }
+
+    /// Move the selection to the previous (better) result
+    pub fn select_prev(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    /// Currently selected command
+    pub fn selected(&self) -> Option<&str> {
+        self.results.get(self.selected).map(String::as_str)
+    }
+
+    /// Index of the selected result (0-based)
+    pub fn selected_index(&self) -> usize {
+        self.selected
+    }
+
+    /// Number of matching commands
+    pub fn result_count(&self) -> usize {
+        self.results.len()
+    }
+
+    /// Current query
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    fn refresh(&mut self, history: &CommandHistory) {
+        self.results = history.search(&self.query).into_iter().map(str::to_string).collect();
+        self.selected = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(commands: &[&str]) -> CommandHistory {
+        let mut history = CommandHistory::default();
+        for command in commands {
+            history.push(command);
+        }
+        history
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_saved_history_is_private_to_the_user() {
+        use std::os::unix::fs::PermissionsExt;
+        let path = std::env::temp_dir().join(format!("saternal-history-{}", std::process::id()));
+        // Left world-readable by an older version
+        std::fs::write(&path, "ls\n").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+        let mut history = CommandHistory::load(&path).unwrap();
+        history.push("export TOKEN=secret");
+        history.save().unwrap();
+        assert_eq!(path.metadata().unwrap().permissions().mode() & 0o777, 0o600);
+        assert_eq!(CommandHistory::load(&path).unwrap().len(), 2);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_push_dedups_and_moves_to_most_recent() {
+        let mut history = history(&["ls", "cargo build", "ls"]);
+        assert_eq!(history.search(""), vec!["ls", "cargo build"]);
+        assert!(!history.push("ls"));
+        assert!(!history.push("   "));
+        assert_eq!(history.len(), 2);
+    }
+
+    #[test]
+    fn test_fuzzy_search_prefers_tight_then_recent_matches() {
+        let history = history(&["git status", "cargo test", "git stash", "gst"]);
+        assert_eq!(history.search("gst")[0], "gst");
+        assert_eq!(history.search("gits"), vec!["git stash", "git status"]);
+        assert_eq!(history.search("CT"), vec!["cargo test"]);
+        assert!(history.search("xyz").is_empty());
+    }
+
+    #[test]
+    fn test_suggest_completes_the_most_recent_prefix_match() {
+        let history = history(&["git status", "git stash", "ls"]);
+        assert_eq!(history.suggest("git st"), Some("git stash"));
+        assert_eq!(history.suggest("git stat"), Some("git status"));
+        assert_eq!(history.suggest("ls"), None);
+        assert_eq!(history.suggest("  "), None);
+    }
+
+    #[test]
+    fn test_history_search_selection() {
+        let history = history(&["one", "two", "three"]);
+        let mut search = HistorySearch::new();
+        search.activate(&history);
+        assert_eq!(search.selected(), Some("three"));
+
+        search.select_next();
+        search.select_next();
+        search.select_next();
+        assert_eq!(search.selected(), Some("one"));
+
+        search.insert_text("tw", &history);
+        assert_eq!(search.selected(), Some("two"));
+        assert_eq!(search.result_count(), 1);
+    }
+}
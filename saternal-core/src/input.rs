@@ -18,6 +18,7 @@ pub enum MouseButton {
 #[derive(Debug, Clone)]
 pub struct MouseState {
     pub position: Point,
+    pub pixel_position: (f32, f32),  // Raw window coordinates of the last move
     pub button_pressed: Option<MouseButton>,
     pub drag_start: Option<Point>,
     pub click_count: u8,  // For double/triple click detection
@@ -28,6 +29,7 @@ impl MouseState {
     pub fn new() -> Self {
         Self {
             position: Point::new(Line(0), Column(0)),
+            pixel_position: (0.0, 0.0),
             button_pressed: None,
             drag_start: None,
             click_count: 0,
@@ -38,6 +40,7 @@ impl MouseState {
     /// Update mouse position from pixel coordinates
     pub fn update_position(&mut self, pixel_x: f32, pixel_y: f32, cell_width: f32, cell_height: f32) {
         self.position = pixel_to_grid(pixel_x, pixel_y, cell_width, cell_height);
+        self.pixel_position = (pixel_x, pixel_y);
     }
 
     /// Handle mouse button press
@@ -121,6 +124,33 @@ pub fn is_jump_to_bottom(_key: &Key, physical_key: KeyCode, mods: InputModifiers
     false
 }
 
+/// Scrollback navigation triggered from the keyboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScrollAction {
+    PageUp,
+    PageDown,
+    LineUp,
+    LineDown,
+    Top,
+    Bottom,
+}
+
+/// Scrollback keys: Shift+PageUp/PageDown, Shift+Up/Down, Cmd+Home/End
+pub fn scroll_action(physical_key: KeyCode, mods: InputModifiers) -> Option<ScrollAction> {
+    if mods.ctrl || mods.alt {
+        return None;
+    }
+    match (physical_key, mods.shift, mods.meta) {
+        (KeyCode::PageUp, true, false) => Some(ScrollAction::PageUp),
+        (KeyCode::PageDown, true, false) => Some(ScrollAction::PageDown),
+        (KeyCode::ArrowUp, true, false) => Some(ScrollAction::LineUp),
+        (KeyCode::ArrowDown, true, false) => Some(ScrollAction::LineDown),
+        (KeyCode::Home, false, true) => Some(ScrollAction::Top),
+        (KeyCode::End, false, true) => Some(ScrollAction::Bottom),
+        _ => None,
+    }
+}
+
 /// Convert a keyboard input to terminal bytes
 /// Returns Some(bytes) if the key produces terminal input, None otherwise
 pub fn key_to_bytes(
@@ -205,9 +235,8 @@ fn ctrl_key_to_byte(key: KeyCode) -> Option<u8> {
 /// Map special keys to ANSI escape sequences
 fn special_key_to_sequence(key: KeyCode, mods: InputModifiers) -> Option<Vec<u8>> {
     match key {
-        // Backspace and Delete
+        // Backspace
         KeyCode::Backspace => Some(vec![0x7F]), // DEL character (127)
-        KeyCode::Delete => Some(b"\x1b[3~".to_vec()),
         
         // Enter/Return
         KeyCode::Enter => Some(vec![0x0D]), // CR
@@ -225,63 +254,76 @@ fn special_key_to_sequence(key: KeyCode, mods: InputModifiers) -> Option<Vec<u8>
         // Escape
         KeyCode::Escape => Some(vec![0x1B]),
         
-        // Arrow keys
-        KeyCode::ArrowUp => arrow_sequence(b'A', mods),
-        KeyCode::ArrowDown => arrow_sequence(b'B', mods),
-        KeyCode::ArrowRight => arrow_sequence(b'C', mods),
-        KeyCode::ArrowLeft => arrow_sequence(b'D', mods),
-        
-        // Navigation keys
-        KeyCode::Home => navigation_sequence(b'H', 1, mods),
-        KeyCode::End => navigation_sequence(b'F', 4, mods),
-        KeyCode::PageUp => Some(b"\x1b[5~".to_vec()),
-        KeyCode::PageDown => Some(b"\x1b[6~".to_vec()),
-        KeyCode::Insert => Some(b"\x1b[2~".to_vec()),
-        
-        // Function keys
-        KeyCode::F1 => Some(b"\x1bOP".to_vec()),
-        KeyCode::F2 => Some(b"\x1bOQ".to_vec()),
-        KeyCode::F3 => Some(b"\x1bOR".to_vec()),
-        KeyCode::F4 => Some(b"\x1bOS".to_vec()),
-        KeyCode::F5 => Some(b"\x1b[15~".to_vec()),
-        KeyCode::F6 => Some(b"\x1b[17~".to_vec()),
-        KeyCode::F7 => Some(b"\x1b[18~".to_vec()),
-        KeyCode::F8 => Some(b"\x1b[19~".to_vec()),
-        KeyCode::F9 => Some(b"\x1b[20~".to_vec()),
-        KeyCode::F10 => Some(b"\x1b[21~".to_vec()),
-        KeyCode::F11 => Some(b"\x1b[23~".to_vec()),
-        KeyCode::F12 => Some(b"\x1b[24~".to_vec()),
-        
-        _ => None,
+        // Arrows, navigation and function keys (xterm encoding)
+        _ => csi_key(key).map(|csi| csi.encode(mods)),
     }
 }
 
-/// Generate arrow key sequences with optional modifiers
-/// Format: ESC[A or ESC[1;5A for modified (e.g., Ctrl+Up)
-fn arrow_sequence(letter: u8, mods: InputModifiers) -> Option<Vec<u8>> {
-    if mods.ctrl || mods.shift || mods.alt {
-        // Modified arrow key: ESC[1;{mod}A
-        let mod_code = mods.xterm_modifier_code();
-        Some(format!("\x1b[1;{}{}", mod_code, letter as char).into_bytes())
-    } else {
-        // Simple arrow key: ESC[A
-        Some(format!("\x1b[{}", letter as char).into_bytes())
-    }
+/// xterm encoding of a key that accepts modifiers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CsiKey {
+    /// ESC[{c}, modified ESC[1;{mod}{c} (arrows, Home/End)
+    Letter(u8),
+    /// ESC O{c}, modified ESC[1;{mod}{c} (F1-F4)
+    Ss3(u8),
+    /// ESC[{n}~, modified ESC[{n};{mod}~ (editing keys, F5-F20)
+    Tilde(u8),
 }
 
-/// Generate navigation key sequences (Home/End)
-/// Can use either ESC[H or ESC[1~ style depending on terminal mode
-fn navigation_sequence(letter: u8, _tilde_code: u8, mods: InputModifiers) -> Option<Vec<u8>> {
-    if mods.ctrl || mods.shift || mods.alt {
-        // Modified: ESC[1;{mod}H
+impl CsiKey {
+    fn encode(self, mods: InputModifiers) -> Vec<u8> {
+        let modified = mods.ctrl || mods.shift || mods.alt;
         let mod_code = mods.xterm_modifier_code();
-        Some(format!("\x1b[1;{}{}", mod_code, letter as char).into_bytes())
-    } else {
-        // Simple: ESC[H (using letter form, more compatible)
-        Some(format!("\x1b[{}", letter as char).into_bytes())
+        match self {
+            CsiKey::Letter(c) | CsiKey::Ss3(c) if modified => {
+                format!("\x1b[1;{}{}", mod_code, c as char).into_bytes()
+            }
+            CsiKey::Letter(c) => format!("\x1b[{}", c as char).into_bytes(),
+            CsiKey::Ss3(c) => format!("\x1bO{}", c as char).into_bytes(),
+            CsiKey::Tilde(n) if modified => format!("\x1b[{};{}~", n, mod_code).into_bytes(),
+            CsiKey::Tilde(n) => format!("\x1b[{}~", n).into_bytes(),
+        }
     }
 }
 
+/// Keys encoded as CSI/SS3 sequences, matching xterm's defaults
+const CSI_KEYS: &[(KeyCode, CsiKey)] = &[
+    (KeyCode::ArrowUp, CsiKey::Letter(b'A')),
+    (KeyCode::ArrowDown, CsiKey::Letter(b'B')),
+    (KeyCode::ArrowRight, CsiKey::Letter(b'C')),
+    (KeyCode::ArrowLeft, CsiKey::Letter(b'D')),
+    (KeyCode::Home, CsiKey::Letter(b'H')),
+    (KeyCode::End, CsiKey::Letter(b'F')),
+    (KeyCode::Insert, CsiKey::Tilde(2)),
+    (KeyCode::Delete, CsiKey::Tilde(3)),
+    (KeyCode::PageUp, CsiKey::Tilde(5)),
+    (KeyCode::PageDown, CsiKey::Tilde(6)),
+    (KeyCode::F1, CsiKey::Ss3(b'P')),
+    (KeyCode::F2, CsiKey::Ss3(b'Q')),
+    (KeyCode::F3, CsiKey::Ss3(b'R')),
+    (KeyCode::F4, CsiKey::Ss3(b'S')),
+    (KeyCode::F5, CsiKey::Tilde(15)),
+    (KeyCode::F6, CsiKey::Tilde(17)),
+    (KeyCode::F7, CsiKey::Tilde(18)),
+    (KeyCode::F8, CsiKey::Tilde(19)),
+    (KeyCode::F9, CsiKey::Tilde(20)),
+    (KeyCode::F10, CsiKey::Tilde(21)),
+    (KeyCode::F11, CsiKey::Tilde(23)),
+    (KeyCode::F12, CsiKey::Tilde(24)),
+    (KeyCode::F13, CsiKey::Tilde(25)),
+    (KeyCode::F14, CsiKey::Tilde(26)),
+    (KeyCode::F15, CsiKey::Tilde(28)),
+    (KeyCode::F16, CsiKey::Tilde(29)),
+    (KeyCode::F17, CsiKey::Tilde(31)),
+    (KeyCode::F18, CsiKey::Tilde(32)),
+    (KeyCode::F19, CsiKey::Tilde(33)),
+    (KeyCode::F20, CsiKey::Tilde(34)),
+];
+
+fn csi_key(key: KeyCode) -> Option<CsiKey> {
+    CSI_KEYS.iter().find(|(code, _)| *code == key).map(|(_, csi)| *csi)
+}
+
 /// Enable bracketed paste mode
 pub fn enable_bracketed_paste() -> Vec<u8> {
     b"\x1b[?2004h".to_vec()
@@ -327,7 +369,7 @@ mod tests {
             alt: false,
             meta: false,
         };
-        assert_eq!(arrow_sequence(b'A', mods), Some(b"\x1b[A".to_vec())); // Up
+        assert_eq!(special_key_to_sequence(KeyCode::ArrowUp, mods), Some(b"\x1b[A".to_vec())); // Up
         
         let mods_ctrl = InputModifiers {
             shift: false,
@@ -335,7 +377,7 @@ mod tests {
             alt: false,
             meta: false,
         };
-        assert_eq!(arrow_sequence(b'A', mods_ctrl), Some(b"\x1b[1;5A".to_vec())); // Ctrl+Up
+        assert_eq!(special_key_to_sequence(KeyCode::ArrowUp, mods_ctrl), Some(b"\x1b[1;5A".to_vec())); // Ctrl+Up
     }
 
     #[test]
@@ -350,4 +392,64 @@ mod tests {
         assert_eq!(special_key_to_sequence(KeyCode::Enter, mods), Some(vec![0x0D]));
         assert_eq!(special_key_to_sequence(KeyCode::Escape, mods), Some(vec![0x1B]));
     }
+
+    #[test]
+    fn test_scroll_actions() {
+        let mods = |shift, ctrl, meta| InputModifiers { shift, ctrl, alt: false, meta };
+        assert_eq!(scroll_action(KeyCode::PageUp, mods(true, false, false)), Some(ScrollAction::PageUp));
+        assert_eq!(scroll_action(KeyCode::ArrowDown, mods(true, false, false)), Some(ScrollAction::LineDown));
+        assert_eq!(scroll_action(KeyCode::Home, mods(false, false, true)), Some(ScrollAction::Top));
+        assert_eq!(scroll_action(KeyCode::End, mods(false, false, true)), Some(ScrollAction::Bottom));
+
+        // Unshifted and Ctrl-modified keys still go to the shell
+        assert_eq!(scroll_action(KeyCode::PageUp, mods(false, false, false)), None);
+        assert_eq!(scroll_action(KeyCode::ArrowUp, mods(true, true, false)), None);
+    }
+
+    #[test]
+    fn test_modified_key_encoding_table() {
+        let mods = |shift, alt, ctrl| InputModifiers { shift, ctrl, alt, meta: false };
+        let none = mods(false, false, false);
+        let shift = mods(true, false, false);
+        let alt = mods(false, true, false);
+        let ctrl = mods(false, false, true);
+        let all = mods(true, true, true);
+
+        let cases: &[(KeyCode, InputModifiers, &[u8])] = &[
+            (KeyCode::ArrowLeft, ctrl, b"\x1b[1;5D"),
+            (KeyCode::ArrowRight, alt, b"\x1b[1;3C"),
+            (KeyCode::ArrowDown, shift, b"\x1b[1;2B"),
+            (KeyCode::Home, none, b"\x1b[H"),
+            (KeyCode::End, shift, b"\x1b[1;2F"),
+            (KeyCode::PageUp, none, b"\x1b[5~"),
+            (KeyCode::PageDown, ctrl, b"\x1b[6;5~"),
+            (KeyCode::Delete, alt, b"\x1b[3;3~"),
+            (KeyCode::Insert, none, b"\x1b[2~"),
+            (KeyCode::F1, none, b"\x1bOP"),
+            (KeyCode::F4, shift, b"\x1b[1;2S"),
+            (KeyCode::F5, ctrl, b"\x1b[15;5~"),
+            (KeyCode::F12, all, b"\x1b[24;8~"),
+            (KeyCode::F13, none, b"\x1b[25~"),
+            (KeyCode::F20, shift, b"\x1b[34;2~"),
+        ];
+        for (key, mods, expected) in cases {
+            assert_eq!(
+                special_key_to_sequence(*key, *mods).as_deref(),
+                Some(*expected),
+                "{:?} with {:?}",
+                key,
+                mods
+            );
+        }
+    }
+
+    #[test]
+    fn test_every_table_key_encodes_modifiers() {
+        let ctrl = InputModifiers { shift: false, ctrl: true, alt: false, meta: false };
+        for (key, _) in CSI_KEYS {
+            let bytes = special_key_to_sequence(*key, ctrl).unwrap();
+            assert!(bytes.starts_with(b"\x1b["), "{:?}", key);
+            assert!(String::from_utf8(bytes).unwrap().contains(";5"), "{:?}", key);
+        }
+    }
 }
@@ -0,0 +1,816 @@
+/// Remote control of a running instance over a unix socket
+///
+/// Two protocols share the socket, told apart by the first line:
+/// - Plain (the CLI): one line such as `split v`, answered by one reply
+///   line, `ok [output]` or `error: <message>`, then the connection closes.
+/// - JSON-RPC 2.0 (lines starting with `{`): one request object per line,
+///   the connection stays open, and `subscribe` pushes `event`
+///   notifications for pane output and title changes.
+///
+/// Requests are handed to the UI thread through a channel; the `wake`
+/// callback given to IpcServer::start() nudges the event loop so they're
+/// handled right away.
+use crate::pane::SplitDirection;
+use anyhow::{anyhow, bail, Context, Result};
+use log::{info, warn};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Write};
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+// Windows 10 has AF_UNIX sockets too, std just doesn't wrap them there
+#[cfg(windows)]
+use uds_windows::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::net::Shutdown;
+use std::sync::mpsc::{self, Receiver, Sender, SyncSender};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Environment variable overriding the socket location
+pub const SOCKET_PATH_ENV: &str = "SATERNAL_SOCKET";
+
+/// How long a connection waits for the UI thread to answer
+const REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Lines queued for one JSON-RPC client before it counts as stuck and is disconnected
+const SUBSCRIBER_QUEUE_LINES: usize = 1024;
+
+/// Usage shown by the CLI for unknown subcommands
+pub const CLI_USAGE: &str = "\
+usage: saternal [--print-default]
+       saternal toggle             show or hide the dropdown
+       saternal run -- <command>   run a command in the focused pane
+       saternal split <h|v>        split the focused pane (h = stacked, v = side by side)
+       saternal set-theme <name>   switch the color theme";
+
+/// Socket of the running instance: $SATERNAL_SOCKET, else one only the user can reach
+///
+/// The socket can type into and read from every pane, so it lives in
+/// $XDG_RUNTIME_DIR, or else in a 0700 directory named after the uid in the
+/// temp dir (a name another user can't guess wrong or take over). On Windows
+/// the temp dir is already the user's own.
+pub fn socket_path() -> PathBuf {
+    if let Some(path) = std::env::var_os(SOCKET_PATH_ENV).filter(|value| !value.is_empty()) {
+        return PathBuf::from(path);
+    }
+    #[cfg(unix)]
+    {
+        if let Some(dir) = std::env::var_os("XDG_RUNTIME_DIR").filter(|value| !value.is_empty()) {
+            return PathBuf::from(dir).join("saternal.sock");
+        }
+        std::env::temp_dir().join(format!("saternal-{}", current_uid())).join("saternal.sock")
+    }
+    #[cfg(windows)]
+    std::env::temp_dir().join("saternal.sock")
+}
+
+#[cfg(unix)]
+fn current_uid() -> u32 {
+    // SAFETY: getuid has no failure modes
+    unsafe { libc::getuid() }
+}
+
+/// Fail unless `path` belongs to the current user, so another user's socket
+/// (or directory) is never talked to, listened in, or removed
+#[cfg(unix)]
+fn check_owner(path: &Path) -> Result<()> {
+    use std::os::unix::fs::MetadataExt;
+    let owner = std::fs::symlink_metadata(path)
+        .with_context(|| format!("reading {}", path.display()))?
+        .uid();
+    if owner != current_uid() {
+        bail!("{} belongs to another user", path.display());
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+fn check_owner(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+/// Create the socket's directory private to the user, or check that an existing one is theirs
+/// (or root's, like /tmp, where the sticky bit keeps the socket ours)
+#[cfg(unix)]
+fn prepare_socket_dir(path: &Path) -> Result<()> {
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+    let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) else {
+        return Ok(());
+    };
+    if !dir.exists() {
+        return std::fs::DirBuilder::new()
+            .recursive(true)
+            .mode(0o700)
+            .create(dir)
+            .with_context(|| format!("creating {}", dir.display()));
+    }
+    if dir.metadata()?.uid() != 0 {
+        check_owner(dir)?;
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+fn prepare_socket_dir(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+/// Bind the socket so that only the user can ever connect to it
+///
+/// The socket is bound in a fresh 0700 directory next to `path`, made 0600
+/// and only then moved into place, leaving no window where it's reachable
+/// with the umask's permissions.
+#[cfg(unix)]
+fn bind_private(path: &Path) -> Result<UnixListener> {
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+    let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let staging = dir.join(format!(".saternal-bind-{}", std::process::id()));
+    std::fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&staging)
+        .with_context(|| format!("creating {}", staging.display()))?;
+    let staged = staging.join("sock");
+    let bound = (|| {
+        let listener = UnixListener::bind(&staged).with_context(|| format!("binding {}", path.display()))?;
+        std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o600))?;
+        std::fs::rename(&staged, path).with_context(|| format!("moving the socket to {}", path.display()))?;
+        Ok(listener)
+    })();
+    let _ = std::fs::remove_file(&staged);
+    let _ = std::fs::remove_dir(&staging);
+    bound
+}
+
+#[cfg(windows)]
+fn bind_private(path: &Path) -> Result<UnixListener> {
+    UnixListener::bind(path).with_context(|| format!("binding {}", path.display()))
+}
+
+/// Something a script asks the running terminal to do
+#[derive(Debug, Clone, PartialEq)]
+pub enum IpcRequest {
+    /// Show or hide the dropdown, like the global hotkey
+    Toggle,
+    /// Type a command into the focused pane and press Enter
+    Run { command: String },
+    Split { direction: SplitDirection },
+    SetTheme { name: String },
+    /// Tabs with their panes (JSON-RPC only, like the rest below)
+    ListTabs,
+    /// Write text to a pane as if typed (no Enter added)
+    SendText { target: PaneTarget, text: String },
+    /// Text on a pane's screen, optionally with its scrollback
+    GetText { target: PaneTarget, scrollback: bool },
+}
+
+/// Pane addressed by a request; unset fields mean the active tab / focused pane
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PaneTarget {
+    pub tab: Option<usize>,
+    pub pane: Option<usize>,
+}
+
+/// Reply sent back to the client: a JSON result on success, a message on failure
+pub type IpcReply = std::result::Result<Value, String>;
+
+/// A tab as reported by `list_tabs`
+#[derive(Debug, Clone, Serialize)]
+pub struct TabInfo {
+    pub id: usize,
+    pub title: String,
+    pub active: bool,
+    pub panes: Vec<PaneInfo>,
+}
+
+/// A pane as reported by `list_tabs`
+#[derive(Debug, Clone, Serialize)]
+pub struct PaneInfo {
+    pub id: usize,
+    pub focused: bool,
+    pub cols: usize,
+    pub rows: usize,
+    /// Title set by the program (OSC 0/2)
+    pub title: Option<String>,
+    /// Foreground process name and working directory
+    pub process: Option<String>,
+    pub cwd: Option<PathBuf>,
+}
+
+/// Kinds of events a JSON-RPC client can subscribe to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventKind {
+    Output,
+    Title,
+}
+
+/// Pushed to subscribers as `{"jsonrpc": "2.0", "method": "event", "params": ...}`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum IpcEvent {
+    /// Raw PTY output (escape sequences included), decoded lossily as UTF-8
+    Output { tab: usize, pane: usize, data: String },
+    Title { tab: usize, pane: usize, title: Option<String> },
+}
+
+impl IpcEvent {
+    pub fn kind(&self) -> EventKind {
+        match self {
+            IpcEvent::Output { .. } => EventKind::Output,
+            IpcEvent::Title { .. } => EventKind::Title,
+        }
+    }
+}
+
+// JSON-RPC 2.0 error codes
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const SERVER_ERROR: i64 = -32000;
+
+/// Error object of a JSON-RPC response
+#[derive(Debug, PartialEq)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl ToString) -> Self {
+        Self { code, message: message.to_string() }
+    }
+}
+
+impl IpcRequest {
+    /// Parse CLI arguments (without the program name)
+    /// Returns None if the first argument isn't a remote-control subcommand
+    pub fn from_args(args: &[String]) -> Option<Result<Self>> {
+        let (subcommand, rest) = args.split_first()?;
+        let request = match subcommand.as_str() {
+            "toggle" => Ok(IpcRequest::Toggle),
+            "run" => {
+                let words = match rest.first() {
+                    Some(first) if first == "--" => &rest[1..],
+                    _ => rest,
+                };
+                match words {
+                    [] => Err(anyhow!("run needs a command: saternal run -- <command>")),
+                    // A single argument is taken as a complete shell command line
+                    [command] => Ok(IpcRequest::Run { command: command.clone() }),
+                    words => Ok(IpcRequest::Run {
+                        command: words.iter().map(|word| shell_quote(word)).collect::<Vec<_>>().join(" "),
+                    }),
+                }
+            }
+            "split" | "set-theme" => Self::parse_line(&args.join(" ")),
+            _ => return None,
+        };
+        Some(request)
+    }
+
+    /// Parse a request line as sent over the socket
+    pub fn parse_line(line: &str) -> Result<Self> {
+        let line = line.trim_end_matches(['\r', '\n']);
+        let (verb, arg) = line.split_once(' ').unwrap_or((line, ""));
+        let arg = arg.trim();
+        match (verb, arg) {
+            ("toggle", "") => Ok(IpcRequest::Toggle),
+            ("run", command) if !command.is_empty() => Ok(IpcRequest::Run { command: command.to_string() }),
+            ("split", "h") => Ok(IpcRequest::Split { direction: SplitDirection::Horizontal }),
+            ("split", "v") => Ok(IpcRequest::Split { direction: SplitDirection::Vertical }),
+            ("split", _) => Err(anyhow!("split takes h (stacked) or v (side by side)")),
+            ("set-theme", name) if !name.is_empty() => Ok(IpcRequest::SetTheme { name: name.to_string() }),
+            _ => Err(anyhow!("unknown request '{}'", line)),
+        }
+    }
+
+    /// Plain-protocol form of this request (one line, no newline)
+    /// None for requests only available over JSON-RPC
+    pub fn to_line(&self) -> Option<String> {
+        let line = match self {
+            IpcRequest::Toggle => "toggle".to_string(),
+            IpcRequest::Run { command } => format!("run {}", command),
+            IpcRequest::Split { direction: SplitDirection::Horizontal } => "split h".to_string(),
+            IpcRequest::Split { direction: SplitDirection::Vertical } => "split v".to_string(),
+            IpcRequest::SetTheme { name } => format!("set-theme {}", name),
+            IpcRequest::ListTabs | IpcRequest::SendText { .. } | IpcRequest::GetText { .. } => return None,
+        };
+        Some(line)
+    }
+
+    /// Build a request from a JSON-RPC method and its params
+    fn from_rpc(method: &str, params: Value) -> std::result::Result<Self, RpcError> {
+        #[derive(Deserialize)]
+        struct Run {
+            command: String,
+        }
+        #[derive(Deserialize)]
+        struct Split {
+            direction: SplitDirection,
+        }
+        #[derive(Deserialize)]
+        struct SetTheme {
+            name: String,
+        }
+        #[derive(Deserialize)]
+        struct SendText {
+            text: String,
+            tab: Option<usize>,
+            pane: Option<usize>,
+        }
+        #[derive(Deserialize)]
+        struct GetText {
+            tab: Option<usize>,
+            pane: Option<usize>,
+            #[serde(default)]
+            scrollback: bool,
+        }
+        fn params_as<T: serde::de::DeserializeOwned>(params: Value) -> std::result::Result<T, RpcError> {
+            let params = if params.is_null() { json!({}) } else { params };
+            serde_json::from_value(params).map_err(|e| RpcError::new(INVALID_PARAMS, e))
+        }
+
+        Ok(match method {
+            "toggle" => IpcRequest::Toggle,
+            "run" => {
+                let Run { command } = params_as(params)?;
+                IpcRequest::Run { command }
+            }
+            "split" => {
+                let Split { direction } = params_as(params)?;
+                IpcRequest::Split { direction }
+            }
+            "set_theme" => {
+                let SetTheme { name } = params_as(params)?;
+                IpcRequest::SetTheme { name }
+            }
+            "list_tabs" => IpcRequest::ListTabs,
+            "send_text" => {
+                let SendText { text, tab, pane } = params_as(params)?;
+                IpcRequest::SendText { target: PaneTarget { tab, pane }, text }
+            }
+            "get_text" => {
+                let GetText { tab, pane, scrollback } = params_as(params)?;
+                IpcRequest::GetText { target: PaneTarget { tab, pane }, scrollback }
+            }
+            _ => return Err(RpcError::new(METHOD_NOT_FOUND, format!("unknown method '{}'", method))),
+        })
+    }
+}
+
+/// Quote a word for the shell unless it only has safe characters
+fn shell_quote(word: &str) -> String {
+    let safe = |c: char| c.is_ascii_alphanumeric() || "-_./=:,+@%".contains(c);
+    if !word.is_empty() && word.chars().all(safe) {
+        word.to_string()
+    } else {
+        format!("'{}'", word.replace('\'', r"'\''"))
+    }
+}
+
+fn format_reply(reply: &IpcReply) -> String {
+    let one_line = |text: &str| text.replace('\n', " ");
+    match reply {
+        Ok(Value::Null) => "ok".to_string(),
+        Ok(Value::String(output)) if output.is_empty() => "ok".to_string(),
+        Ok(Value::String(output)) => format!("ok {}", one_line(output)),
+        Ok(result) => format!("ok {}", result),
+        Err(message) => format!("error: {}", one_line(message)),
+    }
+}
+
+fn parse_reply(line: &str) -> std::result::Result<String, String> {
+    let line = line.trim_end_matches(['\r', '\n']);
+    if let Some(message) = line.strip_prefix("error: ") {
+        return Err(message.to_string());
+    }
+    match line.strip_prefix("ok") {
+        Some(output) => Ok(output.trim_start().to_string()),
+        None => Err(format!("unexpected reply '{}'", line)),
+    }
+}
+
+/// A request waiting for the UI thread
+pub struct IpcCall {
+    pub request: IpcRequest,
+    reply: Sender<IpcReply>,
+}
+
+impl IpcCall {
+    /// Answer the client (ignored if it already gave up)
+    pub fn reply(self, reply: IpcReply) {
+        let _ = self.reply.send(reply);
+    }
+}
+
+/// Listens on the control socket; the socket file is removed on drop
+pub struct IpcServer {
+    calls: Receiver<IpcCall>,
+    shared: Arc<Shared>,
+    path: PathBuf,
+}
+
+/// State the connection threads share with the server
+struct Shared {
+    calls: Sender<IpcCall>,
+    wake: Mutex<Box<dyn Fn() + Send>>,
+    subscribers: Mutex<Vec<Subscriber>>,
+    next_connection: AtomicUsize,
+}
+
+/// A JSON-RPC connection that asked for events
+struct Subscriber {
+    connection: usize,
+    events: Vec<EventKind>,
+    out: SyncSender<String>,
+    /// Shut down when the client falls too far behind
+    stream: UnixStream,
+}
+
+impl IpcServer {
+    /// Bind the socket and accept connections on a background thread
+    /// Fails if another instance is already listening on `path`, or if it belongs to another user
+    pub fn start(path: &Path, wake: impl Fn() + Send + 'static) -> Result<Self> {
+        prepare_socket_dir(path)?;
+        if path.exists() {
+            check_owner(path)?;
+            if UnixStream::connect(path).is_ok() {
+                bail!("another instance is listening on {}", path.display());
+            }
+            // Left behind by a crashed instance
+            std::fs::remove_file(path)?;
+        }
+        let listener = bind_private(path)?;
+        info!("Remote control listening on {}", path.display());
+
+        let (sender, calls) = mpsc::channel();
+        let shared = Arc::new(Shared {
+            calls: sender,
+            wake: Mutex::new(Box::new(wake)),
+            subscribers: Mutex::new(Vec::new()),
+            next_connection: AtomicUsize::new(0),
+        });
+        let listener_shared = shared.clone();
+        std::thread::Builder::new()
+            .name("saternal-ipc".to_string())
+            .spawn(move || {
+                for stream in listener.incoming() {
+                    let stream = match stream {
+                        Ok(stream) => stream,
+                        Err(e) => {
+                            warn!("Remote control accept failed: {}", e);
+                            continue;
+                        }
+                    };
+                    // JSON-RPC clients stay connected, so each gets its own thread
+                    let shared = listener_shared.clone();
+                    let spawned = std::thread::Builder::new()
+                        .name("saternal-ipc-conn".to_string())
+                        .spawn(move || {
+                            if let Err(e) = serve(stream, &shared) {
+                                warn!("Remote control connection failed: {}", e);
+                            }
+                        });
+                    if let Err(e) = spawned {
+                        warn!("Remote control connection dropped: {}", e);
+                    }
+                }
+            })?;
+
+        Ok(Self { calls, shared, path: path.to_path_buf() })
+    }
+
+    /// Next request waiting to be handled, if any
+    pub fn try_recv(&self) -> Option<IpcCall> {
+        self.calls.try_recv().ok()
+    }
+
+    /// Whether any client subscribed to events of this kind
+    pub fn is_subscribed(&self, kind: EventKind) -> bool {
+        self.shared.subscribers.lock().iter().any(|subscriber| subscriber.events.contains(&kind))
+    }
+
+    /// Push an event to the clients subscribed to its kind
+    pub fn publish(&self, event: &IpcEvent) {
+        let kind = event.kind();
+        let mut subscribers = self.shared.subscribers.lock();
+        if !subscribers.iter().any(|subscriber| subscriber.events.contains(&kind)) {
+            return;
+        }
+        let line = json!({ "jsonrpc": "2.0", "method": "event", "params": event }).to_string();
+        // A client whose queue is full isn't reading; cut it off rather than buffer without limit
+        subscribers.retain(|subscriber| {
+            if !subscriber.events.contains(&kind) || subscriber.out.try_send(line.clone()).is_ok() {
+                return true;
+            }
+            warn!("Remote control client {} fell behind, disconnecting", subscriber.connection);
+            let _ = subscriber.stream.shutdown(Shutdown::Both);
+            false
+        });
+    }
+}
+
+impl Drop for IpcServer {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Hand a request to the UI thread and wait for its reply
+fn call(shared: &Shared, request: IpcRequest) -> IpcReply {
+    let (reply, response) = mpsc::channel();
+    if shared.calls.send(IpcCall { request, reply }).is_err() {
+        return Err("the terminal is shutting down".to_string());
+    }
+    (shared.wake.lock())();
+    response
+        .recv_timeout(REPLY_TIMEOUT)
+        .unwrap_or_else(|_| Err("no reply from the terminal".to_string()))
+}
+
+/// Answer one plain request, or JSON-RPC requests until the client hangs up
+fn serve(stream: UnixStream, shared: &Shared) -> Result<()> {
+    stream.set_read_timeout(Some(REPLY_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+
+    if !line.trim_start().starts_with('{') {
+        let reply = match IpcRequest::parse_line(&line) {
+            Ok(request) => call(shared, request),
+            Err(e) => Err(e.to_string()),
+        };
+        writeln!(&stream, "{}", format_reply(&reply))?;
+        return Ok(());
+    }
+
+    // Responses and events both go through one writer thread, so a slow
+    // client never blocks the UI thread publishing events
+    stream.set_read_timeout(None)?;
+    let (out, outgoing) = mpsc::sync_channel::<String>(SUBSCRIBER_QUEUE_LINES);
+    let mut writer = stream.try_clone()?;
+    std::thread::Builder::new()
+        .name("saternal-ipc-write".to_string())
+        .spawn(move || {
+            for line in outgoing {
+                if writeln!(writer, "{}", line).is_err() {
+                    break;
+                }
+            }
+        })?;
+
+    let connection = shared.next_connection.fetch_add(1, Ordering::Relaxed);
+    loop {
+        if !line.trim().is_empty() {
+            if let Some(response) = handle_rpc(&line, connection, &out, &stream, shared) {
+                if out.send(response).is_err() {
+                    break;
+                }
+            }
+        }
+        line.clear();
+        // Reads fail too once a lagging subscriber was shut down
+        if reader.read_line(&mut line).unwrap_or(0) == 0 {
+            break;
+        }
+    }
+    shared.subscribers.lock().retain(|subscriber| subscriber.connection != connection);
+    Ok(())
+}
+
+/// Run one JSON-RPC request line; None for notifications (no id)
+fn handle_rpc(line: &str, connection: usize, out: &SyncSender<String>, stream: &UnixStream, shared: &Shared) -> Option<String> {
+    #[derive(Deserialize)]
+    struct Message {
+        #[serde(default)]
+        id: Value,
+        method: String,
+        #[serde(default)]
+        params: Value,
+    }
+
+    let message: Message = match serde_json::from_str(line) {
+        Ok(message) => message,
+        Err(e) => return Some(rpc_response(Value::Null, Err(RpcError::new(PARSE_ERROR, e)))),
+    };
+    let result = if message.method == "subscribe" {
+        subscribe(message.params, connection, out, stream, shared)
+    } else {
+        IpcRequest::from_rpc(&message.method, message.params)
+            .and_then(|request| call(shared, request).map_err(|e| RpcError::new(SERVER_ERROR, e)))
+    };
+    (!message.id.is_null()).then(|| rpc_response(message.id, result))
+}
+
+/// `subscribe {"events": ["output", "title"]}` (default: all events)
+fn subscribe(
+    params: Value,
+    connection: usize,
+    out: &SyncSender<String>,
+    stream: &UnixStream,
+    shared: &Shared,
+) -> std::result::Result<Value, RpcError> {
+    #[derive(Deserialize)]
+    struct Subscribe {
+        #[serde(default = "all_events")]
+        events: Vec<EventKind>,
+    }
+    fn all_events() -> Vec<EventKind> {
+        vec![EventKind::Output, EventKind::Title]
+    }
+
+    let params = if params.is_null() { json!({}) } else { params };
+    let Subscribe { events } = serde_json::from_value(params).map_err(|e| RpcError::new(INVALID_PARAMS, e))?;
+    let result = json!(events);
+    let stream = stream.try_clone().map_err(|e| RpcError::new(SERVER_ERROR, e))?;
+    let mut subscribers = shared.subscribers.lock();
+    subscribers.retain(|subscriber| subscriber.connection != connection);
+    subscribers.push(Subscriber { connection, events, out: out.clone(), stream });
+    Ok(result)
+}
+
+fn rpc_response(id: Value, result: std::result::Result<Value, RpcError>) -> String {
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(error) => json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": error.code, "message": error.message },
+        }),
+    }
+    .to_string()
+}
+
+/// Send a request to the running instance and wait for its reply
+pub fn send(path: &Path, request: &IpcRequest) -> Result<std::result::Result<String, String>> {
+    let line = request
+        .to_line()
+        .ok_or_else(|| anyhow!("{:?} is only available over JSON-RPC", request))?;
+    if path.exists() {
+        check_owner(path)?;
+    }
+    let stream = UnixStream::connect(path)
+        .with_context(|| format!("no running Saternal at {}", path.display()))?;
+    stream.set_read_timeout(Some(REPLY_TIMEOUT * 2))?;
+    writeln!(&stream, "{}", line)?;
+
+    let mut line = String::new();
+    BufReader::new(&stream).read_line(&mut line)?;
+    Ok(parse_reply(&line))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    #[test]
+    fn test_cli_arguments() {
+        let parse = |words: &[&str]| IpcRequest::from_args(&args(words)).map(|r| r.map_err(|e| e.to_string()));
+
+        assert_eq!(parse(&["toggle"]).unwrap(), Ok(IpcRequest::Toggle));
+        assert_eq!(
+            parse(&["run", "--", "echo", "it's here", "$HOME"]).unwrap(),
+            Ok(IpcRequest::Run { command: r"echo 'it'\''s here' '$HOME'".to_string() })
+        );
+        assert_eq!(
+            parse(&["run", "cargo build && cargo test"]).unwrap(),
+            Ok(IpcRequest::Run { command: "cargo build && cargo test".to_string() })
+        );
+        assert_eq!(parse(&["split", "v"]).unwrap(), Ok(IpcRequest::Split { direction: SplitDirection::Vertical }));
+        assert_eq!(parse(&["set-theme", "dracula"]).unwrap(), Ok(IpcRequest::SetTheme { name: "dracula".to_string() }));
+        assert!(parse(&["run", "--"]).unwrap().is_err());
+        assert!(parse(&["split", "x"]).unwrap().is_err());
+        assert!(parse(&["--print-default"]).is_none());
+        assert!(parse(&[]).is_none());
+    }
+
+    #[test]
+    fn test_request_round_trips_through_the_socket() {
+        let path = std::env::temp_dir().join(format!("saternal-test-{}.sock", std::process::id()));
+        let server = IpcServer::start(&path, || {}).unwrap();
+        assert!(IpcServer::start(&path, || {}).is_err());
+
+        let answer = std::thread::spawn(move || loop {
+            if let Some(call) = server.try_recv() {
+                let reply = match &call.request {
+                    IpcRequest::SetTheme { name } if name == "dracula" => Ok(Value::Null),
+                    _ => Err("unknown theme".to_string()),
+                };
+                call.reply(reply);
+                return server;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        });
+
+        let request = IpcRequest::SetTheme { name: "dracula".to_string() };
+        assert_eq!(send(&path, &request).unwrap(), Ok(String::new()));
+        drop(answer.join().unwrap());
+        assert!(!path.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_socket_is_private_to_the_user() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = std::env::temp_dir().join(format!("saternal-private-test-{}", std::process::id()));
+        let path = dir.join("saternal.sock");
+        let server = IpcServer::start(&path, || {}).unwrap();
+        assert_eq!(dir.metadata().unwrap().permissions().mode() & 0o777, 0o700);
+        assert_eq!(path.metadata().unwrap().permissions().mode() & 0o777, 0o600);
+        assert!(check_owner(&path).is_ok());
+        drop(server);
+        std::fs::remove_dir(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_rpc_methods() {
+        assert_eq!(IpcRequest::from_rpc("toggle", Value::Null), Ok(IpcRequest::Toggle));
+        assert_eq!(
+            IpcRequest::from_rpc("split", json!({ "direction": "vertical" })),
+            Ok(IpcRequest::Split { direction: SplitDirection::Vertical })
+        );
+        assert_eq!(
+            IpcRequest::from_rpc("get_text", json!({ "pane": 2 })),
+            Ok(IpcRequest::GetText { target: PaneTarget { tab: None, pane: Some(2) }, scrollback: false })
+        );
+        assert_eq!(IpcRequest::from_rpc("send_text", json!({})).unwrap_err().code, INVALID_PARAMS);
+        assert_eq!(IpcRequest::from_rpc("reboot", Value::Null).unwrap_err().code, METHOD_NOT_FOUND);
+        assert_eq!(IpcRequest::ListTabs.to_line(), None);
+    }
+
+    #[test]
+    fn test_json_rpc_calls_and_events() {
+        let path = std::env::temp_dir().join(format!("saternal-rpc-test-{}.sock", std::process::id()));
+        let server = IpcServer::start(&path, || {}).unwrap();
+
+        let client_path = path.clone();
+        let client = std::thread::spawn(move || {
+            let stream = UnixStream::connect(client_path).unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut exchange = |request: &str| {
+                writeln!(&stream, "{}", request).unwrap();
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                serde_json::from_str::<Value>(&line).unwrap()
+            };
+            let subscribed = exchange(r#"{"jsonrpc":"2.0","id":1,"method":"subscribe","params":{"events":["title"]}}"#);
+            let unknown = exchange(r#"{"jsonrpc":"2.0","id":2,"method":"reboot"}"#);
+            // The event may arrive before or after the list_tabs response
+            let first = exchange(r#"{"jsonrpc":"2.0","id":3,"method":"list_tabs"}"#);
+            let mut second = String::new();
+            reader.read_line(&mut second).unwrap();
+            let second = serde_json::from_str::<Value>(&second).unwrap();
+            let (tabs, event) = if first.get("id").is_some() { (first, second) } else { (second, first) };
+            (subscribed, unknown, tabs, event)
+        });
+
+        loop {
+            if let Some(call) = server.try_recv() {
+                assert_eq!(call.request, IpcRequest::ListTabs);
+                call.reply(Ok(json!([{ "id": 0 }])));
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert!(server.is_subscribed(EventKind::Title));
+        assert!(!server.is_subscribed(EventKind::Output));
+        server.publish(&IpcEvent::Output { tab: 0, pane: 0, data: "ignored".to_string() });
+        server.publish(&IpcEvent::Title { tab: 0, pane: 1, title: Some("vim".to_string()) });
+
+        let (subscribed, unknown, tabs, event) = client.join().unwrap();
+        assert_eq!(subscribed["result"], json!(["title"]));
+        assert_eq!(unknown["error"]["code"], json!(METHOD_NOT_FOUND));
+        assert_eq!(tabs, json!({ "jsonrpc": "2.0", "id": 3, "result": [{ "id": 0 }] }));
+        assert_eq!(
+            event,
+            json!({ "jsonrpc": "2.0", "method": "event", "params": { "type": "title", "tab": 0, "pane": 1, "title": "vim" } })
+        );
+    }
+
+    #[test]
+    fn test_stuck_subscriber_is_disconnected() {
+        let path = std::env::temp_dir().join(format!("saternal-stuck-test-{}.sock", std::process::id()));
+        let server = IpcServer::start(&path, || {}).unwrap();
+        // Nobody drains this queue, like a client that stopped reading
+        let (out, _outgoing) = mpsc::sync_channel(SUBSCRIBER_QUEUE_LINES);
+        let (stream, mut client) = UnixStream::pair().unwrap();
+        server.shared.subscribers.lock().push(Subscriber { connection: 0, events: vec![EventKind::Title], out, stream });
+
+        let event = IpcEvent::Title { tab: 0, pane: 0, title: None };
+        for _ in 0..SUBSCRIBER_QUEUE_LINES {
+            server.publish(&event);
+        }
+        assert!(server.is_subscribed(EventKind::Title));
+        server.publish(&event);
+        assert!(!server.is_subscribed(EventKind::Title));
+        let mut rest = Vec::new();
+        assert_eq!(std::io::Read::read_to_end(&mut client, &mut rest).unwrap(), 0);
+    }
+}
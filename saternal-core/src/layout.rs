@@ -0,0 +1,155 @@
+/// Declarative pane layouts from config
+///
+/// ```toml
+/// [layouts.dev]
+/// split = "vertical"          # side by side ("horizontal" = stacked)
+/// [[layouts.dev.panes]]
+/// cwd = "~/code/saternal"
+/// [[layouts.dev.panes]]
+/// split = "horizontal"
+/// [[layouts.dev.panes.panes]]
+/// command = "cargo watch -x check"
+/// [[layouts.dev.panes.panes]]
+/// command = "btop"
+/// ```
+///
+/// A node with `panes` is a split; anything else is a single pane. Splits
+/// with more than two panes are divided evenly. The top-level node may set
+/// `wallpaper` (and `wallpaper_opacity`) for the tab the layout opens in.
+use crate::pane::SplitDirection;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// One node of a configured layout
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LayoutNode {
+    /// Split direction for `panes` (default: vertical, side by side)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub split: Option<SplitDirection>,
+    /// Share of space for the first pane of a two-pane split (0.1-0.9)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ratio: Option<f32>,
+    /// Child panes; empty for a single pane
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub panes: Vec<LayoutNode>,
+    /// Working directory for the pane's shell (`~` expands to $HOME)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    /// Command sent once the pane's shell shows its prompt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// Wallpaper for the whole tab (top-level node only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wallpaper: Option<String>,
+    /// Opacity for the tab's wallpaper (top-level node only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wallpaper_opacity: Option<f32>,
+}
+
+/// Layout normalized into the binary shape of the pane tree
+#[derive(Debug, Clone, PartialEq)]
+pub enum LayoutPlan {
+    Pane {
+        cwd: Option<PathBuf>,
+        command: Option<String>,
+    },
+    Split {
+        direction: SplitDirection,
+        ratio: f32,
+        children: Box<[LayoutPlan; 2]>,
+    },
+}
+
+impl LayoutNode {
+    /// Normalize into a tree of two-way splits
+    pub fn plan(&self) -> LayoutPlan {
+        match self.panes.as_slice() {
+            [] => LayoutPlan::Pane {
+                cwd: self.cwd.as_deref().map(expand_home),
+                command: self.command.clone().filter(|c| !c.trim().is_empty()),
+            },
+            [only] => only.plan(),
+            [first, rest @ ..] => {
+                let direction = self.split.unwrap_or(SplitDirection::Vertical);
+                let even = 1.0 / self.panes.len() as f32;
+                let ratio = match rest.len() {
+                    1 => self.ratio.unwrap_or(even).clamp(0.1, 0.9),
+                    _ => even,
+                };
+                let remainder = LayoutNode {
+                    split: Some(direction),
+                    panes: rest.to_vec(),
+                    ..Default::default()
+                };
+                LayoutPlan::Split {
+                    direction,
+                    ratio,
+                    children: Box::new([first.plan(), remainder.plan()]),
+                }
+            }
+        }
+    }
+}
+
+/// Expand a leading `~` to the home directory
+pub(crate) fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix('~'), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => PathBuf::from(home).join(rest.trim_start_matches('/')),
+        _ => PathBuf::from(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn pane(command: &str) -> LayoutPlan {
+        LayoutPlan::Pane { cwd: None, command: Some(command.to_string()) }
+    }
+
+    #[test]
+    fn test_three_panes_split_evenly() {
+        let layouts: BTreeMap<String, LayoutNode> = toml::from_str(
+            r#"
+            [dev]
+            split = "horizontal"
+            [[dev.panes]]
+            command = "a"
+            [[dev.panes]]
+            command = "b"
+            [[dev.panes]]
+            command = "c"
+            "#,
+        )
+        .unwrap();
+
+        let LayoutPlan::Split { direction, ratio, children } = layouts["dev"].plan() else {
+            panic!("expected a split");
+        };
+        assert_eq!(direction, SplitDirection::Horizontal);
+        assert!((ratio - 1.0 / 3.0).abs() < 0.001);
+        assert_eq!(children[0], pane("a"));
+        assert_eq!(
+            children[1],
+            LayoutPlan::Split {
+                direction: SplitDirection::Horizontal,
+                ratio: 0.5,
+                children: Box::new([pane("b"), pane("c")]),
+            }
+        );
+    }
+
+    #[test]
+    fn test_single_pane_and_ratio() {
+        let leaf = LayoutNode { cwd: Some("/tmp".to_string()), ..Default::default() };
+        assert_eq!(leaf.plan(), LayoutPlan::Pane { cwd: Some(PathBuf::from("/tmp")), command: None });
+
+        let node = LayoutNode { ratio: Some(0.7), panes: vec![leaf.clone(), leaf], ..Default::default() };
+        let LayoutPlan::Split { direction, ratio, .. } = node.plan() else {
+            panic!("expected a split");
+        };
+        assert_eq!(direction, SplitDirection::Vertical);
+        assert_eq!(ratio, 0.7);
+    }
+}
@@ -1,23 +1,61 @@
+pub mod backend;
+pub mod badge;
+pub mod blink;
 pub mod clipboard;
+pub mod close_guard;
+pub mod command_duration;
 pub mod config;
+#[cfg(windows)]
+pub mod conpty;
+pub mod crash;
 pub mod constants;
+pub mod cursor;
+pub mod feedback;
 pub mod font;
 pub mod geometry;
+pub mod history;
 pub mod input;
+pub mod ipc;
+pub mod layout;
+pub mod local_echo;
+pub mod output_parser;
 pub mod pane;
-pub mod renderer;
+pub mod pane_log;
+pub mod platform;
+pub mod privacy;
+pub mod process;
+pub mod pty_reader;
 pub mod search;
+pub mod secure_input;
 pub mod selection;
+pub mod shell_integration;
+pub mod snippet;
+pub mod startup;
 pub mod terminal;
+pub mod theme;
+pub mod trigger;
+pub mod update;
+pub mod wallpaper;
 
-pub use clipboard::Clipboard;
-pub use config::Config;
+pub use backend::RendererBackend;
+pub use clipboard::{Clipboard, ClipboardHistory, PasteTransform};
+pub use close_guard::{CloseGuard, CloseTarget};
+pub use secure_input::PasteGuard;
+pub use config::{BellMode, ColorSpace, Config, WindowMode};
+pub use feedback::FeedbackKind;
 pub use constants::{PADDING_LEFT, PADDING_TOP, PADDING_RIGHT, PADDING_BOTTOM, MIN_CELL_DIMENSION};
-pub use font::FontManager;
-pub use geometry::TerminalGeometry;
-pub use input::{key_to_bytes, InputModifiers, is_jump_to_bottom, MouseButton, MouseState, pixel_to_grid};
+pub use font::{Antialias, FontManager};
+pub use geometry::{Padding, TerminalGeometry};
+pub use history::{CommandHistory, HistorySearch};
+pub use layout::{LayoutNode, LayoutPlan};
+pub use ipc::{IpcCall, IpcEvent, IpcReply, IpcRequest, IpcServer};
+pub use input::{key_to_bytes, InputModifiers, is_jump_to_bottom, scroll_action, MouseButton, MouseState, pixel_to_grid, ScrollAction};
 pub use pane::{Pane, PaneNode, SplitDirection};
-pub use renderer::Renderer;
-pub use search::{SearchEngine, SearchState};
-pub use selection::{SelectionManager, SelectionMode, SelectionRange, PaneViewport, calculate_pane_viewports};
+pub use platform::{ClipboardProvider, DropdownSurface, GlobalHotkeys};
+pub use privacy::PrivacyLock;
+pub use process::ProcessInfo;
+pub use search::{LineFilter, SearchEngine, SearchOptions, SearchState};
+pub use snippet::{SnippetInsert, SnippetPicker};
+pub use selection::{SelectionManager, SelectionMode, SelectionRange, SelectionStyle, PaneViewport, calculate_pane_viewports, pane_at};
 pub use terminal::{Terminal, TermEventListener};
+pub use theme::ColorPalette;
@@ -0,0 +1,133 @@
+/// Predictive local echo for high-latency sessions (as in mosh)
+///
+/// Typed characters are drawn right away, in their own style, at the cells
+/// where the echo should land. Once the PTY output puts the same character
+/// there the prediction is confirmed and dropped. If something else shows
+/// up, the cursor moves past it, or no echo comes within ECHO_TIMEOUT
+/// (a password prompt, a program that doesn't echo), every prediction is
+/// dropped and the screen shows only what the program drew.
+use alacritty_terminal::grid::{Dimensions, Grid};
+use alacritty_terminal::index::Point;
+use alacritty_terminal::term::cell::Cell;
+use std::time::{Duration, Instant};
+
+/// Longest a prediction is shown without its echo
+pub const ECHO_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy)]
+struct Prediction {
+    point: Point,
+    c: char,
+    typed: Instant,
+}
+
+/// Characters typed into a pane whose echo hasn't arrived yet
+#[derive(Debug, Default)]
+pub struct LocalEcho {
+    predictions: Vec<Prediction>,
+}
+
+impl LocalEcho {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.predictions.is_empty()
+    }
+
+    /// Predict the echo of `text`, typed with the cursor at `cursor`
+    ///
+    /// Only plain ASCII is predicted, on the cursor's line; anything else
+    /// drops the predictions, since its width or effect can't be known.
+    pub fn predict(&mut self, text: &str, cursor: Point, columns: usize, now: Instant) {
+        let mut next = self.predictions.last().map_or(cursor, |last| Point::new(last.point.line, last.point.column + 1));
+        for c in text.chars() {
+            if !(c.is_ascii_graphic() || c == ' ') || next.column.0 >= columns {
+                self.clear();
+                return;
+            }
+            self.predictions.push(Prediction { point: next, c, typed: now });
+            next.column += 1;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.predictions.clear();
+    }
+
+    /// Drop predictions the output confirmed; drop them all once one turns out wrong or stale
+    pub fn reconcile(&mut self, grid: &Grid<Cell>, now: Instant) {
+        let cursor = grid.cursor.point;
+        let confirmed = self
+            .predictions
+            .iter()
+            .take_while(|p| {
+                let on_screen = p.point.line.0 < grid.screen_lines() as i32 && p.point.column.0 < grid.columns();
+                on_screen && grid[p.point].c == p.c
+            })
+            .count();
+        self.predictions.drain(..confirmed);
+
+        if let Some(first) = self.predictions.first() {
+            let passed = cursor.line != first.point.line || cursor.column > first.point.column;
+            if passed || now.duration_since(first.typed) > ECHO_TIMEOUT {
+                self.clear();
+            }
+        }
+    }
+
+    /// Cells to draw predicted characters in, and the characters
+    pub fn predictions(&self) -> Vec<(Point, char)> {
+        self.predictions.iter().map(|p| (p.point, p.c)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alacritty_terminal::index::{Column, Line};
+
+    fn grid_with(text: &str, cursor: usize) -> Grid<Cell> {
+        let mut grid: Grid<Cell> = Grid::new(2, 10, 0);
+        for (col, c) in text.chars().enumerate() {
+            grid[Point::new(Line(0), Column(col))].c = c;
+        }
+        grid.cursor.point = Point::new(Line(0), Column(cursor));
+        grid
+    }
+
+    #[test]
+    fn test_echo_confirms_predictions_in_order() {
+        let now = Instant::now();
+        let mut echo = LocalEcho::new();
+        echo.predict("ls", Point::new(Line(0), Column(2)), 10, now);
+        echo.predict(" -l", Point::new(Line(0), Column(2)), 10, now);
+        assert_eq!(echo.predictions().len(), 5);
+        assert_eq!(echo.predictions()[4], (Point::new(Line(0), Column(6)), 'l'));
+
+        // The space is confirmed along with "ls": that cell is blank already
+        echo.reconcile(&grid_with("$ ls", 4), now);
+        assert_eq!(echo.predictions().len(), 2);
+        echo.reconcile(&grid_with("$ ls -l", 7), now);
+        assert!(echo.is_empty());
+    }
+
+    #[test]
+    fn test_wrong_or_missing_echo_drops_predictions() {
+        let now = Instant::now();
+        let mut echo = LocalEcho::new();
+        echo.predict("ab", Point::new(Line(0), Column(0)), 10, now);
+        echo.reconcile(&grid_with("x", 1), now);
+        assert!(echo.is_empty());
+
+        echo.predict("ab", Point::new(Line(0), Column(0)), 10, now);
+        echo.reconcile(&grid_with("", 0), now);
+        assert_eq!(echo.predictions().len(), 2);
+        echo.reconcile(&grid_with("", 0), now + ECHO_TIMEOUT * 2);
+        assert!(echo.is_empty());
+
+        echo.predict("é", Point::new(Line(0), Column(0)), 10, now);
+        assert!(echo.is_empty());
+    }
+}
@@ -0,0 +1,482 @@
+/// Background parsing of PTY output
+///
+/// Each terminal gets a parser thread that takes chunks from the PTY reader,
+/// runs them through the emulator and the output scanners (shell
+/// integration marks, badges, triggers, blink attributes), and wakes the
+/// event loop. The UI thread only collects what was found and draws, so a
+/// slow frame never holds up parsing and a flood of output never holds up
+/// input.
+///
+/// Lock order is parser, then term: the UI must not lock the parser (inject
+/// output, take results) while it holds a term lock.
+use alacritty_terminal::{
+    grid::{Dimensions, Grid},
+    index::{Column, Line, Point},
+    term::{cell::{Cell, Flags}, Term, TermMode},
+    vte::ansi::Processor,
+};
+use log::{debug, warn};
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, OnceLock,
+};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+use crate::badge::BadgeScanner;
+use crate::blink::{BlinkScanner, BLINK};
+use crate::command_duration::{DurationMark, MAX_DURATION_MARKS, MIN_ANNOTATED_DURATION};
+use crate::pane_log::PaneLog;
+use crate::shell_integration::{FinishedCommand, MarkScanner, ShellMark};
+use crate::startup::StartupCommand;
+use crate::terminal::TermEventListener;
+use crate::trigger::{queue_hits, TriggerHit, TriggerSet};
+
+/// OSC 104/110/111/112: drop every color set with OSC 4/10/11/12
+pub(crate) const RESET_DYNAMIC_COLORS: &[u8] = b"\x1b]104\x07\x1b]110\x07\x1b]111\x07\x1b]112\x07";
+
+/// Longest the UI waits for a term the parser is writing to (one chunk takes far less)
+pub const TERM_LOCK_TIMEOUT: Duration = Duration::from_millis(50);
+
+/// Called from parser threads when there is new output to draw
+type Waker = Box<dyn Fn() + Send + Sync>;
+
+static WAKER: OnceLock<Waker> = OnceLock::new();
+
+/// A wakeup was sent and the event loop hasn't picked it up yet
+static WAKE_PENDING: AtomicBool = AtomicBool::new(false);
+
+/// Nothing is on screen: parser threads work in batches
+pub(crate) static THROTTLED: AtomicBool = AtomicBool::new(false);
+
+/// Pause between batches while throttled
+pub const THROTTLED_PARSE_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How often a pause between batches checks whether the window came back
+const THROTTLE_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Set how parser threads wake the event loop (once, at startup)
+pub fn set_waker(waker: impl Fn() + Send + Sync + 'static) {
+    if WAKER.set(Box::new(waker)).is_err() {
+        warn!("Output waker already set");
+    }
+}
+
+/// Let the next parsed chunk send a wakeup again; call before collecting output
+pub fn clear_wakeup() {
+    WAKE_PENDING.store(false, Ordering::Release);
+}
+
+/// Parse in batches, THROTTLED_PARSE_INTERVAL apart, while nothing is on screen
+///
+/// Each batch takes what the readers had queued when it started and wakes the
+/// event loop once; the parser lock is released between batches. The readers
+/// fill their channels in between and then stop reading, so a program flooding
+/// a hidden terminal is held back by the PTY, while one writing a little now
+/// and then runs as before.
+pub fn set_throttled(throttled: bool) {
+    THROTTLED.store(throttled, Ordering::Release);
+}
+
+fn wake() {
+    let Some(waker) = WAKER.get() else {
+        return;
+    };
+    if !WAKE_PENDING.swap(true, Ordering::AcqRel) {
+        waker();
+    }
+}
+
+/// Command between its OSC 133;C and the end of its run
+struct RunningCommand {
+    /// Empty when the shell didn't mark where input started (133;B)
+    command: String,
+    started: Instant,
+    /// Start line, prompt-end column, and history size when it was accepted
+    typed_at: Option<(i32, usize, usize)>,
+}
+
+/// Parsing state of one terminal, shared by its parser thread and the UI
+pub(crate) struct OutputParser {
+    term: Arc<Mutex<Term<TermEventListener>>>,
+    processor: Processor,
+    pub(crate) startup: Option<StartupCommand>,
+    marks: MarkScanner,
+    /// Where command input started (OSC 133;B), until the command is accepted
+    pub(crate) prompt_end: Option<Point>,
+    /// Commands seen via OSC 133;C since they were last taken
+    pub(crate) captured_commands: Vec<String>,
+    /// Command running since its 133;C
+    running: Option<RunningCommand>,
+    /// Commands that finished (133;D) since they were last taken
+    pub(crate) finished_commands: Vec<FinishedCommand>,
+    /// Where recent long-running commands were typed, oldest first
+    pub(crate) duration_marks: VecDeque<DurationMark>,
+    /// Copy of the raw PTY output kept for remote-control subscribers
+    pub(crate) output_tap: Option<Vec<u8>>,
+    /// Badge set for this pane; None shows the configured default, "" shows none
+    pub(crate) badge: Option<String>,
+    badges: BadgeScanner,
+    /// Triggers matched against each completed line, and the hits not yet taken
+    pub(crate) triggers: Option<Arc<TriggerSet>>,
+    pub(crate) trigger_hits: Vec<TriggerHit>,
+    /// File this pane's output is being appended to
+    pub(crate) log: Option<PaneLog>,
+    /// Last parsed byte was ESC, so a leading `c` completes a reset (RIS)
+    after_escape: bool,
+    /// SGR 5/6/25, which the emulator ignores
+    blink: BlinkScanner,
+    /// Bytes parsed since they were last counted
+    pub(crate) parsed_bytes: usize,
+}
+
+impl OutputParser {
+    pub(crate) fn new(term: Arc<Mutex<Term<TermEventListener>>>) -> Self {
+        Self {
+            term,
+            processor: Processor::new(),
+            startup: None,
+            marks: MarkScanner::new(),
+            prompt_end: None,
+            captured_commands: Vec::new(),
+            running: None,
+            finished_commands: Vec::new(),
+            duration_marks: VecDeque::new(),
+            output_tap: None,
+            badge: None,
+            badges: BadgeScanner::new(),
+            triggers: None,
+            trigger_hits: Vec::new(),
+            log: None,
+            after_escape: false,
+            blink: BlinkScanner::new(),
+            parsed_bytes: 0,
+        }
+    }
+
+    /// Forget the state of the previous program's output (after a respawn)
+    pub(crate) fn reset(&mut self) {
+        self.processor = Processor::new();
+        self.startup = None;
+        self.marks = MarkScanner::new();
+        self.prompt_end = None;
+        self.running = None;
+        self.duration_marks.clear();
+        self.after_escape = false;
+        self.blink = BlinkScanner::new();
+    }
+
+    /// Handle one chunk read from the PTY
+    fn process_chunk(&mut self, buf: &[u8]) {
+        let n = buf.len();
+        self.parsed_bytes += n;
+        if let Some(startup) = &mut self.startup {
+            startup.observe_output(buf, Instant::now());
+        }
+        if let Some(tap) = &mut self.output_tap {
+            tap.extend_from_slice(buf);
+        }
+        if let Some(log) = &self.log {
+            log.write(buf);
+        }
+        if let Some(badge) = self.badges.scan(buf).pop() {
+            self.badge = Some(badge);
+        }
+        debug!("Read {} bytes from PTY: {:?}", n, String::from_utf8_lossy(buf));
+
+        // Parse up to each shell integration mark so the cursor position is exact
+        let mut parsed = 0;
+        for (end, mark) in self.marks.scan(buf) {
+            self.parse_output(&buf[parsed..end]);
+            parsed = end;
+            self.handle_mark(mark);
+        }
+        self.parse_output(&buf[parsed..n]);
+    }
+
+    /// Parse PTY output; a full reset (RIS) also drops the program's dynamic colors,
+    /// which the emulator itself keeps
+    fn parse_output(&mut self, bytes: &[u8]) {
+        let mut parsed = 0;
+        for end in reset_ends(bytes, self.after_escape) {
+            self.parse_lines(&bytes[parsed..end]);
+            parsed = end;
+            self.inject(RESET_DYNAMIC_COLORS);
+        }
+        self.parse_lines(&bytes[parsed..]);
+        if let Some(&last) = bytes.last() {
+            self.after_escape = last == 0x1b;
+        }
+    }
+
+    /// Parse PTY output, running triggers on each line as its newline arrives
+    fn parse_lines(&mut self, bytes: &[u8]) {
+        let Some(triggers) = self.triggers.clone() else {
+            self.parse_attributes(bytes);
+            return;
+        };
+        for piece in bytes.split_inclusive(|&b| b == b'\n') {
+            self.parse_attributes(piece);
+            if piece.ends_with(b"\n") {
+                let mut term = self.term.lock();
+                if !term.mode().contains(TermMode::ALT_SCREEN) {
+                    queue_hits(&mut self.trigger_hits, triggers.apply(term.grid_mut(), Instant::now()));
+                }
+            }
+        }
+    }
+
+    /// Parse PTY output, marking text printed while SGR 5/6 is in effect as blinking
+    fn parse_attributes(&mut self, bytes: &[u8]) {
+        let mut parsed = 0;
+        for (end, blinking) in self.blink.scan(bytes) {
+            self.inject(&bytes[parsed..end]);
+            parsed = end;
+            self.term.lock().grid_mut().cursor.template.flags.set(BLINK, blinking);
+        }
+        self.inject(&bytes[parsed..]);
+    }
+
+    /// Track command input boundaries and run times from OSC 133 marks
+    fn handle_mark(&mut self, mark: ShellMark) {
+        match mark {
+            ShellMark::PromptEnd => {
+                // Shells that don't send 133;D finish a command by drawing the next prompt
+                self.finish_command();
+                self.prompt_end = Some(self.term.lock().grid().cursor.point);
+            }
+            ShellMark::CommandStart => {
+                let mut running = RunningCommand { command: String::new(), started: Instant::now(), typed_at: None };
+                if let Some(prompt_end) = self.prompt_end.take() {
+                    let term = self.term.lock();
+                    let grid = term.grid();
+                    if let Some((start_line, command)) = accepted_command(grid, prompt_end.column) {
+                        debug!("Captured command ({} chars)", command.len());
+                        self.captured_commands.push(command.clone());
+                        running.typed_at = Some((start_line, prompt_end.column.0, grid.history_size()));
+                        running.command = command;
+                    }
+                }
+                self.running = Some(running);
+            }
+            ShellMark::CommandEnd => self.finish_command(),
+        }
+    }
+
+    /// Record how long the running command took, marking its line if it ran long
+    fn finish_command(&mut self) {
+        let Some(RunningCommand { command, started, typed_at }) = self.running.take() else {
+            return;
+        };
+        let duration = started.elapsed();
+        if let Some((line, column, history_size)) = typed_at.filter(|_| duration >= MIN_ANNOTATED_DURATION) {
+            if self.duration_marks.len() == MAX_DURATION_MARKS {
+                self.duration_marks.pop_front();
+            }
+            self.duration_marks.push_back(DurationMark::new(line, column, history_size, command.clone(), duration));
+        }
+        self.finished_commands.push(FinishedCommand { command, duration });
+    }
+
+    /// Feed bytes straight into the emulator
+    pub(crate) fn inject(&mut self, bytes: &[u8]) {
+        let mut term = self.term.lock();
+        self.processor.advance(&mut *term, bytes);
+    }
+}
+
+/// Parse everything `output` delivers on a thread of its own, until the PTY
+/// closes or `stop` is set
+///
+/// `throttle` is the flag set_throttled() sets (THROTTLED), taken as a
+/// parameter so tests can use their own.
+pub(crate) fn spawn(
+    parser: Arc<Mutex<OutputParser>>,
+    mut output: mpsc::Receiver<Vec<u8>>,
+    stop: Arc<AtomicBool>,
+    throttle: &'static AtomicBool,
+) -> std::io::Result<()> {
+    std::thread::Builder::new()
+        .name("saternal-parser".to_string())
+        .spawn(move || {
+            while let Some(buf) = output.blocking_recv() {
+                if stop.load(Ordering::Acquire) {
+                    break;
+                }
+                let throttled = throttle.load(Ordering::Acquire);
+                // Only what was queued when the batch started, so the readers
+                // refilling the channel can't keep the parser lock held
+                let queued = if throttled { output.len() } else { 0 };
+                {
+                    let mut parser = parser.lock();
+                    parser.process_chunk(&buf);
+                    for buf in std::iter::from_fn(|| output.try_recv().ok()).take(queued) {
+                        parser.process_chunk(&buf);
+                    }
+                }
+                wake();
+                if throttled {
+                    let resume = Instant::now() + THROTTLED_PARSE_INTERVAL;
+                    while throttle.load(Ordering::Acquire) && Instant::now() < resume {
+                        std::thread::sleep(THROTTLE_POLL_INTERVAL);
+                    }
+                }
+            }
+            debug!("Parser thread finished");
+        })?;
+    Ok(())
+}
+
+/// Grid line the command the shell just accepted starts on, and its text
+/// (None when nothing was typed)
+///
+/// At OSC 133;C the cursor sits at the start of the line after the command,
+/// so the command ends on the previous line and starts at the prompt-end
+/// column of the first line in that run of soft-wrapped lines.
+fn accepted_command(grid: &Grid<Cell>, prompt_end: Column) -> Option<(i32, String)> {
+    let oldest = -(grid.history_size() as i32);
+    let end_line = grid.cursor.point.line.0 - 1;
+    if end_line < oldest {
+        return None;
+    }
+
+    let last_col = Column(grid.columns() - 1);
+    let mut start_line = end_line;
+    while start_line > oldest
+        && grid[Point::new(Line(start_line - 1), last_col)].flags.contains(Flags::WRAPLINE)
+    {
+        start_line -= 1;
+    }
+
+    let mut command = String::new();
+    for line in start_line..=end_line {
+        let first_col = if line == start_line { prompt_end.0 } else { 0 };
+        for col in first_col..grid.columns() {
+            let cell = &grid[Point::new(Line(line), Column(col))];
+            if !cell.flags.contains(Flags::WIDE_CHAR_SPACER) {
+                command.push(if cell.c == '\0' { ' ' } else { cell.c });
+            }
+        }
+    }
+    let command = command.trim();
+    (!command.is_empty()).then(|| (start_line, command.to_string()))
+}
+
+/// Text typed after the prompt ending at `prompt_end`, while the cursor is at the
+/// end of it on the same line (None once the input wraps, or text follows the cursor)
+pub(crate) fn typed_input(grid: &Grid<Cell>, prompt_end: Point) -> Option<String> {
+    let cursor = grid.cursor.point;
+    if cursor.line != prompt_end.line || cursor.column < prompt_end.column {
+        return None;
+    }
+    let row = &grid[cursor.line];
+    let blank = |col: usize| matches!(row[Column(col)].c, ' ' | '\0');
+    if !(cursor.column.0..grid.columns()).all(blank) {
+        return None;
+    }
+    Some(
+        (prompt_end.column.0..cursor.column.0)
+            .map(|col| &row[Column(col)])
+            .filter(|cell| !cell.flags.contains(Flags::WIDE_CHAR_SPACER))
+            .map(|cell| if cell.c == '\0' { ' ' } else { cell.c })
+            .collect(),
+    )
+}
+
+/// Offsets just past each reset (ESC c) in `bytes`; `after_escape` means the
+/// previous chunk ended in ESC
+fn reset_ends(bytes: &[u8], after_escape: bool) -> Vec<usize> {
+    let split = (after_escape && bytes.first() == Some(&b'c')).then_some(1);
+    split
+        .into_iter()
+        .chain(bytes.windows(2).enumerate().filter(|(_, pair)| *pair == b"\x1bc").map(|(i, _)| i + 2))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reset_ends() {
+        assert_eq!(reset_ends(b"a\x1bcb\x1b[0m\x1bc", false), vec![3, 10]);
+        assert_eq!(reset_ends(b"clear", true), vec![1]);
+        assert!(reset_ends(b"clear", false).is_empty());
+    }
+
+    #[test]
+    fn test_typed_input_only_with_the_cursor_at_its_end() {
+        use alacritty_terminal::term::{test::TermSize, Config};
+
+        let mut term = Term::new(Config::default(), &TermSize::new(20, 2), TermEventListener::new());
+        let mut processor: Processor = Processor::new();
+        processor.advance(&mut term, b"$ git st");
+        let prompt_end = Point::new(Line(0), Column(2));
+        assert_eq!(typed_input(term.grid(), prompt_end).as_deref(), Some("git st"));
+
+        // Cursor moved back into the input: text follows it
+        processor.advance(&mut term, b"\x1b[2D");
+        assert_eq!(typed_input(term.grid(), prompt_end), None);
+
+        // Input wrapped onto the next line
+        processor.advance(&mut term, b"\x1b[2C long enough to wrap");
+        assert_eq!(typed_input(term.grid(), prompt_end), None);
+    }
+
+    #[test]
+    fn test_parser_thread_feeds_the_term() {
+        use alacritty_terminal::term::{test::TermSize, Config};
+
+        let term = Term::new(Config::default(), &TermSize::new(10, 2), TermEventListener::new());
+        let term = Arc::new(Mutex::new(term));
+        let parser = Arc::new(Mutex::new(OutputParser::new(term.clone())));
+        let (sender, output) = mpsc::channel(4);
+        spawn(parser.clone(), output, Arc::new(AtomicBool::new(false)), &THROTTLED).unwrap();
+
+        sender.blocking_send(b"hi".to_vec()).unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while parser.lock().parsed_bytes < 2 && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(parser.lock().parsed_bytes, 2);
+        assert_eq!(term.lock().grid()[Line(0)][Column(1)].c, 'i');
+    }
+
+    #[test]
+    fn test_throttled_parser_takes_queued_output_in_one_batch() {
+        use alacritty_terminal::term::{test::TermSize, Config};
+
+        // Its own flag: THROTTLED is shared with every other test's parser
+        static THROTTLE: AtomicBool = AtomicBool::new(false);
+
+        let term = Term::new(Config::default(), &TermSize::new(10, 2), TermEventListener::new());
+        let parser = Arc::new(Mutex::new(OutputParser::new(Arc::new(Mutex::new(term)))));
+        let (sender, output) = mpsc::channel(4);
+        for chunk in [b"ab", b"cd", b"ef"] {
+            sender.blocking_send(chunk.to_vec()).unwrap();
+        }
+
+        THROTTLE.store(true, Ordering::Release);
+        spawn(parser.clone(), output, Arc::new(AtomicBool::new(false)), &THROTTLE).unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while parser.lock().parsed_bytes == 0 && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(parser.lock().parsed_bytes, 6);
+
+        // Queued after the batch started: waits for the next one
+        sender.blocking_send(b"gh".to_vec()).unwrap();
+        std::thread::sleep(THROTTLED_PARSE_INTERVAL / 5);
+        assert_eq!(parser.lock().parsed_bytes, 6);
+
+        // Visible again: the pause ends early
+        THROTTLE.store(false, Ordering::Release);
+        let deadline = Instant::now() + THROTTLED_PARSE_INTERVAL / 2;
+        while parser.lock().parsed_bytes < 8 && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(parser.lock().parsed_bytes, 8);
+    }
+}
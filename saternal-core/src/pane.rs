@@ -1,34 +1,95 @@
+use crate::layout::LayoutPlan;
+use crate::startup::DEFAULT_STARTUP_TIMEOUT;
 use crate::terminal::Terminal;
 use anyhow::Result;
 use log::info;
+use serde::{Deserialize, Serialize};
 
 /// Direction for splitting panes
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum SplitDirection {
     Horizontal,
     Vertical,
 }
 
+/// Range of a pane's font scale (Cmd+Alt+= / -), and the step of each keypress
+pub const MIN_FONT_SCALE: f32 = 0.5;
+pub const MAX_FONT_SCALE: f32 = 3.0;
+pub const FONT_SCALE_STEP: f32 = 0.1;
+
 /// A single terminal pane
 pub struct Pane {
     pub id: usize,
     pub terminal: Terminal,
     pub focused: bool,
+    /// Input stays on this pane: clicks and pane navigation don't move focus away
+    pub focus_locked: bool,
+    /// Rows reserved above the terminal for the pane header
+    header_rows: usize,
+    /// Font size relative to the window's (1.0 = not zoomed)
+    font_scale: f32,
+    /// Space given to the terminal, in cells of the window's font size
+    content_size: (usize, usize),
 }
 
 impl Pane {
     pub fn new(id: usize, cols: usize, rows: usize, shell: Option<String>) -> Result<Self> {
         let terminal = Terminal::new(cols, rows, shell)?;
-        Ok(Self {
+        Ok(Self::with_terminal(id, terminal, cols, rows))
+    }
+
+    fn with_terminal(id: usize, terminal: Terminal, cols: usize, rows: usize) -> Self {
+        Self {
             id,
             terminal,
             focused: false,
-        })
+            focus_locked: false,
+            header_rows: 0,
+            font_scale: 1.0,
+            content_size: (cols, rows),
+        }
     }
 
+    /// Resize to `rows` total, including the header
+    /// A zoomed pane fits fewer (or more) of its own cells in the same space
     pub fn resize(&mut self, cols: usize, rows: usize) -> Result<()> {
+        self.content_size = (cols, rows.saturating_sub(self.header_rows).max(1));
+        let (cols, rows) = zoomed_size(self.content_size, self.font_scale);
         self.terminal.resize(cols, rows)
     }
+
+    /// Space taken by this pane (cols x rows), including the header
+    pub fn dimensions(&self) -> (usize, usize) {
+        let (cols, rows) = self.content_size;
+        (cols, rows + self.header_rows)
+    }
+
+    /// Font size relative to the window's
+    pub fn font_scale(&self) -> f32 {
+        self.font_scale
+    }
+
+    /// Zoom this pane's text (clamped to MIN_FONT_SCALE-MAX_FONT_SCALE) and refit its
+    /// terminal to the same space; returns the scale it ended up with
+    pub fn set_font_scale(&mut self, font_scale: f32) -> Result<f32> {
+        self.font_scale = snap_font_scale(font_scale);
+        let (cols, rows) = zoomed_size(self.content_size, self.font_scale);
+        self.terminal.resize(cols, rows)?;
+        Ok(self.font_scale)
+    }
+}
+
+/// Clamp a font scale and round it to whole steps, so zooming in and back out lands on exactly 1.0
+fn snap_font_scale(font_scale: f32) -> f32 {
+    let steps = (font_scale.clamp(MIN_FONT_SCALE, MAX_FONT_SCALE) / FONT_SCALE_STEP).round();
+    steps / FONT_SCALE_STEP.recip().round()
+}
+
+/// Cells of `font_scale` times the window's font size that fit in `size` unzoomed cells
+fn zoomed_size((cols, rows): (usize, usize), font_scale: f32) -> (usize, usize) {
+    let fit = |cells: usize| ((cells as f32 / font_scale).floor() as usize).max(1);
+    (fit(cols), fit(rows))
 }
 
 /// Node in the pane tree - either a leaf (single pane) or a split
@@ -51,6 +112,40 @@ impl PaneNode {
         Ok(PaneNode::Leaf { pane })
     }
 
+    /// Build a tree from a layout plan, numbering panes from `*next_id`
+    /// Each pane's command is sent once its shell shows a prompt
+    pub fn from_plan(
+        plan: &LayoutPlan,
+        cols: usize,
+        rows: usize,
+        shell: Option<String>,
+        next_id: &mut usize,
+    ) -> Result<Self> {
+        match plan {
+            LayoutPlan::Pane { cwd, command } => {
+                let id = *next_id;
+                *next_id += 1;
+                let mut terminal = Terminal::with_cwd(cols, rows, shell, cwd.clone())?;
+                if let Some(command) = command {
+                    terminal.run_when_ready(command.as_str(), DEFAULT_STARTUP_TIMEOUT);
+                }
+                Ok(PaneNode::Leaf { pane: Pane::with_terminal(id, terminal, cols, rows) })
+            }
+            LayoutPlan::Split { direction, ratio, children } => {
+                let first = Self::from_plan(&children[0], cols, rows, shell.clone(), next_id)?;
+                let second = Self::from_plan(&children[1], cols, rows, shell, next_id)?;
+                let mut node = PaneNode::Split {
+                    direction: *direction,
+                    children: vec![first, second],
+                    ratio: *ratio,
+                };
+                // Give each pane its share of the space
+                node.resize(cols, rows)?;
+                Ok(node)
+            }
+        }
+    }
+
     /// Split this node in the given direction
     pub fn split(
         &mut self,
@@ -106,6 +201,11 @@ impl PaneNode {
         }
     }
 
+    /// Whether the focused pane holds on to focus (see `Pane::focus_locked`)
+    pub fn focus_locked(&self) -> bool {
+        self.focused_pane().is_some_and(|pane| pane.focus_locked)
+    }
+
     /// Set focus on a specific pane by ID
     pub fn set_focus(&mut self, id: usize) -> bool {
         match self {
@@ -181,7 +281,8 @@ impl PaneNode {
         match self {
             PaneNode::Leaf { pane } if pane.focused => {
                 // Found the focused pane - split it
-                let (cols, rows) = pane.terminal.dimensions();
+                let (cols, rows) = pane.dimensions();
+                let header_rows = pane.header_rows;
 
                 // Calculate split dimensions based on direction
                 let (new_cols, new_rows) = match direction {
@@ -198,12 +299,14 @@ impl PaneNode {
                 if let PaneNode::Split { children, .. } = self {
                     // Resize the original pane (left/top)
                     if let Some(PaneNode::Leaf { pane }) = children.get_mut(0) {
-                        pane.terminal.resize(new_cols.max(1), new_rows.max(1))?;
+                        pane.resize(new_cols.max(1), new_rows.max(1))?;
                         pane.focused = false;
                     }
 
                     // Set focus to new pane (right/bottom)
                     if let Some(PaneNode::Leaf { pane }) = children.get_mut(1) {
+                        pane.header_rows = header_rows;
+                        pane.resize(new_cols.max(1), new_rows.max(1))?;
                         pane.focused = true;
                     }
                 }
@@ -235,10 +338,10 @@ impl PaneNode {
         }
     }
 
-    /// Move focus to next pane (circular)
+    /// Move focus to next pane (circular), unless focus is locked
     pub fn focus_next(&mut self) -> bool {
         let pane_ids = self.pane_ids();
-        if pane_ids.is_empty() {
+        if pane_ids.is_empty() || self.focus_locked() {
             return false;
         }
 
@@ -253,10 +356,10 @@ impl PaneNode {
         false
     }
 
-    /// Move focus to previous pane (circular)
+    /// Move focus to previous pane (circular), unless focus is locked
     pub fn focus_prev(&mut self) -> bool {
         let pane_ids = self.pane_ids();
-        if pane_ids.is_empty() {
+        if pane_ids.is_empty() || self.focus_locked() {
             return false;
         }
 
@@ -306,6 +409,112 @@ impl PaneNode {
         }
     }
 
+    /// Swap the focused pane with its sibling in the parent split
+    pub fn swap_focused_with_sibling(&mut self) -> bool {
+        match self {
+            PaneNode::Leaf { .. } => false,
+            PaneNode::Split { children, .. } => {
+                let focused_child = children.iter().any(|child| {
+                    matches!(child, PaneNode::Leaf { pane } if pane.focused)
+                });
+                if focused_child && children.len() == 2 {
+                    children.swap(0, 1);
+                    info!("Swapped focused pane with its sibling");
+                    return true;
+                }
+
+                children
+                    .iter_mut()
+                    .any(|child| child.swap_focused_with_sibling())
+            }
+        }
+    }
+
+    /// Rotate the innermost split containing the focused pane by 90 degrees
+    ///
+    /// Left/right becomes top/bottom, and top/bottom becomes right/left, so
+    /// four rotations bring the layout back to where it started.
+    pub fn rotate_focused_split(&mut self) -> bool {
+        match self {
+            PaneNode::Leaf { .. } => false,
+            PaneNode::Split {
+                direction,
+                children,
+                ratio,
+            } => {
+                // Prefer the deepest split so nested layouts rotate locally
+                for child in children.iter_mut() {
+                    if child.rotate_focused_split() {
+                        return true;
+                    }
+                }
+
+                if !children.iter().any(|child| child.focused_pane().is_some()) {
+                    return false;
+                }
+
+                *direction = match direction {
+                    SplitDirection::Vertical => SplitDirection::Horizontal,
+                    SplitDirection::Horizontal => {
+                        children.reverse();
+                        *ratio = 1.0 - *ratio;
+                        SplitDirection::Vertical
+                    }
+                };
+                info!("Rotated split to {:?}", direction);
+                true
+            }
+        }
+    }
+
+    /// Reset every split in the tree to an even 50/50 ratio
+    pub fn balance(&mut self) {
+        if let PaneNode::Split {
+            children, ratio, ..
+        } = self
+        {
+            *ratio = 0.5;
+            for child in children {
+                child.balance();
+            }
+        }
+    }
+
+    /// Reserve `rows` above every pane's terminal for a header
+    /// Call resize() afterwards to shrink the terminals to fit
+    pub fn set_header_rows(&mut self, rows: usize) {
+        match self {
+            PaneNode::Leaf { pane } => pane.header_rows = rows,
+            PaneNode::Split { children, .. } => {
+                for child in children {
+                    child.set_header_rows(rows);
+                }
+            }
+        }
+    }
+
+    /// Total terminal dimensions (cols x rows) covered by this subtree
+    pub fn dimensions(&self) -> (usize, usize) {
+        match self {
+            PaneNode::Leaf { pane } => pane.dimensions(),
+            PaneNode::Split {
+                direction,
+                children,
+                ..
+            } => {
+                let dims = children.iter().map(|child| child.dimensions());
+                match direction {
+                    SplitDirection::Horizontal => {
+                        dims.fold((0, 0), |(cols, rows), (c, r)| (cols.max(c), rows + r))
+                    }
+                    SplitDirection::Vertical => {
+                        dims.fold((0, 0), |(cols, rows), (c, r)| (cols + c, rows.max(r)))
+                    }
+                }
+            }
+        }
+    }
+
     /// Resize all panes in the tree to specified terminal dimensions (cols x rows)
     pub fn resize(&mut self, cols: usize, rows: usize) -> Result<()> {
         match self {
@@ -346,3 +555,101 @@ impl PaneNode {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_font_scale_snaps_to_steps() {
+        let zoomed_in = (0..3).fold(1.0, |scale, _| snap_font_scale(scale + FONT_SCALE_STEP));
+        assert_eq!(zoomed_in, 1.3);
+        assert_eq!((0..3).fold(zoomed_in, |scale, _| snap_font_scale(scale - FONT_SCALE_STEP)), 1.0);
+        assert_eq!(snap_font_scale(0.1), MIN_FONT_SCALE);
+        assert_eq!(snap_font_scale(9.0), MAX_FONT_SCALE);
+    }
+
+    #[test]
+    fn test_zoomed_size_fits_the_same_space() {
+        assert_eq!(zoomed_size((80, 24), 1.0), (80, 24));
+        assert_eq!(zoomed_size((80, 24), 2.0), (40, 12));
+        assert_eq!(zoomed_size((80, 24), 0.5), (160, 48));
+        assert_eq!(zoomed_size((1, 1), 3.0), (1, 1));
+    }
+
+    /// Tree operations on real leaves, each running /bin/sh
+    #[cfg(unix)]
+    mod tree {
+        use super::*;
+
+        fn leaf(id: usize) -> PaneNode {
+            PaneNode::new_leaf(id, 80, 24, Some("/bin/sh".to_string())).unwrap()
+        }
+
+        fn split(direction: SplitDirection, ratio: f32, first: PaneNode, second: PaneNode) -> PaneNode {
+            PaneNode::Split { direction, children: vec![first, second], ratio }
+        }
+
+        /// Direction and ratio of a split node
+        fn shape(node: &PaneNode) -> (SplitDirection, f32) {
+            match node {
+                PaneNode::Split { direction, ratio, .. } => (*direction, *ratio),
+                PaneNode::Leaf { .. } => panic!("expected a split"),
+            }
+        }
+
+        #[test]
+        fn test_swap_moves_focus_with_the_pane() {
+            let mut tree = split(SplitDirection::Vertical, 0.5, leaf(0), leaf(1));
+            tree.set_focus(0);
+            assert!(tree.swap_focused_with_sibling());
+            assert_eq!(tree.pane_ids(), vec![1, 0]);
+            assert_eq!(tree.focused_pane().map(|pane| pane.id), Some(0));
+
+            // Only the split holding the focused pane changes
+            let inner = split(SplitDirection::Horizontal, 0.5, leaf(1), leaf(2));
+            let mut tree = split(SplitDirection::Vertical, 0.5, leaf(0), inner);
+            tree.set_focus(2);
+            assert!(tree.swap_focused_with_sibling());
+            assert_eq!(tree.pane_ids(), vec![0, 2, 1]);
+            assert_eq!(tree.focused_pane().map(|pane| pane.id), Some(2));
+        }
+
+        #[test]
+        fn test_rotate_flips_the_split_direction() {
+            let mut tree = split(SplitDirection::Vertical, 0.3, leaf(0), leaf(1));
+            tree.set_focus(0);
+
+            // Left/right becomes top/bottom in the same order
+            assert!(tree.rotate_focused_split());
+            assert_eq!(shape(&tree), (SplitDirection::Horizontal, 0.3));
+            assert_eq!(tree.pane_ids(), vec![0, 1]);
+
+            // Top/bottom becomes right/left
+            assert!(tree.rotate_focused_split());
+            assert_eq!(shape(&tree).0, SplitDirection::Vertical);
+            assert!((shape(&tree).1 - 0.7).abs() < f32::EPSILON);
+            assert_eq!(tree.pane_ids(), vec![1, 0]);
+        }
+
+        #[test]
+        fn test_balance_resets_nested_ratios() {
+            let inner = split(SplitDirection::Horizontal, 0.8, leaf(1), leaf(2));
+            let mut tree = split(SplitDirection::Vertical, 0.3, leaf(0), inner);
+            tree.balance();
+            assert_eq!(shape(&tree), (SplitDirection::Vertical, 0.5));
+            let PaneNode::Split { children, .. } = &tree else { unreachable!() };
+            assert_eq!(shape(&children[1]), (SplitDirection::Horizontal, 0.5));
+        }
+
+        #[test]
+        fn test_single_pane_is_left_alone() {
+            let mut tree = leaf(0);
+            tree.set_focus(0);
+            assert!(!tree.swap_focused_with_sibling());
+            assert!(!tree.rotate_focused_split());
+            tree.balance();
+            assert!(matches!(&tree, PaneNode::Leaf { pane } if pane.id == 0 && pane.focused));
+        }
+    }
+}
@@ -0,0 +1,298 @@
+/// Pane logging: a copy of a pane's output appended to a file
+///
+/// Output is handed to a writer task on the PTY runtime, so disk I/O never
+/// blocks the UI thread. Plain logs drop escape sequences and carriage
+/// returns and stamp each line with the local time it arrived; raw logs keep
+/// the bytes exactly as the program wrote them. Both mark when logging
+/// started and stopped, and where output was skipped because the disk fell
+/// behind. Logs hold everything the pane showed, so they are created
+/// readable by the owner only.
+use anyhow::Result;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::SystemTime;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc;
+
+use crate::layout::expand_home;
+use crate::pty_reader;
+
+/// What goes into a pane log
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Text only, each line prefixed with a timestamp
+    #[default]
+    Plain,
+    /// PTY output byte for byte, escape sequences included
+    Raw,
+}
+
+/// Chunks queued for the writer; past this, output is skipped until it catches up
+const LOG_QUEUE_CHUNKS: usize = 256;
+
+/// A chunk of PTY output on its way to the file
+struct LogChunk {
+    time: SystemTime,
+    bytes: Vec<u8>,
+    /// Bytes skipped just before this chunk while the queue was full
+    skipped: usize,
+}
+
+/// Appends one pane's output to a file until dropped
+pub struct PaneLog {
+    path: PathBuf,
+    tx: mpsc::Sender<LogChunk>,
+    /// Bytes skipped since the last chunk that made it into the queue
+    skipped: AtomicUsize,
+}
+
+impl PaneLog {
+    /// Open `path` for appending (creating it and its directory, both private
+    /// to the user) and start the writer
+    pub fn start(path: &Path, format: LogFormat) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            let mut builder = std::fs::DirBuilder::new();
+            builder.recursive(true);
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::DirBuilderExt;
+                builder.mode(0o700);
+            }
+            builder.create(parent)?;
+        }
+        let mut options = OpenOptions::new();
+        options.create(true).append(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let file = options.open(path)?;
+        // The mode only applies to new files; tighten one logged to before
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+        }
+        let (tx, rx) = mpsc::channel(LOG_QUEUE_CHUNKS);
+        pty_reader::runtime().spawn(write_loop(tokio::fs::File::from_std(file), rx, format));
+        Ok(Self { path: path.to_path_buf(), tx, skipped: AtomicUsize::new(0) })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Queue a chunk of PTY output, or skip it if the writer is LOG_QUEUE_CHUNKS behind
+    pub fn write(&self, bytes: &[u8]) {
+        let skipped = self.skipped.swap(0, Ordering::Relaxed);
+        let chunk = LogChunk { time: SystemTime::now(), bytes: bytes.to_vec(), skipped };
+        match self.tx.try_send(chunk) {
+            Ok(()) => {}
+            Err(mpsc::error::TrySendError::Full(chunk)) => {
+                self.skipped.fetch_add(chunk.skipped + chunk.bytes.len(), Ordering::Relaxed);
+            }
+            // The writer gave up after an I/O error (already logged)
+            Err(mpsc::error::TrySendError::Closed(_)) => {}
+        }
+    }
+}
+
+async fn write_loop(mut file: tokio::fs::File, mut rx: mpsc::Receiver<LogChunk>, format: LogFormat) {
+    let mut encoder = LogEncoder::new(format);
+    let mut pending = encoder.marker("started", SystemTime::now());
+    loop {
+        if let Err(e) = file.write_all(&pending).await {
+            warn!("Stopped pane log after write error: {}", e);
+            return;
+        }
+        // The sender is dropped when logging stops
+        match rx.recv().await {
+            Some(LogChunk { time, bytes, skipped }) => {
+                pending = if skipped > 0 {
+                    encoder.marker(&format!("skipped {} bytes", skipped), time)
+                } else {
+                    Vec::new()
+                };
+                pending.extend(encoder.encode(time, &bytes));
+            }
+            None => break,
+        }
+    }
+    let footer = encoder.marker("stopped", SystemTime::now());
+    if let Err(e) = async {
+        file.write_all(&footer).await?;
+        file.flush().await
+    }
+    .await
+    {
+        warn!("Failed to finish pane log: {}", e);
+    }
+}
+
+/// Escape sequence parsing state for plain logs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EscapeState {
+    Text,
+    /// After ESC
+    Escape,
+    /// ESC followed by intermediate bytes, waiting for the final byte
+    Intermediate,
+    /// CSI parameters, up to the final byte
+    Csi,
+    /// OSC/DCS/APC string, up to BEL or ST
+    String,
+    /// ESC inside a string (the start of ST)
+    StringEscape,
+}
+
+/// Turns PTY output into log file contents; state carries across chunks
+struct LogEncoder {
+    format: LogFormat,
+    state: EscapeState,
+    at_line_start: bool,
+}
+
+impl LogEncoder {
+    fn new(format: LogFormat) -> Self {
+        Self { format, state: EscapeState::Text, at_line_start: true }
+    }
+
+    /// `--- Saternal log started <time> ---` on a line of its own
+    fn marker(&mut self, event: &str, time: SystemTime) -> Vec<u8> {
+        let newline = if self.at_line_start { "" } else { "\n" };
+        self.at_line_start = true;
+        format!("{}--- Saternal log {} {} ---\n", newline, event, local_timestamp(time)).into_bytes()
+    }
+
+    fn encode(&mut self, time: SystemTime, bytes: &[u8]) -> Vec<u8> {
+        if self.format == LogFormat::Raw {
+            if let Some(&last) = bytes.last() {
+                self.at_line_start = last == b'\n';
+            }
+            return bytes.to_vec();
+        }
+
+        let mut out = Vec::with_capacity(bytes.len());
+        for &byte in bytes {
+            self.state = match (self.state, byte) {
+                (EscapeState::Text, 0x1b) => EscapeState::Escape,
+                (EscapeState::Text, b'\n') => {
+                    out.push(b'\n');
+                    self.at_line_start = true;
+                    EscapeState::Text
+                }
+                (EscapeState::Text, b'\t' | 0x20..=0x7e | 0x80..) => {
+                    if self.at_line_start {
+                        out.extend_from_slice(format!("[{}] ", local_timestamp(time)).as_bytes());
+                        self.at_line_start = false;
+                    }
+                    out.push(byte);
+                    EscapeState::Text
+                }
+                // \r and other controls
+                (EscapeState::Text, _) => EscapeState::Text,
+                (EscapeState::Escape, b'[') => EscapeState::Csi,
+                (EscapeState::Escape, b']' | b'P' | b'X' | b'^' | b'_') => EscapeState::String,
+                (EscapeState::Escape | EscapeState::Intermediate, 0x20..=0x2f) => EscapeState::Intermediate,
+                (EscapeState::Escape | EscapeState::Intermediate, _) => EscapeState::Text,
+                (EscapeState::Csi, 0x40..=0x7e) => EscapeState::Text,
+                (EscapeState::Csi, _) => EscapeState::Csi,
+                (EscapeState::String, 0x07) => EscapeState::Text,
+                (EscapeState::String, 0x1b) => EscapeState::StringEscape,
+                (EscapeState::String, _) => EscapeState::String,
+                (EscapeState::StringEscape, b'\\') => EscapeState::Text,
+                (EscapeState::StringEscape, _) => EscapeState::String,
+            };
+        }
+        out
+    }
+}
+
+/// File for an automatically logged pane: `<directory>/<date>_<time>-<pid>-<n>.log`
+pub fn auto_log_path(directory: &str) -> PathBuf {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    let n = NEXT.fetch_add(1, Ordering::Relaxed);
+    let stamp = local_timestamp(SystemTime::now()).replace(' ', "_").replace(':', "-");
+    expand_home(directory).join(format!("{}-{}-{}.log", stamp, std::process::id(), n))
+}
+
+/// `YYYY-MM-DD HH:MM:SS` in local time
+pub fn local_timestamp(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs()) as libc::time_t;
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    #[cfg(unix)]
+    let converted = !unsafe { libc::localtime_r(&secs, &mut tm) }.is_null();
+    #[cfg(windows)]
+    let converted = unsafe { libc::localtime_s(&mut tm, &secs) } == 0;
+    if !converted {
+        return secs.to_string();
+    }
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        tm.tm_year + 1900,
+        tm.tm_mon + 1,
+        tm.tm_mday,
+        tm.tm_hour,
+        tm.tm_min,
+        tm.tm_sec
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_log_strips_escapes_and_stamps_lines() {
+        let time = SystemTime::now();
+        let stamp = format!("[{}] ", local_timestamp(time));
+        let mut encoder = LogEncoder::new(LogFormat::Plain);
+
+        // Colors, a title (split across chunks), CRLF, and a progress line redrawn with \r
+        let mut out = encoder.encode(time, b"\x1b[1;31merror\x1b[0m: x\r\n\x1b]0;ti");
+        out.extend(encoder.encode(time, b"tle\x0750%\r100%\r\n\x1b(Bdone"));
+        let expected = format!("{stamp}error: x\n{stamp}50%100%\n{stamp}done");
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+
+        assert!(String::from_utf8(encoder.marker("stopped", time)).unwrap().starts_with("\n--- Saternal log stopped"));
+    }
+
+    #[test]
+    fn test_log_appends_to_file() {
+        let path = std::env::temp_dir().join(format!("saternal-log-test-{}/pane.log", std::process::id()));
+        let log = PaneLog::start(&path, LogFormat::Raw).unwrap();
+        log.write(b"\x1b[32mok\x1b[0m\n");
+        drop(log);
+
+        // The writer finishes after the sender is dropped
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        let contents = loop {
+            let contents = std::fs::read_to_string(&path).unwrap_or_default();
+            if contents.contains("stopped") || std::time::Instant::now() > deadline {
+                break contents;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        };
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = |path: &Path| path.metadata().unwrap().permissions().mode() & 0o777;
+            assert_eq!(mode(&path), 0o600);
+            assert_eq!(mode(path.parent().unwrap()), 0o700);
+        }
+        std::fs::remove_dir_all(path.parent().unwrap()).ok();
+
+        let lines: Vec<_> = contents.lines().collect();
+        assert_eq!(lines.len(), 3, "{:?}", contents);
+        assert!(lines[0].starts_with("--- Saternal log started"));
+        assert_eq!(lines[1], "\x1b[32mok\x1b[0m");
+        assert!(lines[2].starts_with("--- Saternal log stopped"));
+    }
+}
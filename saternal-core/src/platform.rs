@@ -0,0 +1,176 @@
+/// What a frontend needs from the desktop it runs on
+///
+/// `saternal-macos` implements these on AppKit and `saternal-linux` on X11;
+/// the app picks one at compile time. Everything else in this crate is
+/// platform independent.
+use crate::config::{ColorSpace, WindowMode};
+use anyhow::Result;
+use std::ffi::OsString;
+use std::path::PathBuf;
+use winit::window::Window;
+
+/// System-wide shortcuts that reach Saternal while another app has focus
+pub trait GlobalHotkeys {
+    /// Run the callbacks of hotkeys pressed since the last call (call from the event loop)
+    fn process_events(&self);
+
+    /// Run the toggle callback as if the hotkey had been pressed
+    fn trigger_toggle(&self);
+}
+
+/// Places, shows, and hides the main window
+///
+/// In dropdown mode the window hangs from the top edge of the active screen
+/// and is toggled by the hotkey; otherwise it's a regular window the toggle
+/// hides and brings back. Sizes are (width, height, scale_factor) in physical pixels.
+pub trait DropdownSurface {
+    /// Set `window` up for `mode` before the renderer creates its surface
+    fn configure(
+        &mut self,
+        window: &Window,
+        mode: WindowMode,
+        width_percentage: f64,
+        height_percentage: f64,
+    ) -> Result<(u32, u32, f64)>;
+
+    /// Finish setup once the renderer has a surface on `window`
+    fn surface_created(&self, _window: &Window) {}
+
+    /// Tag the surface on `window` with the color space its pixels are in, so
+    /// the compositor converts them for the display. Only macOS does
+    fn set_color_space(&self, _window: &Window, _color_space: ColorSpace) {}
+
+    /// Show or hide the window
+    /// Returns the new size when it moved to another screen on the way in
+    fn toggle(&mut self, window: &Window) -> Result<Option<(u32, u32, f64)>>;
+
+    /// Hide the dropdown because another window took focus, unless it's pinned
+    /// or in glass mode. Returns true if it was hidden
+    fn hide_on_focus_loss(&mut self, window: &Window) -> Result<bool>;
+
+    fn is_visible(&self) -> bool;
+
+    /// Keep the dropdown open while other apps have focus
+    /// Returns true if it's now pinned
+    fn toggle_pin(&mut self) -> bool;
+
+    fn is_pinned(&self) -> bool;
+
+    /// Window opacity (text included) in normal and glass mode (0.1-1.0)
+    /// Takes effect the next time the window is shown or `apply_opacity` is called
+    fn set_opacity(&mut self, opacity: f64, glass_opacity: f64);
+
+    /// Push the current opacity to a visible window
+    fn apply_opacity(&self, window: &Window);
+
+    /// Whether the dropdown is on every Space and may open over fullscreen apps
+    /// Takes effect the next time it's shown. Only macOS has Spaces
+    fn set_spaces(&mut self, _can_join_all_spaces: bool, _appear_over_fullscreen: bool) {}
+
+    /// Toggle click-through glass mode
+    /// Returns true if glass mode is now on
+    fn toggle_glass(&mut self, window: &Window) -> bool;
+
+    /// Height in logical pixels and scale factor of the screen the dropdown is on
+    /// None when `window` isn't the dropdown
+    fn dropdown_screen(&self, window: &Window) -> Option<(f64, f64)>;
+
+    /// Resize the dropdown to `height` logical pixels, keeping its top edge in place
+    fn set_height(&self, window: &Window, height: f64);
+}
+
+/// Text copy and paste through the system clipboard
+pub trait ClipboardProvider {
+    fn set_text(&mut self, text: &str) -> Result<()>;
+
+    fn get_text(&mut self) -> Result<String>;
+}
+
+/// Kinds of per-user files Saternal keeps outside the config directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserDir {
+    /// Safe to delete (downloaded updates)
+    Cache,
+    /// Kept across launches but not worth backing up (logs, crash reports)
+    State,
+}
+
+/// Saternal's directory of `kind` on this OS:
+/// - macOS: ~/Library/Caches/Saternal, ~/Library/Logs/Saternal
+/// - Windows: %LOCALAPPDATA%\Saternal for both
+/// - Elsewhere: $XDG_CACHE_HOME/saternal (~/.cache), $XDG_STATE_HOME/saternal (~/.local/state)
+pub fn user_dir(kind: UserDir) -> Option<PathBuf> {
+    user_dir_for(kind, std::env::consts::OS, |name| std::env::var_os(name))
+}
+
+fn user_dir_for(kind: UserDir, os: &str, var: impl Fn(&str) -> Option<OsString>) -> Option<PathBuf> {
+    // Unset and empty mean the same; XDG also ignores relative paths
+    let absolute = |name: &str| var(name).map(PathBuf::from).filter(|dir| dir.is_absolute());
+    match os {
+        "macos" => {
+            let home = absolute("HOME")?;
+            Some(match kind {
+                UserDir::Cache => home.join("Library/Caches/Saternal"),
+                UserDir::State => home.join("Library/Logs/Saternal"),
+            })
+        }
+        "windows" => Some(absolute("LOCALAPPDATA")?.join("Saternal")),
+        _ => {
+            let (xdg, fallback) = match kind {
+                UserDir::Cache => ("XDG_CACHE_HOME", ".cache"),
+                UserDir::State => ("XDG_STATE_HOME", ".local/state"),
+            };
+            let base = absolute(xdg).or_else(|| Some(absolute("HOME")?.join(fallback)))?;
+            Some(base.join("saternal"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env<'a>(vars: &'a [(&'a str, &'a str)]) -> impl Fn(&str) -> Option<OsString> + 'a {
+        move |name| vars.iter().find(|(key, _)| *key == name).map(|(_, value)| OsString::from(value))
+    }
+
+    #[test]
+    fn test_user_dirs_per_platform() {
+        let home = [("HOME", "/home/sam")];
+        assert_eq!(
+            user_dir_for(UserDir::State, "macos", env(&home)),
+            Some(PathBuf::from("/home/sam/Library/Logs/Saternal"))
+        );
+        assert_eq!(
+            user_dir_for(UserDir::Cache, "macos", env(&home)),
+            Some(PathBuf::from("/home/sam/Library/Caches/Saternal"))
+        );
+
+        // Linux without XDG variables, then with them (relative ones don't count)
+        assert_eq!(
+            user_dir_for(UserDir::State, "linux", env(&home)),
+            Some(PathBuf::from("/home/sam/.local/state/saternal"))
+        );
+        assert_eq!(
+            user_dir_for(UserDir::Cache, "linux", env(&home)),
+            Some(PathBuf::from("/home/sam/.cache/saternal"))
+        );
+        let xdg = [("HOME", "/home/sam"), ("XDG_STATE_HOME", "/xdg/state"), ("XDG_CACHE_HOME", "cache")];
+        assert_eq!(user_dir_for(UserDir::State, "linux", env(&xdg)), Some(PathBuf::from("/xdg/state/saternal")));
+        assert_eq!(
+            user_dir_for(UserDir::Cache, "linux", env(&xdg)),
+            Some(PathBuf::from("/home/sam/.cache/saternal"))
+        );
+
+        let windows = [("LOCALAPPDATA", "C:\\Users\\sam\\AppData\\Local")];
+        if cfg!(windows) {
+            assert_eq!(
+                user_dir_for(UserDir::Cache, "windows", env(&windows)),
+                Some(PathBuf::from("C:\\Users\\sam\\AppData\\Local\\Saternal"))
+            );
+        }
+        // No home at all
+        assert_eq!(user_dir_for(UserDir::Cache, "windows", env(&[])), None);
+        assert_eq!(user_dir_for(UserDir::State, "linux", env(&[])), None);
+    }
+}
@@ -0,0 +1,96 @@
+/// Privacy lock that hides pane content while the user is away
+///
+/// Revealing is a two-step gesture: the global hotkey arms the reveal and the
+/// next keypress unlocks. A stray keypress on its own never exposes scrollback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PrivacyLock {
+    #[default]
+    Unlocked,
+    Locked,
+    RevealArmed,
+}
+
+impl PrivacyLock {
+    /// Hide content (no-op if already locked)
+    pub fn lock(&mut self) {
+        if *self == PrivacyLock::Unlocked {
+            *self = PrivacyLock::Locked;
+        }
+    }
+
+    /// Check if content should be hidden
+    pub fn is_locked(&self) -> bool {
+        *self != PrivacyLock::Unlocked
+    }
+
+    /// Called when the global hotkey fires - arms the reveal if locked
+    pub fn arm_reveal(&mut self) {
+        if *self == PrivacyLock::Locked {
+            *self = PrivacyLock::RevealArmed;
+        }
+    }
+
+    /// Drop an armed reveal (window hidden before the unlocking keypress)
+    pub fn disarm(&mut self) {
+        if *self == PrivacyLock::RevealArmed {
+            *self = PrivacyLock::Locked;
+        }
+    }
+
+    /// Called on every keypress
+    /// Returns true if the key was consumed by the lock (must not reach the terminal)
+    pub fn handle_keypress(&mut self) -> bool {
+        match *self {
+            PrivacyLock::Unlocked => false,
+            PrivacyLock::Locked => true,
+            PrivacyLock::RevealArmed => {
+                *self = PrivacyLock::Unlocked;
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keypress_alone_does_not_unlock() {
+        let mut lock = PrivacyLock::default();
+        lock.lock();
+        assert!(lock.handle_keypress());
+        assert!(lock.is_locked());
+    }
+
+    #[test]
+    fn test_hotkey_then_keypress_unlocks() {
+        let mut lock = PrivacyLock::default();
+        lock.lock();
+        lock.arm_reveal();
+        assert!(lock.is_locked());
+        assert!(lock.handle_keypress());
+        assert!(!lock.is_locked());
+        // Subsequent keys go to the terminal again
+        assert!(!lock.handle_keypress());
+    }
+
+    #[test]
+    fn test_hotkey_while_unlocked_is_ignored() {
+        let mut lock = PrivacyLock::default();
+        lock.arm_reveal();
+        assert_eq!(lock, PrivacyLock::Unlocked);
+    }
+
+    #[test]
+    fn test_disarm_keeps_content_locked() {
+        let mut lock = PrivacyLock::default();
+        lock.lock();
+        lock.arm_reveal();
+        lock.disarm();
+        assert_eq!(lock, PrivacyLock::Locked);
+        // A keypress after hiding and showing again no longer unlocks
+        assert!(lock.handle_keypress());
+        assert!(lock.is_locked());
+    }
+}
@@ -0,0 +1,256 @@
+/// Foreground process lookup for a PTY
+///
+/// The foreground process group of the PTY is whatever currently owns the
+/// terminal - the shell at a prompt, or the program it is running.
+#[cfg(unix)]
+use std::os::unix::io::RawFd;
+use std::path::{Path, PathBuf};
+
+/// Name and working directory of a process
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessInfo {
+    pub pid: i32,
+    pub name: String,
+    pub cwd: Option<PathBuf>,
+    /// Host an ssh process is connected to, read from its arguments
+    pub remote_host: Option<String>,
+}
+
+/// Foreground process of the terminal behind `pty_fd`
+#[cfg(unix)]
+pub fn foreground_process(pty_fd: RawFd) -> Option<ProcessInfo> {
+    let pgid = unsafe { libc::tcgetpgrp(pty_fd) };
+    if pgid <= 0 {
+        return None;
+    }
+    process_info(pgid)
+}
+
+/// Look up a process by pid
+pub fn process_info(pid: i32) -> Option<ProcessInfo> {
+    let name = process_name(pid)?;
+    let remote_host = (name == "ssh")
+        .then(|| process_args(pid))
+        .flatten()
+        .and_then(|args| ssh_host(&args));
+    Some(ProcessInfo { pid, name, cwd: process_cwd(pid), remote_host })
+}
+
+#[cfg(target_os = "macos")]
+fn process_name(pid: i32) -> Option<String> {
+    let mut buf = vec![0u8; libc::PROC_PIDPATHINFO_MAXSIZE as usize];
+    let len = unsafe { libc::proc_pidpath(pid, buf.as_mut_ptr().cast(), buf.len() as u32) };
+    if len <= 0 {
+        return None;
+    }
+    let path = String::from_utf8_lossy(&buf[..len as usize]).into_owned();
+    Path::new(&path).file_name().map(|name| name.to_string_lossy().into_owned())
+}
+
+#[cfg(target_os = "macos")]
+fn process_cwd(pid: i32) -> Option<PathBuf> {
+    let mut info: libc::proc_vnodepathinfo = unsafe { std::mem::zeroed() };
+    let size = std::mem::size_of::<libc::proc_vnodepathinfo>() as i32;
+    let written = unsafe {
+        libc::proc_pidinfo(
+            pid,
+            libc::PROC_PIDVNODEPATHINFO,
+            0,
+            (&mut info as *mut libc::proc_vnodepathinfo).cast(),
+            size,
+        )
+    };
+    if written != size {
+        return None;
+    }
+    // vip_path is a MAXPATHLEN C string split into 32-byte rows
+    let bytes: Vec<u8> = info.pvi_cdir.vip_path.iter().flatten().map(|&c| c as u8).collect();
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    (end > 0).then(|| PathBuf::from(String::from_utf8_lossy(&bytes[..end]).into_owned()))
+}
+
+/// argv of a process, from KERN_PROCARGS2: argc, the executable path, NUL padding, then argv
+#[cfg(target_os = "macos")]
+fn process_args(pid: i32) -> Option<Vec<String>> {
+    let mut mib = [libc::CTL_KERN, libc::KERN_PROCARGS2, pid];
+    let mut size: libc::size_t = 0;
+    let mut sysctl = |buf: *mut libc::c_void, size: &mut libc::size_t| unsafe {
+        libc::sysctl(mib.as_mut_ptr(), mib.len() as u32, buf, size, std::ptr::null_mut(), 0) == 0
+    };
+    if !sysctl(std::ptr::null_mut(), &mut size) {
+        return None;
+    }
+    let mut buf = vec![0u8; size];
+    if !sysctl(buf.as_mut_ptr().cast(), &mut size) {
+        return None;
+    }
+    buf.truncate(size);
+    let argc = i32::from_ne_bytes(buf.get(..4)?.try_into().ok()?) as usize;
+    let mut strings = buf[4..].split(|&b| b == 0).filter(|s| !s.is_empty());
+    strings.next()?;
+    Some(strings.take(argc).map(|arg| String::from_utf8_lossy(arg).into_owned()).collect())
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn process_args(pid: i32) -> Option<Vec<String>> {
+    let cmdline = std::fs::read(format!("/proc/{}/cmdline", pid)).ok()?;
+    let cmdline = cmdline.strip_suffix(&[0]).unwrap_or(&cmdline);
+    Some(cmdline.split(|&b| b == 0).map(|arg| String::from_utf8_lossy(arg).into_owned()).collect())
+}
+
+/// Another process's command line is only in its memory on Windows
+#[cfg(windows)]
+fn process_args(_pid: i32) -> Option<Vec<String>> {
+    None
+}
+
+/// Host an ssh command line connects to: `ssh -p 2222 deploy@web-1 uptime` is "web-1"
+pub fn ssh_host(args: &[String]) -> Option<String> {
+    // ssh(1) options that take a value, either attached (-p2222) or as the next argument
+    const WITH_VALUE: &str = "BbcDEeFIiJLlmOopQRSWw";
+    let mut args = args.iter().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            return args.next().and_then(|destination| destination_host(destination));
+        }
+        match arg.strip_prefix('-') {
+            // Flags can be grouped (-vA); one that takes a value ends the group
+            Some(flags) if !flags.is_empty() => {
+                if flags.find(|c| WITH_VALUE.contains(c)) == Some(flags.len() - 1) {
+                    args.next();
+                }
+            }
+            _ => return destination_host(arg),
+        }
+    }
+    None
+}
+
+/// Host part of `[user@]host` or `ssh://[user@]host[:port]`
+fn destination_host(destination: &str) -> Option<String> {
+    let uri = destination.strip_prefix("ssh://");
+    let rest = uri.unwrap_or(destination);
+    let host = rest.rsplit_once('@').map_or(rest, |(_, host)| host);
+    let host = match host.strip_prefix('[') {
+        Some(bracketed) => bracketed.split(']').next().unwrap_or_default(),
+        None if uri.is_some() => host.split([':', '/']).next().unwrap_or_default(),
+        None => host,
+    };
+    (!host.is_empty()).then(|| host.to_string())
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn process_name(pid: i32) -> Option<String> {
+    let comm = std::fs::read_to_string(format!("/proc/{}/comm", pid)).ok()?;
+    Some(comm.trim_end().to_string())
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn process_cwd(pid: i32) -> Option<PathBuf> {
+    std::fs::read_link(format!("/proc/{}/cwd", pid)).ok()
+}
+
+#[cfg(windows)]
+fn process_name(pid: i32) -> Option<String> {
+    use std::os::windows::ffi::OsStringExt;
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    let process = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid as u32) };
+    if process == 0 {
+        return None;
+    }
+    let mut buf = vec![0u16; 1024];
+    let mut len = buf.len() as u32;
+    let ok = unsafe { QueryFullProcessImageNameW(process, PROCESS_NAME_WIN32, buf.as_mut_ptr(), &mut len) } != 0;
+    unsafe { CloseHandle(process) };
+    if !ok {
+        return None;
+    }
+    let path = PathBuf::from(std::ffi::OsString::from_wide(&buf[..len as usize]));
+    path.file_name().map(|name| name.to_string_lossy().into_owned())
+}
+
+/// Another process's working directory is only in its memory on Windows
+#[cfg(windows)]
+fn process_cwd(_pid: i32) -> Option<PathBuf> {
+    None
+}
+
+/// Shorten a path under `home` to start with `~`
+pub fn abbreviate_home(path: &Path, home: Option<&Path>) -> String {
+    if let Some(rest) = home.and_then(|home| path.strip_prefix(home).ok()) {
+        if rest.as_os_str().is_empty() {
+            return "~".to_string();
+        }
+        return format!("~/{}", rest.display());
+    }
+    path.display().to_string()
+}
+
+/// Header text for a pane: "1 · vim · ~/src", or "1 · ssh · web-1" with the remote host in
+/// place of the (local) directory
+pub fn pane_header_label(index: usize, process: Option<&ProcessInfo>) -> String {
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    let mut label = index.to_string();
+    if let Some(process) = process {
+        label.push_str(" · ");
+        label.push_str(&process.name);
+        if let Some(host) = &process.remote_host {
+            label.push_str(" · ");
+            label.push_str(host);
+        } else if let Some(cwd) = &process.cwd {
+            label.push_str(" · ");
+            label.push_str(&abbreviate_home(cwd, home.as_deref()));
+        }
+    }
+    label
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_abbreviate_home() {
+        let home = Path::new("/Users/sam");
+        assert_eq!(abbreviate_home(Path::new("/Users/sam"), Some(home)), "~");
+        assert_eq!(abbreviate_home(Path::new("/Users/sam/src/app"), Some(home)), "~/src/app");
+        assert_eq!(abbreviate_home(Path::new("/Users/samuel"), Some(home)), "/Users/samuel");
+        assert_eq!(abbreviate_home(Path::new("/tmp"), None), "/tmp");
+    }
+
+    #[test]
+    fn test_ssh_host() {
+        let host = |line: &str| ssh_host(&line.split(' ').map(String::from).collect::<Vec<_>>());
+        assert_eq!(host("ssh web-1").as_deref(), Some("web-1"));
+        assert_eq!(host("ssh -p 2222 -vA deploy@web-1 uptime").as_deref(), Some("web-1"));
+        assert_eq!(host("ssh -p2222 -i ~/.ssh/id -o BatchMode=yes web-1").as_deref(), Some("web-1"));
+        assert_eq!(host("ssh -- deploy@db.internal").as_deref(), Some("db.internal"));
+        assert_eq!(host("ssh ssh://deploy@[::1]:2222").as_deref(), Some("::1"));
+        assert_eq!(host("ssh ssh://web-1:2222").as_deref(), Some("web-1"));
+        assert_eq!(host("ssh -V"), None);
+    }
+
+    #[test]
+    fn test_pane_header_label_shows_remote_host() {
+        let ssh = ProcessInfo {
+            pid: 1,
+            name: "ssh".to_string(),
+            cwd: Some(PathBuf::from("/tmp")),
+            remote_host: Some("web-1".to_string()),
+        };
+        assert_eq!(pane_header_label(2, Some(&ssh)), "2 · ssh · web-1");
+        assert_eq!(pane_header_label(2, Some(&ProcessInfo { remote_host: None, ..ssh })), "2 · ssh · /tmp");
+    }
+
+    #[test]
+    fn test_own_process_info() {
+        let info = process_info(std::process::id() as i32).unwrap();
+        assert!(!info.name.is_empty());
+        #[cfg(unix)]
+        assert_eq!(info.cwd, std::env::current_dir().ok());
+    }
+}
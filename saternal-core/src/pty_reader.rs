@@ -0,0 +1,148 @@
+/// Background PTY reads with backpressure
+///
+/// Each terminal's PTY is read on a shared tokio runtime and handed to the
+/// pane's parser thread (`output_parser`) through a bounded channel. When the
+/// parser falls behind the channel fills, the reader stops draining the PTY and
+/// the kernel buffer throttles the child, so `cat largefile` can't outrun it.
+///
+/// A pseudo console's output on Windows is an anonymous pipe, which can't be
+/// polled; each one gets a blocking thread that feeds the same channel.
+use log::debug;
+use std::fs::File;
+use std::io::Read;
+use std::sync::OnceLock;
+#[cfg(unix)]
+use tokio::io::unix::AsyncFd;
+#[cfg(unix)]
+use tokio::io::Interest;
+use tokio::runtime::Runtime;
+use tokio::sync::mpsc;
+
+/// Bytes read from the PTY per chunk
+pub const PTY_CHUNK_SIZE: usize = 16 * 1024;
+
+/// Chunks buffered between the reader and the parser thread before the reader waits
+pub const PTY_CHANNEL_CAPACITY: usize = 64;
+
+/// Runtime shared by the readers of every pane
+pub(crate) fn runtime() -> &'static Runtime {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("saternal-pty")
+            .enable_io()
+            .build()
+            .expect("failed to start PTY reader runtime")
+    })
+}
+
+/// Start reading `file` (a non-blocking PTY fd) in the background
+/// The reader stops at EOF, on a read error, or when the receiver is dropped
+#[cfg(unix)]
+pub fn spawn(file: File) -> anyhow::Result<mpsc::Receiver<Vec<u8>>> {
+    let (tx, rx) = mpsc::channel(PTY_CHANNEL_CAPACITY);
+    let runtime = runtime();
+    let fd = {
+        let _guard = runtime.enter();
+        AsyncFd::with_interest(file, Interest::READABLE)?
+    };
+    runtime.spawn(read_loop(fd, tx));
+    Ok(rx)
+}
+
+/// Start reading `file` (a pseudo console's output pipe) in the background
+/// The reader stops at EOF, on a read error, or when the receiver is dropped
+#[cfg(windows)]
+pub fn spawn(mut file: File) -> anyhow::Result<mpsc::Receiver<Vec<u8>>> {
+    let (tx, rx) = mpsc::channel(PTY_CHANNEL_CAPACITY);
+    std::thread::Builder::new()
+        .name("saternal-pty".to_string())
+        .spawn(move || {
+            let mut buf = vec![0u8; PTY_CHUNK_SIZE];
+            loop {
+                match file.read(&mut buf) {
+                    Ok(0) => return, // EOF (the console closed)
+                    Ok(n) => {
+                        // Waits while the parser is behind - this is the backpressure
+                        if tx.blocking_send(buf[..n].to_vec()).is_err() {
+                            return;
+                        }
+                    }
+                    Err(e) => {
+                        debug!("PTY read error: {}", e);
+                        return;
+                    }
+                }
+            }
+        })?;
+    Ok(rx)
+}
+
+#[cfg(unix)]
+async fn read_loop(fd: AsyncFd<File>, tx: mpsc::Sender<Vec<u8>>) {
+    let mut buf = vec![0u8; PTY_CHUNK_SIZE];
+    loop {
+        let mut ready = match fd.readable().await {
+            Ok(ready) => ready,
+            Err(e) => {
+                debug!("PTY poll error: {}", e);
+                return;
+            }
+        };
+        match ready.try_io(|inner| inner.get_ref().read(&mut buf)) {
+            Ok(Ok(0)) => return, // EOF
+            Ok(Ok(n)) => {
+                // Waits while the parser is behind - this is the backpressure
+                if tx.send(buf[..n].to_vec()).await.is_err() {
+                    return;
+                }
+            }
+            Ok(Err(e)) => {
+                debug!("PTY read error: {}", e);
+                return;
+            }
+            Err(_would_block) => continue,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_reads_until_eof() {
+        #[cfg(unix)]
+        let (mut writer, file) = {
+            let (writer, reader) = std::os::unix::net::UnixStream::pair().unwrap();
+            reader.set_nonblocking(true).unwrap();
+            (writer, File::from(std::os::fd::OwnedFd::from(reader)))
+        };
+        #[cfg(windows)]
+        let (mut writer, file) = {
+            let (reader, writer) = std::io::pipe().unwrap();
+            (writer, File::from(std::os::windows::io::OwnedHandle::from(reader)))
+        };
+        let mut rx = spawn(file).unwrap();
+
+        writer.write_all(b"hello").unwrap();
+        drop(writer);
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut received = Vec::new();
+        loop {
+            match rx.try_recv() {
+                Ok(chunk) => received.extend(chunk),
+                Err(mpsc::error::TryRecvError::Disconnected) => break,
+                Err(mpsc::error::TryRecvError::Empty) => {
+                    assert!(Instant::now() < deadline, "reader never finished");
+                    std::thread::sleep(Duration::from_millis(5));
+                }
+            }
+        }
+        assert_eq!(received, b"hello");
+    }
+}
@@ -1,7 +0,0 @@
-mod config;
-mod pipeline;
-mod state;
-
-pub use config::{CursorConfig, CursorStyle};
-pub use pipeline::create_cursor_pipeline;
-pub use state::CursorState;
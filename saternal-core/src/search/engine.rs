@@ -1,34 +1,55 @@
-/// Search engine using Boyer-Moore-Horspool algorithm for fast substring search
+/// Search engine matching literal text or regular expressions line by line
 use alacritty_terminal::grid::{Dimensions, Grid};
 use alacritty_terminal::index::{Column, Line, Point};
-use alacritty_terminal::term::cell::Cell;
+use alacritty_terminal::term::cell::{Cell, Flags};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+/// Search matching options (defaults are persisted in Config)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchOptions {
+    /// Treat the query as a regular expression
+    pub regex: bool,
+    /// Match letter case exactly
+    pub case_sensitive: bool,
+    /// Only match whole words
+    pub whole_word: bool,
+}
 
-/// Fast text search engine
+/// Text search engine
 pub struct SearchEngine {
     pattern: String,
-    bad_char_table: [usize; 256],
+    regex: Regex,
 }
 
 impl SearchEngine {
-    /// Create a new search engine with the given pattern
+    /// Create a case-insensitive literal search for the given pattern
     pub fn new(pattern: &str) -> Self {
-        let bad_char_table = Self::build_bad_char_table(pattern);
-        Self {
-            pattern: pattern.to_string(),
-            bad_char_table,
-        }
+        Self::with_options(pattern, SearchOptions::default())
+            .expect("escaped literal pattern is always valid")
     }
 
-    /// Build Boyer-Moore-Horspool skip table
-    fn build_bad_char_table(pattern: &str) -> [usize; 256] {
-        let mut table = [pattern.len(); 256];
-        let pattern_bytes = pattern.as_bytes();
-        
-        for (i, &byte) in pattern_bytes.iter().enumerate().take(pattern.len().saturating_sub(1)) {
-            table[byte as usize] = pattern.len() - 1 - i;
+    /// Create a search engine with explicit options
+    /// Fails if the pattern is not a valid regular expression
+    pub fn with_options(pattern: &str, options: SearchOptions) -> Result<Self, regex::Error> {
+        let mut source = if options.regex {
+            pattern.to_string()
+        } else {
+            regex::escape(pattern)
+        };
+        if options.whole_word {
+            source = format!(r"\b(?:{})\b", source);
         }
-        
-        table
+
+        let regex = RegexBuilder::new(&source)
+            .case_insensitive(!options.case_sensitive)
+            .build()?;
+
+        Ok(Self {
+            pattern: pattern.to_string(),
+            regex,
+        })
     }
 
     /// Find next match starting from given point
@@ -37,22 +58,12 @@ impl SearchEngine {
             return None;
         }
 
-        let num_lines = grid.screen_lines();
-        let num_cols = grid.columns();
-        
-        // Convert grid to searchable text with position mapping
-        let mut current_line = start.line.0 as usize;
-        let mut current_col = start.column.0;
-
-        while current_line < num_lines {
-            let line_start_col = if current_line == start.line.0 as usize { current_col } else { 0 };
-            
-            if let Some(match_col) = self.search_line(grid, current_line, line_start_col, num_cols) {
-                return Some(Point::new(Line(current_line as i32), Column(match_col)));
+        let num_lines = grid.screen_lines() as i32;
+        for line in start.line.0..num_lines {
+            let min_col = if line == start.line.0 { start.column.0 } else { 0 };
+            if let Some(col) = self.line_matches(grid, line).into_iter().find(|&col| col >= min_col) {
+                return Some(Point::new(Line(line), Column(col)));
             }
-            
-            current_line += 1;
-            current_col = 0;
         }
 
         None
@@ -64,24 +75,16 @@ impl SearchEngine {
             return None;
         }
 
-        let num_cols = grid.columns();
-        let mut current_line = start.line.0 as usize;
-        
-        loop {
-            let line_end_col = if current_line == start.line.0 as usize { 
-                start.column.0.saturating_sub(1)
-            } else { 
-                num_cols.saturating_sub(1)
+        for line in (0..=start.line.0).rev() {
+            let matches = self.line_matches(grid, line);
+            let found = if line == start.line.0 {
+                matches.into_iter().rev().find(|&col| col < start.column.0)
+            } else {
+                matches.last().copied()
             };
-            
-            if let Some(match_col) = self.search_line_reverse(grid, current_line, 0, line_end_col) {
-                return Some(Point::new(Line(current_line as i32), Column(match_col)));
-            }
-            
-            if current_line == 0 {
-                break;
+            if let Some(col) = found {
+                return Some(Point::new(Line(line), Column(col)));
             }
-            current_line -= 1;
         }
 
         None
@@ -89,26 +92,31 @@ impl SearchEngine {
 
     /// Find all matches in the grid
     pub fn find_all(&self, grid: &Grid<Cell>, max_matches: usize) -> Vec<Point> {
+        self.find_all_in_lines(grid, 0..grid.screen_lines() as i32, max_matches)
+    }
+
+    /// Find all matches including scrollback history (history lines are negative)
+    pub fn find_all_with_history(&self, grid: &Grid<Cell>, max_matches: usize) -> Vec<Point> {
+        let history = grid.history_size() as i32;
+        self.find_all_in_lines(grid, -history..grid.screen_lines() as i32, max_matches)
+    }
+
+    fn find_all_in_lines(
+        &self,
+        grid: &Grid<Cell>,
+        lines: std::ops::Range<i32>,
+        max_matches: usize,
+    ) -> Vec<Point> {
         if self.pattern.is_empty() {
             return Vec::new();
         }
 
         let mut matches = Vec::new();
-        let num_lines = grid.screen_lines();
-        let num_cols = grid.columns();
-
-        for line in 0..num_lines {
-            let mut col = 0;
-            while col < num_cols {
-                if let Some(match_col) = self.search_line(grid, line, col, num_cols) {
-                    matches.push(Point::new(Line(line as i32), Column(match_col)));
-                    col = match_col + self.pattern.len();
-                    
-                    if matches.len() >= max_matches {
-                        return matches;
-                    }
-                } else {
-                    break;
+        for line in lines {
+            for col in self.line_matches(grid, line) {
+                matches.push(Point::new(Line(line), Column(col)));
+                if matches.len() >= max_matches {
+                    return matches;
                 }
             }
         }
@@ -116,84 +124,121 @@ impl SearchEngine {
         matches
     }
 
-    /// Search a single line using Boyer-Moore-Horspool
-    fn search_line(&self, grid: &Grid<Cell>, line: usize, start_col: usize, end_col: usize) -> Option<usize> {
-        let pattern_bytes = self.pattern.as_bytes();
-        let pattern_len = pattern_bytes.len();
-        
-        if pattern_len == 0 || start_col + pattern_len > end_col {
-            return None;
-        }
+    /// Last cell of the match starting at `start`, if one starts there
+    pub fn match_end(&self, grid: &Grid<Cell>, start: Point) -> Option<Point> {
+        let (text, byte_to_col) = line_text(grid, start.line.0);
+        self.regex
+            .find_iter(&text)
+            .filter(|m| !m.is_empty())
+            .find(|m| byte_to_col[m.start()] == start.column.0)
+            .map(|m| Point::new(start.line, Column(byte_to_col[m.end() - 1])))
+    }
 
-        let mut pos = start_col;
-        
-        while pos + pattern_len <= end_col {
-            let mut matched = true;
-            
-            // Check if pattern matches at current position
-            for i in 0..pattern_len {
-                let point = Point::new(Line(line as i32), Column(pos + i));
-                let cell = &grid[point];
-                let pattern_char = pattern_bytes[i].to_ascii_lowercase() as char;
-                if cell.c.to_lowercase().next() != Some(pattern_char) {
-                    matched = false;
-                    
-                    // Use bad character rule for skip
-                    let last_char_point = Point::new(Line(line as i32), Column(pos + pattern_len - 1));
-                    let last_cell = &grid[last_char_point];
-                    let skip = self.bad_char_table[last_cell.c as usize];
-                    pos += skip.max(1);
-                    break;
-                }
-            }
-            
-            if matched {
-                return Some(pos);
-            }
-        }
+    /// Start columns of all non-overlapping matches on a line
+    fn line_matches(&self, grid: &Grid<Cell>, line: i32) -> Vec<usize> {
+        let (text, byte_to_col) = line_text(grid, line);
+        self.regex
+            .find_iter(&text)
+            .filter(|m| !m.is_empty())
+            .map(|m| byte_to_col[m.start()])
+            .collect()
+    }
 
-        None
+    /// Get the search pattern
+    pub fn pattern(&self) -> &str {
+        &self.pattern
     }
+}
 
-    /// Search a single line in reverse
-    fn search_line_reverse(&self, grid: &Grid<Cell>, line: usize, start_col: usize, end_col: usize) -> Option<usize> {
-        let pattern_bytes = self.pattern.as_bytes();
-        let pattern_len = pattern_bytes.len();
-        
-        if pattern_len == 0 || end_col < pattern_len {
-            return None;
+/// Text of a grid line plus the column each byte of it came from
+/// Wide-char spacer cells are skipped so wide characters read as one char
+fn line_text(grid: &Grid<Cell>, line: i32) -> (String, Vec<usize>) {
+    let num_cols = grid.columns();
+    let mut text = String::with_capacity(num_cols);
+    let mut byte_to_col = Vec::with_capacity(num_cols);
+
+    for col in 0..num_cols {
+        let cell = &grid[Point::new(Line(line), Column(col))];
+        if cell.flags.contains(Flags::WIDE_CHAR_SPACER) {
+            continue;
         }
+        text.push(cell.c);
+        byte_to_col.extend(std::iter::repeat_n(col, cell.c.len_utf8()));
+    }
 
-        let mut pos = end_col.saturating_sub(pattern_len - 1);
-        
-        loop {
-            let mut matched = true;
-            
-            for i in 0..pattern_len {
-                let point = Point::new(Line(line as i32), Column(pos + i));
-                let cell = &grid[point];
-                let pattern_char = pattern_bytes[i].to_ascii_lowercase() as char;
-                if cell.c.to_lowercase().next() != Some(pattern_char) {
-                    matched = false;
-                    break;
-                }
-            }
-            
-            if matched {
-                return Some(pos);
-            }
-            
-            if pos == start_col {
-                break;
+    (text, byte_to_col)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid_with(lines: &[&str]) -> Grid<Cell> {
+        let mut grid: Grid<Cell> = Grid::new(lines.len(), 20, 0);
+        for (line, text) in lines.iter().enumerate() {
+            for (col, c) in text.chars().enumerate() {
+                grid[Point::new(Line(line as i32), Column(col))].c = c;
             }
-            pos = pos.saturating_sub(1);
         }
+        grid
+    }
 
-        None
+    fn columns(engine: &SearchEngine, grid: &Grid<Cell>) -> Vec<(i32, usize)> {
+        engine.find_all(grid, 100).iter().map(|p| (p.line.0, p.column.0)).collect()
     }
 
-    /// Get the search pattern
-    pub fn pattern(&self) -> &str {
-        &self.pattern
+    #[test]
+    fn test_literal_search_is_case_insensitive_by_default() {
+        let grid = grid_with(&["Error error", "a.b"]);
+        assert_eq!(columns(&SearchEngine::new("error"), &grid), vec![(0, 0), (0, 6)]);
+        // Regex metacharacters are literal unless regex mode is on
+        assert_eq!(columns(&SearchEngine::new("."), &grid), vec![(1, 1)]);
+    }
+
+    #[test]
+    fn test_search_options() {
+        let grid = grid_with(&["Error error errors"]);
+
+        let case = SearchOptions { case_sensitive: true, ..Default::default() };
+        let engine = SearchEngine::with_options("error", case).unwrap();
+        assert_eq!(columns(&engine, &grid), vec![(0, 6), (0, 12)]);
+
+        let word = SearchOptions { whole_word: true, ..Default::default() };
+        let engine = SearchEngine::with_options("error", word).unwrap();
+        assert_eq!(columns(&engine, &grid), vec![(0, 0), (0, 6)]);
+
+        let regex = SearchOptions { regex: true, ..Default::default() };
+        let engine = SearchEngine::with_options(r"errors?\b", regex).unwrap();
+        assert_eq!(columns(&engine, &grid), vec![(0, 0), (0, 6), (0, 12)]);
+
+        assert!(SearchEngine::with_options("(", regex).is_err());
+    }
+
+    #[test]
+    fn test_find_next_and_prev() {
+        let grid = grid_with(&["foo foo", "foo"]);
+        let engine = SearchEngine::new("foo");
+
+        let next = engine.find_next(&grid, Point::new(Line(0), Column(1))).unwrap();
+        assert_eq!((next.line.0, next.column.0), (0, 4));
+
+        let prev = engine.find_prev(&grid, Point::new(Line(1), Column(0))).unwrap();
+        assert_eq!((prev.line.0, prev.column.0), (0, 4));
+    }
+
+    #[test]
+    fn test_match_end() {
+        let grid = grid_with(&["a foo → foo"]);
+        let engine = SearchEngine::new("foo");
+        let end = |col| engine.match_end(&grid, Point::new(Line(0), Column(col))).map(|p| p.column.0);
+        assert_eq!(end(2), Some(4));
+        assert_eq!(end(8), Some(10));
+        assert_eq!(end(3), None);
+    }
+
+    #[test]
+    fn test_non_ascii_cells_do_not_panic() {
+        let grid = grid_with(&["héllo → wörld"]);
+        assert_eq!(columns(&SearchEngine::new("wörld"), &grid), vec![(0, 8)]);
     }
 }
@@ -0,0 +1,94 @@
+/// Filtered view of a pane: only the lines matching the search are shown (like `less &pattern`)
+use alacritty_terminal::index::Line;
+
+/// Index of the grid lines (negative = history) that match, oldest first
+///
+/// Rows are laid out like an unfiltered screen: the newest matching lines sit at
+/// the bottom, and scrolling moves up through the older ones. Positions in that
+/// layout are "view lines", which play the role of grid lines for scrolling.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LineFilter {
+    lines: Vec<i32>,
+}
+
+impl LineFilter {
+    /// Build the index from matching grid lines (any order, duplicates allowed)
+    pub fn new(lines: impl IntoIterator<Item = i32>) -> Self {
+        let mut lines: Vec<i32> = lines.into_iter().collect();
+        lines.sort_unstable();
+        lines.dedup();
+        Self { lines }
+    }
+
+    /// Number of matching lines
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// How far the filtered view scrolls (its "history" size)
+    pub fn max_offset(&self, screen_lines: usize) -> usize {
+        self.lines.len().saturating_sub(screen_lines)
+    }
+
+    /// Grid line shown on screen row `row` while scrolled `offset` lines up
+    pub fn line_at(&self, row: usize, screen_lines: usize, offset: usize) -> Option<Line> {
+        let max_offset = self.max_offset(screen_lines);
+        let index = row + max_offset - offset.min(max_offset);
+        self.lines.get(index).map(|&line| Line(line))
+    }
+
+    /// Screen row showing grid line `line` while scrolled `offset` lines up
+    pub fn row_of(&self, line: i32, screen_lines: usize, offset: usize) -> Option<usize> {
+        let view_line = self.view_line(line, screen_lines)?;
+        let row = view_line + offset.min(self.max_offset(screen_lines)) as i32;
+        (0..screen_lines as i32).contains(&row).then_some(row as usize)
+    }
+
+    /// Where grid line `line` sits in the filtered view (negative = scrolled out above)
+    pub fn view_line(&self, line: i32, screen_lines: usize) -> Option<i32> {
+        let index = self.lines.binary_search(&line).ok()?;
+        Some(index as i32 - self.max_offset(screen_lines) as i32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_filter_fills_from_the_top() {
+        let filter = LineFilter::new([5, -20, 5, -3]);
+        assert_eq!(filter.len(), 3);
+        assert_eq!(filter.max_offset(4), 0);
+        assert_eq!(filter.line_at(0, 4, 0), Some(Line(-20)));
+        assert_eq!(filter.line_at(2, 4, 0), Some(Line(5)));
+        assert_eq!(filter.line_at(3, 4, 0), None);
+        // Scrolling has nothing to reveal
+        assert_eq!(filter.line_at(0, 4, 7), Some(Line(-20)));
+    }
+
+    #[test]
+    fn test_scrolling_the_filtered_view() {
+        let filter = LineFilter::new([-40, -30, -20, -10, 0, 10]);
+        assert_eq!(filter.max_offset(4), 2);
+
+        // Newest matches at the bottom of the live view
+        assert_eq!(filter.line_at(0, 4, 0), Some(Line(-20)));
+        assert_eq!(filter.line_at(3, 4, 0), Some(Line(10)));
+        assert_eq!(filter.line_at(0, 4, 2), Some(Line(-40)));
+        assert_eq!(filter.line_at(0, 4, 9), Some(Line(-40)));
+
+        assert_eq!(filter.row_of(-20, 4, 0), Some(0));
+        assert_eq!(filter.row_of(-30, 4, 0), None);
+        assert_eq!(filter.row_of(-30, 4, 1), Some(0));
+        assert_eq!(filter.row_of(10, 4, 2), None);
+        assert_eq!(filter.row_of(-25, 4, 0), None);
+
+        assert_eq!(filter.view_line(-40, 4), Some(-2));
+        assert_eq!(filter.view_line(10, 4), Some(3));
+    }
+}
@@ -1,6 +1,8 @@
 /// Search functionality for terminal content
 mod engine;
+mod filter;
 mod state;
 
-pub use engine::SearchEngine;
-pub use state::{SearchDirection, SearchState};
+pub use engine::{SearchEngine, SearchOptions};
+pub use filter::LineFilter;
+pub use state::{SearchDirection, SearchState, MAX_HISTORY_MATCHES};
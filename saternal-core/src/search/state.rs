@@ -1,8 +1,54 @@
 /// Search state management
-use super::engine::SearchEngine;
+use super::engine::{SearchEngine, SearchOptions};
+use super::filter::LineFilter;
+use crate::selection::{SelectionMode, SelectionRange};
 use alacritty_terminal::grid::{Dimensions, Grid};
-use alacritty_terminal::index::Point;
+use alacritty_terminal::index::{Column, Line, Point};
 use alacritty_terminal::term::cell::Cell;
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// Cap on scrollback matches tracked for scrollbar markers and the line filter
+pub const MAX_HISTORY_MATCHES: usize = 10_000;
+
+/// The grid a line filter was built from
+///
+/// Output always reaches the screen before it scrolls into history, so an
+/// unchanged screen with the same history size means the same matching lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct GridStamp {
+    history_size: usize,
+    screen_lines: usize,
+    columns: usize,
+    screen_hash: u64,
+}
+
+impl GridStamp {
+    fn of(grid: &Grid<Cell>) -> Self {
+        let mut hasher = DefaultHasher::new();
+        for line in 0..grid.screen_lines() as i32 {
+            for col in 0..grid.columns() {
+                grid[Point::new(Line(line), Column(col))].c.hash(&mut hasher);
+            }
+        }
+        Self {
+            history_size: grid.history_size(),
+            screen_lines: grid.screen_lines(),
+            columns: grid.columns(),
+            screen_hash: hasher.finish(),
+        }
+    }
+}
+
+/// Line filter kept until the query or the grid changes
+struct CachedFilter {
+    stamp: GridStamp,
+    filter: LineFilter,
+    /// MAX_HISTORY_MATCHES was reached, so later lines are left out
+    truncated: bool,
+}
 
 /// Search direction
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -18,18 +64,46 @@ pub struct SearchState {
     engine: Option<SearchEngine>,
     current_match: Option<Point>,
     all_matches: Vec<Point>,
+    /// Matches across scrollback + screen (for scrollbar markers)
+    history_matches: Vec<Point>,
     direction: SearchDirection,
+    /// Pane the search was opened in
+    pane_id: Option<usize>,
+    /// Query is selected: next typed text replaces it (like a native find field)
+    query_selected: bool,
+    /// Last query used in each pane, restored on the next Cmd+F
+    last_queries: HashMap<usize, String>,
+    options: SearchOptions,
+    /// Set when the query is not a valid regex
+    error: Option<String>,
+    /// Show only the lines that match (Alt+F)
+    filter: bool,
+    /// Last line filter built, reused each frame until the query or the grid changes
+    filter_cache: RefCell<Option<CachedFilter>>,
 }
 
 impl SearchState {
     pub fn new() -> Self {
+        Self::with_options(SearchOptions::default())
+    }
+
+    /// Create search state with default matching options (from config)
+    pub fn with_options(options: SearchOptions) -> Self {
         Self {
             active: false,
             pattern: String::new(),
             engine: None,
             current_match: None,
             all_matches: Vec::new(),
+            history_matches: Vec::new(),
             direction: SearchDirection::Forward,
+            pane_id: None,
+            query_selected: false,
+            last_queries: HashMap::new(),
+            options,
+            error: None,
+            filter: false,
+            filter_cache: RefCell::new(None),
         }
     }
 
@@ -38,13 +112,83 @@ impl SearchState {
         self.active = true;
     }
 
+    /// Activate search for a pane (Cmd+F)
+    ///
+    /// Restores the last query used in that pane and selects it, so typing
+    /// replaces it while Enter searches again. Pressing Cmd+F while already
+    /// active just re-selects the current query.
+    pub fn activate_for_pane(&mut self, pane_id: usize, grid: &Grid<Cell>) {
+        if !self.active || self.pane_id != Some(pane_id) {
+            if self.active {
+                self.remember_query();
+            }
+            self.active = true;
+            self.pane_id = Some(pane_id);
+            let query = self.last_queries.get(&pane_id).cloned().unwrap_or_default();
+            self.update_pattern(&query, grid);
+        }
+        self.query_selected = !self.pattern.is_empty();
+    }
+
+    /// Type text into the query (replaces the query if it is selected)
+    pub fn insert_text(&mut self, text: &str, grid: &Grid<Cell>) {
+        let mut pattern = if self.query_selected {
+            String::new()
+        } else {
+            self.pattern.clone()
+        };
+        pattern.push_str(text);
+        self.query_selected = false;
+        self.update_pattern(&pattern, grid);
+    }
+
+    /// Delete the last character of the query (or the whole query if selected)
+    pub fn delete_backward(&mut self, grid: &Grid<Cell>) {
+        let mut pattern = self.pattern.clone();
+        if self.query_selected {
+            pattern.clear();
+        } else {
+            pattern.pop();
+        }
+        self.query_selected = false;
+        self.update_pattern(&pattern, grid);
+    }
+
+    /// Check if the query is selected (next keystroke replaces it)
+    pub fn is_query_selected(&self) -> bool {
+        self.query_selected
+    }
+
+    /// Pane the active search belongs to
+    pub fn pane_id(&self) -> Option<usize> {
+        self.pane_id
+    }
+
+    /// Store the current query as the pane's last query
+    fn remember_query(&mut self) {
+        if let Some(pane_id) = self.pane_id {
+            if self.pattern.is_empty() {
+                self.last_queries.remove(&pane_id);
+            } else {
+                self.last_queries.insert(pane_id, self.pattern.clone());
+            }
+        }
+    }
+
     /// Deactivate search mode
     pub fn deactivate(&mut self) {
+        self.remember_query();
+        self.pane_id = None;
+        self.query_selected = false;
         self.active = false;
         self.pattern.clear();
         self.engine = None;
         self.current_match = None;
         self.all_matches.clear();
+        self.history_matches.clear();
+        self.error = None;
+        self.filter = false;
+        *self.filter_cache.get_mut() = None;
     }
 
     /// Check if search is active
@@ -55,21 +199,114 @@ impl SearchState {
     /// Update search pattern
     pub fn update_pattern(&mut self, pattern: &str, grid: &Grid<Cell>) {
         self.pattern = pattern.to_string();
-        
+        *self.filter_cache.get_mut() = None;
+
+        self.error = None;
+
         if pattern.is_empty() {
             self.engine = None;
-            self.all_matches.clear();
-            self.current_match = None;
         } else {
-            self.engine = Some(SearchEngine::new(pattern));
+            match SearchEngine::with_options(pattern, self.options) {
+                Ok(engine) => self.engine = Some(engine),
+                Err(e) => {
+                    log::debug!("Invalid search regex: {}", e);
+                    self.error = Some("invalid regex".to_string());
+                    self.engine = None;
+                }
+            }
+        }
+
+        if self.engine.is_some() {
             self.refresh_matches(grid);
+        } else {
+            self.all_matches.clear();
+            self.history_matches.clear();
+            self.current_match = None;
+        }
+    }
+
+    /// Toggle regex matching (Alt+R)
+    pub fn toggle_regex(&mut self, grid: &Grid<Cell>) {
+        self.options.regex = !self.options.regex;
+        self.reapply(grid);
+    }
+
+    /// Toggle case-sensitive matching (Alt+C)
+    pub fn toggle_case_sensitive(&mut self, grid: &Grid<Cell>) {
+        self.options.case_sensitive = !self.options.case_sensitive;
+        self.reapply(grid);
+    }
+
+    /// Toggle whole-word matching (Alt+W)
+    pub fn toggle_whole_word(&mut self, grid: &Grid<Cell>) {
+        self.options.whole_word = !self.options.whole_word;
+        self.reapply(grid);
+    }
+
+    /// Re-run the current query after an option changed
+    fn reapply(&mut self, grid: &Grid<Cell>) {
+        let pattern = self.pattern.clone();
+        self.update_pattern(&pattern, grid);
+    }
+
+    /// Toggle filter mode (Alt+F): the pane shows only the lines that match
+    pub fn toggle_filter(&mut self) {
+        self.filter = !self.filter;
+        *self.filter_cache.get_mut() = None;
+    }
+
+    /// Check if filter mode is on
+    pub fn is_filtering(&self) -> bool {
+        self.filter
+    }
+
+    /// Lines matching the query across scrollback and screen, while filtering
+    ///
+    /// Built from the grid as it is now, so lines keep matching as output scrolls them
+    /// into history, and rebuilt only when the query or the grid changes. An empty
+    /// query (or an invalid regex) shows every line.
+    pub fn line_filter(&self, grid: &Grid<Cell>) -> Option<LineFilter> {
+        if !self.active || !self.filter {
+            return None;
+        }
+        let stamp = GridStamp::of(grid);
+        let mut cache = self.filter_cache.borrow_mut();
+        if let Some(cached) = cache.as_ref().filter(|cached| cached.stamp == stamp) {
+            return Some(cached.filter.clone());
         }
+        let (lines, truncated): (Vec<i32>, bool) = match &self.engine {
+            Some(engine) => {
+                let matches = engine.find_all_with_history(grid, MAX_HISTORY_MATCHES);
+                let truncated = matches.len() >= MAX_HISTORY_MATCHES;
+                (matches.into_iter().map(|point| point.line.0).collect(), truncated)
+            }
+            None => ((-(grid.history_size() as i32)..grid.screen_lines() as i32).collect(), false),
+        };
+        let filter = LineFilter::new(lines);
+        *cache = Some(CachedFilter { stamp, filter: filter.clone(), truncated });
+        Some(filter)
+    }
+
+    /// The last line filter stopped at MAX_HISTORY_MATCHES, so it leaves out matching lines
+    pub fn filter_truncated(&self) -> bool {
+        self.filter_cache.borrow().as_ref().is_some_and(|cached| cached.truncated)
+    }
+
+    /// Current matching options
+    pub fn options(&self) -> SearchOptions {
+        self.options
+    }
+
+    /// Error for the current query (e.g. invalid regex)
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
     }
 
     /// Refresh all matches in the grid
     fn refresh_matches(&mut self, grid: &Grid<Cell>) {
         if let Some(engine) = &self.engine {
             self.all_matches = engine.find_all(grid, 1000);  // Cap at 1000 matches
+            self.history_matches = engine.find_all_with_history(grid, MAX_HISTORY_MATCHES);
             
             // Set current match to first result
             if !self.all_matches.is_empty() {
@@ -161,6 +398,57 @@ impl SearchState {
         &self.all_matches
     }
 
+    /// Get all match positions including scrollback (negative lines are history)
+    pub fn history_matches(&self) -> &[Point] {
+        &self.history_matches
+    }
+
+    /// Cells covered by the current match (to select it)
+    pub fn current_match_range(&self, grid: &Grid<Cell>) -> Option<SelectionRange> {
+        self.match_range(grid, self.current_match?)
+    }
+
+    /// Cells covered by every match on screen (to select them all)
+    pub fn match_ranges(&self, grid: &Grid<Cell>) -> Vec<SelectionRange> {
+        self.all_matches.iter().filter_map(|&start| self.match_range(grid, start)).collect()
+    }
+
+    /// Matches on screen while scrolled `display_offset` lines up, in screen rows
+    /// (through `filter` when only matching lines are shown)
+    /// Returns the current match (if shown) and the others, for highlighting
+    pub fn visible_match_ranges(
+        &self,
+        grid: &Grid<Cell>,
+        display_offset: usize,
+        filter: Option<&LineFilter>,
+    ) -> (Option<SelectionRange>, Vec<SelectionRange>) {
+        let screen_lines = grid.screen_lines();
+        let row_of = |line: i32| match filter {
+            Some(filter) => filter.row_of(line, screen_lines, display_offset).map(|row| row as i32),
+            None => Some(line + display_offset as i32).filter(|row| (0..screen_lines as i32).contains(row)),
+        };
+        let mut current = None;
+        let mut others = Vec::new();
+        for &start in &self.history_matches {
+            let Some(row) = row_of(start.line.0) else { continue };
+            let Some(range) = self.match_range(grid, start) else { continue };
+            // Matches don't span lines, so the whole range moves to the start's row
+            let to_screen = |point: Point| Point::new(Line(row), point.column);
+            let range = SelectionRange::new(to_screen(range.start), to_screen(range.end), range.mode);
+            if Some(start) == self.current_match {
+                current = Some(range);
+            } else {
+                others.push(range);
+            }
+        }
+        (current, others)
+    }
+
+    fn match_range(&self, grid: &Grid<Cell>, start: Point) -> Option<SelectionRange> {
+        let end = self.engine.as_ref()?.match_end(grid, start)?;
+        Some(SelectionRange::new(start, end, SelectionMode::Normal))
+    }
+
     /// Get match count
     pub fn match_count(&self) -> usize {
         self.all_matches.len()
@@ -178,3 +466,154 @@ impl Default for SearchState {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alacritty_terminal::index::Column;
+
+    fn grid() -> Grid<Cell> {
+        Grid::new(4, 20, 0)
+    }
+
+    #[test]
+    fn test_query_remembered_per_pane() {
+        let grid = grid();
+        let mut state = SearchState::new();
+
+        state.activate_for_pane(1, &grid);
+        state.insert_text("foo", &grid);
+        state.deactivate();
+
+        state.activate_for_pane(2, &grid);
+        assert_eq!(state.pattern(), "");
+        state.insert_text("bar", &grid);
+        state.deactivate();
+
+        state.activate_for_pane(1, &grid);
+        assert_eq!(state.pattern(), "foo");
+        assert!(state.is_query_selected());
+    }
+
+    #[test]
+    fn test_typing_replaces_selected_query() {
+        let grid = grid();
+        let mut state = SearchState::new();
+
+        state.activate_for_pane(0, &grid);
+        state.insert_text("old", &grid);
+        state.deactivate();
+
+        state.activate_for_pane(0, &grid);
+        state.insert_text("n", &grid);
+        state.insert_text("ew", &grid);
+        assert_eq!(state.pattern(), "new");
+    }
+
+    #[test]
+    fn test_backspace_clears_selected_query() {
+        let grid = grid();
+        let mut state = SearchState::new();
+
+        state.activate_for_pane(0, &grid);
+        state.insert_text("abc", &grid);
+        state.delete_backward(&grid);
+        assert_eq!(state.pattern(), "ab");
+
+        // Cmd+F again selects the query; backspace then clears it
+        state.activate_for_pane(0, &grid);
+        state.delete_backward(&grid);
+        assert_eq!(state.pattern(), "");
+    }
+
+    #[test]
+    fn test_match_ranges() {
+        let mut grid = grid();
+        for (col, c) in "ab abc".chars().enumerate() {
+            grid[Point::new(Line(1), Column(col))].c = c;
+        }
+        let mut state = SearchState::new();
+        state.activate_for_pane(0, &grid);
+        state.insert_text("ab", &grid);
+
+        let range = |start, end| {
+            SelectionRange::new(Point::new(Line(1), Column(start)), Point::new(Line(1), Column(end)), SelectionMode::Normal)
+        };
+        assert_eq!(state.current_match_range(&grid), Some(range(0, 1)));
+        assert_eq!(state.match_ranges(&grid), vec![range(0, 1), range(3, 4)]);
+    }
+
+    #[test]
+    fn test_visible_match_ranges() {
+        let mut grid = grid();
+        for line in [1, 3] {
+            for (col, c) in "ab".chars().enumerate() {
+                grid[Point::new(Line(line), Column(col))].c = c;
+            }
+        }
+        let mut state = SearchState::new();
+        state.activate_for_pane(0, &grid);
+        state.insert_text("ab", &grid);
+
+        let range = |line| {
+            SelectionRange::new(Point::new(Line(line), Column(0)), Point::new(Line(line), Column(1)), SelectionMode::Normal)
+        };
+        assert_eq!(state.visible_match_ranges(&grid, 0, None), (Some(range(1)), vec![range(3)]));
+
+        // Scrolled up a line: the bottom match falls off the screen
+        assert_eq!(state.visible_match_ranges(&grid, 1, None), (Some(range(2)), vec![]));
+
+        // Filtered: the matching lines are the top rows
+        let filter = state.line_filter(&grid);
+        assert_eq!(filter, None);
+        state.toggle_filter();
+        let filter = state.line_filter(&grid).unwrap();
+        assert_eq!(filter, LineFilter::new([1, 3]));
+        assert_eq!(state.visible_match_ranges(&grid, 0, Some(&filter)), (Some(range(0)), vec![range(1)]));
+
+        // New output on screen is filtered too; a changed query rebuilds the filter
+        grid[Point::new(Line(0), Column(5))].c = 'a';
+        grid[Point::new(Line(0), Column(6))].c = 'b';
+        assert_eq!(state.line_filter(&grid).unwrap(), LineFilter::new([0, 1, 3]));
+        state.insert_text("x", &grid);
+        assert!(state.line_filter(&grid).unwrap().is_empty());
+        assert!(!state.filter_truncated());
+
+        state.deactivate();
+        assert!(!state.is_filtering());
+    }
+
+    #[test]
+    fn test_filter_reports_the_match_cap() {
+        let columns = MAX_HISTORY_MATCHES / 4;
+        let mut grid: Grid<Cell> = Grid::new(4, columns, 0);
+        for line in 0..4 {
+            for col in 0..columns {
+                grid[Point::new(Line(line), Column(col))].c = 'a';
+            }
+        }
+        let mut state = SearchState::new();
+        state.activate_for_pane(0, &grid);
+        state.insert_text("a", &grid);
+        state.toggle_filter();
+        assert!(state.line_filter(&grid).is_some());
+        assert!(state.filter_truncated());
+    }
+
+    #[test]
+    fn test_invalid_regex_reports_error_until_fixed() {
+        let grid = grid();
+        let mut state = SearchState::new();
+
+        state.activate_for_pane(0, &grid);
+        state.insert_text("a(", &grid);
+        assert_eq!(state.error(), None);
+
+        state.toggle_regex(&grid);
+        assert!(state.options().regex);
+        assert!(state.error().is_some());
+
+        state.insert_text(")", &grid);
+        assert_eq!(state.error(), None);
+    }
+}
@@ -0,0 +1,112 @@
+/// Password prompt detection and paste protection
+///
+/// A program reading a password turns echo off on the PTY but keeps
+/// line-buffered (canonical) input - sudo, ssh, `read -s`. Full-screen
+/// programs and line editors turn both off, so they don't count.
+/// Pasting several lines there would send the first as the password and run
+/// the rest as commands, so a multi-line paste first prints a warning and
+/// only goes through when repeated within PASTE_CONFIRM_WINDOW.
+#[cfg(unix)]
+use std::os::unix::io::RawFd;
+use std::time::{Duration, Instant};
+
+/// How long a warned paste stays armed
+pub const PASTE_CONFIRM_WINDOW: Duration = Duration::from_secs(3);
+
+/// The terminal behind `pty_fd` is reading input without echoing it
+#[cfg(unix)]
+pub fn is_password_prompt(pty_fd: RawFd) -> bool {
+    let mut termios: libc::termios = unsafe { std::mem::zeroed() };
+    if unsafe { libc::tcgetattr(pty_fd, &mut termios) } != 0 {
+        return false;
+    }
+    termios.c_lflag & libc::ECHO == 0 && termios.c_lflag & libc::ICANON != 0
+}
+
+/// Text spans more than one line (a trailing newline alone doesn't count)
+pub fn is_multiline(text: &str) -> bool {
+    text.lines().nth(1).is_some()
+}
+
+/// Warning printed when a paste into a password prompt is held back
+pub fn paste_warning(text: &str) -> String {
+    format!(
+        "Paste of {} lines into a password prompt held back. Paste again within {}s to send it anyway.",
+        text.lines().count(),
+        PASTE_CONFIRM_WINDOW.as_secs()
+    )
+}
+
+#[derive(Debug, Default)]
+pub struct PasteGuard {
+    armed: Option<Instant>,
+}
+
+impl PasteGuard {
+    /// Whether a paste can go ahead: it isn't risky, or it repeats a warned
+    /// paste in time. Otherwise arms the guard.
+    pub fn check(&mut self, risky: bool, now: Instant) -> bool {
+        if !risky {
+            return true;
+        }
+        if let Some(since) = self.armed.take() {
+            if now.duration_since(since) <= PASTE_CONFIRM_WINDOW {
+                return true;
+            }
+        }
+        self.armed = Some(now);
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multiline() {
+        assert!(!is_multiline("hunter2"));
+        assert!(!is_multiline("hunter2\n"));
+        assert!(is_multiline("hunter2\nrm -rf build"));
+    }
+
+    #[test]
+    fn test_risky_paste_needs_a_second_request() {
+        let mut guard = PasteGuard::default();
+        let now = Instant::now();
+
+        assert!(guard.check(false, now));
+        assert!(!guard.check(true, now));
+        assert!(guard.check(true, now + Duration::from_secs(1)));
+        assert!(!guard.check(true, now + Duration::from_secs(2)));
+        assert!(!guard.check(true, now + Duration::from_secs(10)));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_detects_echo_off() {
+        let (mut master, mut slave) = (0, 0);
+        let opened = unsafe {
+            libc::openpty(&mut master, &mut slave, std::ptr::null_mut(), std::ptr::null(), std::ptr::null())
+        };
+        assert_eq!(opened, 0);
+        assert!(!is_password_prompt(master));
+
+        let mut termios: libc::termios = unsafe { std::mem::zeroed() };
+        unsafe {
+            libc::tcgetattr(slave, &mut termios);
+            termios.c_lflag &= !libc::ECHO;
+            termios.c_lflag |= libc::ICANON;
+            libc::tcsetattr(slave, libc::TCSANOW, &termios);
+        }
+        assert!(is_password_prompt(master));
+
+        unsafe {
+            termios.c_lflag &= !libc::ICANON;
+            libc::tcsetattr(slave, libc::TCSANOW, &termios);
+            libc::close(slave);
+        }
+        assert!(!is_password_prompt(master));
+        unsafe { libc::close(master) };
+    }
+}
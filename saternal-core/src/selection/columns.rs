@@ -0,0 +1,96 @@
+/// Column detection for table-like output (ps, ls -l, docker ps)
+///
+/// Rows keep their screen alignment, so a column boundary is any run of
+/// cell positions that is blank on every row. Header rows take part like
+/// any other row, which keeps right-aligned headers (`  PID`) with their
+/// values.
+use std::ops::Range;
+
+/// Selected rows split into columns
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextColumns {
+    rows: Vec<Vec<char>>,
+    spans: Vec<Range<usize>>,
+}
+
+impl TextColumns {
+    /// Find columns in screen-aligned rows; None unless there are at least two
+    pub fn detect(rows: &[String]) -> Option<Self> {
+        let rows: Vec<Vec<char>> = rows.iter().map(|row| row.chars().collect()).collect();
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let occupied: Vec<bool> = (0..width)
+            .map(|i| rows.iter().any(|row| row.get(i).is_some_and(|c| !c.is_whitespace())))
+            .collect();
+
+        let mut spans = Vec::new();
+        let mut start = None;
+        for (i, &filled) in occupied.iter().chain(std::iter::once(&false)).enumerate() {
+            match (start, filled) {
+                (None, true) => start = Some(i),
+                (Some(s), false) => {
+                    spans.push(s..i);
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        (spans.len() >= 2).then_some(Self { rows, spans })
+    }
+
+    pub fn len(&self) -> usize {
+        self.spans.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    /// First non-blank value in a column, used to label it (usually the header)
+    pub fn label(&self, index: usize) -> String {
+        self.cells(index).find(|cell| !cell.is_empty()).unwrap_or_default()
+    }
+
+    /// One column's values, one per row, with alignment padding trimmed
+    pub fn column(&self, index: usize) -> String {
+        self.cells(index).collect::<Vec<_>>().join("\n")
+    }
+
+    fn cells(&self, index: usize) -> impl Iterator<Item = String> + '_ {
+        let span = self.spans.get(index).cloned().unwrap_or_default();
+        self.rows.iter().map(move |row| {
+            let end = span.end.min(row.len());
+            let start = span.start.min(end);
+            row[start..end].iter().collect::<String>().trim().to_string()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_detects_aligned_columns() {
+        let columns = TextColumns::detect(&rows(&[
+            "  PID TTY      CMD",
+            "  812 ttys000  -zsh",
+            "12345 ttys001  vim",
+        ]))
+        .unwrap();
+
+        assert_eq!(columns.len(), 3);
+        assert_eq!(columns.label(0), "PID");
+        assert_eq!(columns.column(0), "PID\n812\n12345");
+        assert_eq!(columns.column(2), "CMD\n-zsh\nvim");
+    }
+
+    #[test]
+    fn test_single_column_is_not_a_table() {
+        assert!(TextColumns::detect(&rows(&["hello", "world"])).is_none());
+        assert!(TextColumns::detect(&[]).is_none());
+    }
+}
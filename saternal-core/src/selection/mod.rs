@@ -1,33 +1,69 @@
 /// Text selection module - mouse and keyboard selection support
+mod columns;
 mod range;
 mod smart;
-pub mod renderer;
+mod viewport;
 
+pub use columns::TextColumns;
 pub use range::{SelectionMode, SelectionRange};
-pub use renderer::{SelectionRenderer, PaneViewport, calculate_pane_viewports};
+pub use smart::DEFAULT_WORD_CHARS;
+pub use viewport::{PaneViewport, calculate_pane_viewports, pane_at};
 
 use alacritty_terminal::grid::{Dimensions, Grid};
-use alacritty_terminal::index::Point;
-use alacritty_terminal::term::cell::Cell;
+use alacritty_terminal::index::{Column, Line, Point};
+use alacritty_terminal::term::cell::{Cell, Flags};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// How selected cells are drawn
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SelectionStyle {
+    /// Tint the cells with the palette's selection_bg
+    #[default]
+    Highlight,
+    /// Swap each cell's text and background colors
+    Invert,
+}
 
 /// Selection manager handling user interactions
 pub struct SelectionManager {
     range: Option<SelectionRange>,
+    /// Further disjoint ranges selected along with `range` (e.g. all search matches)
+    extra_ranges: Vec<SelectionRange>,
     active: bool,
+    /// The word or line a double- or triple-click picked; dragging extends from it
+    /// in whole words or lines (its mode is the granularity)
+    anchor: Option<SelectionRange>,
+    /// Per pane, what the selection's lines were last counted against
+    scroll_bases: HashMap<usize, ScrollBase>,
+    /// Non-alphanumeric characters that double-click keeps inside a word
+    word_chars: String,
 }
 
 impl SelectionManager {
     pub fn new() -> Self {
         Self {
             range: None,
+            extra_ranges: Vec::new(),
             active: false,
+            anchor: None,
+            scroll_bases: HashMap::new(),
+            word_chars: DEFAULT_WORD_CHARS.to_string(),
         }
     }
 
+    /// Set the characters double-click treats as part of a word
+    pub fn set_word_chars(&mut self, word_chars: &str) {
+        self.word_chars = word_chars.to_string();
+    }
+
     /// Start a new selection
     pub fn start(&mut self, point: Point, mode: SelectionMode) {
         self.range = Some(SelectionRange::new(point, point, mode));
+        self.extra_ranges.clear();
         self.active = true;
+        self.anchor = None;
     }
 
     /// Update selection end point
@@ -37,6 +73,90 @@ impl SelectionManager {
         }
     }
 
+    /// Drag the selection to `point`, by whole words or lines after a double- or triple-click
+    /// The clicked word or line stays selected whichever way the drag goes
+    pub fn extend(&mut self, grid: &Grid<Cell>, point: Point) {
+        let Some(anchor) = self.anchor else {
+            self.update(point);
+            return;
+        };
+        let unit = match anchor.mode {
+            SelectionMode::Word => smart::expand_word(grid, point, &self.word_chars),
+            SelectionMode::Line => Some(smart::expand_line(grid, point)),
+            SelectionMode::Normal => None,
+        };
+        let (unit_start, unit_end) = unit.map_or((point, point), |unit| unit.normalized());
+        let (anchor_start, anchor_end) = anchor.normalized();
+        // Backward drags keep the anchor's end and reach the start of the word or line under the pointer
+        let (start, end) = if unit_start < anchor_start {
+            (anchor_end, unit_start)
+        } else {
+            (anchor_start, unit_end.max(anchor_end))
+        };
+        self.range = Some(SelectionRange::new(start, end, anchor.mode));
+    }
+
+    /// Keep the selection on the same text while output scrolls it up into the history
+    ///
+    /// `grid` belongs to `pane_id`, the pane the selection is in, and keeps at most
+    /// `history_limit` lines of scrollback. Each pane's history is counted on its own,
+    /// so moving focus away and back doesn't lose track. A shrinking history means it
+    /// was cleared, which drops the selection. Once the scrollback is full, lines
+    /// rotate out without the history growing; the selection's first line is then
+    /// looked up by its text, and the selection is dropped if it's gone.
+    /// Returns true if the selection moved or was dropped
+    pub fn follow_scroll(&mut self, pane_id: usize, grid: &Grid<Cell>, history_limit: usize) -> bool {
+        let history_size = grid.history_size();
+        let base = self.scroll_bases.remove(&pane_id);
+        let scrolled = match base.filter(|_| self.range.is_some()) {
+            Some(base) => history_size.checked_sub(base.history_size).and_then(|grown| {
+                let rotated = if history_size >= history_limit { self.rotated_lines(grid, &base, grown)? } else { 0 };
+                Some(grown + rotated)
+            }),
+            None => Some(0),
+        };
+        let changed = match scrolled {
+            Some(0) => false,
+            Some(scrolled) => {
+                let lines = scrolled as i32;
+                for range in self.range.iter_mut().chain(&mut self.extra_ranges).chain(&mut self.anchor) {
+                    range.scroll_up(lines);
+                }
+                true
+            }
+            None => {
+                self.clear();
+                true
+            }
+        };
+        let first_line = self.range.map(|range| {
+            let line = range.normalized().0.line;
+            (line, row_text(grid, line))
+        });
+        self.scroll_bases.insert(pane_id, ScrollBase { history_size, first_line });
+        changed
+    }
+
+    /// How many lines the selection's first line rotated up on top of `grown`, found
+    /// by its text; None once it rotated out of the scrollback or was overwritten
+    fn rotated_lines(&self, grid: &Grid<Cell>, base: &ScrollBase, grown: usize) -> Option<usize> {
+        let line = self.range?.normalized().0.line;
+        match &base.first_line {
+            // A blank line would match anywhere
+            Some((base_line, text)) if *base_line == line && !text.is_empty() => {
+                let moved = line - grown as i32;
+                let top = Line(-(grid.history_size() as i32));
+                (0..)
+                    .map(|rotated| (rotated, moved - rotated as i32))
+                    .take_while(|(_, line)| *line >= top)
+                    .find(|(_, line)| row_text(grid, *line) == *text)
+                    .map(|(rotated, _)| rotated)
+            }
+            // The selection changed since the base was taken: nothing to compare
+            _ => Some(0),
+        }
+    }
+
     /// Finalize selection and return selected text
     pub fn finalize(&mut self, grid: &Grid<Cell>) -> Option<String> {
         self.active = false;
@@ -46,7 +166,9 @@ impl SelectionManager {
     /// Clear selection
     pub fn clear(&mut self) {
         self.range = None;
+        self.extra_ranges.clear();
         self.active = false;
+        self.anchor = None;
     }
 
     /// Get current selection range
@@ -54,65 +176,148 @@ impl SelectionManager {
         self.range
     }
 
+    /// Select several disjoint ranges at once (the first becomes the primary range)
+    pub fn select_ranges(&mut self, ranges: Vec<SelectionRange>) {
+        let mut ranges = ranges.into_iter();
+        self.range = ranges.next();
+        self.extra_ranges = ranges.collect();
+        self.active = false;
+        self.anchor = None;
+    }
+
+    /// Every selected range, primary first
+    pub fn ranges(&self) -> Vec<SelectionRange> {
+        self.range.into_iter().chain(self.extra_ranges.iter().copied()).collect()
+    }
+
     /// Check if selection is active
     pub fn is_active(&self) -> bool {
         self.active
     }
 
     /// Get selected text from grid
+    /// Soft-wrapped rows are joined without a newline; trailing padding is trimmed.
+    /// Several disjoint ranges are copied one per line, top to bottom.
     pub fn get_text(&self, grid: &Grid<Cell>) -> Option<String> {
+        let mut ranges = self.ranges();
+        if ranges.is_empty() {
+            return None;
+        }
+        ranges.sort_by_key(|range| range.normalized().0);
+        let texts: Vec<String> = ranges.iter().map(|range| range_text(grid, range)).collect();
+        Some(texts.join("\n"))
+    }
+
+    /// Split the primary selection into table columns (see TextColumns)
+    /// Rows keep their screen alignment: cells outside the selection read as blanks
+    pub fn columns(&self, grid: &Grid<Cell>) -> Option<TextColumns> {
         let range = self.range?;
         let (start, end) = range.normalized();
-        
-        let mut text = String::new();
-        let max_col = grid.columns().saturating_sub(1);
         let max_line = (grid.screen_lines() as i32).saturating_sub(1);
-        
-        // Clamp line indices to valid range
-        let start_line = start.line.0.max(0).min(max_line);
-        let end_line = end.line.0.max(0).min(max_line);
-        
-        for line in start_line..=end_line {
-            let line_start = if line == start_line { 
-                start.column.0.min(max_col) 
-            } else { 
-                0 
-            };
-            let line_end = if line == end_line { 
-                end.column.0.min(max_col) 
-            } else { 
-                max_col 
-            };
-            
-            for col in line_start..=line_end {
-                let point = Point::new(alacritty_terminal::index::Line(line), alacritty_terminal::index::Column(col));
-                let cell = &grid[point];
-                text.push(cell.c);
-            }
-            
-            // Add newline between lines (except for last line)
-            if line < end_line {
-                text.push('\n');
-            }
-        }
-        
-        Some(text)
+        let min_line = -(grid.history_size() as i32);
+        let rows: Vec<String> = (start.line.0.max(min_line)..=end.line.0.min(max_line))
+            .map(|line| {
+                (0..grid.columns())
+                    .map(|col| {
+                        let point = Point::new(alacritty_terminal::index::Line(line), alacritty_terminal::index::Column(col));
+                        match grid[point].c {
+                            c if range.contains(point) && c != '\0' => c,
+                            _ => ' ',
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        TextColumns::detect(&rows)
     }
 
-    /// Expand selection to word boundaries (double-click)
+    /// Expand selection to word boundaries (double-click); a URL is taken up to whitespace
+    /// Stays active so a drag extends it word by word
     pub fn expand_word(&mut self, grid: &Grid<Cell>, point: Point) {
-        if let Some(range) = smart::expand_word(grid, point) {
-            self.range = Some(range);
-            self.active = false;  // Finalized
+        if let Some(range) = smart::expand_url(grid, point, &self.word_chars) {
+            self.select_anchor(range);
         }
     }
 
     /// Expand selection to line boundaries (triple-click)
+    /// Stays active so a drag extends it line by line
     pub fn expand_line(&mut self, grid: &Grid<Cell>, point: Point) {
-        let range = smart::expand_line(grid, point);
+        self.select_anchor(smart::expand_line(grid, point));
+    }
+
+    fn select_anchor(&mut self, range: SelectionRange) {
         self.range = Some(range);
-        self.active = false;  // Finalized
+        self.extra_ranges.clear();
+        self.active = true;  // Finalized on release
+        self.anchor = Some(range);
+    }
+}
+
+/// What a pane's history looked like when the selection was last moved along with it
+struct ScrollBase {
+    history_size: usize,
+    /// The selection's first line and its text then
+    first_line: Option<(Line, String)>,
+}
+
+/// Text of a whole row, without trailing blanks (empty for lines outside the grid)
+fn row_text(grid: &Grid<Cell>, line: Line) -> String {
+    if line < Line(-(grid.history_size() as i32)) || line >= Line(grid.screen_lines() as i32) {
+        return String::new();
+    }
+    let text: String = (0..grid.columns()).map(|col| grid[Point::new(line, Column(col))].c).collect();
+    text.trim_end_matches([' ', '\0']).to_string()
+}
+
+/// Text of one range; see SelectionManager::get_text
+fn range_text(grid: &Grid<Cell>, range: &SelectionRange) -> String {
+    let (start, end) = range.normalized();
+    
+    let mut text = String::new();
+    let max_col = grid.columns().saturating_sub(1);
+    let max_line = (grid.screen_lines() as i32).saturating_sub(1);
+    // Lines output scrolled into the history are still there to copy
+    let min_line = -(grid.history_size() as i32);
+    
+    // Clamp line indices to valid range
+    let start_line = start.line.0.max(min_line).min(max_line);
+    let end_line = end.line.0.max(min_line).min(max_line);
+    
+    for line in start_line..=end_line {
+        let line_start = if line == start_line { 
+            start.column.0.min(max_col) 
+        } else { 
+            0 
+        };
+        let line_end = if line == end_line { 
+            end.column.0.min(max_col) 
+        } else { 
+            max_col 
+        };
+        
+        let row_start = text.len();
+        for col in line_start..=line_end {
+            let point = Point::new(alacritty_terminal::index::Line(line), alacritty_terminal::index::Column(col));
+            let cell = &grid[point];
+            text.push(cell.c);
+        }
+
+        // A row that wrapped continues the same logical line
+        let last_cell = Point::new(alacritty_terminal::index::Line(line), alacritty_terminal::index::Column(max_col));
+        let wrapped = line_end == max_col && grid[last_cell].flags.contains(Flags::WRAPLINE);
+        if !wrapped {
+            // Only this row's padding: a blank row must keep the newline before it
+            let row_len = text[row_start..].trim_end().len();
+            text.truncate(row_start + row_len);
+        }
+
+        // Add newline between lines (except for last line)
+        if line < end_line && !wrapped {
+            text.push('\n');
+        }
     }
+    
+    text
 }
 
 impl Default for SelectionManager {
@@ -120,3 +325,180 @@ impl Default for SelectionManager {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alacritty_terminal::index::{Column, Line};
+
+    fn write_row(grid: &mut Grid<Cell>, line: i32, text: &str) {
+        for (col, c) in text.chars().enumerate() {
+            grid[Point::new(Line(line), Column(col))].c = c;
+        }
+    }
+
+    #[test]
+    fn test_get_text_joins_wrapped_rows_and_trims_padding() {
+        let mut grid: Grid<Cell> = Grid::new(3, 6, 0);
+        write_row(&mut grid, 0, "abcdef");
+        grid[Point::new(Line(0), Column(5))].flags.insert(Flags::WRAPLINE);
+        write_row(&mut grid, 1, "gh");
+        write_row(&mut grid, 2, "ij  ");
+
+        let mut selection = SelectionManager::new();
+        selection.start(Point::new(Line(0), Column(0)), SelectionMode::Normal);
+        selection.update(Point::new(Line(2), Column(5)));
+        assert_eq!(selection.get_text(&grid).as_deref(), Some("abcdefgh\nij"));
+    }
+
+    #[test]
+    fn test_get_text_keeps_blank_rows() {
+        let mut grid: Grid<Cell> = Grid::new(4, 6, 0);
+        write_row(&mut grid, 0, "ab  ");
+        write_row(&mut grid, 3, "cd");
+
+        let mut selection = SelectionManager::new();
+        selection.start(Point::new(Line(0), Column(0)), SelectionMode::Normal);
+        selection.update(Point::new(Line(3), Column(5)));
+        assert_eq!(selection.get_text(&grid).as_deref(), Some("ab\n\n\ncd"));
+    }
+
+    #[test]
+    fn test_get_text_of_disjoint_ranges_in_screen_order() {
+        let mut grid: Grid<Cell> = Grid::new(3, 8, 0);
+        write_row(&mut grid, 0, "id=7 x");
+        write_row(&mut grid, 2, "id=12");
+        let range = |line, start, end| {
+            SelectionRange::new(Point::new(Line(line), Column(start)), Point::new(Line(line), Column(end)), SelectionMode::Normal)
+        };
+
+        let mut selection = SelectionManager::new();
+        selection.select_ranges(vec![range(2, 0, 4), range(0, 0, 3)]);
+        assert_eq!(selection.ranges().len(), 2);
+        assert_eq!(selection.get_text(&grid).as_deref(), Some("id=7\nid=12"));
+
+        selection.start(Point::new(Line(1), Column(0)), SelectionMode::Normal);
+        assert_eq!(selection.ranges().len(), 1);
+    }
+
+    #[test]
+    fn test_drag_after_double_click_extends_by_words() {
+        let mut grid: Grid<Cell> = Grid::new(2, 20, 0);
+        write_row(&mut grid, 0, "one two three four");
+
+        let mut selection = SelectionManager::new();
+        selection.expand_word(&grid, Point::new(Line(0), Column(5)));
+        assert!(selection.is_active());
+        assert_eq!(selection.get_text(&grid).as_deref(), Some("two"));
+
+        // Forward into "three" takes all of it
+        selection.extend(&grid, Point::new(Line(0), Column(9)));
+        assert_eq!(selection.get_text(&grid).as_deref(), Some("two three"));
+
+        // Backward into "one" keeps "two" and takes all of "one"
+        selection.extend(&grid, Point::new(Line(0), Column(1)));
+        assert_eq!(selection.get_text(&grid).as_deref(), Some("one two"));
+
+        // Back inside the clicked word: just the word
+        selection.extend(&grid, Point::new(Line(0), Column(4)));
+        assert_eq!(selection.get_text(&grid).as_deref(), Some("two"));
+
+        // A URL is taken whole, past characters outside word_chars
+        write_row(&mut grid, 1, "go https://a.io?q=1");
+        selection.expand_word(&grid, Point::new(Line(1), Column(5)));
+        assert_eq!(selection.get_text(&grid).as_deref(), Some("https://a.io?q=1"));
+    }
+
+    #[test]
+    fn test_drag_after_triple_click_extends_by_lines() {
+        let mut grid: Grid<Cell> = Grid::new(3, 10, 0);
+        write_row(&mut grid, 0, "first");
+        write_row(&mut grid, 1, "second");
+        write_row(&mut grid, 2, "third");
+
+        let mut selection = SelectionManager::new();
+        selection.expand_line(&grid, Point::new(Line(1), Column(3)));
+        selection.extend(&grid, Point::new(Line(2), Column(1)));
+        assert_eq!(selection.get_text(&grid).as_deref(), Some("second\nthird"));
+        selection.extend(&grid, Point::new(Line(0), Column(8)));
+        assert_eq!(selection.get_text(&grid).as_deref(), Some("first\nsecond"));
+
+        // A plain click drops the granularity
+        selection.start(Point::new(Line(0), Column(0)), SelectionMode::Normal);
+        selection.extend(&grid, Point::new(Line(0), Column(2)));
+        assert_eq!(selection.get_text(&grid).as_deref(), Some("fir"));
+    }
+
+    #[test]
+    fn test_selection_follows_output_scroll() {
+        let mut grid: Grid<Cell> = Grid::new(3, 6, 10);
+        write_row(&mut grid, 2, "keep");
+
+        let mut selection = SelectionManager::new();
+        selection.follow_scroll(0, &grid, 10);
+        selection.start(Point::new(Line(2), Column(0)), SelectionMode::Normal);
+        selection.update(Point::new(Line(2), Column(3)));
+
+        // Two lines of output push the selected row up into the history
+        grid.scroll_up(&(Line(0)..Line(3)), 2);
+        assert!(selection.follow_scroll(0, &grid, 10));
+        assert_eq!(selection.range().unwrap().start.line, Line(0));
+        grid.scroll_up(&(Line(0)..Line(3)), 1);
+        assert!(selection.follow_scroll(0, &grid, 10));
+        assert_eq!(selection.range().unwrap().start.line, Line(-1));
+        assert_eq!(selection.get_text(&grid).as_deref(), Some("keep"));
+        assert!(!selection.follow_scroll(0, &grid, 10));
+
+        // Another pane has a base of its own; output meanwhile is caught up on return
+        let other: Grid<Cell> = Grid::new(3, 6, 10);
+        assert!(!selection.follow_scroll(1, &other, 10));
+        grid.scroll_up(&(Line(0)..Line(3)), 1);
+        assert!(selection.follow_scroll(0, &grid, 10));
+        assert_eq!(selection.get_text(&grid).as_deref(), Some("keep"));
+
+        // Cleared history drops the selection
+        grid.clear_history();
+        assert!(selection.follow_scroll(0, &grid, 10));
+        assert!(selection.range().is_none());
+    }
+
+    #[test]
+    fn test_selection_follows_rotation_in_full_scrollback() {
+        let mut grid: Grid<Cell> = Grid::new(3, 6, 2);
+        let mut selection = SelectionManager::new();
+        grid.scroll_up(&(Line(0)..Line(3)), 2);
+        write_row(&mut grid, 2, "keep");
+        selection.start(Point::new(Line(2), Column(0)), SelectionMode::Normal);
+        selection.update(Point::new(Line(2), Column(3)));
+        selection.follow_scroll(0, &grid, 2);
+
+        // The history is full: it stays at two lines while the row moves up
+        grid.scroll_up(&(Line(0)..Line(3)), 3);
+        assert_eq!(grid.history_size(), 2);
+        assert!(selection.follow_scroll(0, &grid, 2));
+        assert_eq!(selection.range().unwrap().start.line, Line(-1));
+        assert_eq!(selection.get_text(&grid).as_deref(), Some("keep"));
+
+        // Rotated out of the scrollback altogether
+        grid.scroll_up(&(Line(0)..Line(3)), 2);
+        assert!(selection.follow_scroll(0, &grid, 2));
+        assert!(selection.range().is_none());
+    }
+
+    #[test]
+    fn test_columns_keep_screen_alignment() {
+        let mut grid: Grid<Cell> = Grid::new(3, 10, 0);
+        write_row(&mut grid, 0, "a   1  x");
+        write_row(&mut grid, 1, "bb  22 y");
+        write_row(&mut grid, 2, "ccc 3  z");
+
+        // Starting mid-row leaves the first row's leading cells blank
+        let mut selection = SelectionManager::new();
+        selection.start(Point::new(Line(0), Column(4)), SelectionMode::Normal);
+        selection.update(Point::new(Line(2), Column(9)));
+        let columns = selection.columns(&grid).unwrap();
+        assert_eq!(columns.len(), 3);
+        assert_eq!(columns.column(0), "\nbb\nccc");
+        assert_eq!(columns.column(1), "1\n22\n3");
+    }
+}
@@ -66,6 +66,12 @@ impl SelectionRange {
     pub fn update_end(&mut self, end: Point) {
         self.end = end;
     }
+
+    /// Move the range `lines` lines up (into the scrollback when it goes past the top)
+    pub fn scroll_up(&mut self, lines: i32) {
+        self.start.line -= lines;
+        self.end.line -= lines;
+    }
 }
 
 #[cfg(test)]
@@ -4,8 +4,12 @@ use alacritty_terminal::index::{Column, Line, Point};
 use alacritty_terminal::term::cell::Cell;
 use super::range::{SelectionRange, SelectionMode};
 
+/// Characters besides alphanumerics that double-click treats as part of a word
+pub const DEFAULT_WORD_CHARS: &str = "_-./:";
+
 /// Expand selection to include the word at the given point
-pub fn expand_word(grid: &Grid<Cell>, point: Point) -> Option<SelectionRange> {
+/// `word_chars` lists the non-alphanumeric characters that belong to words
+pub fn expand_word(grid: &Grid<Cell>, point: Point, word_chars: &str) -> Option<SelectionRange> {
     let num_cols = grid.columns();
     let num_lines = grid.screen_lines();
     
@@ -24,7 +28,7 @@ pub fn expand_word(grid: &Grid<Cell>, point: Point) -> Option<SelectionRange> {
     while start_col > 0 {
         let p = Point::new(line_index, Column(start_col - 1));
         let cell = &grid[p];
-        if is_word_char(cell.c) {
+        if is_word_char(cell.c, word_chars) {
             start_col -= 1;
         } else {
             break;
@@ -35,7 +39,7 @@ pub fn expand_word(grid: &Grid<Cell>, point: Point) -> Option<SelectionRange> {
     while end_col < num_cols - 1 {
         let p = Point::new(line_index, Column(end_col + 1));
         let cell = &grid[p];
-        if is_word_char(cell.c) {
+        if is_word_char(cell.c, word_chars) {
             end_col += 1;
         } else {
             break;
@@ -60,16 +64,16 @@ pub fn expand_line(grid: &Grid<Cell>, point: Point) -> SelectionRange {
     )
 }
 
-/// Check if character is part of a word (alphanumeric, underscore, hyphen)
+/// Check if character is part of a word (alphanumeric or one of `word_chars`)
 #[inline]
-fn is_word_char(c: char) -> bool {
-    c.is_alphanumeric() || c == '_' || c == '-' || c == '.' || c == '/' || c == ':'
+fn is_word_char(c: char, word_chars: &str) -> bool {
+    c.is_alphanumeric() || word_chars.contains(c)
 }
 
 /// Detect if selection looks like a URL and expand accordingly
-pub fn expand_url(grid: &Grid<Cell>, point: Point) -> Option<SelectionRange> {
-    // First expand as word
-    let mut range = expand_word(grid, point)?;
+pub fn expand_url(grid: &Grid<Cell>, point: Point, word_chars: &str) -> Option<SelectionRange> {
+    // First expand as word, with the same boundaries as double-click
+    let mut range = expand_word(grid, point, word_chars)?;
     
     // Check if it contains URL-like patterns
     let text = extract_text(grid, range);
@@ -145,3 +149,39 @@ fn extract_text(grid: &Grid<Cell>, range: SelectionRange) -> String {
     
     text
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alacritty_terminal::index::Line;
+
+    fn grid_with(text: &str) -> Grid<Cell> {
+        let mut grid: Grid<Cell> = Grid::new(1, 40, 0);
+        for (col, c) in text.chars().enumerate() {
+            grid[Point::new(Line(0), Column(col))].c = c;
+        }
+        grid
+    }
+
+    #[test]
+    fn test_word_chars_control_boundaries() {
+        let grid = grid_with("cat ~/src/main.rs now");
+        let point = Point::new(Line(0), Column(8));
+
+        let range = expand_word(&grid, point, DEFAULT_WORD_CHARS).unwrap();
+        assert_eq!(extract_text(&grid, range), "/src/main.rs");
+
+        let range = expand_word(&grid, point, "~/.").unwrap();
+        assert_eq!(extract_text(&grid, range), "~/src/main.rs");
+
+        let range = expand_word(&grid, point, "").unwrap();
+        assert_eq!(extract_text(&grid, range), "src");
+    }
+
+    #[test]
+    fn test_url_expands_past_word_chars() {
+        let grid = grid_with("see https://a.io/x?q=1 ok");
+        let range = expand_url(&grid, Point::new(Line(0), Column(6)), DEFAULT_WORD_CHARS).unwrap();
+        assert_eq!(extract_text(&grid, range), "https://a.io/x?q=1");
+    }
+}
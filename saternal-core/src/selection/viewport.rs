@@ -0,0 +1,182 @@
+/// Where each pane sits in the window, and which pane a pixel falls in
+use crate::geometry::Padding;
+use crate::pane::PaneNode;
+use alacritty_terminal::index::Point;
+
+
+/// Viewport for rendering a single pane
+#[derive(Debug, Clone)]
+pub struct PaneViewport {
+    pub pane_id: usize,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    pub focused: bool,
+    /// The pane's font size relative to the window's (its cells are this much larger)
+    pub font_scale: f32,
+}
+
+impl PaneViewport {
+    /// Whether a window pixel position falls inside this pane
+    pub fn contains(&self, pixel_x: f32, pixel_y: f32) -> bool {
+        pixel_x >= self.x as f32
+            && pixel_x < (self.x + self.width) as f32
+            && pixel_y >= self.y as f32
+            && pixel_y < (self.y + self.height) as f32
+    }
+
+    /// Area left for the terminal below a header strip of `header_height` pixels
+    pub fn without_header(&self, header_height: u32) -> PaneViewport {
+        let header_height = header_height.min(self.height);
+        PaneViewport {
+            y: self.y + header_height,
+            height: self.height - header_height,
+            ..self.clone()
+        }
+    }
+
+    /// Grid cell under a window pixel position, relative to this pane's content
+    /// (positions in the padding clamp to the first row/column)
+    pub fn cell_at(&self, pixel_x: f32, pixel_y: f32, cell_width: f32, cell_height: f32, padding: &Padding) -> Point {
+        let local_x = (pixel_x - self.x as f32 - padding.left).max(0.0);
+        let local_y = (pixel_y - self.y as f32 - padding.top).max(0.0);
+        crate::input::pixel_to_grid(local_x, local_y, cell_width, cell_height)
+    }
+}
+
+/// Pane viewport under a window pixel position
+pub fn pane_at(viewports: &[PaneViewport], pixel_x: f32, pixel_y: f32) -> Option<&PaneViewport> {
+    viewports.iter().find(|vp| vp.contains(pixel_x, pixel_y))
+}
+
+/// Calculate viewports for all panes in the tree
+pub fn calculate_pane_viewports(
+    pane_tree: &PaneNode,
+    window_width: u32,
+    window_height: u32,
+) -> Vec<PaneViewport> {
+    let mut viewports = Vec::new();
+    calculate_viewports_recursive(
+        pane_tree,
+        0, 0,
+        window_width, window_height,
+        &mut viewports
+    );
+    viewports
+}
+
+fn calculate_viewports_recursive(
+    node: &PaneNode,
+    x: u32, y: u32,
+    width: u32, height: u32,
+    viewports: &mut Vec<PaneViewport>
+) {
+    use crate::pane::{PaneNode as PN, SplitDirection};
+    
+    match node {
+        PN::Leaf { pane } => {
+            viewports.push(PaneViewport {
+                pane_id: pane.id,
+                x, y, width, height,
+                focused: pane.focused,
+                font_scale: pane.font_scale(),
+            });
+        }
+        PN::Split { direction, children, ratio } => {
+            const BORDER_WIDTH: u32 = 2;
+            let a = BORDER_WIDTH / 2;
+            let b = BORDER_WIDTH - a;
+
+            match direction {
+                SplitDirection::Horizontal => {
+                    // Top/bottom split
+                    let split_y = (height as f32 * ratio) as u32;
+
+                    if let Some(top) = children.first() {
+                        calculate_viewports_recursive(
+                            top,
+                            x, y,
+                            width,
+                            split_y.saturating_sub(a),
+                            viewports
+                        );
+                    }
+
+                    if let Some(bottom) = children.get(1) {
+                        calculate_viewports_recursive(
+                            bottom,
+                            x,
+                            y + split_y + b,
+                            width,
+                            height.saturating_sub(split_y + b),
+                            viewports
+                        );
+                    }
+                }
+                SplitDirection::Vertical => {
+                    // Left/right split
+                    let split_x = (width as f32 * ratio) as u32;
+
+                    if let Some(left) = children.first() {
+                        calculate_viewports_recursive(
+                            left,
+                            x, y,
+                            split_x.saturating_sub(a),
+                            height,
+                            viewports
+                        );
+                    }
+
+                    if let Some(right) = children.get(1) {
+                        calculate_viewports_recursive(
+                            right,
+                            x + split_x + b,
+                            y,
+                            width.saturating_sub(split_x + b),
+                            height,
+                            viewports
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alacritty_terminal::index::{Column, Line};
+
+    const PADDING: Padding = Padding { top: 8.0, left: 12.0, right: 4.0, bottom: 4.0 };
+
+    #[test]
+    fn test_pane_hit_test_is_viewport_relative() {
+        let viewport = |pane_id, x| PaneViewport { pane_id, x, y: 0, width: 400, height: 300, focused: false, font_scale: 1.0 };
+        let viewports = vec![viewport(1, 0), viewport(2, 402)];
+
+        let right = pane_at(&viewports, 402.0 + PADDING.left + 25.0, PADDING.top + 45.0).unwrap();
+        assert_eq!(right.pane_id, 2);
+        assert_eq!(
+            right.cell_at(402.0 + PADDING.left + 25.0, PADDING.top + 45.0, 10.0, 20.0, &PADDING),
+            Point::new(Line(2), Column(2))
+        );
+
+        // Border gap and padding
+        assert!(pane_at(&viewports, 401.0, 10.0).is_none());
+        assert_eq!(right.cell_at(403.0, 1.0, 10.0, 20.0, &PADDING), Point::new(Line(0), Column(0)));
+    }
+
+    #[test]
+    fn test_header_shifts_content_down() {
+        let viewport = PaneViewport { pane_id: 1, x: 0, y: 100, width: 400, height: 300, focused: true, font_scale: 1.0 };
+        let content = viewport.without_header(20);
+        assert_eq!((content.y, content.height), (120, 280));
+
+        // A click on the header still lands in the pane, on the first content row
+        assert!(viewport.contains(50.0, 105.0));
+        assert_eq!(content.cell_at(50.0, 105.0, 10.0, 20.0, &PADDING).line, Line(0));
+        assert_eq!(viewport.without_header(500).height, 0);
+    }
+}
@@ -0,0 +1,130 @@
+//! OSC 133 shell integration marks
+//!
+//! Shells with integration scripts wrap each prompt and command in marks:
+//! `133;A` prompt start, `133;B` prompt end (command input starts),
+//! `133;C` command executed, `133;D` command finished. The scanner finds
+//! them in raw PTY output so the terminal can note where the cursor was at
+//! each mark while feeding the bytes to the parser.
+
+use std::time::Duration;
+
+/// A shell integration mark we act on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShellMark {
+    /// 133;B - the prompt is drawn, user input starts at the cursor
+    PromptEnd,
+    /// 133;C - the typed command was accepted and is about to run
+    CommandStart,
+    /// 133;D - the command finished (an exit status may follow)
+    CommandEnd,
+}
+
+/// A command that ran between 133;C and 133;D
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FinishedCommand {
+    /// Text typed at the prompt (empty if the prompt wasn't marked)
+    pub command: String,
+    /// How long it ran
+    pub duration: Duration,
+}
+
+/// Short human-readable duration: "45s", "2m 05s", "1h 03m"
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match (secs / 3600, secs / 60 % 60, secs % 60) {
+        (0, 0, s) => format!("{}s", s),
+        (0, m, s) => format!("{}m {:02}s", m, s),
+        (h, m, _) => format!("{}h {:02}m", h, m),
+    }
+}
+
+const MARK_PREFIX: &[u8] = b"\x1b]133;";
+/// Prefix plus the mark letter
+const MARK_LEN: usize = MARK_PREFIX.len() + 1;
+
+/// Finds OSC 133 marks in PTY output, including marks split across reads
+#[derive(Debug, Default)]
+pub struct MarkScanner {
+    carry: Vec<u8>,
+}
+
+impl MarkScanner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Scan a chunk of output
+    ///
+    /// Returns `(offset, mark)` pairs, where `offset` is the position in
+    /// `bytes` just past the mark letter - everything before it has to be
+    /// parsed before the mark takes effect.
+    pub fn scan(&mut self, bytes: &[u8]) -> Vec<(usize, ShellMark)> {
+        let carried = self.carry.len();
+        let mut window = std::mem::take(&mut self.carry);
+        window.extend_from_slice(bytes);
+
+        let mut marks = Vec::new();
+        let mut i = 0;
+        while i + MARK_LEN <= window.len() {
+            if &window[i..i + MARK_PREFIX.len()] == MARK_PREFIX {
+                let mark = match window[i + MARK_PREFIX.len()] {
+                    b'B' => Some(ShellMark::PromptEnd),
+                    b'C' => Some(ShellMark::CommandStart),
+                    b'D' => Some(ShellMark::CommandEnd),
+                    _ => None,
+                };
+                if let Some(mark) = mark {
+                    let end = (i + MARK_LEN).saturating_sub(carried);
+                    marks.push((end, mark));
+                }
+                i += MARK_LEN;
+            } else {
+                i += 1;
+            }
+        }
+
+        // Keep a possible partial mark at the end for the next read
+        let tail_start = (window.len().saturating_sub(MARK_PREFIX.len())).max(i.min(window.len()));
+        if let Some(start) = (tail_start..window.len()).find(|&j| MARK_PREFIX.starts_with(&window[j..])) {
+            self.carry = window[start..].to_vec();
+        }
+
+        marks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_finds_marks_with_offsets() {
+        let mut scanner = MarkScanner::new();
+        let out = b"$ \x1b]133;B\x07ls\r\n\x1b]133;C\x07file";
+        let marks = scanner.scan(out);
+        assert_eq!(
+            marks,
+            vec![(9, ShellMark::PromptEnd), (21, ShellMark::CommandStart)]
+        );
+    }
+
+    #[test]
+    fn test_ignores_other_marks() {
+        let mut scanner = MarkScanner::new();
+        assert_eq!(scanner.scan(b"\x1b]133;A\x07\x1b]133;D;0\x07"), vec![(15, ShellMark::CommandEnd)]);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_millis(45_900)), "45s");
+        assert_eq!(format_duration(Duration::from_secs(125)), "2m 05s");
+        assert_eq!(format_duration(Duration::from_secs(3 * 3600 + 7 * 60 + 59)), "3h 07m");
+    }
+
+    #[test]
+    fn test_mark_split_across_reads() {
+        let mut scanner = MarkScanner::new();
+        assert!(scanner.scan(b"prompt \x1b]13").is_empty());
+        assert_eq!(scanner.scan(b"3;B\x07"), vec![(3, ShellMark::PromptEnd)]);
+    }
+}
@@ -0,0 +1,304 @@
+/// Snippets: named text blocks from config.toml, inserted at the prompt
+///
+/// Placeholders are written `{{name}}` or `{{name:default}}`. Before a snippet
+/// is inserted each distinct placeholder is filled in turn, and a name used
+/// more than once gets the same value everywhere.
+use crate::history::fuzzy_score;
+use std::collections::BTreeMap;
+
+/// A `{{name}}` or `{{name:default}}` field of a snippet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placeholder {
+    pub name: String,
+    pub default: String,
+}
+
+/// Literal text and placeholders, in order
+enum Part<'a> {
+    Text(&'a str),
+    Field { name: &'a str, default: &'a str },
+}
+
+/// Split a snippet into text and placeholders; `{{` without a valid field after it is kept as text
+fn parts(text: &str) -> Vec<Part<'_>> {
+    let mut parts = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else { break };
+        let inner = &after[..end];
+        let (name, default) = inner.split_once(':').unwrap_or((inner, ""));
+        let name = name.trim();
+        if name.is_empty() || inner.contains(['{', '\n']) {
+            parts.push(Part::Text(&rest[..start + 2]));
+            rest = after;
+            continue;
+        }
+        parts.push(Part::Text(&rest[..start]));
+        parts.push(Part::Field { name, default });
+        rest = &after[end + 2..];
+    }
+    parts.push(Part::Text(rest));
+    parts
+}
+
+/// Distinct placeholders of a snippet, in order of first use
+pub fn placeholders(text: &str) -> Vec<Placeholder> {
+    let mut found: Vec<Placeholder> = Vec::new();
+    for part in parts(text) {
+        if let Part::Field { name, default } = part {
+            if !found.iter().any(|placeholder| placeholder.name == name) {
+                found.push(Placeholder { name: name.to_string(), default: default.to_string() });
+            }
+        }
+    }
+    found
+}
+
+/// Snippet text with its placeholders replaced by `values` (name, value) pairs
+/// A placeholder without a value, or with an empty one, gets its default
+pub fn expand(text: &str, values: &[(String, String)]) -> String {
+    parts(text)
+        .into_iter()
+        .map(|part| match part {
+            Part::Text(text) => text,
+            Part::Field { name, default } => values
+                .iter()
+                .find(|(field, value)| field == name && !value.is_empty())
+                .map_or(default, |(_, value)| value.as_str()),
+        })
+        .collect()
+}
+
+/// A filled-in snippet, ready to send to the focused pane
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnippetInsert {
+    pub text: String,
+    /// Clear the shell's input line first (it holds the `snippet <name>` command that opened it)
+    pub replace_line: bool,
+}
+
+#[derive(Debug)]
+enum Stage {
+    /// Typing filters the snippet names
+    Choosing { snippets: Vec<(String, String)>, query: String, results: Vec<usize>, selected: usize },
+    /// Typing fills the current placeholder
+    Filling { name: String, text: String, fields: Vec<Placeholder>, values: Vec<String>, current: usize, replace_line: bool },
+}
+
+/// State of the Cmd+; snippet picker: pick a snippet, then fill its placeholders
+#[derive(Debug, Default)]
+pub struct SnippetPicker {
+    stage: Option<Stage>,
+}
+
+impl SnippetPicker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open the picker on every snippet (false when there are none)
+    pub fn open(&mut self, snippets: &BTreeMap<String, String>) -> bool {
+        if snippets.is_empty() {
+            return false;
+        }
+        let snippets: Vec<(String, String)> = snippets.iter().map(|(name, text)| (name.clone(), text.clone())).collect();
+        let results = (0..snippets.len()).collect();
+        self.stage = Some(Stage::Choosing { snippets, query: String::new(), results, selected: 0 });
+        true
+    }
+
+    /// Start filling `text`'s placeholders; returns it right away when it has none
+    pub fn start(&mut self, name: &str, text: &str, replace_line: bool) -> Option<SnippetInsert> {
+        let fields = placeholders(text);
+        if fields.is_empty() {
+            self.close();
+            return Some(SnippetInsert { text: text.to_string(), replace_line });
+        }
+        self.stage = Some(Stage::Filling {
+            name: name.to_string(),
+            text: text.to_string(),
+            values: vec![String::new(); fields.len()],
+            fields,
+            current: 0,
+            replace_line,
+        });
+        None
+    }
+
+    /// Close the picker without inserting anything
+    pub fn close(&mut self) {
+        self.stage = None;
+    }
+
+    /// Check if the picker is open
+    pub fn is_active(&self) -> bool {
+        self.stage.is_some()
+    }
+
+    /// Append typed text to the query or the current placeholder
+    pub fn insert_text(&mut self, typed: &str) {
+        match &mut self.stage {
+            Some(Stage::Choosing { query, .. }) => {
+                query.push_str(typed);
+                self.refresh();
+            }
+            Some(Stage::Filling { values, current, .. }) => values[*current].push_str(typed),
+            None => {}
+        }
+    }
+
+    /// Delete the last character of the query or the current placeholder
+    pub fn delete_backward(&mut self) {
+        match &mut self.stage {
+            Some(Stage::Choosing { query, .. }) => {
+                query.pop();
+                self.refresh();
+            }
+            Some(Stage::Filling { values, current, .. }) => {
+                values[*current].pop();
+            }
+            None => {}
+        }
+    }
+
+    /// Highlight the next matching snippet
+    pub fn select_next(&mut self) {
+        if let Some(Stage::Choosing { results, selected, .. }) = &mut self.stage {
+            if *selected + 1 < results.len() {
+                *selected += 1;
+            }
+        }
+    }
+
+    /// Highlight the previous matching snippet
+    pub fn select_prev(&mut self) {
+        if let Some(Stage::Choosing { selected, .. }) = &mut self.stage {
+            *selected = selected.saturating_sub(1);
+        }
+    }
+
+    /// Enter or Tab: pick the highlighted snippet, or move on to the next placeholder
+    /// Returns the snippet once the last placeholder is filled (the picker closes)
+    pub fn advance(&mut self) -> Option<SnippetInsert> {
+        match self.stage.as_mut()? {
+            Stage::Choosing { snippets, results, selected, .. } => {
+                let (name, text) = results.get(*selected).map(|&index| snippets[index].clone())?;
+                self.start(&name, &text, false)
+            }
+            Stage::Filling { text, fields, values, current, replace_line, .. } => {
+                if *current + 1 < fields.len() {
+                    *current += 1;
+                    return None;
+                }
+                let values: Vec<(String, String)> =
+                    fields.iter().map(|field| field.name.clone()).zip(values.iter().cloned()).collect();
+                let insert = SnippetInsert { text: expand(text, &values), replace_line: *replace_line };
+                self.close();
+                Some(insert)
+            }
+        }
+    }
+
+    /// Shift+Tab: back to the previous placeholder
+    pub fn back(&mut self) {
+        if let Some(Stage::Filling { current, .. }) = &mut self.stage {
+            *current = current.saturating_sub(1);
+        }
+    }
+
+    /// One-line description of the picker for the status line
+    pub fn status(&self) -> Option<String> {
+        match self.stage.as_ref()? {
+            Stage::Choosing { snippets, query, results, selected } => {
+                let pick = match results.get(*selected) {
+                    Some(&index) => format!("{} ({}/{})", snippets[index].0, selected + 1, results.len()),
+                    None => "no matches".to_string(),
+                };
+                Some(format!("Snippets: {} → {}", query, pick))
+            }
+            Stage::Filling { name, fields, values, current, .. } => {
+                let field = &fields[*current];
+                let default = if field.default.is_empty() { String::new() } else { format!(" [{}]", field.default) };
+                Some(format!(
+                    "Snippet {}: {}{} = {} ({}/{})",
+                    name,
+                    field.name,
+                    default,
+                    values[*current],
+                    current + 1,
+                    fields.len()
+                ))
+            }
+        }
+    }
+
+    fn refresh(&mut self) {
+        if let Some(Stage::Choosing { snippets, query, results, selected }) = &mut self.stage {
+            let query: Vec<char> = query.to_lowercase().chars().collect();
+            let mut scored: Vec<(usize, usize)> = snippets
+                .iter()
+                .enumerate()
+                .filter_map(|(index, (name, _))| fuzzy_score(&query, name).map(|score| (score, index)))
+                .collect();
+            scored.sort();
+            *results = scored.into_iter().map(|(_, index)| index).collect();
+            *selected = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_placeholders_and_expand() {
+        let text = "ssh {{user:deploy}}@{{host}} && echo {{host}} {{ }} {{a\nb}}";
+        assert_eq!(
+            placeholders(text),
+            vec![
+                Placeholder { name: "user".to_string(), default: "deploy".to_string() },
+                Placeholder { name: "host".to_string(), default: String::new() },
+            ]
+        );
+        let values = vec![("user".to_string(), String::new()), ("host".to_string(), "web-1".to_string())];
+        assert_eq!(expand(text, &values), "ssh deploy@web-1 && echo web-1 {{ }} {{a\nb}}");
+        assert_eq!(expand("echo ${HOME} {{unclosed", &[]), "echo ${HOME} {{unclosed");
+    }
+
+    #[test]
+    fn test_picker_filters_then_fills_placeholders() {
+        let snippets = BTreeMap::from([
+            ("deploy".to_string(), "ssh {{user:deploy}}@{{host}}".to_string()),
+            ("disk".to_string(), "df -h".to_string()),
+        ]);
+        let mut picker = SnippetPicker::new();
+        assert!(!picker.open(&BTreeMap::new()));
+        assert!(picker.open(&snippets));
+
+        picker.insert_text("dep");
+        assert_eq!(picker.status().as_deref(), Some("Snippets: dep → deploy (1/1)"));
+        assert_eq!(picker.advance(), None);
+        assert_eq!(picker.status().as_deref(), Some("Snippet deploy: user [deploy] =  (1/2)"));
+
+        // Empty keeps the default; Shift+Tab goes back to fix a field
+        assert_eq!(picker.advance(), None);
+        picker.insert_text("web-2");
+        picker.delete_backward();
+        picker.insert_text("1");
+        picker.back();
+        picker.insert_text("root");
+        assert_eq!(picker.advance(), None);
+        let insert = picker.advance().unwrap();
+        assert_eq!(insert, SnippetInsert { text: "ssh root@web-1".to_string(), replace_line: false });
+        assert!(!picker.is_active());
+
+        // A snippet without placeholders is inserted straight away
+        assert!(picker.open(&snippets));
+        picker.insert_text("dis");
+        assert_eq!(picker.advance().map(|insert| insert.text).as_deref(), Some("df -h"));
+        assert_eq!(picker.start("x", "ls {{dir:.}}", true), None);
+        assert_eq!(picker.advance(), Some(SnippetInsert { text: "ls .".to_string(), replace_line: true }));
+    }
+}
@@ -0,0 +1,193 @@
+/// Ready-state detection for commands sent to a freshly spawned shell
+///
+/// A startup command must not be written to the PTY before the shell has
+/// finished initializing, or it gets echoed into rc-file output or swallowed
+/// entirely. The shell counts as ready when either:
+/// - it emits an OSC 133;A prompt mark (shell integration), or
+/// - no mark came within `PROMPT_MARK_GRACE` (a shell without integration),
+///   and it has produced output and then gone quiet for `SETTLE_PERIOD`
+///   (prompt drawn, waiting for input)
+use std::time::{Duration, Instant};
+
+/// How long the shell must stay quiet after output before we consider it ready
+pub const SETTLE_PERIOD: Duration = Duration::from_millis(250);
+
+/// How long to wait for a prompt mark before falling back to the quiet-output check,
+/// so a slow rc file pausing between lines doesn't get the command typed into it
+pub const PROMPT_MARK_GRACE: Duration = Duration::from_millis(1500);
+
+/// Default time to wait for a prompt before giving up
+pub const DEFAULT_STARTUP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// OSC 133;A - "prompt start" mark emitted by shell integration scripts
+const PROMPT_MARK: &[u8] = b"\x1b]133;A";
+
+/// What the owner of the PTY should do after polling
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StartupAction {
+    /// Shell not ready yet - poll again later
+    Wait,
+    /// Shell is ready - write this command (already terminated with `\r`)
+    Send(String),
+    /// No prompt appeared in time - report this message in the pane
+    TimedOut(String),
+    /// Command already sent or abandoned
+    Done,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ReadyState {
+    WaitingForOutput,
+    Settling { last_output: Instant },
+    PromptSeen,
+    Finished,
+}
+
+/// A command queued to run once the shell shows its first prompt
+#[derive(Debug, Clone)]
+pub struct StartupCommand {
+    command: String,
+    timeout: Duration,
+    queued_at: Instant,
+    state: ReadyState,
+    // Tail of the previous chunk, so a prompt mark split across reads is found
+    carry: Vec<u8>,
+}
+
+impl StartupCommand {
+    /// Queue a command with a timeout measured from `now`
+    pub fn new(command: impl Into<String>, timeout: Duration, now: Instant) -> Self {
+        Self {
+            command: command.into(),
+            timeout,
+            queued_at: now,
+            state: ReadyState::WaitingForOutput,
+            carry: Vec::new(),
+        }
+    }
+
+    /// Feed raw PTY output (before it's handed to the VTE parser)
+    pub fn observe_output(&mut self, bytes: &[u8], now: Instant) {
+        if bytes.is_empty() || matches!(self.state, ReadyState::PromptSeen | ReadyState::Finished) {
+            return;
+        }
+
+        let mut window = std::mem::take(&mut self.carry);
+        window.extend_from_slice(bytes);
+
+        if window.windows(PROMPT_MARK.len()).any(|w| w == PROMPT_MARK) {
+            self.state = ReadyState::PromptSeen;
+            return;
+        }
+
+        let keep = (PROMPT_MARK.len() - 1).min(window.len());
+        self.carry = window[window.len() - keep..].to_vec();
+        self.state = ReadyState::Settling { last_output: now };
+    }
+
+    /// Decide whether to send the command now
+    pub fn poll(&mut self, now: Instant) -> StartupAction {
+        let ready = match self.state {
+            ReadyState::Finished => return StartupAction::Done,
+            ReadyState::PromptSeen => true,
+            ReadyState::Settling { last_output } => {
+                now.duration_since(self.queued_at) >= PROMPT_MARK_GRACE
+                    && now.duration_since(last_output) >= SETTLE_PERIOD
+            }
+            ReadyState::WaitingForOutput => false,
+        };
+
+        if ready {
+            self.state = ReadyState::Finished;
+            return StartupAction::Send(format!("{}\r", self.command));
+        }
+
+        if now.duration_since(self.queued_at) >= self.timeout {
+            self.state = ReadyState::Finished;
+            return StartupAction::TimedOut(format!(
+                "startup command `{}` not sent: no shell prompt after {:.1}s",
+                self.command,
+                self.timeout.as_secs_f32()
+            ));
+        }
+
+        StartupAction::Wait
+    }
+
+    /// Check if the command is still waiting to be sent
+    pub fn is_pending(&self) -> bool {
+        self.state != ReadyState::Finished
+    }
+
+    /// The command text
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn test_waits_for_output_before_sending() {
+        let t0 = Instant::now();
+        let mut cmd = StartupCommand::new("btop", ms(5000), t0);
+        assert_eq!(cmd.poll(t0 + ms(1000)), StartupAction::Wait);
+    }
+
+    #[test]
+    fn test_sends_after_output_settles() {
+        let t0 = Instant::now();
+        let mut cmd = StartupCommand::new("btop", ms(5000), t0);
+        cmd.observe_output(b"Last login: today\r\n$ ", t0 + ms(100));
+        assert_eq!(cmd.poll(t0 + ms(200)), StartupAction::Wait);
+        // Quiet, but a prompt mark may still be on its way
+        assert_eq!(cmd.poll(t0 + ms(400)), StartupAction::Wait);
+        assert_eq!(cmd.poll(t0 + ms(1500)), StartupAction::Send("btop\r".to_string()));
+        assert_eq!(cmd.poll(t0 + ms(1600)), StartupAction::Done);
+        assert!(!cmd.is_pending());
+    }
+
+    #[test]
+    fn test_prompt_mark_sends_immediately() {
+        let t0 = Instant::now();
+        let mut cmd = StartupCommand::new("ls", ms(5000), t0);
+        cmd.observe_output(b"\x1b]133;A\x07$ ", t0 + ms(10));
+        assert_eq!(cmd.poll(t0 + ms(10)), StartupAction::Send("ls\r".to_string()));
+    }
+
+    #[test]
+    fn test_prompt_mark_after_a_quiet_rc_file() {
+        let t0 = Instant::now();
+        let mut cmd = StartupCommand::new("ls", ms(5000), t0);
+        cmd.observe_output(b"loading plugins...\r\n", t0 + ms(10));
+        assert_eq!(cmd.poll(t0 + ms(800)), StartupAction::Wait);
+        cmd.observe_output(b"\x1b]133;A\x07$ ", t0 + ms(900));
+        assert_eq!(cmd.poll(t0 + ms(900)), StartupAction::Send("ls\r".to_string()));
+    }
+
+    #[test]
+    fn test_prompt_mark_split_across_reads() {
+        let t0 = Instant::now();
+        let mut cmd = StartupCommand::new("ls", ms(5000), t0);
+        cmd.observe_output(b"motd\x1b]13", t0 + ms(10));
+        cmd.observe_output(b"3;A\x07$ ", t0 + ms(11));
+        assert_eq!(cmd.poll(t0 + ms(11)), StartupAction::Send("ls\r".to_string()));
+    }
+
+    #[test]
+    fn test_times_out_without_prompt() {
+        let t0 = Instant::now();
+        let mut cmd = StartupCommand::new("btop", ms(1000), t0);
+        match cmd.poll(t0 + ms(1000)) {
+            StartupAction::TimedOut(msg) => assert!(msg.contains("btop")),
+            other => panic!("expected timeout, got {:?}", other),
+        }
+        assert_eq!(cmd.poll(t0 + ms(2000)), StartupAction::Done);
+    }
+}
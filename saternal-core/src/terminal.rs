@@ -1,82 +1,153 @@
 use alacritty_terminal::{
     event::{EventListener, OnResize},
-    grid::Dimensions,
-    term::{test::TermSize, Config as TermConfig, Term},
-    tty::{self, EventedReadWrite},
-    vte::ansi::Processor,
+    grid::{Dimensions, Grid},
+    index::{Column, Line, Point},
+    term::{cell::{Cell, Flags}, test::TermSize, Config as TermConfig, Term, TermMode},
+    vte::ansi::Rgb,
 };
+#[cfg(unix)]
+use alacritty_terminal::tty::{self, EventedReadWrite, Pty};
 use anyhow::Result;
 use log::{debug, info};
 use parking_lot::Mutex;
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+use tokio::sync::mpsc;
+
+/// Builds the reply to a color query from the color's current value
+type ColorReply = Arc<dyn Fn(Rgb) -> String + Sync + Send>;
+
+use crate::command_duration::DurationMark;
+#[cfg(windows)]
+use crate::conpty::{self, Pty};
+use crate::crash;
+use crate::feedback::{format_feedback, FeedbackKind};
+use crate::local_echo::LocalEcho;
+use crate::output_parser::{self, OutputParser, RESET_DYNAMIC_COLORS};
+use crate::pane_log::{LogFormat, PaneLog};
+use crate::process::{self, ProcessInfo};
+use crate::pty_reader;
+#[cfg(unix)]
+use crate::secure_input;
+use crate::shell_integration::FinishedCommand;
+use crate::theme::ColorPalette;
+use crate::startup::{StartupAction, StartupCommand};
+use crate::trigger::{TriggerHit, TriggerSet};
+
+/// Lines of scrollback each terminal keeps
+pub const SCROLLBACK_LINES: usize = 10_000;
 
 /// Wrapper around Alacritty's terminal emulator
 pub struct Terminal {
     term: Arc<Mutex<Term<TermEventListener>>>,
-    pty: tty::Pty,
-    processor: Processor,
+    pty: Pty,
+    /// Output parsed on a thread of its own, and what was found in it
+    parser: Arc<Mutex<OutputParser>>,
+    /// Tells the current parser thread to finish (set on respawn and drop)
+    stop_parsing: Arc<AtomicBool>,
+    /// Set by the event listener when the terminal receives BEL
+    bell: Arc<AtomicBool>,
+    /// Shell command, kept for respawn()
+    shell: Option<String>,
+    /// Window title set by the program (OSC 0/2), and whether it changed
+    title: Arc<Mutex<Option<String>>>,
+    title_changed: Arc<AtomicBool>,
+    /// Theme colors, before any the program set with OSC 4/10/11/12 (those live in the Term)
+    palette: ColorPalette,
+    /// Color queries (OSC 4/10/11/12 with `?`) waiting for a reply
+    color_requests: Arc<Mutex<Vec<(usize, ColorReply)>>>,
+    /// Typed characters drawn ahead of their echo
+    local_echo: LocalEcho,
 }
 
 impl Terminal {
     /// Create a new terminal with the specified dimensions
     pub fn new(cols: usize, rows: usize, shell: Option<String>) -> Result<Self> {
-        info!("Creating new terminal: {}x{}", cols, rows);
-
-        // Create PTY with WindowSize
-        let mut env = HashMap::new();
-        // Set TERM environment variable for proper shell initialization
-        env.insert("TERM".to_string(), "xterm-256color".to_string());
-        // Inherit PATH and other important env vars
-        if let Ok(path) = std::env::var("PATH") {
-            env.insert("PATH".to_string(), path);
-        }
-        if let Ok(home) = std::env::var("HOME") {
-            env.insert("HOME".to_string(), home);
-        }
-        if let Ok(user) = std::env::var("USER") {
-            env.insert("USER".to_string(), user);
-        }
-        
-        let pty_config = tty::Options {
-            shell: shell.map(|s| tty::Shell::new(s, vec![])),
-            working_directory: std::env::current_dir().ok(),
-            drain_on_exit: true,
-            env,
-        };
+        Self::with_cwd(cols, rows, shell, None)
+    }
 
-        let window_size = alacritty_terminal::event::WindowSize {
-            num_cols: cols as u16,
-            num_lines: rows as u16,
-            cell_width: 8,
-            cell_height: 16,
-        };
+    /// Create a new terminal whose shell starts in `cwd` (default: current directory)
+    pub fn with_cwd(cols: usize, rows: usize, shell: Option<String>, cwd: Option<PathBuf>) -> Result<Self> {
+        info!("Creating new terminal: {}x{}", cols, rows);
 
-        let pty = tty::new(&pty_config, window_size, 0)?;
+        let (pty, output) = spawn_pty(cols, rows, shell.clone(), cwd)?;
 
         // Create terminal with TermSize
         let event_listener = TermEventListener::new();
+        let bell = event_listener.bell.clone();
+        let title = event_listener.title.clone();
+        let title_changed = event_listener.title_changed.clone();
+        let color_requests = event_listener.color_requests.clone();
         let size = TermSize::new(cols, rows);
-        let term = Term::new(TermConfig::default(), &size, event_listener);
+        let config = TermConfig { scrolling_history: SCROLLBACK_LINES, ..TermConfig::default() };
+        let term = Term::new(config, &size, event_listener);
 
         let term = Arc::new(Mutex::new(term));
 
-        // Create VTE processor
-        let processor = Processor::new();
+        let parser = Arc::new(Mutex::new(OutputParser::new(term.clone())));
+        let stop_parsing = Arc::new(AtomicBool::new(false));
+        output_parser::spawn(parser.clone(), output, stop_parsing.clone(), &output_parser::THROTTLED)?;
 
         Ok(Self {
             term,
             pty,
-            processor,
+            parser,
+            stop_parsing,
+            bell,
+            shell,
+            title,
+            title_changed,
+            palette: ColorPalette::default(),
+            color_requests,
+            local_echo: LocalEcho::new(),
         })
     }
 
+    /// Kill the shell and start a fresh one in the same size and working directory
+    ///
+    /// The grid and scrollback are kept; the new prompt appears below the old output.
+    pub fn respawn(&mut self) -> Result<()> {
+        let shell_pid = self.pty.child().id() as i32;
+        let cwd = process::process_info(shell_pid).and_then(|process| process.cwd);
+        let (cols, rows) = self.dimensions();
+        let (pty, output) = spawn_pty(cols, rows, self.shell.clone(), cwd)?;
+        info!("Respawning shell (pid {}) in a {}x{} pane", shell_pid, cols, rows);
+
+        // A wedged shell may ignore the SIGHUP sent when the old PTY drops
+        #[cfg(unix)]
+        unsafe {
+            libc::kill(shell_pid, libc::SIGKILL);
+        }
+        #[cfg(windows)]
+        let _ = self.pty.child().kill();
+        crash::unregister_child(shell_pid as u32);
+        self.pty = pty;
+        // Output the old shell left unread is dropped with its parser thread
+        self.stop_parsing.store(true, Ordering::Release);
+        self.stop_parsing = Arc::new(AtomicBool::new(false));
+        self.parser.lock().reset();
+        output_parser::spawn(self.parser.clone(), output, self.stop_parsing.clone(), &output_parser::THROTTLED)?;
+
+        // Undo modes the old program may have left on: alt screen, hidden cursor, colors
+        self.inject_output(b"\x1b[?1049l\x1b[?25h\x1b[0m");
+        self.inject_output(RESET_DYNAMIC_COLORS);
+        Ok(())
+    }
+
     /// Get reference to the terminal
     pub fn term(&self) -> Arc<Mutex<Term<TermEventListener>>> {
         self.term.clone()
     }
 
     /// Get the PTY for I/O operations
-    pub fn pty(&self) -> &tty::Pty {
+    pub fn pty(&self) -> &Pty {
         &self.pty
     } //what does PTY mean here, and what are expeting in return in this function?
     //PTY means Pseudo Terminal, and we are expecting a reference to the PTY
@@ -87,6 +158,38 @@ impl Terminal {
     //the Writer trait is a trait that implements the write method
     //the EventedPty trait is a trait that implements the on_resize method
 
+    /// Process currently in the foreground of this terminal (the shell or the program it runs)
+    #[cfg(unix)]
+    pub fn foreground_process(&self) -> Option<ProcessInfo> {
+        use std::os::unix::io::AsRawFd;
+        process::foreground_process(self.pty.file().as_raw_fd())
+    }
+
+    /// A pseudo console has no foreground process group to ask, so this is always the shell
+    #[cfg(windows)]
+    pub fn foreground_process(&self) -> Option<ProcessInfo> {
+        process::process_info(self.pty.child().id() as i32)
+    }
+
+    /// The program is reading a password: echo is off, input still line-buffered
+    #[cfg(unix)]
+    pub fn at_password_prompt(&self) -> bool {
+        use std::os::unix::io::AsRawFd;
+        secure_input::is_password_prompt(self.pty.file().as_raw_fd())
+    }
+
+    /// A pseudo console doesn't expose its programs' console modes
+    #[cfg(windows)]
+    pub fn at_password_prompt(&self) -> bool {
+        false
+    }
+
+    /// Foreground process, if it is something other than the shell itself
+    pub fn busy_process(&self) -> Option<ProcessInfo> {
+        let shell_pid = self.pty.child().id() as i32;
+        self.foreground_process().filter(|process| process.pid != shell_pid)
+    }
+
     /// Resize the terminal
     pub fn resize(&mut self, cols: usize, rows: usize) -> Result<()> {
         debug!("Resizing terminal to {}x{}", cols, rows);
@@ -110,35 +213,251 @@ impl Terminal {
     /// Write input to the terminal
     pub fn write_input(&mut self, data: &[u8]) -> Result<()> {
         use std::io::Write;
+        // Keys other than plain text move the cursor in ways predictions can't follow
+        self.local_echo.clear();
         self.pty.writer().write_all(data)?;
         Ok(())
     }
 
-    /// Read output from the terminal and process it
-    /// Returns the number of bytes processed
+    /// Write typed text, drawing it ahead of its echo when `predict` is set
+    /// Nothing is predicted in full-screen programs or at a password prompt
+    pub fn write_typed(&mut self, text: &str, predict: bool) -> Result<()> {
+        use std::io::Write;
+        if predict && !self.at_password_prompt() {
+            let term = self.term.lock();
+            if term.mode().contains(TermMode::ALT_SCREEN) {
+                self.local_echo.clear();
+            } else {
+                self.local_echo.predict(text, term.grid().cursor.point, term.columns(), Instant::now());
+            }
+        } else {
+            self.local_echo.clear();
+        }
+        self.pty.writer().write_all(text.as_bytes())?;
+        Ok(())
+    }
+
+    /// Predicted characters still waiting for their echo, and where they go
+    pub fn predictions(&self) -> Vec<(Point, char)> {
+        self.local_echo.predictions()
+    }
+
+    /// Collect what the parser thread found since the last call, answer color
+    /// queries, and send a waiting startup command
+    /// Returns the number of bytes parsed in that time
     pub fn process_output(&mut self) -> Result<usize> {
-        use std::io::Read;
-
-        let mut buf = [0u8; 4096];
-        let mut total_bytes = 0;
-        loop {
-            match self.pty.reader().read(&mut buf) {
-                Ok(0) => break, // EOF
-                Ok(n) => {
-                    total_bytes += n;
-                    debug!("Read {} bytes from PTY: {:?}", n, String::from_utf8_lossy(&buf[..n]));
-                    let mut term = self.term.lock();
-                    self.processor.advance(&mut *term, &buf[..n]);
-                }
-                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => break,
-                Err(e) => {
-                    debug!("PTY read error: {}", e);
-                    return Err(e.into());
-                }
+        let parsed_bytes = std::mem::take(&mut self.parser.lock().parsed_bytes);
+        if !self.local_echo.is_empty() {
+            self.local_echo.reconcile(self.term.lock().grid(), Instant::now());
+        }
+        self.answer_color_requests()?;
+        self.poll_startup()?;
+        Ok(parsed_bytes)
+    }
+
+    /// Set the triggers run on output (None turns them off)
+    pub fn set_triggers(&mut self, triggers: Option<Arc<TriggerSet>>) {
+        self.parser.lock().triggers = triggers;
+    }
+
+    /// Notify and run triggers that fired since the last call
+    pub fn take_trigger_hits(&mut self) -> Vec<TriggerHit> {
+        std::mem::take(&mut self.parser.lock().trigger_hits)
+    }
+
+    /// Set the theme colors this pane starts from
+    pub fn set_palette(&mut self, palette: ColorPalette) {
+        self.palette = palette;
+    }
+
+    /// Colors to draw this pane with: the theme plus any the program changed
+    pub fn palette(&self) -> ColorPalette {
+        self.palette.with_dynamic_colors(self.term.lock().colors())
+    }
+
+    /// Theme colors for this pane, ignoring changes made by the program
+    pub fn base_palette(&self) -> &ColorPalette {
+        &self.palette
+    }
+
+    /// Reply to color queries with the colors currently in effect
+    fn answer_color_requests(&mut self) -> Result<()> {
+        let requests = std::mem::take(&mut *self.color_requests.lock());
+        if requests.is_empty() {
+            return Ok(());
+        }
+        let palette = self.palette();
+        for (index, reply) in requests {
+            if let Some(color) = palette.term_color(index) {
+                self.write_input(reply(color).as_bytes())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Check (and clear) whether BEL was received since the last call
+    pub fn take_bell(&self) -> bool {
+        self.bell.swap(false, Ordering::Relaxed)
+    }
+
+    /// Title set by the program via OSC 0/2, if any
+    pub fn title(&self) -> Option<String> {
+        self.title.lock().clone()
+    }
+
+    /// Check (and clear) whether the title changed since the last call
+    pub fn take_title_change(&self) -> bool {
+        self.title_changed.swap(false, Ordering::Relaxed)
+    }
+
+    /// Badge set for this pane (by escape sequence or the `badge` command)
+    /// None means the configured default applies; "" means no badge
+    pub fn badge(&self) -> Option<String> {
+        self.parser.lock().badge.clone()
+    }
+
+    pub fn set_badge(&mut self, badge: Option<String>) {
+        self.parser.lock().badge = badge;
+    }
+
+    /// Append this pane's output to `path` until stop_log(), replacing any current log
+    pub fn start_log(&mut self, path: &Path, format: LogFormat) -> Result<()> {
+        self.parser.lock().log = Some(PaneLog::start(path, format)?);
+        info!("Logging pane output to {}", path.display());
+        Ok(())
+    }
+
+    /// Stop logging; returns the file that was being written
+    pub fn stop_log(&mut self) -> Option<PathBuf> {
+        self.parser.lock().log.take().map(|log| log.path().to_path_buf())
+    }
+
+    /// File this pane is being logged to, if any
+    pub fn log_path(&self) -> Option<PathBuf> {
+        self.parser.lock().log.as_ref().map(|log| log.path().to_path_buf())
+    }
+
+    /// Start or stop keeping a copy of PTY output for take_tapped_output()
+    pub fn set_output_tap(&mut self, enabled: bool) {
+        let mut parser = self.parser.lock();
+        match (enabled, &parser.output_tap) {
+            (true, None) => parser.output_tap = Some(Vec::new()),
+            (false, Some(_)) => parser.output_tap = None,
+            _ => {}
+        }
+    }
+
+    /// PTY output processed since the last call while the tap is on
+    pub fn take_tapped_output(&mut self) -> String {
+        let mut parser = self.parser.lock();
+        let Some(tap) = &mut parser.output_tap else {
+            return String::new();
+        };
+        // Hold back a UTF-8 sequence split across reads until the rest arrives
+        let incomplete = match std::str::from_utf8(tap) {
+            Err(e) if e.error_len().is_none() => tap.len() - e.valid_up_to(),
+            _ => 0,
+        };
+        let tail = tap.split_off(tap.len() - incomplete);
+        let text = String::from_utf8_lossy(tap).into_owned();
+        *tap = tail;
+        text
+    }
+
+    /// Text on screen, with the scrollback above it if `scrollback` is set
+    pub fn screen_text(&self, scrollback: bool) -> String {
+        let term = self.term.lock();
+        let grid = term.grid();
+        let first_line = if scrollback { -(grid.history_size() as i32) } else { 0 };
+        grid_text(grid, first_line)
+    }
+
+    /// Take commands captured via shell integration since the last call
+    pub fn take_captured_commands(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.parser.lock().captured_commands)
+    }
+
+    /// Commands that finished running since the last call (via shell integration)
+    pub fn take_finished_commands(&mut self) -> Vec<FinishedCommand> {
+        std::mem::take(&mut self.parser.lock().finished_commands)
+    }
+
+    /// What's typed at the shell prompt and the cursor after it, while the cursor is at
+    /// the end of the input (needs shell integration to know where the prompt ends)
+    pub fn prompt_input(&self) -> Option<(Point, String)> {
+        let prompt_end = self.parser.lock().prompt_end?;
+        let term = self.term.lock();
+        if term.mode().contains(TermMode::ALT_SCREEN) {
+            return None;
+        }
+        let typed = output_parser::typed_input(term.grid(), prompt_end)?;
+        Some((term.grid().cursor.point, typed))
+    }
+
+    /// Where recent long-running commands were typed, to annotate with their run times
+    pub fn duration_marks(&self) -> Vec<DurationMark> {
+        self.parser.lock().duration_marks.iter().cloned().collect()
+    }
+
+    /// Queue a command to be written once the shell shows its first prompt
+    pub fn run_when_ready(&mut self, command: impl Into<String>, timeout: Duration) {
+        let command = StartupCommand::new(command, timeout, Instant::now());
+        info!("Queued startup command: {}", command.command());
+        self.parser.lock().startup = Some(command);
+    }
+
+    /// Check if a startup command is still waiting for the shell
+    pub fn has_pending_startup(&self) -> bool {
+        self.parser.lock().startup.as_ref().is_some_and(|s| s.is_pending())
+    }
+
+    /// Send the queued startup command if the shell is ready
+    fn poll_startup(&mut self) -> Result<()> {
+        // Decide under the parser lock, act after releasing it (show_warning takes it again)
+        let action = {
+            let mut parser = self.parser.lock();
+            let Some(startup) = &mut parser.startup else {
+                return Ok(());
+            };
+            let action = startup.poll(Instant::now());
+            if !matches!(action, StartupAction::Wait) {
+                parser.startup = None;
+            }
+            action
+        };
+
+        match action {
+            StartupAction::Wait | StartupAction::Done => {}
+            StartupAction::Send(command) => {
+                info!("Shell ready - sending startup command");
+                self.write_input(command.as_bytes())?;
+            }
+            StartupAction::TimedOut(message) => {
+                log::warn!("{}", message);
+                self.show_warning(&message);
             }
         }
+        Ok(())
+    }
+
+    /// Print styled feedback from Saternal itself into the terminal (never sent to the shell)
+    pub fn print_feedback(&mut self, kind: FeedbackKind, text: &str) {
+        self.inject_output(format_feedback(kind, text).as_bytes());
+    }
+
+    /// Print a warning line from Saternal itself into the terminal
+    pub fn show_warning(&mut self, message: &str) {
+        self.print_feedback(FeedbackKind::Warning, &format!("[saternal] {}", message));
+    }
 
-        Ok(total_bytes)
+    /// Feed bytes straight into the terminal emulator (displayed, never sent to the shell)
+    pub fn inject_output(&mut self, bytes: &[u8]) {
+        self.parser.lock().inject(bytes);
+    }
+
+    /// Drop all scrollback history, keeping the visible screen
+    pub fn clear_scrollback(&mut self) {
+        self.term.lock().grid_mut().clear_history();
     }
 
     /// Get grid dimensions
@@ -148,21 +467,142 @@ impl Terminal {
     }
 }
 
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        self.stop_parsing.store(true, Ordering::Release);
+        crash::unregister_child(self.pty.child().id());
+    }
+}
+
+/// Start `shell` (default: the user's shell) on a new PTY and begin reading its output
+fn spawn_pty(cols: usize, rows: usize, shell: Option<String>, cwd: Option<PathBuf>) -> Result<(Pty, mpsc::Receiver<Vec<u8>>)> {
+    let mut env = HashMap::new();
+    // Set TERM environment variable for proper shell initialization
+    env.insert("TERM".to_string(), "xterm-256color".to_string());
+    // Inherit PATH and other important env vars
+    if let Ok(path) = std::env::var("PATH") {
+        env.insert("PATH".to_string(), path);
+    }
+    if let Ok(home) = std::env::var("HOME") {
+        env.insert("HOME".to_string(), home);
+    }
+    if let Ok(user) = std::env::var("USER") {
+        env.insert("USER".to_string(), user);
+    }
+    
+    let working_directory = cwd.or_else(|| std::env::current_dir().ok());
+
+    let window_size = alacritty_terminal::event::WindowSize {
+        num_cols: cols as u16,
+        num_lines: rows as u16,
+        cell_width: 8,
+        cell_height: 16,
+    };
+
+    #[cfg(unix)]
+    let pty = {
+        let pty_config = tty::Options {
+            shell: shell.map(|s| tty::Shell::new(s, vec![])),
+            working_directory,
+            drain_on_exit: true,
+            env,
+        };
+        tty::new(&pty_config, window_size, 0)?
+    };
+    #[cfg(windows)]
+    let pty = conpty::new(shell.as_deref(), working_directory.as_deref(), &env, window_size)?;
+    crash::register_child(pty.child().id());
+    let output = pty_reader::spawn(pty.file().try_clone()?)?;
+    Ok((pty, output))
+}
+
+/// Lines `first_line..` of the grid down to the bottom of the screen
+///
+/// Soft-wrapped rows are joined, trailing blanks trimmed, and trailing empty
+/// lines dropped.
+fn grid_text(grid: &Grid<Cell>, first_line: i32) -> String {
+    let last_col = Column(grid.columns() - 1);
+    let mut text = String::new();
+    for line in first_line..grid.screen_lines() as i32 {
+        for col in 0..grid.columns() {
+            let cell = &grid[Point::new(Line(line), Column(col))];
+            if !cell.flags.contains(Flags::WIDE_CHAR_SPACER) {
+                text.push(if cell.c == '\0' { ' ' } else { cell.c });
+            }
+        }
+        if !grid[Point::new(Line(line), last_col)].flags.contains(Flags::WRAPLINE) {
+            text.truncate(text.trim_end_matches(' ').len());
+            text.push('\n');
+        }
+    }
+    text.truncate(text.trim_end().len());
+    text
+}
+
 /// Event listener for terminal events
 pub struct TermEventListener {
-    // We can add fields here to track terminal events
-    // For now, we just implement the required trait
+    bell: Arc<AtomicBool>,
+    title: Arc<Mutex<Option<String>>>,
+    title_changed: Arc<AtomicBool>,
+    color_requests: Arc<Mutex<Vec<(usize, ColorReply)>>>,
 }
 
 impl TermEventListener {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            bell: Arc::new(AtomicBool::new(false)),
+            title: Arc::new(Mutex::new(None)),
+            title_changed: Arc::new(AtomicBool::new(false)),
+            color_requests: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+}
+
+impl Default for TermEventListener {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 impl EventListener for TermEventListener {
     fn send_event(&self, event: alacritty_terminal::event::Event) {
         debug!("Terminal event: {:?}", event);
-        // Handle terminal events like title changes, etc.
+        use alacritty_terminal::event::Event;
+        match event {
+            Event::Bell => self.bell.store(true, Ordering::Relaxed),
+            Event::Title(title) => {
+                *self.title.lock() = Some(title);
+                self.title_changed.store(true, Ordering::Relaxed);
+            }
+            Event::ResetTitle => {
+                *self.title.lock() = None;
+                self.title_changed.store(true, Ordering::Relaxed);
+            }
+            Event::ColorRequest(index, reply) => self.color_requests.lock().push((index, reply)),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_row(grid: &mut Grid<Cell>, line: i32, text: &str) {
+        for (col, c) in text.chars().enumerate() {
+            grid[Point::new(Line(line), Column(col))].c = c;
+        }
+    }
+
+    #[test]
+    fn test_grid_text_joins_wrapped_rows_and_drops_blank_tail() {
+        let mut grid: Grid<Cell> = Grid::new(4, 6, 0);
+        write_row(&mut grid, 0, "$ echo");
+        grid[Point::new(Line(0), Column(5))].flags.insert(Flags::WRAPLINE);
+        write_row(&mut grid, 1, " hi");
+        write_row(&mut grid, 2, "hi  ");
+
+        assert_eq!(grid_text(&grid, 0), "$ echo hi\nhi");
+        assert_eq!(grid_text(&grid, 2), "hi");
     }
 }
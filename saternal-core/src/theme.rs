@@ -0,0 +1,294 @@
+use alacritty_terminal::term::color::Colors;
+use alacritty_terminal::vte::ansi::{NamedColor, Rgb};
+use serde::{Deserialize, Serialize};
+
+/// Names accepted by `ColorPalette::by_name`
+pub const THEME_NAMES: &[&str] = &["tokyo-night", "dracula", "solarized-dark"];
+
+/// Color palette for terminal theming
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ColorPalette {
+    /// Background color (RGBA, 0.0-1.0)
+    pub background: [f32; 4],
+    /// Foreground text color
+    pub foreground: [f32; 4],
+    /// Cursor color
+    pub cursor: [f32; 4],
+    /// Selection background color
+    pub selection_bg: [f32; 4],
+    /// Text color of selected cells (None keeps each cell's own; inverted cells use their background)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selection_fg: Option<[f32; 4]>,
+    /// Tint of search matches
+    #[serde(default = "default_search_match_bg")]
+    pub search_match_bg: [f32; 4],
+    /// Tint of the focused search match (the one Enter jumps from)
+    #[serde(default = "default_search_focused_bg")]
+    pub search_focused_bg: [f32; 4],
+    /// ANSI colors (0-15: black, red, green, yellow, blue, magenta, cyan, white + bright variants)
+    pub ansi_colors: [[f32; 4]; 16],
+    /// Colors 16-255 (the xterm 6x6x6 cube and grayscale ramp); themes don't set these
+    #[serde(skip, default = "xterm_extended_colors")]
+    pub extended_colors: [[f32; 4]; 240],
+}
+
+impl Default for ColorPalette {
+    fn default() -> Self {
+        Self::tokyo_night()
+    }
+}
+
+impl ColorPalette {
+    /// Tokyo Night theme (beautiful dark theme)
+    pub fn tokyo_night() -> Self {
+        Self {
+            // Background: Deep dark blue-purple
+            // Alpha = 1.0 in texture; final opacity controlled by shader's background_opacity uniform
+            background: [0.09, 0.09, 0.13, 1.0], // #16161D, fully opaque in texture
+            // Foreground: Light blue-white
+            foreground: [0.76, 0.78, 0.84, 1.0],  // #C0CAF5
+            // Cursor: Bright white with slight transparency
+            cursor: [1.0, 1.0, 1.0, 0.8],
+            // Selection: Subtle blue with transparency
+            selection_bg: [0.2, 0.25, 0.35, 0.5],
+            selection_fg: None,
+            search_match_bg: default_search_match_bg(),
+            search_focused_bg: default_search_focused_bg(),
+            // ANSI colors optimized for Tokyo Night
+            ansi_colors: [
+                // Normal colors (0-7)
+                [0.09, 0.11, 0.16, 1.0],  // 0: Black - #15161E
+                [0.95, 0.40, 0.49, 1.0],  // 1: Red - #F7768E
+                [0.60, 0.82, 0.58, 1.0],  // 2: Green - #9ECE6A
+                [0.89, 0.80, 0.46, 1.0],  // 3: Yellow - #E0CC75
+                [0.49, 0.68, 0.97, 1.0],  // 4: Blue - #7DACF8
+                [0.74, 0.56, 0.98, 1.0],  // 5: Magenta - #BB9AF7
+                [0.45, 0.84, 0.89, 1.0],  // 6: Cyan - #73DAE3
+                [0.67, 0.71, 0.78, 1.0],  // 7: White - #ACB5C6
+                
+                // Bright colors (8-15)
+                [0.34, 0.36, 0.43, 1.0],  // 8: Bright Black - #565A6E
+                [0.96, 0.54, 0.60, 1.0],  // 9: Bright Red - #F58A99
+                [0.72, 0.87, 0.69, 1.0],  // 10: Bright Green - #B8DEB0
+                [0.92, 0.86, 0.63, 1.0],  // 11: Bright Yellow - #EBDCA1
+                [0.63, 0.77, 0.98, 1.0],  // 12: Bright Blue - #A1C4FA
+                [0.82, 0.70, 0.99, 1.0],  // 13: Bright Magenta - #D1B3FC
+                [0.63, 0.89, 0.93, 1.0],  // 14: Bright Cyan - #A1E3ED
+                [0.76, 0.78, 0.84, 1.0],  // 15: Bright White - #C0CAF5
+            ],
+            extended_colors: xterm_extended_colors(),
+        }
+    }
+
+    /// Dracula theme
+    pub fn dracula() -> Self {
+        Self::from_hex(
+            "#282A36",
+            "#F8F8F2",
+            [
+                "#21222C", "#FF5555", "#50FA7B", "#F1FA8C", "#BD93F9", "#FF79C6", "#8BE9FD", "#F8F8F2",
+                "#6272A4", "#FF6E6E", "#69FF94", "#FFFFA5", "#D6ACFF", "#FF92DF", "#A4FFFF", "#FFFFFF",
+            ],
+        )
+    }
+
+    /// Solarized Dark theme
+    pub fn solarized_dark() -> Self {
+        Self::from_hex(
+            "#002B36",
+            "#839496",
+            [
+                "#073642", "#DC322F", "#859900", "#B58900", "#268BD2", "#D33682", "#2AA198", "#EEE8D5",
+                "#002B36", "#CB4B16", "#586E75", "#657B83", "#839496", "#6C71C4", "#93A1A1", "#FDF6E3",
+            ],
+        )
+    }
+
+    /// Look up a builtin theme by name (see THEME_NAMES)
+    pub fn by_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "tokyo-night" => Some(Self::tokyo_night()),
+            "dracula" => Some(Self::dracula()),
+            "solarized-dark" => Some(Self::solarized_dark()),
+            _ => None,
+        }
+    }
+
+    /// Build a palette from hex colors, with cursor and selection derived from the foreground
+    fn from_hex(background: &str, foreground: &str, ansi: [&str; 16]) -> Self {
+        let fg = Self::hex_to_rgba(foreground, 1.0);
+        Self {
+            background: Self::hex_to_rgba(background, 1.0),
+            foreground: fg,
+            cursor: [fg[0], fg[1], fg[2], 0.8],
+            selection_bg: [fg[0], fg[1], fg[2], 0.25],
+            selection_fg: None,
+            search_match_bg: default_search_match_bg(),
+            search_focused_bg: default_search_focused_bg(),
+            ansi_colors: ansi.map(|hex| Self::hex_to_rgba(hex, 1.0)),
+            extended_colors: xterm_extended_colors(),
+        }
+    }
+
+    /// Convert hex color to normalized RGBA
+    pub fn hex_to_rgba(hex: &str, alpha: f32) -> [f32; 4] {
+        let hex = hex.trim_start_matches('#');
+        if hex.len() != 6 {
+            return [0.0, 0.0, 0.0, alpha];
+        }
+        
+        let r = u8::from_str_radix(&hex[0..2], 16).unwrap_or(0) as f32 / 255.0;
+        let g = u8::from_str_radix(&hex[2..4], 16).unwrap_or(0) as f32 / 255.0;
+        let b = u8::from_str_radix(&hex[4..6], 16).unwrap_or(0) as f32 / 255.0;
+        
+        [r, g, b, alpha]
+    }
+
+    /// Get ANSI color by index (0-15)
+    pub fn get_ansi_color(&self, index: u8) -> [f32; 4] {
+        if (index as usize) < self.ansi_colors.len() {
+            self.ansi_colors[index as usize]
+        } else {
+            self.foreground // Fallback
+        }
+    }
+
+    /// Get any of the 256 indexed colors
+    pub fn indexed_color(&self, index: u8) -> [f32; 4] {
+        match index {
+            0..=15 => self.ansi_colors[index as usize],
+            _ => self.extended_colors[index as usize - 16],
+        }
+    }
+
+    /// This palette with the colors a program set via OSC 4/10/11/12 (a pane's Term colors)
+    pub fn with_dynamic_colors(&self, colors: &Colors) -> Self {
+        let rgba = |rgb: Rgb, alpha: f32| [rgb.r as f32 / 255.0, rgb.g as f32 / 255.0, rgb.b as f32 / 255.0, alpha];
+        let mut palette = *self;
+        for index in 0..256 {
+            if let Some(rgb) = colors[index] {
+                match index {
+                    0..=15 => palette.ansi_colors[index] = rgba(rgb, 1.0),
+                    _ => palette.extended_colors[index - 16] = rgba(rgb, 1.0),
+                }
+            }
+        }
+        if let Some(rgb) = colors[NamedColor::Foreground] {
+            palette.foreground = rgba(rgb, self.foreground[3]);
+        }
+        if let Some(rgb) = colors[NamedColor::Background] {
+            palette.background = rgba(rgb, self.background[3]);
+        }
+        if let Some(rgb) = colors[NamedColor::Cursor] {
+            palette.cursor = rgba(rgb, self.cursor[3]);
+        }
+        palette
+    }
+
+    /// Color for a Term color index (0-255, foreground, background, cursor), to answer queries
+    pub fn term_color(&self, index: usize) -> Option<Rgb> {
+        let color = match index {
+            0..=255 => self.indexed_color(index as u8),
+            _ if index == NamedColor::Foreground as usize => self.foreground,
+            _ if index == NamedColor::Background as usize => self.background,
+            _ if index == NamedColor::Cursor as usize => self.cursor,
+            _ => return None,
+        };
+        let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+        Some(Rgb { r: channel(color[0]), g: channel(color[1]), b: channel(color[2]) })
+    }
+}
+
+/// Search matches: a faint amber
+fn default_search_match_bg() -> [f32; 4] {
+    [0.89, 0.80, 0.46, 0.25]
+}
+
+/// Focused search match: a stronger orange, set apart from the other matches
+fn default_search_focused_bg() -> [f32; 4] {
+    [1.0, 0.6, 0.2, 0.55]
+}
+
+/// xterm's default colors 16-255
+fn xterm_extended_colors() -> [[f32; 4]; 240] {
+    std::array::from_fn(|i| {
+        let index = i + 16;
+        let [r, g, b] = if index < 232 {
+            let level = |value: usize| if value == 0 { 0 } else { 55 + 40 * value };
+            let cube = index - 16;
+            [level(cube / 36), level(cube / 6 % 6), level(cube % 6)]
+        } else {
+            let gray = 8 + 10 * (index - 232);
+            [gray; 3]
+        };
+        [r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, 1.0]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex_to_rgba() {
+        let color = ColorPalette::hex_to_rgba("#FF8800", 1.0);
+        assert!((color[0] - 1.0).abs() < 0.01); // Red channel
+        assert!((color[1] - 0.533).abs() < 0.01); // Green channel (~136/255)
+        assert!((color[2] - 0.0).abs() < 0.01); // Blue channel
+        assert_eq!(color[3], 1.0); // Alpha
+    }
+
+    #[test]
+    fn test_tokyo_night_theme() {
+        let theme = ColorPalette::tokyo_night();
+        assert_eq!(theme.ansi_colors.len(), 16);
+        // Verify background is dark
+        assert!(theme.background[0] < 0.2);
+        assert!(theme.background[1] < 0.2);
+        assert!(theme.background[2] < 0.2);
+    }
+
+    #[test]
+    fn test_theme_by_name() {
+        for name in THEME_NAMES {
+            assert!(ColorPalette::by_name(name).is_some(), "missing theme {}", name);
+        }
+        let dracula = ColorPalette::by_name("Dracula").unwrap();
+        assert_eq!(dracula.ansi_colors[1], ColorPalette::hex_to_rgba("#FF5555", 1.0));
+        assert!(ColorPalette::by_name("nope").is_none());
+    }
+
+    #[test]
+    fn test_xterm_extended_colors() {
+        let theme = ColorPalette::default();
+        assert_eq!(theme.term_color(196), Some(Rgb { r: 255, g: 0, b: 0 }));
+        assert_eq!(theme.term_color(231), Some(Rgb { r: 255, g: 255, b: 255 }));
+        assert_eq!(theme.term_color(232), Some(Rgb { r: 8, g: 8, b: 8 }));
+    }
+
+    #[test]
+    fn test_with_dynamic_colors() {
+        let theme = ColorPalette::default();
+        let mut colors = Colors::default();
+        let pink = Rgb { r: 255, g: 0, b: 128 };
+        colors[1] = Some(pink);
+        colors[100] = Some(pink);
+        colors[NamedColor::Background] = Some(pink);
+
+        let palette = theme.with_dynamic_colors(&colors);
+        assert_eq!(palette.term_color(1), Some(pink));
+        assert_eq!(palette.term_color(100), Some(pink));
+        assert_eq!(palette.term_color(NamedColor::Background as usize), Some(pink));
+        assert_eq!(palette.foreground, theme.foreground);
+        assert_eq!(palette.ansi_colors[2], theme.ansi_colors[2]);
+    }
+
+    #[test]
+    fn test_get_ansi_color() {
+        let theme = ColorPalette::default();
+        let red = theme.get_ansi_color(1);
+        let bright_red = theme.get_ansi_color(9);
+        // Bright red should be lighter than normal red
+        assert!(bright_red[0] >= red[0]);
+    }
+}
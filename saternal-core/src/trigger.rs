@@ -0,0 +1,306 @@
+/// Triggers: regexes from config run against each line of output
+///
+/// ```toml
+/// [[triggers]]
+/// regex = "error(\\[E\\d+\\])?:"
+/// action = "highlight"
+/// color = [0.55, 0.1, 0.1, 1.0]     # background behind the match
+/// [[triggers]]
+/// regex = "Finished .* target"
+/// action = "notify"
+/// [[triggers]]
+/// regex = "tests? failed"
+/// action = "run"
+/// command = "afplay /System/Library/Sounds/Basso.aiff"
+/// ```
+///
+/// A line is matched once, right after the newline ending it is parsed, so
+/// match positions are exact. Highlights recolor the matched cells, which
+/// keeps them on the text as it scrolls and reflows; notify and run hits
+/// are handed to the app. A notify or run trigger fires at most once per
+/// MIN_TRIGGER_INTERVAL, so a busy log matching every line starts one
+/// process per burst rather than one per line. Full-screen programs
+/// (alternate screen) are skipped.
+use alacritty_terminal::grid::{Dimensions, Grid};
+use alacritty_terminal::index::{Column, Line, Point};
+use alacritty_terminal::term::cell::{Cell, Flags};
+use alacritty_terminal::vte::ansi::{Color, Rgb};
+use log::{debug, warn};
+use parking_lot::Mutex;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Shortest time between two firings of one notify or run trigger
+/// Matches in between belong to the same burst and are dropped
+pub const MIN_TRIGGER_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Most hits a pane holds until the app takes them
+pub const MAX_PENDING_TRIGGER_HITS: usize = 32;
+
+/// What a trigger does with a match
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TriggerAction {
+    /// Color the background behind the match
+    Highlight,
+    /// Post a desktop notification with the line
+    Notify,
+    /// Run `command` through the shell
+    Run,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TriggerConfig {
+    pub regex: String,
+    pub action: TriggerAction,
+    /// Highlight background (RGBA, 0.0-1.0; alpha is ignored)
+    #[serde(default = "default_highlight_color")]
+    pub color: [f32; 4],
+    /// Shell command for `run`; $SATERNAL_MATCH and $SATERNAL_LINE hold the match and its line
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+}
+
+fn default_highlight_color() -> [f32; 4] {
+    [0.55, 0.45, 0.0, 1.0]
+}
+
+/// A notify or run trigger that fired
+#[derive(Debug, Clone, PartialEq)]
+pub struct TriggerHit {
+    /// Index of the trigger in the set
+    pub trigger: usize,
+    pub action: TriggerAction,
+    /// Matched text
+    pub matched: String,
+    /// Whole line the match was found in
+    pub line: String,
+    pub command: Option<String>,
+}
+
+struct Trigger {
+    regex: Regex,
+    action: TriggerAction,
+    color: Rgb,
+    command: Option<String>,
+}
+
+/// Compiled triggers, shared by every pane
+#[derive(Default)]
+pub struct TriggerSet {
+    triggers: Vec<Trigger>,
+    /// When each trigger last fired, in any pane
+    last_fired: Mutex<Vec<Option<Instant>>>,
+}
+
+impl TriggerSet {
+    /// Compile triggers from config; invalid ones are logged and skipped
+    pub fn new(configs: &[TriggerConfig]) -> Self {
+        let triggers = configs
+            .iter()
+            .filter_map(|config| {
+                let regex = Regex::new(&config.regex)
+                    .map_err(|e| warn!("Skipping trigger '{}': {}", config.regex, e))
+                    .ok()?;
+                if config.action == TriggerAction::Run && config.command.is_none() {
+                    warn!("Skipping run trigger '{}': no command", config.regex);
+                    return None;
+                }
+                let channel = |i: usize| (config.color[i].clamp(0.0, 1.0) * 255.0).round() as u8;
+                Some(Trigger {
+                    regex,
+                    action: config.action,
+                    color: Rgb { r: channel(0), g: channel(1), b: channel(2) },
+                    command: config.command.clone(),
+                })
+            })
+            .collect::<Vec<_>>();
+        let last_fired = Mutex::new(vec![None; triggers.len()]);
+        Self { triggers, last_fired }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.triggers.is_empty()
+    }
+
+    /// Match the line just completed above the cursor
+    /// Highlights are applied to the grid; notify and run hits are returned,
+    /// except for triggers that fired less than MIN_TRIGGER_INTERVAL before `now`
+    pub fn apply(&self, grid: &mut Grid<Cell>, now: Instant) -> Vec<TriggerHit> {
+        let Some((text, cells)) = completed_line(grid) else {
+            return Vec::new();
+        };
+        let mut hits = Vec::new();
+        for (index, trigger) in self.triggers.iter().enumerate() {
+            match trigger.action {
+                TriggerAction::Highlight => {
+                    for found in trigger.regex.find_iter(&text).filter(|found| !found.is_empty()) {
+                        for (offset, point) in &cells {
+                            if found.range().contains(offset) {
+                                grid[*point].bg = Color::Spec(trigger.color);
+                            }
+                        }
+                    }
+                }
+                action => {
+                    if let Some(found) = trigger.regex.find(&text).filter(|found| !found.is_empty()) {
+                        if !self.take_turn(index, now) {
+                            debug!("Trigger '{}' fired recently; skipping this match", trigger.regex);
+                            continue;
+                        }
+                        hits.push(TriggerHit {
+                            trigger: index,
+                            action,
+                            matched: found.as_str().to_string(),
+                            line: text.clone(),
+                            command: trigger.command.clone(),
+                        });
+                    }
+                }
+            }
+        }
+        hits
+    }
+
+    /// Whether trigger `index` may fire at `now`, recording the firing if so
+    fn take_turn(&self, index: usize, now: Instant) -> bool {
+        let mut last_fired = self.last_fired.lock();
+        let Some(last) = last_fired.get_mut(index) else {
+            return false;
+        };
+        if last.is_some_and(|at| now.saturating_duration_since(at) < MIN_TRIGGER_INTERVAL) {
+            return false;
+        }
+        *last = Some(now);
+        true
+    }
+}
+
+/// Add `hits` to those a pane holds for the app
+/// A newer hit of a trigger replaces the one still waiting, and past
+/// MAX_PENDING_TRIGGER_HITS the rest are dropped
+pub fn queue_hits(pending: &mut Vec<TriggerHit>, hits: Vec<TriggerHit>) {
+    for hit in hits {
+        if let Some(waiting) = pending.iter_mut().find(|waiting| waiting.trigger == hit.trigger) {
+            *waiting = hit;
+        } else if pending.len() < MAX_PENDING_TRIGGER_HITS {
+            pending.push(hit);
+        }
+    }
+}
+
+/// Text of the line above the cursor (joined with the rows it wrapped from),
+/// with the byte offset and grid position of each cell
+fn completed_line(grid: &Grid<Cell>) -> Option<(String, Vec<(usize, Point)>)> {
+    let oldest = -(grid.history_size() as i32);
+    let end_line = grid.cursor.point.line.0 - 1;
+    if end_line < oldest {
+        return None;
+    }
+
+    let last_col = Column(grid.columns() - 1);
+    let mut start_line = end_line;
+    while start_line > oldest
+        && grid[Point::new(Line(start_line - 1), last_col)].flags.contains(Flags::WRAPLINE)
+    {
+        start_line -= 1;
+    }
+
+    let mut text = String::new();
+    let mut cells = Vec::new();
+    for line in start_line..=end_line {
+        for col in 0..grid.columns() {
+            let point = Point::new(Line(line), Column(col));
+            let cell = &grid[point];
+            if !cell.flags.contains(Flags::WIDE_CHAR_SPACER) {
+                cells.push((text.len(), point));
+                text.push(if cell.c == '\0' { ' ' } else { cell.c });
+            }
+        }
+    }
+    text.truncate(text.trim_end().len());
+    cells.retain(|(offset, _)| *offset < text.len());
+    Some((text, cells))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid_with_lines(lines: &[&str]) -> Grid<Cell> {
+        let mut grid: Grid<Cell> = Grid::new(4, 10, 0);
+        for (line, text) in lines.iter().enumerate() {
+            for (col, c) in text.chars().enumerate() {
+                grid[Point::new(Line(line as i32), Column(col))].c = c;
+            }
+        }
+        grid.cursor.point = Point::new(Line(lines.len() as i32), Column(0));
+        grid
+    }
+
+    fn trigger(regex: &str, action: TriggerAction) -> TriggerConfig {
+        TriggerConfig {
+            regex: regex.to_string(),
+            action,
+            color: [1.0, 0.0, 0.0, 1.0],
+            command: (action == TriggerAction::Run).then(|| "true".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_highlights_matches_on_the_completed_line() {
+        // "error: x" wraps from row 1 into row 2
+        let mut grid = grid_with_lines(&["error: old", "   error: ", "x"]);
+        grid[Point::new(Line(1), Column(9))].flags.insert(Flags::WRAPLINE);
+        let triggers = TriggerSet::new(&[trigger("error:", TriggerAction::Highlight)]);
+
+        assert!(triggers.apply(&mut grid, Instant::now()).is_empty());
+        let red = Color::Spec(Rgb { r: 255, g: 0, b: 0 });
+        let highlighted: Vec<_> = (0..3)
+            .flat_map(|line| (0..10).map(move |col| Point::new(Line(line), Column(col))))
+            .filter(|point| grid[*point].bg == red)
+            .collect();
+        let expected: Vec<_> = (3..9).map(|col| Point::new(Line(1), Column(col))).collect();
+        assert_eq!(highlighted, expected);
+    }
+
+    #[test]
+    fn test_notify_and_run_hits() {
+        let mut grid = grid_with_lines(&["ok", "3 failed"]);
+        let triggers = TriggerSet::new(&[
+            trigger("fail", TriggerAction::Notify),
+            trigger(r"\d+ failed", TriggerAction::Run),
+            trigger("(unclosed", TriggerAction::Notify),
+        ]);
+
+        let hits = triggers.apply(&mut grid, Instant::now());
+        assert_eq!(hits.len(), 2);
+        assert_eq!((hits[0].action, hits[0].matched.as_str()), (TriggerAction::Notify, "fail"));
+        assert_eq!((hits[1].action, hits[1].line.as_str()), (TriggerAction::Run, "3 failed"));
+        assert_eq!(hits[1].command.as_deref(), Some("true"));
+    }
+
+    #[test]
+    fn test_busy_output_fires_once_per_interval() {
+        let mut grid = grid_with_lines(&["3 failed"]);
+        let triggers = TriggerSet::new(&[trigger("failed", TriggerAction::Run)]);
+        let start = Instant::now();
+
+        let mut pending = Vec::new();
+        for line in 0..100 {
+            let now = start + Duration::from_millis(line * 10);
+            queue_hits(&mut pending, triggers.apply(&mut grid, now));
+        }
+        assert_eq!(pending.len(), 1);
+
+        // The burst is still going; a later one fires again
+        assert!(triggers.apply(&mut grid, start + MIN_TRIGGER_INTERVAL / 2).is_empty());
+        let later = triggers.apply(&mut grid, start + MIN_TRIGGER_INTERVAL);
+        assert_eq!(later.len(), 1);
+
+        // Waiting hits of one trigger collapse into the newest
+        queue_hits(&mut pending, later);
+        assert_eq!(pending.len(), 1);
+    }
+}
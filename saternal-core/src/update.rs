@@ -0,0 +1,751 @@
+/// Automatic updates: check a release feed, download and verify the new
+/// version, and swap it in the next time Saternal starts
+///
+/// The feed is a JSON description of the latest release, in the spirit of a
+/// Sparkle appcast. Every archive is a zip checked against its SHA-256 and an
+/// Ed25519 signature from the release key baked in at build time. A release
+/// can also list deltas from earlier versions: zips holding only the files that
+/// changed (plus a list of removed ones), unpacked over a copy of the running
+/// bundle. A verified bundle is staged in the cache and installed on relaunch;
+/// the signed zip is kept beside it and verified and unpacked again before the
+/// install, and the bundle's own Info.plist must carry the feed's version.
+use anyhow::{anyhow, bail, Context, Result};
+use base64::Engine;
+use ed25519_dalek::{Signature, VerifyingKey};
+use log::{info, warn};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::cmp::Ordering;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::platform::{user_dir, UserDir};
+
+/// Base64 Ed25519 key that release archives are signed with, set when building releases
+const PUBLIC_KEY: Option<&str> = option_env!("SATERNAL_UPDATE_PUBLIC_KEY");
+
+/// Names the version whose bundle is staged for the next launch
+const STAGED_MARKER: &str = "staged";
+
+/// Name of the bundle inside a full archive and in the staging directory
+const BUNDLE_NAME: &str = "Saternal.app";
+
+/// The verified zip, kept in the version's staging directory
+const STAGED_ARCHIVE: &str = "update.zip";
+
+/// What the staged zip was verified against
+const STAGED_RECORD: &str = "update.json";
+
+/// Paths a delta removes from the bundle, one per line
+const DELTA_REMOVALS: &str = ".saternal-delta-removed";
+
+/// Wait after launch before the first automatic check
+const FIRST_CHECK_DELAY: Duration = Duration::from_secs(30);
+
+/// Give up on a feed or archive download after this many seconds
+const DOWNLOAD_TIMEOUT_SECS: &str = "600";
+
+/// Set when the app should start again (and install the staged update) once it exits
+static RELAUNCH: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UpdateConfig {
+    /// Check the feed shortly after launch and then every `check_interval_hours`
+    pub auto_check: bool,
+    pub feed_url: String,
+    pub check_interval_hours: u64,
+}
+
+impl Default for UpdateConfig {
+    fn default() -> Self {
+        Self {
+            auto_check: true,
+            feed_url: "https://github.com/nextbysam/Saternal/releases/latest/download/feed.json".to_string(),
+            check_interval_hours: 24,
+        }
+    }
+}
+
+/// The latest release, as described by the feed
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Release {
+    pub version: String,
+    #[serde(default)]
+    pub notes: Option<String>,
+    /// Zip of the whole Saternal.app
+    #[serde(flatten)]
+    pub archive: Archive,
+    #[serde(default)]
+    pub deltas: Vec<Delta>,
+}
+
+/// A downloadable zip and what it must hash and verify to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Archive {
+    pub url: String,
+    /// Hex SHA-256 of the zip
+    pub sha256: String,
+    /// Base64 Ed25519 signature of the zip
+    pub signature: String,
+}
+
+/// Only the files that changed since `from`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Delta {
+    pub from: String,
+    #[serde(flatten)]
+    pub archive: Archive,
+}
+
+impl Release {
+    pub fn parse(json: &str) -> Result<Self> {
+        serde_json::from_str(json).context("malformed update feed")
+    }
+
+    /// The delta from `current` when the release has one
+    pub fn delta_from(&self, current: &str) -> Option<&Archive> {
+        self.deltas
+            .iter()
+            .find(|delta| compare_versions(&delta.from, current) == Ordering::Equal)
+            .map(|delta| &delta.archive)
+    }
+}
+
+/// Compare dotted versions numerically ("0.10.0" > "0.9.2"; a leading "v" is ignored)
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let (a, b) = (version_parts(a), version_parts(b));
+    let len = a.len().max(b.len());
+    let part = |parts: &[u64], i: usize| parts.get(i).copied().unwrap_or(0);
+    (0..len)
+        .map(|i| part(&a, i).cmp(&part(&b, i)))
+        .find(|order| order.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// Refuse a release version that isn't plain dotted numbers ("0.2.0")
+///
+/// The feed isn't signed, the staged marker is just a file in the cache, and
+/// the version names the staging directory, so anything else (separators,
+/// `..`) could point outside the Updates cache.
+fn check_release_version(version: &str) -> Result<()> {
+    let numeric = version.split('.').all(|field| !field.is_empty() && field.chars().all(|c| c.is_ascii_digit()));
+    let mut components = Path::new(version).components();
+    let single = matches!((components.next(), components.next()), (Some(Component::Normal(_)), None));
+    if !numeric || !single {
+        bail!("invalid release version {:?}", version);
+    }
+    Ok(())
+}
+
+/// Numeric fields of a version, each cut at its first non-digit ("1.2.0-beta" = [1, 2, 0])
+fn version_parts(version: &str) -> Vec<u64> {
+    version
+        .trim()
+        .trim_start_matches('v')
+        .split('.')
+        .map(|field| {
+            let digits: String = field.chars().take_while(char::is_ascii_digit).collect();
+            digits.parse().unwrap_or(0)
+        })
+        .collect()
+}
+
+/// Check a download against the archive's hash and signature
+pub fn verify(data: &[u8], archive: &Archive, key: &VerifyingKey) -> Result<()> {
+    let digest: String = Sha256::digest(data).iter().map(|byte| format!("{:02x}", byte)).collect();
+    if !digest.eq_ignore_ascii_case(archive.sha256.trim()) {
+        bail!("checksum mismatch for {}", archive.url);
+    }
+    let signature = base64::engine::general_purpose::STANDARD
+        .decode(archive.signature.trim())
+        .ok()
+        .and_then(|bytes| Signature::from_slice(&bytes).ok())
+        .ok_or_else(|| anyhow!("malformed signature for {}", archive.url))?;
+    key.verify_strict(data, &signature)
+        .map_err(|_| anyhow!("bad signature for {}", archive.url))
+}
+
+/// The release signing key this build trusts
+fn release_key() -> Result<VerifyingKey> {
+    let encoded = PUBLIC_KEY.ok_or_else(|| anyhow!("this build has no update signing key"))?;
+    parse_key(encoded)
+}
+
+fn parse_key(encoded: &str) -> Result<VerifyingKey> {
+    let bytes = base64::engine::general_purpose::STANDARD.decode(encoded.trim())?;
+    let bytes: [u8; 32] = bytes.try_into().map_err(|_| anyhow!("update signing key must be 32 bytes"))?;
+    Ok(VerifyingKey::from_bytes(&bytes)?)
+}
+
+/// `Updates` in the per-user cache directory (`~/Library/Caches/Saternal` on
+/// macOS), where updates are downloaded and staged
+pub fn cache_dir() -> Option<PathBuf> {
+    user_dir(UserDir::Cache).map(|dir| dir.join("Updates"))
+}
+
+/// The .app the running binary belongs to (None for `cargo run` and other bare binaries)
+pub fn current_bundle() -> Option<PathBuf> {
+    bundle_of(&std::env::current_exe().ok()?)
+}
+
+/// `X.app` for `X.app/Contents/MacOS/<binary>`
+fn bundle_of(exe: &Path) -> Option<PathBuf> {
+    let bundle = exe.parent()?.parent()?.parent()?;
+    (bundle.extension() == Some(OsStr::new("app"))).then(|| bundle.to_path_buf())
+}
+
+/// What a check found, for the event loop to show
+#[derive(Debug, Clone, PartialEq)]
+pub enum UpdateEvent {
+    /// Only reported for checks asked for from the menu
+    UpToDate,
+    /// Verified and staged; installed on the next launch
+    Ready { version: String },
+    /// Only reported for checks asked for from the menu
+    Failed(String),
+}
+
+/// Checks the feed on background threads and reports back to the event loop
+pub struct Updater {
+    events: Receiver<UpdateEvent>,
+    shared: Arc<Shared>,
+}
+
+/// State the check threads share with the updater
+struct Shared {
+    events: Sender<UpdateEvent>,
+    wake: Mutex<Box<dyn Fn() + Send>>,
+    /// A check is running
+    busy: AtomicBool,
+    /// Version staged for the next launch
+    ready: Mutex<Option<String>>,
+    feed_url: String,
+    dir: PathBuf,
+}
+
+impl Updater {
+    /// Start checking in the background when `auto_check` is on; `wake` runs after each finding
+    pub fn start(config: &UpdateConfig, dir: PathBuf, wake: impl Fn() + Send + 'static) -> Self {
+        let (sender, events) = mpsc::channel();
+        let shared = Arc::new(Shared {
+            events: sender,
+            wake: Mutex::new(Box::new(wake)),
+            busy: AtomicBool::new(false),
+            ready: Mutex::new(staged_version(&dir)),
+            feed_url: config.feed_url.clone(),
+            dir,
+        });
+
+        if config.auto_check {
+            let interval = Duration::from_secs(config.check_interval_hours.max(1) * 3600);
+            let shared = shared.clone();
+            std::thread::Builder::new()
+                .name("update-check".to_string())
+                .spawn(move || {
+                    std::thread::sleep(FIRST_CHECK_DELAY);
+                    loop {
+                        shared.check(false);
+                        std::thread::sleep(interval);
+                    }
+                })
+                .map_err(|e| warn!("Failed to start update checks: {}", e))
+                .ok();
+        }
+
+        Self { events, shared }
+    }
+
+    /// Check right away (from the menu), reporting "up to date" and failures too
+    pub fn check_now(&self) {
+        let shared = self.shared.clone();
+        std::thread::spawn(move || shared.check(true));
+    }
+
+    pub fn is_checking(&self) -> bool {
+        self.shared.busy.load(AtomicOrdering::Acquire)
+    }
+
+    /// Version waiting to be installed on relaunch
+    pub fn ready_version(&self) -> Option<String> {
+        self.shared.ready.lock().clone()
+    }
+
+    pub fn try_recv(&self) -> Option<UpdateEvent> {
+        self.events.try_recv().ok()
+    }
+}
+
+impl Shared {
+    fn check(&self, manual: bool) {
+        if self.busy.swap(true, AtomicOrdering::AcqRel) {
+            return;
+        }
+        let result = self.fetch_and_stage();
+        self.busy.store(false, AtomicOrdering::Release);
+
+        let event = match result {
+            Ok(Some(version)) => {
+                let already_ready = self.ready.lock().replace(version.clone()).as_ref() == Some(&version);
+                // Automatic checks mention a staged update once
+                if already_ready && !manual {
+                    return;
+                }
+                UpdateEvent::Ready { version }
+            }
+            Ok(None) if manual => UpdateEvent::UpToDate,
+            Ok(None) => return,
+            Err(e) => {
+                warn!("Update check failed: {:#}", e);
+                if !manual {
+                    return;
+                }
+                UpdateEvent::Failed(format!("{:#}", e))
+            }
+        };
+        let _ = self.events.send(event);
+        (self.wake.lock())();
+    }
+
+    /// Download, verify, and stage the feed's release if it is newer; returns its version
+    fn fetch_and_stage(&self) -> Result<Option<String>> {
+        let current = env!("CARGO_PKG_VERSION");
+        let feed = download(&self.feed_url)?;
+        let release = Release::parse(&String::from_utf8_lossy(&feed))?;
+        check_release_version(&release.version)?;
+        if compare_versions(&release.version, current) != Ordering::Greater {
+            info!("Saternal {} is up to date (feed has {})", current, release.version);
+            return Ok(None);
+        }
+        if staged_version(&self.dir).as_deref() == Some(release.version.as_str()) {
+            return Ok(Some(release.version));
+        }
+
+        let key = release_key()?;
+        let bundle = current_bundle().ok_or_else(|| anyhow!("not running from {}", BUNDLE_NAME))?;
+
+        if let Some(delta) = release.delta_from(current) {
+            info!("Downloading Saternal {} (delta from {})", release.version, current);
+            let staged = download(&delta.url)
+                .and_then(|data| verify(&data, delta, &key).map(|()| data))
+                .and_then(|data| stage(&self.dir, &release.version, &data, delta, Some(&bundle)));
+            match staged {
+                Ok(_) => return Ok(Some(release.version)),
+                // The bundle may have been changed by hand; the full archive still works
+                Err(e) => warn!("Delta update failed, downloading the full archive: {:#}", e),
+            }
+        }
+
+        info!("Downloading Saternal {}", release.version);
+        let data = download(&release.archive.url)?;
+        verify(&data, &release.archive, &key)?;
+        stage(&self.dir, &release.version, &data, &release.archive, None)?;
+        Ok(Some(release.version))
+    }
+}
+
+/// Fetch `url` with the system curl
+fn download(url: &str) -> Result<Vec<u8>> {
+    let output = Command::new("curl")
+        .args(["--fail", "--silent", "--show-error", "--location", "--max-time", DOWNLOAD_TIMEOUT_SECS])
+        .arg(url)
+        .stdin(Stdio::null())
+        .output()
+        .context("running curl")?;
+    if !output.status.success() {
+        bail!("downloading {}: {}", url, String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(output.stdout)
+}
+
+/// Run a command that only matters for its exit status
+fn run(command: &mut Command) -> Result<()> {
+    let output = command.stdin(Stdio::null()).output()?;
+    if !output.status.success() {
+        bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+/// What a staged zip was verified against, so it can be verified again before installing
+#[derive(Debug, Serialize, Deserialize)]
+struct StagedArchive {
+    archive: Archive,
+    /// Unpacked over a copy of the running bundle
+    delta: bool,
+}
+
+/// Unpack a verified archive into `dir/<version>/Saternal.app` and mark it staged
+///
+/// A full archive holds Saternal.app itself; a delta (`base` set) is unpacked
+/// over a copy of `base`. The zip and what it was verified against stay in
+/// `dir/<version>` for `install_staged`.
+fn stage(dir: &Path, version: &str, data: &[u8], archive: &Archive, base: Option<&Path>) -> Result<PathBuf> {
+    check_release_version(version)?;
+    let version_dir = dir.join(version);
+    if version_dir.exists() {
+        fs::remove_dir_all(&version_dir)?;
+    }
+    fs::create_dir_all(&version_dir)?;
+    fs::write(version_dir.join(STAGED_ARCHIVE), data)?;
+    let record = StagedArchive { archive: archive.clone(), delta: base.is_some() };
+    fs::write(version_dir.join(STAGED_RECORD), serde_json::to_vec(&record)?)?;
+
+    let bundle = match unpack(&version_dir, base).and_then(|bundle| check_bundle_version(&bundle, version).map(|()| bundle)) {
+        Ok(bundle) => bundle,
+        Err(e) => {
+            let _ = fs::remove_dir_all(&version_dir);
+            return Err(e);
+        }
+    };
+
+    fs::write(dir.join(STAGED_MARKER), version)?;
+    info!("Saternal {} is staged in {}", version, bundle.display());
+    Ok(bundle)
+}
+
+/// Unpack `version_dir`'s zip into `version_dir/Saternal.app`, replacing what was there
+fn unpack(version_dir: &Path, base: Option<&Path>) -> Result<PathBuf> {
+    let archive = version_dir.join(STAGED_ARCHIVE);
+    let bundle = version_dir.join(BUNDLE_NAME);
+    if bundle.symlink_metadata().is_ok() {
+        fs::remove_dir_all(&bundle)?;
+    }
+
+    // ditto keeps the bundle's permissions, symlinks, and extended attributes
+    match base {
+        Some(base) => {
+            run(Command::new("ditto").arg(base).arg(&bundle)).context("copying the running bundle")?;
+            run(Command::new("ditto").args(["-x", "-k"]).arg(&archive).arg(&bundle)).context("unpacking the delta")?;
+            apply_removals(&bundle)?;
+        }
+        None => {
+            run(Command::new("ditto").args(["-x", "-k"]).arg(&archive).arg(version_dir)).context("unpacking the update")?;
+        }
+    }
+    if !bundle.join("Contents").is_dir() {
+        bail!("update in {} has no {}", version_dir.display(), BUNDLE_NAME);
+    }
+    Ok(bundle)
+}
+
+/// Verify the kept zip again and unpack it afresh, ignoring whatever bundle is in the cache
+///
+/// A delta is unpacked over `base`, the bundle about to be replaced.
+fn restage(version_dir: &Path, version: &str, base: &Path, key: &VerifyingKey) -> Result<PathBuf> {
+    let record: StagedArchive = serde_json::from_slice(&fs::read(version_dir.join(STAGED_RECORD))?)
+        .context("malformed staged update record")?;
+    let data = fs::read(version_dir.join(STAGED_ARCHIVE))?;
+    verify(&data, &record.archive, key)?;
+    let bundle = unpack(version_dir, record.delta.then_some(base))?;
+    check_bundle_version(&bundle, version)?;
+    Ok(bundle)
+}
+
+/// Fail unless the bundle is `version`
+///
+/// The signature covers the zip but not the feed's version, so this keeps an
+/// older signed archive from being served as an update.
+fn check_bundle_version(bundle: &Path, version: &str) -> Result<()> {
+    let found = bundle_version(bundle)?;
+    if compare_versions(&found, version) != Ordering::Equal {
+        bail!("update to {} holds Saternal {}", version, found);
+    }
+    Ok(())
+}
+
+/// CFBundleShortVersionString from the bundle's (XML) Info.plist
+fn bundle_version(bundle: &Path) -> Result<String> {
+    let plist = fs::read_to_string(bundle.join("Contents/Info.plist")).context("reading Info.plist")?;
+    plist_string(&plist, "CFBundleShortVersionString")
+        .ok_or_else(|| anyhow!("Info.plist has no CFBundleShortVersionString"))
+}
+
+/// The `<string>` following `<key>{key}</key>` in an XML property list
+fn plist_string(plist: &str, key: &str) -> Option<String> {
+    let (_, rest) = plist.split_once(&format!("<key>{}</key>", key))?;
+    let (value, _) = rest.trim_start().strip_prefix("<string>")?.split_once("</string>")?;
+    Some(value.trim().to_string())
+}
+
+/// Delete the files a delta lists as removed, then the list itself
+fn apply_removals(bundle: &Path) -> Result<()> {
+    let list = bundle.join(DELTA_REMOVALS);
+    let Ok(contents) = fs::read_to_string(&list) else {
+        return Ok(());
+    };
+    for line in contents.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let relative = Path::new(line);
+        // Never reach outside the bundle
+        if !relative.components().all(|component| matches!(component, Component::Normal(_))) {
+            bail!("delta removes a path outside the bundle: {}", line);
+        }
+        let path = bundle.join(relative);
+        if path.is_dir() {
+            fs::remove_dir_all(&path)?;
+        } else if path.symlink_metadata().is_ok() {
+            fs::remove_file(&path)?;
+        }
+    }
+    fs::remove_file(&list)?;
+    Ok(())
+}
+
+/// Version staged for the next launch, if any
+/// A marker that isn't a plain version is ignored before it names any path
+pub fn staged_version(dir: &Path) -> Option<String> {
+    let version = fs::read_to_string(dir.join(STAGED_MARKER)).ok()?;
+    let version = version.trim();
+    check_release_version(version).ok()?;
+    dir.join(version).join(BUNDLE_NAME).is_dir().then(|| version.to_string())
+}
+
+/// Replace `bundle` with the staged update if it is newer than `current`
+///
+/// The staged bundle isn't trusted as found: its zip is verified again and
+/// unpacked afresh first. Returns the installed version. The marker is cleared
+/// first, so a failing install isn't retried on every launch; a failed swap
+/// puts the old bundle back.
+pub fn install_staged(dir: &Path, bundle: &Path, current: &str) -> Result<Option<String>> {
+    let Some(version) = staged_version(dir) else {
+        return Ok(None);
+    };
+    let _ = fs::remove_file(dir.join(STAGED_MARKER));
+    let version_dir = dir.join(&version);
+    let installed = if compare_versions(&version, current) == Ordering::Greater {
+        release_key()
+            .and_then(|key| restage(&version_dir, &version, bundle, &key))
+            .and_then(|staged| swap_in(&staged, bundle))
+            .map(|()| Some(version))
+    } else {
+        Ok(None)
+    };
+    let _ = fs::remove_dir_all(&version_dir);
+    installed
+}
+
+/// Move `bundle` aside and `staged` into its place, putting it back if that fails
+fn swap_in(staged: &Path, bundle: &Path) -> Result<()> {
+    let previous = bundle.with_extension("app-previous");
+    if previous.exists() {
+        fs::remove_dir_all(&previous)?;
+    }
+    fs::rename(bundle, &previous).with_context(|| format!("moving {} aside", bundle.display()))?;
+    if let Err(e) = fs::rename(staged, bundle) {
+        fs::rename(&previous, bundle)?;
+        return Err(e).with_context(|| format!("installing {}", staged.display()));
+    }
+    let _ = fs::remove_dir_all(&previous);
+    Ok(())
+}
+
+/// Start again once the event loop exits, to install the staged update
+pub fn relaunch_on_exit() {
+    RELAUNCH.store(true, AtomicOrdering::Release);
+}
+
+pub fn relaunch_requested() -> bool {
+    RELAUNCH.load(AtomicOrdering::Acquire)
+}
+
+/// Replace this process with a fresh start of the (possibly just updated) binary
+///
+/// Only returns if that fails.
+#[cfg(unix)]
+pub fn relaunch() -> std::io::Error {
+    use std::os::unix::process::CommandExt;
+
+    match std::env::current_exe() {
+        Ok(exe) => Command::new(exe).args(std::env::args_os().skip(1)).exec(),
+        Err(e) => e,
+    }
+}
+
+/// Windows has no exec: start the new copy, then exit
+#[cfg(windows)]
+pub fn relaunch() -> std::io::Error {
+    let started = std::env::current_exe().and_then(|exe| Command::new(exe).args(std::env::args_os().skip(1)).spawn());
+    match started {
+        Ok(_) => std::process::exit(0),
+        Err(e) => e,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    fn signed_archive(data: &[u8], key: &SigningKey) -> Archive {
+        let sha256 = Sha256::digest(data).iter().map(|byte| format!("{:02x}", byte)).collect();
+        let signature = base64::engine::general_purpose::STANDARD.encode(key.sign(data).to_bytes());
+        Archive { url: "https://example.com/Saternal.zip".to_string(), sha256, signature }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("saternal-update-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_version_order() {
+        assert_eq!(compare_versions("0.10.0", "0.9.2"), Ordering::Greater);
+        assert_eq!(compare_versions("v1.2", "1.2.0"), Ordering::Equal);
+        assert_eq!(compare_versions("1.2.0-beta", "1.2.1"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_feed_picks_matching_delta() {
+        let release = Release::parse(
+            r#"{
+                "version": "0.3.0",
+                "url": "https://example.com/Saternal-0.3.0.zip",
+                "sha256": "00",
+                "signature": "",
+                "deltas": [
+                    { "from": "0.2.0", "url": "https://example.com/0.2.0-0.3.0.zip", "sha256": "01", "signature": "" }
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(release.archive.url, "https://example.com/Saternal-0.3.0.zip");
+        assert_eq!(release.delta_from("0.2.0").unwrap().sha256, "01");
+        assert_eq!(release.delta_from("0.1.0"), None);
+    }
+
+    #[test]
+    fn test_verify_rejects_tampering() {
+        let signing = SigningKey::from_bytes(&[7; 32]);
+        let key = parse_key(&base64::engine::general_purpose::STANDARD.encode(signing.verifying_key().to_bytes())).unwrap();
+        let archive = signed_archive(b"bundle", &signing);
+        assert!(verify(b"bundle", &archive, &key).is_ok());
+        assert!(verify(b"bundle!", &archive, &key).is_err());
+
+        // Right checksum, signed by someone else
+        let forged = Archive { signature: signed_archive(b"bundle", &SigningKey::from_bytes(&[8; 32])).signature, ..archive };
+        assert!(verify(b"bundle", &forged, &key).is_err());
+    }
+
+    #[test]
+    fn test_bundle_of_binary() {
+        let exe = Path::new("/Applications/Saternal.app/Contents/MacOS/saternal");
+        assert_eq!(bundle_of(exe), Some(PathBuf::from("/Applications/Saternal.app")));
+        assert_eq!(bundle_of(Path::new("/src/saternal/target/debug/saternal")), None);
+    }
+
+    #[test]
+    fn test_delta_removals_stay_inside_bundle() {
+        let bundle = temp_dir("removals");
+        fs::create_dir_all(bundle.join("Contents/Resources/old")).unwrap();
+        fs::write(bundle.join("Contents/Resources/stale.png"), "").unwrap();
+        fs::write(bundle.join(DELTA_REMOVALS), "Contents/Resources/old\nContents/Resources/stale.png\n").unwrap();
+        apply_removals(&bundle).unwrap();
+        assert!(!bundle.join("Contents/Resources/old").exists());
+        assert!(!bundle.join("Contents/Resources/stale.png").exists());
+        assert!(!bundle.join(DELTA_REMOVALS).exists());
+
+        fs::write(bundle.join(DELTA_REMOVALS), "../outside\n").unwrap();
+        assert!(apply_removals(&bundle).is_err());
+        fs::remove_dir_all(&bundle).unwrap();
+    }
+
+    #[test]
+    fn test_traversal_versions_are_refused() {
+        assert!(check_release_version("0.10.2").is_ok());
+        for version in ["9.9.9/../../..", "..", "1..2", "", "/9.9.9", "9.9.9\\..", "v1.0.0", "1.0.0-beta"] {
+            assert!(check_release_version(version).is_err(), "{:?}", version);
+        }
+
+        // Refused before anything is written or removed
+        let dir = std::env::temp_dir().join(format!("saternal-update-traversal-{}", std::process::id()));
+        let signing = SigningKey::from_bytes(&[7; 32]);
+        let archive = signed_archive(b"bundle", &signing);
+        assert!(stage(&dir, "9.9.9/../../..", b"bundle", &archive, None).is_err());
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn test_bundle_version_from_info_plist() {
+        let plist = r#"<plist version="1.0"><dict>
+            <key>CFBundleName</key>
+            <string>Saternal</string>
+            <key>CFBundleShortVersionString</key>
+            <string>0.2.0</string>
+        </dict></plist>"#;
+        assert_eq!(plist_string(plist, "CFBundleShortVersionString").as_deref(), Some("0.2.0"));
+        assert_eq!(plist_string(plist, "CFBundleVersion"), None);
+
+        let bundle = temp_dir("plist").join(BUNDLE_NAME);
+        fs::create_dir_all(bundle.join("Contents")).unwrap();
+        fs::write(bundle.join("Contents/Info.plist"), plist).unwrap();
+        assert!(check_bundle_version(&bundle, "0.2.0").is_ok());
+        // An older signed archive passed off as the feed's version
+        assert!(check_bundle_version(&bundle, "0.3.0").is_err());
+        fs::remove_dir_all(bundle.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_restage_verifies_kept_archive() {
+        let signing = SigningKey::from_bytes(&[7; 32]);
+        let version_dir = temp_dir("restage");
+        let record = StagedArchive { archive: signed_archive(b"bundle", &signing), delta: false };
+        fs::write(version_dir.join(STAGED_RECORD), serde_json::to_vec(&record).unwrap()).unwrap();
+        // Swapped after staging
+        fs::write(version_dir.join(STAGED_ARCHIVE), b"tampered").unwrap();
+
+        let base = version_dir.join("base.app");
+        let err = restage(&version_dir, "0.2.0", &base, &signing.verifying_key()).unwrap_err();
+        assert!(err.to_string().contains("checksum mismatch"));
+        fs::remove_dir_all(&version_dir).unwrap();
+    }
+
+    #[test]
+    fn test_install_swaps_in_staged_bundle() {
+        let root = temp_dir("install");
+        let dir = root.join("Updates");
+        let bundle = root.join("Applications").join(BUNDLE_NAME);
+        fs::create_dir_all(bundle.join("Contents")).unwrap();
+        fs::write(bundle.join("Contents/version"), "0.1.0").unwrap();
+        assert_eq!(install_staged(&dir, &bundle, "0.1.0").unwrap(), None);
+
+        let staged = dir.join("0.2.0").join(BUNDLE_NAME);
+        fs::create_dir_all(staged.join("Contents")).unwrap();
+        fs::write(staged.join("Contents/version"), "0.2.0").unwrap();
+        fs::write(dir.join(STAGED_MARKER), "0.2.0").unwrap();
+        assert_eq!(staged_version(&dir).as_deref(), Some("0.2.0"));
+
+        // A bundle dropped into the cache without its signed zip never goes in
+        assert!(install_staged(&dir, &bundle, "0.1.0").is_err());
+        assert_eq!(fs::read_to_string(bundle.join("Contents/version")).unwrap(), "0.1.0");
+        assert!(!dir.join("0.2.0").exists());
+        // Tried once
+        assert_eq!(staged_version(&dir), None);
+
+        fs::create_dir_all(staged.join("Contents")).unwrap();
+        fs::write(staged.join("Contents/version"), "0.2.0").unwrap();
+        swap_in(&staged, &bundle).unwrap();
+        assert_eq!(fs::read_to_string(bundle.join("Contents/version")).unwrap(), "0.2.0");
+        assert!(!bundle.with_extension("app-previous").exists());
+
+        // A planted marker naming a directory outside the cache is ignored
+        let outside = root.join(BUNDLE_NAME);
+        fs::create_dir_all(&outside).unwrap();
+        for marker in ["..", "../Applications", &root.display().to_string()] {
+            fs::write(dir.join(STAGED_MARKER), marker).unwrap();
+            assert_eq!(staged_version(&dir), None, "{:?}", marker);
+            assert_eq!(install_staged(&dir, &bundle, "0.1.0").unwrap(), None);
+        }
+        assert!(outside.is_dir());
+        assert!(bundle.is_dir());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
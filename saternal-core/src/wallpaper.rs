@@ -0,0 +1,281 @@
+/// How wallpapers are fitted to the window, and per-tab overrides
+use serde::{Deserialize, Serialize};
+
+/// How the wallpaper is fitted to the window
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WallpaperMode {
+    /// Scale to cover the window, cropping the overflow
+    #[default]
+    Fill,
+    /// Scale to fit inside the window, leaving bars
+    Fit,
+    /// Scale to the window size, ignoring aspect ratio
+    Stretch,
+    /// Repeat at the image's own size from the top-left
+    Tile,
+    /// Image's own size, centered
+    Center,
+}
+
+/// Mapping from surface UV to wallpaper UV (`uv * scale + offset`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WallpaperUv {
+    pub scale: [f32; 2],
+    pub offset: [f32; 2],
+    /// Wrap coordinates to repeat the image
+    pub tile: bool,
+    /// Draw no wallpaper outside the image rect
+    pub clip: bool,
+}
+
+impl Default for WallpaperUv {
+    fn default() -> Self {
+        Self { scale: [1.0, 1.0], offset: [0.0, 0.0], tile: false, clip: false }
+    }
+}
+
+/// Crops snap to this many steps across each side of the image
+const CROP_STEPS: u32 = 16;
+
+/// Texture scales snap to this many steps per doubling (about 9% apart)
+const SCALE_STEPS: f64 = 8.0;
+
+/// The part of the image a mode shows and how to upload and sample it
+///
+/// Crop and size are snapped to coarse steps, so surfaces of nearby sizes
+/// (a window being dragged larger) share one texture and only the UV changes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WallpaperFit {
+    /// Visible part of the image in image pixels, widened to the crop grid: x, y, width, height
+    pub crop: (u32, u32, u32, u32),
+    /// Texture size for the cropped part: never above the image's own
+    /// resolution, same aspect, within the GPU's limit
+    pub size: (u32, u32),
+    pub uv: WallpaperUv,
+}
+
+impl WallpaperMode {
+    pub fn by_name(name: &str) -> Option<Self> {
+        match name {
+            "fill" => Some(Self::Fill),
+            "fit" => Some(Self::Fit),
+            "stretch" => Some(Self::Stretch),
+            "tile" => Some(Self::Tile),
+            "center" => Some(Self::Center),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Fill => "fill",
+            Self::Fit => "fit",
+            Self::Stretch => "stretch",
+            Self::Tile => "tile",
+            Self::Center => "center",
+        }
+    }
+
+    /// Pixel size the image is drawn at on a `target`-sized surface
+    pub fn display_size(self, image: (u32, u32), target: (u32, u32)) -> (u32, u32) {
+        let scale_x = target.0 as f32 / image.0.max(1) as f32;
+        let scale_y = target.1 as f32 / image.1.max(1) as f32;
+        let scaled = |scale: f32| {
+            (
+                ((image.0 as f32 * scale).round() as u32).max(1),
+                ((image.1 as f32 * scale).round() as u32).max(1),
+            )
+        };
+        match self {
+            Self::Fill => scaled(scale_x.max(scale_y)),
+            Self::Fit => scaled(scale_x.min(scale_y)),
+            Self::Stretch => target,
+            Self::Tile | Self::Center => image,
+        }
+    }
+
+    /// Crop, texture size, and UV transform for an `image` on a `target`-sized surface
+    ///
+    /// Whatever is scaled or placed off the surface is cropped away. The texture
+    /// keeps the crop's aspect, isn't upscaled (the sampler does that), and is
+    /// scaled down so neither side exceeds `max_dimension`.
+    pub fn fit(self, image: (u32, u32), target: (u32, u32), max_dimension: u32) -> WallpaperFit {
+        let display = self.display_size(image, target);
+        let centered = matches!(self, Self::Fill | Self::Fit | Self::Center);
+        let (x, crop_w) = visible_span(image.0, display.0, target.0, centered);
+        let (y, crop_h) = visible_span(image.1, display.1, target.1, centered);
+
+        let texels = |crop: u32, display: u32, image: u32| {
+            ((crop as f64 * texel_scale(display as f64 / image.max(1) as f64)).ceil() as u32).max(1)
+        };
+        let (width, height) = (texels(crop_w, display.0, image.0), texels(crop_h, display.1, image.1));
+        let largest = width.max(height);
+        let size = if largest > max_dimension {
+            let shrink = |side: u32| ((side as u64 * max_dimension as u64 / largest as u64) as u32).max(1);
+            (shrink(width), shrink(height))
+        } else {
+            (width, height)
+        };
+
+        // Map onto the crop rather than the whole image
+        let mut uv = self.uv_transform(display, target);
+        for (axis, (start, length, whole)) in [(x, crop_w, image.0), (y, crop_h, image.1)].into_iter().enumerate() {
+            let zoom = whole.max(1) as f32 / length as f32;
+            uv.scale[axis] *= zoom;
+            uv.offset[axis] = uv.offset[axis] * zoom - start as f32 / length as f32;
+        }
+
+        WallpaperFit { crop: (x, y, crop_w, crop_h), size, uv }
+    }
+
+    /// UV transform for an image drawn at `display` size on a `target`-sized surface
+    pub fn uv_transform(self, display: (u32, u32), target: (u32, u32)) -> WallpaperUv {
+        let (display_w, display_h) = (display.0.max(1) as f32, display.1.max(1) as f32);
+        let (target_w, target_h) = (target.0 as f32, target.1 as f32);
+        // Top-left corner of the image in surface pixels
+        let origin = match self {
+            Self::Tile | Self::Stretch => (0.0, 0.0),
+            Self::Fill | Self::Fit | Self::Center => {
+                ((target_w - display_w) / 2.0, (target_h - display_h) / 2.0)
+            }
+        };
+        WallpaperUv {
+            scale: [target_w / display_w, target_h / display_h],
+            offset: [-origin.0 / display_w, -origin.1 / display_h],
+            tile: self == Self::Tile,
+            clip: matches!(self, Self::Fit | Self::Center),
+        }
+    }
+}
+
+/// Image start and length of the visible part of one axis, widened to the crop grid
+///
+/// The image is `image` pixels long, drawn `display` long on a `target`-long
+/// surface, either centered or from the start.
+fn visible_span(image: u32, display: u32, target: u32, centered: bool) -> (u32, u32) {
+    let (image, display) = (image.max(1), display.max(1));
+    let skipped = if centered { display.saturating_sub(target) / 2 } else { 0 };
+    let visible = display.min(target).max(1);
+    let scale = image as f64 / display as f64;
+    let step = image.div_ceil(CROP_STEPS);
+    let end = ((skipped + visible) as f64 * scale).ceil() as u32;
+    let end = end.div_ceil(step).saturating_mul(step).clamp(1, image);
+    let start = ((skipped as f64 * scale).floor() as u32 / step * step).min(end - 1);
+    (start, end - start)
+}
+
+/// Texture pixels per image pixel for an image drawn at `scale`: rounded up to
+/// the next step, and never above 1
+fn texel_scale(scale: f64) -> f64 {
+    ((SCALE_STEPS * scale.log2() - 1e-9).ceil() / SCALE_STEPS).exp2().min(1.0)
+}
+
+/// Wallpaper shown instead of the global one while a particular tab is active
+#[derive(Debug, Clone, PartialEq)]
+pub struct WallpaperOverride {
+    pub path: String,
+    /// Falls back to the global wallpaper opacity
+    pub opacity: Option<f32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_size_per_mode() {
+        let image = (1000, 500);
+        let target = (800, 800);
+        assert_eq!(WallpaperMode::Fill.display_size(image, target), (1600, 800));
+        assert_eq!(WallpaperMode::Fit.display_size(image, target), (800, 400));
+        assert_eq!(WallpaperMode::Stretch.display_size(image, target), (800, 800));
+        assert_eq!(WallpaperMode::Tile.display_size(image, target), (1000, 500));
+        assert_eq!(WallpaperMode::Center.display_size(image, target), (1000, 500));
+    }
+
+    #[test]
+    fn test_uv_transform_maps_surface_onto_image_rect() {
+        let target = (800, 800);
+
+        // Fit: image occupies rows 200..600, so the surface's center row is UV 0.5
+        let uv = WallpaperMode::Fit.uv_transform((800, 400), target);
+        assert_eq!(uv.scale, [1.0, 2.0]);
+        assert_eq!(uv.offset, [0.0, -0.5]);
+        assert!(uv.clip && !uv.tile);
+
+        // Fill: the 1600px-wide image is cropped evenly on both sides
+        let uv = WallpaperMode::Fill.uv_transform((1600, 800), target);
+        assert_eq!(uv.scale, [0.5, 1.0]);
+        assert_eq!(uv.offset, [0.25, 0.0]);
+
+        // Tile: repeats from the top-left
+        let uv = WallpaperMode::Tile.uv_transform((200, 400), target);
+        assert_eq!(uv.scale, [4.0, 2.0]);
+        assert_eq!(uv.offset, [0.0, 0.0]);
+        assert!(uv.tile);
+
+        assert_eq!(WallpaperMode::Stretch.uv_transform(target, target), WallpaperUv::default());
+    }
+
+    /// Image pixel a surface UV lands on through a fit's crop (x axis)
+    fn image_x(fit: &WallpaperFit, u: f32) -> f32 {
+        fit.crop.0 as f32 + (u * fit.uv.scale[0] + fit.uv.offset[0]) * fit.crop.2 as f32
+    }
+
+    #[test]
+    fn test_fit_crops_to_the_surface() {
+        let target = (800, 800);
+
+        // Fill: the middle square of a 2:1 image shows, cropped to the 1/16 grid around it
+        let fit = WallpaperMode::Fill.fit((1000, 500), target, 8192);
+        assert_eq!(fit.crop, (189, 0, 567, 500));
+        // Drawn larger than it is, so uploaded as is and upscaled by the sampler
+        assert_eq!(fit.size, (567, 500));
+        assert!((image_x(&fit, 0.0) - 250.0).abs() < 0.01);
+        assert!((image_x(&fit, 1.0) - 750.0).abs() < 0.01);
+
+        // Center: a large photo keeps only the part around the surface
+        let fit = WallpaperMode::Center.fit((6000, 4000), target, 8192);
+        assert_eq!(fit.crop, (2250, 1500, 1500, 1000));
+        assert_eq!(fit.size, (1500, 1000));
+        assert!((image_x(&fit, 0.0) - 2600.0).abs() < 0.01);
+
+        // Tile: a small image is uploaded whole
+        let fit = WallpaperMode::Tile.fit((200, 100), target, 8192);
+        assert_eq!(fit.crop, (0, 0, 200, 100));
+        assert_eq!(fit.size, (200, 100));
+        assert!(fit.uv.tile);
+    }
+
+    #[test]
+    fn test_nearby_sizes_share_a_texture() {
+        // A photo scaled down to fill the window keeps its aspect
+        let fit = WallpaperMode::Fill.fit((6000, 4000), (1600, 1000), 8192);
+        assert_eq!(fit.crop, (0, 0, 6000, 4000));
+        assert_eq!(fit.size, (1636, 1091));
+
+        // Dragging the window a little larger reuses it; only the UV moves
+        let dragged = WallpaperMode::Fill.fit((6000, 4000), (1620, 1010), 8192);
+        assert_eq!((dragged.crop, dragged.size), (fit.crop, fit.size));
+        assert_ne!(dragged.uv, fit.uv);
+
+        // Far enough for a sharper texture
+        let larger = WallpaperMode::Fill.fit((6000, 4000), (1800, 1100), 8192);
+        assert!(larger.size.0 > fit.size.0 && larger.size.0 >= 1800);
+    }
+
+    #[test]
+    fn test_fit_stays_within_texture_limit() {
+        // A 12000px-wide tile on an even wider surface
+        let fit = WallpaperMode::Tile.fit((12000, 3000), (16000, 4000), 8192);
+        assert_eq!(fit.crop, (0, 0, 12000, 3000));
+        assert_eq!(fit.size, (8192, 2048));
+        // Sampling is in UV space, so a smaller texture covers the same area
+        assert_eq!(fit.uv, WallpaperMode::Tile.uv_transform((12000, 3000), (16000, 4000)));
+
+        let fit = WallpaperMode::Stretch.fit((12000, 6000), (10000, 2000), 4096);
+        assert_eq!(fit.size.0, 4096);
+        assert!(fit.size.1 < 4096);
+    }
+}
@@ -0,0 +1,29 @@
+use anyhow::Result;
+use arboard::{LinuxClipboardKind, SetExtLinux};
+use saternal_core::ClipboardProvider;
+
+/// The X11 clipboard, with copies mirrored to the primary selection
+/// so a middle click pastes them in other apps too
+pub struct Clipboard {
+    ctx: arboard::Clipboard,
+}
+
+impl Clipboard {
+    pub fn new() -> Result<Self> {
+        Ok(Self { ctx: arboard::Clipboard::new()? })
+    }
+}
+
+impl ClipboardProvider for Clipboard {
+    fn set_text(&mut self, text: &str) -> Result<()> {
+        self.ctx.set_text(text)?;
+        if let Err(e) = self.ctx.set().clipboard(LinuxClipboardKind::Primary).text(text) {
+            log::debug!("Failed to set the primary selection: {}", e);
+        }
+        Ok(())
+    }
+
+    fn get_text(&mut self) -> Result<String> {
+        Ok(self.ctx.get_text()?)
+    }
+}
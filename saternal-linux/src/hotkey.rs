@@ -0,0 +1,280 @@
+use anyhow::Result;
+use evdev::{Device, InputEventKind, Key};
+use global_hotkey::{
+    hotkey::{Code, HotKey, Modifiers},
+    GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState,
+};
+use log::info;
+use parking_lot::Mutex;
+use saternal_core::GlobalHotkeys;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+
+type HotkeyCallback = Arc<Mutex<Box<dyn FnMut() + Send + 'static>>>;
+
+type Waker = Arc<dyn Fn() + Send + Sync + 'static>;
+
+/// The two global shortcuts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Chord {
+    /// Super+` - show/hide the terminal
+    Toggle,
+    /// Super+Shift+` - click-through glass mode
+    Glass,
+}
+
+/// Where key presses come from
+enum Source {
+    /// Grabs on the X root window
+    X11 { manager: GlobalHotKeyManager, toggle: HotKey, glass: HotKey },
+    /// Reader threads on the keyboards in /dev/input (they stop with the channel)
+    Evdev,
+}
+
+/// Manages global hotkey registration and events
+///
+/// Presses arrive on other threads, which wake the event loop; callbacks run
+/// from `process_events` there, like on macOS.
+pub struct HotkeyManager {
+    source: Source,
+    events: Receiver<Chord>,
+    callback: HotkeyCallback,
+    glass_toggle: Option<HotkeyCallback>,
+}
+
+impl HotkeyManager {
+    /// Create a new hotkey manager with Super+` (backtick)
+    /// `wake` runs on another thread for each press, to wake the event loop
+    pub fn new<F>(callback: F, wake: impl Fn() + Send + Sync + 'static) -> Result<Self>
+    where
+        F: FnMut() + Send + 'static,
+    {
+        info!("Initializing global hotkey manager");
+        let (sender, events) = mpsc::channel();
+        let wake: Waker = Arc::new(wake);
+
+        // XWayland only sees keys while one of its clients has focus
+        let source = if crate::is_wayland_session() {
+            start_evdev(sender, wake)?
+        } else {
+            start_x11(sender, wake)?
+        };
+
+        Ok(Self {
+            source,
+            events,
+            callback: Arc::new(Mutex::new(Box::new(callback))),
+            glass_toggle: None,
+        })
+    }
+
+    /// Register Super+Shift+` to toggle glass mode
+    /// It has to be global: a click-through window can't be clicked to get focus back
+    pub fn register_glass_toggle<F>(&mut self, callback: F) -> Result<()>
+    where
+        F: FnMut() + Send + 'static,
+    {
+        if let Source::X11 { manager, glass, .. } = &self.source {
+            manager
+                .register(*glass)
+                .map_err(|e| anyhow::anyhow!("Failed to register glass mode hotkey: {}", e))?;
+        }
+        info!("Registered global hotkey: Super+Shift+` (glass mode)");
+        self.glass_toggle = Some(Arc::new(Mutex::new(Box::new(callback))));
+        Ok(())
+    }
+
+    /// Unregister the hotkeys
+    pub fn unregister(&self) -> Result<()> {
+        if let Source::X11 { manager, toggle, glass } = &self.source {
+            if self.glass_toggle.is_some() {
+                manager
+                    .unregister(*glass)
+                    .map_err(|e| anyhow::anyhow!("Failed to unregister glass mode hotkey: {}", e))?;
+            }
+            manager
+                .unregister(*toggle)
+                .map_err(|e| anyhow::anyhow!("Failed to unregister hotkey: {}", e))?;
+        }
+        Ok(())
+    }
+}
+
+impl GlobalHotkeys for HotkeyManager {
+    fn process_events(&self) {
+        while let Ok(chord) = self.events.try_recv() {
+            match chord {
+                Chord::Toggle => self.trigger_toggle(),
+                Chord::Glass => {
+                    if let Some(callback) = &self.glass_toggle {
+                        let mut callback = callback.lock();
+                        callback();
+                    }
+                }
+            }
+        }
+    }
+
+    fn trigger_toggle(&self) {
+        let mut callback = self.callback.lock();
+        callback();
+    }
+}
+
+impl Drop for HotkeyManager {
+    fn drop(&mut self) {
+        let _ = self.unregister();
+    }
+}
+
+fn start_x11(sender: Sender<Chord>, wake: Waker) -> Result<Source> {
+    let manager = GlobalHotKeyManager::new()
+        .map_err(|e| anyhow::anyhow!("Failed to create hotkey manager: {}", e))?;
+
+    // SUPER is Mod4 - the Windows/logo key
+    let toggle = HotKey::new(Some(Modifiers::SUPER), Code::Backquote);
+    let glass = HotKey::new(Some(Modifiers::SUPER | Modifiers::SHIFT), Code::Backquote);
+    let (toggle_id, glass_id) = (toggle.id(), glass.id());
+    GlobalHotKeyEvent::set_event_handler(Some(move |event: GlobalHotKeyEvent| {
+        // Only trigger on key DOWN events to avoid repeated triggers
+        if event.state != HotKeyState::Pressed {
+            return;
+        }
+        let chord = if event.id == toggle_id {
+            Chord::Toggle
+        } else if event.id == glass_id {
+            Chord::Glass
+        } else {
+            return;
+        };
+        if sender.send(chord).is_ok() {
+            wake();
+        }
+    }));
+
+    manager
+        .register(toggle)
+        .map_err(|e| anyhow::anyhow!("Failed to register hotkey: {}", e))?;
+    info!("Registered global hotkey: Super+` (X11)");
+
+    Ok(Source::X11 { manager, toggle, glass })
+}
+
+fn start_evdev(sender: Sender<Chord>, wake: Waker) -> Result<Source> {
+    let keyboards: Vec<(std::path::PathBuf, Device)> = evdev::enumerate()
+        .filter(|(_, device)| device.supported_keys().is_some_and(|keys| keys.contains(Key::KEY_GRAVE)))
+        .collect();
+    if keyboards.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No readable keyboard in /dev/input (the hotkey on Wayland needs membership in the input group)"
+        ));
+    }
+
+    for (path, mut device) in keyboards {
+        info!("Watching {} ({}) for the hotkey", path.display(), device.name().unwrap_or("keyboard"));
+        let sender = sender.clone();
+        let wake = wake.clone();
+        std::thread::Builder::new()
+            .name("saternal-hotkey".to_string())
+            .spawn(move || {
+                let mut tracker = ChordTracker::default();
+                loop {
+                    let events = match device.fetch_events() {
+                        Ok(events) => events,
+                        Err(e) => {
+                            log::warn!("Stopped reading {}: {}", path.display(), e);
+                            return;
+                        }
+                    };
+                    for event in events {
+                        let InputEventKind::Key(key) = event.kind() else {
+                            continue;
+                        };
+                        if let Some(chord) = tracker.key(key, event.value()) {
+                            if sender.send(chord).is_err() {
+                                return;
+                            }
+                            wake();
+                        }
+                    }
+                }
+            })?;
+    }
+    info!("Registered global hotkey: Super+` (evdev)");
+
+    Ok(Source::Evdev)
+}
+
+/// Follows one keyboard's modifiers to spot the chords in its key events
+#[derive(Debug, Default)]
+struct ChordTracker {
+    held: Vec<Key>,
+}
+
+impl ChordTracker {
+    /// Feed a key event (`value` 1 = press, 0 = release, 2 = autorepeat)
+    /// Returns the chord a press of ` completed
+    fn key(&mut self, key: Key, value: i32) -> Option<Chord> {
+        match value {
+            0 => {
+                self.held.retain(|&held| held != key);
+                None
+            }
+            1 if key == Key::KEY_GRAVE => self.chord(),
+            1 => {
+                if !self.held.contains(&key) {
+                    self.held.push(key);
+                }
+                None
+            }
+            // Holding the chord down mustn't toggle over and over
+            _ => None,
+        }
+    }
+
+    /// Exactly Super, or Super and Shift, like the X11 grabs
+    fn chord(&self) -> Option<Chord> {
+        let held = |keys: [Key; 2]| keys.iter().any(|key| self.held.contains(key));
+        if !held([Key::KEY_LEFTMETA, Key::KEY_RIGHTMETA])
+            || held([Key::KEY_LEFTCTRL, Key::KEY_RIGHTCTRL])
+            || held([Key::KEY_LEFTALT, Key::KEY_RIGHTALT])
+        {
+            return None;
+        }
+        if held([Key::KEY_LEFTSHIFT, Key::KEY_RIGHTSHIFT]) {
+            Some(Chord::Glass)
+        } else {
+            Some(Chord::Toggle)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(tracker: &mut ChordTracker, keys: &[Key]) -> Option<Chord> {
+        keys.iter().fold(None, |_, &key| tracker.key(key, 1))
+    }
+
+    #[test]
+    fn test_chords_need_super() {
+        let mut tracker = ChordTracker::default();
+        assert_eq!(press(&mut tracker, &[Key::KEY_GRAVE]), None);
+        assert_eq!(press(&mut tracker, &[Key::KEY_LEFTMETA, Key::KEY_GRAVE]), Some(Chord::Toggle));
+        assert_eq!(press(&mut tracker, &[Key::KEY_RIGHTSHIFT, Key::KEY_GRAVE]), Some(Chord::Glass));
+
+        // Other modifiers make it a different shortcut
+        assert_eq!(press(&mut tracker, &[Key::KEY_LEFTCTRL, Key::KEY_GRAVE]), None);
+    }
+
+    #[test]
+    fn test_released_modifiers_and_autorepeat() {
+        let mut tracker = ChordTracker::default();
+        assert_eq!(press(&mut tracker, &[Key::KEY_LEFTMETA, Key::KEY_GRAVE]), Some(Chord::Toggle));
+        assert_eq!(tracker.key(Key::KEY_GRAVE, 2), None);
+
+        tracker.key(Key::KEY_LEFTMETA, 0);
+        assert_eq!(press(&mut tracker, &[Key::KEY_GRAVE]), None);
+    }
+}
@@ -0,0 +1,43 @@
+//! X11 side of the platform traits in `saternal_core::platform`
+//!
+//! Wayland sessions run Saternal through XWayland: winit can't put a Wayland
+//! toplevel at the top of the screen, hide it, or fade it, and the
+//! layer-shell surface a native Wayland dropdown needs isn't something winit
+//! can hand the renderer. The hotkey is the exception - X11 grabs only see
+//! keys while an X client has focus, so on Wayland it's read from the
+//! keyboards in `/dev/input` instead.
+#![cfg(target_os = "linux")]
+
+pub mod clipboard;
+pub mod hotkey;
+pub mod notification;
+pub mod sound;
+pub mod window;
+mod x11;
+
+pub use clipboard::Clipboard;
+pub use hotkey::HotkeyManager;
+pub use notification::{notify, show_crash_report};
+pub use sound::play_bell;
+pub use window::DropdownWindow;
+
+use winit::error::EventLoopError;
+use winit::event_loop::{EventLoop, EventLoopBuilder};
+use winit::platform::x11::EventLoopBuilderExtX11;
+
+/// Build the event loop on X11, through XWayland in Wayland sessions
+/// Stays on native Wayland when there's no X server to connect to
+pub fn event_loop() -> Result<EventLoop<()>, EventLoopError> {
+    let mut builder = EventLoopBuilder::new();
+    if std::env::var_os("DISPLAY").is_some() {
+        builder.with_x11();
+    } else {
+        log::warn!("No X server (DISPLAY is unset); the dropdown can't be placed or hidden on native Wayland");
+    }
+    builder.build()
+}
+
+/// Whether the desktop is a Wayland session (XWayland or not)
+pub fn is_wayland_session() -> bool {
+    std::env::var_os("WAYLAND_DISPLAY").is_some()
+}
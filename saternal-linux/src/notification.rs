@@ -0,0 +1,29 @@
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Post a desktop notification
+///
+/// Goes through `notify-send` (libnotify), which every notification daemon understands.
+pub fn notify(title: &str, body: &str) {
+    let spawned = Command::new("notify-send")
+        .args(["--app-name=Saternal", title, body])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    match spawned {
+        // Reap it off the main thread
+        Ok(mut child) => {
+            std::thread::spawn(move || child.wait());
+        }
+        Err(e) => log::warn!("Failed to post notification: {}", e),
+    }
+}
+
+/// Tell the user the last run crashed and where the report went
+pub fn show_crash_report(report: &Path) {
+    notify(
+        "Saternal quit unexpectedly",
+        &format!("A crash report was saved to {}", report.display()),
+    );
+}
@@ -0,0 +1,19 @@
+use std::process::{Command, Stdio};
+
+/// Play the desktop theme's bell sound
+///
+/// Goes through `canberra-gtk-play`; without it the bell is silent.
+pub fn play_bell() {
+    let spawned = Command::new("canberra-gtk-play")
+        .args(["--id=bell", "--description=Saternal bell"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    match spawned {
+        Ok(mut child) => {
+            std::thread::spawn(move || child.wait());
+        }
+        Err(e) => log::debug!("Failed to play bell: {}", e),
+    }
+}
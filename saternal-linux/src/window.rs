@@ -0,0 +1,251 @@
+use crate::x11;
+use anyhow::Result;
+use log::info;
+use saternal_core::{DropdownSurface, WindowMode};
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+use winit::monitor::MonitorHandle;
+use winit::window::{Fullscreen, Window, WindowId, WindowLevel};
+
+/// Lowest window alpha - keeps the terminal findable on screen
+const MIN_WINDOW_OPACITY: f64 = 0.1;
+
+/// Manages the dropdown window behavior on X11
+///
+/// Shows and hides right away: window managers animate mapping themselves,
+/// if at all, and fading through `_NET_WM_WINDOW_OPACITY` each frame would
+/// repaint the whole window under the compositor.
+pub struct DropdownWindow {
+    visible: bool,
+    opacity: f64,        // Window alpha while shown, text included (1.0 = opaque)
+    glass_opacity: f64,  // Window alpha in glass mode
+    glass: bool,         // Click-through glass mode: mouse events pass to the windows below
+    standard: bool,      // Regular decorated window instead of a dropdown
+    fullscreen_pending: bool,  // Go fullscreen the first time the window is shown
+    window_id: Option<WindowId>,  // The window configured as the dropdown
+    pinned: bool,        // Stay open when another window takes focus
+}
+
+impl DropdownWindow {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            opacity: 1.0,
+            glass_opacity: 1.0,
+            glass: false,
+            standard: false,
+            fullscreen_pending: false,
+            window_id: None,
+            pinned: false,
+        }
+    }
+
+    /// The monitor under the pointer (active screen)
+    /// Falls back to the window's monitor, then the primary one
+    fn monitor_with_pointer(window: &Window) -> Option<MonitorHandle> {
+        let pointer = x11::pointer_position();
+        let under_pointer = pointer.and_then(|(x, y)| {
+            window.available_monitors().find(|monitor| {
+                let position = monitor.position();
+                let size = monitor.size();
+                x >= position.x
+                    && x < position.x + size.width as i32
+                    && y >= position.y
+                    && y < position.y + size.height as i32
+            })
+        });
+        under_pointer.or_else(|| window.current_monitor()).or_else(|| window.primary_monitor())
+    }
+
+    /// Hang the dropdown from the top edge of `monitor`, `height` physical pixels tall
+    fn place(window: &Window, monitor: &MonitorHandle, height: u32) -> (u32, u32, f64) {
+        let width = monitor.size().width;
+        window.set_outer_position(monitor.position());
+        let _ = window.request_inner_size(PhysicalSize::new(width, height));
+        (width, height, monitor.scale_factor())
+    }
+
+    fn show(&mut self, window: &Window, reposition: bool) -> Option<(u32, u32, f64)> {
+        info!("Showing dropdown window (reposition: {})", reposition);
+        let dims = if reposition {
+            Self::monitor_with_pointer(window).map(|monitor| {
+                // Same logical height on the new screen
+                let logical_height = window.inner_size().height as f64 / window.scale_factor();
+                let height = (logical_height * monitor.scale_factor()).round() as u32;
+                Self::place(window, &monitor, height)
+            })
+        } else {
+            None
+        };
+        window.set_visible(true);
+        window.focus_window();
+        self.apply_opacity(window);
+        dims
+    }
+
+    fn toggle_standard(&mut self, window: &Window, show: bool) {
+        if show {
+            info!("Showing standard window");
+            window.set_visible(true);
+            window.focus_window();
+            self.apply_opacity(window);
+            if self.fullscreen_pending {
+                self.fullscreen_pending = false;
+                window.set_fullscreen(Some(Fullscreen::Borderless(None)));
+            }
+        } else {
+            info!("Hiding standard window");
+            window.set_visible(false);
+        }
+    }
+
+    fn is_dropdown(&self, window: &Window) -> bool {
+        !self.standard && self.window_id == Some(window.id())
+    }
+
+    pub fn is_glass(&self) -> bool {
+        self.glass
+    }
+
+    fn target_alpha(&self) -> f64 {
+        if self.glass {
+            self.glass_opacity
+        } else {
+            self.opacity
+        }
+    }
+}
+
+impl DropdownSurface for DropdownWindow {
+    fn configure(
+        &mut self,
+        window: &Window,
+        mode: WindowMode,
+        width_percentage: f64,
+        height_percentage: f64,
+    ) -> Result<(u32, u32, f64)> {
+        let monitor = Self::monitor_with_pointer(window).ok_or_else(|| anyhow::anyhow!("No monitor to place the window on"))?;
+        let screen = monitor.size();
+
+        if mode == WindowMode::Dropdown {
+            self.window_id = Some(window.id());
+            window.set_decorations(false);
+            window.set_window_level(WindowLevel::AlwaysOnTop);
+            let height = (screen.height as f64 * height_percentage).round() as u32;
+            let (width, height, scale_factor) = Self::place(window, &monitor, height);
+            info!("Configured dropdown window: {}x{} with scale factor {:.2}x", width, height, scale_factor);
+            return Ok((width, height, scale_factor));
+        }
+
+        let width = (screen.width as f64 * width_percentage).round() as u32;
+        let height = (screen.height as f64 * height_percentage).round() as u32;
+        let origin = monitor.position();
+        window.set_decorations(true);
+        window.set_window_level(WindowLevel::Normal);
+        window.set_outer_position(PhysicalPosition::new(
+            origin.x + (screen.width.saturating_sub(width) / 2) as i32,
+            origin.y + (screen.height.saturating_sub(height) / 2) as i32,
+        ));
+        let _ = window.request_inner_size(PhysicalSize::new(width, height));
+        self.standard = true;
+        self.fullscreen_pending = mode == WindowMode::Fullscreen;
+
+        info!("Configured standard window: {}x{} (fullscreen: {}) with scale factor {:.2}x",
+              width, height, self.fullscreen_pending, monitor.scale_factor());
+        Ok((width, height, monitor.scale_factor()))
+    }
+
+    fn toggle(&mut self, window: &Window) -> Result<Option<(u32, u32, f64)>> {
+        let was_visible = self.visible;
+        self.visible = !self.visible;
+
+        if self.standard {
+            self.toggle_standard(window, self.visible);
+            return Ok(None);
+        }
+
+        if self.visible {
+            // Only move to the pointer's screen when opening, not when already shown
+            Ok(self.show(window, !was_visible))
+        } else {
+            info!("Hiding dropdown window");
+            window.set_visible(false);
+            Ok(None)
+        }
+    }
+
+    fn hide_on_focus_loss(&mut self, window: &Window) -> Result<bool> {
+        if self.pinned || self.glass || !self.visible || !self.is_dropdown(window) {
+            return Ok(false);
+        }
+        info!("Dropdown lost focus");
+        self.visible = false;
+        window.set_visible(false);
+        Ok(true)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn toggle_pin(&mut self) -> bool {
+        self.pinned = !self.pinned;
+        info!("Dropdown {}", if self.pinned { "pinned" } else { "unpinned" });
+        self.pinned
+    }
+
+    fn is_pinned(&self) -> bool {
+        self.pinned
+    }
+
+    fn set_opacity(&mut self, opacity: f64, glass_opacity: f64) {
+        self.opacity = opacity.clamp(MIN_WINDOW_OPACITY, 1.0);
+        self.glass_opacity = glass_opacity.clamp(MIN_WINDOW_OPACITY, 1.0);
+    }
+
+    fn apply_opacity(&self, window: &Window) {
+        if !self.visible {
+            return;
+        }
+        if let Some(xid) = x11::window_id(window) {
+            if let Err(e) = x11::set_opacity(xid, self.target_alpha()) {
+                log::warn!("Failed to set window opacity: {}", e);
+            }
+        }
+    }
+
+    fn toggle_glass(&mut self, window: &Window) -> bool {
+        self.glass = !self.glass;
+        if let Err(e) = window.set_cursor_hittest(!self.glass) {
+            log::warn!("Failed to make the window click-through: {}", e);
+        }
+        self.apply_opacity(window);
+        info!("Glass mode {}", if self.glass { "on" } else { "off" });
+        self.glass
+    }
+
+    fn dropdown_screen(&self, window: &Window) -> Option<(f64, f64)> {
+        if !self.is_dropdown(window) {
+            return None;
+        }
+        let monitor = window.current_monitor()?;
+        let scale = monitor.scale_factor();
+        Some((monitor.size().height as f64 / scale, scale))
+    }
+
+    fn set_height(&self, window: &Window, height: f64) {
+        let size = window.inner_size();
+        let height = (height * window.scale_factor()).round() as u32;
+        if size.height == height {
+            return;
+        }
+        // X11 keeps the top-left corner where it is
+        let _ = window.request_inner_size(PhysicalSize::new(size.width, height));
+        info!("Dropdown height snapped to {} pixels", height);
+    }
+}
+
+impl Default for DropdownWindow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,44 @@
+/// The few X11 requests winit doesn't wrap
+///
+/// Each call opens its own connection: they run on toggles and config
+/// reloads, not per frame.
+use anyhow::Result;
+use winit::raw_window_handle::{HasWindowHandle, RawWindowHandle};
+use winit::window::Window;
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::{AtomEnum, ConnectionExt, PropMode};
+use x11rb::rust_connection::RustConnection;
+use x11rb::wrapper::ConnectionExt as _;
+
+/// The X window id behind a winit window, None on Wayland
+pub(crate) fn window_id(window: &Window) -> Option<u32> {
+    match window.window_handle().ok()?.as_raw() {
+        RawWindowHandle::Xlib(handle) => u32::try_from(handle.window).ok(),
+        RawWindowHandle::Xcb(handle) => Some(handle.window.get()),
+        _ => None,
+    }
+}
+
+/// Set `_NET_WM_WINDOW_OPACITY`, which compositing window managers apply to
+/// the whole window (text included)
+pub(crate) fn set_opacity(window: u32, alpha: f64) -> Result<()> {
+    let (conn, _) = RustConnection::connect(None)?;
+    let atom = conn.intern_atom(false, b"_NET_WM_WINDOW_OPACITY")?.reply()?.atom;
+    if alpha >= 1.0 {
+        // No property means opaque, and lets the compositor unredirect the window
+        conn.delete_property(window, atom)?;
+    } else {
+        let value = (alpha.clamp(0.0, 1.0) * u32::MAX as f64).round() as u32;
+        conn.change_property32(PropMode::REPLACE, window, atom, AtomEnum::CARDINAL, &[value])?;
+    }
+    conn.flush()?;
+    Ok(())
+}
+
+/// Pointer position on the root window, in physical pixels
+pub(crate) fn pointer_position() -> Option<(i32, i32)> {
+    let (conn, screen_num) = RustConnection::connect(None).ok()?;
+    let root = conn.setup().roots.get(screen_num)?.root;
+    let pointer = conn.query_pointer(root).ok()?.reply().ok()?;
+    Some((pointer.root_x.into(), pointer.root_y.into()))
+}
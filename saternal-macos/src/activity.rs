@@ -0,0 +1,52 @@
+use cocoa::base::{id, nil};
+use cocoa::foundation::NSString;
+use log::debug;
+use objc::{class, msg_send, sel, sel_impl};
+
+/// NSActivityUserInitiatedAllowingIdleSystemSleep: keeps App Nap away, but
+/// the Mac can still go to sleep
+const USER_INITIATED_ALLOWING_IDLE_SYSTEM_SLEEP: u64 = 0x00FF_FFFF & !(1 << 20);
+
+/// NSProcessInfo activity held while a window is on screen
+///
+/// Without it macOS naps the app once nothing is visible: background priority
+/// and coalesced timers, which is what a hidden dropdown wants
+#[derive(Default)]
+pub struct AppActivity {
+    token: Option<id>,
+}
+
+impl AppActivity {
+    /// Begin the activity, or end it so App Nap can kick in
+    pub fn set_active(&mut self, active: bool) {
+        if active == self.token.is_some() {
+            return;
+        }
+        unsafe {
+            let process_info: id = msg_send![class!(NSProcessInfo), processInfo];
+            match self.token.take() {
+                Some(token) => {
+                    let () = msg_send![process_info, endActivity: token];
+                    let () = msg_send![token, release];
+                    debug!("Nothing on screen, App Nap allowed");
+                }
+                None => {
+                    let reason = NSString::alloc(nil).init_str("Terminal window on screen");
+                    let token: id = msg_send![process_info,
+                        beginActivityWithOptions: USER_INITIATED_ALLOWING_IDLE_SYSTEM_SLEEP
+                                          reason: reason];
+                    let () = msg_send![reason, release];
+                    let _: id = msg_send![token, retain];
+                    self.token = Some(token);
+                    debug!("Window on screen, App Nap held off");
+                }
+            }
+        }
+    }
+}
+
+impl Drop for AppActivity {
+    fn drop(&mut self) {
+        self.set_active(false);
+    }
+}
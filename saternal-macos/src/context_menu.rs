@@ -0,0 +1,79 @@
+use cocoa::base::{id, nil, BOOL, NO, YES};
+use cocoa::foundation::{NSPoint, NSString};
+use objc::declare::ClassDecl;
+use objc::runtime::{Class, Object, Sel};
+use objc::{class, msg_send, sel, sel_impl};
+use std::sync::atomic::{AtomicIsize, Ordering};
+use std::sync::Once;
+
+/// Tag of the item picked from the open menu, -1 while nothing is picked
+static CHOSEN_ITEM: AtomicIsize = AtomicIsize::new(-1);
+
+const TARGET_CLASS_NAME: &str = "SaternalContextMenuTarget";
+
+/// An entry in a context menu
+pub enum MenuItem {
+    Action { title: String, enabled: bool },
+    Separator,
+}
+
+/// Pop up a menu at the mouse pointer and block until it closes
+/// Returns the index (into `items`) of the chosen action, if any
+pub unsafe fn show_context_menu(items: &[MenuItem]) -> Option<usize> {
+    let target: id = msg_send![target_class(), new];
+    let menu: id = msg_send![class!(NSMenu), alloc];
+    let menu: id = msg_send![menu, initWithTitle: NSString::alloc(nil).init_str("")];
+    let () = msg_send![menu, setAutoenablesItems: NO];
+
+    for (index, item) in items.iter().enumerate() {
+        let menu_item: id = match item {
+            MenuItem::Separator => msg_send![class!(NSMenuItem), separatorItem],
+            MenuItem::Action { title, enabled } => {
+                let menu_item: id = msg_send![class!(NSMenuItem), alloc];
+                let menu_item: id = msg_send![menu_item, initWithTitle:NSString::alloc(nil).init_str(title)
+                                                                 action:sel!(itemChosen:)
+                                                          keyEquivalent:NSString::alloc(nil).init_str("")];
+                let () = msg_send![menu_item, setTarget: target];
+                let () = msg_send![menu_item, setTag: index as isize];
+                let () = msg_send![menu_item, setEnabled: if *enabled { YES } else { NO }];
+                let () = msg_send![menu_item, autorelease];
+                menu_item
+            }
+        };
+        let () = msg_send![menu, addItem: menu_item];
+    }
+
+    // With no view the location is in screen coordinates
+    CHOSEN_ITEM.store(-1, Ordering::Release);
+    let location: NSPoint = msg_send![class!(NSEvent), mouseLocation];
+    let _: BOOL = msg_send![menu, popUpMenuPositioningItem:nil atLocation:location inView:nil];
+
+    let () = msg_send![menu, release];
+    let () = msg_send![target, release];
+    usize::try_from(CHOSEN_ITEM.swap(-1, Ordering::AcqRel)).ok()
+}
+
+/// Lazily register the Objective-C class that receives menu actions
+fn target_class() -> &'static Class {
+    static REGISTER: Once = Once::new();
+
+    REGISTER.call_once(|| {
+        let mut decl = ClassDecl::new(TARGET_CLASS_NAME, class!(NSObject))
+            .expect("menu target class already registered");
+
+        extern "C" fn item_chosen(_this: &Object, _cmd: Sel, sender: id) {
+            let tag: isize = unsafe { msg_send![sender, tag] };
+            CHOSEN_ITEM.store(tag, Ordering::Release);
+        }
+
+        unsafe {
+            decl.add_method(
+                sel!(itemChosen:),
+                item_chosen as extern "C" fn(&Object, Sel, id),
+            );
+        }
+        decl.register();
+    });
+
+    Class::get(TARGET_CLASS_NAME).expect("menu target class registered")
+}
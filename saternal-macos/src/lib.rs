@@ -1,7 +1,23 @@
+//! AppKit side of the platform traits in `saternal_core::platform`, plus the
+//! macOS-only extras (menu bar pin, screen lock, Dock icon, App Nap)
+#![cfg(target_os = "macos")]
+
+pub mod activity;
+pub mod context_menu;
 pub mod hotkey;
 pub mod icon;
+pub mod notification;
+pub mod screen_lock;
+pub mod sound;
+pub mod status_item;
 pub mod window;
 
+pub use activity::AppActivity;
+pub use context_menu::{show_context_menu, MenuItem};
 pub use hotkey::HotkeyManager;
 pub use icon::set_app_icon;
-pub use window::DropdownWindow;
+pub use notification::{notify, show_crash_report};
+pub use screen_lock::ScreenLockMonitor;
+pub use sound::play_bell;
+pub use status_item::PinStatusItem;
+pub use window::{appkit_handles, configure_transparent_layer, set_layer_color_space, DropdownWindow};
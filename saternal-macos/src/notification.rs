@@ -0,0 +1,54 @@
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Post a Notification Center banner
+///
+/// Goes through `osascript` so no notification entitlement or bundle is needed.
+pub fn notify(title: &str, body: &str) {
+    let script = format!(
+        "display notification \"{}\" with title \"{}\"",
+        applescript_escape(body),
+        applescript_escape(title)
+    );
+    let spawned = Command::new("osascript")
+        .args(["-e", &script])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    match spawned {
+        // Reap it off the main thread
+        Ok(mut child) => {
+            std::thread::spawn(move || child.wait());
+        }
+        Err(e) => log::warn!("Failed to post notification: {}", e),
+    }
+}
+
+/// Tell the user the last run crashed, offering to reveal `report` in Finder
+///
+/// The alert runs in `osascript` off the main thread, so startup doesn't wait on it.
+pub fn show_crash_report(report: &Path) {
+    let script = format!(
+        "display alert \"Saternal quit unexpectedly\" message \"A crash report was saved to {}\" \
+         buttons {{\"Ignore\", \"Show Report\"}} default button \"Show Report\"",
+        applescript_escape(&report.display().to_string())
+    );
+    let report = report.to_path_buf();
+    std::thread::spawn(move || {
+        let output = match Command::new("osascript").args(["-e", &script]).stdin(Stdio::null()).output() {
+            Ok(output) => output,
+            Err(e) => {
+                log::warn!("Failed to show crash report alert: {}", e);
+                return;
+            }
+        };
+        if String::from_utf8_lossy(&output.stdout).contains("Show Report") {
+            let _ = Command::new("open").arg("-R").arg(&report).status();
+        }
+    });
+}
+
+fn applescript_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
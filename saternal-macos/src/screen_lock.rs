@@ -0,0 +1,82 @@
+use cocoa::base::{id, nil};
+use cocoa::foundation::NSString;
+use log::info;
+use objc::declare::ClassDecl;
+use objc::runtime::{Class, Object, Sel};
+use objc::{class, msg_send, sel, sel_impl};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Once;
+
+/// Set by the notification observer, consumed by the app's event loop
+static LOCK_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Distributed notifications posted when the user steps away
+const LOCK_NOTIFICATIONS: &[&str] = &[
+    "com.apple.screenIsLocked",
+    "com.apple.screensaver.didstart",
+];
+
+const OBSERVER_CLASS_NAME: &str = "SaternalScreenLockObserver";
+
+/// Watches system screen lock and screensaver notifications
+pub struct ScreenLockMonitor {
+    observer: id,
+}
+
+impl ScreenLockMonitor {
+    /// Register for screen lock / screensaver notifications
+    pub unsafe fn start() -> Self {
+        let observer: id = msg_send![observer_class(), new];
+        let center: id = msg_send![class!(NSDistributedNotificationCenter), defaultCenter];
+
+        for name in LOCK_NOTIFICATIONS {
+            let ns_name = NSString::alloc(nil).init_str(name);
+            let () = msg_send![center, addObserver:observer
+                                         selector:sel!(screenLocked:)
+                                             name:ns_name
+                                           object:nil];
+        }
+
+        info!("Watching screen lock notifications");
+        Self { observer }
+    }
+
+    /// Returns true once for every lock/screensaver event since the last call
+    pub fn take_lock_request(&self) -> bool {
+        LOCK_REQUESTED.swap(false, Ordering::AcqRel)
+    }
+}
+
+impl Drop for ScreenLockMonitor {
+    fn drop(&mut self) {
+        unsafe {
+            let center: id = msg_send![class!(NSDistributedNotificationCenter), defaultCenter];
+            let () = msg_send![center, removeObserver:self.observer];
+            let () = msg_send![self.observer, release];
+        }
+    }
+}
+
+/// Lazily register the Objective-C observer class
+fn observer_class() -> &'static Class {
+    static REGISTER: Once = Once::new();
+
+    REGISTER.call_once(|| {
+        let mut decl = ClassDecl::new(OBSERVER_CLASS_NAME, class!(NSObject))
+            .expect("observer class already registered");
+
+        extern "C" fn screen_locked(_this: &Object, _cmd: Sel, _notification: id) {
+            LOCK_REQUESTED.store(true, Ordering::Release);
+        }
+
+        unsafe {
+            decl.add_method(
+                sel!(screenLocked:),
+                screen_locked as extern "C" fn(&Object, Sel, id),
+            );
+        }
+        decl.register();
+    });
+
+    Class::get(OBSERVER_CLASS_NAME).expect("observer class registered")
+}
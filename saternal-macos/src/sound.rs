@@ -0,0 +1,9 @@
+#[link(name = "AppKit", kind = "framework")]
+extern "C" {
+    fn NSBeep();
+}
+
+/// Play the user's system alert sound (System Settings > Sound)
+pub fn play_bell() {
+    unsafe { NSBeep() }
+}
@@ -0,0 +1,117 @@
+use cocoa::base::{id, nil};
+use cocoa::foundation::NSString;
+use log::info;
+use objc::declare::ClassDecl;
+use objc::runtime::{Class, Object, Sel};
+use objc::{class, msg_send, sel, sel_impl};
+use parking_lot::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Once;
+
+/// Set by the status item's button, consumed by the app's event loop
+static CLICKED: AtomicBool = AtomicBool::new(false);
+
+/// Wakes the app's event loop after a click
+static WAKER: Mutex<Option<Box<dyn Fn() + Send>>> = Mutex::new(None);
+
+const TARGET_CLASS_NAME: &str = "SaternalPinStatusTarget";
+
+/// NSVariableStatusItemLength
+const VARIABLE_LENGTH: f64 = -1.0;
+
+/// Menu bar icon that pins the dropdown open while other apps have focus
+pub struct PinStatusItem {
+    item: id,
+    target: id,
+    pinned: bool,
+}
+
+impl PinStatusItem {
+    /// Add the (unpinned) icon to the menu bar; `wake` runs on every click
+    pub unsafe fn new(wake: impl Fn() + Send + 'static) -> Self {
+        *WAKER.lock() = Some(Box::new(wake));
+
+        let status_bar: id = msg_send![class!(NSStatusBar), systemStatusBar];
+        let item: id = msg_send![status_bar, statusItemWithLength: VARIABLE_LENGTH];
+        let item: id = msg_send![item, retain];
+        let target: id = msg_send![target_class(), new];
+        let button: id = msg_send![item, button];
+        let () = msg_send![button, setTarget: target];
+        let () = msg_send![button, setAction: sel!(pinClicked:)];
+
+        let mut status_item = Self { item, target, pinned: true };
+        status_item.show_pinned(false);
+        info!("Added pin icon to the menu bar");
+        status_item
+    }
+
+    /// Returns true once for every click since the last call
+    pub fn take_click(&self) -> bool {
+        CLICKED.swap(false, Ordering::AcqRel)
+    }
+
+    /// Switch the icon between pinned and unpinned (no-op when it already matches)
+    pub unsafe fn show_pinned(&mut self, pinned: bool) {
+        if self.pinned == pinned {
+            return;
+        }
+        self.pinned = pinned;
+
+        let (symbol, tooltip) = if pinned {
+            ("pin.fill", "Saternal stays open (click to unpin)")
+        } else {
+            ("pin", "Saternal hides when another app is clicked (click to pin)")
+        };
+        let button: id = msg_send![self.item, button];
+        let tooltip = NSString::alloc(nil).init_str(tooltip);
+        let image: id = msg_send![class!(NSImage), imageWithSystemSymbolName: NSString::alloc(nil).init_str(symbol)
+                                                    accessibilityDescription: tooltip];
+        if image != nil {
+            let () = msg_send![button, setImage: image];
+        } else {
+            // SF Symbols need macOS 11
+            let title = if pinned { "◆" } else { "◇" };
+            let () = msg_send![button, setTitle: NSString::alloc(nil).init_str(title)];
+        }
+        let () = msg_send![button, setToolTip: tooltip];
+    }
+}
+
+impl Drop for PinStatusItem {
+    fn drop(&mut self) {
+        unsafe {
+            let status_bar: id = msg_send![class!(NSStatusBar), systemStatusBar];
+            let () = msg_send![status_bar, removeStatusItem: self.item];
+            let () = msg_send![self.item, release];
+            let () = msg_send![self.target, release];
+        }
+        *WAKER.lock() = None;
+    }
+}
+
+/// Lazily register the Objective-C class that receives the button's action
+fn target_class() -> &'static Class {
+    static REGISTER: Once = Once::new();
+
+    REGISTER.call_once(|| {
+        let mut decl = ClassDecl::new(TARGET_CLASS_NAME, class!(NSObject))
+            .expect("status item target class already registered");
+
+        extern "C" fn pin_clicked(_this: &Object, _cmd: Sel, _sender: id) {
+            CLICKED.store(true, Ordering::Release);
+            if let Some(wake) = WAKER.lock().as_ref() {
+                wake();
+            }
+        }
+
+        unsafe {
+            decl.add_method(
+                sel!(pinClicked:),
+                pin_clicked as extern "C" fn(&Object, Sel, id),
+            );
+        }
+        decl.register();
+    });
+
+    Class::get(TARGET_CLASS_NAME).expect("status item target class registered")
+}
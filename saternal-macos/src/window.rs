@@ -1,18 +1,59 @@
 use anyhow::Result;
 use cocoa::appkit::{NSEvent, NSScreen, NSWindow, NSWindowStyleMask};
-use cocoa::base::{id, nil, YES, NO};
+use cocoa::base::{id, nil, BOOL, YES, NO};
 use cocoa::foundation::{NSPoint, NSRect, NSSize};
+use core_foundation::string::CFStringRef;
+use core_graphics::color_space::{kCGColorSpaceDisplayP3, kCGColorSpaceSRGB};
 use core_graphics::display::CGDisplay;
 use log::info;
 use objc::runtime::Object;
 use objc::{class, msg_send, sel, sel_impl};
 use parking_lot::Mutex;
+use saternal_core::{ColorSpace, DropdownSurface, WindowMode};
+use std::ffi::c_void;
 use std::sync::Arc;
+use winit::raw_window_handle::{HasWindowHandle, RawWindowHandle};
+use winit::window::Window;
 
 /// Manages the dropdown window behavior on macOS
 pub struct DropdownWindow {
     visible: Arc<Mutex<bool>>,
     animation_duration: f64,
+    opacity: f64,        // Window alpha while shown, text included (1.0 = opaque)
+    glass_opacity: f64,  // Window alpha in glass mode
+    glass: bool,         // Click-through glass mode: mouse events pass to the apps below
+    standard: bool,      // Regular titled window instead of a dropdown
+    fullscreen_pending: bool,  // Enter native fullscreen the first time the window is shown
+    window_number: Option<isize>,  // The NSWindow configured as the dropdown
+    previous_app: Option<i32>,  // Pid of the app that was frontmost before the dropdown opened
+    pinned: bool,        // Stay open when another app takes focus
+    can_join_all_spaces: bool,  // On every Space, instead of moving to the current one when shown
+    appear_over_fullscreen: bool,  // Open over apps in native fullscreen
+}
+
+/// Lowest window alpha - keeps the terminal findable on screen
+const MIN_WINDOW_OPACITY: f64 = 0.1;
+
+/// NSApplicationActivateIgnoringOtherApps
+const NS_APPLICATION_ACTIVATE_IGNORING_OTHER_APPS: usize = 1 << 1;
+
+/// NSWindowCollectionBehavior flags
+const NS_WINDOW_COLLECTION_BEHAVIOR_CAN_JOIN_ALL_SPACES: u64 = 1 << 0;
+const NS_WINDOW_COLLECTION_BEHAVIOR_MOVE_TO_ACTIVE_SPACE: u64 = 1 << 1;
+const NS_WINDOW_COLLECTION_BEHAVIOR_FULL_SCREEN_AUXILIARY: u64 = 1 << 8;
+
+/// Collection behavior of the dropdown for the `[window]` Spaces settings
+fn dropdown_collection_behavior(can_join_all_spaces: bool, appear_over_fullscreen: bool) -> u64 {
+    let spaces = if can_join_all_spaces {
+        NS_WINDOW_COLLECTION_BEHAVIOR_CAN_JOIN_ALL_SPACES
+    } else {
+        NS_WINDOW_COLLECTION_BEHAVIOR_MOVE_TO_ACTIVE_SPACE
+    };
+    if appear_over_fullscreen {
+        spaces | NS_WINDOW_COLLECTION_BEHAVIOR_FULL_SCREEN_AUXILIARY
+    } else {
+        spaces
+    }
 }
 
 impl DropdownWindow {
@@ -20,6 +61,16 @@ impl DropdownWindow {
         Self {
             visible: Arc::new(Mutex::new(false)),
             animation_duration: 0.18, // 180ms
+            opacity: 1.0,
+            glass_opacity: 1.0,
+            glass: false,
+            standard: false,
+            fullscreen_pending: false,
+            window_number: None,
+            previous_app: None,
+            pinned: false,
+            can_join_all_spaces: true,
+            appear_over_fullscreen: true,
         }
     }
 
@@ -58,7 +109,9 @@ impl DropdownWindow {
     /// Configure a winit window to behave as a dropdown terminal
     /// ns_view is the winit NSView where wgpu will create the CAMetalLayer
     /// Returns (width, height, scale_factor) for terminal sizing
-    pub unsafe fn configure_window(&self, ns_window: id, ns_view: id, height_percentage: f64) -> Result<(u32, u32, f64)> {
+    unsafe fn configure_window(&mut self, ns_window: id, ns_view: id, height_percentage: f64) -> Result<(u32, u32, f64)> {
+        self.window_number = Some(msg_send![ns_window, windowNumber]);
+
         // Get screen containing mouse cursor (active screen)
         let screen = Self::get_screen_with_mouse();
         let screen_frame: NSRect = msg_send![screen, frame];
@@ -83,6 +136,7 @@ impl DropdownWindow {
         // NSStatusWindowLevel = 25
         let window_level: i64 = 25;
         let () = msg_send![ns_window, setLevel:window_level];
+        self.apply_collection_behavior(ns_window);
 
         // Set borderless style
         let style_mask = NSWindowStyleMask::NSBorderlessWindowMask
@@ -118,60 +172,90 @@ impl DropdownWindow {
         Ok((physical_width, physical_height, backing_scale_factor))
     }
 
-    /// Enable transparency layer after wgpu surface is created
-    /// Call this AFTER the renderer is initialized
-    pub unsafe fn enable_vibrancy_layer(&self, ns_view: id) -> Result<()> {
-        // Configure the Metal layer for transparency
-        self.configure_metal_layer(ns_view)?;
-        info!("✓ Transparency layer configured");
-        Ok(())
-    }
+    /// Configure a winit window as a regular titled, resizable window centered on
+    /// the main screen, optionally entering native fullscreen when first shown
+    /// Returns (width, height, scale_factor) for terminal sizing
+    unsafe fn configure_standard_window(
+        &mut self,
+        ns_window: id,
+        ns_view: id,
+        width_percentage: f64,
+        height_percentage: f64,
+        fullscreen: bool,
+    ) -> Result<(u32, u32, f64)> {
+        let screen: id = msg_send![class!(NSScreen), mainScreen];
+        let visible_frame: NSRect = msg_send![screen, visibleFrame];
+
+        let window_width = visible_frame.size.width * width_percentage;
+        let window_height = visible_frame.size.height * height_percentage;
+        let window_x = visible_frame.origin.x + (visible_frame.size.width - window_width) / 2.0;
+        let window_y = visible_frame.origin.y + (visible_frame.size.height - window_height) / 2.0;
+
+        let style_mask = NSWindowStyleMask::NSTitledWindowMask
+            | NSWindowStyleMask::NSClosableWindowMask
+            | NSWindowStyleMask::NSMiniaturizableWindowMask
+            | NSWindowStyleMask::NSResizableWindowMask;
+        let () = msg_send![ns_window, setStyleMask:style_mask];
 
-    /// Configure the CAMetalLayer for transparency
-    /// ns_view is the winit NSView where wgpu adds the CAMetalLayer
-    unsafe fn configure_metal_layer(&self, ns_view: id) -> Result<()> {
-        // Get the layer from the WINIT VIEW (not the window's contentView!)
-        let layer: id = msg_send![ns_view, layer];
+        // setFrame after setStyleMask - the titlebar changes the content size
+        let window_frame = NSRect::new(
+            NSPoint::new(window_x, window_y),
+            NSSize::new(window_width, window_height),
+        );
+        let () = msg_send![ns_window, setFrame:window_frame display:YES];
 
-        if layer != nil {
-            info!("Found layer on winit NSView");
+        // NSNormalWindowLevel, and allow the green button to enter fullscreen
+        let window_level: i64 = 0;
+        let () = msg_send![ns_window, setLevel:window_level];
+        let full_screen_primary: u64 = 1 << 7; // NSWindowCollectionBehaviorFullScreenPrimary
+        let () = msg_send![ns_window, setCollectionBehavior:full_screen_primary];
 
-            // Check if it's a CAMetalLayer
-            let layer_class: id = msg_send![layer, class];
-            // Get the class name properly - need to convert Class to NSString first
-            let class_name_nsstring: id = msg_send![layer_class, description];
-            let class_name: *const i8 = msg_send![class_name_nsstring, UTF8String];
-            let class_str = std::ffi::CStr::from_ptr(class_name).to_str().unwrap_or("unknown");
-            info!("Layer class: {}", class_str);
+        // Same transparency setup as the dropdown (wallpaper/opacity still apply)
+        let () = msg_send![ns_window, setOpaque:NO];
+        let clear_color: id = msg_send![class!(NSColor), clearColor];
+        let () = msg_send![ns_window, setBackgroundColor:clear_color];
+        let () = msg_send![ns_view, setWantsLayer:YES];
 
-            // CRITICAL: Set layer to transparent for blur/wallpaper to work
-            let () = msg_send![layer, setOpaque:NO];
+        self.standard = true;
+        self.fullscreen_pending = fullscreen;
 
-            // Ensure it's not hidden
-            let () = msg_send![layer, setHidden:NO];
+        // Size terminals by the content area (below the titlebar)
+        let content_view: id = msg_send![ns_window, contentView];
+        let content_frame: NSRect = msg_send![content_view, frame];
+        let backing_scale_factor: f64 = msg_send![screen, backingScaleFactor];
+        let physical_width = (content_frame.size.width * backing_scale_factor).round() as u32;
+        let physical_height = (content_frame.size.height * backing_scale_factor).round() as u32;
 
-            info!("Layer configured: opaque=NO (transparent), hidden=NO");
-        } else {
-            info!("WARNING: No layer found on winit NSView! wgpu may not have created it yet.");
-        }
+        info!("Configured standard window: {}x{} (fullscreen: {}) with scale factor {:.2}x",
+              window_width, window_height, fullscreen, backing_scale_factor);
 
-        Ok(())
+        Ok((physical_width, physical_height, backing_scale_factor))
     }
 
     /// Toggle window visibility with animation
-    /// Returns (width, height, scale_factor) if window was shown and repositioned
-    pub unsafe fn toggle(&self, ns_window: id) -> Result<Option<(u32, u32, f64)>> {
+    /// Returns (width, height, scale_factor) in physical pixels if window was shown and repositioned
+    unsafe fn toggle_ns_window(&mut self, ns_window: id) -> Result<Option<(u32, u32, f64)>> {
         let mut visible = self.visible.lock();
         let was_visible = *visible;
         *visible = !*visible;
 
-        if *visible {
+        let show = *visible;
+        drop(visible);
+
+        if self.standard {
+            self.toggle_standard(ns_window, show);
+            return Ok(None);
+        }
+
+        if show {
+            self.previous_app = Self::frontmost_other_app();
             // Only reposition if window was hidden (transitioning hidden→visible)
             // Don't reposition if toggling while already visible
             let dims = self.show_animated(ns_window, !was_visible)?;
             Ok(dims)
         } else {
             self.hide_animated(ns_window)?;
+            self.restore_previous_app();
             Ok(None)
         }
     }
@@ -183,6 +267,7 @@ impl DropdownWindow {
         info!("Showing dropdown window (reposition: {})", should_reposition);
 
         let mut new_dims = None;
+        self.apply_collection_behavior(ns_window);
         
         // Only reposition if window was hidden (opening on active screen)
         // Don't reposition if window is already visible (just a toggle)
@@ -206,19 +291,24 @@ impl DropdownWindow {
             // Get the new screen's scale factor
             let backing_scale_factor: f64 = msg_send![screen, backingScaleFactor];
             
-            info!("Window repositioned to screen with scale factor: {:.2}x, dimensions: {}x{}",
-                  backing_scale_factor, new_width as u32, current_frame.size.height as u32);
-            
-            new_dims = Some((
-                new_width as u32,
-                current_frame.size.height as u32,
-                backing_scale_factor,
-            ));
+            // Physical pixels, like configure_window
+            let physical_width = (new_width * backing_scale_factor).round() as u32;
+            let physical_height = (current_frame.size.height * backing_scale_factor).round() as u32;
+            info!("Window repositioned to screen with scale factor: {:.2}x, dimensions: {}x{} (physical: {}x{})",
+                  backing_scale_factor, new_width as u32, current_frame.size.height as u32,
+                  physical_width, physical_height);
+
+            new_dims = Some((physical_width, physical_height, backing_scale_factor));
         }
 
-        // Make window visible
+        // Make window visible, and take keyboard focus from whatever app had it
         let () = msg_send![ns_window, makeKeyAndOrderFront:nil];
         let () = msg_send![ns_window, orderFrontRegardless];
+        let app: id = msg_send![class!(NSApplication), sharedApplication];
+        let () = msg_send![app, activateIgnoringOtherApps:YES];
+        let () = msg_send![ns_window, makeKeyWindow];
+        let content_view: id = msg_send![ns_window, contentView];
+        let _: BOOL = msg_send![ns_window, makeFirstResponder:content_view];
 
         // Animate opacity
         let () = msg_send![ns_window, setAlphaValue:0.0f64];
@@ -228,11 +318,31 @@ impl DropdownWindow {
         let () = msg_send![animation_context, setDuration:self.animation_duration];
 
         let () = msg_send![ns_window, animator];
-        let () = msg_send![ns_window, setAlphaValue:1.0f64];
+        let () = msg_send![ns_window, setAlphaValue:self.target_alpha()];
 
         Ok(new_dims)
     }
 
+    /// Show or hide a standard window by hiding the whole app, like Cmd+H
+    /// (keeps a fullscreen window's space instead of leaving an empty one)
+    unsafe fn toggle_standard(&mut self, ns_window: id, show: bool) {
+        let app: id = msg_send![class!(NSApplication), sharedApplication];
+        if show {
+            info!("Showing standard window");
+            let () = msg_send![app, unhide:nil];
+            let () = msg_send![ns_window, setAlphaValue:self.target_alpha()];
+            let () = msg_send![ns_window, makeKeyAndOrderFront:nil];
+            let () = msg_send![app, activateIgnoringOtherApps:YES];
+            if self.fullscreen_pending {
+                self.fullscreen_pending = false;
+                let () = msg_send![ns_window, toggleFullScreen:nil];
+            }
+        } else {
+            info!("Hiding standard window");
+            let () = msg_send![app, hide:nil];
+        }
+    }
+
     /// Hide window with slide-up animation
     unsafe fn hide_animated(&self, ns_window: id) -> Result<()> {
         info!("Hiding dropdown window");
@@ -252,13 +362,290 @@ impl DropdownWindow {
         Ok(())
     }
 
-    pub fn is_visible(&self) -> bool {
-        *self.visible.lock()
+    /// Pid of the frontmost app, unless it's Saternal itself
+    unsafe fn frontmost_other_app() -> Option<i32> {
+        let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+        let app: id = msg_send![workspace, frontmostApplication];
+        if app == nil {
+            return None;
+        }
+        let pid: i32 = msg_send![app, processIdentifier];
+        (pid != std::process::id() as i32).then_some(pid)
+    }
+
+    /// Give focus back to the app that was frontmost when the dropdown opened
+    unsafe fn restore_previous_app(&mut self) {
+        let Some(pid) = self.previous_app.take() else {
+            return;
+        };
+        let app: id = msg_send![class!(NSRunningApplication), runningApplicationWithProcessIdentifier:pid];
+        if app != nil {
+            let _: BOOL = msg_send![app, activateWithOptions:NS_APPLICATION_ACTIVATE_IGNORING_OTHER_APPS];
+        }
+    }
+
+    /// Hide the dropdown because another window took focus, unless it's pinned
+    /// or in glass mode (meant to sit over other apps). Focus stays where the user clicked.
+    /// Returns true if the dropdown was hidden
+    unsafe fn hide_ns_window_on_focus_loss(&mut self, ns_window: id) -> Result<bool> {
+        if self.pinned || self.glass || !self.is_visible() || !self.is_dropdown(ns_window) {
+            return Ok(false);
+        }
+        info!("Dropdown lost focus");
+        *self.visible.lock() = false;
+        self.previous_app = None;
+        self.hide_animated(ns_window)?;
+        Ok(true)
+    }
+
+    /// Whether `ns_window` is the one configured as the dropdown
+    unsafe fn is_dropdown(&self, ns_window: id) -> bool {
+        let number: isize = msg_send![ns_window, windowNumber];
+        !self.standard && self.window_number == Some(number)
+    }
+
+    /// Height in points and backing scale factor of the screen `ns_window` is on
+    unsafe fn screen_height(ns_window: id) -> Option<(f64, f64)> {
+        let screen: id = msg_send![ns_window, screen];
+        if screen == nil {
+            return None;
+        }
+        let frame: NSRect = msg_send![screen, frame];
+        let scale: f64 = msg_send![screen, backingScaleFactor];
+        Some((frame.size.height, scale))
+    }
+
+    /// Resize the dropdown to `height` points, keeping its top edge in place
+    unsafe fn set_ns_window_height(&self, ns_window: id, height: f64) {
+        let frame: NSRect = msg_send![ns_window, frame];
+        if (frame.size.height - height).abs() < 0.01 {
+            return;
+        }
+        let top = frame.origin.y + frame.size.height;
+        let new_frame = NSRect::new(NSPoint::new(frame.origin.x, top - height), NSSize::new(frame.size.width, height));
+        let () = msg_send![ns_window, setFrame:new_frame display:YES];
+        info!("Dropdown height snapped to {:.1} points", height);
     }
 
     pub fn set_animation_duration(&mut self, duration: f64) {
         self.animation_duration = duration;
     }
+
+    /// Set which Spaces the dropdown joins (before it's ordered front, so it opens on the current one)
+    unsafe fn apply_collection_behavior(&self, ns_window: id) {
+        let behavior = dropdown_collection_behavior(self.can_join_all_spaces, self.appear_over_fullscreen);
+        let () = msg_send![ns_window, setCollectionBehavior:behavior];
+    }
+
+    /// Push the current opacity to a visible window
+    unsafe fn apply_ns_window_opacity(&self, ns_window: id) {
+        if self.is_visible() {
+            let () = msg_send![ns_window, setAlphaValue:self.target_alpha()];
+        }
+    }
+
+    /// Toggle click-through glass mode: the window stops receiving mouse events
+    /// and switches to the glass opacity, so it can sit over other apps as a dashboard
+    /// Returns true if glass mode is now on
+    unsafe fn toggle_ns_window_glass(&mut self, ns_window: id) -> bool {
+        self.glass = !self.glass;
+        let ignores_mouse = if self.glass { YES } else { NO };
+        let () = msg_send![ns_window, setIgnoresMouseEvents:ignores_mouse];
+        self.apply_ns_window_opacity(ns_window);
+        info!("Glass mode {}", if self.glass { "on" } else { "off" });
+        self.glass
+    }
+
+    pub fn is_glass(&self) -> bool {
+        self.glass
+    }
+
+    fn target_alpha(&self) -> f64 {
+        if self.glass {
+            self.glass_opacity
+        } else {
+            self.opacity
+        }
+    }
+}
+
+/// Make the CAMetalLayer wgpu added to `ns_view` transparent, so the window's
+/// background opacity and wallpaper show through
+/// Call after the renderer is initialized
+pub unsafe fn configure_transparent_layer(ns_view: id) {
+    // Get the layer from the WINIT VIEW (not the window's contentView!)
+    let layer: id = msg_send![ns_view, layer];
+
+    if layer != nil {
+        info!("Found layer on winit NSView");
+
+        // Check if it's a CAMetalLayer
+        let layer_class: id = msg_send![layer, class];
+        // Get the class name properly - need to convert Class to NSString first
+        let class_name_nsstring: id = msg_send![layer_class, description];
+        let class_name: *const i8 = msg_send![class_name_nsstring, UTF8String];
+        let class_str = std::ffi::CStr::from_ptr(class_name).to_str().unwrap_or("unknown");
+        info!("Layer class: {}", class_str);
+
+        // CRITICAL: Set layer to transparent for blur/wallpaper to work
+        let () = msg_send![layer, setOpaque:NO];
+
+        // Ensure it's not hidden
+        let () = msg_send![layer, setHidden:NO];
+
+        info!("Layer configured: opaque=NO (transparent), hidden=NO");
+    } else {
+        info!("WARNING: No layer found on winit NSView! wgpu may not have created it yet.");
+    }
+}
+
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    fn CGColorSpaceCreateWithName(name: CFStringRef) -> *mut c_void;
+    fn CGColorSpaceRelease(space: *mut c_void);
+}
+
+/// Tag the CAMetalLayer on `ns_view` with `color_space`. An untagged layer is
+/// taken to be in the display's own color space, which oversaturates sRGB
+/// colors on wide-gamut (P3) screens
+pub unsafe fn set_layer_color_space(ns_view: id, color_space: ColorSpace) {
+    let layer: id = msg_send![ns_view, layer];
+    if layer == nil {
+        return;
+    }
+    let name = match color_space {
+        ColorSpace::Srgb => kCGColorSpaceSRGB,
+        ColorSpace::DisplayP3 => kCGColorSpaceDisplayP3,
+    };
+    let space = CGColorSpaceCreateWithName(name);
+    if space.is_null() {
+        return;
+    }
+    // The layer retains the color space
+    let () = msg_send![layer, setColorspace: space];
+    CGColorSpaceRelease(space);
+    info!("Layer color space: {:?}", color_space);
+}
+
+/// The NSWindow and NSView behind a winit window
+pub unsafe fn appkit_handles(window: &Window) -> Option<(id, id)> {
+    let handle = window.window_handle().ok()?;
+    match handle.as_raw() {
+        RawWindowHandle::AppKit(appkit_handle) => {
+            let ns_view = appkit_handle.ns_view.as_ptr() as id;
+            Some((msg_send![ns_view, window], ns_view))
+        }
+        _ => None,
+    }
+}
+
+impl DropdownSurface for DropdownWindow {
+    fn configure(
+        &mut self,
+        window: &Window,
+        mode: WindowMode,
+        width_percentage: f64,
+        height_percentage: f64,
+    ) -> Result<(u32, u32, f64)> {
+        unsafe {
+            let (ns_window, ns_view) =
+                appkit_handles(window).ok_or_else(|| anyhow::anyhow!("Failed to get AppKit window handle"))?;
+            match mode {
+                WindowMode::Dropdown => self.configure_window(ns_window, ns_view, height_percentage),
+                mode => self.configure_standard_window(
+                    ns_window,
+                    ns_view,
+                    width_percentage,
+                    height_percentage,
+                    mode == WindowMode::Fullscreen,
+                ),
+            }
+        }
+    }
+
+    fn surface_created(&self, window: &Window) {
+        unsafe {
+            if let Some((_, ns_view)) = appkit_handles(window) {
+                configure_transparent_layer(ns_view);
+                info!("✓ Transparency layer configured");
+            }
+        }
+    }
+
+    fn set_color_space(&self, window: &Window, color_space: ColorSpace) {
+        unsafe {
+            if let Some((_, ns_view)) = appkit_handles(window) {
+                set_layer_color_space(ns_view, color_space);
+            }
+        }
+    }
+
+    fn toggle(&mut self, window: &Window) -> Result<Option<(u32, u32, f64)>> {
+        match unsafe { appkit_handles(window) } {
+            Some((ns_window, _)) => unsafe { self.toggle_ns_window(ns_window) },
+            None => Ok(None),
+        }
+    }
+
+    fn hide_on_focus_loss(&mut self, window: &Window) -> Result<bool> {
+        match unsafe { appkit_handles(window) } {
+            Some((ns_window, _)) => unsafe { self.hide_ns_window_on_focus_loss(ns_window) },
+            None => Ok(false),
+        }
+    }
+
+    fn is_visible(&self) -> bool {
+        *self.visible.lock()
+    }
+
+    fn toggle_pin(&mut self) -> bool {
+        self.pinned = !self.pinned;
+        info!("Dropdown {}", if self.pinned { "pinned" } else { "unpinned" });
+        self.pinned
+    }
+
+    fn is_pinned(&self) -> bool {
+        self.pinned
+    }
+
+    fn set_opacity(&mut self, opacity: f64, glass_opacity: f64) {
+        self.opacity = opacity.clamp(MIN_WINDOW_OPACITY, 1.0);
+        self.glass_opacity = glass_opacity.clamp(MIN_WINDOW_OPACITY, 1.0);
+    }
+
+    fn apply_opacity(&self, window: &Window) {
+        if let Some((ns_window, _)) = unsafe { appkit_handles(window) } {
+            unsafe { self.apply_ns_window_opacity(ns_window) }
+        }
+    }
+
+    fn set_spaces(&mut self, can_join_all_spaces: bool, appear_over_fullscreen: bool) {
+        self.can_join_all_spaces = can_join_all_spaces;
+        self.appear_over_fullscreen = appear_over_fullscreen;
+    }
+
+    fn toggle_glass(&mut self, window: &Window) -> bool {
+        match unsafe { appkit_handles(window) } {
+            Some((ns_window, _)) => unsafe { self.toggle_ns_window_glass(ns_window) },
+            None => self.glass,
+        }
+    }
+
+    fn dropdown_screen(&self, window: &Window) -> Option<(f64, f64)> {
+        unsafe {
+            let (ns_window, _) = appkit_handles(window)?;
+            if !self.is_dropdown(ns_window) {
+                return None;
+            }
+            Self::screen_height(ns_window)
+        }
+    }
+
+    fn set_height(&self, window: &Window, height: f64) {
+        if let Some((ns_window, _)) = unsafe { appkit_handles(window) } {
+            unsafe { self.set_ns_window_height(ns_window, height) }
+        }
+    }
 }
 
 impl Default for DropdownWindow {
@@ -266,3 +653,14 @@ impl Default for DropdownWindow {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dropdown_collection_behavior() {
+        assert_eq!(dropdown_collection_behavior(true, true), (1 << 0) | (1 << 8));
+        assert_eq!(dropdown_collection_behavior(false, false), 1 << 1);
+    }
+}
@@ -0,0 +1,70 @@
+//! Renderer benchmarks: VTE parsing, CPU text rasterization, and whole frames
+//! drawn the way the window draws a pane, each over the vtebench-style workloads
+//!
+//! Runs headless. Frame benchmarks are skipped when no GPU adapter (hardware
+//! or software) is available.
+use alacritty_terminal::event::VoidListener;
+use alacritty_terminal::term::{test::TermSize, Config, Term};
+use alacritty_terminal::vte::ansi::Processor;
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use saternal_renderer::HeadlessRenderer;
+
+// Workloads shared with saternal-core's PTY benchmark
+#[path = "../../saternal-core/benches/common/mod.rs"]
+mod common;
+
+const FONT_FAMILY: &str = "Menlo";
+const FONT_SIZE: f32 = 14.0;
+const FRAME_WIDTH: u32 = 1280;
+const FRAME_HEIGHT: u32 = 800;
+
+fn renderer() -> HeadlessRenderer {
+    HeadlessRenderer::new(FONT_FAMILY, FONT_SIZE, FRAME_WIDTH, FRAME_HEIGHT).expect("load font")
+}
+
+/// A terminal with `workload` parsed into it
+fn screen(workload: &[u8], cols: usize, rows: usize) -> Term<VoidListener> {
+    let mut term = Term::new(Config::default(), &TermSize::new(cols, rows), VoidListener);
+    let mut processor: Processor = Processor::new();
+    processor.advance(&mut term, workload);
+    term
+}
+
+fn vte_parse(c: &mut Criterion) {
+    let (cols, rows) = renderer().grid_size();
+    let mut group = c.benchmark_group("vte_parse");
+    for (name, workload) in common::workloads(cols, rows) {
+        group.throughput(Throughput::Bytes(workload.len() as u64));
+        group.bench_function(name, |b| b.iter(|| screen(&workload, cols, rows)));
+    }
+    group.finish();
+}
+
+fn text_rasterization(c: &mut Criterion) {
+    let renderer = renderer();
+    let (cols, rows) = renderer.grid_size();
+    let mut group = c.benchmark_group("text_rasterization");
+    for (name, workload) in common::workloads(cols, rows) {
+        let term = screen(&workload, cols, rows);
+        group.bench_function(name, |b| b.iter(|| renderer.rasterize(&term).unwrap()));
+    }
+    group.finish();
+}
+
+fn frames(c: &mut Criterion) {
+    let mut renderer = renderer();
+    if let Err(e) = renderer.enable_gpu() {
+        eprintln!("Skipping frame benchmarks: {}", e);
+        return;
+    }
+    let (cols, rows) = renderer.grid_size();
+    let mut group = c.benchmark_group("frames");
+    for (name, workload) in common::workloads(cols, rows) {
+        let term = screen(&workload, cols, rows);
+        group.bench_function(name, |b| b.iter(|| renderer.draw_frame(&term).unwrap()));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, vte_parse, text_rasterization, frames);
+criterion_main!(benches);
@@ -0,0 +1,45 @@
+/// Visual bell: a brief translucent flash over the pane that rang
+use saternal_core::selection::PaneViewport;
+use std::time::Duration;
+
+use super::scrollbar::PixelRect;
+
+/// How long the flash takes to fade out
+pub const BELL_FLASH_DURATION: Duration = Duration::from_millis(150);
+
+/// Flash opacity right after the bell
+const BELL_FLASH_ALPHA: f32 = 0.25;
+
+/// Flash rectangle covering the pane, fading linearly; None once it has faded
+pub fn bell_flash_rect(viewport: &PaneViewport, elapsed: Duration) -> Option<PixelRect> {
+    if elapsed >= BELL_FLASH_DURATION {
+        return None;
+    }
+    let remaining = 1.0 - elapsed.as_secs_f32() / BELL_FLASH_DURATION.as_secs_f32();
+    Some(PixelRect {
+        x: viewport.x as f32,
+        y: viewport.y as f32,
+        width: viewport.width as f32,
+        height: viewport.height as f32,
+        color: [1.0, 1.0, 1.0, BELL_FLASH_ALPHA * remaining],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bell_flash_fades_out() {
+        let viewport = PaneViewport { pane_id: 0, x: 10, y: 20, width: 300, height: 200, focused: true, font_scale: 1.0 };
+
+        let start = bell_flash_rect(&viewport, Duration::ZERO).unwrap();
+        assert_eq!((start.x, start.y, start.width, start.height), (10.0, 20.0, 300.0, 200.0));
+        assert_eq!(start.color[3], BELL_FLASH_ALPHA);
+
+        let halfway = bell_flash_rect(&viewport, BELL_FLASH_DURATION / 2).unwrap();
+        assert!(halfway.color[3] < start.color[3]);
+
+        assert!(bell_flash_rect(&viewport, BELL_FLASH_DURATION).is_none());
+    }
+}
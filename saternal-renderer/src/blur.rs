@@ -0,0 +1,246 @@
+/// Two-pass GPU Gaussian blur for the wallpaper
+///
+/// The scaled wallpaper is blurred horizontally into an intermediate texture,
+/// then vertically into the texture that gets bound for drawing. This runs once
+/// per blur strength (or size) change instead of blurring on the CPU and
+/// re-uploading the full image.
+use super::shaders::Shader;
+use wgpu;
+use wgpu::util::DeviceExt;
+
+/// Blur uniform data (matches shader layout)
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+struct BlurParams {
+    step: [f32; 2],  // One texel along the blur direction
+    sigma: f32,
+    _padding: f32,
+}
+
+unsafe impl bytemuck::Pod for BlurParams {}
+unsafe impl bytemuck::Zeroable for BlurParams {}
+
+/// Convert the user-facing blur strength (0.0-10.0) to a Gaussian sigma in pixels
+/// (1.0 = 2px, 10.0 = 20px, matching the previous CPU blur)
+pub fn blur_sigma(strength: f32) -> f32 {
+    (strength * 2.0).round().max(1.0)
+}
+
+/// Separable blur pipeline for wallpaper textures
+pub struct BlurRenderer {
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    format: wgpu::TextureFormat,
+}
+
+impl BlurRenderer {
+    pub fn new(device: &wgpu::Device, format: wgpu::TextureFormat) -> Self {
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Blur Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        // Clamp so edge pixels don't pull in the opposite side of the image
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        let pipeline = create_blur_pipeline(device, &bind_group_layout, format);
+
+        Self {
+            pipeline,
+            bind_group_layout,
+            sampler,
+            format,
+        }
+    }
+
+    /// Blur `source` (of the given size) into a new texture
+    pub fn blur(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        source: &wgpu::TextureView,
+        dimensions: (u32, u32),
+        sigma: f32,
+    ) -> wgpu::Texture {
+        let (width, height) = dimensions;
+        let intermediate = self.create_target(device, dimensions, "Wallpaper Blur Intermediate");
+        let output = self.create_target(device, dimensions, "Wallpaper Blur Output");
+        let intermediate_view = intermediate.create_view(&wgpu::TextureViewDescriptor::default());
+        let output_view = output.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Wallpaper Blur Encoder"),
+        });
+
+        // Each pass gets its own uniform buffer - both are read at submit time
+        let passes = [
+            (source, &intermediate_view, [1.0 / width as f32, 0.0]),
+            (&intermediate_view, &output_view, [0.0, 1.0 / height as f32]),
+        ];
+        for (input, target, step) in passes {
+            let params = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Blur Params Buffer"),
+                contents: bytemuck::cast_slice(&[BlurParams { step, sigma, _padding: 0.0 }]),
+                usage: wgpu::BufferUsages::UNIFORM,
+            });
+            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("Blur Bind Group"),
+                layout: &self.bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(input),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::Sampler(&self.sampler),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: params.as_entire_binding(),
+                    },
+                ],
+            });
+
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Wallpaper Blur Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: target,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            render_pass.set_pipeline(&self.pipeline);
+            render_pass.set_bind_group(0, &bind_group, &[]);
+            render_pass.draw(0..3, 0..1);
+        }
+
+        queue.submit(std::iter::once(encoder.finish()));
+        output
+    }
+
+    /// Texture that can be both rendered into and sampled
+    fn create_target(&self, device: &wgpu::Device, dimensions: (u32, u32), label: &str) -> wgpu::Texture {
+        device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(label),
+            size: wgpu::Extent3d {
+                width: dimensions.0,
+                height: dimensions.1,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: self.format,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        })
+    }
+}
+
+/// Create blur render pipeline
+fn create_blur_pipeline(
+    device: &wgpu::Device,
+    bind_group_layout: &wgpu::BindGroupLayout,
+    format: wgpu::TextureFormat,
+) -> wgpu::RenderPipeline {
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("Blur Shader"),
+        source: Shader::Blur.source(),
+    });
+
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("Blur Pipeline Layout"),
+        bind_group_layouts: &[bind_group_layout],
+        push_constant_ranges: &[],
+    });
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Blur Pipeline"),
+        layout: Some(&pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: "vs_main",
+            buffers: &[],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point: "fs_main",
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: None,
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: None,
+            polygon_mode: wgpu::PolygonMode::Fill,
+            unclipped_depth: false,
+            conservative: false,
+        },
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState {
+            count: 1,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        },
+        multiview: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blur_sigma_matches_cpu_radius() {
+        assert_eq!(blur_sigma(0.2), 1.0);
+        assert_eq!(blur_sigma(2.0), 4.0);
+        assert_eq!(blur_sigma(10.0), 20.0);
+    }
+}
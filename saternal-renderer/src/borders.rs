@@ -1,6 +1,8 @@
 /// GPU-accelerated pane border rendering
 /// Generates border rectangles for rendering with the border shader
-use crate::selection::renderer::PaneViewport;
+use saternal_core::config::PaneBorderConfig;
+use saternal_core::selection::PaneViewport;
+use super::shaders::Shader;
 use wgpu;
 use wgpu::util::DeviceExt;
 
@@ -33,40 +35,20 @@ pub struct BorderUniforms {
 unsafe impl bytemuck::Pod for BorderUniforms {}
 unsafe impl bytemuck::Zeroable for BorderUniforms {}
 
-/// Border configuration
-#[derive(Debug, Clone)]
-pub struct BorderConfig {
-    pub enabled: bool,
-    pub thickness: u32,
-    pub active_color: [f32; 4],    // RGBA
-    pub inactive_color: [f32; 4],
-}
-
-impl Default for BorderConfig {
-    fn default() -> Self {
-        Self {
-            enabled: true,
-            thickness: 2,
-            active_color: [0.29, 0.56, 0.89, 0.6],   // #4A90E2 blue with 60% opacity
-            inactive_color: [0.24, 0.24, 0.24, 0.4], // #3C3C3C gray with 40% opacity
-        }
-    }
-}
-
 /// Border renderer for panes
 pub struct BorderRenderer {
     uniform_buffer: wgpu::Buffer,
     bind_group: wgpu::BindGroup,
     pub bind_group_layout: wgpu::BindGroupLayout,
     pipeline: wgpu::RenderPipeline,
-    config: BorderConfig,
+    config: PaneBorderConfig,
     current_uniforms: BorderUniforms,
     dirty: bool,
 }
 
 impl BorderRenderer {
     pub fn new(device: &wgpu::Device, surface_format: wgpu::TextureFormat) -> Self {
-        let config = BorderConfig::default();
+        let config = PaneBorderConfig::default();
 
         let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             label: Some("Border Bind Group Layout"),
@@ -169,6 +151,15 @@ impl BorderRenderer {
         self.dirty = true;
     }
 
+    /// Apply the `[panes.border]` settings
+    pub fn set_config(&mut self, config: PaneBorderConfig) {
+        self.current_uniforms.thickness = config.thickness as f32;
+        self.current_uniforms.active_color = config.active_color;
+        self.current_uniforms.inactive_color = config.inactive_color;
+        self.config = config;
+        self.dirty = true;
+    }
+
     /// Upload uniforms to GPU
     pub fn upload_uniforms(&mut self, queue: &wgpu::Queue) {
         if self.dirty {
@@ -183,7 +174,7 @@ impl BorderRenderer {
 
     /// Check if borders should be rendered
     pub fn has_borders(&self) -> bool {
-        self.config.enabled && self.current_uniforms.count > 0
+        self.config.enabled && self.config.thickness > 0 && self.current_uniforms.count > 0
     }
 
     /// Get the bind group for rendering
@@ -196,6 +187,15 @@ impl BorderRenderer {
         &self.pipeline
     }
 
+    /// Rebuild the pipeline from the shader on disk, keeping the old one if it doesn't compile
+    #[cfg(feature = "shader-hot-reload")]
+    pub(crate) fn reload_pipeline(&mut self, device: &wgpu::Device, surface_format: wgpu::TextureFormat) {
+        let create = || create_border_pipeline(device, &self.bind_group_layout, surface_format);
+        if let Some(pipeline) = super::shaders::rebuild(device, "border", create) {
+            self.pipeline = pipeline;
+        }
+    }
+
     /// Get instance count for rendering
     pub fn instance_count(&self) -> u32 {
         self.current_uniforms.count
@@ -252,7 +252,7 @@ fn create_border_pipeline(
 ) -> wgpu::RenderPipeline {
     let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
         label: Some("Border Shader"),
-        source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/border.wgsl").into()),
+        source: Shader::Border.source(),
     });
 
     let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
@@ -1,5 +1,10 @@
+use alacritty_terminal::term::cell::{Cell, Flags};
 use alacritty_terminal::vte::ansi::{Color as AnsiColor, NamedColor};
 use super::theme::ColorPalette;
+use saternal_core::selection::SelectionStyle;
+
+/// 8-bit RGB color
+type Rgb8 = (u8, u8, u8);
 
 /// Convert ANSI terminal color to RGB tuple
 pub(crate) fn ansi_to_rgb(color: &AnsiColor) -> (u8, u8, u8) {
@@ -91,14 +96,93 @@ pub(crate) fn ansi_to_rgb_with_palette(color: &AnsiColor, palette: &ColorPalette
         }
         AnsiColor::Spec(rgb) => (rgb.r, rgb.g, rgb.b),
         AnsiColor::Indexed(idx) => {
-            if *idx < 16 {
-                let color = palette.get_ansi_color(*idx);
-                ((color[0] * 255.0) as u8, (color[1] * 255.0) as u8, (color[2] * 255.0) as u8)
-            } else {
-                // For colors 16-255, use the spec or default to foreground
-                let fg = palette.foreground;
-                ((fg[0] * 255.0) as u8, (fg[1] * 255.0) as u8, (fg[2] * 255.0) as u8)
-            }
+            let color = palette.indexed_color(*idx);
+            ((color[0] * 255.0) as u8, (color[1] * 255.0) as u8, (color[2] * 255.0) as u8)
         }
     }
 }
+
+/// Decode an sRGB color to linear (alpha unchanged)
+pub(crate) fn srgb_to_linear(color: [f32; 4]) -> [f32; 4] {
+    let decode = |c: f32| {
+        if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+    };
+    [decode(color[0]), decode(color[1]), decode(color[2]), color[3]]
+}
+
+/// Text color of a cell, and the background it needs drawn (None: the default background)
+/// Reverse video swaps the two: the cell is painted with its foreground color
+/// and the text takes the background color
+pub(crate) fn cell_colors(cell: &Cell, palette: &ColorPalette) -> (Rgb8, Option<Rgb8>) {
+    let fg = ansi_to_rgb_with_palette(&cell.fg, palette);
+    let bg = match cell.bg {
+        AnsiColor::Named(NamedColor::Background) => None,
+        color => Some(ansi_to_rgb_with_palette(&color, palette)),
+    };
+    if cell.flags.contains(Flags::INVERSE) {
+        let [r, g, b, _] = palette.background.map(|channel| (channel * 255.0) as u8);
+        (bg.unwrap_or((r, g, b)), Some(fg))
+    } else {
+        (fg, bg)
+    }
+}
+
+/// Text color of a selected cell, and the background to paint under it (None: leave it to the overlay)
+///
+/// Invert swaps the cell's text and background colors; the palette's selection_fg,
+/// when set, replaces the text color in either style.
+pub(crate) fn selected_cell_colors(
+    fg: Rgb8,
+    bg: Rgb8,
+    style: SelectionStyle,
+    palette: &ColorPalette,
+) -> (Rgb8, Option<Rgb8>) {
+    let override_fg = palette
+        .selection_fg
+        .map(|color| ((color[0] * 255.0) as u8, (color[1] * 255.0) as u8, (color[2] * 255.0) as u8));
+    match style {
+        SelectionStyle::Highlight => (override_fg.unwrap_or(fg), None),
+        SelectionStyle::Invert => (override_fg.unwrap_or(bg), Some(fg)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alacritty_terminal::vte::ansi::Rgb;
+
+    #[test]
+    fn test_cell_background() {
+        let palette = ColorPalette::default();
+        let [fg_r, fg_g, fg_b, _] = palette.foreground.map(|channel| (channel * 255.0) as u8);
+        let [bg_r, bg_g, bg_b, _] = palette.background.map(|channel| (channel * 255.0) as u8);
+        let mut cell = Cell::default();
+        assert_eq!(cell_colors(&cell, &palette), ((fg_r, fg_g, fg_b), None));
+
+        cell.bg = AnsiColor::Spec(Rgb { r: 10, g: 20, b: 30 });
+        assert_eq!(cell_colors(&cell, &palette), ((fg_r, fg_g, fg_b), Some((10, 20, 30))));
+
+        // Reverse video paints the foreground color behind the text, drawn in the background color
+        let mut inverse = Cell {
+            fg: AnsiColor::Spec(Rgb { r: 1, g: 2, b: 3 }),
+            flags: Flags::INVERSE,
+            ..Default::default()
+        };
+        assert_eq!(cell_colors(&inverse, &palette), ((bg_r, bg_g, bg_b), Some((1, 2, 3))));
+
+        inverse.bg = AnsiColor::Spec(Rgb { r: 40, g: 50, b: 60 });
+        assert_eq!(cell_colors(&inverse, &palette), ((40, 50, 60), Some((1, 2, 3))));
+    }
+
+    #[test]
+    fn test_selected_cell_colors() {
+        let mut palette = ColorPalette::default();
+        let (fg, bg) = ((200, 200, 200), (20, 20, 30));
+        assert_eq!(selected_cell_colors(fg, bg, SelectionStyle::Highlight, &palette), (fg, None));
+        assert_eq!(selected_cell_colors(fg, bg, SelectionStyle::Invert, &palette), (bg, Some(fg)));
+
+        palette.selection_fg = Some([1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(selected_cell_colors(fg, bg, SelectionStyle::Highlight, &palette), ((255, 0, 0), None));
+        assert_eq!(selected_cell_colors(fg, bg, SelectionStyle::Invert, &palette), ((255, 0, 0), Some(fg)));
+    }
+}
@@ -0,0 +1,43 @@
+/// Copy feedback: the selection overlay pulses brighter for a moment after a copy
+use std::time::Duration;
+
+/// How long the pulse takes to fade back to the selection color
+pub const COPY_FLASH_DURATION: Duration = Duration::from_millis(250);
+
+/// How far the selection color is pulled toward white right after the copy
+const COPY_FLASH_STRENGTH: f32 = 0.5;
+
+/// Overlay opacity at the peak of the pulse
+const COPY_FLASH_ALPHA: f32 = 0.6;
+
+/// The selection color `elapsed` into a pulse, fading linearly; None once it has faded
+pub fn copy_flash_color(base: [f32; 4], elapsed: Duration) -> Option<[f32; 4]> {
+    if elapsed >= COPY_FLASH_DURATION {
+        return None;
+    }
+    let remaining = 1.0 - elapsed.as_secs_f32() / COPY_FLASH_DURATION.as_secs_f32();
+    let toward_white = COPY_FLASH_STRENGTH * remaining;
+    let [r, g, b, a] = base;
+    let lighten = |c: f32| c + (1.0 - c) * toward_white;
+    Some([lighten(r), lighten(g), lighten(b), a + (a.max(COPY_FLASH_ALPHA) - a) * remaining])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_copy_flash_fades_to_selection_color() {
+        let base = [0.2, 0.4, 0.6, 0.3];
+
+        let start = copy_flash_color(base, Duration::ZERO).unwrap();
+        assert!(start[..3].iter().zip(&base[..3]).all(|(flash, base)| flash > base));
+        assert_eq!(start[3], COPY_FLASH_ALPHA);
+
+        let halfway = copy_flash_color(base, COPY_FLASH_DURATION / 2).unwrap();
+        assert!(halfway[0] < start[0] && halfway[0] > base[0]);
+        assert!(halfway[3] < start[3] && halfway[3] > base[3]);
+
+        assert!(copy_flash_color(base, COPY_FLASH_DURATION).is_none());
+    }
+}
@@ -0,0 +1,6 @@
+mod pipeline;
+mod state;
+
+pub use saternal_core::cursor::{CursorConfig, CursorStyle};
+pub use pipeline::create_cursor_pipeline;
+pub use state::{CursorState, CursorTextColors};
@@ -1,19 +1,21 @@
+use crate::shaders::Shader;
 use wgpu;
 
 /// Create cursor render pipeline
 pub fn create_cursor_pipeline(
     device: &wgpu::Device,
     bind_group_layout: &wgpu::BindGroupLayout,
+    text_texture_layout: &wgpu::BindGroupLayout,
     surface_format: wgpu::TextureFormat,
 ) -> wgpu::RenderPipeline {
     let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
         label: Some("Cursor Shader"),
-        source: wgpu::ShaderSource::Wgsl(include_str!("../../shaders/cursor.wgsl").into()),
+        source: Shader::Cursor.source(),
     });
 
     let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
         label: Some("Cursor Pipeline Layout"),
-        bind_group_layouts: &[bind_group_layout],
+        bind_group_layouts: &[bind_group_layout, text_texture_layout],
         push_constant_ranges: &[],
     });
 
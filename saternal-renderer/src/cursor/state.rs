@@ -0,0 +1,380 @@
+use saternal_core::cursor::{CursorConfig, CursorStyle};
+use saternal_core::geometry::Padding;
+use alacritty_terminal::index::Point;
+use std::time::{Duration, Instant};
+use wgpu;
+use wgpu::util::DeviceExt;
+
+/// Cursor uniform data (matches shader layout)
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct CursorUniforms {
+    position: [f32; 2],      // NDC position
+    size: [f32; 2],          // NDC size
+    color: [f32; 4],         // RGBA
+    text_color: [f32; 4],    // Character under a block cursor (alpha 0 = leave as is)
+    cell_fg: [f32; 4],       // Cell colors as stored in the text texture
+    cell_bg: [f32; 4],
+    border: [f32; 2],        // Outline thickness of a hollow cursor, NDC
+    visible: u32,            // 0 or 1
+    style: u32,              // 0=block, 1=beam, 2=underline
+    hollow: u32,             // 1 = draw only the outline
+    _padding: [u32; 3],      // Alignment to 16 bytes
+}
+
+/// Colors for drawing the character under a block cursor in reverse
+#[derive(Copy, Clone, Debug)]
+pub struct CursorTextColors {
+    /// Color the character is drawn in
+    pub text: [f32; 4],
+    /// Foreground and background of the cell, in the text texture's color space
+    pub cell_fg: [f32; 4],
+    pub cell_bg: [f32; 4],
+}
+
+unsafe impl bytemuck::Pod for CursorUniforms {}
+unsafe impl bytemuck::Zeroable for CursorUniforms {}
+
+/// Cursor blinking state
+struct BlinkState {
+    visible: bool,
+    last_toggle: Instant,
+    interval: Duration,
+    /// Last keypress; blinking stops `timeout` after it
+    last_input: Instant,
+    timeout: Option<Duration>,
+}
+
+impl BlinkState {
+    fn new(interval_ms: u64, timeout_secs: u64) -> Self {
+        let now = Instant::now();
+        Self {
+            visible: true,
+            last_toggle: now,
+            interval: Duration::from_millis(interval_ms.max(1)),
+            last_input: now,
+            timeout: (timeout_secs > 0).then(|| Duration::from_secs(timeout_secs)),
+        }
+    }
+
+    /// Idle for longer than the timeout
+    fn timed_out(&self) -> bool {
+        self.timeout.is_some_and(|timeout| self.last_input.elapsed() >= timeout)
+    }
+
+    fn update(&mut self) -> bool {
+        if self.timed_out() {
+            // Stop on the visible phase
+            let changed = !self.visible;
+            self.visible = true;
+            return changed;
+        }
+        let elapsed = self.last_toggle.elapsed();
+        if elapsed >= self.interval {
+            self.visible = !self.visible;
+            self.last_toggle = Instant::now();
+            true // State changed
+        } else {
+            false // No change
+        }
+    }
+
+    /// Show the cursor and start a full interval from now (a key was pressed)
+    fn restart(&mut self) -> bool {
+        let changed = !self.visible;
+        self.visible = true;
+        self.last_toggle = Instant::now();
+        self.last_input = self.last_toggle;
+        changed
+    }
+
+    /// When the next frame is needed to animate the blink; None once it has stopped
+    fn next_toggle(&self) -> Option<Instant> {
+        if !self.timed_out() {
+            Some(self.last_toggle + self.interval)
+        } else if !self.visible {
+            Some(Instant::now())
+        } else {
+            None
+        }
+    }
+}
+
+/// Cursor state management
+pub struct CursorState {
+    pub uniform_buffer: wgpu::Buffer,
+    pub bind_group: wgpu::BindGroup,
+    pub bind_group_layout: wgpu::BindGroupLayout,
+    blink_state: BlinkState,
+    pub config: CursorConfig,
+    /// Color set by the program (OSC 12), used instead of the configured one
+    color_override: Option<[f32; 4]>,
+    text_colors: Option<CursorTextColors>,
+    /// Outline thickness (NDC) while drawn hollow
+    hollow: Option<[f32; 2]>,
+    /// Blinking allowed at all (the global switch; config.blink still applies)
+    blinking: bool,
+    current_uniforms: CursorUniforms,
+}
+
+impl CursorState {
+    pub fn new(device: &wgpu::Device, config: CursorConfig) -> Self {
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Cursor Bind Group Layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+
+        let initial_uniforms = CursorUniforms {
+            position: [0.0, 0.0],
+            size: [0.0, 0.0],
+            color: config.color,
+            text_color: [0.0; 4],
+            cell_fg: [0.0; 4],
+            cell_bg: [0.0; 4],
+            border: [0.0, 0.0],
+            visible: 1,
+            style: config.style as u32,
+            hollow: 0,
+            _padding: [0; 3],
+        };
+
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Cursor Uniform Buffer"),
+            contents: bytemuck::cast_slice(&[initial_uniforms]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Cursor Bind Group"),
+            layout: &bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+        });
+
+        Self {
+            uniform_buffer,
+            bind_group,
+            bind_group_layout,
+            blink_state: BlinkState::new(config.blink_interval_ms, config.blink_timeout_secs),
+            config,
+            color_override: None,
+            text_colors: None,
+            hollow: None,
+            blinking: true,
+            current_uniforms: initial_uniforms,
+        }
+    }
+
+    /// Update cursor blink state; returns true if the cursor appeared or disappeared
+    /// Blinking stops on the visible phase once the cursor has been idle for the timeout
+    pub fn update_blink(&mut self) -> bool {
+        if self.blinks() {
+            self.blink_state.update()
+        } else {
+            false
+        }
+    }
+
+    /// Hold the cursor solid after a keypress: restarts the blink interval and the idle timeout
+    /// Returns true if the cursor was hidden mid-blink
+    pub fn restart_blink(&mut self) -> bool {
+        self.blink_state.restart()
+    }
+
+    /// When the cursor blinks next, if it's blinking (a hollow cursor doesn't)
+    pub fn next_blink(&self) -> Option<Instant> {
+        if self.blinks() && self.hollow.is_none() {
+            self.blink_state.next_toggle()
+        } else {
+            None
+        }
+    }
+
+    /// Update cursor position and visibility
+    pub fn update_position(
+        &mut self,
+        cursor_pos: Point,
+        cell_width: f32,
+        cell_height: f32,
+        window_width: u32,
+        window_height: u32,
+        scroll_offset: usize,
+        hide_cursor: bool,
+        padding: Padding,
+    ) {
+        // Hide cursor if scrolled or terminal mode requests it
+        // Unless force_show is enabled (overrides application hide requests)
+        let should_hide = scroll_offset > 0 || (hide_cursor && !self.config.force_show);
+        
+        // Calculate pixel position in screen coordinates with padding
+        // cursor_pos.line is in grid coordinates (0-indexed from visible top)
+        // When not scrolled, line 0 should render at pixel row 0
+        let pixel_x = padding.left + cursor_pos.column.0 as f32 * cell_width;
+        let pixel_y = padding.top + cursor_pos.line.0 as f32 * cell_height;
+
+        // Convert to normalized device coordinates (-1 to 1)
+        let ndc_x = (pixel_x / window_width as f32) * 2.0 - 1.0;
+        let ndc_y = -((pixel_y / window_height as f32) * 2.0 - 1.0); // Flip Y
+
+        // Calculate size based on style (exact pixel widths)
+        let (width, height) = match self.config.style {
+            CursorStyle::Block => (cell_width, cell_height),
+            CursorStyle::Beam => (2.0, cell_height),  // 2px wide beam
+            CursorStyle::Underline => (cell_width, 2.0),  // 2px tall underline
+        };
+
+        let ndc_width = (width / window_width as f32) * 2.0;
+        let ndc_height = -((height / window_height as f32) * 2.0); // Negative to extend downward in NDC
+        
+        // For underline, adjust Y position to bottom of cell
+        let ndc_y = if matches!(self.config.style, CursorStyle::Underline) {
+            ndc_y + (cell_height - 2.0) / window_height as f32 * 2.0
+        } else {
+            ndc_y
+        };
+
+        // Determine visibility
+        let visible = if should_hide {
+            0
+        } else if self.blinks() {
+            self.blink_state.visible as u32
+        } else {
+            1
+        };
+        
+        log::debug!("Cursor state: pixel=({:.1}, {:.1}), ndc=({:.3}, {:.3}), size=({:.3}, {:.3}), visible={}, scroll={}, hide={}",
+                   pixel_x, pixel_y, ndc_x, ndc_y, ndc_width, ndc_height, visible, scroll_offset, hide_cursor);
+
+        self.current_uniforms = self.uniforms([ndc_x, ndc_y], [ndc_width, ndc_height], visible);
+    }
+
+    /// Update cursor with pre-calculated NDC coordinates (for viewport-based rendering)
+    pub fn update_position_ndc(
+        &mut self,
+        ndc_x: f32,
+        ndc_y: f32,
+        ndc_width: f32,
+        ndc_height: f32,
+        hide_cursor: bool,
+    ) {
+        // A hollow cursor marks where typing would go; it doesn't blink
+        let visible = if hide_cursor {
+            0
+        } else if self.blinks() && self.hollow.is_none() {
+            self.blink_state.visible as u32
+        } else {
+            1
+        };
+
+        self.current_uniforms = self.uniforms([ndc_x, ndc_y], [ndc_width, ndc_height], visible);
+    }
+
+    fn uniforms(&self, position: [f32; 2], size: [f32; 2], visible: u32) -> CursorUniforms {
+        let text = self.text_colors.filter(|_| self.config.style == CursorStyle::Block && self.hollow.is_none());
+        CursorUniforms {
+            position,
+            size,
+            color: self.color_override.unwrap_or(self.config.color),
+            text_color: text.map_or([0.0; 4], |colors| colors.text),
+            cell_fg: text.map_or([0.0; 4], |colors| colors.cell_fg),
+            cell_bg: text.map_or([0.0; 4], |colors| colors.cell_bg),
+            border: self.hollow.unwrap_or_default(),
+            visible,
+            style: self.config.style as u32,
+            hollow: self.hollow.is_some() as u32,
+            _padding: [0; 3],
+        }
+    }
+
+    /// Draw the cursor in a program-chosen color (RGB; alpha stays as configured), or the configured one
+    pub fn set_color_override(&mut self, rgb: Option<[f32; 3]>) {
+        self.color_override = rgb.map(|[r, g, b]| [r, g, b, self.config.color[3]]);
+    }
+
+    /// Colors for reversing the character under a block cursor (None draws the cursor over it)
+    pub fn set_text_colors(&mut self, colors: Option<CursorTextColors>) {
+        self.text_colors = colors;
+    }
+
+    /// Draw only an outline of the given thickness (NDC), or the normal cursor with None
+    pub fn set_hollow(&mut self, border: Option<[f32; 2]>) {
+        self.hollow = border;
+    }
+
+    /// Allow or stop blinking; a stopped cursor stays visible
+    pub fn set_blinking(&mut self, enabled: bool) {
+        self.blinking = enabled;
+        if !enabled {
+            self.blink_state.visible = true;
+        }
+    }
+
+    fn blinks(&self) -> bool {
+        self.config.blink && self.blinking
+    }
+
+    /// Upload uniforms to GPU
+    pub fn upload_uniforms(&self, queue: &wgpu::Queue) {
+        queue.write_buffer(
+            &self.uniform_buffer,
+            0,
+            bytemuck::cast_slice(&[self.current_uniforms]),
+        );
+    }
+
+    /// Check if cursor should be rendered
+    pub fn is_visible(&self) -> bool {
+        self.current_uniforms.visible == 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blink_stops_visible_after_timeout() {
+        let mut blink = BlinkState::new(1, 0);
+        blink.timeout = Some(Duration::from_millis(5));
+        std::thread::sleep(Duration::from_millis(2));
+        assert!(blink.update());
+        assert!(!blink.visible);
+
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(blink.next_toggle().is_some_and(|at| at <= Instant::now()));
+        assert!(blink.update());
+        assert!(blink.visible);
+        assert_eq!(blink.next_toggle(), None);
+        assert!(!blink.update());
+    }
+
+    #[test]
+    fn test_restart_shows_cursor_and_resumes_blinking() {
+        let mut blink = BlinkState::new(1000, 0);
+        blink.timeout = Some(Duration::from_millis(5));
+        blink.visible = false;
+        std::thread::sleep(Duration::from_millis(6));
+        assert!(blink.restart());
+        assert!(blink.visible);
+        assert!(blink.next_toggle().is_some_and(|at| at > Instant::now()));
+        assert!(!blink.restart());
+    }
+
+    #[test]
+    fn test_zero_timeout_blinks_forever() {
+        let blink = BlinkState::new(530, 0);
+        assert_eq!(blink.timeout, None);
+        assert!(blink.next_toggle().is_some());
+    }
+}
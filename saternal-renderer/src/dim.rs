@@ -0,0 +1,31 @@
+//! Inactive pane dimming, applied to each unfocused pane's CPU buffer before compositing
+
+/// Strongest allowed dimming (fully black panes would hide their content)
+pub const MAX_INACTIVE_DIM: f32 = 0.8;
+
+/// Darken the color channels of an RGBA/BGRA buffer by `amount` (0.0 = unchanged)
+/// Alpha is left alone, so transparency and the wallpaper blend are unaffected
+pub fn dim_pixels(buffer: &mut [u8], amount: f32) {
+    let keep = 1.0 - amount.clamp(0.0, MAX_INACTIVE_DIM);
+    for pixel in buffer.chunks_exact_mut(4) {
+        for channel in &mut pixel[..3] {
+            *channel = (*channel as f32 * keep).round() as u8;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dim_pixels_keeps_alpha() {
+        let mut buffer = vec![200, 100, 50, 255, 10, 20, 30, 128];
+        dim_pixels(&mut buffer, 0.5);
+        assert_eq!(buffer, vec![100, 50, 25, 255, 5, 10, 15, 128]);
+
+        let mut unchanged = vec![200, 100, 50, 255];
+        dim_pixels(&mut unchanged, 0.0);
+        assert_eq!(unchanged, vec![200, 100, 50, 255]);
+    }
+}
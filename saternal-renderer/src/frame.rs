@@ -0,0 +1,274 @@
+/// Window frame: rounded corners and an accent outline around the whole window
+/// Drawn last, so the corners clip everything under them
+use super::shaders::Shader;
+use wgpu;
+use wgpu::util::DeviceExt;
+
+/// How the window's edge is drawn
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct WindowFrame {
+    /// Corner radius in pixels (0 = square corners)
+    pub corner_radius: f32,
+    /// Round the top corners (off for a dropdown hanging from the top of the screen)
+    pub round_top: bool,
+    /// Round the bottom corners
+    pub round_bottom: bool,
+    /// Accent outline width in pixels (0 = none)
+    pub border_width: f32,
+    /// RGBA of the outline
+    pub border_color: [f32; 4],
+}
+
+impl WindowFrame {
+    /// Whether the window's edge differs from a plain rectangle
+    pub fn is_visible(&self) -> bool {
+        self.radius(self.round_top) > 0.0 || self.radius(self.round_bottom) > 0.0 || self.border_width > 0.0
+    }
+
+    fn radius(&self, rounded: bool) -> f32 {
+        if rounded { self.corner_radius.max(0.0) } else { 0.0 }
+    }
+}
+
+/// Frame uniform data (matches shader layout with std140 alignment)
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct FrameUniforms {
+    border_color: [f32; 4],  // RGBA of the outline (16 bytes)
+    size: [f32; 2],          // Window size in pixels (8 bytes)
+    radius_top: f32,         // Top corner radius in pixels (4 bytes)
+    radius_bottom: f32,      // Bottom corner radius in pixels (4 bytes)
+    border_width: f32,       // Outline width in pixels (4 bytes)
+    _padding: [f32; 3],      // Padding to 16-byte boundary (12 bytes)
+}
+
+unsafe impl bytemuck::Pod for FrameUniforms {}
+unsafe impl bytemuck::Zeroable for FrameUniforms {}
+
+impl FrameUniforms {
+    /// Uniforms for a `width`×`height` window, with radii and outline no larger than fit in it
+    fn new(frame: &WindowFrame, width: u32, height: u32) -> Self {
+        let (width, height) = (width as f32, height as f32);
+        let max_radius = width.min(height) / 2.0;
+        Self {
+            border_color: frame.border_color,
+            size: [width, height],
+            radius_top: frame.radius(frame.round_top).min(max_radius),
+            radius_bottom: frame.radius(frame.round_bottom).min(max_radius),
+            border_width: frame.border_width.max(0.0).min(max_radius),
+            _padding: [0.0; 3],
+        }
+    }
+}
+
+/// Draws the window frame over a finished frame
+pub struct FrameRenderer {
+    uniform_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    #[cfg_attr(not(feature = "shader-hot-reload"), allow(dead_code))]
+    bind_group_layout: wgpu::BindGroupLayout,
+    mask_pipeline: wgpu::RenderPipeline,
+    border_pipeline: wgpu::RenderPipeline,
+    frame: WindowFrame,
+    current_uniforms: FrameUniforms,
+}
+
+impl FrameRenderer {
+    pub fn new(device: &wgpu::Device, surface_format: wgpu::TextureFormat) -> Self {
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Frame Bind Group Layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+
+        let frame = WindowFrame::default();
+        let initial_uniforms = FrameUniforms::new(&frame, 1, 1);
+
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Frame Uniform Buffer"),
+            contents: bytemuck::cast_slice(&[initial_uniforms]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Frame Bind Group"),
+            layout: &bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+        });
+
+        let mask_pipeline = create_mask_pipeline(device, &bind_group_layout, surface_format);
+        let border_pipeline = create_border_pipeline(device, &bind_group_layout, surface_format);
+
+        Self {
+            uniform_buffer,
+            bind_group,
+            bind_group_layout,
+            mask_pipeline,
+            border_pipeline,
+            frame,
+            current_uniforms: initial_uniforms,
+        }
+    }
+
+    /// Change the corner radius and outline
+    pub fn set_frame(&mut self, frame: WindowFrame) {
+        self.frame = frame;
+    }
+
+    /// Whether there is anything to draw
+    pub fn is_visible(&self) -> bool {
+        self.frame.is_visible()
+    }
+
+    /// Fit the frame to the window size and upload it if it changed
+    pub fn update(&mut self, queue: &wgpu::Queue, window_width: u32, window_height: u32) {
+        let uniforms = FrameUniforms::new(&self.frame, window_width, window_height);
+        if uniforms != self.current_uniforms {
+            self.current_uniforms = uniforms;
+            queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[self.current_uniforms]));
+        }
+    }
+
+    /// Clear the rounded-off corners, then draw the outline
+    pub fn render<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+        render_pass.set_pipeline(&self.mask_pipeline);
+        render_pass.draw(0..3, 0..1);
+        if self.current_uniforms.border_width > 0.0 {
+            render_pass.set_pipeline(&self.border_pipeline);
+            render_pass.draw(0..3, 0..1);
+        }
+    }
+
+    /// Rebuild the pipelines from the shader on disk, keeping the old ones if it doesn't compile
+    #[cfg(feature = "shader-hot-reload")]
+    pub(crate) fn reload_pipeline(&mut self, device: &wgpu::Device, surface_format: wgpu::TextureFormat) {
+        let create_mask = || create_mask_pipeline(device, &self.bind_group_layout, surface_format);
+        if let Some(pipeline) = super::shaders::rebuild(device, "frame mask", create_mask) {
+            self.mask_pipeline = pipeline;
+        }
+        let create_border = || create_border_pipeline(device, &self.bind_group_layout, surface_format);
+        if let Some(pipeline) = super::shaders::rebuild(device, "frame border", create_border) {
+            self.border_pipeline = pipeline;
+        }
+    }
+}
+
+/// The mask writes coverage as alpha and the blend keeps that share of the frame
+/// underneath, so the corners fade to transparent with antialiased edges
+const KEEP_COVERAGE: wgpu::BlendComponent = wgpu::BlendComponent {
+    src_factor: wgpu::BlendFactor::Zero,
+    dst_factor: wgpu::BlendFactor::SrcAlpha,
+    operation: wgpu::BlendOperation::Add,
+};
+
+fn create_mask_pipeline(
+    device: &wgpu::Device,
+    bind_group_layout: &wgpu::BindGroupLayout,
+    surface_format: wgpu::TextureFormat,
+) -> wgpu::RenderPipeline {
+    let blend = wgpu::BlendState { color: KEEP_COVERAGE, alpha: KEEP_COVERAGE };
+    create_frame_pipeline(device, bind_group_layout, surface_format, "fs_mask", blend)
+}
+
+fn create_border_pipeline(
+    device: &wgpu::Device,
+    bind_group_layout: &wgpu::BindGroupLayout,
+    surface_format: wgpu::TextureFormat,
+) -> wgpu::RenderPipeline {
+    create_frame_pipeline(device, bind_group_layout, surface_format, "fs_border", wgpu::BlendState::ALPHA_BLENDING)
+}
+
+/// Create a frame pipeline drawing one window-covering triangle with the fragment shader `entry_point`
+fn create_frame_pipeline(
+    device: &wgpu::Device,
+    bind_group_layout: &wgpu::BindGroupLayout,
+    surface_format: wgpu::TextureFormat,
+    entry_point: &str,
+    blend: wgpu::BlendState,
+) -> wgpu::RenderPipeline {
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("Frame Shader"),
+        source: Shader::Frame.source(),
+    });
+
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("Frame Pipeline Layout"),
+        bind_group_layouts: &[bind_group_layout],
+        push_constant_ranges: &[],
+    });
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Frame Pipeline"),
+        layout: Some(&pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: "vs_main",
+            buffers: &[],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point,
+            targets: &[Some(wgpu::ColorTargetState {
+                format: surface_format,
+                blend: Some(blend),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: None,
+            polygon_mode: wgpu::PolygonMode::Fill,
+            unclipped_depth: false,
+            conservative: false,
+        },
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState {
+            count: 1,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        },
+        multiview: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dropdown_rounds_only_bottom_corners_that_fit() {
+        let frame = WindowFrame {
+            corner_radius: 12.0,
+            round_top: false,
+            round_bottom: true,
+            border_width: 1.0,
+            border_color: [1.0, 1.0, 1.0, 0.5],
+        };
+        let uniforms = FrameUniforms::new(&frame, 800, 600);
+        assert_eq!((uniforms.radius_top, uniforms.radius_bottom), (0.0, 12.0));
+        assert_eq!(uniforms.size, [800.0, 600.0]);
+
+        // A window shorter than two radii gets pill-shaped ends, not overlapping corners
+        let squashed = FrameUniforms::new(&frame, 800, 10);
+        assert_eq!(squashed.radius_bottom, 5.0);
+
+        // Square corners and no outline leave nothing to draw
+        assert!(frame.is_visible());
+        assert!(!WindowFrame { round_bottom: false, border_width: 0.0, ..frame }.is_visible());
+        assert!(!WindowFrame::default().is_visible());
+    }
+}
@@ -0,0 +1,205 @@
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Number of recent frames averaged for FPS and frame time
+const FRAME_SAMPLES: usize = 120;
+
+/// Frames whose GPU work may still be running when the next one is submitted
+pub const FRAMES_IN_FLIGHT: usize = 3;
+
+/// Window over which PTY read throughput is measured
+const THROUGHPUT_WINDOW: Duration = Duration::from_secs(1);
+
+/// Rolling renderer and PTY statistics for the debug HUD
+#[derive(Debug, Default)]
+pub struct FrameStats {
+    /// (frame start, time spent rendering) for the most recent frames
+    frames: VecDeque<(Instant, Duration)>,
+    /// GPU time (submit to done) of the most recent frames that finished in time
+    gpu_frames: VecDeque<Duration>,
+    gpu_ring: TimingRing,
+    /// (read time, bytes) for PTY reads inside the throughput window
+    pty_reads: VecDeque<(Instant, usize)>,
+    pub glyph_instances: usize,
+    pub atlas_glyphs: usize,
+    /// Fraction of the glyph atlas already packed (0.0-1.0)
+    pub atlas_occupancy: f32,
+}
+
+impl FrameStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a rendered frame that started at `start` and took `elapsed`
+    pub fn record_frame(&mut self, start: Instant, elapsed: Duration) {
+        if self.frames.len() == FRAME_SAMPLES {
+            self.frames.pop_front();
+        }
+        self.frames.push_back((start, elapsed));
+    }
+
+    /// Take the next timing slot for a frame submitted at `submitted`
+    ///
+    /// Fill the returned slot with the time the GPU finished it (from
+    /// `Queue::on_submitted_work_done`). The frame that last used the slot is
+    /// sampled now, so the stats never wait on the GPU.
+    pub fn submit_gpu_frame(&mut self, submitted: Instant) -> GpuDone {
+        let (finished, done) = self.gpu_ring.submit(submitted);
+        if let Some(elapsed) = finished {
+            if self.gpu_frames.len() == FRAME_SAMPLES {
+                self.gpu_frames.pop_front();
+            }
+            self.gpu_frames.push_back(elapsed);
+        }
+        done
+    }
+
+    /// Record bytes read from a PTY
+    pub fn record_pty_bytes(&mut self, now: Instant, bytes: usize) {
+        self.pty_reads.push_back((now, bytes));
+        self.expire_pty_reads(now);
+    }
+
+    /// Frames per second over the sampled frames (0 until two frames are seen)
+    pub fn fps(&self) -> f32 {
+        match (self.frames.front(), self.frames.back()) {
+            (Some((first, _)), Some((last, _))) if self.frames.len() > 1 => {
+                let span = last.duration_since(*first).as_secs_f32();
+                if span > 0.0 {
+                    (self.frames.len() - 1) as f32 / span
+                } else {
+                    0.0
+                }
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// Average time spent rendering a frame
+    pub fn avg_frame_time(&self) -> Duration {
+        if self.frames.is_empty() {
+            return Duration::ZERO;
+        }
+        let total: Duration = self.frames.iter().map(|(_, elapsed)| *elapsed).sum();
+        total / self.frames.len() as u32
+    }
+
+    /// Average GPU time of the sampled frames (zero until a slot comes around)
+    pub fn avg_gpu_time(&self) -> Duration {
+        if self.gpu_frames.is_empty() {
+            return Duration::ZERO;
+        }
+        self.gpu_frames.iter().sum::<Duration>() / self.gpu_frames.len() as u32
+    }
+
+    /// PTY bytes per second over the last second
+    pub fn pty_throughput(&mut self, now: Instant) -> usize {
+        self.expire_pty_reads(now);
+        self.pty_reads.iter().map(|(_, bytes)| bytes).sum()
+    }
+
+    /// Lines of the HUD box
+    pub fn lines(&mut self, now: Instant) -> Vec<String> {
+        let throughput = self.pty_throughput(now);
+        vec![
+            format!("{:.0} fps", self.fps()),
+            format!(
+                "frame {:.1} ms, gpu {:.1} ms",
+                self.avg_frame_time().as_secs_f64() * 1000.0,
+                self.avg_gpu_time().as_secs_f64() * 1000.0,
+            ),
+            format!("{} glyphs", self.glyph_instances),
+            format!("atlas {} ({:.0}%)", self.atlas_glyphs, self.atlas_occupancy * 100.0),
+            format!("pty {:.1} KB/s", throughput as f64 / 1024.0),
+        ]
+    }
+
+    fn expire_pty_reads(&mut self, now: Instant) {
+        while let Some((time, _)) = self.pty_reads.front() {
+            if now.duration_since(*time) <= THROUGHPUT_WINDOW {
+                break;
+            }
+            self.pty_reads.pop_front();
+        }
+    }
+}
+
+/// Slot a frame's GPU completion time is written to
+pub type GpuDone = Arc<Mutex<Option<Instant>>>;
+
+/// Triple-buffered ring of GPU timing slots, one per frame in flight
+#[derive(Debug, Default)]
+struct TimingRing {
+    /// (submitted, done) per slot
+    slots: [(Option<Instant>, GpuDone); FRAMES_IN_FLIGHT],
+    next: usize,
+}
+
+impl TimingRing {
+    /// Reuse the next slot for a frame submitted at `submitted`
+    /// Returns the GPU time of the frame it held, if that one had finished
+    fn submit(&mut self, submitted: Instant) -> (Option<Duration>, GpuDone) {
+        let (previous, done) = &mut self.slots[self.next];
+        self.next = (self.next + 1) % FRAMES_IN_FLIGHT;
+
+        let finished = match (previous.replace(submitted), done.lock().take()) {
+            (Some(start), Some(end)) => Some(end.saturating_duration_since(start)),
+            _ => None,
+        };
+        // A frame still running when its slot comes back is dropped; its
+        // callback writes into the old slot nobody reads
+        *done = Arc::new(Mutex::new(None));
+        (finished, done.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fps_and_frame_time() {
+        let mut stats = FrameStats::new();
+        let start = Instant::now();
+        for i in 0..11 {
+            stats.record_frame(start + Duration::from_millis(i * 100), Duration::from_millis(4));
+        }
+        assert!((stats.fps() - 10.0).abs() < 0.01);
+        assert_eq!(stats.avg_frame_time(), Duration::from_millis(4));
+    }
+
+    #[test]
+    fn test_gpu_times_are_read_a_ring_later() {
+        let mut stats = FrameStats::new();
+        let start = Instant::now();
+        let frame = |i: u64| start + Duration::from_millis(i * 16);
+
+        let slots: Vec<GpuDone> = (0..FRAMES_IN_FLIGHT as u64).map(|i| stats.submit_gpu_frame(frame(i))).collect();
+        // Nothing comes back until the ring wraps around
+        assert_eq!(stats.avg_gpu_time(), Duration::ZERO);
+        *slots[0].lock() = Some(frame(0) + Duration::from_millis(3));
+        // The second frame is still running when its slot is reused
+        stats.submit_gpu_frame(frame(3));
+        stats.submit_gpu_frame(frame(4));
+        assert_eq!(stats.avg_gpu_time(), Duration::from_millis(3));
+
+        // Its late callback doesn't reach the frame now in the slot
+        *slots[1].lock() = Some(frame(5));
+        *slots[2].lock() = Some(frame(2) + Duration::from_millis(5));
+        stats.submit_gpu_frame(frame(5));
+        assert_eq!(stats.avg_gpu_time(), Duration::from_millis(4));
+    }
+
+    #[test]
+    fn test_pty_throughput_window() {
+        let mut stats = FrameStats::new();
+        let start = Instant::now();
+        stats.record_pty_bytes(start, 1000);
+        stats.record_pty_bytes(start + Duration::from_millis(500), 500);
+        assert_eq!(stats.pty_throughput(start + Duration::from_millis(900)), 1500);
+        assert_eq!(stats.pty_throughput(start + Duration::from_millis(1200)), 500);
+    }
+}
@@ -1,6 +1,7 @@
-use crate::font::FontManager;
+use saternal_core::font::FontManager;
 use anyhow::Result;
 use std::collections::HashMap;
+use std::sync::Arc;
 use wgpu;
 
 /// UV coordinates for a glyph in the atlas texture
@@ -16,13 +17,20 @@ pub struct GlyphUV {
     pub offset_y: f32, // Vertical bearing (distance from baseline)
 }
 
+/// Side of the fully-covered block reserved at the atlas origin for solid fills
+const SOLID_BLOCK_SIZE: u32 = 4;
+
 /// Manages a texture atlas of pre-rasterized glyphs
 pub struct GlyphAtlas {
     pub texture: wgpu::Texture,
     pub view: wgpu::TextureView,
     pub sampler: wgpu::Sampler,
-    pub bind_group_layout: wgpu::BindGroupLayout,
+    /// Shared by atlases made with `sibling`, so one glyph pipeline draws any of them
+    pub bind_group_layout: Arc<wgpu::BindGroupLayout>,
     pub bind_group: wgpu::BindGroup,
+
+    /// Effective font size the glyphs are rasterized at
+    font_size: f32,
     
     /// Map from character to UV coordinates
     glyph_map: HashMap<char, GlyphUV>,
@@ -44,6 +52,30 @@ impl GlyphAtlas {
         queue: &wgpu::Queue,
         font_manager: &FontManager,
         atlas_size: u32,
+    ) -> Result<Self> {
+        let bind_group_layout = Arc::new(create_bind_group_layout(device));
+        Self::with_layout(device, queue, font_manager, atlas_size, font_manager.effective_font_size(), bind_group_layout)
+    }
+
+    /// Another atlas of the same size for a different effective font size
+    /// (e.g. another display's scale factor), drawable by the same pipeline
+    pub fn sibling(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        font_manager: &FontManager,
+        font_size: f32,
+    ) -> Result<Self> {
+        Self::with_layout(device, queue, font_manager, self.atlas_width, font_size, self.bind_group_layout.clone())
+    }
+
+    fn with_layout(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        font_manager: &FontManager,
+        atlas_size: u32,
+        font_size: f32,
+        bind_group_layout: Arc<wgpu::BindGroupLayout>,
     ) -> Result<Self> {
         // Create atlas texture
         let texture = device.create_texture(&wgpu::TextureDescriptor {
@@ -74,28 +106,6 @@ impl GlyphAtlas {
             ..Default::default()
         });
 
-        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-            label: Some("Glyph Atlas Bind Group Layout"),
-            entries: &[
-                wgpu::BindGroupLayoutEntry {
-                    binding: 0,
-                    visibility: wgpu::ShaderStages::FRAGMENT,
-                    ty: wgpu::BindingType::Texture {
-                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
-                        view_dimension: wgpu::TextureViewDimension::D2,
-                        multisampled: false,
-                    },
-                    count: None,
-                },
-                wgpu::BindGroupLayoutEntry {
-                    binding: 1,
-                    visibility: wgpu::ShaderStages::FRAGMENT,
-                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
-                    count: None,
-                },
-            ],
-        });
-
         let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
             label: Some("Glyph Atlas Bind Group"),
             layout: &bind_group_layout,
@@ -117,6 +127,7 @@ impl GlyphAtlas {
             sampler,
             bind_group_layout,
             bind_group,
+            font_size,
             glyph_map: HashMap::new(),
             atlas_width: atlas_size,
             atlas_height: atlas_size,
@@ -125,15 +136,48 @@ impl GlyphAtlas {
             row_height: 0,
         };
 
-        // Pre-generate common ASCII characters
+        // Solid block for cell backgrounds, then common ASCII characters
+        atlas.reserve_solid_block(queue);
         atlas.generate_ascii_set(device, queue, font_manager)?;
 
-        log::info!("Created glyph atlas {}x{} with {} glyphs", 
-                   atlas_size, atlas_size, atlas.glyph_map.len());
+        log::info!("Created glyph atlas {}x{} with {} glyphs at {}px",
+                   atlas_size, atlas_size, atlas.glyph_map.len(), font_size);
 
         Ok(atlas)
     }
 
+    /// Upload a fully-covered block at the origin so quads can sample solid color
+    fn reserve_solid_block(&mut self, queue: &wgpu::Queue) {
+        let size = SOLID_BLOCK_SIZE;
+        queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &self.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            &vec![255u8; (size * size) as usize],
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(size),
+                rows_per_image: Some(size),
+            },
+            wgpu::Extent3d {
+                width: size,
+                height: size,
+                depth_or_array_layers: 1,
+            },
+        );
+        self.pack_x = size + 2; // 2px padding
+        self.row_height = size;
+    }
+
+    /// UV of the solid block's center (full coverage, no filtering bleed)
+    pub fn solid_uv(&self) -> [f32; 2] {
+        let center = SOLID_BLOCK_SIZE as f32 / 2.0;
+        [center / self.atlas_width as f32, center / self.atlas_height as f32]
+    }
+
     /// Generate all printable ASCII characters
     fn generate_ascii_set(
         &mut self,
@@ -169,7 +213,7 @@ impl GlyphAtlas {
         }
 
         // Rasterize glyph
-        let (metrics, bitmap) = font_manager.rasterize(c);
+        let (metrics, bitmap) = font_manager.font().rasterize(c, self.font_size);
         
         let glyph_width = metrics.width as u32;
         let glyph_height = metrics.height as u32;
@@ -258,6 +302,11 @@ impl GlyphAtlas {
             .ok_or_else(|| anyhow::anyhow!("Glyph '{}' not found in atlas after add attempt", c))
     }
 
+    /// Effective font size the glyphs are rasterized at
+    pub fn font_size(&self) -> f32 {
+        self.font_size
+    }
+
     /// Get atlas dimensions
     pub fn dimensions(&self) -> (u32, u32) {
         (self.atlas_width, self.atlas_height)
@@ -267,4 +316,34 @@ impl GlyphAtlas {
     pub fn glyph_count(&self) -> usize {
         self.glyph_map.len()
     }
+
+    /// Fraction of atlas rows already used by packed glyphs (0.0-1.0)
+    pub fn occupancy(&self) -> f32 {
+        ((self.pack_y + self.row_height) as f32 / self.atlas_height as f32).min(1.0)
+    }
+}
+
+/// Texture and sampler bindings shared by every glyph atlas
+fn create_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+    device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("Glyph Atlas Bind Group Layout"),
+        entries: &[
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                count: None,
+            },
+        ],
+    })
 }
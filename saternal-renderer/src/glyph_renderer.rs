@@ -1,9 +1,12 @@
-use crate::constants::{PADDING_LEFT, PADDING_TOP};
-use crate::font::FontManager;
-use crate::renderer::color::ansi_to_rgb_with_palette;
-use crate::renderer::theme::ColorPalette;
+use saternal_core::blink::{BlinkClock, BLINK};
+use saternal_core::font::FontManager;
+use saternal_core::geometry::Padding;
+use crate::color::{cell_colors, selected_cell_colors};
+use crate::shaders::Shader;
+use crate::theme::ColorPalette;
+use saternal_core::selection::{SelectionRange, SelectionStyle};
 use alacritty_terminal::grid::Dimensions;
-use alacritty_terminal::index::{Column, Line};
+use alacritty_terminal::index::{Column, Line, Point};
 use alacritty_terminal::term::Term;
 use anyhow::Result;
 use wgpu;
@@ -14,6 +17,9 @@ use super::glyph_atlas::GlyphAtlas;
 const MAX_INSTANCE_CAPACITY: usize = 100_000;
 const INITIAL_INSTANCE_CAPACITY: usize = 10_000;
 
+/// Instance flag: hidden during the off phase of the blink clock
+const GLYPH_BLINK: u32 = 1;
+
 /// Instance data for a single glyph (sent to GPU)
 #[repr(C)]
 #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
@@ -27,6 +33,8 @@ struct GlyphInstance {
     uv_max: [f32; 2],
     /// Color (RGBA)
     color: [f32; 4],
+    /// GLYPH_* bits
+    flags: u32,
 }
 
 /// Uniform data for screen dimensions
@@ -35,7 +43,9 @@ struct GlyphInstance {
 struct ScreenUniforms {
     width: f32,
     height: f32,
-    _padding: [f32; 2],
+    /// Seconds on the blink clock, and its interval (0 = blinking off)
+    time: f32,
+    blink_interval: f32,
 }
 
 /// GPU-based glyph renderer using instanced rendering
@@ -52,6 +62,9 @@ pub struct GlyphRenderer {
     cell_width: f32,
     cell_height: f32,
     baseline_offset: f32,
+    padding: Padding,
+    /// Place quads on whole pixels so the atlas is sampled texel for texel
+    pixel_snap: bool,
 }
 
 impl GlyphRenderer {
@@ -97,101 +110,7 @@ impl GlyphRenderer {
             }],
         });
 
-        // Create render pipeline
-        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Glyph Shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/glyph.wgsl").into()),
-        });
-
-        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-            label: Some("Glyph Pipeline Layout"),
-            bind_group_layouts: &[
-                &atlas.bind_group_layout,           // @group(0) - Atlas texture
-                &uniform_bind_group_layout,          // @group(1) - Screen uniforms
-            ],
-            push_constant_ranges: &[],
-        });
-
-        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Glyph Pipeline"),
-            layout: Some(&pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &shader,
-                entry_point: "vs_main",
-                buffers: &[wgpu::VertexBufferLayout {
-                    array_stride: std::mem::size_of::<GlyphInstance>() as u64,
-                    step_mode: wgpu::VertexStepMode::Instance,
-                    attributes: &[
-                        // position
-                        wgpu::VertexAttribute {
-                            offset: 0,
-                            shader_location: 0,
-                            format: wgpu::VertexFormat::Float32x2,
-                        },
-                        // size
-                        wgpu::VertexAttribute {
-                            offset: 8,
-                            shader_location: 1,
-                            format: wgpu::VertexFormat::Float32x2,
-                        },
-                        // uv_min
-                        wgpu::VertexAttribute {
-                            offset: 16,
-                            shader_location: 2,
-                            format: wgpu::VertexFormat::Float32x2,
-                        },
-                        // uv_max
-                        wgpu::VertexAttribute {
-                            offset: 24,
-                            shader_location: 3,
-                            format: wgpu::VertexFormat::Float32x2,
-                        },
-                        // color
-                        wgpu::VertexAttribute {
-                            offset: 32,
-                            shader_location: 4,
-                            format: wgpu::VertexFormat::Float32x4,
-                        },
-                    ],
-                }],
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &shader,
-                entry_point: "fs_main",
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: surface_format,
-                    blend: Some(wgpu::BlendState {
-                        color: wgpu::BlendComponent {
-                            src_factor: wgpu::BlendFactor::One,
-                            dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
-                            operation: wgpu::BlendOperation::Add,
-                        },
-                        alpha: wgpu::BlendComponent {
-                            src_factor: wgpu::BlendFactor::One,
-                            dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
-                            operation: wgpu::BlendOperation::Add,
-                        },
-                    }),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                strip_index_format: None,
-                front_face: wgpu::FrontFace::Ccw,
-                cull_mode: None,
-                unclipped_depth: false,
-                polygon_mode: wgpu::PolygonMode::Fill,
-                conservative: false,
-            },
-            depth_stencil: None,
-            multisample: wgpu::MultisampleState {
-                count: 1,
-                mask: !0,
-                alpha_to_coverage_enabled: false,
-            },
-            multiview: None,
-        });
+        let pipeline = create_glyph_pipeline(device, &atlas.bind_group_layout, &uniform_bind_group_layout, surface_format);
 
         // Create instance buffer (initial capacity: 10,000 glyphs)
         let instance_capacity = INITIAL_INSTANCE_CAPACITY.min(MAX_INSTANCE_CAPACITY);
@@ -213,6 +132,28 @@ impl GlyphRenderer {
             cell_width,
             cell_height,
             baseline_offset,
+            padding: Padding::default(),
+            pixel_snap: false,
+        }
+    }
+
+    /// Space between the screen edges and the grid
+    pub fn set_padding(&mut self, padding: Padding) {
+        self.padding = padding;
+    }
+
+    pub fn set_pixel_snap(&mut self, pixel_snap: bool) {
+        self.pixel_snap = pixel_snap;
+    }
+
+    /// Top-left pixel of a cell, rounded to a pixel boundary with pixel snapping
+    fn cell_origin(&self, col: usize, row: usize) -> (f32, f32) {
+        let x = self.padding.left + col as f32 * self.cell_width;
+        let y = self.padding.top + row as f32 * self.cell_height;
+        if self.pixel_snap {
+            (x.round(), y.round())
+        } else {
+            (x, y)
         }
     }
 
@@ -228,7 +169,8 @@ impl GlyphRenderer {
         let uniforms = ScreenUniforms {
             width: width as f32,
             height: height as f32,
-            _padding: [0.0, 0.0],
+            time: 0.0,
+            blink_interval: 0.0,
         };
 
         queue.write_buffer(
@@ -238,7 +180,18 @@ impl GlyphRenderer {
         );
     }
 
+    /// Advance the blink phase the shader hides blinking glyphs with
+    pub fn update_blink(&self, queue: &wgpu::Queue, clock: &BlinkClock) {
+        let interval = if clock.is_enabled() { clock.interval().as_secs_f32() } else { 0.0 };
+        queue.write_buffer(
+            &self.uniform_buffer,
+            std::mem::offset_of!(ScreenUniforms, time) as u64,
+            bytemuck::cast_slice(&[clock.elapsed().as_secs_f32(), interval]),
+        );
+    }
+
     /// Generate instances from terminal grid
+    /// Cells inside `selection` (screen rows) are colored per `selection_style`
     pub fn generate_instances<T>(
         &mut self,
         queue: &wgpu::Queue,
@@ -250,8 +203,15 @@ impl GlyphRenderer {
         palette: &ColorPalette,
         screen_width: u32,
         screen_height: u32,
+        selection: &[SelectionRange],
+        selection_style: SelectionStyle,
     ) -> Result<()> {
+        // Backgrounds go first so glyphs draw over them
+        let mut backgrounds = Vec::new();
         let mut instances = Vec::new();
+        let solid_uv = atlas.solid_uv();
+        let cell_ndc_width = (self.cell_width / screen_width as f32) * 2.0;
+        let cell_ndc_height = -((self.cell_height / screen_height as f32) * 2.0);
         
         let rows = term.screen_lines();
         let cols = term.columns();
@@ -267,6 +227,31 @@ impl GlyphRenderer {
             for col_idx in 0..cols {
                 let column = Column(col_idx);
                 let cell = &term.grid()[line][column];
+                let (mut fg, mut bg) = cell_colors(cell, palette);
+
+                // Selected cells may get their own text color and (inverted) a solid background
+                if selection.iter().any(|range| range.contains(Point::new(Line(row_idx as i32), column))) {
+                    let [r, g, b, _] = palette.background.map(|channel| (channel * 255.0) as u8);
+                    let (text, background) = selected_cell_colors(fg, bg.unwrap_or((r, g, b)), selection_style, palette);
+                    fg = text;
+                    bg = background.or(bg);
+                }
+
+                // Full-cell background for colored cells, including blank ones
+                if let Some((bg_r, bg_g, bg_b)) = bg {
+                    let (cell_x, cell_y) = self.cell_origin(col_idx, row_idx);
+                    backgrounds.push(GlyphInstance {
+                        position: [
+                            (cell_x / screen_width as f32) * 2.0 - 1.0,
+                            -((cell_y / screen_height as f32) * 2.0 - 1.0),
+                        ],
+                        size: [cell_ndc_width, cell_ndc_height],
+                        uv_min: solid_uv,
+                        uv_max: solid_uv,
+                        color: [bg_r as f32 / 255.0, bg_g as f32 / 255.0, bg_b as f32 / 255.0, 1.0],
+                        flags: 0,
+                    });
+                }
 
                 let c = cell.c;
                 if c == '\0' || c == ' ' {
@@ -282,12 +267,10 @@ impl GlyphRenderer {
                     }
                 };
 
-                // Get color from palette
-                let (fg_r, fg_g, fg_b) = ansi_to_rgb_with_palette(&cell.fg, palette);
+                let (fg_r, fg_g, fg_b) = fg;
 
                 // Calculate pixel position
-                let cell_x = PADDING_LEFT + col_idx as f32 * self.cell_width;
-                let cell_y = PADDING_TOP + row_idx as f32 * self.cell_height;
+                let (cell_x, cell_y) = self.cell_origin(col_idx, row_idx);
 
                 // Calculate glyph position using baseline alignment
                 let baseline_y = cell_y + self.baseline_offset;
@@ -313,10 +296,14 @@ impl GlyphRenderer {
                         fg_b as f32 / 255.0,
                         1.0,
                     ],
+                    flags: if cell.flags.contains(BLINK) { GLYPH_BLINK } else { 0 },
                 });
             }
         }
 
+        backgrounds.append(&mut instances);
+        let instances = backgrounds;
+
         // Validate instance count against capacity before assignment
         let instances_len = instances.len();
         
@@ -403,4 +390,123 @@ impl GlyphRenderer {
     pub fn instance_count(&self) -> usize {
         self.instance_count
     }
+
+    /// Rebuild the pipeline from the shader on disk, keeping the old one if it doesn't compile
+    #[cfg(feature = "shader-hot-reload")]
+    pub(crate) fn reload_pipeline(&mut self, device: &wgpu::Device, atlas: &GlyphAtlas, surface_format: wgpu::TextureFormat) {
+        let uniform_layout = &self.uniform_bind_group_layout;
+        let create = || create_glyph_pipeline(device, &atlas.bind_group_layout, uniform_layout, surface_format);
+        if let Some(pipeline) = super::shaders::rebuild(device, "glyph", create) {
+            self.pipeline = pipeline;
+        }
+    }
+}
+
+/// Create the instanced glyph pipeline
+fn create_glyph_pipeline(
+    device: &wgpu::Device,
+    atlas_layout: &wgpu::BindGroupLayout,
+    uniform_layout: &wgpu::BindGroupLayout,
+    surface_format: wgpu::TextureFormat,
+) -> wgpu::RenderPipeline {
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("Glyph Shader"),
+        source: Shader::Glyph.source(),
+    });
+
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("Glyph Pipeline Layout"),
+        bind_group_layouts: &[
+            atlas_layout,                  // @group(0) - Atlas texture
+            uniform_layout,                // @group(1) - Screen uniforms
+        ],
+        push_constant_ranges: &[],
+    });
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Glyph Pipeline"),
+        layout: Some(&pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: "vs_main",
+            buffers: &[wgpu::VertexBufferLayout {
+                array_stride: std::mem::size_of::<GlyphInstance>() as u64,
+                step_mode: wgpu::VertexStepMode::Instance,
+                attributes: &[
+                    // position
+                    wgpu::VertexAttribute {
+                        offset: 0,
+                        shader_location: 0,
+                        format: wgpu::VertexFormat::Float32x2,
+                    },
+                    // size
+                    wgpu::VertexAttribute {
+                        offset: 8,
+                        shader_location: 1,
+                        format: wgpu::VertexFormat::Float32x2,
+                    },
+                    // uv_min
+                    wgpu::VertexAttribute {
+                        offset: 16,
+                        shader_location: 2,
+                        format: wgpu::VertexFormat::Float32x2,
+                    },
+                    // uv_max
+                    wgpu::VertexAttribute {
+                        offset: 24,
+                        shader_location: 3,
+                        format: wgpu::VertexFormat::Float32x2,
+                    },
+                    // color
+                    wgpu::VertexAttribute {
+                        offset: 32,
+                        shader_location: 4,
+                        format: wgpu::VertexFormat::Float32x4,
+                    },
+                    // flags
+                    wgpu::VertexAttribute {
+                        offset: 48,
+                        shader_location: 5,
+                        format: wgpu::VertexFormat::Uint32,
+                    },
+                ],
+            }],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point: "fs_main",
+            targets: &[Some(wgpu::ColorTargetState {
+                format: surface_format,
+                blend: Some(wgpu::BlendState {
+                    color: wgpu::BlendComponent {
+                        src_factor: wgpu::BlendFactor::One,
+                        dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                        operation: wgpu::BlendOperation::Add,
+                    },
+                    alpha: wgpu::BlendComponent {
+                        src_factor: wgpu::BlendFactor::One,
+                        dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                        operation: wgpu::BlendOperation::Add,
+                    },
+                }),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: None,
+            unclipped_depth: false,
+            polygon_mode: wgpu::PolygonMode::Fill,
+            conservative: false,
+        },
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState {
+            count: 1,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        },
+        multiview: None,
+    })
 }
@@ -0,0 +1,215 @@
+use anyhow::Result;
+use log::{info, warn};
+use saternal_core::config::{PowerPreference, RendererConfig};
+use wgpu;
+
+/// Graphics APIs to pick an adapter from
+/// Windows sticks to DX12, which every GPU there has a driver for; whether
+/// Vulkan is installed, and how well it presents, depends on the vendor
+#[cfg(target_os = "windows")]
+const BACKENDS: wgpu::Backends = wgpu::Backends::DX12;
+#[cfg(not(target_os = "windows"))]
+const BACKENDS: wgpu::Backends = wgpu::Backends::PRIMARY;
+
+/// GPU context including device, queue, surface, and configuration
+/// 
+/// Safety: The Surface has a 'static lifetime, but is actually tied to the Window's lifetime.
+/// This is sound because:
+/// 1. We store Arc<Window> to keep the window alive
+/// 2. Rust drops struct fields in declaration order (top to bottom)
+/// 3. Therefore, surface drops before _window, preventing use-after-free
+/// 4. The window cannot be dropped while the surface exists
+pub(crate) struct GpuContext {
+    pub device: wgpu::Device,
+    pub queue: wgpu::Queue,
+    pub surface: wgpu::Surface<'static>,
+    pub config: wgpu::SurfaceConfiguration,
+    /// Present modes the surface supports (Fifo always is)
+    pub present_modes: Vec<wgpu::PresentMode>,
+    pub adapter_info: wgpu::AdapterInfo,
+    _window: std::sync::Arc<winit::window::Window>, // Keep window alive - must be last for drop order
+}
+
+impl GpuContext {
+    /// Initialize GPU context with wgpu (Metal, DX12, or Vulkan)
+    /// 
+    /// Takes Arc<Window> to ensure proper lifetime management. The Window is kept alive
+    /// via the stored Arc, ensuring the Surface remains valid through drop order guarantees.
+    pub async fn new(window: std::sync::Arc<winit::window::Window>, settings: &RendererConfig) -> Result<Self> {
+        info!("Initializing GPU renderer");
+
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+            backends: BACKENDS,
+            ..Default::default()
+        });
+
+        // Create surface from window reference, then extend lifetime to 'static
+        // Safety: The window Arc is stored in the struct and drops after the surface,
+        // ensuring the window outlives the surface through Rust's drop order guarantees
+        let surface_temp = instance.create_surface(window.as_ref())?;
+        let surface: wgpu::Surface<'static> = unsafe { std::mem::transmute(surface_temp) };
+
+        let named = settings.adapter.as_deref().and_then(|name| {
+            let adapter = find_adapter(&instance, &surface, name);
+            if adapter.is_none() {
+                warn!("No GPU adapter matching {:?}, using the power preference", name);
+            }
+            adapter
+        });
+        let adapter = match named {
+            Some(adapter) => adapter,
+            None => instance
+                .request_adapter(&wgpu::RequestAdapterOptions {
+                    power_preference: match settings.power_preference {
+                        PowerPreference::Low => wgpu::PowerPreference::LowPower,
+                        PowerPreference::High => wgpu::PowerPreference::HighPerformance,
+                    },
+                    compatible_surface: Some(&surface),
+                    force_fallback_adapter: false,
+                })
+                .await
+                .ok_or_else(|| anyhow::anyhow!("Failed to find suitable GPU adapter"))?,
+        };
+
+        let adapter_info = adapter.get_info();
+        info!("Using GPU adapter: {:?}", adapter_info);
+        saternal_core::crash::set_gpu_info(format!(
+            "{} ({:?}, {} {})",
+            adapter_info.name, adapter_info.backend, adapter_info.driver, adapter_info.driver_info
+        ));
+
+        let (device, queue) = adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    label: Some("Saternal Device"),
+                    required_features: wgpu::Features::empty(),
+                    required_limits: wgpu::Limits::default(),
+                },
+                None,
+            )
+            .await?;
+
+        let size = window.as_ref().inner_size();
+
+        // Get the preferred surface format
+        let surface_caps = surface.get_capabilities(&adapter);
+        let surface_format = surface_caps
+            .formats
+            .iter()
+            .copied()
+            .find(|f| f.is_srgb())
+            .unwrap_or(surface_caps.formats[0]);
+
+        // Choose alpha mode - MUST be PreMultiplied to match shader output
+        // The text rasterizer outputs premultiplied alpha (rgb * a), so the surface
+        // must use PreMultiplied mode for correct blending
+        let alpha_mode =
+            if surface_caps
+                .alpha_modes
+                .contains(&wgpu::CompositeAlphaMode::PreMultiplied)
+            {
+                wgpu::CompositeAlphaMode::PreMultiplied
+            } else if surface_caps
+                .alpha_modes
+                .contains(&wgpu::CompositeAlphaMode::PostMultiplied)
+            {
+                log::warn!("PreMultiplied alpha not supported, falling back to PostMultiplied - transparency may not work correctly");
+                wgpu::CompositeAlphaMode::PostMultiplied
+            } else {
+                log::warn!("Neither PreMultiplied nor PostMultiplied alpha supported, using default - transparency may not work");
+                surface_caps.alpha_modes[0]
+            };
+
+        info!(
+            "Using surface format: {:?}, alpha mode: {:?}",
+            surface_format, alpha_mode
+        );
+
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format: surface_format,
+            width: size.width.max(1),
+            height: size.height.max(1),
+            present_mode: wgpu::PresentMode::Fifo, // VSync
+            desired_maximum_frame_latency: 2,
+            alpha_mode,
+            view_formats: vec![],
+        };
+
+        surface.configure(&device, &config);
+
+        Ok(Self {
+            device,
+            queue,
+            surface,
+            config,
+            present_modes: surface_caps.present_modes,
+            adapter_info,
+            _window: window, // Must be last to ensure correct drop order
+        })
+    }
+}
+
+/// First adapter that can draw to `surface` and whose name contains `name` (any case)
+fn find_adapter(instance: &wgpu::Instance, surface: &wgpu::Surface, name: &str) -> Option<wgpu::Adapter> {
+    let name = name.to_lowercase();
+    instance
+        .enumerate_adapters(BACKENDS)
+        .into_iter()
+        .find(|adapter| {
+            adapter.get_info().name.to_lowercase().contains(&name) && adapter.is_surface_supported(surface)
+        })
+}
+
+/// Lines printed by the `renderer info` command
+pub(crate) fn describe(
+    info: &wgpu::AdapterInfo,
+    format: wgpu::TextureFormat,
+    present_mode: wgpu::PresentMode,
+) -> String {
+    let driver = [info.driver.as_str(), info.driver_info.as_str()]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let backend = if driver.is_empty() {
+        format!("{:?}", info.backend)
+    } else {
+        format!("{:?} ({})", info.backend, driver)
+    };
+    format!(
+        "Adapter: {} ({:?})\nBackend: {}\nSurface format: {:?}\nPresent mode: {:?}",
+        info.name, info.device_type, backend, format, present_mode
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_lists_adapter_backend_format_and_present_mode() {
+        let info = wgpu::AdapterInfo {
+            name: "Apple M2".to_string(),
+            vendor: 0,
+            device: 0,
+            device_type: wgpu::DeviceType::IntegratedGpu,
+            driver: String::new(),
+            driver_info: String::new(),
+            backend: wgpu::Backend::Metal,
+        };
+        assert_eq!(
+            describe(&info, wgpu::TextureFormat::Bgra8UnormSrgb, wgpu::PresentMode::Fifo),
+            "Adapter: Apple M2 (IntegratedGpu)\nBackend: Metal\nSurface format: Bgra8UnormSrgb\nPresent mode: Fifo"
+        );
+
+        let info = wgpu::AdapterInfo {
+            driver: "NVIDIA".to_string(),
+            driver_info: "550.54".to_string(),
+            backend: wgpu::Backend::Vulkan,
+            ..info
+        };
+        assert!(describe(&info, wgpu::TextureFormat::Bgra8UnormSrgb, wgpu::PresentMode::Mailbox)
+            .contains("Backend: Vulkan (NVIDIA 550.54)\n"));
+    }
+}
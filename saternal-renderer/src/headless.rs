@@ -0,0 +1,260 @@
+/// Rendering without a window, for benchmarks, tests, and CI
+///
+/// Draws the way the windowed renderer draws a pane: the CPU text rasterizer
+/// fills a window-sized buffer, which is uploaded and composited by the same
+/// text pipeline (wallpaper and opacity uniforms included) when an adapter can
+/// be had without a surface (a software one will do). GPU frames go to an
+/// offscreen texture and can be read back; nothing is presented. The cursor
+/// and other overlays aren't drawn.
+use saternal_core::font::FontManager;
+use saternal_core::geometry::Padding;
+use saternal_core::selection::SelectionStyle;
+use alacritty_terminal::term::Term;
+use anyhow::Result;
+use image::RgbaImage;
+use log::info;
+use wgpu;
+
+use super::opacity::OpacityUniforms;
+use super::pipeline::{create_render_pipeline, create_vertex_buffer};
+use super::screenshot;
+use super::text_rasterizer::TextRasterizer;
+use super::texture::TextureManager;
+use super::theme::ColorPalette;
+use super::wallpaper::WallpaperManager;
+
+/// Pixel format headless frames are drawn in (what macOS surfaces use)
+const HEADLESS_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Bgra8UnormSrgb;
+
+struct HeadlessGpu {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    /// The rasterized window, as the windowed renderer uploads it each frame
+    texture_manager: TextureManager,
+    /// No wallpaper: its 1x1 placeholder
+    wallpaper_manager: WallpaperManager,
+    opacity_uniforms: OpacityUniforms,
+    render_pipeline: wgpu::RenderPipeline,
+    vertex_buffer: wgpu::Buffer,
+    /// Offscreen stand-in for the surface
+    frame: wgpu::Texture,
+}
+
+/// Text rendering into a fixed-size frame that is never shown
+pub struct HeadlessRenderer {
+    font_manager: FontManager,
+    text_rasterizer: TextRasterizer,
+    palette: ColorPalette,
+    width: u32,
+    height: u32,
+    gpu: Option<HeadlessGpu>,
+}
+
+impl HeadlessRenderer {
+    /// CPU rendering only; call enable_gpu() for composited frames
+    pub fn new(font_family: &str, font_size: f32, width: u32, height: u32) -> Result<Self> {
+        Ok(Self::with_font(FontManager::new(font_family, font_size)?, width, height))
+    }
+
+    /// Render with a font that's already loaded (FontManager::bundled() for snapshots)
+    pub fn with_font(font_manager: FontManager, width: u32, height: u32) -> Self {
+        let (cell_width, cell_height, baseline_offset) = font_manager.cell_metrics();
+        Self {
+            font_manager,
+            text_rasterizer: TextRasterizer::new(cell_width, cell_height, baseline_offset),
+            palette: ColorPalette::default(),
+            width: width.max(1),
+            height: height.max(1),
+            gpu: None,
+        }
+    }
+
+    /// Set up a GPU device without a surface; fails when there is no adapter at all
+    pub fn enable_gpu(&mut self) -> Result<()> {
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+        let adapter = [false, true]
+            .into_iter()
+            .find_map(|force_fallback_adapter| {
+                futures::executor::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+                    power_preference: wgpu::PowerPreference::HighPerformance,
+                    compatible_surface: None,
+                    force_fallback_adapter,
+                }))
+            })
+            .ok_or_else(|| anyhow::anyhow!("No GPU adapter available for headless rendering"))?;
+        info!("Headless rendering on {:?}", adapter.get_info());
+
+        let (device, queue) = futures::executor::block_on(adapter.request_device(
+            &wgpu::DeviceDescriptor {
+                label: Some("Saternal Headless Device"),
+                required_features: wgpu::Features::empty(),
+                required_limits: wgpu::Limits::default(),
+            },
+            None,
+        ))?;
+
+        let texture_manager = TextureManager::new(&device, self.width, self.height, HEADLESS_FORMAT);
+        let wallpaper_manager = WallpaperManager::new(&device);
+        // Opaque, as a window without transparency shows it
+        let opacity_uniforms = OpacityUniforms::new(&device, 0.0, 1.0, false);
+        let render_pipeline = create_render_pipeline(
+            &device,
+            &texture_manager.bind_group_layout,
+            wallpaper_manager.bind_group_layout(),
+            opacity_uniforms.bind_group_layout(),
+            HEADLESS_FORMAT,
+        );
+        let vertex_buffer = create_vertex_buffer(&device);
+        let frame = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Headless Frame Texture"),
+            size: wgpu::Extent3d { width: self.width, height: self.height, depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: HEADLESS_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+
+        self.gpu = Some(HeadlessGpu {
+            device,
+            queue,
+            texture_manager,
+            wallpaper_manager,
+            opacity_uniforms,
+            render_pipeline,
+            vertex_buffer,
+            frame,
+        });
+        Ok(())
+    }
+
+    pub fn has_gpu(&self) -> bool {
+        self.gpu.is_some()
+    }
+
+    /// Columns and rows that fit the frame (with the default padding)
+    pub fn grid_size(&self) -> (usize, usize) {
+        let (cell_width, cell_height, _) = self.font_manager.cell_metrics();
+        Padding::default().grid_size(self.width, self.height, cell_width, cell_height)
+    }
+
+    /// Draw the screen with the CPU rasterizer, as a pane is drawn
+    pub fn rasterize<T>(&self, term: &Term<T>) -> Result<Vec<u8>> {
+        self.text_rasterizer.render_to_buffer(
+            term,
+            &self.font_manager,
+            self.width,
+            self.height,
+            0,
+            None,
+            HEADLESS_FORMAT,
+            &self.palette,
+            true,
+            &[],
+            SelectionStyle::default(),
+        )
+    }
+
+    /// Rasterize the screen as a single pane filling the window, upload it, and
+    /// composite it into the offscreen frame with the windowed renderer's pipeline
+    pub fn draw_frame<T>(&mut self, term: &Term<T>) -> Result<()> {
+        let buffer = self.rasterize(term)?;
+        let gpu = self
+            .gpu
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Headless GPU not enabled"))?;
+        let (width, height) = (self.width, self.height);
+
+        gpu.queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &gpu.texture_manager.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            &buffer,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(width * 4),
+                rows_per_image: Some(height),
+            },
+            wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+        );
+
+        let view = gpu.frame.create_view(&wgpu::TextureViewDescriptor::default());
+        let mut encoder = gpu.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Headless Frame Encoder"),
+        });
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Headless Frame Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            render_pass.set_pipeline(&gpu.render_pipeline);
+            render_pass.set_bind_group(0, &gpu.texture_manager.bind_group, &[]);
+            render_pass.set_bind_group(1, gpu.wallpaper_manager.bind_group(), &[]);
+            render_pass.set_bind_group(2, gpu.opacity_uniforms.bind_group(), &[]);
+            render_pass.set_vertex_buffer(0, gpu.vertex_buffer.slice(..));
+            render_pass.draw(0..6, 0..1);
+        }
+        gpu.queue.submit(std::iter::once(encoder.finish()));
+        // Let the frame finish so each call measures one whole frame's work
+        gpu.device.poll(wgpu::Maintain::Wait);
+        Ok(())
+    }
+
+    /// Draw a frame (see draw_frame) and read it back
+    pub fn render_frame<T>(&mut self, term: &Term<T>) -> Result<RgbaImage> {
+        self.draw_frame(term)?;
+        let gpu = self.gpu.as_ref().expect("draw_frame checked for a GPU");
+        let (width, height) = (self.width, self.height);
+
+        let bytes_per_row = screenshot::padded_bytes_per_row(width);
+        let readback = gpu.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Headless Frame Readback"),
+            size: (bytes_per_row * height) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = gpu.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Headless Readback Encoder"),
+        });
+        encoder.copy_texture_to_buffer(
+            gpu.frame.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &readback,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(bytes_per_row),
+                    rows_per_image: Some(height),
+                },
+            },
+            wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+        );
+        gpu.queue.submit(std::iter::once(encoder.finish()));
+
+        let slice = readback.slice(..);
+        let (tx, rx) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = tx.send(result);
+        });
+        gpu.device.poll(wgpu::Maintain::Wait);
+        rx.recv()??;
+
+        let image = screenshot::to_rgba_image(&slice.get_mapped_range(), width, height, bytes_per_row, true);
+        readback.unmap();
+        Ok(image)
+    }
+}
@@ -0,0 +1,2188 @@
+//! GPU rendering for Saternal (wgpu/Metal)
+//!
+//! Draws the pane trees of `saternal-core` into a window surface, or into
+//! offscreen frames with `HeadlessRenderer`. `Renderer` is the wgpu
+//! implementation of `saternal_core::RendererBackend`.
+mod bell;
+mod blur;
+mod borders;
+mod color;
+mod copy_flash;
+mod dim;
+mod frame;
+pub mod cursor;
+pub mod frame_stats;
+mod glyph_atlas;
+mod glyph_renderer;
+mod gpu;
+mod headless;
+mod momentum;
+mod opacity;
+mod pipeline;
+mod post_process;
+pub mod scrollbar;
+pub mod screenshot;
+mod selection;
+pub(crate) mod shaders;
+mod text_rasterizer;
+mod texture;
+mod ui_box;
+mod wallpaper;
+
+use saternal_core::config::{ColorSpace, RendererConfig};
+use saternal_core::blink::{has_blinking_text, BlinkClock};
+use saternal_core::font::{Antialias, FontManager};
+use saternal_core::geometry::Padding;
+use saternal_core::output_parser::TERM_LOCK_TIMEOUT;
+use saternal_core::pane::PaneNode;
+use saternal_core::search::LineFilter;
+use saternal_core::selection::{SelectionRange, SelectionStyle, PaneViewport, calculate_pane_viewports};
+use saternal_core::RendererBackend;
+use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::index::Point;
+use alacritty_terminal::term::color::Colors;
+use alacritty_terminal::term::{Term, TermMode};
+use alacritty_terminal::vte::ansi::NamedColor;
+use anyhow::Result;
+use log::info;
+use parking_lot::Mutex;
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use wgpu;
+
+use bell::bell_flash_rect;
+use dim::dim_pixels;
+use borders::BorderRenderer;
+use frame::FrameRenderer;
+use ui_box::UiBox;
+pub use frame::WindowFrame;
+use color::{cell_colors, srgb_to_linear};
+use copy_flash::copy_flash_color;
+use cursor::{create_cursor_pipeline, CursorConfig, CursorState, CursorStyle, CursorTextColors};
+use frame_stats::FrameStats;
+use glyph_atlas::GlyphAtlas;
+use glyph_renderer::GlyphRenderer;
+use momentum::ScrollMomentum;
+pub use saternal_core::constants::{DEFAULT_SCROLL_DECELERATION, DEFAULT_SCROLL_MULTIPLIER};
+use gpu::GpuContext;
+use opacity::OpacityUniforms;
+use pipeline::{create_render_pipeline, create_vertex_buffer};
+use post_process::PostProcess;
+use scrollbar::{offset_for_anchor, ScrollbarRenderer, ScrollbarState};
+use selection::SelectionRenderer;
+use text_rasterizer::{PaneTarget, TextRasterizer};
+use texture::TextureManager;
+pub use headless::HeadlessRenderer;
+#[cfg(feature = "shader-hot-reload")]
+pub use shaders::ShaderWatcher;
+pub use saternal_core::theme::{self, ColorPalette};
+use wallpaper::WallpaperManager;
+pub use saternal_core::wallpaper::{WallpaperMode, WallpaperOverride};
+
+/// A pane rendered on the CPU: its viewport, pixels, and header strip pixels
+type RenderedPane<'a> = (&'a PaneViewport, Vec<u8>, Option<Vec<u8>>);
+
+/// Opacity of the padlock shown at password prompts
+const LOCK_ICON_ALPHA: f32 = 0.7;
+
+/// Padlock in the top-right corner of a pane input is locked to (the focused border's blue)
+const LOCK_BADGE_COLOR: [f32; 4] = [0.29, 0.56, 0.89, 0.9];
+/// Gap in pixels between the lock badge and the pane's edges
+const LOCK_BADGE_INSET: i32 = 4;
+
+/// Least opacity of selection and search tints over a wallpaper
+const MIN_WALLPAPER_HIGHLIGHT_ALPHA: f32 = 0.45;
+
+/// Redraws requested right away after a skipped frame before waiting for the next real one
+const MAX_FRAME_RETRIES: u32 = 3;
+
+/// Rest of a history command that starts with what's typed at a pane's prompt,
+/// drawn faded after its cursor
+#[derive(Debug, Clone, PartialEq)]
+pub struct Autosuggestion {
+    pub pane_id: usize,
+    /// Cell the suggestion starts in (the cursor, at the end of the typed text)
+    pub cell: Point,
+    pub text: String,
+}
+
+// Deleted: ScrollAnimation spring physics (Step 2 - Delete unnecessary complexity)
+// Replaced with simple fractional scrolling for smooth, jitter-free scrolling
+
+/// GPU-accelerated renderer using wgpu/Metal
+/// 
+/// Safety: The Surface has a 'static lifetime, but is actually tied to the Window's lifetime.
+/// This is sound because:
+/// 1. We store Arc<Window> to keep the window alive
+/// 2. Rust drops struct fields in declaration order (top to bottom)
+/// 3. Therefore, surface drops before _window, preventing use-after-free
+pub struct Renderer {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    surface: wgpu::Surface<'static>,
+    config: wgpu::SurfaceConfiguration,
+    present_modes: Vec<wgpu::PresentMode>,  // Supported by the surface, for low latency mode
+    low_latency: bool,  // Present without waiting for vsync; the app draws right after input
+    adapter_info: wgpu::AdapterInfo,  // GPU the surface was created on, for `renderer info`
+    font_manager: FontManager,
+    texture_manager: TextureManager,
+    glyph_atlas: GlyphAtlas,
+    spare_atlases: Vec<GlyphAtlas>,  // Ready for the other displays' scale factors, swapped in on a move
+    display_scale_factors: Vec<f64>,  // Scale factors of the connected displays
+    glyph_renderer: GlyphRenderer,
+    text_rasterizer: TextRasterizer, // Keep for backward compatibility during transition
+    zoomed_fonts: HashMap<u32, (FontManager, TextRasterizer)>,  // Font scale bits -> font and rasterizer of zoomed panes
+    render_pipeline: wgpu::RenderPipeline,
+    vertex_buffer: wgpu::Buffer,
+    scroll_offset: f32,  // Fractional scroll position for smooth scrolling
+    scroll_multiplier: f32,  // Lines per mouse wheel notch
+    scroll_momentum: ScrollMomentum,
+    cursor_state: CursorState,
+    cursor_pipeline: wgpu::RenderPipeline,
+    color_palette: ColorPalette,
+    selection_renderer: SelectionRenderer,
+    search_renderer: SelectionRenderer,  // Search matches
+    search_focus_renderer: SelectionRenderer,  // The current search match, drawn over the others
+    border_renderer: BorderRenderer,
+    scrollbar_renderer: ScrollbarRenderer,
+    bell_renderer: ScrollbarRenderer,  // Same rect overlay, used for visual bell flashes
+    bell_flashes: HashMap<usize, Instant>,  // Pane id -> when its bell rang
+    frame_renderer: FrameRenderer,  // Rounded window corners and accent outline
+    copy_flash: Option<Instant>,  // When the selection was last copied (pulses the overlay)
+    search_markers: Vec<i32>,  // Grid lines of search matches (negative = history)
+    wallpaper_manager: WallpaperManager,
+    wallpaper_path: Option<String>,  // Global wallpaper (config / `wallpaper` command)
+    wallpaper_opacity: f32,
+    wallpaper_override: Option<WallpaperOverride>,  // Active tab's own wallpaper
+    opacity_uniforms: OpacityUniforms,
+    content_hidden: bool,  // Privacy lock: draw backgrounds only
+    window_focused: bool,  // Cursors are drawn hollow while the window is in the background
+    text_blink: BlinkClock,  // Phase of blinking text (SGR 5), on the cursor's blink interval
+    blinking_text: bool,  // The last frame showed blinking text, so it needs redraws to animate
+    blink_drawn: bool,  // Blink phase of the last frame (true = blinking text shown)
+    inactive_pane_dim: f32,  // How much darker unfocused panes are drawn (0.0 = off)
+    pane_headers: bool,  // Draw a title strip above each pane
+    padding: Padding,  // Between each pane's edges and its grid
+    default_badge: Option<String>,  // Badge for panes that didn't set their own (placeholders filled in)
+    ssh_badge: bool,  // Panes running ssh are badged with the remote host instead of the default
+    command_durations: bool,  // Annotate finished commands' prompt lines with their run times
+    autosuggestions: bool,  // Suggest the rest of a history command as it's typed
+    autosuggestion: Option<Autosuggestion>,  // Suggestion for the focused pane's prompt
+    status_line: Option<String>,  // Find query, picker, hints or notice along the bottom edge
+    selection_viewport: Option<PaneViewport>,  // Focused pane as of the last frame (selections are drawn there)
+    selection_ranges: Vec<SelectionRange>,  // Screen rows of the focused pane, for per-cell selection colors
+    selection_style: SelectionStyle,
+    line_filter: Option<LineFilter>,  // Focused pane shows only these lines (search filter mode)
+    dropped_frames: u32,  // Frames skipped in a row because no surface texture could be had
+    frame_stats: FrameStats,
+    stats_hud: bool,  // Show frame statistics (toggled by the `hud` command)
+    post_process: Option<PostProcess>,  // User shader run over each finished frame
+    post_process_animate: bool,  // Redraw continuously so the shader's `post.time` moves
+    _window: std::sync::Arc<winit::window::Window>, // Keep window alive - must be last for drop order
+}
+
+/// Cursor color for a pane: set by its program (OSC 12) or configured
+fn cursor_color(config: &CursorConfig, colors: &Colors) -> [f32; 4] {
+    match colors[NamedColor::Cursor] {
+        Some(rgb) => [rgb.r as f32 / 255.0, rgb.g as f32 / 255.0, rgb.b as f32 / 255.0, config.color[3]],
+        None => config.color,
+    }
+}
+
+impl Renderer {
+    /// Create a new renderer
+    ///
+    /// Takes Arc<Window> to ensure proper lifetime management through drop order guarantees.
+    pub async fn new(
+        window: std::sync::Arc<winit::window::Window>,
+        font_family: &str,
+        font_size: f32,
+        cursor_config: CursorConfig,
+        color_palette: ColorPalette,
+        wallpaper_path: Option<&str>,
+        wallpaper_opacity: f32,
+        background_opacity: f32,
+        gpu_settings: &RendererConfig,
+    ) -> Result<Self> {
+        // Initialize GPU context
+        let gpu = GpuContext::new(window.clone(), gpu_settings).await?;
+
+        // Get current DPI scale factor
+        let scale_factor = window.as_ref().scale_factor();
+        let font_manager = FontManager::new_with_scale(font_family, font_size, scale_factor)?;
+
+        // Calculate cell dimensions and baseline using effective font size
+        let (cell_width, cell_height, baseline_offset) = font_manager.cell_metrics();
+
+        // Create glyph atlas (2048x2048 texture)
+        let glyph_atlas = GlyphAtlas::new(&gpu.device, &gpu.queue, &font_manager, 2048)?;
+        
+        // Create GPU glyph renderer
+        let mut glyph_renderer = GlyphRenderer::new(
+            &gpu.device,
+            gpu.config.format,
+            &glyph_atlas,
+            cell_width,
+            cell_height,
+            baseline_offset,
+            gpu.config.width,
+            gpu.config.height,
+        );
+        
+        // Upload initial screen dimensions
+        glyph_renderer.update_screen_size(&gpu.queue, gpu.config.width, gpu.config.height);
+
+        // Create text rasterizer (kept for backward compatibility)
+        let text_rasterizer = TextRasterizer::new(cell_width, cell_height, baseline_offset);
+
+        // Create texture manager
+        let texture_manager = TextureManager::new(
+            &gpu.device,
+            gpu.config.width,
+            gpu.config.height,
+            gpu.config.format,
+        );
+
+        // Create wallpaper manager, sized to the surface so the image is
+        // resampled once at the monitor's physical resolution
+        let mut wallpaper_manager = WallpaperManager::new(&gpu.device);
+        wallpaper_manager.set_target_size(&gpu.device, &gpu.queue, gpu.config.width, gpu.config.height)?;
+
+        // Load wallpaper if path provided
+        if let Some(path) = wallpaper_path {
+            log::info!("Attempting to load wallpaper from: {}", path);
+            match wallpaper_manager.load(&gpu.device, &gpu.queue, path) {
+                Ok(_) => log::info!("✓ Wallpaper loaded successfully: {}", path),
+                Err(e) => log::error!("✗ WALLPAPER LOADING FAILED: {} - Error: {}", path, e),
+            }
+        } else {
+            log::info!("No wallpaper path configured");
+        }
+
+        // Create opacity uniforms
+        let has_wallpaper = wallpaper_manager.has_wallpaper();
+        log::info!("Initializing opacity uniforms: wallpaper_opacity={}, background_opacity={}, has_wallpaper={}",
+                   wallpaper_opacity, background_opacity, has_wallpaper);
+        let mut opacity_uniforms = OpacityUniforms::new(
+            &gpu.device,
+            wallpaper_opacity,
+            background_opacity,
+            has_wallpaper,
+        );
+        opacity_uniforms.set_wallpaper_uv(&gpu.queue, wallpaper_manager.uv());
+
+        // Create render pipeline with all bind group layouts
+        let render_pipeline = create_render_pipeline(
+            &gpu.device,
+            &texture_manager.bind_group_layout,
+            wallpaper_manager.bind_group_layout(),
+            opacity_uniforms.bind_group_layout(),
+            gpu.config.format,
+        );
+
+        // Create vertex buffer
+        let vertex_buffer = create_vertex_buffer(&gpu.device);
+
+        // Create cursor state and pipeline
+        let cursor_state = CursorState::new(&gpu.device, cursor_config);
+        let cursor_pipeline = create_cursor_pipeline(
+            &gpu.device,
+            &cursor_state.bind_group_layout,
+            &texture_manager.bind_group_layout,
+            gpu.config.format,
+        );
+
+        // Create selection renderer
+        let selection_renderer = SelectionRenderer::new(&gpu.device, gpu.config.format);
+        let search_renderer = SelectionRenderer::new(&gpu.device, gpu.config.format);
+        let search_focus_renderer = SelectionRenderer::new(&gpu.device, gpu.config.format);
+
+        // Create border renderer
+        let border_renderer = BorderRenderer::new(&gpu.device, gpu.config.format);
+        let frame_renderer = FrameRenderer::new(&gpu.device, gpu.config.format);
+        let scrollbar_renderer = ScrollbarRenderer::new(&gpu.device, gpu.config.format);
+        let bell_renderer = ScrollbarRenderer::new(&gpu.device, gpu.config.format);
+
+        Ok(Self {
+            device: gpu.device,
+            queue: gpu.queue,
+            surface: gpu.surface,
+            config: gpu.config,
+            present_modes: gpu.present_modes,
+            low_latency: false,
+            adapter_info: gpu.adapter_info,
+            font_manager,
+            texture_manager,
+            glyph_atlas,
+            spare_atlases: Vec::new(),
+            display_scale_factors: Vec::new(),
+            glyph_renderer,
+            text_rasterizer,
+            zoomed_fonts: HashMap::new(),
+            render_pipeline,
+            vertex_buffer,
+            scroll_offset: 0.0,
+            scroll_multiplier: DEFAULT_SCROLL_MULTIPLIER,
+            scroll_momentum: ScrollMomentum::new(false, DEFAULT_SCROLL_DECELERATION),
+            cursor_state,
+            cursor_pipeline,
+            color_palette,
+            selection_renderer,
+            search_renderer,
+            search_focus_renderer,
+            border_renderer,
+            scrollbar_renderer,
+            bell_renderer,
+            bell_flashes: HashMap::new(),
+            frame_renderer,
+            copy_flash: None,
+            search_markers: Vec::new(),
+            wallpaper_manager,
+            wallpaper_path: wallpaper_path.map(str::to_string),
+            wallpaper_opacity,
+            wallpaper_override: None,
+            opacity_uniforms,
+            content_hidden: false,
+            window_focused: true,
+            text_blink: BlinkClock::new(cursor_config.blink_interval_ms, true),
+            blinking_text: false,
+            blink_drawn: true,
+            inactive_pane_dim: 0.0,
+            pane_headers: false,
+            padding: Padding::default(),
+            default_badge: None,
+            ssh_badge: true,
+            command_durations: true,
+            autosuggestions: true,
+            autosuggestion: None,
+            status_line: None,
+            selection_viewport: None,
+            selection_ranges: Vec::new(),
+            selection_style: SelectionStyle::default(),
+            line_filter: None,
+            dropped_frames: 0,
+            frame_stats: FrameStats::new(),
+            stats_hud: false,
+            post_process: None,
+            post_process_animate: false,
+            _window: window, // Must be last to ensure correct drop order
+        })
+    }
+
+    /// Scroll viewport by fractional delta (direct, smooth scrolling)
+    /// Positive delta = scroll up (into history), Negative delta = scroll down (toward present)
+    pub fn scroll(&mut self, delta: f32) {
+        // Directly apply the delta for smooth scrolling
+        self.scroll_offset = (self.scroll_offset + delta).max(0.0);
+        // Bounds checking happens in render() where we clamp to history_size
+    }
+
+    /// Scroll by mouse wheel notches (positive = up), scaled by the configured multiplier
+    pub fn scroll_notches(&mut self, notches: f32) {
+        self.scroll_momentum.stop();
+        self.scroll(notches * self.scroll_multiplier);
+    }
+
+    /// Scroll by trackpad pixels (positive = up) using the real cell height
+    /// `gesture_ended` starts momentum coasting when it's enabled
+    pub fn scroll_pixels(&mut self, pixels: f32, gesture_ended: bool) {
+        let (_, cell_height, _) = self.font_manager.cell_metrics();
+        let lines = pixels / cell_height.max(saternal_core::constants::MIN_CELL_DIMENSION);
+        let now = Instant::now();
+        self.scroll_momentum.track(lines, now);
+        self.scroll(lines);
+        if gesture_ended {
+            self.scroll_momentum.release(now);
+        }
+    }
+
+    /// Advance momentum scrolling; returns true while it is still coasting
+    pub fn tick_scroll_momentum(&mut self) -> bool {
+        if !self.scroll_momentum.is_coasting() {
+            return false;
+        }
+        let lines = self.scroll_momentum.step(Instant::now());
+        self.scroll(lines);
+        // Coasting into the live view ends the gesture
+        if self.scroll_offset <= 0.0 {
+            self.scroll_momentum.stop();
+        }
+        self.scroll_momentum.is_coasting()
+    }
+
+    /// Set lines per wheel notch and trackpad momentum (deceleration is a per-second decay rate)
+    pub fn set_scrolling(&mut self, multiplier: f32, momentum: bool, deceleration: f32) {
+        self.scroll_multiplier = multiplier.max(0.0);
+        self.scroll_momentum = ScrollMomentum::new(momentum, deceleration);
+    }
+
+    /// Reset scroll to bottom (live view)
+    pub fn reset_scroll(&mut self) {
+        self.scroll_offset = 0.0;
+        self.scroll_momentum.stop();
+        log::debug!("Reset scroll to bottom");
+    }
+
+    /// Render a frame with terminal content
+    pub fn render<T>(&mut self, term: Option<Arc<Mutex<Term<T>>>>) -> Result<()> {
+        let frame_start = Instant::now();
+
+        // Update cursor blink state
+        let blink_changed = self.cursor_state.update_blink();
+
+        // Generate GPU instances for terminal text
+        if let Some(term_arc) = &term {
+            log::debug!("Attempting to lock terminal for rendering");
+            if let Some(term_lock) = term_arc.try_lock_for(TERM_LOCK_TIMEOUT) {
+                log::debug!("Terminal locked, generating text instances");
+                
+                // Clamp scroll offset to available history
+                let history_size = term_lock.grid().history_size();
+                self.scroll_offset = self.scroll_offset.min(history_size as f32);
+                
+                self.generate_text_instances(&term_lock)?;
+                self.blinking_text = has_blinking_text(&term_lock, self.scroll_offset.round() as usize);
+                self.blink_drawn = self.text_blink.is_visible();
+                
+                // Update cursor position
+                self.update_cursor_position(&term_lock);
+            } else {
+                log::warn!("Could not lock terminal for rendering");
+            }
+        } else {
+            log::warn!("No terminal provided to render");
+        }
+
+        // Upload cursor uniforms if blink changed
+        if blink_changed {
+            self.cursor_state.upload_uniforms(&self.queue);
+        }
+        self.glyph_renderer.update_blink(&self.queue, &self.text_blink);
+        self.update_copy_flash();
+
+        self.execute_render_pass()?;
+        self.record_frame(frame_start);
+        Ok(())
+    }
+
+    /// Render a frame with pane tree (shows all panes in their viewports)
+    /// Uses parallel rendering for improved performance with multiple panes
+    pub fn render_with_panes(&mut self, pane_tree: &PaneNode) -> Result<()> {
+        let frame_start = Instant::now();
+
+        // Calculate pane viewports; terminals render below the header strip when headers are on
+        let header_height = self.pane_header_height();
+        let pane_areas = calculate_pane_viewports(pane_tree, self.config.width, self.config.height);
+        let viewports: Vec<PaneViewport> = pane_areas.iter().map(|vp| vp.without_header(header_height)).collect();
+        self.selection_viewport = viewports.iter().find(|vp| vp.focused).cloned();
+        self.prepare_zoomed_fonts(&viewports);
+        
+        // Create a black buffer for the entire window
+        let total_pixels = (self.config.width * self.config.height) as usize;
+        let mut combined_buffer = vec![0u8; total_pixels * 4];
+
+        // Collect pane data for parallel rendering (clone Arc<Mutex> to own it)
+        let pane_data: Vec<_> = viewports.iter()
+            .enumerate()
+            .filter_map(|(index, viewport)| {
+                pane_tree.find_pane(viewport.pane_id).map(|pane| {
+                    let term_arc = pane.terminal.term();  // Clone Arc for ownership
+                    // Privacy lock: the process, cwd and remote host are content too
+                    let process = (!self.content_hidden && (header_height > 0 || self.ssh_badge))
+                        .then(|| pane.terminal.foreground_process())
+                        .flatten();
+                    let header = (header_height > 0).then(|| {
+                        saternal_core::process::pane_header_label(index + 1, process.as_ref())
+                    });
+                    let remote_host = process
+                        .and_then(|process| process.remote_host)
+                        .filter(|_| self.ssh_badge);
+                    let badge = pane.terminal.badge()
+                        .or(remote_host)
+                        .or_else(|| self.default_badge.clone())
+                        .filter(|badge| !badge.is_empty());
+                    let password_prompt = pane.terminal.at_password_prompt();
+                    let predictions = pane.terminal.predictions();
+                    let duration_marks = if self.command_durations {
+                        pane.terminal.duration_marks()
+                    } else {
+                        Vec::new()
+                    };
+                    let suggestion = self.autosuggestion.as_ref()
+                        .filter(|suggestion| suggestion.pane_id == pane.id)
+                        .map(|suggestion| (suggestion.cell, suggestion.text.clone()));
+                    (term_arc, viewport, header, badge, *pane.terminal.base_palette(), password_prompt, predictions, duration_marks, suggestion)
+                })
+            })
+            .collect();
+
+        // Extract immutable references for parallel access
+        let text_rasterizer = &self.text_rasterizer;
+        let font_manager = &self.font_manager;
+        let zoomed_fonts = &self.zoomed_fonts;
+        let surface_format = self.config.format;
+        let color_palette = &self.color_palette;
+        let scroll_offset = self.scroll_offset;
+        let content_hidden = self.content_hidden;
+        // A single pane is always the focused one - nothing to set apart
+        let inactive_dim = if viewports.len() > 1 { self.inactive_pane_dim } else { 0.0 };
+        let cursor_config = self.cursor_state.config;
+        let blink_visible = self.text_blink.is_visible();
+        let blinking_text = AtomicBool::new(false);
+        let selection_ranges = &self.selection_ranges[..];
+        let selection_style = self.selection_style;
+        let line_filter = self.line_filter.as_ref();
+
+        // PARALLEL: Render all panes simultaneously on multiple CPU cores
+        // Returns (viewport, buffer) pairs for successful renders
+        let rendered_panes: Vec<RenderedPane> = pane_data.par_iter()
+            .filter_map(|(term_arc, viewport, header, badge, pane_palette, password_prompt, predictions, duration_marks, suggestion)| {
+                let mut header_buffer = header.as_ref().map(|label| {
+                    text_rasterizer.render_header(
+                        label,
+                        font_manager,
+                        viewport.width,
+                        header_height,
+                        surface_format,
+                        color_palette,
+                    )
+                });
+                if !viewport.focused && inactive_dim > 0.0 {
+                    if let Some(header_buffer) = &mut header_buffer {
+                        dim_pixels(header_buffer, inactive_dim);
+                    }
+                }
+
+                // Headers keep the window's font; a zoomed pane's content uses its own
+                let (font_manager, text_rasterizer) = match zoomed_fonts.get(&viewport.font_scale.to_bits()) {
+                    Some((font_manager, text_rasterizer)) => (font_manager, text_rasterizer),
+                    None => (font_manager, text_rasterizer),
+                };
+
+                // Privacy lock: never touch the grid, just paint the background
+                if content_hidden {
+                    let blank = text_rasterizer.background_buffer(
+                        viewport.width,
+                        viewport.height,
+                        surface_format,
+                        pane_palette,
+                    );
+                    return Some((*viewport, blank, header_buffer));
+                }
+
+                // Try to lock terminal (non-blocking)
+                let term_lock = term_arc.try_lock_for(TERM_LOCK_TIMEOUT)?;
+                // The pane's theme plus colors its program set (OSC 4/10/11)
+                let pane_palette = &pane_palette.with_dynamic_colors(term_lock.colors());
+                
+                log::debug!("Rendering pane {} to viewport ({}, {}) {}x{}", 
+                    viewport.pane_id, viewport.x, viewport.y, viewport.width, viewport.height);
+                
+                // Clamp scroll offset to available history (or filtered lines) for focused pane
+                let filter = line_filter.filter(|_| viewport.focused);
+                let pane_scroll_offset = if viewport.focused {
+                    let history_size = match filter {
+                        Some(filter) => filter.max_offset(term_lock.screen_lines()),
+                        None => term_lock.grid().history_size(),
+                    };
+                    scroll_offset.min(history_size as f32).round() as usize
+                } else {
+                    0 // Non-focused panes show live view
+                };
+                // The prompt and what's typed at it only sit where they are in the live view
+                let live_view = pane_scroll_offset == 0 && filter.is_none();
+                
+                // Render this pane's terminal to a viewport-sized buffer (CPU-bound work)
+                let mut pane_buffer = text_rasterizer.render_to_buffer(
+                    &term_lock,
+                    font_manager,
+                    viewport.width,
+                    viewport.height,
+                    pane_scroll_offset,
+                    filter,
+                    surface_format,
+                    pane_palette,
+                    blink_visible,
+                    if viewport.focused { selection_ranges } else { &[] },
+                    selection_style,
+                ).ok()?;
+                if has_blinking_text(&term_lock, pane_scroll_offset) {
+                    blinking_text.store(true, Ordering::Relaxed);
+                }
+                let mut target = PaneTarget {
+                    buffer: &mut pane_buffer,
+                    width: viewport.width,
+                    height: viewport.height,
+                    surface_format,
+                };
+
+                // Run times of finished commands, right-aligned on their prompt lines
+                let screen_lines = term_lock.screen_lines();
+                for mark in duration_marks {
+                    let Some(line) = mark.locate(term_lock.grid()) else {
+                        continue;
+                    };
+                    let row = match filter {
+                        Some(filter) => filter.row_of(line.0, screen_lines, pane_scroll_offset),
+                        None => usize::try_from(line.0 + pane_scroll_offset as i32).ok(),
+                    };
+                    if let Some(row) = row.filter(|&row| row < screen_lines) {
+                        text_rasterizer.draw_annotation(
+                            &mut target,
+                            row,
+                            term_lock.columns(),
+                            mark.label(),
+                            font_manager,
+                            pane_palette,
+                        );
+                    }
+                }
+
+                if let Some(badge) = badge {
+                    text_rasterizer.draw_badge(&mut target, badge, font_manager, pane_palette);
+                }
+
+                // Only the focused pane gets the GPU cursor; the rest show where input would go
+                let show_cursor = term_lock.mode().contains(TermMode::SHOW_CURSOR) || cursor_config.force_show;
+                if !viewport.focused && cursor_config.hollow_when_unfocused && show_cursor {
+                    text_rasterizer.draw_cursor_outline(
+                        &mut target,
+                        term_lock.grid().cursor.point,
+                        cursor_color(&cursor_config, term_lock.colors()),
+                    );
+                }
+
+                // Padlock after the cursor while a password is typed (nothing echoes there)
+                if *password_prompt && live_view {
+                    let cursor = term_lock.grid().cursor.point;
+                    let column = (cursor.column + 1).min(term_lock.last_column());
+                    let [r, g, b, _] = pane_palette.foreground;
+                    text_rasterizer.draw_lock_icon(
+                        &mut target,
+                        Point::new(cursor.line, column),
+                        [r, g, b, LOCK_ICON_ALPHA],
+                    );
+                }
+
+                // Autosuggestion: the rest of a history command, after what's typed
+                if let Some((cell, text)) = suggestion.as_ref().filter(|_| live_view) {
+                    let room = term_lock.columns().saturating_sub(cell.column.0);
+                    let text: String = text.chars().take(room).collect();
+                    text_rasterizer.draw_faded_text(&mut target, *cell, &text, font_manager, pane_palette);
+                }
+
+                // Local echo: what was typed, ahead of the (slow) echo
+                if live_view {
+                    for &(cell, c) in predictions {
+                        text_rasterizer.draw_prediction(&mut target, cell, c, font_manager, pane_palette);
+                    }
+                }
+
+                if !viewport.focused && inactive_dim > 0.0 {
+                    dim_pixels(&mut pane_buffer, inactive_dim);
+                }
+                
+                Some((*viewport, pane_buffer, header_buffer))
+            })
+            .collect();
+        self.blinking_text = blinking_text.into_inner();
+        self.blink_drawn = blink_visible;
+
+        let locked_panes: Vec<usize> = pane_tree.all_panes().into_iter()
+            .filter(|(_, pane)| pane.focus_locked)
+            .map(|(id, _)| id)
+            .collect();
+
+        // SEQUENTIAL: Copy buffers to combined buffer and update cursor
+        for (viewport, pane_buffer, header_buffer) in rendered_panes {
+            if let Some(header_buffer) = header_buffer {
+                self.copy_buffer_to_region(
+                    &header_buffer,
+                    &mut combined_buffer,
+                    viewport.x,
+                    viewport.y - header_height,
+                    viewport.width,
+                    header_height,
+                    self.config.width,
+                );
+            }
+
+            // Copy pane buffer to combined buffer at viewport position
+            self.copy_buffer_to_region(
+                &pane_buffer,
+                &mut combined_buffer,
+                viewport.x,
+                viewport.y,
+                viewport.width,
+                viewport.height,
+                self.config.width,
+            );
+
+            // Lock badge on the pane's top-right corner, above the header when there is one
+            if locked_panes.contains(&viewport.pane_id) {
+                let mut target = PaneTarget {
+                    buffer: &mut combined_buffer,
+                    width: self.config.width,
+                    height: self.config.height,
+                    surface_format: self.config.format,
+                };
+                self.text_rasterizer.draw_lock_badge(
+                    &mut target,
+                    (viewport.x + viewport.width) as i32 - LOCK_BADGE_INSET,
+                    (viewport.y - header_height) as i32 + LOCK_BADGE_INSET,
+                    LOCK_BADGE_COLOR,
+                );
+            }
+        }
+        
+        // Status strip over the bottom row; the privacy lock hides it with the rest
+        if let Some(line) = self.status_line.as_ref().filter(|_| !self.content_hidden) {
+            let (_, cell_height, _) = self.font_manager.cell_metrics();
+            let height = (cell_height as u32).min(self.config.height);
+            let strip = self.text_rasterizer.render_header(
+                line,
+                &self.font_manager,
+                self.config.width,
+                height,
+                self.config.format,
+                &self.color_palette,
+            );
+            self.copy_buffer_to_region(
+                &strip,
+                &mut combined_buffer,
+                0,
+                self.config.height - height,
+                self.config.width,
+                height,
+                self.config.width,
+            );
+        }
+
+        // Stats HUD box over the top-right corner
+        if self.stats_hud && !self.content_hidden {
+            let hud = UiBox::new(self.frame_stats.lines(Instant::now()));
+            let (cell_width, cell_height, _) = self.font_manager.cell_metrics();
+            // Header strip text starts at the left padding and stops short of the right
+            let margin = self.padding.left.max(self.padding.right);
+            if let Some((x, y, width, height)) = hud.rect((cell_width, cell_height), margin, (self.config.width, self.config.height)) {
+                let rendered = self.text_rasterizer.render_box(
+                    &hud.lines,
+                    &self.font_manager,
+                    width,
+                    height,
+                    self.config.format,
+                    &self.color_palette,
+                );
+                self.copy_buffer_to_region(&rendered, &mut combined_buffer, x, y, width, height, self.config.width);
+            }
+        }
+
+        // Update cursor for focused pane (requires re-locking)
+        if self.content_hidden {
+            self.cursor_state.update_position_ndc(0.0, 0.0, 0.0, 0.0, true);
+            self.cursor_state.upload_uniforms(&self.queue);
+        } else if let Some(focused_vp) = viewports.iter().find(|vp| vp.focused) {
+            if let Some(pane) = pane_tree.find_pane(focused_vp.pane_id) {
+                if let Some(term_lock) = pane.terminal.term().try_lock_for(TERM_LOCK_TIMEOUT) {
+                    let palette = pane.terminal.base_palette().with_dynamic_colors(term_lock.colors());
+                    self.update_cursor_position_with_viewport(&term_lock, focused_vp, &palette);
+                }
+            }
+        }
+
+        // Update scrollbar and search markers for focused pane
+        self.update_scrollbar(pane_tree, &viewports);
+        self.update_bell_flashes(&viewports);
+        self.update_copy_flash();
+
+        // Upload combined buffer to GPU texture
+        log::debug!("Uploading {}x{} combined texture to GPU", self.config.width, self.config.height);
+        self.queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &self.texture_manager.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            &combined_buffer,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(self.config.width * 4),
+                rows_per_image: Some(self.config.height),
+            },
+            wgpu::Extent3d {
+                width: self.config.width,
+                height: self.config.height,
+                depth_or_array_layers: 1,
+            },
+        );
+
+        // Update cursor blink
+        let blink_changed = self.cursor_state.update_blink();
+        if blink_changed {
+            self.cursor_state.upload_uniforms(&self.queue);
+        }
+        
+        // Execute render pass with borders
+        self.execute_render_pass_with_borders(&viewports)?;
+        self.record_frame(frame_start);
+        Ok(())
+    }
+
+    /// Copy a buffer to a specific region of the combined buffer
+    fn copy_buffer_to_region(
+        &self,
+        src: &[u8],
+        dst: &mut [u8],
+        dst_x: u32,
+        dst_y: u32,
+        src_width: u32,
+        src_height: u32,
+        dst_width: u32,
+    ) {
+        let bytes_per_pixel = 4;
+        for y in 0..src_height {
+            let src_row_start = (y * src_width * bytes_per_pixel) as usize;
+            let src_row_end = src_row_start + (src_width * bytes_per_pixel) as usize;
+            
+            let dst_row_start = ((dst_y + y) * dst_width * bytes_per_pixel + dst_x * bytes_per_pixel) as usize;
+            let dst_row_end = dst_row_start + (src_width * bytes_per_pixel) as usize;
+            
+            if src_row_end <= src.len() && dst_row_end <= dst.len() {
+                dst[dst_row_start..dst_row_end].copy_from_slice(&src[src_row_start..src_row_end]);
+            }
+        }
+    }
+
+    /// Update cursor position based on terminal state
+    fn update_cursor_position<T>(&mut self, term: &Term<T>) {
+        let cursor_pos = term.grid().cursor.point;
+        
+        // Cursor visibility is managed by the terminal's DECTCEM mode (CSI ? 25 h/l)
+        // SHOW_CURSOR flag present = visible, absent = hidden
+        // Also hide cursor when scrolled in history
+        let hide_cursor = !term.mode().contains(TermMode::SHOW_CURSOR) 
+                          || self.scroll_offset > 0.01;
+        
+        log::debug!("Cursor: pos=({}, {}), SHOW_CURSOR={}, hide={}", 
+                   cursor_pos.column.0, cursor_pos.line.0, 
+                   term.mode().contains(TermMode::SHOW_CURSOR), hide_cursor);
+        
+        // Use effective font size to account for DPI scaling across monitors
+        let (cell_width, cell_height, _) = self.font_manager.cell_metrics();
+
+        self.cursor_state.update_position(
+            cursor_pos,
+            cell_width,
+            cell_height,
+            self.config.width,
+            self.config.height,
+            self.scroll_offset.round() as usize,  // Convert to usize for cursor position
+            hide_cursor,
+            self.padding,
+        );
+        
+        // Upload uniforms to GPU
+        self.cursor_state.upload_uniforms(&self.queue);
+    }
+
+    /// Update cursor position with viewport offset
+    fn update_cursor_position_with_viewport<T>(&mut self, term: &Term<T>, viewport: &PaneViewport, palette: &ColorPalette) {
+        let cursor_pos = term.grid().cursor.point;
+        
+        let hide_cursor = !term.mode().contains(TermMode::SHOW_CURSOR) 
+                          || self.scroll_offset > 0.01
+                          || self.line_filter.is_some();
+        
+        let (cell_width, cell_height, _) = self.pane_cell_metrics(viewport.font_scale);
+
+        // Calculate cursor position relative to viewport
+        let cursor_pixel_x = viewport.x as f32 + cursor_pos.column.0 as f32 * cell_width + self.padding.left;
+        let cursor_pixel_y = viewport.y as f32 + cursor_pos.line.0 as f32 * cell_height + self.padding.top;
+        
+        // Convert to NDC
+        let ndc_x = (cursor_pixel_x / self.config.width as f32) * 2.0 - 1.0;
+        let mut ndc_y = -((cursor_pixel_y / self.config.height as f32) * 2.0 - 1.0);
+        
+        // A window in the background shows a hollow block whatever the style
+        let hollow = !self.window_focused && self.cursor_state.config.hollow_when_unfocused;
+        let outline = self.text_rasterizer.cursor_outline_width();
+        self.cursor_state.set_hollow(hollow.then(|| {
+            [outline / self.config.width as f32 * 2.0, outline / self.config.height as f32 * 2.0]
+        }));
+        let style = if hollow { CursorStyle::Block } else { self.cursor_state.config.style };
+
+        // Calculate size based on cursor style
+        let (width, height) = match style {
+            CursorStyle::Block => (cell_width, cell_height),
+            CursorStyle::Beam => (2.0, cell_height),
+            CursorStyle::Underline => (cell_width, 2.0),
+        };
+
+        let ndc_width = (width / self.config.width as f32) * 2.0;
+        let ndc_height = -((height / self.config.height as f32) * 2.0);
+        
+        // Adjust Y for underline style
+        if matches!(style, CursorStyle::Underline) {
+            ndc_y += (cell_height - 2.0) / self.config.height as f32 * 2.0;
+        }
+
+        let text_colors = self.cursor_state.config.reverse_text.then(|| {
+            let cell = &term.grid()[cursor_pos];
+            let (fg, bg) = cell_colors(cell, palette);
+            let to_rgba = |(r, g, b): (u8, u8, u8)| [r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, 1.0];
+            // The shader reads the text texture, which an sRGB format decodes to linear
+            let decode = |color: [f32; 4]| {
+                if self.config.format.is_srgb() { srgb_to_linear(color) } else { color }
+            };
+            let [r, g, b, _] = decode(self.cursor_state.config.text_color.unwrap_or(palette.background));
+            CursorTextColors {
+                text: [r, g, b, 1.0],
+                cell_fg: decode(to_rgba(fg)),
+                cell_bg: decode(bg.map_or(palette.background, to_rgba)),
+            }
+        });
+        self.cursor_state.set_text_colors(text_colors);
+        
+        log::debug!("Cursor at viewport offset: pixel=({:.1}, {:.1}), ndc=({:.3}, {:.3})", 
+                   cursor_pixel_x, cursor_pixel_y, ndc_x, ndc_y);
+        
+        let color = term.colors()[NamedColor::Cursor]
+            .map(|rgb| [rgb.r as f32 / 255.0, rgb.g as f32 / 255.0, rgb.b as f32 / 255.0]);
+        self.cursor_state.set_color_override(color);
+
+        // Use pre-calculated NDC coordinates
+        self.cursor_state.update_position_ndc(ndc_x, ndc_y, ndc_width, ndc_height, hide_cursor);
+        self.cursor_state.upload_uniforms(&self.queue);
+    }
+
+    /// Next surface texture to draw into, or None to skip this frame
+    ///
+    /// A surface that went stale (Outdated or Lost, e.g. after a GPU reset or
+    /// external displays changing across sleep) or ran out of memory is
+    /// reconfigured, with its size-dependent textures rebuilt, and tried once more.
+    fn acquire_frame(&mut self) -> Option<wgpu::SurfaceTexture> {
+        let error = match self.surface.get_current_texture() {
+            Ok(frame) => {
+                self.dropped_frames = 0;
+                return Some(frame);
+            }
+            Err(error) => error,
+        };
+        if error == wgpu::SurfaceError::Timeout {
+            log::warn!("Skipping frame: {}", error);
+        } else {
+            log::warn!("Surface error ({}), reconfiguring", error);
+            self.reconfigure_surface();
+            match self.surface.get_current_texture() {
+                Ok(frame) => {
+                    self.dropped_frames = 0;
+                    return Some(frame);
+                }
+                Err(error) => log::error!("Skipping frame after reconfiguring the surface: {}", error),
+            }
+        }
+        self.dropped_frames += 1;
+        None
+    }
+
+    /// The last frame was skipped and a few more attempts are due (see `acquire_frame`)
+    /// After that, the next redraw for any other reason tries again
+    pub fn should_retry_frame(&self) -> bool {
+        (1..=MAX_FRAME_RETRIES).contains(&self.dropped_frames)
+    }
+
+    /// Execute the GPU render pass to draw the frame
+    fn execute_render_pass(&mut self) -> Result<()> {
+        log::trace!("Getting surface texture for rendering...");
+        let Some(frame) = self.acquire_frame() else {
+            return Ok(());
+        };
+        log::trace!("Got surface texture, creating view...");
+        let view = frame
+            .texture
+            .create_view(&wgpu::TextureViewDescriptor::default());
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Render Encoder"),
+            });
+        self.upload_search_highlights();
+
+        // With a post-process shader, draw into its frame texture and run it over that
+        let mut post_process = self.post_process.take();
+        if let Some(post) = &mut post_process {
+            post.prepare(&self.device, &self.queue, self.config.format, self.config.width, self.config.height);
+        }
+
+        {
+            let target = post_process.as_ref().map_or(&view, |post| post.frame_view());
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Render Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: target,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color {
+                            r: 0.0,
+                            g: 0.0,
+                            b: 0.0,
+                            a: 0.0,  // Transparent clear for window transparency
+                        }),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+
+            // Draw background/wallpaper first
+            log::trace!("Drawing background/wallpaper");
+            render_pass.set_pipeline(&self.render_pipeline);
+            render_pass.set_bind_group(0, &self.texture_manager.bind_group, &[]);
+            render_pass.set_bind_group(1, self.wallpaper_manager.bind_group(), &[]);
+            render_pass.set_bind_group(2, self.opacity_uniforms.bind_group(), &[]);
+            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+            render_pass.draw(0..6, 0..1);
+            
+            // Draw GPU-rasterized text using instanced rendering
+            log::trace!("Drawing text glyphs with GPU instancing");
+            self.glyph_renderer.render(&mut render_pass, &self.glyph_atlas);
+            
+            // Draw search match highlights, the current match on top
+            if !self.content_hidden {
+                for highlights in [&self.search_renderer, &self.search_focus_renderer] {
+                    if highlights.has_selection() {
+                        render_pass.set_pipeline(highlights.pipeline());
+                        render_pass.set_bind_group(0, highlights.bind_group(), &[]);
+                        render_pass.draw(0..6, 0..highlights.instance_count());
+                    }
+                }
+            }
+
+            // Draw selection highlights
+            if self.draws_selection_overlay() {
+                log::trace!("Drawing selection highlights");
+                self.selection_renderer.set_color(self.selection_color());
+                self.selection_renderer.upload_uniforms(&self.queue);
+                render_pass.set_pipeline(self.selection_renderer.pipeline());
+                render_pass.set_bind_group(0, self.selection_renderer.bind_group(), &[]);
+                let instance_count = self.selection_renderer.instance_count();
+                render_pass.draw(0..6, 0..instance_count);
+            }
+            
+            // Draw cursor overlay
+            if self.cursor_state.is_visible() {
+                log::trace!("Drawing cursor overlay");
+                render_pass.set_pipeline(&self.cursor_pipeline);
+                render_pass.set_bind_group(0, &self.cursor_state.bind_group, &[]);
+                render_pass.set_bind_group(1, &self.texture_manager.bind_group, &[]);
+                render_pass.draw(0..6, 0..1);
+            }
+        }
+
+        if let Some(post) = &post_process {
+            post.render(&mut encoder, &view);
+        }
+        self.post_process = post_process;
+        
+        log::trace!("Submitting command buffer and presenting frame...");
+        self.queue.submit(std::iter::once(encoder.finish()));
+        self.track_gpu_frame();
+        frame.present();
+
+        Ok(())
+    }
+
+    /// Execute the GPU render pass with pane borders
+    fn execute_render_pass_with_borders(&mut self, viewports: &[PaneViewport]) -> Result<()> {
+        log::trace!("Getting surface texture for rendering...");
+        let Some(frame) = self.acquire_frame() else {
+            return Ok(());
+        };
+        let view = frame
+            .texture
+            .create_view(&wgpu::TextureViewDescriptor::default());
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Render Encoder"),
+            });
+        self.encode_post_processed_frame(&mut encoder, &view, viewports);
+
+        self.queue.submit(std::iter::once(encoder.finish()));
+        self.track_gpu_frame();
+        frame.present();
+
+        Ok(())
+    }
+
+    /// Record a frame into `view`, through the post-process shader when one is loaded
+    fn encode_post_processed_frame(&mut self, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView, viewports: &[PaneViewport]) {
+        let Some(mut post) = self.post_process.take() else {
+            return self.encode_frame(encoder, view, viewports);
+        };
+        post.prepare(&self.device, &self.queue, self.config.format, self.config.width, self.config.height);
+        self.encode_frame(encoder, post.frame_view(), viewports);
+        post.render(encoder, view);
+        self.post_process = Some(post);
+    }
+
+    /// Record the draw calls for a frame into `view` (the surface, or an offscreen texture)
+    fn encode_frame(&mut self, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView, viewports: &[PaneViewport]) {
+        // Update border renderer with current viewports
+        if viewports.len() > 1 {
+            self.border_renderer.update(viewports, self.config.width, self.config.height);
+            self.border_renderer.upload_uniforms(&self.queue);
+        }
+        self.upload_search_highlights();
+        if self.frame_renderer.is_visible() {
+            self.frame_renderer.update(&self.queue, self.config.width, self.config.height);
+        }
+        if self.draws_selection_overlay() {
+            self.selection_renderer.set_color(self.selection_color());
+            self.selection_renderer.upload_uniforms(&self.queue);
+        }
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Render Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color {
+                        r: 0.0,
+                        g: 0.0,
+                        b: 0.0,
+                        a: 0.0,  // Transparent clear for window transparency
+                    }),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        // Draw terminal content
+        render_pass.set_pipeline(&self.render_pipeline);
+        render_pass.set_bind_group(0, &self.texture_manager.bind_group, &[]);
+        render_pass.set_bind_group(1, self.wallpaper_manager.bind_group(), &[]);
+        render_pass.set_bind_group(2, self.opacity_uniforms.bind_group(), &[]);
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        render_pass.draw(0..6, 0..1);
+
+        // Draw search match highlights, the current match on top
+        if !self.content_hidden {
+            for highlights in [&self.search_renderer, &self.search_focus_renderer] {
+                if highlights.has_selection() {
+                    render_pass.set_pipeline(highlights.pipeline());
+                    render_pass.set_bind_group(0, highlights.bind_group(), &[]);
+                    render_pass.draw(0..6, 0..highlights.instance_count());
+                }
+            }
+        }
+
+        // Draw selection highlights
+        if self.draws_selection_overlay() {
+            render_pass.set_pipeline(self.selection_renderer.pipeline());
+            render_pass.set_bind_group(0, self.selection_renderer.bind_group(), &[]);
+            let instance_count = self.selection_renderer.instance_count();
+            render_pass.draw(0..6, 0..instance_count);
+        }
+
+        // Draw cursor overlay
+        if self.cursor_state.is_visible() {
+            render_pass.set_pipeline(&self.cursor_pipeline);
+            render_pass.set_bind_group(0, &self.cursor_state.bind_group, &[]);
+            render_pass.set_bind_group(1, &self.texture_manager.bind_group, &[]);
+            render_pass.draw(0..6, 0..1);
+        }
+
+        // Draw pane borders if we have multiple panes
+        if viewports.len() > 1 {
+            log::trace!("Drawing {} pane borders with GPU shader", viewports.len());
+            self.render_pane_borders(&mut render_pass, viewports);
+        }
+
+        // Draw scrollbar with search markers
+        if self.scrollbar_renderer.is_visible() {
+            self.scrollbar_renderer.render(&mut render_pass);
+        }
+
+        // Draw visual bell flashes on top of everything
+        if self.bell_renderer.is_visible() {
+            self.bell_renderer.render(&mut render_pass);
+        }
+
+        // Round off the window's corners (clipping all of the above) and outline it
+        if self.frame_renderer.is_visible() {
+            self.frame_renderer.render(&mut render_pass);
+        }
+    }
+
+    /// Save the window (or just the focused pane) as a PNG at `path`
+    ///
+    /// Draws the current frame again into an offscreen texture and reads it back.
+    pub fn screenshot(&mut self, pane_tree: &PaneNode, path: &Path, pane_only: bool) -> Result<()> {
+        // Bring the CPU-side buffers and uniforms up to date, as for a normal frame
+        self.render_with_panes(pane_tree)?;
+
+        let (width, height) = (self.config.width, self.config.height);
+        let header_height = self.pane_header_height();
+        let pane_areas = calculate_pane_viewports(pane_tree, width, height);
+        let viewports: Vec<PaneViewport> = pane_areas.iter().map(|vp| vp.without_header(header_height)).collect();
+
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Screenshot Texture"),
+            size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: self.config.format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let bytes_per_row = screenshot::padded_bytes_per_row(width);
+        let readback = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Screenshot Readback"),
+            size: (bytes_per_row * height) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Screenshot Encoder"),
+        });
+        self.encode_post_processed_frame(&mut encoder, &view, &viewports);
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &readback,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(bytes_per_row),
+                    rows_per_image: Some(height),
+                },
+            },
+            wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+        );
+        self.queue.submit(std::iter::once(encoder.finish()));
+
+        let slice = readback.slice(..);
+        let (tx, rx) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = tx.send(result);
+        });
+        self.device.poll(wgpu::Maintain::Wait);
+        rx.recv()??;
+
+        let bgra = matches!(
+            self.config.format,
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb
+        );
+        let mut image = screenshot::to_rgba_image(&slice.get_mapped_range(), width, height, bytes_per_row, bgra);
+        readback.unmap();
+
+        if pane_only {
+            let area = pane_areas
+                .iter()
+                .find(|vp| vp.focused)
+                .ok_or_else(|| anyhow::anyhow!("no focused pane"))?;
+            image = image::imageops::crop_imm(&image, area.x, area.y, area.width, area.height).to_image();
+        }
+        image.save(path)?;
+        log::info!("Saved {}x{} screenshot to {}", image.width(), image.height(), path.display());
+        Ok(())
+    }
+
+    /// Lay out the scrollbar for the focused pane
+    fn update_scrollbar(&mut self, pane_tree: &PaneNode, viewports: &[PaneViewport]) {
+        let layout = viewports.iter()
+            .find(|vp| vp.focused)
+            .filter(|_| !self.content_hidden)
+            .and_then(|vp| {
+                let pane = pane_tree.find_pane(vp.pane_id)?;
+                let term = pane.terminal.term();
+                let term_lock = term.try_lock_for(TERM_LOCK_TIMEOUT)?;
+                let screen_lines = term_lock.screen_lines();
+                // A filtered view scrolls through its own lines, which the markers don't point into
+                let (history_size, markers) = match &self.line_filter {
+                    Some(filter) => (filter.max_offset(screen_lines), &[][..]),
+                    None => (term_lock.grid().history_size(), &self.search_markers[..]),
+                };
+                let state = ScrollbarState {
+                    history_size,
+                    screen_lines,
+                    scroll_offset: (self.scroll_offset.round() as usize).min(history_size),
+                };
+                Some(scrollbar::scrollbar_rects(vp, state, markers))
+            })
+            .unwrap_or_default();
+
+        self.scrollbar_renderer.update(&layout, self.config.width, self.config.height);
+        self.scrollbar_renderer.upload_uniforms(&self.queue);
+    }
+
+    /// Lay out fading flashes for panes whose bell rang recently
+    fn update_bell_flashes(&mut self, viewports: &[PaneViewport]) {
+        let now = Instant::now();
+        let rects: Vec<_> = viewports.iter()
+            .filter_map(|vp| {
+                let rang_at = self.bell_flashes.get(&vp.pane_id)?;
+                bell_flash_rect(vp, now.duration_since(*rang_at))
+            })
+            .collect();
+        self.bell_flashes.retain(|_, rang_at| now.duration_since(*rang_at) < bell::BELL_FLASH_DURATION);
+
+        self.bell_renderer.update(&rects, self.config.width, self.config.height);
+        self.bell_renderer.upload_uniforms(&self.queue);
+    }
+
+    /// Render pane borders using GPU-accelerated shader
+    fn render_pane_borders<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>, viewports: &[PaneViewport]) {
+        if !self.border_renderer.has_borders() {
+            return;
+        }
+
+        log::trace!("Rendering {} pane borders with GPU shader", viewports.len());
+        render_pass.set_pipeline(self.border_renderer.pipeline());
+        render_pass.set_bind_group(0, self.border_renderer.bind_group(), &[]);
+        let instance_count = self.border_renderer.instance_count();
+        render_pass.draw(0..6, 0..instance_count);
+    }
+
+    /// Generate GPU instances for terminal content
+    fn generate_text_instances<T>(&mut self, term: &Term<T>) -> Result<()> {
+        self.glyph_renderer.generate_instances(
+            &self.queue,
+            term,
+            &mut self.glyph_atlas,
+            &self.font_manager,
+            &self.device,
+            self.scroll_offset.round() as usize,
+            &self.color_palette,
+            self.config.width,
+            self.config.height,
+            &self.selection_ranges,
+            self.selection_style,
+        )
+    }
+
+    /// Resize the renderer
+    pub fn resize(&mut self, width: u32, height: u32) {
+        if width > 0 && height > 0 {
+            info!("Resizing renderer to {}x{}", width, height);
+
+            // Update surface configuration
+            self.config.width = width;
+            self.config.height = height;
+            self.reconfigure_surface();
+
+            info!("Renderer resized successfully");
+        }
+    }
+
+    /// Configure the surface for the current size and rebuild what depends on it
+    fn reconfigure_surface(&mut self) {
+        let (width, height) = (self.config.width, self.config.height);
+        self.surface.configure(&self.device, &self.config);
+
+        // Resize texture manager
+        self.texture_manager.resize(&self.device, width, height, self.config.format);
+
+        // Update glyph renderer screen size
+        self.glyph_renderer.update_screen_size(&self.queue, width, height);
+
+        // Swap in the wallpaper variant for this resolution (cached per size)
+        if let Err(e) = self.wallpaper_manager.set_target_size(&self.device, &self.queue, width, height) {
+            log::error!("Failed to rescale wallpaper: {}", e);
+        }
+        self.opacity_uniforms.set_wallpaper_uv(&self.queue, self.wallpaper_manager.uv());
+    }
+
+    /// Get font manager
+    pub fn font_manager(&mut self) -> &mut FontManager {
+        &mut self.font_manager
+    }
+
+    /// Get current scroll offset
+    pub fn scroll_offset(&self) -> usize {
+        self.scroll_offset.round() as usize
+    }
+
+    /// Jump to an absolute scroll offset (lines into history)
+    pub fn set_scroll_offset(&mut self, offset: usize) {
+        self.scroll_offset = offset as f32;
+    }
+
+    /// Scroll by whole lines (positive = into history), clamped to the available history
+    pub fn scroll_lines(&mut self, lines: i32, history_size: usize) {
+        let offset = (self.scroll_offset().min(history_size) as i32 + lines).clamp(0, history_size as i32);
+        self.set_scroll_offset(offset as usize);
+    }
+
+    /// Bring a grid line (negative = history) into view, centering it if it's off screen
+    /// Used to follow the current search match
+    pub fn scroll_to_line(&mut self, line: i32, history_size: usize, screen_lines: usize) {
+        let state = ScrollbarState {
+            history_size,
+            screen_lines,
+            scroll_offset: self.scroll_offset().min(history_size),
+        };
+        if !state.is_line_visible(line) {
+            self.set_scroll_offset(state.offset_for_line(line));
+        }
+    }
+
+    /// Top visible line counted from the oldest history line (None at the live view)
+    /// Take this before a resize and pass it to `restore_scroll_anchor` afterwards
+    pub fn scroll_anchor(&self, history_size: usize, screen_lines: usize) -> Option<usize> {
+        ScrollbarState {
+            history_size,
+            screen_lines,
+            scroll_offset: self.scroll_offset(),
+        }
+        .top_line_anchor()
+    }
+
+    /// Keep the anchored line on top after the grid was resized
+    pub fn restore_scroll_anchor(&mut self, anchor: Option<usize>, history_size: usize) {
+        if let Some(anchor) = anchor {
+            self.set_scroll_offset(offset_for_anchor(anchor, history_size));
+        }
+    }
+
+    /// Show only these lines in the focused pane (None for the whole grid)
+    pub fn set_line_filter(&mut self, filter: Option<LineFilter>) {
+        self.line_filter = filter;
+    }
+
+    /// Set search match lines shown as scrollbar markers (negative = history)
+    pub fn set_search_markers(&mut self, markers: Vec<i32>) {
+        self.search_markers = markers;
+    }
+
+    /// Update selection rendering
+    pub fn update_selection(&mut self, ranges: &[SelectionRange], grid_cols: usize, grid_lines: usize) {
+        self.selection_ranges = ranges.to_vec();
+        let (cell_width, cell_height, viewport) = self.highlight_layout();
+        self.selection_renderer.update(
+            ranges,
+            cell_width,
+            cell_height,
+            &viewport,
+            &self.padding,
+            self.config.width,
+            self.config.height,
+            grid_cols,
+            grid_lines,
+        );
+    }
+
+    /// Update search match highlights in the focused pane (screen rows)
+    /// `current` is the match Enter jumps from, drawn in its own color
+    pub fn update_search_highlights(
+        &mut self,
+        current: Option<SelectionRange>,
+        others: &[SelectionRange],
+        grid_cols: usize,
+        grid_lines: usize,
+    ) {
+        let (cell_width, cell_height, viewport) = self.highlight_layout();
+        let current: Vec<SelectionRange> = current.into_iter().collect();
+        for (highlights, ranges) in [(&mut self.search_renderer, others), (&mut self.search_focus_renderer, &current[..])] {
+            highlights.update(
+                ranges,
+                cell_width,
+                cell_height,
+                &viewport,
+                &self.padding,
+                self.config.width,
+                self.config.height,
+                grid_cols,
+                grid_lines,
+            );
+        }
+    }
+
+    /// Cell size and pane that selection and search highlights are laid out in
+    fn highlight_layout(&self) -> (f32, f32, PaneViewport) {
+        let viewport = self.selection_viewport.clone().unwrap_or(PaneViewport {
+            pane_id: 0,
+            x: 0,
+            y: 0,
+            width: self.config.width,
+            height: self.config.height,
+            focused: true,
+            font_scale: 1.0,
+        });
+        let font_size = self.font_manager.font_size() * viewport.font_scale;
+        let (cell_width, cell_height, _) = self.font_manager.cell_metrics_at(font_size);
+        (cell_width, cell_height, viewport)
+    }
+
+    /// Upload search highlights in the palette's colors (before a render pass)
+    fn upload_search_highlights(&mut self) {
+        if self.content_hidden {
+            return;
+        }
+        let colors = [
+            self.highlight_color(self.color_palette.search_match_bg),
+            self.highlight_color(self.color_palette.search_focused_bg),
+        ];
+        for (highlights, color) in [&mut self.search_renderer, &mut self.search_focus_renderer].into_iter().zip(colors) {
+            if highlights.has_selection() {
+                highlights.set_color(color);
+                highlights.upload_uniforms(&self.queue);
+            }
+        }
+    }
+
+    /// Round the window's corners and outline its edge
+    pub fn set_window_frame(&mut self, frame: WindowFrame) {
+        self.frame_renderer.set_frame(frame);
+    }
+
+    /// Outline split panes with the `[panes.border]` settings, or not at all
+    pub fn set_pane_borders(&mut self, config: saternal_core::config::PaneBorderConfig) {
+        self.border_renderer.set_config(config);
+    }
+
+    /// How selected cells are drawn: tinted by the overlay, or with their colors swapped
+    pub fn set_selection_style(&mut self, style: SelectionStyle) {
+        self.selection_style = style;
+    }
+
+    /// Whether the tinted overlay is drawn over the selection this frame
+    /// (inverted cells are recolored by the text pass instead)
+    /// While a copy pulses, the overlay is drawn in the inverted style too
+    fn draws_selection_overlay(&self) -> bool {
+        (self.selection_style == SelectionStyle::Highlight || self.copy_flash.is_some())
+            && self.selection_renderer.has_selection()
+            && !self.content_hidden
+    }
+
+    /// Overlay color from the palette, kept strong enough to read over a wallpaper
+    /// and brightened while a copy pulses
+    fn selection_color(&self) -> [f32; 4] {
+        let color = self.highlight_color(self.color_palette.selection_bg);
+        self.copy_flash
+            .and_then(|copied_at| copy_flash_color(color, copied_at.elapsed()))
+            .unwrap_or(color)
+    }
+
+    /// Drop the copy pulse once it has faded, so this frame draws the plain selection
+    fn update_copy_flash(&mut self) {
+        self.copy_flash = self.copy_flash.filter(|copied_at| copied_at.elapsed() < copy_flash::COPY_FLASH_DURATION);
+    }
+
+    /// A highlight color with its alpha raised over a wallpaper, so busy images don't wash it out
+    fn highlight_color(&self, mut color: [f32; 4]) -> [f32; 4] {
+        if self.wallpaper_manager.has_wallpaper() {
+            color[3] = color[3].max(MIN_WALLPAPER_HIGHLIGHT_ALPHA);
+        }
+        color
+    }
+
+    /// Update font size and recalculate cell dimensions
+    pub fn set_font_size(&mut self, font_size: f32) -> Result<()> {
+        // Update font manager
+        self.font_manager.set_font_size(font_size);
+        self.refresh_glyph_atlases();
+        
+        let (cell_width, cell_height, baseline_offset) = self.update_cell_dimensions();
+        info!("Font size updated to {} (effective: {}): cell={}x{}, baseline={}", 
+              font_size, self.font_manager.effective_font_size(), cell_width, cell_height, baseline_offset);
+        
+        Ok(())
+    }
+
+    /// Update line-height and letter-spacing multipliers and recalculate cell dimensions
+    pub fn set_spacing(&mut self, line_height: f32, letter_spacing: f32) {
+        self.font_manager.set_spacing(line_height, letter_spacing);
+        let (cell_width, cell_height, _) = self.update_cell_dimensions();
+        info!("Spacing updated: line_height={}, letter_spacing={}, cell={}x{}",
+              line_height, letter_spacing, cell_width, cell_height);
+    }
+
+    /// Present each frame as soon as it's drawn instead of waiting for vsync
+    /// Uses Mailbox, or Immediate (which may tear) where the surface has no Mailbox
+    pub fn set_low_latency(&mut self, enabled: bool) {
+        self.low_latency = enabled;
+        let (present_mode, frame_latency) = if enabled {
+            let mode = [wgpu::PresentMode::Mailbox, wgpu::PresentMode::Immediate]
+                .into_iter()
+                .find(|mode| self.present_modes.contains(mode))
+                .unwrap_or(wgpu::PresentMode::Fifo);
+            (mode, 1)
+        } else {
+            (wgpu::PresentMode::Fifo, 2)
+        };
+        if self.config.present_mode == present_mode && self.config.desired_maximum_frame_latency == frame_latency {
+            return;
+        }
+        info!("Present mode: {:?}, frame latency {}", present_mode, frame_latency);
+        self.config.present_mode = present_mode;
+        self.config.desired_maximum_frame_latency = frame_latency;
+        self.reconfigure_surface();
+    }
+
+    /// Whether frames skip the vsync wait (see set_low_latency)
+    pub fn low_latency(&self) -> bool {
+        self.low_latency
+    }
+
+    /// Run the WGSL `source` (see `post_process`) over each finished frame, or
+    /// stop for None. A shader that doesn't compile is an error and the
+    /// previous one stays; an unchanged one isn't rebuilt
+    pub fn set_post_process(&mut self, source: Option<&str>, animate: bool) -> Result<()> {
+        self.post_process_animate = animate;
+        match source {
+            None => {
+                if self.post_process.take().is_some() {
+                    info!("Post-process shader removed");
+                }
+            }
+            Some(source) if self.post_process.as_ref().is_some_and(|post| post.source() == source) => {}
+            Some(source) => {
+                self.post_process = Some(PostProcess::new(&self.device, self.config.format, source)?);
+                info!("Post-process shader loaded");
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that a post-process shader compiles without installing it
+    pub fn check_post_process(&self, source: &str) -> Result<()> {
+        if self.post_process.as_ref().is_some_and(|post| post.source() == source) {
+            return Ok(());
+        }
+        PostProcess::new(&self.device, self.config.format, source).map(drop)
+    }
+
+    /// Whether a post-process shader wants a frame every display refresh
+    pub fn animates_post_process(&self) -> bool {
+        self.post_process_animate && self.post_process.is_some()
+    }
+
+    /// Adapter, backend, surface format, and present mode, one per line
+    pub fn gpu_info(&self) -> String {
+        gpu::describe(&self.adapter_info, self.config.format, self.config.present_mode)
+    }
+
+    /// Rasterize at a whole-pixel font size into whole-pixel cells and place glyphs
+    /// on pixel boundaries (sharper text at non-integer scale factors)
+    pub fn set_pixel_snap(&mut self, pixel_snap: bool) {
+        if self.font_manager.pixel_snap() == pixel_snap {
+            return;
+        }
+        self.font_manager.set_pixel_snap(pixel_snap);
+        self.glyph_renderer.set_pixel_snap(pixel_snap);
+        self.refresh_glyph_atlases();
+        let (cell_width, cell_height, _) = self.update_cell_dimensions();
+        info!("Pixel snapping {}: font size {}, cell={}x{}",
+              if pixel_snap { "on" } else { "off" }, self.font_manager.effective_font_size(), cell_width, cell_height);
+    }
+
+    /// Push the font manager's current cell metrics to the glyph renderer and rasterizer
+    fn update_cell_dimensions(&mut self) -> (f32, f32, f32) {
+        let (cell_width, cell_height, baseline_offset) = self.font_manager.cell_metrics();
+
+        // Update glyph renderer
+        self.glyph_renderer.update_dimensions(cell_width, cell_height, baseline_offset);
+
+        // Update text rasterizer (kept for backward compatibility)
+        self.text_rasterizer.update_dimensions(cell_width, cell_height, baseline_offset);
+        self.zoomed_fonts.clear();
+
+        (cell_width, cell_height, baseline_offset)
+    }
+
+    /// Cell (width, height, baseline offset) of a pane zoomed to `font_scale` times the font size
+    pub fn pane_cell_metrics(&self, font_scale: f32) -> (f32, f32, f32) {
+        self.font_manager.cell_metrics_at(self.font_manager.zoomed_font_size(font_scale))
+    }
+
+    /// Keep a font and rasterizer for each zoom level on screen and drop the rest
+    fn prepare_zoomed_fonts(&mut self, viewports: &[PaneViewport]) {
+        let scales: Vec<f32> = viewports.iter().map(|vp| vp.font_scale).filter(|&scale| scale != 1.0).collect();
+        self.zoomed_fonts.retain(|bits, _| scales.iter().any(|scale| scale.to_bits() == *bits));
+        for scale in scales {
+            self.zoomed_fonts.entry(scale.to_bits()).or_insert_with(|| {
+                let font_manager = self.font_manager.zoomed(scale);
+                let (cell_width, cell_height, baseline_offset) = font_manager.cell_metrics();
+                let text_rasterizer = self.text_rasterizer.with_dimensions(cell_width, cell_height, baseline_offset);
+                (font_manager, text_rasterizer)
+            });
+        }
+    }
+
+    /// Switch to a different font family, keeping the current size and scale
+    /// Fails (leaving the current font in place) if the family isn't installed
+    pub fn set_font_family(&mut self, font_family: &str) -> Result<()> {
+        self.font_manager = self.load_font_family(font_family)?;
+        // Every atlas holds the old font's glyphs
+        self.spare_atlases.clear();
+        let font_size = self.font_manager.effective_font_size();
+        self.glyph_atlas = self.glyph_atlas.sibling(&self.device, &self.queue, &self.font_manager, font_size)?;
+        self.set_font_size(self.font_manager.font_size())
+    }
+
+    /// Check that a font family is installed without switching to it
+    pub fn check_font_family(&self, font_family: &str) -> Result<()> {
+        self.load_font_family(font_family).map(drop)
+    }
+
+    /// Font manager for `font_family` at the current size, scale, and spacing
+    fn load_font_family(&self, font_family: &str) -> Result<FontManager> {
+        let mut font_manager = FontManager::new_with_scale(
+            font_family,
+            self.font_manager.font_size(),
+            self.font_manager.scale_factor(),
+        )?;
+        let (line_height, letter_spacing) = self.font_manager.spacing();
+        font_manager.set_spacing(line_height, letter_spacing);
+        font_manager.set_pixel_snap(self.font_manager.pixel_snap());
+        if font_manager.fallback_warning().is_some() {
+            anyhow::bail!("font \"{}\" is not installed", font_family);
+        }
+        Ok(font_manager)
+    }
+
+    /// Record the connected displays' scale factors and rasterize an atlas for
+    /// each, so moving the window between them swaps atlases instead of
+    /// re-rasterizing (empty with the DPI override, which applies everywhere)
+    pub fn set_display_scale_factors(&mut self, scale_factors: Vec<f64>) {
+        self.display_scale_factors = scale_factors;
+        self.refresh_glyph_atlases();
+    }
+
+    /// Make the active atlas match the effective font size and keep one for each
+    /// other display, reusing atlases that already have the right size
+    fn refresh_glyph_atlases(&mut self) {
+        let mut unused = std::mem::take(&mut self.spare_atlases);
+        let font_size = self.font_manager.effective_font_size();
+        if self.glyph_atlas.font_size() != font_size {
+            match self.reuse_or_create_atlas(&mut unused, font_size) {
+                Ok(atlas) => unused.push(std::mem::replace(&mut self.glyph_atlas, atlas)),
+                Err(e) => log::error!("Failed to create a glyph atlas at {}px: {}", font_size, e),
+            }
+        }
+
+        for &scale_factor in &self.display_scale_factors {
+            let font_size = self.font_manager.effective_font_size_at(scale_factor);
+            if self.glyph_atlas.font_size() == font_size
+                || self.spare_atlases.iter().any(|atlas| atlas.font_size() == font_size)
+            {
+                continue;
+            }
+            match self.reuse_or_create_atlas(&mut unused, font_size) {
+                Ok(atlas) => self.spare_atlases.push(atlas),
+                Err(e) => log::error!("Failed to create a glyph atlas at {}px: {}", font_size, e),
+            }
+        }
+    }
+
+    /// Take the atlas for `font_size` out of `unused`, or rasterize a new one
+    fn reuse_or_create_atlas(&self, unused: &mut Vec<GlyphAtlas>, font_size: f32) -> Result<GlyphAtlas> {
+        match unused.iter().position(|atlas| atlas.font_size() == font_size) {
+            Some(index) => Ok(unused.swap_remove(index)),
+            None => self.glyph_atlas.sibling(&self.device, &self.queue, &self.font_manager, font_size),
+        }
+    }
+
+    /// Replace the color palette used for pane headers; panes draw with their own (see Terminal::palette)
+    pub fn set_palette(&mut self, palette: ColorPalette) {
+        self.color_palette = palette;
+    }
+
+    /// Handle DPI scale factor change (monitor change, etc.)
+    pub fn handle_scale_factor_changed(&mut self, scale_factor: f64) -> Result<()> {
+        info!("Scale factor changed to: {:.2}x", scale_factor);
+        
+        // Update font manager with new scale, and swap in the display's atlas
+        self.font_manager.update_scale_factor(scale_factor);
+        self.refresh_glyph_atlases();
+        
+        // Recalculate cell dimensions with new effective font size
+        let (cell_width, cell_height, _) = self.update_cell_dimensions();
+        info!("DPI updated: effective font size={}, cell={}x{}",
+              self.font_manager.effective_font_size(), cell_width, cell_height);
+
+        Ok(())
+    }
+
+    /// Track whether the window has keyboard focus (unfocused windows get a hollow cursor)
+    pub fn set_window_focused(&mut self, focused: bool) {
+        self.window_focused = focused;
+    }
+
+    /// Turn all blinking (cursor and SGR 5 text) on or off; off shows blinking text steadily
+    pub fn set_blinking(&mut self, enabled: bool) {
+        self.cursor_state.set_blinking(enabled);
+        self.text_blink = BlinkClock::new(self.cursor_state.config.blink_interval_ms, enabled);
+    }
+
+    /// Keep the cursor solid while typing: restart its blink and idle timeout
+    /// Returns true if it was hidden mid-blink and needs a redraw to show
+    pub fn restart_cursor_blink(&mut self) -> bool {
+        self.cursor_state.restart_blink()
+    }
+
+    /// When the cursor or blinking text on screen needs the next frame: now if
+    /// text changed phase since the last one, None when there is nothing to animate
+    pub fn next_blink(&self) -> Option<Instant> {
+        let text = if !self.blinking_text || !self.text_blink.is_enabled() {
+            None
+        } else if self.text_blink.is_visible() != self.blink_drawn {
+            Some(Instant::now())
+        } else {
+            Some(self.text_blink.next_toggle())
+        };
+        match (text, self.cursor_state.next_blink()) {
+            (Some(text), Some(cursor)) => Some(text.min(cursor)),
+            (text, cursor) => text.or(cursor),
+        }
+    }
+
+    /// Hide or show pane content (privacy lock)
+    /// While hidden, only backgrounds, wallpaper, and borders are drawn
+    pub fn set_content_hidden(&mut self, hidden: bool) {
+        if self.content_hidden != hidden {
+            info!("Pane content {}", if hidden { "hidden" } else { "revealed" });
+            self.content_hidden = hidden;
+        }
+    }
+
+    /// Check if pane content is currently hidden
+    pub fn content_hidden(&self) -> bool {
+        self.content_hidden
+    }
+
+    /// Switch glyph antialiasing (grayscale or RGB subpixel)
+    pub fn set_antialias(&mut self, antialias: Antialias) {
+        info!("Glyph antialiasing: {:?}", antialias);
+        self.text_rasterizer.set_antialias(antialias);
+        self.zoomed_fonts.clear();
+    }
+
+    /// Set how much darker unfocused panes are drawn (0.0 = off, up to 0.8)
+    pub fn set_inactive_pane_dim(&mut self, amount: f32) {
+        self.inactive_pane_dim = amount.clamp(0.0, dim::MAX_INACTIVE_DIM);
+    }
+
+    /// Show or hide the title strip (index, process, cwd) above each pane
+    /// Pane trees need set_header_rows() to match so the grids leave room for it
+    pub fn set_pane_headers(&mut self, enabled: bool) {
+        self.pane_headers = enabled;
+    }
+
+    /// Space between each pane's edges and its grid
+    /// Terminal sizes must be calculated with padding() so the grids fit
+    pub fn set_padding(&mut self, padding: Padding) {
+        self.padding = padding.sanitized();
+        self.glyph_renderer.set_padding(self.padding);
+        self.text_rasterizer.set_padding(self.padding);
+        self.zoomed_fonts.clear();
+    }
+
+    pub fn padding(&self) -> Padding {
+        self.padding
+    }
+
+    /// Rebuild the pipelines drawn each frame from the shaders on disk (shader hot reload)
+    /// A shader that doesn't compile is logged and its old pipeline kept
+    #[cfg(feature = "shader-hot-reload")]
+    pub fn reload_shaders(&mut self) {
+        let device = &self.device;
+        let format = self.config.format;
+        let create_text = || create_render_pipeline(
+            device,
+            &self.texture_manager.bind_group_layout,
+            self.wallpaper_manager.bind_group_layout(),
+            self.opacity_uniforms.bind_group_layout(),
+            format,
+        );
+        if let Some(pipeline) = shaders::rebuild(device, "text", create_text) {
+            self.render_pipeline = pipeline;
+        }
+        let create_cursor = || create_cursor_pipeline(
+            device,
+            &self.cursor_state.bind_group_layout,
+            &self.texture_manager.bind_group_layout,
+            format,
+        );
+        if let Some(pipeline) = shaders::rebuild(device, "cursor", create_cursor) {
+            self.cursor_pipeline = pipeline;
+        }
+        self.glyph_renderer.reload_pipeline(device, &self.glyph_atlas, format);
+        self.border_renderer.reload_pipeline(device, format);
+        self.scrollbar_renderer.reload_pipeline(device, format);
+        self.bell_renderer.reload_pipeline(device, format);
+        self.frame_renderer.reload_pipeline(device, format);
+        self.selection_renderer.reload_pipeline(device, format);
+        self.search_renderer.reload_pipeline(device, format);
+        self.search_focus_renderer.reload_pipeline(device, format);
+    }
+
+    /// Show how long finished commands ran at the end of their prompt lines
+    pub fn set_command_durations(&mut self, enabled: bool) {
+        self.command_durations = enabled;
+    }
+
+    /// Suggest the rest of a history command as it's typed at a prompt
+    pub fn set_autosuggestions(&mut self, enabled: bool) {
+        self.autosuggestions = enabled;
+        if !enabled {
+            self.autosuggestion = None;
+        }
+    }
+
+    /// Whether autosuggestions are on
+    pub fn autosuggestions(&self) -> bool {
+        self.autosuggestions
+    }
+
+    /// Suggestion to draw after the focused pane's cursor (None: nothing to suggest)
+    pub fn set_autosuggestion(&mut self, suggestion: Option<Autosuggestion>) {
+        self.autosuggestion = suggestion.filter(|_| self.autosuggestions);
+    }
+
+    /// Text for the strip along the bottom of the window (None: no strip)
+    ///
+    /// The dropdown has no title bar, so find, the pickers, command hints and
+    /// notices are drawn here instead.
+    pub fn set_status_line(&mut self, line: Option<String>) {
+        self.status_line = line.filter(|line| !line.is_empty());
+    }
+
+    /// Badge drawn on panes that didn't set their own; `{hostname}` and `{user}` are filled in
+    pub fn set_default_badge(&mut self, badge: Option<&str>) {
+        let user = std::env::var("USER").unwrap_or_default();
+        self.default_badge = badge
+            .map(|format| saternal_core::badge::expand_badge(format, &saternal_core::badge::hostname(), &user))
+            .filter(|badge| !badge.trim().is_empty());
+    }
+
+    /// Badge panes running ssh with the remote host, unless they set their own
+    pub fn set_ssh_badge(&mut self, enabled: bool) {
+        self.ssh_badge = enabled;
+    }
+
+    /// Height of the pane header strip in pixels (0 when headers are off)
+    pub fn pane_header_height(&self) -> u32 {
+        if self.pane_headers {
+            let (_, cell_height, _) = self.font_manager.cell_metrics();
+            cell_height as u32
+        } else {
+            0
+        }
+    }
+
+    /// Start a visual bell flash over a pane
+    pub fn ring_bell(&mut self, pane_id: usize) {
+        self.bell_flashes.insert(pane_id, Instant::now());
+    }
+
+    /// Check if a bell flash is still fading (keep redrawing until it ends)
+    pub fn is_bell_flashing(&self) -> bool {
+        !self.bell_flashes.is_empty()
+    }
+
+    /// Pulse the selection overlay to show it was copied
+    pub fn flash_selection(&mut self) {
+        self.copy_flash = Some(Instant::now());
+    }
+
+    /// Check if a copy pulse is still fading (keep redrawing until it ends)
+    pub fn is_copy_flashing(&self) -> bool {
+        self.copy_flash.is_some()
+    }
+
+    /// Show or hide the frame statistics HUD, returning the new state
+    pub fn toggle_stats_hud(&mut self) -> bool {
+        self.stats_hud = !self.stats_hud;
+        self.stats_hud
+    }
+
+    /// Check if the frame statistics HUD is shown
+    pub fn stats_hud_visible(&self) -> bool {
+        self.stats_hud
+    }
+
+    /// Count bytes read from the PTYs towards the HUD throughput
+    pub fn record_pty_bytes(&mut self, bytes: usize) {
+        self.frame_stats.record_pty_bytes(Instant::now(), bytes);
+    }
+
+    /// Time the frame just submitted on the GPU while the HUD shows
+    fn track_gpu_frame(&mut self) {
+        if self.stats_hud {
+            // Run callbacks of frames that have finished, so their slots are filled when read
+            self.device.poll(wgpu::Maintain::Poll);
+            let done = self.frame_stats.submit_gpu_frame(Instant::now());
+            self.queue.on_submitted_work_done(move || *done.lock() = Some(Instant::now()));
+        }
+    }
+
+    /// Sample timing and glyph cache usage for a finished frame
+    fn record_frame(&mut self, frame_start: Instant) {
+        self.frame_stats.record_frame(frame_start, frame_start.elapsed());
+        self.frame_stats.glyph_instances = self.glyph_renderer.instance_count();
+        self.frame_stats.atlas_glyphs = self.glyph_atlas.glyph_count();
+        self.frame_stats.atlas_occupancy = self.glyph_atlas.occupancy();
+    }
+
+    /// Decode a wallpaper image ahead of time, so setting it afterwards can't fail
+    pub fn preload_wallpaper(&mut self, path: &str) -> Result<()> {
+        self.wallpaper_manager.preload(path)
+    }
+
+    /// Set or clear the global wallpaper
+    /// Tabs with their own wallpaper keep showing it
+    pub fn set_wallpaper(&mut self, path: Option<&str>) -> Result<()> {
+        if self.wallpaper_override.is_none() {
+            self.bind_wallpaper(path)?;
+        }
+        self.wallpaper_path = path.map(str::to_string);
+        Ok(())
+    }
+
+    /// Set the global wallpaper opacity
+    pub fn set_wallpaper_opacity(&mut self, opacity: f32) {
+        info!("Setting wallpaper opacity: {}", opacity);
+        self.wallpaper_opacity = opacity;
+        let tab_opacity = self.wallpaper_override.as_ref().and_then(|o| o.opacity);
+        self.update_wallpaper_opacity(tab_opacity.unwrap_or(opacity));
+    }
+
+    /// Show the active tab's own wallpaper, or the global one for `None`
+    /// Cheap when nothing changed, so it can be called every frame
+    pub fn set_wallpaper_override(&mut self, wallpaper: Option<&WallpaperOverride>) {
+        if self.wallpaper_override.as_ref() == wallpaper {
+            return;
+        }
+        self.wallpaper_override = wallpaper.cloned();
+
+        let (path, opacity) = match wallpaper {
+            Some(w) => (Some(w.path.clone()), w.opacity.unwrap_or(self.wallpaper_opacity)),
+            None => (self.wallpaper_path.clone(), self.wallpaper_opacity),
+        };
+        if self.wallpaper_manager.path() != path.as_deref() {
+            if let Err(e) = self.bind_wallpaper(path.as_deref()) {
+                log::error!("Failed to switch wallpaper: {}", e);
+            }
+        }
+        self.update_wallpaper_opacity(opacity);
+    }
+
+    /// Load (or clear) the wallpaper texture that is drawn
+    fn bind_wallpaper(&mut self, path: Option<&str>) -> Result<()> {
+        match path {
+            Some(p) => {
+                info!("Setting wallpaper: {}", p);
+                self.wallpaper_manager.load(&self.device, &self.queue, p)?;
+            }
+            None => {
+                info!("Clearing wallpaper");
+                self.wallpaper_manager.clear(&self.device);
+            }
+        }
+
+        // Update opacity uniforms with new wallpaper status
+        self.update_wallpaper_opacity(self.opacity_uniforms.wallpaper_opacity());
+        self.opacity_uniforms.set_wallpaper_uv(&self.queue, self.wallpaper_manager.uv());
+        Ok(())
+    }
+
+    /// Set how the wallpaper is fitted to the window
+    pub fn set_wallpaper_mode(&mut self, mode: WallpaperMode) -> Result<()> {
+        info!("Setting wallpaper mode: {:?}", mode);
+        self.wallpaper_manager.set_mode(&self.device, &self.queue, mode)?;
+        self.opacity_uniforms.set_wallpaper_uv(&self.queue, self.wallpaper_manager.uv());
+        Ok(())
+    }
+
+    fn update_wallpaper_opacity(&mut self, opacity: f32) {
+        self.opacity_uniforms.update(
+            &self.queue,
+            opacity,
+            self.opacity_uniforms.background_opacity(),
+            self.wallpaper_manager.has_wallpaper(),
+        );
+    }
+
+    /// Color space the window's surface is tagged with; on Display P3 the wallpaper
+    /// is converted from sRGB while palette colors are drawn as P3 values
+    pub fn set_color_space(&mut self, color_space: ColorSpace) {
+        info!("Setting color space: {:?}", color_space);
+        self.opacity_uniforms.set_color_space(&self.queue, color_space);
+    }
+
+    /// Set background opacity
+    pub fn set_background_opacity(&mut self, opacity: f32) {
+        info!("Setting background opacity: {}", opacity);
+        self.opacity_uniforms.update(
+            &self.queue,
+            self.opacity_uniforms.wallpaper_opacity(),
+            opacity,
+            self.wallpaper_manager.has_wallpaper(),
+        );
+    }
+
+    /// Set blur strength (0.0 = disabled, 2.0 = default, 10.0 = heavy)
+    /// Blurs the wallpaper on the GPU once per change
+    pub fn set_blur_strength(&mut self, strength: f32) {
+        info!("Setting blur strength: {}", strength);
+        if let Err(e) = self.wallpaper_manager.set_blur_strength(&self.device, &self.queue, strength) {
+            log::error!("Failed to apply blur: {}", e);
+        }
+    }
+}
+
+impl RendererBackend for Renderer {
+    fn render_panes(&mut self, pane_tree: &PaneNode) -> Result<()> {
+        self.render_with_panes(pane_tree)
+    }
+
+    fn size(&self) -> (u32, u32) {
+        (self.config.width, self.config.height)
+    }
+
+    fn resize(&mut self, width: u32, height: u32) {
+        Renderer::resize(self, width, height)
+    }
+
+    fn cell_metrics(&self) -> (f32, f32, f32) {
+        self.font_manager.cell_metrics()
+    }
+
+    fn padding(&self) -> Padding {
+        Renderer::padding(self)
+    }
+
+    fn set_font_size(&mut self, font_size: f32) -> Result<()> {
+        Renderer::set_font_size(self, font_size)
+    }
+
+    fn scale_factor(&self) -> f64 {
+        self.font_manager.scale_factor()
+    }
+
+    fn set_scale_factor(&mut self, scale_factor: f64) -> Result<()> {
+        self.handle_scale_factor_changed(scale_factor)
+    }
+
+    fn set_palette(&mut self, palette: ColorPalette) {
+        Renderer::set_palette(self, palette)
+    }
+}
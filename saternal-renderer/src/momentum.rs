@@ -0,0 +1,142 @@
+/// Trackpad scroll momentum
+///
+/// Tracks the velocity of trackpad (pixel) scrolling and, once the fingers
+/// lift, keeps scrolling with exponentially decaying speed. Off by default
+/// since macOS usually delivers its own momentum events.
+use std::time::Instant;
+
+/// Coasting stops below this speed (lines per second)
+const MIN_VELOCITY: f32 = 1.0;
+
+/// Gestures paused longer than this (seconds) carry no velocity
+const MAX_EVENT_GAP: f32 = 0.1;
+
+/// Weight of the previous velocity estimate when a new delta arrives
+const VELOCITY_SMOOTHING: f32 = 0.5;
+
+#[derive(Debug, Clone)]
+pub struct ScrollMomentum {
+    enabled: bool,
+    deceleration: f32,
+    velocity: f32,  // Lines per second, positive = into history
+    last_input: Option<Instant>,
+    coasting_since: Option<Instant>,  // Time of the last coasting step
+}
+
+impl ScrollMomentum {
+    pub fn new(enabled: bool, deceleration: f32) -> Self {
+        Self {
+            enabled,
+            deceleration: deceleration.max(0.1),
+            velocity: 0.0,
+            last_input: None,
+            coasting_since: None,
+        }
+    }
+
+    /// Record a trackpad delta (in lines) to estimate the gesture's speed
+    pub fn track(&mut self, lines: f32, now: Instant) {
+        self.coasting_since = None;
+        if let Some(last) = self.last_input {
+            let dt = now.duration_since(last).as_secs_f32();
+            if dt > 0.0 && dt < MAX_EVENT_GAP {
+                let velocity = lines / dt;
+                self.velocity = self.velocity * VELOCITY_SMOOTHING + velocity * (1.0 - VELOCITY_SMOOTHING);
+            } else {
+                self.velocity = 0.0;
+            }
+        }
+        self.last_input = Some(now);
+    }
+
+    /// The gesture ended - start coasting if momentum is on and it was fast enough
+    pub fn release(&mut self, now: Instant) {
+        self.last_input = None;
+        if self.enabled && self.velocity.abs() >= MIN_VELOCITY {
+            self.coasting_since = Some(now);
+        } else {
+            self.stop();
+        }
+    }
+
+    /// Cancel any coasting (e.g. on mouse wheel input or when the view hits the bottom)
+    pub fn stop(&mut self) {
+        self.velocity = 0.0;
+        self.last_input = None;
+        self.coasting_since = None;
+    }
+
+    pub fn is_coasting(&self) -> bool {
+        self.coasting_since.is_some()
+    }
+
+    /// Lines to scroll since the last step while coasting
+    pub fn step(&mut self, now: Instant) -> f32 {
+        let Some(last) = self.coasting_since else {
+            return 0.0;
+        };
+        let dt = now.duration_since(last).as_secs_f32();
+        let decay = (-self.deceleration * dt).exp();
+        // Distance covered by v(t) = v0 * e^(-kt) over dt
+        let distance = self.velocity * (1.0 - decay) / self.deceleration;
+        self.velocity *= decay;
+
+        if self.velocity.abs() < MIN_VELOCITY {
+            self.stop();
+        } else {
+            self.coasting_since = Some(now);
+        }
+        distance
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use saternal_core::constants::DEFAULT_SCROLL_DECELERATION;
+    use std::time::Duration;
+
+    fn flick(momentum: &mut ScrollMomentum, start: Instant) -> Instant {
+        // 2 lines every 10ms = 200 lines/s
+        let mut now = start;
+        for _ in 0..5 {
+            momentum.track(2.0, now);
+            now += Duration::from_millis(10);
+        }
+        momentum.release(now);
+        now
+    }
+
+    #[test]
+    fn test_coasting_decays_and_stops() {
+        let mut momentum = ScrollMomentum::new(true, DEFAULT_SCROLL_DECELERATION);
+        let mut now = flick(&mut momentum, Instant::now());
+        assert!(momentum.is_coasting());
+
+        let mut total = 0.0;
+        let mut last_step = f32::MAX;
+        while momentum.is_coasting() {
+            now += Duration::from_millis(16);
+            let step = momentum.step(now);
+            assert!(step > 0.0 && step <= last_step);
+            last_step = step;
+            total += step;
+        }
+        // Bounded by v0 / k
+        assert!(total > 10.0 && total < 200.0 / DEFAULT_SCROLL_DECELERATION);
+    }
+
+    #[test]
+    fn test_disabled_or_slow_gestures_do_not_coast() {
+        let mut momentum = ScrollMomentum::new(false, DEFAULT_SCROLL_DECELERATION);
+        flick(&mut momentum, Instant::now());
+        assert!(!momentum.is_coasting());
+
+        let mut momentum = ScrollMomentum::new(true, DEFAULT_SCROLL_DECELERATION);
+        let now = Instant::now();
+        momentum.track(1.0, now);
+        momentum.track(1.0, now + Duration::from_millis(500));
+        momentum.release(now + Duration::from_millis(500));
+        assert!(!momentum.is_coasting());
+    }
+}
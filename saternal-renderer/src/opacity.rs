@@ -1,3 +1,5 @@
+use saternal_core::config::ColorSpace;
+use saternal_core::wallpaper::WallpaperUv;
 use wgpu;
 use wgpu::util::DeviceExt;
 
@@ -7,6 +9,8 @@ use wgpu::util::DeviceExt;
 /// - Wallpaper opacity (how visible the wallpaper is)
 /// - Background opacity (overall window transparency)
 /// - Wallpaper presence flag (for shader branching)
+/// - Wallpaper UV transform (scaling mode)
+/// - Output color space (the sRGB wallpaper is converted for Display P3)
 pub struct OpacityUniforms {
     buffer: wgpu::Buffer,
     bind_group: wgpu::BindGroup,
@@ -16,6 +20,8 @@ pub struct OpacityUniforms {
     wallpaper_opacity: f32,
     background_opacity: f32,
     has_wallpaper: bool,
+    wallpaper_uv: WallpaperUv,
+    color_space: ColorSpace,
 }
 
 /// Uniform data structure matching shader layout
@@ -26,7 +32,34 @@ struct OpacityUniformsData {
     wallpaper_opacity: f32,
     background_opacity: f32,
     has_wallpaper: u32,
-    _padding: f32, // Ensure 16-byte alignment
+    display_p3: u32,
+    uv_scale: [f32; 2],
+    uv_offset: [f32; 2],
+    tile: u32,
+    clip: u32,
+    _padding2: [u32; 2],
+}
+
+impl OpacityUniformsData {
+    fn new(
+        wallpaper_opacity: f32,
+        background_opacity: f32,
+        has_wallpaper: bool,
+        uv: WallpaperUv,
+        color_space: ColorSpace,
+    ) -> Self {
+        Self {
+            wallpaper_opacity,
+            background_opacity,
+            has_wallpaper: if has_wallpaper { 1 } else { 0 },
+            display_p3: (color_space == ColorSpace::DisplayP3) as u32,
+            uv_scale: uv.scale,
+            uv_offset: uv.offset,
+            tile: uv.tile as u32,
+            clip: uv.clip as u32,
+            _padding2: [0; 2],
+        }
+    }
 }
 
 unsafe impl bytemuck::Pod for OpacityUniformsData {}
@@ -56,12 +89,13 @@ impl OpacityUniforms {
         });
 
         // Create uniform buffer with initial data
-        let data = OpacityUniformsData {
+        let data = OpacityUniformsData::new(
             wallpaper_opacity,
             background_opacity,
-            has_wallpaper: if has_wallpaper { 1 } else { 0 },
-            _padding: 0.0,
-        };
+            has_wallpaper,
+            WallpaperUv::default(),
+            ColorSpace::default(),
+        );
 
         let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Opacity Uniforms Buffer"),
@@ -86,6 +120,8 @@ impl OpacityUniforms {
             wallpaper_opacity,
             background_opacity,
             has_wallpaper,
+            wallpaper_uv: WallpaperUv::default(),
+            color_space: ColorSpace::default(),
         }
     }
 
@@ -108,14 +144,35 @@ impl OpacityUniforms {
         self.wallpaper_opacity = wallpaper_opacity;
         self.background_opacity = background_opacity;
         self.has_wallpaper = has_wallpaper;
+        self.upload(queue);
+    }
 
-        let data = OpacityUniformsData {
-            wallpaper_opacity,
-            background_opacity,
-            has_wallpaper: if has_wallpaper { 1 } else { 0 },
-            _padding: 0.0,
-        };
+    /// Update the wallpaper UV transform (only uploads to GPU if it changed)
+    pub fn set_wallpaper_uv(&mut self, queue: &wgpu::Queue, uv: WallpaperUv) {
+        if self.wallpaper_uv == uv {
+            return;
+        }
+        self.wallpaper_uv = uv;
+        self.upload(queue);
+    }
+
+    /// Set the color space the surface is tagged with (only uploads to GPU if it changed)
+    pub fn set_color_space(&mut self, queue: &wgpu::Queue, color_space: ColorSpace) {
+        if self.color_space == color_space {
+            return;
+        }
+        self.color_space = color_space;
+        self.upload(queue);
+    }
 
+    fn upload(&self, queue: &wgpu::Queue) {
+        let data = OpacityUniformsData::new(
+            self.wallpaper_opacity,
+            self.background_opacity,
+            self.has_wallpaper,
+            self.wallpaper_uv,
+            self.color_space,
+        );
         queue.write_buffer(&self.buffer, 0, bytemuck::cast_slice(&[data]));
     }
 
@@ -1,3 +1,4 @@
+use super::shaders::Shader;
 use wgpu;
 use wgpu::util::DeviceExt;
 
@@ -11,7 +12,7 @@ pub(crate) fn create_render_pipeline(
 ) -> wgpu::RenderPipeline {
     let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
         label: Some("Text Shader"),
-        source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/text.wgsl").into()),
+        source: Shader::Text.source(),
     });
 
     let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
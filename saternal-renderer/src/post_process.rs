@@ -0,0 +1,267 @@
+/// User post-process shader: a last pass over each finished frame (CRT curvature,
+/// scanlines, and the like)
+///
+/// The frame is drawn into an offscreen texture, and a window-covering triangle
+/// then runs the user's `effect` function over it into the surface. The user's
+/// WGSL only sees the bindings of the prelude (`shaders/post_process.wgsl`):
+/// the frame, a sampler, the resolution, and the time.
+use anyhow::{anyhow, bail, Result};
+use std::time::Instant;
+use wgpu;
+use wgpu::util::DeviceExt;
+
+/// Bindings, vertex shader, and entry points the user's shader is appended to
+const PRELUDE: &str = include_str!("shaders/post_process.wgsl");
+
+/// Uniform data (matches `PostProcessUniforms` in the prelude)
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct PostProcessUniforms {
+    resolution: [f32; 2],  // Frame size in pixels
+    time: f32,             // Seconds since the shader was loaded
+    _padding: f32,
+}
+
+unsafe impl bytemuck::Pod for PostProcessUniforms {}
+unsafe impl bytemuck::Zeroable for PostProcessUniforms {}
+
+/// Full WGSL module for a user's shader, or an error if it reaches past the interface
+fn shader_source(user: &str) -> Result<String> {
+    if user.contains("@group") || user.contains("@binding") {
+        bail!("post-process shaders can't declare bindings; use post, source, and source_sampler");
+    }
+    if !user.contains("fn effect") {
+        bail!("post-process shader has no `fn effect(uv: vec2<f32>) -> vec4<f32>`");
+    }
+    Ok(format!("{}\n{}", PRELUDE, user))
+}
+
+/// Offscreen frame the post-process pass reads, sized to the surface
+struct FrameTexture {
+    view: wgpu::TextureView,
+    bind_group: wgpu::BindGroup,
+    width: u32,
+    height: u32,
+}
+
+pub struct PostProcess {
+    source: String,  // The user's WGSL, to skip rebuilding an unchanged shader
+    started: Instant,
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    uniform_buffer: wgpu::Buffer,
+    frame: Option<FrameTexture>,  // Created on first use and when the size changes
+}
+
+impl PostProcess {
+    /// Compile the user's shader `source`
+    /// Errors (WGSL that doesn't parse or validate) are returned rather than
+    /// left to wgpu, which would panic
+    pub fn new(device: &wgpu::Device, surface_format: wgpu::TextureFormat, source: &str) -> Result<Self> {
+        let wgsl = shader_source(source)?;
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Post-process Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let pipeline = create_pipeline(device, &bind_group_layout, surface_format, wgsl);
+        if let Some(error) = futures::executor::block_on(device.pop_error_scope()) {
+            return Err(anyhow!("post-process shader doesn't compile: {}", error));
+        }
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Post-process Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        let uniforms = PostProcessUniforms { resolution: [1.0, 1.0], time: 0.0, _padding: 0.0 };
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Post-process Uniform Buffer"),
+            contents: bytemuck::cast_slice(&[uniforms]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        Ok(Self {
+            source: source.to_string(),
+            started: Instant::now(),
+            pipeline,
+            bind_group_layout,
+            sampler,
+            uniform_buffer,
+            frame: None,
+        })
+    }
+
+    /// The user's WGSL this was built from
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Size the offscreen frame to `width`×`height` and upload the time and resolution
+    pub fn prepare(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, format: wgpu::TextureFormat, width: u32, height: u32) {
+        if self.frame.as_ref().is_none_or(|frame| (frame.width, frame.height) != (width, height)) {
+            self.frame = Some(self.create_frame(device, format, width, height));
+        }
+        let uniforms = PostProcessUniforms {
+            resolution: [width as f32, height as f32],
+            time: self.started.elapsed().as_secs_f32(),
+            _padding: 0.0,
+        };
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
+    }
+
+    /// Texture to draw the frame into (call `prepare` first)
+    pub fn frame_view(&self) -> &wgpu::TextureView {
+        &self.frame.as_ref().expect("prepare() creates the frame texture").view
+    }
+
+    /// Run the shader over the frame into `target`
+    pub fn render(&self, encoder: &mut wgpu::CommandEncoder, target: &wgpu::TextureView) {
+        let Some(frame) = &self.frame else {
+            return;
+        };
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Post-process Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &frame.bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+
+    fn create_frame(&self, device: &wgpu::Device, format: wgpu::TextureFormat, width: u32, height: u32) -> FrameTexture {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Post-process Frame"),
+            size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Post-process Bind Group"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: self.uniform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+            ],
+        });
+        FrameTexture { view, bind_group, width, height }
+    }
+}
+
+fn create_pipeline(
+    device: &wgpu::Device,
+    bind_group_layout: &wgpu::BindGroupLayout,
+    surface_format: wgpu::TextureFormat,
+    wgsl: String,
+) -> wgpu::RenderPipeline {
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("Post-process Shader"),
+        source: wgpu::ShaderSource::Wgsl(wgsl.into()),
+    });
+
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("Post-process Pipeline Layout"),
+        bind_group_layouts: &[bind_group_layout],
+        push_constant_ranges: &[],
+    });
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Post-process Pipeline"),
+        layout: Some(&pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: "post_process_vs",
+            buffers: &[],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point: "post_process_fs",
+            targets: &[Some(wgpu::ColorTargetState {
+                format: surface_format,
+                blend: None,  // The effect's output replaces the frame
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+        }),
+        primitive: wgpu::PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState::default(),
+        multiview: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_shader_is_appended_to_the_prelude() {
+        let user = "fn effect(uv: vec2<f32>) -> vec4<f32> {\n    return textureSample(source, source_sampler, uv);\n}\n";
+        let wgsl = shader_source(user).unwrap();
+        assert!(wgsl.starts_with(PRELUDE));
+        assert!(wgsl.ends_with(user));
+
+        assert!(shader_source("@group(1) @binding(0) var<uniform> x: f32;\nfn effect(uv: vec2<f32>) -> vec4<f32> { return vec4<f32>(x); }").is_err());
+        assert!(shader_source("fn main() {}").is_err());
+    }
+}
@@ -0,0 +1,54 @@
+/// Turning a read-back frame into an image file
+use image::RgbaImage;
+use std::path::PathBuf;
+
+/// Row stride for copying a `width`-pixel texture into a buffer (wgpu pads rows to 256 bytes)
+pub(super) fn padded_bytes_per_row(width: u32) -> u32 {
+    let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    (width * 4).div_ceil(align) * align
+}
+
+/// Straight-alpha RGBA image from padded, premultiplied BGRA or RGBA rows
+pub(super) fn to_rgba_image(data: &[u8], width: u32, height: u32, bytes_per_row: u32, bgra: bool) -> RgbaImage {
+    let mut pixels = Vec::with_capacity((width * height * 4) as usize);
+    for row in data.chunks(bytes_per_row as usize).take(height as usize) {
+        for px in row[..(width * 4) as usize].chunks_exact(4) {
+            let (r, g, b, a) = if bgra { (px[2], px[1], px[0], px[3]) } else { (px[0], px[1], px[2], px[3]) };
+            let unpremultiply = |c: u8| match a {
+                0 => 0,
+                _ => ((c as u32 * 255 + a as u32 / 2) / a as u32).min(255) as u8,
+            };
+            pixels.extend_from_slice(&[unpremultiply(r), unpremultiply(g), unpremultiply(b), a]);
+        }
+    }
+    RgbaImage::from_raw(width, height, pixels).expect("buffer holds width * height pixels")
+}
+
+/// `~/Desktop/Saternal <date> at <time>.png`, like macOS screenshots
+pub fn default_screenshot_path() -> PathBuf {
+    let stamp = saternal_core::pane_log::local_timestamp(std::time::SystemTime::now());
+    let (date, time) = stamp.split_once(' ').unwrap_or((&stamp, ""));
+    let name = format!("Saternal {} at {}.png", date, time.replace(':', "."));
+    let desktop = std::env::var_os("HOME").map(|home| PathBuf::from(home).join("Desktop"));
+    desktop.filter(|dir| dir.is_dir()).unwrap_or_else(std::env::temp_dir).join(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_rgba_image_strips_padding_and_alpha() {
+        let stride = padded_bytes_per_row(2);
+        assert_eq!(stride, 256);
+        let mut data = vec![0u8; (stride * 2) as usize];
+        // Opaque blue and half-transparent premultiplied red, in BGRA
+        data[..8].copy_from_slice(&[255, 0, 0, 255, 0, 0, 64, 128]);
+        data[stride as usize..stride as usize + 4].copy_from_slice(&[0, 0, 0, 0]);
+
+        let image = to_rgba_image(&data, 2, 2, stride, true);
+        assert_eq!(image.get_pixel(0, 0).0, [0, 0, 255, 255]);
+        assert_eq!(image.get_pixel(1, 0).0, [128, 0, 0, 128]);
+        assert_eq!(image.get_pixel(0, 1).0, [0, 0, 0, 0]);
+    }
+}
@@ -0,0 +1,444 @@
+/// Scrollbar overlay with search match markers
+///
+/// The scrollbar is drawn along the right edge of the focused pane whenever
+/// the view is scrolled into history or a search has matches. Each match in
+/// scrollback gets a tick mark at its relative position, so the distribution
+/// of matches is visible at a glance; clicking a tick jumps to that match.
+use saternal_core::selection::PaneViewport;
+use super::shaders::Shader;
+use wgpu;
+use wgpu::util::DeviceExt;
+
+/// Width of the scrollbar strip in pixels
+pub const SCROLLBAR_WIDTH: f32 = 6.0;
+
+/// Height of a search marker tick in pixels
+const MARKER_HEIGHT: f32 = 2.0;
+
+/// Clicks within this many pixels of a marker select it
+const MARKER_HIT_SLOP: f32 = 4.0;
+
+/// Maximum rectangles (track + thumb + markers)
+const MAX_RECTS: usize = 64;
+
+const TRACK_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.06];
+const THUMB_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.28];
+const MARKER_COLOR: [f32; 4] = [0.95, 0.75, 0.2, 0.9];
+
+/// Scroll state of the pane the scrollbar belongs to
+#[derive(Debug, Clone, Copy)]
+pub struct ScrollbarState {
+    pub history_size: usize,
+    pub screen_lines: usize,
+    pub scroll_offset: usize,
+}
+
+impl ScrollbarState {
+    fn total_lines(&self) -> usize {
+        self.history_size + self.screen_lines
+    }
+
+    /// Map a grid line (negative = history) to a y pixel within the track
+    fn line_to_y(&self, line: i32, viewport: &PaneViewport) -> f32 {
+        let index = (line + self.history_size as i32).max(0) as f32;
+        viewport.y as f32 + index / self.total_lines().max(1) as f32 * viewport.height as f32
+    }
+
+    /// Scroll offset that centers the given line in the view
+    pub fn offset_for_line(&self, line: i32) -> usize {
+        let centered = self.screen_lines as i32 / 2 - line;
+        centered.clamp(0, self.history_size as i32) as usize
+    }
+
+    /// Whether a grid line (negative = history) is on screen
+    pub fn is_line_visible(&self, line: i32) -> bool {
+        let top = -(self.scroll_offset as i32);
+        line >= top && line < top + self.screen_lines as i32
+    }
+
+    /// Top visible line counted from the oldest history line
+    /// None at the live view, which should keep following new output
+    pub fn top_line_anchor(&self) -> Option<usize> {
+        let offset = self.scroll_offset.min(self.history_size);
+        (offset > 0).then(|| self.history_size - offset)
+    }
+}
+
+/// Scroll offset that puts an anchored line (see `top_line_anchor`) back on top
+pub fn offset_for_anchor(anchor: usize, history_size: usize) -> usize {
+    history_size.saturating_sub(anchor)
+}
+
+/// Rectangle in pixel coordinates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PixelRect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub color: [f32; 4],
+}
+
+/// Lay out the track, thumb, and marker rectangles for a pane
+pub fn scrollbar_rects(
+    viewport: &PaneViewport,
+    state: ScrollbarState,
+    markers: &[i32],
+) -> Vec<PixelRect> {
+    if state.history_size == 0 || (state.scroll_offset == 0 && markers.is_empty()) {
+        return Vec::new();
+    }
+
+    let x = viewport.x as f32 + viewport.width as f32 - SCROLLBAR_WIDTH;
+    let mut rects = Vec::with_capacity(MAX_RECTS);
+
+    // Track
+    rects.push(PixelRect {
+        x,
+        y: viewport.y as f32,
+        width: SCROLLBAR_WIDTH,
+        height: viewport.height as f32,
+        color: TRACK_COLOR,
+    });
+
+    // Thumb covers the visible lines
+    let top_line = -(state.scroll_offset as i32);
+    let thumb_top = state.line_to_y(top_line, viewport);
+    let thumb_bottom = state.line_to_y(top_line + state.screen_lines as i32, viewport);
+    rects.push(PixelRect {
+        x,
+        y: thumb_top,
+        width: SCROLLBAR_WIDTH,
+        height: (thumb_bottom - thumb_top).max(MARKER_HEIGHT * 2.0),
+        color: THUMB_COLOR,
+    });
+
+    // Markers - matches landing on the same pixel row share one tick
+    let mut rows: Vec<f32> = markers
+        .iter()
+        .map(|&line| state.line_to_y(line, viewport).floor())
+        .collect();
+    rows.sort_by(f32::total_cmp);
+    rows.dedup_by(|row, kept| *row - *kept < MARKER_HEIGHT);
+
+    // More rows than ticks: split the track into one band per tick, and mark
+    // every band holding a match so none of them drop off the bottom
+    let room = MAX_RECTS - rects.len();
+    if rows.len() > room {
+        let band = viewport.height as f32 / room as f32;
+        let top = viewport.y as f32;
+        for row in &mut rows {
+            let index = ((*row - top) / band).floor().clamp(0.0, (room - 1) as f32);
+            *row = (top + index * band).floor();
+        }
+        rows.dedup();
+    }
+
+    rects.extend(rows.into_iter().map(|row| PixelRect {
+        x,
+        y: row,
+        width: SCROLLBAR_WIDTH,
+        height: MARKER_HEIGHT,
+        color: MARKER_COLOR,
+    }));
+
+    rects
+}
+
+/// Find the marker line under a click at (x, y), if any
+pub fn marker_at(
+    viewport: &PaneViewport,
+    state: ScrollbarState,
+    markers: &[i32],
+    x: f32,
+    y: f32,
+) -> Option<i32> {
+    let strip_left = viewport.x as f32 + viewport.width as f32 - SCROLLBAR_WIDTH;
+    let strip_right = viewport.x as f32 + viewport.width as f32;
+    if x < strip_left || x > strip_right || state.history_size == 0 {
+        return None;
+    }
+
+    markers
+        .iter()
+        .map(|&line| (line, (state.line_to_y(line, viewport) - y).abs()))
+        .filter(|(_, distance)| *distance <= MARKER_HIT_SLOP)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(line, _)| line)
+}
+
+/// Single scrollbar rectangle in NDC coordinates
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+struct ScrollbarRect {
+    position: [f32; 2],  // NDC position
+    size: [f32; 2],      // NDC size
+    color: [f32; 4],     // RGBA
+}
+
+unsafe impl bytemuck::Pod for ScrollbarRect {}
+unsafe impl bytemuck::Zeroable for ScrollbarRect {}
+
+/// Scrollbar uniform data (matches shader layout)
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+struct ScrollbarUniforms {
+    rects: [ScrollbarRect; MAX_RECTS],  // 64 rects (2048 bytes)
+    count: u32,                          // 4 bytes
+    _padding1: [u32; 3],                 // 12 bytes padding to 16-byte boundary
+}
+
+unsafe impl bytemuck::Pod for ScrollbarUniforms {}
+unsafe impl bytemuck::Zeroable for ScrollbarUniforms {}
+
+/// Scrollbar overlay renderer
+pub struct ScrollbarRenderer {
+    uniform_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    pipeline: wgpu::RenderPipeline,
+    current_uniforms: ScrollbarUniforms,
+    dirty: bool,
+}
+
+impl ScrollbarRenderer {
+    pub fn new(device: &wgpu::Device, surface_format: wgpu::TextureFormat) -> Self {
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Scrollbar Bind Group Layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+
+        let initial_uniforms = ScrollbarUniforms {
+            rects: [ScrollbarRect { position: [0.0, 0.0], size: [0.0, 0.0], color: [0.0; 4] }; MAX_RECTS],
+            count: 0,
+            _padding1: [0; 3],
+        };
+
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Scrollbar Uniform Buffer"),
+            contents: bytemuck::cast_slice(&[initial_uniforms]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Scrollbar Bind Group"),
+            layout: &bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+        });
+
+        let pipeline = create_scrollbar_pipeline(device, &bind_group_layout, surface_format);
+
+        Self {
+            uniform_buffer,
+            bind_group,
+            pipeline,
+            current_uniforms: initial_uniforms,
+            dirty: false,
+        }
+    }
+
+    /// Update rectangles from pixel layout
+    pub fn update(&mut self, rects: &[PixelRect], window_width: u32, window_height: u32) {
+        let to_ndc_x = |px: f32| (px / window_width as f32) * 2.0 - 1.0;
+        let to_ndc_y = |py: f32| -((py / window_height as f32) * 2.0 - 1.0);
+
+        let count = rects.len().min(MAX_RECTS);
+        for (slot, rect) in self.current_uniforms.rects.iter_mut().zip(&rects[..count]) {
+            *slot = ScrollbarRect {
+                position: [to_ndc_x(rect.x), to_ndc_y(rect.y)],
+                size: [
+                    rect.width / window_width as f32 * 2.0,
+                    -(rect.height / window_height as f32 * 2.0),
+                ],
+                color: rect.color,
+            };
+        }
+
+        if self.current_uniforms.count != count as u32 || count > 0 {
+            self.current_uniforms.count = count as u32;
+            self.dirty = true;
+        }
+    }
+
+    /// Upload uniforms to GPU
+    pub fn upload_uniforms(&mut self, queue: &wgpu::Queue) {
+        if self.dirty {
+            queue.write_buffer(
+                &self.uniform_buffer,
+                0,
+                bytemuck::cast_slice(&[self.current_uniforms]),
+            );
+            self.dirty = false;
+        }
+    }
+
+    /// Check if the scrollbar should be drawn
+    pub fn is_visible(&self) -> bool {
+        self.current_uniforms.count > 0
+    }
+
+    /// Draw the scrollbar overlay
+    pub fn render<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+        render_pass.draw(0..6, 0..self.current_uniforms.count);
+    }
+
+    /// Rebuild the pipeline from the shader on disk, keeping the old one if it doesn't compile
+    #[cfg(feature = "shader-hot-reload")]
+    pub(crate) fn reload_pipeline(&mut self, device: &wgpu::Device, surface_format: wgpu::TextureFormat) {
+        // The layout the pipeline was created with, which the bind group matches
+        let bind_group_layout = self.pipeline.get_bind_group_layout(0);
+        let create = || create_scrollbar_pipeline(device, &bind_group_layout, surface_format);
+        if let Some(pipeline) = super::shaders::rebuild(device, "scrollbar", create) {
+            self.pipeline = pipeline;
+        }
+    }
+}
+
+/// Create scrollbar render pipeline
+fn create_scrollbar_pipeline(
+    device: &wgpu::Device,
+    bind_group_layout: &wgpu::BindGroupLayout,
+    surface_format: wgpu::TextureFormat,
+) -> wgpu::RenderPipeline {
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("Scrollbar Shader"),
+        source: Shader::Scrollbar.source(),
+    });
+
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("Scrollbar Pipeline Layout"),
+        bind_group_layouts: &[bind_group_layout],
+        push_constant_ranges: &[],
+    });
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Scrollbar Pipeline"),
+        layout: Some(&pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: "vs_main",
+            buffers: &[],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point: "fs_main",
+            targets: &[Some(wgpu::ColorTargetState {
+                format: surface_format,
+                blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: None,
+            polygon_mode: wgpu::PolygonMode::Fill,
+            unclipped_depth: false,
+            conservative: false,
+        },
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState {
+            count: 1,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        },
+        multiview: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn viewport() -> PaneViewport {
+        PaneViewport { pane_id: 0, x: 0, y: 0, width: 800, height: 1000, focused: true, font_scale: 1.0 }
+    }
+
+    fn state(scroll_offset: usize) -> ScrollbarState {
+        ScrollbarState { history_size: 900, screen_lines: 100, scroll_offset }
+    }
+
+    #[test]
+    fn test_hidden_at_bottom_without_markers() {
+        assert!(scrollbar_rects(&viewport(), state(0), &[]).is_empty());
+    }
+
+    #[test]
+    fn test_thumb_tracks_scroll_offset() {
+        let rects = scrollbar_rects(&viewport(), state(900), &[]);
+        let thumb = rects[1];
+        assert_eq!(thumb.y, 0.0);
+        assert_eq!(thumb.height, 100.0);
+
+        let rects = scrollbar_rects(&viewport(), state(0), &[-450]);
+        assert_eq!(rects[1].y, 900.0);
+    }
+
+    #[test]
+    fn test_marker_position_and_hit_test() {
+        // Line -400 is index 500 of 1000 total lines -> halfway down
+        let rects = scrollbar_rects(&viewport(), state(0), &[-400]);
+        assert_eq!(rects[2].y, 500.0);
+
+        assert_eq!(marker_at(&viewport(), state(0), &[-400], 797.0, 501.0), Some(-400));
+        assert_eq!(marker_at(&viewport(), state(0), &[-400], 400.0, 500.0), None);
+        assert_eq!(marker_at(&viewport(), state(0), &[-400], 797.0, 520.0), None);
+    }
+
+    #[test]
+    fn test_markers_beyond_the_rect_limit_are_banded() {
+        // A match on every other line: far more rows than there are ticks
+        let markers: Vec<i32> = (-900..100).step_by(2).rev().collect();
+        let rects = scrollbar_rects(&viewport(), state(0), &markers);
+        assert_eq!(rects.len(), MAX_RECTS);
+
+        // The last match (line 98, near the bottom) still has a tick in its band
+        let band = 1000.0 / (MAX_RECTS - 2) as f32;
+        let last = rects.last().unwrap();
+        assert!(998.0 - last.y < band, "{:?}", last);
+        assert!(rects[2..].windows(2).all(|pair| pair[0].y < pair[1].y));
+    }
+
+    #[test]
+    fn test_offset_for_line_is_clamped() {
+        assert_eq!(state(0).offset_for_line(-400), 450);
+        assert_eq!(state(0).offset_for_line(-899), 900);
+        assert_eq!(state(0).offset_for_line(90), 0);
+    }
+
+    #[test]
+    fn test_line_visibility() {
+        assert!(state(0).is_line_visible(0));
+        assert!(state(0).is_line_visible(99));
+        assert!(!state(0).is_line_visible(-1));
+        assert!(state(300).is_line_visible(-300));
+        assert!(!state(300).is_line_visible(-200));
+    }
+
+    #[test]
+    fn test_anchor_survives_history_growth() {
+        assert_eq!(state(0).top_line_anchor(), None);
+
+        // 300 lines up in 900 lines of history -> line 600 from the top
+        let anchor = state(300).top_line_anchor().unwrap();
+        assert_eq!(anchor, 600);
+
+        // Shrinking the window pushed 20 more lines into history
+        assert_eq!(offset_for_anchor(anchor, 920), 320);
+        assert_eq!(offset_for_anchor(anchor, 500), 0);
+    }
+}
@@ -1,7 +1,7 @@
-/// GPU-accelerated selection highlight rendering and pane border rendering
-use super::range::SelectionRange;
-use alacritty_terminal::grid::Dimensions;
-use crate::pane::PaneNode;
+/// GPU-accelerated selection highlight rendering
+use crate::shaders::Shader;
+use saternal_core::geometry::Padding;
+use saternal_core::selection::{PaneViewport, SelectionRange};
 use wgpu;
 use wgpu::util::DeviceExt;
 
@@ -16,115 +16,14 @@ struct SelectionSpan {
 unsafe impl bytemuck::Pod for SelectionSpan {}
 unsafe impl bytemuck::Zeroable for SelectionSpan {}
 
-/// Viewport for rendering a single pane
-#[derive(Debug, Clone)]
-pub struct PaneViewport {
-    pub pane_id: usize,
-    pub x: u32,
-    pub y: u32,
-    pub width: u32,
-    pub height: u32,
-    pub focused: bool,
-}
-
-/// Calculate viewports for all panes in the tree
-pub fn calculate_pane_viewports(
-    pane_tree: &PaneNode,
-    window_width: u32,
-    window_height: u32,
-) -> Vec<PaneViewport> {
-    let mut viewports = Vec::new();
-    calculate_viewports_recursive(
-        pane_tree,
-        0, 0,
-        window_width, window_height,
-        &mut viewports
-    );
-    viewports
-}
-
-fn calculate_viewports_recursive(
-    node: &PaneNode,
-    x: u32, y: u32,
-    width: u32, height: u32,
-    viewports: &mut Vec<PaneViewport>
-) {
-    use crate::pane::{PaneNode as PN, SplitDirection};
-    
-    match node {
-        PN::Leaf { pane } => {
-            viewports.push(PaneViewport {
-                pane_id: pane.id,
-                x, y, width, height,
-                focused: pane.focused,
-            });
-        }
-        PN::Split { direction, children, ratio } => {
-            const BORDER_WIDTH: u32 = 2;
-            let a = BORDER_WIDTH / 2;
-            let b = BORDER_WIDTH - a;
-
-            match direction {
-                SplitDirection::Horizontal => {
-                    // Top/bottom split
-                    let split_y = (height as f32 * ratio) as u32;
-
-                    if let Some(top) = children.get(0) {
-                        calculate_viewports_recursive(
-                            top,
-                            x, y,
-                            width,
-                            split_y.saturating_sub(a),
-                            viewports
-                        );
-                    }
-
-                    if let Some(bottom) = children.get(1) {
-                        calculate_viewports_recursive(
-                            bottom,
-                            x,
-                            y + split_y + b,
-                            width,
-                            height.saturating_sub(split_y + b),
-                            viewports
-                        );
-                    }
-                }
-                SplitDirection::Vertical => {
-                    // Left/right split
-                    let split_x = (width as f32 * ratio) as u32;
-
-                    if let Some(left) = children.get(0) {
-                        calculate_viewports_recursive(
-                            left,
-                            x, y,
-                            split_x.saturating_sub(a),
-                            height,
-                            viewports
-                        );
-                    }
-
-                    if let Some(right) = children.get(1) {
-                        calculate_viewports_recursive(
-                            right,
-                            x + split_x + b,
-                            y,
-                            width.saturating_sub(split_x + b),
-                            height,
-                            viewports
-                        );
-                    }
-                }
-            }
-        }
-    }
-}
+/// Highlight rectangles the selection shader holds
+const MAX_SPANS: usize = 64;
 
 /// Selection uniform data (matches shader layout)
 #[repr(C)]
 #[derive(Copy, Clone, Debug)]
 struct SelectionUniforms {
-    spans: [SelectionSpan; 64],  // Up to 64 spans (1024 bytes)
+    spans: [SelectionSpan; MAX_SPANS],  // Up to 64 spans (1024 bytes)
     count: u32,                   // 4 bytes
     _padding1: [u32; 7],          // 28 bytes padding to match std140 layout (vec3<u32> alignment + vec4<f32> alignment)
     color: [f32; 4],              // 16 bytes (vec4<f32>)
@@ -160,10 +59,10 @@ impl SelectionRenderer {
         });
 
         let initial_uniforms = SelectionUniforms {
-            spans: [SelectionSpan { position: [0.0, 0.0], size: [0.0, 0.0] }; 64],
+            spans: [SelectionSpan { position: [0.0, 0.0], size: [0.0, 0.0] }; MAX_SPANS],
             count: 0,
             _padding1: [0, 0, 0, 0, 0, 0, 0],
-            color: [0.3, 0.5, 0.8, 0.3],  // Semi-transparent blue
+            color: saternal_core::theme::ColorPalette::default().selection_bg,
         };
 
         let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
@@ -193,28 +92,36 @@ impl SelectionRenderer {
         }
     }
 
-    /// Update selection spans from grid range
+    /// Update selection spans from grid ranges
+    /// `viewport` is the pane the selection belongs to; spans past the first 64 are not drawn
     pub fn update(
         &mut self,
-        range: Option<SelectionRange>,
+        ranges: &[SelectionRange],
         cell_width: f32,
         cell_height: f32,
+        viewport: &PaneViewport,
+        padding: &Padding,
         window_width: u32,
         window_height: u32,
         grid_cols: usize,
         grid_lines: usize,
     ) {
-        if let Some(range) = range {
-            let spans = self.range_to_spans(range, cell_width, cell_height, window_width, window_height, grid_cols, grid_lines);
-            self.current_uniforms.count = spans.len() as u32;
-            for (i, span) in spans.iter().enumerate() {
-                if i < 64 {
-                    self.current_uniforms.spans[i] = *span;
-                }
-            }
-            self.dirty = true;
-        } else {
-            self.current_uniforms.count = 0;
+        let spans: Vec<SelectionSpan> = ranges
+            .iter()
+            .flat_map(|range| {
+                Self::range_to_spans(*range, cell_width, cell_height, viewport, padding, window_width, window_height, grid_cols, grid_lines)
+            })
+            .take(MAX_SPANS)
+            .collect();
+        self.current_uniforms.count = spans.len() as u32;
+        self.current_uniforms.spans[..spans.len()].copy_from_slice(&spans);
+        self.dirty = true;
+    }
+
+    /// Set the highlight color (its alpha is how strongly cells are tinted)
+    pub fn set_color(&mut self, color: [f32; 4]) {
+        if self.current_uniforms.color != color {
+            self.current_uniforms.color = color;
             self.dirty = true;
         }
     }
@@ -246,6 +153,15 @@ impl SelectionRenderer {
         &self.pipeline
     }
 
+    /// Rebuild the pipeline from the shader on disk, keeping the old one if it doesn't compile
+    #[cfg(feature = "shader-hot-reload")]
+    pub(crate) fn reload_pipeline(&mut self, device: &wgpu::Device, surface_format: wgpu::TextureFormat) {
+        let create = || create_selection_pipeline(device, &self.bind_group_layout, surface_format);
+        if let Some(pipeline) = crate::shaders::rebuild(device, "selection", create) {
+            self.pipeline = pipeline;
+        }
+    }
+
     /// Get instance count for rendering
     pub fn instance_count(&self) -> u32 {
         self.current_uniforms.count
@@ -253,10 +169,11 @@ impl SelectionRenderer {
 
     /// Convert selection range to NDC spans
     fn range_to_spans(
-        &self,
         range: SelectionRange,
         cell_width: f32,
         cell_height: f32,
+        viewport: &PaneViewport,
+        padding: &Padding,
         window_width: u32,
         window_height: u32,
         grid_cols: usize,
@@ -265,23 +182,27 @@ impl SelectionRenderer {
         let (start, end) = range.normalized();
         let mut spans = Vec::new();
         
-        // Clamp to grid bounds
+        // Clip to grid bounds: rows scrolled off the screen aren't drawn, and a
+        // selection continuing past an edge covers the whole row there
         let max_col = grid_cols.saturating_sub(1);
         let max_line = (grid_lines as i32).saturating_sub(1);
-        let start_col = start.column.0.min(max_col);
-        let end_col = end.column.0.min(max_col);
-        let start_line = start.line.0.max(0).min(max_line);
-        let end_line = end.line.0.max(0).min(max_line);
+        if end.line.0 < 0 || start.line.0 > max_line {
+            return spans;
+        }
+        let (start_line, start_col) = if start.line.0 < 0 { (0, 0) } else { (start.line.0, start.column.0.min(max_col)) };
+        let (end_line, end_col) = if end.line.0 > max_line { (max_line, max_col) } else { (end.line.0, end.column.0.min(max_col)) };
 
         if start_line == end_line {
             // Single line selection
             let width = end_col.saturating_sub(start_col) + 1;
-            let span = self.create_span(
+            let span = Self::create_span(
                 start_line as usize,
                 start_col,
                 width,
                 cell_width,
                 cell_height,
+                viewport,
+                padding,
                 window_width,
                 window_height,
             );
@@ -290,12 +211,14 @@ impl SelectionRenderer {
             // Multi-line selection
             // First line (from start to end of line)
             let first_width = grid_cols.saturating_sub(start_col);
-            let first_span = self.create_span(
+            let first_span = Self::create_span(
                 start_line as usize,
                 start_col,
                 first_width,
                 cell_width,
                 cell_height,
+                viewport,
+                padding,
                 window_width,
                 window_height,
             );
@@ -303,12 +226,14 @@ impl SelectionRenderer {
 
             // Middle lines (full width)
             for line in (start_line + 1)..end_line {
-                let span = self.create_span(
+                let span = Self::create_span(
                     line as usize,
                     0,
                     grid_cols,
                     cell_width,
                     cell_height,
+                    viewport,
+                    padding,
                     window_width,
                     window_height,
                 );
@@ -317,12 +242,14 @@ impl SelectionRenderer {
 
             // Last line (from start of line to end)
             let last_width = (end_col + 1).min(grid_cols);
-            let last_span = self.create_span(
+            let last_span = Self::create_span(
                 end_line as usize,
                 0,
                 last_width,
                 cell_width,
                 cell_height,
+                viewport,
+                padding,
                 window_width,
                 window_height,
             );
@@ -335,21 +262,19 @@ impl SelectionRenderer {
     /// Create a single span in NDC coordinates
     #[inline]
     fn create_span(
-        &self,
         line: usize,
         col: usize,
         width_cells: usize,
         cell_width: f32,
         cell_height: f32,
+        viewport: &PaneViewport,
+        padding: &Padding,
         window_width: u32,
         window_height: u32,
     ) -> SelectionSpan {
-        // Padding constants (must match TextRasterizer padding)
-        const PADDING_LEFT: f32 = 10.0;
-        const PADDING_TOP: f32 = 5.0;
-        
-        let pixel_x = PADDING_LEFT + col as f32 * cell_width;
-        let pixel_y = PADDING_TOP + line as f32 * cell_height;
+        // Padding is applied inside each pane, like the TextRasterizer does
+        let pixel_x = viewport.x as f32 + padding.left + col as f32 * cell_width;
+        let pixel_y = viewport.y as f32 + padding.top + line as f32 * cell_height;
         let pixel_width = width_cells as f32 * cell_width;
 
         // Convert to NDC
@@ -373,7 +298,7 @@ fn create_selection_pipeline(
 ) -> wgpu::RenderPipeline {
     let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
         label: Some("Selection Shader"),
-        source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/selection.wgsl").into()),
+        source: Shader::Selection.source(),
     });
 
     let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
@@ -417,3 +342,45 @@ fn create_selection_pipeline(
         multiview: None,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PADDING: Padding = Padding { top: 8.0, left: 12.0, right: 4.0, bottom: 4.0 };
+
+    fn spans(range: SelectionRange) -> Vec<SelectionSpan> {
+        let viewport = PaneViewport { pane_id: 0, x: 0, y: 0, width: 800, height: 600, focused: true, font_scale: 1.0 };
+        SelectionRenderer::range_to_spans(range, 10.0, 20.0, &viewport, &PADDING, 800, 600, 80, 24)
+    }
+
+    #[test]
+    fn test_spans_clip_rows_scrolled_off_screen() {
+        use alacritty_terminal::index::{Column, Line, Point};
+        use saternal_core::SelectionMode;
+        let range = |start: (i32, usize), end: (i32, usize)| {
+            SelectionRange::new(Point::new(Line(start.0), Column(start.1)), Point::new(Line(end.0), Column(end.1)), SelectionMode::Normal)
+        };
+
+        // Entirely in the history: nothing on screen
+        assert!(spans(range((-3, 4), (-1, 2))).is_empty());
+
+        // Starting in the history: the top row is covered from its first column
+        let clipped = spans(range((-3, 4), (1, 2)));
+        assert_eq!(clipped.len(), 2);
+        assert_eq!(clipped[0].size[0], spans(range((0, 0), (0, 79)))[0].size[0]);
+        assert_eq!(clipped[0].position, spans(range((0, 0), (0, 0)))[0].position);
+    }
+
+    #[test]
+    fn test_span_is_offset_by_pane_origin() {
+        let viewport = PaneViewport { pane_id: 2, x: 400, y: 300, width: 400, height: 300, focused: true, font_scale: 1.0 };
+        let span = SelectionRenderer::create_span(0, 0, 2, 10.0, 20.0, &viewport, &PADDING, 800, 600);
+
+        let expected_x = (400.0 + PADDING.left) / 800.0 * 2.0 - 1.0;
+        let expected_y = -((300.0 + PADDING.top) / 600.0 * 2.0 - 1.0);
+        assert!((span.position[0] - expected_x).abs() < 1e-6);
+        assert!((span.position[1] - expected_y).abs() < 1e-6);
+        assert!((span.size[0] - 20.0 / 800.0 * 2.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,132 @@
+/// WGSL sources for the render pipelines
+///
+/// Shaders are compiled into the binary. With the `shader-hot-reload` feature
+/// (for working on the shaders), they are read from `saternal-renderer/src/shaders/`
+/// instead, and `ShaderWatcher` reports edits so pipelines can be rebuilt
+/// without restarting.
+use std::borrow::Cow;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Shader {
+    Text,
+    Glyph,
+    Cursor,
+    Border,
+    Scrollbar,
+    Selection,
+    Frame,
+    Blur,
+}
+
+impl Shader {
+    #[cfg_attr(not(feature = "shader-hot-reload"), allow(dead_code))]
+    fn file_name(self) -> &'static str {
+        match self {
+            Shader::Text => "text.wgsl",
+            Shader::Glyph => "glyph.wgsl",
+            Shader::Cursor => "cursor.wgsl",
+            Shader::Border => "border.wgsl",
+            Shader::Scrollbar => "scrollbar.wgsl",
+            Shader::Selection => "selection.wgsl",
+            Shader::Frame => "frame.wgsl",
+            Shader::Blur => "blur.wgsl",
+        }
+    }
+
+    /// Copy compiled into the binary
+    fn builtin(self) -> &'static str {
+        match self {
+            Shader::Text => include_str!("shaders/text.wgsl"),
+            Shader::Glyph => include_str!("shaders/glyph.wgsl"),
+            Shader::Cursor => include_str!("shaders/cursor.wgsl"),
+            Shader::Border => include_str!("shaders/border.wgsl"),
+            Shader::Scrollbar => include_str!("shaders/scrollbar.wgsl"),
+            Shader::Selection => include_str!("shaders/selection.wgsl"),
+            Shader::Frame => include_str!("shaders/frame.wgsl"),
+            Shader::Blur => include_str!("shaders/blur.wgsl"),
+        }
+    }
+
+    /// Source to compile: the file on disk with hot reload (if readable), else the built-in copy
+    pub(crate) fn source(self) -> wgpu::ShaderSource<'static> {
+        #[cfg(feature = "shader-hot-reload")]
+        match std::fs::read_to_string(hot_reload::shader_dir().join(self.file_name())) {
+            Ok(source) => return wgpu::ShaderSource::Wgsl(Cow::Owned(source)),
+            Err(e) => log::warn!("Using built-in {}: {}", self.file_name(), e),
+        }
+        wgpu::ShaderSource::Wgsl(Cow::Borrowed(self.builtin()))
+    }
+}
+
+#[cfg(feature = "shader-hot-reload")]
+pub use hot_reload::ShaderWatcher;
+#[cfg(feature = "shader-hot-reload")]
+pub(crate) use hot_reload::rebuild;
+
+#[cfg(feature = "shader-hot-reload")]
+mod hot_reload {
+    use notify::{EventKind, RecursiveMode, Watcher};
+    use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    /// Shader sources in this checkout
+    pub(super) fn shader_dir() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("src").join("shaders")
+    }
+
+    /// Watches the shader sources for edits
+    pub struct ShaderWatcher {
+        _watcher: notify::RecommendedWatcher,
+        changed: Arc<AtomicBool>,
+    }
+
+    impl ShaderWatcher {
+        /// Start watching; `wake` is called from the watcher thread after each edit
+        pub fn start(wake: impl Fn() + Send + 'static) -> notify::Result<Self> {
+            let changed = Arc::new(AtomicBool::new(false));
+            let flag = changed.clone();
+            let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                let Ok(event) = event else {
+                    return;
+                };
+                let edited = matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+                    && event.paths.iter().any(|path| path.extension().is_some_and(|ext| ext == "wgsl"));
+                if edited {
+                    flag.store(true, Ordering::Release);
+                    wake();
+                }
+            })?;
+            let dir = shader_dir();
+            watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+            log::info!("Watching {} for shader edits", dir.display());
+            Ok(Self { _watcher: watcher, changed })
+        }
+
+        /// Whether a shader was edited since the last call
+        pub fn take_change(&self) -> bool {
+            self.changed.swap(false, Ordering::AcqRel)
+        }
+    }
+
+    /// Build a pipeline from the current sources, or None (logged) if they don't
+    /// compile, so the old pipeline stays in use instead of wgpu panicking
+    pub(crate) fn rebuild(
+        device: &wgpu::Device,
+        name: &str,
+        create: impl FnOnce() -> wgpu::RenderPipeline,
+    ) -> Option<wgpu::RenderPipeline> {
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let pipeline = create();
+        match futures::executor::block_on(device.pop_error_scope()) {
+            Some(error) => {
+                log::error!("Keeping the old {} pipeline: {}", name, error);
+                None
+            }
+            None => {
+                log::info!("Reloaded the {} pipeline", name);
+                Some(pipeline)
+            }
+        }
+    }
+}
@@ -0,0 +1,52 @@
+// Separable Gaussian blur for the wallpaper
+// Run twice: once with a horizontal texel step, once with a vertical one
+
+struct BlurParams {
+    step: vec2<f32>,    // One texel along the blur direction (UV units)
+    sigma: f32,         // Gaussian sigma in texels
+    _padding: f32,
+}
+
+@group(0) @binding(0)
+var source: texture_2d<f32>;
+@group(0) @binding(1)
+var source_sampler: sampler;
+@group(0) @binding(2)
+var<uniform> params: BlurParams;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+// Largest kernel half-width in texels (keeps heavy blur bounded)
+const MAX_TAPS: i32 = 64;
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    var output: VertexOutput;
+
+    // Single triangle covering the whole target
+    let uv = vec2<f32>(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+    output.position = vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+    output.uv = uv;
+
+    return output;
+}
+
+@fragment
+fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
+    let taps = min(i32(ceil(params.sigma * 3.0)), MAX_TAPS);
+    let denominator = 2.0 * params.sigma * params.sigma;
+
+    var sum = vec4<f32>(0.0);
+    var weight_sum = 0.0;
+    for (var i = -taps; i <= taps; i++) {
+        let offset = f32(i);
+        let weight = exp(-(offset * offset) / denominator);
+        sum += textureSampleLevel(source, source_sampler, input.uv + params.step * offset, 0.0) * weight;
+        weight_sum += weight;
+    }
+
+    return sum / weight_sum;
+}
@@ -4,14 +4,25 @@ struct CursorUniform {
     position: vec2<f32>,      // NDC position (-1 to 1)
     size: vec2<f32>,          // NDC size
     color: vec4<f32>,         // RGBA color
+    text_color: vec4<f32>,    // Character under a block cursor (alpha 0 = leave as is)
+    cell_fg: vec4<f32>,       // Cell colors as stored in the text texture
+    cell_bg: vec4<f32>,
+    border: vec2<f32>,        // Outline thickness of a hollow cursor (NDC)
     visible: u32,             // 0 = hidden, 1 = visible
     style: u32,               // 0 = block, 1 = beam, 2 = underline
-    _padding: vec2<u32>,      // Alignment
+    hollow: u32,              // 1 = outline only
+    _padding0: u32,           // Alignment
+    _padding1: u32,
+    _padding2: u32,
 }
 
 @group(0) @binding(0)
 var<uniform> cursor: CursorUniform;
 
+// Rendered terminal text, to find the glyph under a block cursor
+@group(1) @binding(0)
+var text_texture: texture_2d<f32>;
+
 struct VertexOutput {
     @builtin(position) position: vec4<f32>,
     @location(0) local_pos: vec2<f32>,  // Position within cursor quad (0-1)
@@ -47,6 +58,26 @@ fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
     if (cursor.visible == 0u) {
         discard;
     }
-    
+
+    // Hollow: keep a band of `border` along each edge
+    if (cursor.hollow == 1u) {
+        let edge = cursor.border / abs(cursor.size);
+        let inside = input.local_pos > edge && input.local_pos < vec2<f32>(1.0) - edge;
+        if (inside.x && inside.y) {
+            discard;
+        }
+        return cursor.color;
+    }
+
+    // Reversed text: how much of this pixel is glyph, judged by where its color
+    // falls between the cell's background and foreground
+    if (cursor.text_color.a > 0.0) {
+        let pixel = textureLoad(text_texture, vec2<i32>(input.position.xy), 0).rgb;
+        let span = cursor.cell_fg.rgb - cursor.cell_bg.rgb;
+        let coverage = clamp(dot(pixel - cursor.cell_bg.rgb, span) / max(dot(span, span), 0.0001), 0.0, 1.0);
+        let rgb = mix(cursor.color.rgb, cursor.text_color.rgb, coverage);
+        return vec4<f32>(rgb, mix(cursor.color.a, cursor.text_color.a, coverage));
+    }
+
     return cursor.color;
 }
@@ -0,0 +1,54 @@
+// Window frame shader
+// Clears the window's rounded-off corners and draws the accent outline along its edge
+
+struct FrameUniform {
+    border_color: vec4<f32>,  // RGBA of the outline
+    size: vec2<f32>,          // Window size in pixels
+    radius_top: f32,          // Corner radius of the top corners in pixels (0 = square)
+    radius_bottom: f32,       // Corner radius of the bottom corners in pixels
+    border_width: f32,        // Outline width in pixels (0 = none)
+    _padding1: f32,
+    _padding2: f32,
+    _padding3: f32,
+}
+
+@group(0) @binding(0)
+var<uniform> frame: FrameUniform;
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> @builtin(position) vec4<f32> {
+    // One triangle covering the whole window
+    let uv = vec2<f32>(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+    return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+
+// Distance in pixels from the window's rounded edge, negative inside
+fn edge_distance(pixel: vec2<f32>) -> f32 {
+    let half_size = frame.size * 0.5;
+    let p = pixel - half_size;
+    let radius = select(frame.radius_bottom, frame.radius_top, p.y < 0.0);
+    let q = abs(p) - half_size + vec2<f32>(radius);
+    return length(max(q, vec2<f32>(0.0))) + min(max(q.x, q.y), 0.0) - radius;
+}
+
+// Coverage is written as alpha; the blend state keeps that share of what is already there
+@fragment
+fn fs_mask(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    let coverage = clamp(0.5 - edge_distance(position.xy), 0.0, 1.0);
+    if (coverage >= 1.0) {
+        discard;
+    }
+    return vec4<f32>(0.0, 0.0, 0.0, coverage);
+}
+
+@fragment
+fn fs_border(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    let distance = edge_distance(position.xy);
+    let outer = clamp(0.5 - distance, 0.0, 1.0);
+    let inner = clamp(0.5 - (distance + frame.border_width), 0.0, 1.0);
+    let coverage = outer - inner;
+    if (coverage <= 0.0) {
+        discard;
+    }
+    return vec4<f32>(frame.border_color.rgb, frame.border_color.a * coverage);
+}
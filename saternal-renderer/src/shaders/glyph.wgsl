@@ -11,8 +11,12 @@ var atlas_sampler: sampler;
 struct ScreenUniforms {
     width: f32,
     height: f32,
+    time: f32,              // Seconds on the blink clock
+    blink_interval: f32,    // Seconds per blink phase, 0 when blinking is off
 }
 
+const GLYPH_BLINK: u32 = 1u;
+
 @group(1) @binding(0)
 var<uniform> screen: ScreenUniforms;
 
@@ -23,6 +27,7 @@ struct InstanceInput {
     @location(2) uv_min: vec2<f32>,        // Atlas UV min
     @location(3) uv_max: vec2<f32>,        // Atlas UV max
     @location(4) color: vec4<f32>,         // RGBA color
+    @location(5) flags: u32,               // GLYPH_* bits
 }
 
 struct VertexOutput {
@@ -70,8 +75,17 @@ fn vs_main(
         }
     }
     
+    // Blinking glyphs collapse to nothing during the off phase
+    var size = instance.size;
+    if (instance.flags & GLYPH_BLINK) != 0u && screen.blink_interval > 0.0 {
+        let phase = u32(floor(screen.time / screen.blink_interval));
+        if phase % 2u == 1u {
+            size = vec2<f32>(0.0, 0.0);
+        }
+    }
+
     // Transform to instance position and size
-    let world_pos = instance.position + local_pos * instance.size;
+    let world_pos = instance.position + local_pos * size;
     output.clip_position = vec4<f32>(world_pos, 0.0, 1.0);
     
     // Interpolate UV coordinates in atlas
@@ -0,0 +1,44 @@
+// Post-process prelude, prepended to the user's shader (appearance.post_process)
+//
+// The user's file defines
+//     fn effect(uv: vec2<f32>) -> vec4<f32>
+// returning the premultiplied color of the pixel at `uv` ((0, 0) top-left,
+// (1, 1) bottom-right). It can read only what is declared here:
+//     source, source_sampler  the finished frame (premultiplied, linear color)
+//     post.resolution         frame size in pixels
+//     post.time               seconds since the shader was loaded
+
+struct PostProcessUniforms {
+    resolution: vec2<f32>,
+    time: f32,
+    _padding: f32,
+}
+
+@group(0) @binding(0)
+var<uniform> post: PostProcessUniforms;
+
+@group(0) @binding(1)
+var source: texture_2d<f32>;
+
+@group(0) @binding(2)
+var source_sampler: sampler;
+
+struct PostProcessVertex {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+@vertex
+fn post_process_vs(@builtin(vertex_index) vertex_index: u32) -> PostProcessVertex {
+    // One triangle covering the whole frame
+    let corner = vec2<f32>(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+    var output: PostProcessVertex;
+    output.position = vec4<f32>(corner * 2.0 - 1.0, 0.0, 1.0);
+    output.uv = vec2<f32>(corner.x, 1.0 - corner.y);
+    return output;
+}
+
+@fragment
+fn post_process_fs(input: PostProcessVertex) -> @location(0) vec4<f32> {
+    return effect(input.uv);
+}
@@ -0,0 +1,62 @@
+// Scrollbar overlay shader
+// Draws the scrollbar track, thumb, and search match markers as colored rectangles
+
+struct ScrollbarRect {
+    position: vec2<f32>,      // NDC position (top-left corner)
+    size: vec2<f32>,          // NDC size (width, height)
+    color: vec4<f32>,         // RGBA color
+}
+
+struct ScrollbarUniform {
+    rects: array<ScrollbarRect, 64>,  // Track + thumb + markers (2048 bytes)
+    count: u32,                        // Number of active rects (4 bytes)
+    _padding1: vec3<u32>,              // Padding to 16-byte boundary (12 bytes)
+}
+
+@group(0) @binding(0)
+var<uniform> scrollbar: ScrollbarUniform;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+}
+
+@vertex
+fn vs_main(
+    @builtin(vertex_index) vertex_index: u32,
+    @builtin(instance_index) instance_index: u32
+) -> VertexOutput {
+    var output: VertexOutput;
+
+    // Skip if instance is beyond active rect count
+    if (instance_index >= scrollbar.count) {
+        output.position = vec4<f32>(0.0, 0.0, 0.0, 0.0);
+        output.color = vec4<f32>(0.0, 0.0, 0.0, 0.0);
+        return output;
+    }
+
+    let rect = scrollbar.rects[instance_index];
+
+    // Generate quad vertices (6 vertices per quad)
+    var local: vec2<f32>;
+    let vertex_in_quad = vertex_index % 6u;
+    switch vertex_in_quad {
+        case 0u: { local = vec2<f32>(0.0, 0.0); }  // Top-left
+        case 1u: { local = vec2<f32>(1.0, 0.0); }  // Top-right
+        case 2u: { local = vec2<f32>(1.0, 1.0); }  // Bottom-right
+        case 3u: { local = vec2<f32>(0.0, 0.0); }  // Top-left
+        case 4u: { local = vec2<f32>(1.0, 1.0); }  // Bottom-right
+        default: { local = vec2<f32>(0.0, 1.0); }  // Bottom-left
+    }
+
+    let final_pos = rect.position + local * rect.size;
+    output.position = vec4<f32>(final_pos, 0.0, 1.0);
+    output.color = rect.color;
+
+    return output;
+}
+
+@fragment
+fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
+    return input.color;
+}
@@ -34,29 +34,55 @@ var wallpaper_texture: texture_2d<f32>;
 @group(1) @binding(1)
 var wallpaper_sampler: sampler;
 
-// Group 2: Opacity uniforms
+// Group 2: Opacity uniforms (plus the wallpaper's UV transform for its scaling mode)
 struct OpacityUniforms {
     wallpaper_opacity: f32,
     background_opacity: f32,
     has_wallpaper: u32,
-    _padding: f32,
+    display_p3: u32,         // Surface is tagged Display P3
+    uv_scale: vec2<f32>,     // Surface UV -> wallpaper UV
+    uv_offset: vec2<f32>,
+    tile: u32,               // Repeat the wallpaper
+    clip: u32,               // No wallpaper outside its rect (fit/center)
+    _padding2: vec2<u32>,
 }
 
 @group(2) @binding(0)
 var<uniform> opacity: OpacityUniforms;
 
+// Linear sRGB -> linear Display P3 (same D65 white point; columns of the matrix)
+const SRGB_TO_DISPLAY_P3 = mat3x3<f32>(
+    vec3<f32>(0.8225, 0.0332, 0.0171),
+    vec3<f32>(0.1774, 0.9669, 0.0724),
+    vec3<f32>(0.0000, 0.0000, 0.9108),
+);
+
 @fragment
 fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
     // Sample terminal content (text + background)
     let terminal_color = textureSample(t_texture, t_sampler, input.tex_coords);
 
     // If no wallpaper, just return terminal with applied opacity
+    let without_wallpaper = vec4<f32>(terminal_color.rgb * opacity.background_opacity, terminal_color.a * opacity.background_opacity);
     if (opacity.has_wallpaper == 0u) {
-        return vec4<f32>(terminal_color.rgb * opacity.background_opacity, terminal_color.a * opacity.background_opacity);
+        return without_wallpaper;
+    }
+
+    // Map into the wallpaper according to its scaling mode
+    var wallpaper_uv = input.tex_coords * opacity.uv_scale + opacity.uv_offset;
+    if (opacity.tile != 0u) {
+        wallpaper_uv = fract(wallpaper_uv);
     }
+    let inside = all(wallpaper_uv >= vec2<f32>(0.0)) && all(wallpaper_uv <= vec2<f32>(1.0));
 
     // Sample wallpaper texture
-    let wallpaper_color = textureSample(wallpaper_texture, wallpaper_sampler, input.tex_coords);
+    var wallpaper_color = textureSample(wallpaper_texture, wallpaper_sampler, wallpaper_uv);
+
+    // Images are sRGB: keep them looking the same on a Display P3 surface
+    // (palette colors are taken as P3 as they are)
+    if (opacity.display_p3 != 0u) {
+        wallpaper_color = vec4<f32>(SRGB_TO_DISPLAY_P3 * wallpaper_color.rgb, wallpaper_color.a);
+    }
 
     // Apply wallpaper opacity (dim the wallpaper)
     let wallpaper_dimmed = vec4<f32>(
@@ -72,5 +98,6 @@ fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
     // Return blended result
     // Note: We keep the terminal's alpha, not multiplying by background_opacity
     // This ensures text remains visible
-    return blended;
+    // Outside a fit/centered wallpaper, look as if there were no wallpaper
+    return select(blended, without_wallpaper, opacity.clip != 0u && !inside);
 }
@@ -0,0 +1,657 @@
+use saternal_core::blink::BLINK;
+use saternal_core::font::{Antialias, FontManager};
+use saternal_core::geometry::Padding;
+use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::index::{Column, Line, Point};
+use alacritty_terminal::term::Term;
+use anyhow::Result;
+use wgpu;
+
+use super::color::{cell_colors, selected_cell_colors};
+use super::theme::ColorPalette;
+use saternal_core::search::LineFilter;
+use saternal_core::selection::{SelectionRange, SelectionStyle};
+
+/// How far pane header backgrounds are tinted toward the foreground color
+const HEADER_TINT: f32 = 0.12;
+
+/// Headers stay readable over fully transparent backgrounds
+const HEADER_MIN_ALPHA: f32 = 0.85;
+
+/// Badge text size relative to the terminal font
+const BADGE_SCALE: f32 = 3.0;
+
+/// Badge opacity over the pane content
+const BADGE_ALPHA: f32 = 0.2;
+
+/// A rendered pixel buffer the overlay helpers draw onto (a pane's, or the window's)
+pub(crate) struct PaneTarget<'a> {
+    pub buffer: &'a mut [u8],
+    pub width: u32,
+    pub height: u32,
+    pub surface_format: wgpu::TextureFormat,
+}
+
+impl PaneTarget<'_> {
+    fn is_bgra(&self) -> bool {
+        matches!(
+            self.surface_format,
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb
+        )
+    }
+}
+
+/// Rasterizes terminal text to a pixel buffer for GPU upload
+#[derive(Clone)]
+pub(crate) struct TextRasterizer {
+    cell_width: f32,
+    cell_height: f32,
+    baseline_offset: f32,
+    antialias: Antialias,
+    padding: Padding,
+}
+
+impl TextRasterizer {
+    /// Create a new text rasterizer with cell dimensions and the default padding
+    pub fn new(cell_width: f32, cell_height: f32, baseline_offset: f32) -> Self {
+        Self {
+            cell_width,
+            cell_height,
+            baseline_offset,
+            antialias: Antialias::Grayscale,
+            padding: Padding::default(),
+        }
+    }
+
+    /// Space between the pane edges and the grid (must match the terminal size calculation)
+    pub fn set_padding(&mut self, padding: Padding) {
+        self.padding = padding;
+    }
+
+    /// Switch between grayscale and subpixel glyph antialiasing
+    pub fn set_antialias(&mut self, antialias: Antialias) {
+        self.antialias = antialias;
+    }
+
+    /// Update cell dimensions (called when font size changes)
+    pub fn update_dimensions(&mut self, cell_width: f32, cell_height: f32, baseline_offset: f32) {
+        self.cell_width = cell_width;
+        self.cell_height = cell_height;
+        self.baseline_offset = baseline_offset;
+    }
+
+    /// A copy for cells of another size (a zoomed pane's), with the same antialiasing and padding
+    pub fn with_dimensions(&self, cell_width: f32, cell_height: f32, baseline_offset: f32) -> Self {
+        let mut rasterizer = self.clone();
+        rasterizer.update_dimensions(cell_width, cell_height, baseline_offset);
+        rasterizer
+    }
+
+    /// Render terminal content to texture buffer
+    /// Cells inside `selection` (screen rows, not grid lines) are colored per `selection_style`
+    /// With a `filter` only its lines are drawn, and `scroll_offset` scrolls through them
+    pub fn render_to_buffer<T>(
+        &self,
+        term: &Term<T>,
+        font_manager: &FontManager,
+        width: u32,
+        height: u32,
+        scroll_offset: usize,
+        filter: Option<&LineFilter>,
+        surface_format: wgpu::TextureFormat,
+        palette: &ColorPalette,
+        blink_visible: bool,
+        selection: &[SelectionRange],
+        selection_style: SelectionStyle,
+    ) -> Result<Vec<u8>> {
+        let rows = term.screen_lines();
+        let cols = term.columns();
+        let cursor = term.grid().cursor.point;
+        
+        // CRITICAL: Clamp scroll_offset to available history to prevent out-of-bounds access
+        // The grid can access lines from -history_size to screen_lines-1
+        let history_size = term.grid().history_size();
+        let scroll_offset = scroll_offset.min(history_size);
+
+        // Determine if we need BGRA or RGBA based on surface format
+        let is_bgra = matches!(
+            surface_format,
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb
+        );
+
+        // Start from a buffer filled with background color
+        // The wallpaper is rendered BEFORE this in a separate pass
+        let mut buffer = self.background_buffer(width, height, surface_format, palette);
+        let default_bg = palette.background.map(|channel| (channel * 255.0) as u8);
+
+        // Render each cell from the terminal grid
+        let mut char_count = 0;
+        for row_idx in 0..rows {
+            // Apply scroll offset: negative Line indices access scrollback
+            let line = match filter {
+                Some(filter) => match filter.line_at(row_idx, rows, scroll_offset) {
+                    // Lines from a grid that has changed since the filter was built are skipped
+                    Some(line) if line.0 >= -(history_size as i32) && line.0 < rows as i32 => line,
+                    _ => continue,
+                },
+                None => Line(row_idx as i32 - scroll_offset as i32),
+            };
+            for col_idx in 0..cols {
+                let column = Column(col_idx);
+                let cell = &term.grid()[line][column];
+
+                // Get colors from palette (reverse video already swapped)
+                let (mut fg, mut bg) = cell_colors(cell, palette);
+
+                // Selected cells may get their own text color, and (inverted) a painted background
+                let screen_point = Point::new(Line(row_idx as i32), column);
+                if selection.iter().any(|range| range.contains(screen_point)) {
+                    let cell_bg = bg.unwrap_or((default_bg[0], default_bg[1], default_bg[2]));
+                    let (text, background) = selected_cell_colors(fg, cell_bg, selection_style, palette);
+                    fg = text;
+                    bg = background.or(bg);
+                }
+
+                // Colored and reverse-video cells, blank ones included, get their background
+                // painted; the glyph is then blended onto it
+                let painted = bg.is_some();
+                if let Some((r, g, b)) = bg {
+                    let color = [r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, 1.0];
+                    let mut target = PaneTarget { buffer: &mut buffer, width, height, surface_format };
+                    self.blend_in_cell(&mut target, screen_point, color, |_, _| true);
+                }
+
+                // Get character
+                let c = cell.c;
+
+                if c == '\0' || c == ' ' {
+                    continue; // Skip null cells and spaces
+                }
+                // Blinking text (SGR 5) is left out during the off phase
+                if !blink_visible && cell.flags.contains(BLINK) {
+                    continue;
+                }
+                char_count += 1;
+                let (fg_r, fg_g, fg_b) = fg;
+
+                // Rasterize glyph
+                let (metrics, bitmap) = match self.antialias {
+                    Antialias::Grayscale => font_manager.rasterize(c),
+                    Antialias::Subpixel => font_manager.rasterize_subpixel(c),
+                };
+
+                // Calculate cell position in window coordinates with padding
+                let cell_x = self.padding.left + col_idx as f32 * self.cell_width;
+                let cell_y = self.padding.top + row_idx as f32 * self.cell_height;
+
+                // Calculate baseline position (from top of cell)
+                let baseline_y = cell_y + self.baseline_offset;
+
+                // Calculate glyph position using proper baseline alignment
+                let glyph_x = cell_x;
+                let glyph_y = baseline_y - (metrics.height as f32 + metrics.ymin as f32);
+
+                if row_idx == 0 && col_idx < 5 {
+                    log::debug!("Char '{}' at cell ({}, {}) -> glyph ({:.1}, {:.1}), baseline {:.1}, metrics: h={} ymin={}",
+                               c, cell_x, cell_y, glyph_x, glyph_y, baseline_y, metrics.height, metrics.ymin);
+                }
+
+                // Draw glyph to buffer with premultiplied alpha
+                self.draw_glyph(
+                    &mut buffer,
+                    &bitmap,
+                    &metrics,
+                    glyph_x,
+                    glyph_y,
+                    fg_r,
+                    fg_g,
+                    fg_b,
+                    width,
+                    height,
+                    is_bgra,
+                    painted,
+                );
+            }
+        }
+
+        Ok(buffer)
+    }
+
+    /// Create a buffer filled with the palette background color (no text)
+    pub fn background_buffer(
+        &self,
+        width: u32,
+        height: u32,
+        surface_format: wgpu::TextureFormat,
+        palette: &ColorPalette,
+    ) -> Vec<u8> {
+        let is_bgra = matches!(
+            surface_format,
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb
+        );
+
+        let bg = palette.background;
+        let bg_r = (bg[0] * 255.0) as u8;
+        let bg_g = (bg[1] * 255.0) as u8;
+        let bg_b = (bg[2] * 255.0) as u8;
+        let bg_a = (bg[3] * 255.0) as u8;
+
+        let mut buffer = vec![0u8; (width * height * 4) as usize];
+
+        // Fill buffer with background color
+        for pixel in buffer.chunks_exact_mut(4) {
+            if is_bgra {
+                pixel[0] = bg_b;
+                pixel[1] = bg_g;
+                pixel[2] = bg_r;
+                pixel[3] = bg_a;
+            } else {
+                pixel[0] = bg_r;
+                pixel[1] = bg_g;
+                pixel[2] = bg_b;
+                pixel[3] = bg_a;
+            }
+        }
+
+        buffer
+    }
+
+    /// Render a one-line pane header strip with `label` left-aligned
+    /// Text that doesn't fit the width is cut off
+    pub fn render_header(
+        &self,
+        label: &str,
+        font_manager: &FontManager,
+        width: u32,
+        height: u32,
+        surface_format: wgpu::TextureFormat,
+        palette: &ColorPalette,
+    ) -> Vec<u8> {
+        let is_bgra = matches!(
+            surface_format,
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb
+        );
+
+        // Tint the background toward the foreground so the strip stands apart from the pane
+        let (bg, fg) = (palette.background, palette.foreground);
+        let mix = |i: usize| ((bg[i] + (fg[i] - bg[i]) * HEADER_TINT) * 255.0) as u8;
+        let (r, g, b, a) = (mix(0), mix(1), mix(2), (bg[3].max(HEADER_MIN_ALPHA) * 255.0) as u8);
+        let mut buffer = vec![0u8; (width * height * 4) as usize];
+        for pixel in buffer.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&if is_bgra { [b, g, r, a] } else { [r, g, b, a] });
+        }
+
+        let (fg_r, fg_g, fg_b) = ((fg[0] * 255.0) as u8, (fg[1] * 255.0) as u8, (fg[2] * 255.0) as u8);
+        let baseline_y = (height as f32 - self.cell_height) / 2.0 + self.baseline_offset;
+        let max_chars = ((width as f32 - self.padding.left - self.padding.right) / self.cell_width).max(0.0) as usize;
+        for (i, c) in label.chars().take(max_chars).enumerate() {
+            if c == ' ' {
+                continue;
+            }
+            let (metrics, bitmap) = match self.antialias {
+                Antialias::Grayscale => font_manager.rasterize(c),
+                Antialias::Subpixel => font_manager.rasterize_subpixel(c),
+            };
+            let glyph_x = self.padding.left + i as f32 * self.cell_width;
+            let glyph_y = baseline_y - (metrics.height as f32 + metrics.ymin as f32);
+            self.draw_glyph(&mut buffer, &bitmap, &metrics, glyph_x, glyph_y, fg_r, fg_g, fg_b, width, height, is_bgra, false);
+        }
+
+        buffer
+    }
+
+    /// Render a box with a header strip per line and a one-pixel outline in the text color
+    /// Rows are `cell_height` rounded up; lines past `height` are cut off
+    pub fn render_box(
+        &self,
+        lines: &[String],
+        font_manager: &FontManager,
+        width: u32,
+        height: u32,
+        surface_format: wgpu::TextureFormat,
+        palette: &ColorPalette,
+    ) -> Vec<u8> {
+        let is_bgra = matches!(
+            surface_format,
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb
+        );
+        let size = (width * height * 4) as usize;
+        let row_height = self.cell_height.ceil() as u32;
+        let mut buffer = Vec::with_capacity(size);
+        for line in lines {
+            if buffer.len() >= size {
+                break;
+            }
+            buffer.extend(self.render_header(line, font_manager, width, row_height, surface_format, palette));
+        }
+        buffer.resize(size, 0);
+
+        let fg = palette.foreground.map(|c| (c * 255.0) as u8);
+        let outline = if is_bgra { [fg[2], fg[1], fg[0], 255] } else { [fg[0], fg[1], fg[2], 255] };
+        for y in 0..height {
+            for x in 0..width {
+                if x == 0 || y == 0 || x == width - 1 || y == height - 1 {
+                    let idx = ((y * width + x) * 4) as usize;
+                    buffer[idx..idx + 4].copy_from_slice(&outline);
+                }
+            }
+        }
+        buffer
+    }
+
+    /// Blend a badge into the top-right corner of a rendered pane
+    /// Shrinks the text to fit half the pane width
+    pub fn draw_badge(&self, target: &mut PaneTarget, text: &str, font_manager: &FontManager, palette: &ColorPalette) {
+        let (width, height) = (target.width, target.height);
+        let is_bgra = target.is_bgra();
+        let buffer = &mut *target.buffer;
+        let font = font_manager.font();
+        let text_width = |size: f32| text.chars().map(|c| font.metrics(c, size).advance_width).sum::<f32>();
+
+        let mut size = font_manager.effective_font_size() * BADGE_SCALE;
+        let max_width = width as f32 / 2.0;
+        let natural_width = text_width(size);
+        if natural_width > max_width {
+            size *= max_width / natural_width;
+        }
+        let Some(line_metrics) = font.horizontal_line_metrics(size) else {
+            return;
+        };
+
+        let fg = palette.foreground;
+        let color = [fg[0] * 255.0, fg[1] * 255.0, fg[2] * 255.0];
+        let color = if is_bgra { [color[2], color[1], color[0]] } else { color };
+        let mut pen_x = width as f32 - self.padding.right - self.cell_width - text_width(size);
+        let baseline_y = self.padding.top + self.cell_height + line_metrics.ascent;
+        for c in text.chars() {
+            let (metrics, bitmap) = font.rasterize(c, size);
+            let glyph_x = (pen_x + metrics.xmin as f32) as i32;
+            let glyph_y = (baseline_y - (metrics.height as f32 + metrics.ymin as f32)) as i32;
+            for gy in 0..metrics.height {
+                for gx in 0..metrics.width {
+                    let (px, py) = (glyph_x + gx as i32, glyph_y + gy as i32);
+                    if px < 0 || py < 0 || px >= width as i32 || py >= height as i32 {
+                        continue;
+                    }
+                    let coverage = bitmap[gy * metrics.width + gx] as f32 / 255.0 * BADGE_ALPHA;
+                    if coverage <= 0.0 {
+                        continue;
+                    }
+                    // Source-over on premultiplied pixels
+                    let idx = (py as usize * width as usize + px as usize) * 4;
+                    for channel in 0..3 {
+                        let dst = buffer[idx + channel] as f32;
+                        buffer[idx + channel] = (color[channel] * coverage + dst * (1.0 - coverage)) as u8;
+                    }
+                    let dst_alpha = buffer[idx + 3] as f32;
+                    buffer[idx + 3] = (255.0 * coverage + dst_alpha * (1.0 - coverage)) as u8;
+                }
+            }
+            pen_x += metrics.advance_width;
+        }
+    }
+
+    /// Thickness in pixels of a hollow cursor's outline
+    pub fn cursor_outline_width(&self) -> f32 {
+        (self.cell_width / 8.0).round().max(1.0)
+    }
+
+    /// Blend a hollow cursor (a cell-sized outline) into a rendered pane
+    pub fn draw_cursor_outline(&self, target: &mut PaneTarget, cursor: Point, color: [f32; 4]) {
+        let (w, h) = (self.cell_width as i32, self.cell_height as i32);
+        let border = self.cursor_outline_width() as i32;
+        self.blend_in_cell(target, cursor, color, |x, y| {
+            x < border || x >= w - border || y < border || y >= h - border
+        });
+    }
+
+    /// Blend a padlock into a cell (marks a password prompt, where typing isn't echoed)
+    pub fn draw_lock_icon(&self, target: &mut PaneTarget, cell: Point, color: [f32; 4]) {
+        self.blend_in_cell(target, cell, color, self.padlock());
+    }
+
+    /// Blend a cell-sized padlock whose top-right corner is at pixel (`right`, `top`)
+    /// (marks a pane input is locked to, on the window buffer)
+    pub fn draw_lock_badge(&self, target: &mut PaneTarget, right: i32, top: i32, color: [f32; 4]) {
+        let x = right - self.cell_width as i32;
+        self.blend_in_rect(target, x, top, color, self.padlock());
+    }
+
+    /// Padlock shape in cell-relative pixels
+    fn padlock(&self) -> impl Fn(i32, i32) -> bool {
+        let (w, h) = (self.cell_width, self.cell_height);
+        let stroke = self.cursor_outline_width() as i32;
+        // Body across the lower half, shackle an arch-like frame above it
+        let body = ((w * 0.15) as i32, (h * 0.45) as i32, (w * 0.85) as i32, (h * 0.85) as i32);
+        let shackle = ((w * 0.3) as i32, (h * 0.15) as i32, (w * 0.7) as i32, body.1);
+        move |x, y| {
+            let in_body = x >= body.0 && x < body.2 && y >= body.1 && y < body.3;
+            let in_shackle = x >= shackle.0 && x < shackle.2 && y >= shackle.1 && y < shackle.3;
+            let shackle_edge = x < shackle.0 + stroke || x >= shackle.2 - stroke || y < shackle.1 + stroke;
+            in_body || (in_shackle && shackle_edge)
+        }
+    }
+
+    /// Draw `text` faded and right-aligned on screen row `row` of a grid `columns` wide,
+    /// over the cells already there (finished commands' run times)
+    pub fn draw_annotation(
+        &self,
+        target: &mut PaneTarget,
+        row: usize,
+        columns: usize,
+        text: &str,
+        font_manager: &FontManager,
+        palette: &ColorPalette,
+    ) {
+        let first_column = columns.saturating_sub(text.chars().count());
+        let start = Point::new(Line(row as i32), Column(first_column));
+        self.draw_faded_text(target, start, text, font_manager, palette);
+    }
+
+    /// Draw `text` faded from cell `start` on, over the cells already there
+    /// (annotations, and the rest of an autosuggested command after the cursor)
+    pub fn draw_faded_text(
+        &self,
+        target: &mut PaneTarget,
+        start: Point,
+        text: &str,
+        font_manager: &FontManager,
+        palette: &ColorPalette,
+    ) {
+        let is_bgra = target.is_bgra();
+        let [bg_r, bg_g, bg_b, _] = palette.background;
+        let [fg_r, fg_g, fg_b, _] = palette.foreground;
+        let faded = |fg: f32, bg: f32| ((fg + bg) * 0.5 * 255.0) as u8;
+
+        let baseline_y = self.padding.top + start.line.0 as f32 * self.cell_height + self.baseline_offset;
+        for (column, c) in (start.column.0..).zip(text.chars()) {
+            let (metrics, bitmap) = match self.antialias {
+                Antialias::Grayscale => font_manager.rasterize(c),
+                Antialias::Subpixel => font_manager.rasterize_subpixel(c),
+            };
+            self.draw_glyph(
+                target.buffer,
+                &bitmap,
+                &metrics,
+                self.padding.left + column as f32 * self.cell_width,
+                baseline_y - (metrics.height as f32 + metrics.ymin as f32),
+                faded(fg_r, bg_r),
+                faded(fg_g, bg_g),
+                faded(fg_b, bg_b),
+                target.width,
+                target.height,
+                is_bgra,
+                true,
+            );
+        }
+    }
+
+    /// Draw a character typed ahead of its echo: faded, underlined, over whatever the cell held
+    pub fn draw_prediction(
+        &self,
+        target: &mut PaneTarget,
+        cell: Point,
+        c: char,
+        font_manager: &FontManager,
+        palette: &ColorPalette,
+    ) {
+        let is_bgra = target.is_bgra();
+        let [bg_r, bg_g, bg_b, _] = palette.background;
+        let [fg_r, fg_g, fg_b, _] = palette.foreground;
+        self.blend_in_cell(target, cell, [bg_r, bg_g, bg_b, 1.0], |_, _| true);
+
+        // Halfway between foreground and background
+        let faded = |fg: f32, bg: f32| ((fg + bg) * 0.5 * 255.0) as u8;
+        let (metrics, bitmap) = match self.antialias {
+            Antialias::Grayscale => font_manager.rasterize(c),
+            Antialias::Subpixel => font_manager.rasterize_subpixel(c),
+        };
+        let cell_x = self.padding.left + cell.column.0 as f32 * self.cell_width;
+        let baseline_y = self.padding.top + cell.line.0 as f32 * self.cell_height + self.baseline_offset;
+        self.draw_glyph(
+            target.buffer,
+            &bitmap,
+            &metrics,
+            cell_x,
+            baseline_y - (metrics.height as f32 + metrics.ymin as f32),
+            faded(fg_r, bg_r),
+            faded(fg_g, bg_g),
+            faded(fg_b, bg_b),
+            target.width,
+            target.height,
+            is_bgra,
+            false,
+        );
+
+        let h = self.cell_height as i32;
+        let stroke = self.cursor_outline_width() as i32;
+        self.blend_in_cell(target, cell, [fg_r, fg_g, fg_b, 0.5], |_, y| {
+            y >= h - stroke
+        });
+    }
+
+    /// Source-over `color` onto the pixels of a cell where `inside(x, y)` (cell-relative) holds
+    fn blend_in_cell(&self, target: &mut PaneTarget, cell: Point, color: [f32; 4], inside: impl Fn(i32, i32) -> bool) {
+        let x0 = (self.padding.left + cell.column.0 as f32 * self.cell_width) as i32;
+        let y0 = (self.padding.top + cell.line.0 as f32 * self.cell_height) as i32;
+        self.blend_in_rect(target, x0, y0, color, inside);
+    }
+
+    /// Source-over `color` onto the pixels of the cell-sized area at (`x0`, `y0`)
+    /// where `inside(x, y)` (area-relative) holds
+    fn blend_in_rect(
+        &self,
+        target: &mut PaneTarget,
+        x0: i32,
+        y0: i32,
+        color: [f32; 4],
+        inside: impl Fn(i32, i32) -> bool,
+    ) {
+        let (width, height) = (target.width, target.height);
+        let is_bgra = target.is_bgra();
+        let buffer = &mut *target.buffer;
+        let rgb = [color[0] * 255.0, color[1] * 255.0, color[2] * 255.0];
+        let rgb = if is_bgra { [rgb[2], rgb[1], rgb[0]] } else { rgb };
+        let alpha = color[3];
+
+        let (x1, y1) = (x0 + self.cell_width as i32, y0 + self.cell_height as i32);
+        for py in y0.max(0)..y1.min(height as i32) {
+            for px in x0.max(0)..x1.min(width as i32) {
+                if !inside(px - x0, py - y0) {
+                    continue;
+                }
+                // Source-over on premultiplied pixels
+                let idx = (py as usize * width as usize + px as usize) * 4;
+                for channel in 0..3 {
+                    let dst = buffer[idx + channel] as f32;
+                    buffer[idx + channel] = (rgb[channel] * alpha + dst * (1.0 - alpha)) as u8;
+                }
+                let dst_alpha = buffer[idx + 3] as f32;
+                buffer[idx + 3] = (255.0 * alpha + dst_alpha * (1.0 - alpha)) as u8;
+            }
+        }
+    }
+
+    /// Draw a single glyph to the buffer
+    /// With `blend` it is composited onto the pixels already there (a painted cell
+    /// background) instead of replacing them
+    fn draw_glyph(
+        &self,
+        buffer: &mut [u8],
+        bitmap: &[u8],
+        metrics: &fontdue::Metrics,
+        glyph_x: f32,
+        glyph_y: f32,
+        fg_r: u8,
+        fg_g: u8,
+        fg_b: u8,
+        width: u32,
+        height: u32,
+        is_bgra: bool,
+        blend: bool,
+    ) {
+        for gy in 0..metrics.height {
+            for gx in 0..metrics.width {
+                let px = glyph_x as i32 + gx as i32;
+                let py = glyph_y as i32 + gy as i32;
+
+                // Bounds check
+                if px >= 0 && py >= 0 && px < width as i32 && py < height as i32 {
+                    let glyph_idx = gy * metrics.width + gx;
+                    let (cov_r, cov_g, cov_b) = match self.antialias {
+                        Antialias::Grayscale => {
+                            let coverage = bitmap[glyph_idx];
+                            (coverage, coverage, coverage)
+                        }
+                        Antialias::Subpixel => {
+                            let rgb = &bitmap[glyph_idx * 3..glyph_idx * 3 + 3];
+                            (rgb[0], rgb[1], rgb[2])
+                        }
+                    };
+                    let coverage = cov_r.max(cov_g).max(cov_b);
+
+                    if coverage > 0 {
+                        let buffer_idx = ((py as usize * width as usize) + px as usize) * 4;
+
+                        // Premultiply each color channel by its own coverage for correct blending
+                        // (identical for grayscale, per-subpixel for LCD antialiasing)
+                        let fg_r_pre = (fg_r as f32 * cov_r as f32 / 255.0) as u8;
+                        let fg_g_pre = (fg_g as f32 * cov_g as f32 / 255.0) as u8;
+                        let fg_b_pre = (fg_b as f32 * cov_b as f32 / 255.0) as u8;
+
+                        if blend {
+                            // Source-over, each channel by its own coverage
+                            let channels = if is_bgra {
+                                [(fg_b_pre, cov_b), (fg_g_pre, cov_g), (fg_r_pre, cov_r)]
+                            } else {
+                                [(fg_r_pre, cov_r), (fg_g_pre, cov_g), (fg_b_pre, cov_b)]
+                            };
+                            for (channel, (src, cov)) in channels.into_iter().enumerate() {
+                                let dst = buffer[buffer_idx + channel] as f32;
+                                buffer[buffer_idx + channel] = (src as f32 + dst * (1.0 - cov as f32 / 255.0)) as u8;
+                            }
+                            let dst_alpha = buffer[buffer_idx + 3] as f32;
+                            buffer[buffer_idx + 3] = (coverage as f32 + dst_alpha * (1.0 - coverage as f32 / 255.0)) as u8;
+                            continue;
+                        }
+
+                        // Write in correct channel order (BGRA or RGBA)
+                        if is_bgra {
+                            buffer[buffer_idx] = fg_b_pre;     // B (premultiplied)
+                            buffer[buffer_idx + 1] = fg_g_pre; // G (premultiplied)
+                            buffer[buffer_idx + 2] = fg_r_pre; // R (premultiplied)
+                            buffer[buffer_idx + 3] = coverage; // A
+                        } else {
+                            buffer[buffer_idx] = fg_r_pre;     // R (premultiplied)
+                            buffer[buffer_idx + 1] = fg_g_pre; // G (premultiplied)
+                            buffer[buffer_idx + 2] = fg_b_pre; // B (premultiplied)
+                            buffer[buffer_idx + 3] = coverage; // A
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+
+}
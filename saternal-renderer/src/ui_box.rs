@@ -0,0 +1,49 @@
+//! Boxes of text laid over a corner of the window (the stats HUD)
+//!
+//! A box is sized in whole cells: one row per line, as wide as the longest
+//! one plus the text margins. `TextRasterizer::render_box` draws it.
+
+/// Gap in pixels between a box and the window edges
+const UI_BOX_INSET: u32 = 8;
+
+/// Lines of text in a box against the window's top-right corner
+#[derive(Debug, Clone, PartialEq)]
+pub struct UiBox {
+    pub lines: Vec<String>,
+}
+
+impl UiBox {
+    pub fn new(lines: Vec<String>) -> Self {
+        Self { lines }
+    }
+
+    /// Pixel rect (x, y, width, height) of the box in a `frame`-sized window for
+    /// `cell`-sized text with `margin` pixels beside it, cut down to fit; None
+    /// when there's no room or no text
+    pub fn rect(&self, cell: (f32, f32), margin: f32, frame: (u32, u32)) -> Option<(u32, u32, u32, u32)> {
+        let columns = self.lines.iter().map(|line| line.chars().count()).max()?;
+        let width = ((columns as f32 * cell.0 + 2.0 * margin).ceil() as u32).min(frame.0.saturating_sub(2 * UI_BOX_INSET));
+        let height = (self.lines.len() as u32 * cell.1.ceil() as u32).min(frame.1.saturating_sub(2 * UI_BOX_INSET));
+        if width == 0 || height == 0 {
+            return None;
+        }
+        Some((frame.0 - UI_BOX_INSET - width, UI_BOX_INSET, width, height))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_box_sits_in_the_top_right_corner() {
+        let hud = UiBox::new(vec!["60 fps".to_string(), "pty 1.5 KB/s".to_string()]);
+        // 12 characters plus the margins, two rows of whole pixels
+        assert_eq!(hud.rect((8.0, 15.5), 10.0, (800, 600)), Some((800 - 8 - 116, 8, 116, 32)));
+
+        // Cut down to a narrow window, gone when there's no room at all
+        assert_eq!(hud.rect((8.0, 16.0), 10.0, (100, 600)), Some((8, 8, 84, 32)));
+        assert_eq!(hud.rect((8.0, 16.0), 10.0, (16, 600)), None);
+        assert_eq!(UiBox::new(Vec::new()).rect((8.0, 16.0), 10.0, (800, 600)), None);
+    }
+}
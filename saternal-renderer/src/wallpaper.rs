@@ -1,24 +1,62 @@
+use super::blur::{blur_sigma, BlurRenderer};
 use anyhow::{Context, Result};
+use saternal_core::wallpaper::{WallpaperMode, WallpaperUv};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
 use std::path::Path;
 use wgpu;
 
+/// Maximum number of pre-scaled wallpaper variants kept in memory
+/// (enough for a laptop panel plus a couple of external monitors)
+const MAX_CACHED_VARIANTS: usize = 4;
+
+/// Format of wallpaper textures (and of the blur targets that replace them)
+const WALLPAPER_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+/// Maximum number of decoded wallpaper images kept for switching between tabs
+const MAX_CACHED_SOURCES: usize = 4;
+
+/// Key for a cached wallpaper variant: source image, visible part, and texture size
+/// (blur is applied on the GPU, so it isn't part of the key)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct VariantKey {
+    source: u64,
+    crop: (u32, u32, u32, u32),
+    width: u32,
+    height: u32,
+}
+
 /// Manages wallpaper texture and GPU resources
 ///
 /// This module handles:
 /// - Loading images from disk (PNG, JPG, WEBP)
 /// - Creating GPU textures and bind groups
 /// - Providing a dummy fallback texture when no wallpaper is set
-/// - Applying CPU-based blur to wallpaper images
+/// - Blurring wallpaper images on the GPU (two-pass Gaussian)
+/// - Caching variants pre-scaled to each monitor's physical resolution
 pub struct WallpaperManager {
     texture: wgpu::Texture,
     view: wgpu::TextureView,
     sampler: wgpu::Sampler,
     bind_group: wgpu::BindGroup,
     bind_group_layout: wgpu::BindGroupLayout,
+    blur_renderer: BlurRenderer,
     has_wallpaper: bool,
     // Store original image data for re-blurring
     original_image: Option<image::RgbaImage>,
+    // Path the current wallpaper was loaded from
+    path: Option<String>,
+    // Recently decoded images by path, most recently used at the back
+    sources: VecDeque<(String, image::RgbaImage)>,
     current_blur_strength: f32,
+    mode: WallpaperMode,
+    // Surface UV -> wallpaper UV for the current mode and size
+    uv: WallpaperUv,
+    // Physical size of the surface the wallpaper is drawn into
+    target_size: Option<(u32, u32)>,
+    // Scaled + blurred variants, most recently used at the back
+    variants: VecDeque<(VariantKey, image::RgbaImage)>,
 }
 
 impl WallpaperManager {
@@ -50,14 +88,39 @@ impl WallpaperManager {
             sampler,
             bind_group,
             bind_group_layout,
+            blur_renderer: BlurRenderer::new(device, WALLPAPER_FORMAT),
             has_wallpaper: false,
             original_image: None,
+            path: None,
+            sources: VecDeque::new(),
             current_blur_strength: 0.0,
+            mode: WallpaperMode::default(),
+            uv: WallpaperUv::default(),
+            target_size: None,
+            variants: VecDeque::new(),
         }
     }
 
     /// Load a wallpaper image from a file path
+    /// Recently used images are kept decoded, so switching back to one is cheap
     pub fn load(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, path: &str) -> Result<()> {
+        self.preload(path)?;
+        let (_, image) = self.sources.back().expect("preload caches the image");
+        self.original_image = Some(image.clone());
+        self.path = Some(path.to_string());
+        self.has_wallpaper = true;
+        self.refresh_texture(device, queue)
+    }
+
+    /// Decode a wallpaper image into the cache of recent sources without showing it
+    pub fn preload(&mut self, path: &str) -> Result<()> {
+        if let Some(idx) = self.sources.iter().position(|(p, _)| p == path) {
+            log::debug!("Using cached wallpaper source: {}", path);
+            let entry = self.sources.remove(idx).expect("index in range");
+            self.sources.push_back(entry);
+            return Ok(());
+        }
+
         // Expand tilde to home directory
         let expanded_path = if path == "~" {
             // Exact "~" - return HOME directory
@@ -86,7 +149,7 @@ impl WallpaperManager {
         let img = image::open(Path::new(&expanded_path))
             .context(format!("Failed to open wallpaper image: {}", expanded_path))?;
 
-        // Convert to RGBA8 and store original
+        // Convert to RGBA8 and keep it as the source for every variant
         let original_rgba = img.to_rgba8();
         let dimensions = original_rgba.dimensions();
 
@@ -97,104 +160,11 @@ impl WallpaperManager {
             original_rgba.len()
         );
 
-        // Apply blur if current blur strength > 0
-        let rgba = if self.current_blur_strength > 0.0 {
-            log::info!("Applying initial blur with strength: {}", self.current_blur_strength);
-            Self::apply_box_blur(&original_rgba, self.current_blur_strength)
-        } else {
-            original_rgba.clone()
-        };
-
-        // Create texture
-        let size = wgpu::Extent3d {
-            width: dimensions.0,
-            height: dimensions.1,
-            depth_or_array_layers: 1,
-        };
-
-        let texture = device.create_texture(&wgpu::TextureDescriptor {
-            label: Some("Wallpaper Texture"),
-            size,
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Rgba8UnormSrgb,
-            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
-            view_formats: &[],
-        });
-
-        // Upload image data to GPU with proper alignment
-        // wgpu requires bytes_per_row to be aligned to COPY_BYTES_PER_ROW_ALIGNMENT (256 bytes)
-        const ALIGNMENT: u32 = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
-        let unpadded_bytes_per_row = 4 * dimensions.0;
-        let padded_bytes_per_row = (unpadded_bytes_per_row + ALIGNMENT - 1) / ALIGNMENT * ALIGNMENT;
-
-        if unpadded_bytes_per_row == padded_bytes_per_row {
-            // No padding needed - image width naturally aligns
-            queue.write_texture(
-                wgpu::ImageCopyTexture {
-                    texture: &texture,
-                    mip_level: 0,
-                    origin: wgpu::Origin3d::ZERO,
-                    aspect: wgpu::TextureAspect::All,
-                },
-                &rgba,
-                wgpu::ImageDataLayout {
-                    offset: 0,
-                    bytes_per_row: Some(unpadded_bytes_per_row),
-                    rows_per_image: Some(dimensions.1),
-                },
-                size,
-            );
-        } else {
-            // Padding required - create aligned buffer
-            let padded_size = (padded_bytes_per_row * dimensions.1) as usize;
-            let mut padded_data = vec![0u8; padded_size];
-            let rgba_bytes = rgba.as_raw();
-
-            // Copy each row with padding
-            for y in 0..dimensions.1 {
-                let src_offset = (y * unpadded_bytes_per_row) as usize;
-                let dst_offset = (y * padded_bytes_per_row) as usize;
-                padded_data[dst_offset..dst_offset + unpadded_bytes_per_row as usize]
-                    .copy_from_slice(&rgba_bytes[src_offset..src_offset + unpadded_bytes_per_row as usize]);
-            }
-
-            queue.write_texture(
-                wgpu::ImageCopyTexture {
-                    texture: &texture,
-                    mip_level: 0,
-                    origin: wgpu::Origin3d::ZERO,
-                    aspect: wgpu::TextureAspect::All,
-                },
-                &padded_data,
-                wgpu::ImageDataLayout {
-                    offset: 0,
-                    bytes_per_row: Some(padded_bytes_per_row),
-                    rows_per_image: Some(dimensions.1),
-                },
-                size,
-            );
+        // Variants are keyed by source, so other wallpapers' variants stay valid
+        if self.sources.len() >= MAX_CACHED_SOURCES {
+            self.sources.pop_front();
         }
-
-        // Create view
-        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
-
-        // Update bind group with new texture
-        self.bind_group = Self::create_bind_group(
-            device,
-            &self.bind_group_layout,
-            &view,
-            &self.sampler,
-        );
-
-        // Update state
-        self.texture = texture;
-        self.view = view;
-        self.has_wallpaper = true;
-        self.original_image = Some(original_rgba);
-
-        log::info!("Wallpaper loaded successfully");
+        self.sources.push_back((path.to_string(), original_rgba));
         Ok(())
     }
 
@@ -216,9 +186,11 @@ impl WallpaperManager {
         // Update state
         self.texture = texture;
         self.view = view;
+        // Decoded sources and variants stay cached for tabs that switch back;
+        // blur strength is a global setting and survives too
         self.has_wallpaper = false;
         self.original_image = None;
-        self.current_blur_strength = 0.0;
+        self.path = None;
 
         log::info!("Wallpaper cleared");
     }
@@ -228,6 +200,25 @@ impl WallpaperManager {
         self.has_wallpaper
     }
 
+    /// Set how the wallpaper is fitted to the window
+    pub fn set_mode(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, mode: WallpaperMode) -> Result<()> {
+        if self.mode == mode {
+            return Ok(());
+        }
+        self.mode = mode;
+        self.refresh_texture(device, queue)
+    }
+
+    /// Current UV transform for the shader
+    pub fn uv(&self) -> WallpaperUv {
+        self.uv
+    }
+
+    /// Path of the loaded wallpaper, if any
+    pub fn path(&self) -> Option<&str> {
+        self.path.as_deref()
+    }
+
     /// Get the bind group for rendering
     pub fn bind_group(&self) -> &wgpu::BindGroup {
         &self.bind_group
@@ -244,37 +235,144 @@ impl WallpaperManager {
         self.current_blur_strength = strength;
 
         // If no wallpaper loaded, just store the strength for when one is loaded
-        let Some(original) = &self.original_image else {
+        if self.original_image.is_none() {
             log::info!("Blur strength set to {} (no wallpaper loaded yet)", strength);
             return Ok(());
-        };
+        }
 
         log::info!("Applying blur with strength: {}", strength);
+        self.refresh_texture(device, queue)?;
 
-        // Apply blur to original image
-        let blurred = if strength > 0.0 {
-            Self::apply_box_blur(original, strength)
-        } else {
-            original.clone()
+        log::info!("Blur applied successfully");
+        Ok(())
+    }
+
+    /// Set the physical pixel size the wallpaper is displayed at
+    ///
+    /// Called on resize and monitor (scale factor) changes. The wallpaper is
+    /// resampled from the original decode once per texture size and cached, so
+    /// moving between monitors or re-toggling the window doesn't re-decode or
+    /// re-scale. Crops and texture sizes snap to coarse steps (see
+    /// `WallpaperMode::fit`), so a resize drag mostly just moves the UV.
+    pub fn set_target_size(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        width: u32,
+        height: u32,
+    ) -> Result<()> {
+        if width == 0 || height == 0 || self.target_size == Some((width, height)) {
+            return Ok(());
+        }
+
+        self.target_size = Some((width, height));
+
+        if self.original_image.is_some() {
+            self.refresh_texture(device, queue)?;
+        }
+        Ok(())
+    }
+
+    /// Rebuild the GPU texture from the cached variant for the current size/blur
+    fn refresh_texture(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) -> Result<()> {
+        let Some(original) = &self.original_image else {
+            return Ok(());
         };
 
-        // Upload blurred image to GPU
-        self.upload_image_to_texture(device, queue, &blurred)?;
+        let target = self.target_size.unwrap_or_else(|| original.dimensions());
+        let fit = self.mode.fit(original.dimensions(), target, device.limits().max_texture_dimension_2d);
+        let (width, height) = fit.size;
+        self.uv = fit.uv;
+        let key = VariantKey {
+            source: self.path.as_deref().map(source_id).unwrap_or_default(),
+            crop: fit.crop,
+            width,
+            height,
+        };
+
+        let variant = match self.variants.iter().position(|(k, _)| *k == key) {
+            Some(idx) => {
+                log::debug!("Using cached wallpaper variant {}x{}", width, height);
+                // Move to back (most recently used)
+                let entry = self.variants.remove(idx).expect("index in range");
+                self.variants.push_back(entry);
+                &self.variants.back().expect("just pushed").1
+            }
+            None => {
+                let scaled = Self::scale_to(original, fit.crop, width, height);
+                log::info!("Cached wallpaper variant {}x{}", width, height);
+
+                if self.variants.len() >= MAX_CACHED_VARIANTS {
+                    self.variants.pop_front();
+                }
+                self.variants.push_back((key, scaled));
+                &self.variants.back().expect("just pushed").1
+            }
+        };
+
+        let dimensions = variant.dimensions();
+        let mut texture = Self::create_texture(device, dimensions);
+        Self::upload_image_to_texture(queue, &texture, variant);
+
+        if self.current_blur_strength > 0.0 {
+            let sigma = blur_sigma(self.current_blur_strength);
+            log::info!("Blurring wallpaper on the GPU (sigma {}px)", sigma);
+            let source = texture.create_view(&wgpu::TextureViewDescriptor::default());
+            texture = self.blur_renderer.blur(device, queue, &source, dimensions, sigma);
+        }
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        self.bind_group = Self::create_bind_group(
+            device,
+            &self.bind_group_layout,
+            &view,
+            &self.sampler,
+        );
+        self.texture = texture;
+        self.view = view;
 
-        log::info!("Blur applied successfully");
         Ok(())
     }
 
-    /// Upload an RGBA image to the current texture
+    /// Crop the source image to the visible part and resample that to the texture size
+    /// (the same aspect, except in stretch mode)
+    fn scale_to(img: &image::RgbaImage, crop: (u32, u32, u32, u32), width: u32, height: u32) -> image::RgbaImage {
+        let (x, y, crop_w, crop_h) = crop;
+        let visible = image::imageops::crop_imm(img, x, y, crop_w, crop_h);
+        if (crop_w, crop_h) == (width, height) {
+            return visible.to_image();
+        }
+        image::imageops::resize(&*visible, width, height, image::imageops::FilterType::Triangle)
+    }
+
+    /// Create an empty wallpaper texture of the given size
+    fn create_texture(device: &wgpu::Device, dimensions: (u32, u32)) -> wgpu::Texture {
+        device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Wallpaper Texture"),
+            size: wgpu::Extent3d {
+                width: dimensions.0,
+                height: dimensions.1,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: WALLPAPER_FORMAT,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        })
+    }
+
+    /// Upload an RGBA image to a texture of matching size
     fn upload_image_to_texture(
-        &self,
-        device: &wgpu::Device,
         queue: &wgpu::Queue,
+        texture: &wgpu::Texture,
         rgba: &image::RgbaImage,
-    ) -> Result<()> {
+    ) {
         let dimensions = rgba.dimensions();
 
         // Upload with proper alignment
+        // wgpu requires bytes_per_row to be aligned to COPY_BYTES_PER_ROW_ALIGNMENT (256 bytes)
         const ALIGNMENT: u32 = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
         let unpadded_bytes_per_row = 4 * dimensions.0;
         let padded_bytes_per_row = (unpadded_bytes_per_row + ALIGNMENT - 1) / ALIGNMENT * ALIGNMENT;
@@ -286,10 +384,10 @@ impl WallpaperManager {
         };
 
         if unpadded_bytes_per_row == padded_bytes_per_row {
-            // No padding needed
+            // No padding needed - image width naturally aligns
             queue.write_texture(
                 wgpu::ImageCopyTexture {
-                    texture: &self.texture,
+                    texture,
                     mip_level: 0,
                     origin: wgpu::Origin3d::ZERO,
                     aspect: wgpu::TextureAspect::All,
@@ -303,7 +401,7 @@ impl WallpaperManager {
                 size,
             );
         } else {
-            // Padding required
+            // Padding required - create aligned buffer
             let padded_size = (padded_bytes_per_row * dimensions.1) as usize;
             let mut padded_data = vec![0u8; padded_size];
             let rgba_bytes = rgba.as_raw();
@@ -317,7 +415,7 @@ impl WallpaperManager {
 
             queue.write_texture(
                 wgpu::ImageCopyTexture {
-                    texture: &self.texture,
+                    texture,
                     mip_level: 0,
                     origin: wgpu::Origin3d::ZERO,
                     aspect: wgpu::TextureAspect::All,
@@ -331,25 +429,6 @@ impl WallpaperManager {
                 size,
             );
         }
-
-        Ok(())
-    }
-
-    /// Apply box blur to an image (simple, fast CPU blur)
-    /// strength: blur radius in pixels (0.0-10.0)
-    fn apply_box_blur(img: &image::RgbaImage, strength: f32) -> image::RgbaImage {
-        if strength <= 0.0 {
-            return img.clone();
-        }
-
-        // Convert strength to radius (1.0 = 2px radius, 10.0 = 20px radius)
-        let radius = (strength * 2.0).round() as u32;
-        let radius = radius.max(1);
-
-        log::info!("Applying box blur with radius: {}px", radius);
-
-        // Use image crate's built-in blur (Gaussian approximation via box blur)
-        image::imageops::blur(img, radius as f32)
     }
 
     /// Create bind group layout (shared by all wallpaper textures)
@@ -429,3 +508,10 @@ impl WallpaperManager {
         (texture, view)
     }
 }
+
+/// Cache key for a wallpaper source path
+fn source_id(path: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    path.hash(&mut hasher);
+    hasher.finish()
+}
@@ -0,0 +1,113 @@
+//! Rendered-frame snapshots of scripted terminal sessions
+//!
+//! Each session is parsed into a terminal and drawn offscreen the way the
+//! window draws a pane (CPU rasterizer, then the app's text pipeline) with
+//! the bundled font, then compared with
+//! `tests/golden/<name>.png`. Different GPUs antialias slightly differently,
+//! so a few pixels may be off by a little. Set SATERNAL_UPDATE_GOLDEN=1 to
+//! write new golden images. Skipped when no GPU adapter (hardware or
+//! software) is available.
+use alacritty_terminal::event::VoidListener;
+use alacritty_terminal::term::{test::TermSize, Config, Term};
+use alacritty_terminal::vte::ansi::Processor;
+use image::RgbaImage;
+use saternal_core::FontManager;
+use saternal_renderer::HeadlessRenderer;
+use std::path::PathBuf;
+
+const FRAME_WIDTH: u32 = 480;
+const FRAME_HEIGHT: u32 = 160;
+const FONT_SIZE: f32 = 14.0;
+
+/// Largest per-channel difference that still counts as the same pixel
+const CHANNEL_TOLERANCE: u8 = 24;
+
+/// Share of pixels allowed past CHANNEL_TOLERANCE
+const MAX_DIFFERING_PIXELS: f64 = 0.005;
+
+fn golden_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden").join(format!("{}.png", name))
+}
+
+/// Draw `session` and compare it with (or record) its golden image
+fn assert_snapshot(name: &str, session: &[u8]) {
+    let font = FontManager::bundled(FONT_SIZE).unwrap();
+    let mut renderer = HeadlessRenderer::with_font(font, FRAME_WIDTH, FRAME_HEIGHT);
+    if let Err(e) = renderer.enable_gpu() {
+        eprintln!("Skipping snapshot {}: {}", name, e);
+        return;
+    }
+
+    let (cols, rows) = renderer.grid_size();
+    let mut term = Term::new(Config::default(), &TermSize::new(cols, rows), VoidListener);
+    let mut processor: Processor = Processor::new();
+    processor.advance(&mut term, session);
+    let frame = renderer.render_frame(&term).unwrap();
+
+    let golden = golden_path(name);
+    if std::env::var_os("SATERNAL_UPDATE_GOLDEN").is_some() {
+        frame.save(&golden).unwrap();
+        return;
+    }
+    let expected = image::open(&golden)
+        .unwrap_or_else(|e| panic!("{}: {} (SATERNAL_UPDATE_GOLDEN=1 records it)", golden.display(), e))
+        .to_rgba8();
+
+    let differing = differing_pixels(&frame, &expected);
+    let allowed = (FRAME_WIDTH * FRAME_HEIGHT) as f64 * MAX_DIFFERING_PIXELS;
+    if differing as f64 > allowed {
+        let actual = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(format!("{}.actual.png", name));
+        frame.save(&actual).unwrap();
+        panic!(
+            "{} differs from {} in {} pixels (allowed {:.0}); frame saved to {}",
+            name,
+            golden.display(),
+            differing,
+            allowed,
+            actual.display()
+        );
+    }
+}
+
+/// Pixels where any channel is off by more than CHANNEL_TOLERANCE (all of them on a size mismatch)
+fn differing_pixels(actual: &RgbaImage, expected: &RgbaImage) -> usize {
+    if actual.dimensions() != expected.dimensions() {
+        return (actual.width() * actual.height()) as usize;
+    }
+    actual
+        .pixels()
+        .zip(expected.pixels())
+        .filter(|(a, e)| a.0.iter().zip(e.0.iter()).any(|(a, e)| a.abs_diff(*e) > CHANNEL_TOLERANCE))
+        .count()
+}
+
+#[test]
+fn snapshot_shell_session() {
+    assert_snapshot(
+        "shell_session",
+        b"\x1b[1;32muser@host\x1b[0m:\x1b[1;34m~/src\x1b[0m$ ls\r\n\
+          \x1b[1;34mbenches\x1b[0m  Cargo.toml  \x1b[1;34msrc\x1b[0m  \x1b[1;34mtests\x1b[0m\r\n\
+          \x1b[1;32muser@host\x1b[0m:\x1b[1;34m~/src\x1b[0m$ echo done\r\n\
+          done\r\n\
+          \x1b[1;32muser@host\x1b[0m:\x1b[1;34m~/src\x1b[0m$ ",
+    );
+}
+
+#[test]
+fn snapshot_colors_and_backgrounds() {
+    let mut session = Vec::new();
+    // The 16 ANSI colors as text, then as backgrounds, then a truecolor ramp
+    for color in 0..16 {
+        session.extend_from_slice(format!("\x1b[38;5;{}m#{:<2}", color, color).as_bytes());
+    }
+    session.extend_from_slice(b"\x1b[0m\r\n");
+    for color in 0..16 {
+        session.extend_from_slice(format!("\x1b[48;5;{}m   ", color).as_bytes());
+    }
+    session.extend_from_slice(b"\x1b[0m\r\n");
+    for step in 0..48 {
+        session.extend_from_slice(format!("\x1b[48;2;{};64;{}m ", step * 5, 255 - step * 5).as_bytes());
+    }
+    session.extend_from_slice(b"\x1b[0m");
+    assert_snapshot("colors_and_backgrounds", &session);
+}
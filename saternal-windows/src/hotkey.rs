@@ -0,0 +1,112 @@
+use anyhow::Result;
+use global_hotkey::{
+    hotkey::{Code, HotKey, Modifiers},
+    GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState,
+};
+use log::info;
+use parking_lot::Mutex;
+use saternal_core::GlobalHotkeys;
+use std::sync::Arc;
+
+type HotkeyCallback = Arc<Mutex<Box<dyn FnMut() + Send + 'static>>>;
+
+/// Manages global hotkey registration and events
+pub struct HotkeyManager {
+    manager: GlobalHotKeyManager,
+    hotkey: HotKey,
+    callback: HotkeyCallback,
+    /// Win+Shift+` - toggles click-through glass mode (see `register_glass_toggle`)
+    glass_toggle: Option<(HotKey, HotkeyCallback)>,
+}
+
+impl HotkeyManager {
+    /// Create a new hotkey manager with Win+` (backtick), the same chord as
+    /// Windows Terminal's quake mode
+    /// WM_HOTKEY arrives on this thread's message queue, so create it on the event loop's thread
+    pub fn new<F>(callback: F) -> Result<Self>
+    where
+        F: FnMut() + Send + 'static,
+    {
+        info!("Initializing global hotkey manager");
+
+        let manager = GlobalHotKeyManager::new()
+            .map_err(|e| anyhow::anyhow!("Failed to create hotkey manager: {}", e))?;
+
+        // SUPER is MOD_WIN - the Windows logo key
+        let hotkey = HotKey::new(Some(Modifiers::SUPER), Code::Backquote);
+
+        manager
+            .register(hotkey)
+            .map_err(|e| anyhow::anyhow!("Failed to register hotkey: {}", e))?;
+
+        info!("Registered global hotkey: Win+`");
+
+        Ok(Self {
+            manager,
+            hotkey,
+            callback: Arc::new(Mutex::new(Box::new(callback))),
+            glass_toggle: None,
+        })
+    }
+
+    /// Register Win+Shift+` to toggle glass mode
+    /// It has to be global: a click-through window can't be clicked to get focus back
+    pub fn register_glass_toggle<F>(&mut self, callback: F) -> Result<()>
+    where
+        F: FnMut() + Send + 'static,
+    {
+        let hotkey = HotKey::new(Some(Modifiers::SUPER | Modifiers::SHIFT), Code::Backquote);
+        self.manager
+            .register(hotkey)
+            .map_err(|e| anyhow::anyhow!("Failed to register glass mode hotkey: {}", e))?;
+        info!("Registered global hotkey: Win+Shift+` (glass mode)");
+
+        self.glass_toggle = Some((hotkey, Arc::new(Mutex::new(Box::new(callback)))));
+        Ok(())
+    }
+
+    /// Unregister the hotkey
+    pub fn unregister(&self) -> Result<()> {
+        if let Some((hotkey, _)) = &self.glass_toggle {
+            self.manager
+                .unregister(*hotkey)
+                .map_err(|e| anyhow::anyhow!("Failed to unregister glass mode hotkey: {}", e))?;
+        }
+        self.manager
+            .unregister(self.hotkey)
+            .map_err(|e| anyhow::anyhow!("Failed to unregister hotkey: {}", e))
+    }
+}
+
+impl GlobalHotkeys for HotkeyManager {
+    fn process_events(&self) {
+        // Process ALL pending events to avoid queue buildup
+        while let Ok(event) = GlobalHotKeyEvent::receiver().try_recv() {
+            // Only trigger on key DOWN events to avoid repeated triggers
+            if event.state != HotKeyState::Pressed {
+                continue;
+            }
+            if event.id == self.hotkey.id() {
+                let mut callback = self.callback.lock();
+                callback();
+            } else if let Some((hotkey, callback)) = &self.glass_toggle {
+                if event.id == hotkey.id() {
+                    let mut callback = callback.lock();
+                    callback();
+                }
+            }
+        }
+    }
+
+    fn trigger_toggle(&self) {
+        let mut callback = self.callback.lock();
+        callback();
+    }
+}
+
+impl Drop for HotkeyManager {
+    fn drop(&mut self) {
+        let _ = self.unregister();
+    }
+}
+
@@ -0,0 +1,32 @@
+//! Win32 side of the platform traits in `saternal_core::platform`
+//!
+//! The dropdown is a borderless top-most winit window that slides down from
+//! the top of the screen under the pointer. Hotkeys are `RegisterHotKey`
+//! grabs, delivered to the event loop's thread like AppKit's on macOS. The
+//! shell itself runs on a pseudo console (`saternal_core::conpty`).
+#![cfg(target_os = "windows")]
+
+pub mod hotkey;
+pub mod notification;
+pub mod sound;
+pub mod window;
+mod win32;
+
+pub use hotkey::HotkeyManager;
+pub use notification::{notify, show_crash_report};
+pub use sound::play_bell;
+pub use window::DropdownWindow;
+
+/// Point $HOME at the user's profile folder when it isn't set
+///
+/// The config, history, and log paths all hang off $HOME, which Windows
+/// only has when something like Git for Windows set it. Call this before
+/// any other threads start.
+pub fn set_home_from_profile() {
+    if std::env::var_os("HOME").is_some() {
+        return;
+    }
+    if let Some(profile) = std::env::var_os("USERPROFILE") {
+        std::env::set_var("HOME", profile);
+    }
+}
@@ -0,0 +1,98 @@
+use std::ffi::OsStr;
+use std::os::windows::ffi::OsStrExt;
+use std::os::windows::process::CommandExt;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use windows_sys::Win32::UI::WindowsAndMessaging::{MessageBoxW, IDYES, MB_ICONWARNING, MB_SETFOREGROUND, MB_YESNO};
+
+/// CREATE_NO_WINDOW: don't flash a console window for the helper process
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+/// PowerShell's app id, which is registered for toasts on every install
+const POWERSHELL_APP_ID: &str = r"{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\WindowsPowerShell\v1.0\powershell.exe";
+
+/// Post a toast notification
+///
+/// Goes through PowerShell and the WinRT toast API: an unpackaged app has no
+/// app id of its own to post toasts under, so they go out as PowerShell's.
+pub fn notify(title: &str, body: &str) {
+    let script = format!(
+        "[Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] > $null; \
+         $xml = [Windows.UI.Notifications.ToastNotificationManager]::GetTemplateContent([Windows.UI.Notifications.ToastTemplateType]::ToastText02); \
+         $text = $xml.GetElementsByTagName('text'); \
+         $text.Item(0).AppendChild($xml.CreateTextNode({})) > $null; \
+         $text.Item(1).AppendChild($xml.CreateTextNode({})) > $null; \
+         [Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier({}).Show([Windows.UI.Notifications.ToastNotification]::new($xml))",
+        powershell_quote(title),
+        powershell_quote(body),
+        powershell_quote(POWERSHELL_APP_ID)
+    );
+    let spawned = Command::new("powershell.exe")
+        .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+        .creation_flags(CREATE_NO_WINDOW)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    match spawned {
+        // Reap it off the main thread
+        Ok(mut child) => {
+            std::thread::spawn(move || child.wait());
+        }
+        Err(e) => log::warn!("Failed to post notification: {}", e),
+    }
+}
+
+/// Tell the user the last run crashed, offering to reveal `report` in Explorer
+///
+/// The message box runs off the main thread, so startup doesn't wait on it.
+pub fn show_crash_report(report: &Path) {
+    let text = wide(&format!(
+        "A crash report was saved to {}\n\nShow it in Explorer?",
+        report.display()
+    ));
+    let caption = wide("Saternal quit unexpectedly");
+    let report = report.to_path_buf();
+    std::thread::spawn(move || {
+        let answer = unsafe {
+            MessageBoxW(0, text.as_ptr(), caption.as_ptr(), MB_YESNO | MB_ICONWARNING | MB_SETFOREGROUND)
+        };
+        if answer == IDYES {
+            // Explorer wants "/select," and the path as one argument
+            let mut select = std::ffi::OsString::from("/select,");
+            select.push(&report);
+            let _ = Command::new("explorer.exe").arg(select).status();
+        }
+    });
+}
+
+/// A single-quoted PowerShell string literal
+/// PowerShell takes typographic single quotes as quotes too; any of them is escaped by doubling
+fn powershell_quote(text: &str) -> String {
+    let mut quoted = String::from("'");
+    for c in text.chars() {
+        if matches!(c, '\'' | '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}') {
+            quoted.push(c);
+        }
+        quoted.push(c);
+    }
+    quoted.push('\'');
+    quoted
+}
+
+/// Null-terminated UTF-16
+fn wide(text: &str) -> Vec<u16> {
+    OsStr::new(text).encode_wide().chain(Some(0)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_powershell_quote() {
+        assert_eq!(powershell_quote("build done"), "'build done'");
+        assert_eq!(powershell_quote("it's done; rm -r ~"), "'it''s done; rm -r ~'");
+        assert_eq!(powershell_quote("it\u{2019}s"), "'it\u{2019}\u{2019}s'");
+    }
+}
@@ -0,0 +1,9 @@
+use windows_sys::Win32::System::Diagnostics::Debug::MessageBeep;
+use windows_sys::Win32::UI::WindowsAndMessaging::MB_OK;
+
+/// Play the user's default beep (Settings > System > Sound)
+pub fn play_bell() {
+    unsafe {
+        MessageBeep(MB_OK);
+    }
+}
@@ -0,0 +1,49 @@
+use windows_sys::Win32::Foundation::{HWND, POINT};
+use windows_sys::Win32::UI::WindowsAndMessaging::{
+    GetCursorPos, GetWindowLongW, SetLayeredWindowAttributes, SetWindowLongW, SetWindowPos, GWL_EXSTYLE,
+    LWA_ALPHA, SWP_ASYNCWINDOWPOS, SWP_NOACTIVATE, SWP_NOSIZE, SWP_NOZORDER, WS_EX_LAYERED, WS_EX_TRANSPARENT,
+};
+use winit::raw_window_handle::{HasWindowHandle, RawWindowHandle};
+use winit::window::Window;
+
+pub fn hwnd(window: &Window) -> Option<HWND> {
+    match window.window_handle().ok()?.as_raw() {
+        RawWindowHandle::Win32(handle) => Some(handle.hwnd.get()),
+        _ => None,
+    }
+}
+
+/// Pointer position in physical screen coordinates
+pub fn cursor_position() -> Option<(i32, i32)> {
+    let mut point = POINT { x: 0, y: 0 };
+    (unsafe { GetCursorPos(&mut point) } != 0).then_some((point.x, point.y))
+}
+
+/// Set the whole window's alpha, text included
+///
+/// Alpha needs the layered style, which winit drops again whenever it
+/// restyles the window (showing it, for one), so set this after those.
+/// An opaque window leaves the style off unless it's click-through, which
+/// needs it too.
+pub fn set_alpha(hwnd: HWND, alpha: f64) {
+    unsafe {
+        let style = GetWindowLongW(hwnd, GWL_EXSTYLE) as u32;
+        if alpha >= 1.0 && style & WS_EX_TRANSPARENT == 0 {
+            if style & WS_EX_LAYERED != 0 {
+                SetWindowLongW(hwnd, GWL_EXSTYLE, (style & !WS_EX_LAYERED) as i32);
+            }
+            return;
+        }
+        SetWindowLongW(hwnd, GWL_EXSTYLE, (style | WS_EX_LAYERED) as i32);
+        if SetLayeredWindowAttributes(hwnd, 0, (alpha * 255.0).round() as u8, LWA_ALPHA) == 0 {
+            log::warn!("Failed to set window opacity: {}", std::io::Error::last_os_error());
+        }
+    }
+}
+
+/// Move the window's top-left corner without waiting for the thread that owns it
+pub fn move_async(hwnd: HWND, x: i32, y: i32) {
+    unsafe {
+        SetWindowPos(hwnd, 0, x, y, 0, 0, SWP_NOSIZE | SWP_NOZORDER | SWP_NOACTIVATE | SWP_ASYNCWINDOWPOS);
+    }
+}
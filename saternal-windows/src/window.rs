@@ -0,0 +1,314 @@
+use crate::win32;
+use anyhow::Result;
+use log::info;
+use saternal_core::{DropdownSurface, WindowMode};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+use winit::monitor::MonitorHandle;
+use winit::platform::windows::WindowExtWindows;
+use winit::window::{Fullscreen, Window, WindowId, WindowLevel};
+
+/// Lowest window alpha - keeps the terminal findable on screen
+const MIN_WINDOW_OPACITY: f64 = 0.1;
+
+/// Time between two steps of the slide (about one frame at 120Hz)
+const SLIDE_STEP: Duration = Duration::from_millis(8);
+
+/// Manages the dropdown window behavior on Windows
+///
+/// Opening slides the window down from above the top edge; closing hides it
+/// right away, so focus goes straight back to the window below.
+pub struct DropdownWindow {
+    visible: bool,
+    animation_duration: f64,
+    opacity: f64,        // Window alpha while shown, text included (1.0 = opaque)
+    glass_opacity: f64,  // Window alpha in glass mode
+    glass: bool,         // Click-through glass mode: mouse events pass to the windows below
+    standard: bool,      // Regular decorated window instead of a dropdown
+    fullscreen_pending: bool,  // Go fullscreen the first time the window is shown
+    window_id: Option<WindowId>,  // The window configured as the dropdown
+    pinned: bool,        // Stay open when another window takes focus
+    slide: Arc<AtomicU64>,  // Bumped on every show and hide; a slide stops once it changes
+}
+
+impl DropdownWindow {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            animation_duration: 0.18, // 180ms
+            opacity: 1.0,
+            glass_opacity: 1.0,
+            glass: false,
+            standard: false,
+            fullscreen_pending: false,
+            window_id: None,
+            pinned: false,
+            slide: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// The monitor under the pointer (active screen)
+    /// Falls back to the window's monitor, then the primary one
+    fn monitor_with_pointer(window: &Window) -> Option<MonitorHandle> {
+        let pointer = win32::cursor_position();
+        let under_pointer = pointer.and_then(|(x, y)| {
+            window.available_monitors().find(|monitor| {
+                let position = monitor.position();
+                let size = monitor.size();
+                x >= position.x
+                    && x < position.x + size.width as i32
+                    && y >= position.y
+                    && y < position.y + size.height as i32
+            })
+        });
+        under_pointer.or_else(|| window.current_monitor()).or_else(|| window.primary_monitor())
+    }
+
+    /// Hang the dropdown from the top edge of `monitor`, `height` physical pixels tall
+    fn place(window: &Window, monitor: &MonitorHandle, height: u32) -> (u32, u32, f64) {
+        let width = monitor.size().width;
+        window.set_outer_position(monitor.position());
+        let _ = window.request_inner_size(PhysicalSize::new(width, height));
+        (width, height, monitor.scale_factor())
+    }
+
+    fn show(&mut self, window: &Window, reposition: bool) -> Option<(u32, u32, f64)> {
+        info!("Showing dropdown window (reposition: {})", reposition);
+        let dims = if reposition {
+            Self::monitor_with_pointer(window).map(|monitor| {
+                // Same logical height on the new screen
+                let logical_height = window.inner_size().height as f64 / window.scale_factor();
+                let height = (logical_height * monitor.scale_factor()).round() as u32;
+                Self::place(window, &monitor, height)
+            })
+        } else {
+            None
+        };
+
+        // Start just above the top edge, then slide into place
+        let top = window.outer_position().unwrap_or_default();
+        let height = window.outer_size().height as i32;
+        window.set_outer_position(PhysicalPosition::new(top.x, top.y - height));
+        window.set_visible(true);
+        window.focus_window();
+        self.apply_opacity(window);
+        self.slide_in(window, top);
+        dims
+    }
+
+    /// Move the window down to `top` over the animation duration, easing out
+    /// Steps run on a thread of their own, so the event loop keeps drawing
+    fn slide_in(&self, window: &Window, top: PhysicalPosition<i32>) {
+        let generation = self.slide.fetch_add(1, Ordering::AcqRel) + 1;
+        if self.animation_duration <= 0.0 {
+            window.set_outer_position(top);
+            return;
+        }
+
+        let Some(hwnd) = win32::hwnd(window) else {
+            window.set_outer_position(top);
+            return;
+        };
+        let slide = self.slide.clone();
+        let distance = window.outer_size().height as f64;
+        let duration = self.animation_duration;
+        let spawned = std::thread::Builder::new()
+            .name("saternal-slide".to_string())
+            .spawn(move || {
+                let start = Instant::now();
+                loop {
+                    std::thread::sleep(SLIDE_STEP);
+                    if slide.load(Ordering::Acquire) != generation {
+                        return;
+                    }
+                    let progress = (start.elapsed().as_secs_f64() / duration).min(1.0);
+                    let remaining = (1.0 - progress).powi(3);
+                    win32::move_async(hwnd, top.x, top.y - (distance * remaining).round() as i32);
+                    if progress >= 1.0 {
+                        return;
+                    }
+                }
+            });
+        if let Err(e) = spawned {
+            log::warn!("Failed to animate the dropdown: {}", e);
+            window.set_outer_position(top);
+        }
+    }
+
+    fn hide(&self, window: &Window) {
+        info!("Hiding dropdown window");
+        // Stop a slide that's still running
+        self.slide.fetch_add(1, Ordering::AcqRel);
+        window.set_visible(false);
+    }
+
+    fn toggle_standard(&mut self, window: &Window, show: bool) {
+        if show {
+            info!("Showing standard window");
+            window.set_visible(true);
+            window.focus_window();
+            self.apply_opacity(window);
+            if self.fullscreen_pending {
+                self.fullscreen_pending = false;
+                window.set_fullscreen(Some(Fullscreen::Borderless(None)));
+            }
+        } else {
+            info!("Hiding standard window");
+            window.set_visible(false);
+        }
+    }
+
+    fn is_dropdown(&self, window: &Window) -> bool {
+        !self.standard && self.window_id == Some(window.id())
+    }
+
+    pub fn is_glass(&self) -> bool {
+        self.glass
+    }
+
+    pub fn set_animation_duration(&mut self, duration: f64) {
+        self.animation_duration = duration;
+    }
+
+    fn target_alpha(&self) -> f64 {
+        if self.glass {
+            self.glass_opacity
+        } else {
+            self.opacity
+        }
+    }
+}
+
+impl DropdownSurface for DropdownWindow {
+    fn configure(
+        &mut self,
+        window: &Window,
+        mode: WindowMode,
+        width_percentage: f64,
+        height_percentage: f64,
+    ) -> Result<(u32, u32, f64)> {
+        let monitor = Self::monitor_with_pointer(window).ok_or_else(|| anyhow::anyhow!("No monitor to place the window on"))?;
+        let screen = monitor.size();
+
+        if mode == WindowMode::Dropdown {
+            self.window_id = Some(window.id());
+            window.set_decorations(false);
+            window.set_window_level(WindowLevel::AlwaysOnTop);
+            // Summoned by the hotkey, not picked from the taskbar
+            window.set_skip_taskbar(true);
+            let height = (screen.height as f64 * height_percentage).round() as u32;
+            let (width, height, scale_factor) = Self::place(window, &monitor, height);
+            info!("Configured dropdown window: {}x{} with scale factor {:.2}x", width, height, scale_factor);
+            return Ok((width, height, scale_factor));
+        }
+
+        let width = (screen.width as f64 * width_percentage).round() as u32;
+        let height = (screen.height as f64 * height_percentage).round() as u32;
+        let origin = monitor.position();
+        window.set_decorations(true);
+        window.set_window_level(WindowLevel::Normal);
+        window.set_skip_taskbar(false);
+        window.set_outer_position(PhysicalPosition::new(
+            origin.x + (screen.width.saturating_sub(width) / 2) as i32,
+            origin.y + (screen.height.saturating_sub(height) / 2) as i32,
+        ));
+        let _ = window.request_inner_size(PhysicalSize::new(width, height));
+        self.standard = true;
+        self.fullscreen_pending = mode == WindowMode::Fullscreen;
+
+        info!("Configured standard window: {}x{} (fullscreen: {}) with scale factor {:.2}x",
+              width, height, self.fullscreen_pending, monitor.scale_factor());
+        Ok((width, height, monitor.scale_factor()))
+    }
+
+    fn toggle(&mut self, window: &Window) -> Result<Option<(u32, u32, f64)>> {
+        let was_visible = self.visible;
+        self.visible = !self.visible;
+
+        if self.standard {
+            self.toggle_standard(window, self.visible);
+            return Ok(None);
+        }
+
+        if self.visible {
+            // Only move to the pointer's screen when opening, not when already shown
+            Ok(self.show(window, !was_visible))
+        } else {
+            self.hide(window);
+            Ok(None)
+        }
+    }
+
+    fn hide_on_focus_loss(&mut self, window: &Window) -> Result<bool> {
+        if self.pinned || self.glass || !self.visible || !self.is_dropdown(window) {
+            return Ok(false);
+        }
+        info!("Dropdown lost focus");
+        self.visible = false;
+        self.hide(window);
+        Ok(true)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn toggle_pin(&mut self) -> bool {
+        self.pinned = !self.pinned;
+        info!("Dropdown {}", if self.pinned { "pinned" } else { "unpinned" });
+        self.pinned
+    }
+
+    fn is_pinned(&self) -> bool {
+        self.pinned
+    }
+
+    fn set_opacity(&mut self, opacity: f64, glass_opacity: f64) {
+        self.opacity = opacity.clamp(MIN_WINDOW_OPACITY, 1.0);
+        self.glass_opacity = glass_opacity.clamp(MIN_WINDOW_OPACITY, 1.0);
+    }
+
+    fn apply_opacity(&self, window: &Window) {
+        if let Some(hwnd) = win32::hwnd(window).filter(|_| self.visible) {
+            win32::set_alpha(hwnd, self.target_alpha());
+        }
+    }
+
+    fn toggle_glass(&mut self, window: &Window) -> bool {
+        self.glass = !self.glass;
+        if let Err(e) = window.set_cursor_hittest(!self.glass) {
+            log::warn!("Failed to make the window click-through: {}", e);
+        }
+        self.apply_opacity(window);
+        info!("Glass mode {}", if self.glass { "on" } else { "off" });
+        self.glass
+    }
+
+    fn dropdown_screen(&self, window: &Window) -> Option<(f64, f64)> {
+        if !self.is_dropdown(window) {
+            return None;
+        }
+        let monitor = window.current_monitor()?;
+        let scale = monitor.scale_factor();
+        Some((monitor.size().height as f64 / scale, scale))
+    }
+
+    fn set_height(&self, window: &Window, height: f64) {
+        let size = window.inner_size();
+        let height = (height * window.scale_factor()).round() as u32;
+        if size.height == height {
+            return;
+        }
+        // Resizing keeps the top-left corner where it is
+        let _ = window.request_inner_size(PhysicalSize::new(size.width, height));
+        info!("Dropdown height snapped to {} pixels", height);
+    }
+}
+
+impl Default for DropdownWindow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,42 @@
+use log::info;
+use parking_lot::Mutex;
+use saternal_core::CommandHistory;
+use saternal_renderer::{Autosuggestion, Renderer};
+use std::sync::Arc;
+
+/// Fish-style suggestion for the focused pane: the rest of the most recent history
+/// command that starts with what's typed at its prompt
+pub(super) fn focused_suggestion(tab: &crate::tab::Tab, command_history: &CommandHistory) -> Option<Autosuggestion> {
+    let pane = tab.pane_tree.focused_pane()?;
+    let (cell, typed) = pane.terminal.prompt_input()?;
+    let command = command_history.suggest(&typed)?;
+    Some(Autosuggestion { pane_id: pane.id, cell, text: command[typed.len()..].to_string() })
+}
+
+/// Right Arrow at the end of the input: type the rest of the suggested command
+/// Returns false when there is nothing to accept, so the key goes to the shell
+pub(super) fn accept(
+    tab_manager: &Arc<Mutex<crate::tab::TabManager>>,
+    command_history: &CommandHistory,
+    renderer: &Arc<Mutex<Renderer>>,
+    window: &winit::window::Window,
+) -> bool {
+    if !renderer.lock().autosuggestions() {
+        return false;
+    }
+    let mut tab_mgr = tab_manager.lock();
+    let Some(suggestion) = tab_mgr.active_tab().and_then(|tab| focused_suggestion(tab, command_history)) else {
+        return false;
+    };
+    info!("Accepting autosuggestion ({} chars)", suggestion.text.len());
+    if let Err(e) = tab_mgr.type_text(&suggestion.text) {
+        log::error!("Failed to type autosuggestion: {}", e);
+    }
+    drop(tab_mgr);
+
+    let mut renderer = renderer.lock();
+    renderer.set_autosuggestion(None);
+    renderer.reset_scroll();
+    window.request_redraw();
+    true
+}
@@ -1,40 +1,61 @@
 use log::info;
 use parking_lot::Mutex;
-use saternal_core::{Clipboard, SelectionManager};
+use saternal_core::output_parser::TERM_LOCK_TIMEOUT;
+use crate::platform::Clipboard;
+use saternal_core::{ClipboardHistory, ClipboardProvider, SelectionManager};
 use std::sync::Arc;
 
 /// Handle copy operation (Cmd+C)
+/// A successful copy pulses the selection so it's visible what was taken
 pub(super) fn handle_copy(
     tab_manager: &Arc<Mutex<crate::tab::TabManager>>,
     selection_manager: &mut SelectionManager,
+    clipboard_history: &mut ClipboardHistory,
+    renderer: &Arc<Mutex<saternal_renderer::Renderer>>,
+    window: &winit::window::Window,
 ) {
+    let text = tab_manager.try_lock().and_then(|tab_mgr| {
+        let pane = tab_mgr.active_tab()?.pane_tree.focused_pane()?;
+        let term = pane.terminal.term();
+        let term_lock = term.try_lock_for(TERM_LOCK_TIMEOUT)?;
+        selection_manager.get_text(term_lock.grid())
+    });
+    if text.is_some_and(|text| copy_text(&text, clipboard_history)) {
+        renderer.lock().flash_selection();
+        window.request_redraw();
+    }
+}
+
+/// Put text on the clipboard and record it in the clipboard history
+/// Returns true if the clipboard took the text
+pub(super) fn copy_text(text: &str, clipboard_history: &mut ClipboardHistory) -> bool {
     let mut clipboard = match Clipboard::new() {
         Ok(cb) => cb,
         Err(e) => {
             log::error!("Failed to create clipboard: {}", e);
-            return;
+            return false;
         }
     };
 
-    if let Some(tab_mgr) = tab_manager.try_lock() {
-        if let Some(pane) = tab_mgr.active_tab().and_then(|tab| tab.pane_tree.focused_pane()) {
-            if let Some(term_lock) = pane.terminal.term().try_lock() {
-                if let Some(text) = selection_manager.get_text(&term_lock.grid()) {
-                    if let Err(e) = clipboard.set_text(&text) {
-                        log::error!("Failed to copy to clipboard: {}", e);
-                    } else {
-                        info!("Copied {} chars to clipboard", text.len());
-                    }
-                }
+    if let Err(e) = clipboard.set_text(text) {
+        log::error!("Failed to copy to clipboard: {}", e);
+        false
+    } else {
+        info!("Copied {} chars to clipboard", text.len());
+        if clipboard_history.push(text) {
+            if let Err(e) = clipboard_history.save() {
+                log::warn!("Failed to save clipboard history: {}", e);
             }
         }
+        true
     }
 }
 
-/// Handle paste operation (Cmd+V)
+/// Handle paste operation (Cmd+V, or Cmd+Alt+V to skip the shell example cleanup)
 pub(super) fn handle_paste(
+    verbatim: bool,
     tab_manager: &Arc<Mutex<crate::tab::TabManager>>,
-    renderer: &Arc<Mutex<saternal_core::Renderer>>,
+    renderer: &Arc<Mutex<saternal_renderer::Renderer>>,
     window: &winit::window::Window,
 ) {
     let mut clipboard = match Clipboard::new() {
@@ -47,17 +68,37 @@ pub(super) fn handle_paste(
 
     if let Ok(text) = clipboard.get_text() {
         info!("Pasting {} chars from clipboard", text.len());
-        let bytes = if saternal_core::clipboard::should_bracket_paste(&text) {
-            saternal_core::clipboard::bracket_paste(&text)
-        } else {
-            text.into_bytes()
-        };
-        
-        if let Some(active_tab) = tab_manager.lock().active_tab_mut() {
-            let _ = active_tab.write_input(&bytes);
+        let text = if verbatim { Some(text) } else { tab_manager.lock().clean_up_paste(&text) };
+        match text {
+            Some(text) => paste_text(&text, tab_manager, renderer, window),
+            None => window.request_redraw(),
         }
-        // Auto-scroll to bottom when user pastes text
-        renderer.lock().reset_scroll();
+    }
+}
+
+/// Send text to the focused pane as a paste
+pub(super) fn paste_text(
+    text: &str,
+    tab_manager: &Arc<Mutex<crate::tab::TabManager>>,
+    renderer: &Arc<Mutex<saternal_renderer::Renderer>>,
+    window: &winit::window::Window,
+) {
+    // Several lines into a password prompt would run all but the first as commands
+    if !tab_manager.lock().confirm_paste(text) {
         window.request_redraw();
+        return;
+    }
+
+    let bytes = if saternal_core::clipboard::should_bracket_paste(text) {
+        saternal_core::clipboard::bracket_paste(text)
+    } else {
+        text.as_bytes().to_vec()
+    };
+
+    if let Some(active_tab) = tab_manager.lock().active_tab_mut() {
+        let _ = active_tab.write_input(&bytes);
     }
+    // Auto-scroll to bottom when user pastes text
+    renderer.lock().reset_scroll();
+    window.request_redraw();
 }
@@ -0,0 +1,24 @@
+use saternal_core::config::CommandAlertConfig;
+use saternal_core::shell_integration::{format_duration, FinishedCommand};
+use winit::window::{UserAttentionType, Window};
+
+/// Alert about a long command that finished while its window was out of sight
+pub(super) fn command_finished(
+    finished: &FinishedCommand,
+    config: &CommandAlertConfig,
+    window: &Window,
+    out_of_sight: bool,
+) {
+    if !out_of_sight {
+        return;
+    }
+    if config.bounces(finished.duration) {
+        log::debug!("Command finished after {:?}, requesting attention", finished.duration);
+        // Bounces the Dock icon on macOS, sets the urgency hint on X11
+        window.request_user_attention(Some(UserAttentionType::Informational));
+    }
+    if config.notifies(finished.duration) {
+        let command = if finished.command.is_empty() { "Command" } else { finished.command.as_str() };
+        crate::platform::notify(&format!("Finished in {}", format_duration(finished.duration)), command);
+    }
+}
@@ -1,41 +1,137 @@
-/// Terminal commands for runtime wallpaper, opacity, and blur control
-///
-/// Supports:
-/// - `wallpaper <path>` - Set wallpaper image
-/// - `wallpaper clear` - Remove wallpaper
-/// - `wallpaper-opacity <value>` - Set wallpaper opacity (0.0-1.0)
-/// - `background-opacity <value>` - Set background opacity (0.0-1.0)
-/// - `blur-strength <value>` - Set blur strength (0.0-10.0, 0.0 = disabled)
+//! Terminal commands for runtime appearance, font, and pane layout control
+//!
+//! Supports:
+//! - `wallpaper <path>` - Set wallpaper image
+//! - `wallpaper clear` - Remove wallpaper
+//! - `wallpaper --tab <path|clear>` - Set or remove the active tab's own wallpaper
+//! - `wallpaper-opacity <value>` - Set wallpaper opacity (0.0-1.0)
+//! - `wallpaper-mode <fill|fit|stretch|tile|center>` - Set how the wallpaper is fitted
+//! - `background-opacity <value>` - Set background opacity (0.0-1.0)
+//! - `blur-strength <value>` - Set blur strength (0.0-10.0, 0.0 = disabled)
+//! - `swap-pane` - Swap the focused pane with its sibling
+//! - `rotate-panes` - Rotate the split containing the focused pane
+//! - `balance-panes` - Reset all splits to equal sizes
+//! - `theme <name>` - Switch color theme
+//! - `font-size <n>` - Set font size (8-48)
+//! - `font <family>` - Switch font family
+//! - `split h|v` - Split the focused pane (h = stacked, v = side by side)
+//! - `clear-scrollback` - Drop the focused pane's scrollback history
+//! - `respawn` - Restart the focused pane's shell, keeping size, directory, and scrollback
+//! - `badge <text|clear>` - Set or remove the focused pane's badge
+//! - `log start [--raw] <path>` - Append the focused pane's output to a file
+//! - `log stop` - Stop logging the focused pane
+//! - `screenshot [--pane] [path]` - Save the window (or focused pane) as a PNG
+//! - `config reload` - Re-read config.toml and apply appearance settings
+//! - `config init` - Write a commented default config.toml (the old one is kept as .bak)
+//! - `hud` - Toggle the frame statistics HUD
+//! - `renderer info` - Print the GPU adapter, backend, surface format, and present mode
+//! - `layout <name>` - Open a layout from config in a new tab
+//! - `snippet <name>` - Insert a snippet from config, filling its placeholders first
+//! - `help` - List builtin commands
+
+use saternal_core::pane_log::LogFormat;
+use saternal_core::wallpaper::WallpaperMode;
+use saternal_core::SplitDirection;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum TerminalCommand {
     Wallpaper { path: Option<String> },
+    TabWallpaper { path: Option<String> },
     WallpaperOpacity { opacity: f32 },
+    WallpaperMode { mode: WallpaperMode },
     BackgroundOpacity { opacity: f32 },
     BlurStrength { strength: f32 },
+    SwapPane,
+    RotatePanes,
+    BalancePanes,
+    Theme { name: String },
+    FontSize { size: f32 },
+    Font { family: String },
+    Split { direction: SplitDirection },
+    ClearScrollback,
+    Respawn,
+    /// None removes the badge from the focused pane
+    Badge { text: Option<String> },
+    LogStart { path: String, format: LogFormat },
+    LogStop,
+    /// None saves to the Desktop with a timestamped name
+    Screenshot { path: Option<String>, pane_only: bool },
+    ConfigReload,
+    ConfigInit,
+    ToggleHud,
+    RendererInfo,
+    Layout { name: String },
+    Snippet { name: String },
+    Help,
+}
+
+/// Description of a builtin command, used for `help` and completion hints
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CommandSpec {
+    pub name: &'static str,
+    pub args: &'static str,
+    pub description: &'static str,
 }
 
+/// All builtin commands, in the order shown by `help`
+pub const BUILTIN_COMMANDS: &[CommandSpec] = &[
+    CommandSpec { name: "wallpaper", args: "[--tab] <path|clear>", description: "Set or remove the wallpaper image (--tab: this tab only)" },
+    CommandSpec { name: "wallpaper-opacity", args: "<0.0-1.0>", description: "Set wallpaper opacity" },
+    CommandSpec { name: "wallpaper-mode", args: "<fill|fit|stretch|tile|center>", description: "Set how the wallpaper is fitted" },
+    CommandSpec { name: "background-opacity", args: "<0.0-1.0>", description: "Set background opacity" },
+    CommandSpec { name: "blur-strength", args: "<0.0-10.0>", description: "Set wallpaper blur (0 disables)" },
+    CommandSpec { name: "swap-pane", args: "", description: "Swap the focused pane with its sibling" },
+    CommandSpec { name: "rotate-panes", args: "", description: "Rotate the split containing the focused pane" },
+    CommandSpec { name: "balance-panes", args: "", description: "Reset all splits to equal sizes" },
+    CommandSpec { name: "theme", args: "<tokyo-night|dracula|solarized-dark>", description: "Switch color theme" },
+    CommandSpec { name: "font-size", args: "<8-48>", description: "Set font size" },
+    CommandSpec { name: "font", args: "<family>", description: "Switch font family" },
+    CommandSpec { name: "split", args: "<h|v>", description: "Split the focused pane (h = stacked, v = side by side)" },
+    CommandSpec { name: "clear-scrollback", args: "", description: "Drop the focused pane's scrollback" },
+    CommandSpec { name: "respawn", args: "", description: "Restart the focused pane's shell (Cmd+Shift+E)" },
+    CommandSpec { name: "badge", args: "<text|clear>", description: "Set or remove the focused pane's corner badge" },
+    CommandSpec { name: "log", args: "<start [--raw] <path>|stop>", description: "Log the focused pane's output to a file (plain text unless --raw)" },
+    CommandSpec { name: "screenshot", args: "[--pane] [path]", description: "Save the window (or focused pane) as a PNG" },
+    CommandSpec { name: "config", args: "<reload|init>", description: "Re-read config.toml, or write a commented default one" },
+    CommandSpec { name: "hud", args: "", description: "Toggle frame statistics (FPS, frame time, PTY throughput)" },
+    CommandSpec { name: "renderer", args: "info", description: "Print the GPU adapter, backend, surface format, and present mode" },
+    CommandSpec { name: "layout", args: "<name>", description: "Open a layout from config.toml in a new tab" },
+    CommandSpec { name: "snippet", args: "<name>", description: "Insert a snippet from config.toml at the prompt (Cmd+; to pick)" },
+    CommandSpec { name: "help", args: "", description: "List builtin commands" },
+];
+
+/// Font size limits (same as the Cmd+/Cmd- shortcuts)
+pub const MIN_FONT_SIZE: f32 = 8.0;
+pub const MAX_FONT_SIZE: f32 = 48.0;
+
+/// Shortest prefix that triggers completion hints
+const MIN_HINT_PREFIX: usize = 2;
+
 /// Parse a command from terminal input
-/// Simply looks for command keywords anywhere in the line (handles prompts automatically)
+/// Keywords count only at the start of the line or right after a prompt symbol,
+/// so shell commands that merely mention one (`echo theme x`) reach the shell
 pub fn parse_command(line: &str) -> Option<TerminalCommand> {
     let line = line.trim();
     log::debug!("🔍 PARSING COMMAND: '{}'", line);
 
-    // Wallpaper command - find "wallpaper " anywhere in line
-    if let Some(pos) = line.find("wallpaper ") {
-        let arg = line[pos + 10..].trim();
-
-        // First check: empty argument is not a valid command
-        if arg.is_empty() {
-            return None;
+    // Wallpaper command
+    if let Some(arg) = keyword_argument(line, "wallpaper") {
+        // "--tab" targets only the active tab
+        if let Some(rest) = arg.strip_prefix("--tab") {
+            let rest = rest.trim();
+            return match rest {
+                "" => None,
+                "clear" => Some(TerminalCommand::TabWallpaper { path: None }),
+                _ => Some(TerminalCommand::TabWallpaper { path: Some(expand_tilde(rest)) }),
+            };
         }
 
-        // Second check: "clear" means remove wallpaper
+        // "clear" means remove wallpaper
         if arg == "clear" {
             return Some(TerminalCommand::Wallpaper { path: None });
         }
 
-        // Third check: expand tilde and validate resulting path
+        // Expand tilde and validate resulting path
         let expanded_path = expand_tilde(arg);
         if expanded_path.is_empty() {
             return None;
@@ -46,9 +142,8 @@ pub fn parse_command(line: &str) -> Option<TerminalCommand> {
         });
     }
 
-    // Wallpaper opacity command - find anywhere in line
-    if let Some(pos) = line.find("wallpaper-opacity ") {
-        let arg = line[pos + 18..].trim();
+    // Wallpaper opacity command
+    if let Some(arg) = keyword_argument(line, "wallpaper-opacity") {
         if let Ok(opacity) = arg.parse::<f32>() {
             if (0.0..=1.0).contains(&opacity) {
                 return Some(TerminalCommand::WallpaperOpacity { opacity });
@@ -62,9 +157,12 @@ pub fn parse_command(line: &str) -> Option<TerminalCommand> {
         }
     }
 
-    // Background opacity command - find anywhere in line
-    if let Some(pos) = line.find("background-opacity ") {
-        let arg = line[pos + 19..].trim();
+    if let Some(arg) = keyword_argument(line, "wallpaper-mode") {
+        return WallpaperMode::by_name(arg).map(|mode| TerminalCommand::WallpaperMode { mode });
+    }
+
+    // Background opacity command
+    if let Some(arg) = keyword_argument(line, "background-opacity") {
         if let Ok(opacity) = arg.parse::<f32>() {
             if (0.0..=1.0).contains(&opacity) {
                 return Some(TerminalCommand::BackgroundOpacity { opacity });
@@ -78,9 +176,8 @@ pub fn parse_command(line: &str) -> Option<TerminalCommand> {
         }
     }
 
-    // Blur strength command - find anywhere in line
-    if let Some(pos) = line.find("blur-strength ") {
-        let arg = line[pos + 14..].trim();
+    // Blur strength command
+    if let Some(arg) = keyword_argument(line, "blur-strength") {
         if let Ok(strength) = arg.parse::<f32>() {
             if (0.0..=10.0).contains(&strength) {
                 return Some(TerminalCommand::BlurStrength { strength });
@@ -94,9 +191,176 @@ pub fn parse_command(line: &str) -> Option<TerminalCommand> {
         }
     }
 
+    // Font size command - checked before `font` so the keyword isn't mistaken for a family
+    if let Some(arg) = keyword_argument(line, "font-size") {
+        return match arg.parse::<f32>() {
+            Ok(size) if (MIN_FONT_SIZE..=MAX_FONT_SIZE).contains(&size) => {
+                Some(TerminalCommand::FontSize { size })
+            }
+            _ => {
+                log::warn!("Invalid font size: {}", arg);
+                None
+            }
+        };
+    }
+
+    if let Some(arg) = keyword_argument(line, "font") {
+        return Some(TerminalCommand::Font { family: arg.to_string() });
+    }
+
+    if let Some(arg) = keyword_argument(line, "theme") {
+        return Some(TerminalCommand::Theme { name: arg.to_string() });
+    }
+
+    if let Some(arg) = keyword_argument(line, "log start") {
+        let (format, path) = match arg.strip_prefix("--raw") {
+            Some(rest) => (LogFormat::Raw, rest.trim()),
+            None => (LogFormat::Plain, arg),
+        };
+        return (!path.is_empty()).then(|| TerminalCommand::LogStart { path: expand_tilde(path), format });
+    }
+    if is_prompt_command(line, "log stop") {
+        return Some(TerminalCommand::LogStop);
+    }
+
+    if let Some(arg) = keyword_argument(line, "screenshot") {
+        let (pane_only, path) = match arg.strip_prefix("--pane") {
+            Some(rest) => (true, rest.trim()),
+            None => (false, arg),
+        };
+        let path = (!path.is_empty()).then(|| expand_tilde(path));
+        return Some(TerminalCommand::Screenshot { path, pane_only });
+    }
+    if is_prompt_command(line, "screenshot") {
+        return Some(TerminalCommand::Screenshot { path: None, pane_only: false });
+    }
+
+    if let Some(arg) = keyword_argument(line, "badge") {
+        let text = (arg != "clear").then(|| arg.to_string());
+        return Some(TerminalCommand::Badge { text });
+    }
+
+    if let Some(arg) = keyword_argument(line, "layout") {
+        return Some(TerminalCommand::Layout { name: arg.to_string() });
+    }
+
+    if let Some(arg) = keyword_argument(line, "snippet") {
+        return Some(TerminalCommand::Snippet { name: arg.to_string() });
+    }
+
+    if let Some(arg) = keyword_argument(line, "split") {
+        return match arg {
+            "h" => Some(TerminalCommand::Split { direction: SplitDirection::Horizontal }),
+            "v" => Some(TerminalCommand::Split { direction: SplitDirection::Vertical }),
+            _ => None,
+        };
+    }
+
+    if is_prompt_command(line, "clear-scrollback") {
+        return Some(TerminalCommand::ClearScrollback);
+    }
+    if is_prompt_command(line, "respawn") {
+        return Some(TerminalCommand::Respawn);
+    }
+    if is_prompt_command(line, "config reload") {
+        return Some(TerminalCommand::ConfigReload);
+    }
+    if is_prompt_command(line, "config init") {
+        return Some(TerminalCommand::ConfigInit);
+    }
+    if is_prompt_command(line, "hud") {
+        return Some(TerminalCommand::ToggleHud);
+    }
+    if is_prompt_command(line, "renderer info") {
+        return Some(TerminalCommand::RendererInfo);
+    }
+
+    // Pane layout commands take no arguments
+    if is_prompt_command(line, "swap-pane") {
+        return Some(TerminalCommand::SwapPane);
+    }
+    if is_prompt_command(line, "rotate-panes") {
+        return Some(TerminalCommand::RotatePanes);
+    }
+    if is_prompt_command(line, "balance-panes") {
+        return Some(TerminalCommand::BalancePanes);
+    }
+
+    if is_prompt_command(line, "help") {
+        return Some(TerminalCommand::Help);
+    }
+
     None
 }
 
+/// Builtin commands matching what is being typed on the current line
+///
+/// Returns the command whose arguments are being typed, or every command
+/// the last word is a prefix of.
+pub fn command_hints(line: &str) -> Vec<&'static CommandSpec> {
+    let line = line.trim_start();
+
+    // Typing arguments: "wallpaper-opacity 0." -> hint for wallpaper-opacity
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let typing_new_word = line.is_empty() || line.ends_with(char::is_whitespace);
+    let complete_words = if typing_new_word { &words[..] } else { &words[..words.len().saturating_sub(1)] };
+    if let Some(spec) = complete_words
+        .iter()
+        .rev()
+        .find_map(|word| BUILTIN_COMMANDS.iter().find(|spec| spec.name == *word && !spec.args.is_empty()))
+    {
+        return vec![spec];
+    }
+
+    // Typing a command name: "wall" -> wallpaper, wallpaper-opacity
+    match words.last() {
+        Some(prefix) if !typing_new_word && prefix.len() >= MIN_HINT_PREFIX => BUILTIN_COMMANDS
+            .iter()
+            .filter(|spec| spec.name.starts_with(prefix) && spec.name != *prefix)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Help text listing every builtin command
+pub fn help_text() -> String {
+    let width = BUILTIN_COMMANDS
+        .iter()
+        .map(|spec| spec.name.len() + spec.args.len() + 1)
+        .max()
+        .unwrap_or(0);
+
+    let mut text = String::from("Saternal builtin commands:\n");
+    for spec in BUILTIN_COMMANDS {
+        let usage = format!("{} {}", spec.name, spec.args);
+        text.push_str(&format!("  {:<width$}  {}\n", usage.trim_end(), spec.description, width = width));
+    }
+    text
+}
+
+/// Characters that end a shell prompt
+const PROMPT_SYMBOLS: [char; 5] = ['$', '%', '>', '#', '❯'];
+
+/// Check that `prefix` (the line before a keyword) is empty or ends in a prompt
+fn is_prompt(prefix: &str) -> bool {
+    let prefix = prefix.trim_end();
+    prefix.is_empty() || prefix.ends_with(PROMPT_SYMBOLS)
+}
+
+/// Check that the line is just the keyword, or the keyword right after a prompt symbol
+fn is_prompt_command(line: &str, keyword: &str) -> bool {
+    line.strip_suffix(keyword).is_some_and(is_prompt)
+}
+
+/// Find `keyword <argument>` as the whole command after the prompt and return the trimmed argument
+fn keyword_argument<'a>(line: &'a str, keyword: &str) -> Option<&'a str> {
+    let pattern = format!("{} ", keyword);
+    line.match_indices(&pattern)
+        .find(|(pos, _)| is_prompt(&line[..*pos]))
+        .map(|(pos, _)| line[pos + pattern.len()..].trim())
+        .filter(|arg| !arg.is_empty())
+}
+
 /// Expand tilde (~) to home directory
 fn expand_tilde(path: &str) -> String {
     if !path.starts_with('~') {
@@ -159,9 +423,12 @@ pub fn format_success_message(cmd: &TerminalCommand) -> String {
         TerminalCommand::Wallpaper { path: None } => {
             "✓ Wallpaper cleared".to_string()
         }
+        TerminalCommand::TabWallpaper { path: Some(p) } => format!("✓ Tab wallpaper set: {}", p),
+        TerminalCommand::TabWallpaper { path: None } => "✓ Tab wallpaper cleared (using the global wallpaper)".to_string(),
         TerminalCommand::WallpaperOpacity { opacity } => {
             format!("✓ Wallpaper opacity set to {:.1}%", opacity * 100.0)
         }
+        TerminalCommand::WallpaperMode { mode } => format!("✓ Wallpaper mode set to {}", mode.name()),
         TerminalCommand::BackgroundOpacity { opacity } => {
             format!("✓ Background opacity set to {:.1}%", opacity * 100.0)
         }
@@ -172,6 +439,31 @@ pub fn format_success_message(cmd: &TerminalCommand) -> String {
                 format!("✓ Blur strength set to {:.1}", strength)
             }
         }
+        TerminalCommand::SwapPane => "✓ Panes swapped".to_string(),
+        TerminalCommand::RotatePanes => "✓ Panes rotated".to_string(),
+        TerminalCommand::BalancePanes => "✓ Panes balanced".to_string(),
+        TerminalCommand::Theme { name } => format!("✓ Theme set to {}", name),
+        TerminalCommand::FontSize { size } => format!("✓ Font size set to {}", size),
+        TerminalCommand::Font { family } => format!("✓ Font set to {}", family),
+        TerminalCommand::Split { direction: SplitDirection::Horizontal } => "✓ Pane split (stacked)".to_string(),
+        TerminalCommand::Split { direction: SplitDirection::Vertical } => "✓ Pane split (side by side)".to_string(),
+        TerminalCommand::ClearScrollback => "✓ Scrollback cleared".to_string(),
+        TerminalCommand::Respawn => "✓ Shell restarted".to_string(),
+        TerminalCommand::Badge { text: Some(text) } => format!("✓ Badge set to {}", text),
+        TerminalCommand::Badge { text: None } => "✓ Badge removed".to_string(),
+        TerminalCommand::LogStart { path, .. } => format!("✓ Logging output to {}", path),
+        TerminalCommand::LogStop => "✓ Logging stopped".to_string(),
+        TerminalCommand::Screenshot { path: Some(path), .. } => format!("✓ Screenshot saved to {}", path),
+        TerminalCommand::Screenshot { path: None, .. } => "✓ Screenshot saved".to_string(),
+        TerminalCommand::ConfigReload => "✓ Config reloaded".to_string(),
+        TerminalCommand::ConfigInit => "✓ Wrote a commented default config.toml (any previous file is saved as config.toml.bak)".to_string(),
+        TerminalCommand::ToggleHud => "✓ Frame statistics toggled (shown in the top-right corner)".to_string(),
+        TerminalCommand::RendererInfo => {
+            "✓ Choose another GPU with [renderer] in config.toml (applies to new windows)".to_string()
+        }
+        TerminalCommand::Layout { name } => format!("✓ Opened layout {}", name),
+        TerminalCommand::Snippet { name } => format!("✓ Inserting snippet {}", name),
+        TerminalCommand::Help => help_text(),
     }
 }
 
@@ -184,15 +476,40 @@ pub fn format_error_message(cmd: &TerminalCommand, error: &str) -> String {
         TerminalCommand::Wallpaper { path: None } => {
             format!("✗ Failed to clear wallpaper: {}", error)
         }
+        TerminalCommand::TabWallpaper { path: Some(p) } => {
+            format!("✗ Failed to set tab wallpaper '{}': {}", p, error)
+        }
+        TerminalCommand::TabWallpaper { path: None } => format!("✗ Failed to clear tab wallpaper: {}", error),
         TerminalCommand::WallpaperOpacity { .. } => {
             format!("✗ Failed to set wallpaper opacity: {}", error)
         }
+        TerminalCommand::WallpaperMode { .. } => format!("✗ Failed to set wallpaper mode: {}", error),
         TerminalCommand::BackgroundOpacity { .. } => {
             format!("✗ Failed to set background opacity: {}", error)
         }
         TerminalCommand::BlurStrength { .. } => {
             format!("✗ Failed to set blur strength: {}", error)
         }
+        TerminalCommand::SwapPane => format!("✗ Failed to swap panes: {}", error),
+        TerminalCommand::RotatePanes => format!("✗ Failed to rotate panes: {}", error),
+        TerminalCommand::BalancePanes => format!("✗ Failed to balance panes: {}", error),
+        TerminalCommand::Theme { name } => format!("✗ Failed to set theme '{}': {}", name, error),
+        TerminalCommand::FontSize { .. } => format!("✗ Failed to set font size: {}", error),
+        TerminalCommand::Font { family } => format!("✗ Failed to set font '{}': {}", family, error),
+        TerminalCommand::Split { .. } => format!("✗ Failed to split pane: {}", error),
+        TerminalCommand::ClearScrollback => format!("✗ Failed to clear scrollback: {}", error),
+        TerminalCommand::Respawn => format!("✗ Failed to restart shell: {}", error),
+        TerminalCommand::Badge { .. } => format!("✗ Failed to set badge: {}", error),
+        TerminalCommand::LogStart { path, .. } => format!("✗ Failed to log to '{}': {}", path, error),
+        TerminalCommand::LogStop => format!("✗ Failed to stop logging: {}", error),
+        TerminalCommand::Screenshot { .. } => format!("✗ Failed to save screenshot: {}", error),
+        TerminalCommand::ConfigReload => format!("✗ Failed to reload config: {}", error),
+        TerminalCommand::ConfigInit => format!("✗ Failed to write default config: {}", error),
+        TerminalCommand::ToggleHud => format!("✗ Failed to toggle frame statistics: {}", error),
+        TerminalCommand::RendererInfo => format!("✗ Failed to read renderer info: {}", error),
+        TerminalCommand::Layout { name } => format!("✗ Failed to open layout '{}': {}", name, error),
+        TerminalCommand::Snippet { name } => format!("✗ Failed to insert snippet '{}': {}", name, error),
+        TerminalCommand::Help => format!("✗ Failed to show help: {}", error),
     }
 }
 
@@ -217,6 +534,25 @@ mod tests {
         assert_eq!(cmd, Some(TerminalCommand::Wallpaper { path: None }));
     }
 
+    #[test]
+    fn test_parse_tab_wallpaper() {
+        assert_eq!(
+            parse_command("$ wallpaper --tab /tmp/a.png"),
+            Some(TerminalCommand::TabWallpaper { path: Some("/tmp/a.png".to_string()) })
+        );
+        assert_eq!(parse_command("wallpaper --tab clear"), Some(TerminalCommand::TabWallpaper { path: None }));
+        assert_eq!(parse_command("wallpaper --tab"), None);
+    }
+
+    #[test]
+    fn test_parse_wallpaper_mode() {
+        assert_eq!(
+            parse_command("$ wallpaper-mode tile"),
+            Some(TerminalCommand::WallpaperMode { mode: WallpaperMode::Tile })
+        );
+        assert_eq!(parse_command("wallpaper-mode zoom"), None);
+    }
+
     #[test]
     fn test_parse_wallpaper_opacity() {
         let cmd = parse_command("wallpaper-opacity 0.5");
@@ -301,4 +637,118 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn test_parse_pane_layout_commands() {
+        assert_eq!(parse_command("swap-pane"), Some(TerminalCommand::SwapPane));
+        assert_eq!(
+            parse_command("user@host $ rotate-panes"),
+            Some(TerminalCommand::RotatePanes)
+        );
+        assert_eq!(
+            parse_command("> balance-panes  "),
+            Some(TerminalCommand::BalancePanes)
+        );
+    }
+
+    #[test]
+    fn test_parse_pane_layout_requires_standalone_keyword() {
+        assert_eq!(parse_command("my-swap-pane"), None);
+        assert_eq!(parse_command("swap-pane --now"), None);
+    }
+
+    #[test]
+    fn test_parse_ignores_keywords_inside_shell_commands() {
+        assert_eq!(parse_command("$ git commit -m \"split h\""), None);
+        assert_eq!(parse_command("$ echo theme x"), None);
+        assert_eq!(parse_command("% grep font config.toml"), None);
+        assert_eq!(parse_command("$ cat layout dev"), None);
+        assert_eq!(parse_command("$ echo wallpaper-mode tile"), None);
+        assert_eq!(parse_command("$ echo wallpaper ~/a.png"), None);
+        assert_eq!(parse_command("$ ls hud"), None);
+        assert_eq!(parse_command("~/theme $ theme nord"), Some(TerminalCommand::Theme { name: "nord".to_string() }));
+    }
+
+    #[test]
+    fn test_parse_help_only_after_prompt() {
+        assert_eq!(parse_command("help"), Some(TerminalCommand::Help));
+        assert_eq!(parse_command("user@host ~/dir $ help"), Some(TerminalCommand::Help));
+        assert_eq!(parse_command("sam@mac saternal % help"), Some(TerminalCommand::Help));
+        assert_eq!(parse_command("$ git help"), None);
+    }
+
+    #[test]
+    fn test_parse_appearance_commands() {
+        assert_eq!(
+            parse_command("$ theme dracula"),
+            Some(TerminalCommand::Theme { name: "dracula".to_string() })
+        );
+        assert_eq!(parse_command("$ font-size 16"), Some(TerminalCommand::FontSize { size: 16.0 }));
+        assert_eq!(parse_command("$ font-size 100"), None);
+        assert_eq!(
+            parse_command("$ font JetBrains Mono"),
+            Some(TerminalCommand::Font { family: "JetBrains Mono".to_string() })
+        );
+        assert_eq!(parse_command("$ myfont x"), None);
+    }
+
+    #[test]
+    fn test_parse_split_and_maintenance_commands() {
+        assert_eq!(
+            parse_command("$ split h"),
+            Some(TerminalCommand::Split { direction: SplitDirection::Horizontal })
+        );
+        assert_eq!(
+            parse_command("split v"),
+            Some(TerminalCommand::Split { direction: SplitDirection::Vertical })
+        );
+        assert_eq!(parse_command("$ split x"), None);
+        assert_eq!(parse_command("% clear-scrollback"), Some(TerminalCommand::ClearScrollback));
+        assert_eq!(parse_command("$ respawn"), Some(TerminalCommand::Respawn));
+        assert_eq!(parse_command("$ config reload"), Some(TerminalCommand::ConfigReload));
+        assert_eq!(parse_command("$ config init"), Some(TerminalCommand::ConfigInit));
+        assert_eq!(parse_command("❯ hud"), Some(TerminalCommand::ToggleHud));
+        assert_eq!(parse_command("$ renderer info"), Some(TerminalCommand::RendererInfo));
+        assert_eq!(parse_command("$ renderer"), None);
+        assert_eq!(parse_command("$ layout dev"), Some(TerminalCommand::Layout { name: "dev".to_string() }));
+        assert_eq!(parse_command("$ snippet deploy"), Some(TerminalCommand::Snippet { name: "deploy".to_string() }));
+        assert_eq!(parse_command("$ badge PROD db"), Some(TerminalCommand::Badge { text: Some("PROD db".to_string()) }));
+        assert_eq!(parse_command("$ badge clear"), Some(TerminalCommand::Badge { text: None }));
+        assert_eq!(
+            parse_command("$ log start --raw /tmp/build.log"),
+            Some(TerminalCommand::LogStart { path: "/tmp/build.log".to_string(), format: LogFormat::Raw })
+        );
+        assert_eq!(
+            parse_command("log start out.txt"),
+            Some(TerminalCommand::LogStart { path: "out.txt".to_string(), format: LogFormat::Plain })
+        );
+        assert_eq!(parse_command("$ log stop"), Some(TerminalCommand::LogStop));
+        assert_eq!(parse_command("$ git log start x"), None);
+        assert_eq!(parse_command("$ git log stop"), None);
+        assert_eq!(parse_command("$ screenshot"), Some(TerminalCommand::Screenshot { path: None, pane_only: false }));
+        assert_eq!(
+            parse_command("$ screenshot --pane /tmp/pane.png"),
+            Some(TerminalCommand::Screenshot { path: Some("/tmp/pane.png".to_string()), pane_only: true })
+        );
+        assert_eq!(parse_command("$ screenshot --pane"), Some(TerminalCommand::Screenshot { path: None, pane_only: true }));
+    }
+
+    #[test]
+    fn test_command_hints() {
+        let names = |line: &str| command_hints(line).iter().map(|s| s.name).collect::<Vec<_>>();
+
+        assert_eq!(names("$ wall"), vec!["wallpaper", "wallpaper-opacity", "wallpaper-mode"]);
+        assert_eq!(names("$ wallpaper-opacity 0."), vec!["wallpaper-opacity"]);
+        assert_eq!(names("$ blur-strength "), vec!["blur-strength"]);
+        assert!(names("$ w").is_empty());
+        assert!(names("$ ls -la").is_empty());
+    }
+
+    #[test]
+    fn test_help_lists_every_command() {
+        let help = help_text();
+        for spec in BUILTIN_COMMANDS {
+            assert!(help.contains(spec.name));
+        }
+    }
 }
@@ -0,0 +1,114 @@
+use log::info;
+use parking_lot::Mutex;
+use saternal_core::output_parser::TERM_LOCK_TIMEOUT;
+use saternal_core::selection::TextColumns;
+use saternal_core::update::Updater;
+use saternal_core::{ClipboardHistory, CloseTarget, SelectionManager};
+use saternal_renderer::Renderer;
+use saternal_macos::{show_context_menu, MenuItem};
+use std::sync::Arc;
+use winit::event_loop::EventLoopWindowTarget;
+
+/// Longest column label shown in the menu
+const MAX_LABEL_CHARS: usize = 24;
+
+/// Show the right-click menu: Copy, Paste, one "Copy Column" entry per
+/// column when the selection looks like a table, and the update item
+///
+/// Returns true when "Restart to Update" was picked.
+pub(super) fn handle_right_click(
+    tab_manager: &Arc<Mutex<crate::tab::TabManager>>,
+    selection_manager: &mut SelectionManager,
+    clipboard_history: &mut ClipboardHistory,
+    renderer: &Arc<Mutex<Renderer>>,
+    window: &winit::window::Window,
+    updater: Option<&Updater>,
+) -> bool {
+    let columns = selection_columns(tab_manager, selection_manager);
+
+    let mut items = vec![
+        MenuItem::Action { title: "Copy".to_string(), enabled: selection_manager.range().is_some() },
+        MenuItem::Action { title: "Paste".to_string(), enabled: true },
+    ];
+    if let Some(columns) = &columns {
+        items.push(MenuItem::Separator);
+        items.extend((0..columns.len()).map(|index| MenuItem::Action {
+            title: column_title(index, &columns.label(index)),
+            enabled: true,
+        }));
+    }
+    let ready_version = updater.and_then(Updater::ready_version);
+    let update_item = updater.map(|updater| {
+        items.push(MenuItem::Separator);
+        items.push(match &ready_version {
+            Some(version) => MenuItem::Action { title: format!("Restart to Update to {}", version), enabled: true },
+            None => MenuItem::Action { title: "Check for Updates…".to_string(), enabled: !updater.is_checking() },
+        });
+        items.len() - 1
+    });
+
+    // Locks are released above: the menu runs a nested event loop until it closes
+    match unsafe { show_context_menu(&items) } {
+        Some(0) => super::clipboard::handle_copy(tab_manager, selection_manager, clipboard_history, renderer, window),
+        Some(1) => super::clipboard::handle_paste(false, tab_manager, renderer, window),
+        Some(item) if Some(item) == update_item => {
+            if ready_version.is_some() {
+                return true;
+            }
+            if let Some(updater) = updater {
+                info!("Checking for updates");
+                updater.check_now();
+            }
+        }
+        Some(item) => {
+            if let Some(columns) = &columns {
+                // Items after Copy, Paste, and the separator
+                let index = item - 3;
+                info!("Copying column {} of the selection", index + 1);
+                if super::clipboard::copy_text(&columns.column(index), clipboard_history) {
+                    renderer.lock().flash_selection();
+                    window.request_redraw();
+                }
+            }
+        }
+        None => {}
+    }
+    false
+}
+
+/// Quit (after the usual confirmation) and start again to install the staged update
+pub(super) fn restart(tab_manager: &Arc<Mutex<crate::tab::TabManager>>, elwt: &EventLoopWindowTarget<()>) {
+    let confirmed = {
+        let mut tab_mgr = tab_manager.lock();
+        let busy = tab_mgr.busy_processes();
+        tab_mgr.confirm_close(CloseTarget::Window, &busy)
+    };
+    if confirmed {
+        info!("Restarting to install the update");
+        saternal_core::update::relaunch_on_exit();
+        elwt.exit();
+    }
+}
+
+fn selection_columns(
+    tab_manager: &Arc<Mutex<crate::tab::TabManager>>,
+    selection_manager: &SelectionManager,
+) -> Option<TextColumns> {
+    let tab_mgr = tab_manager.try_lock()?;
+    let pane = tab_mgr.active_tab()?.pane_tree.focused_pane()?;
+    let term = pane.terminal.term();
+    let term_lock = term.try_lock_for(TERM_LOCK_TIMEOUT)?;
+    selection_manager.columns(term_lock.grid())
+}
+
+/// `Copy Column 2 (PID)`, with long labels shortened
+fn column_title(index: usize, label: &str) -> String {
+    if label.is_empty() {
+        return format!("Copy Column {}", index + 1);
+    }
+    let mut short: String = label.chars().take(MAX_LABEL_CHARS).collect();
+    if short.len() < label.len() {
+        short.push('…');
+    }
+    format!("Copy Column {} ({})", index + 1, short)
+}
@@ -0,0 +1,74 @@
+use crate::platform::DropdownWindow;
+use log::info;
+use parking_lot::Mutex;
+use saternal_core::config::MIN_HEIGHT_PERCENTAGE;
+use saternal_core::{Config, DropdownSurface};
+use saternal_renderer::Renderer;
+use std::sync::Arc;
+use winit::event::ElementState;
+use winit::window::CursorIcon;
+
+/// Logical pixels above the dropdown's bottom edge that grab it for resizing
+const EDGE_GRAB_HEIGHT: f64 = 6.0;
+
+/// Resizing the borderless dropdown by dragging its bottom edge
+#[derive(Default)]
+pub(super) struct EdgeResize {
+    /// The pointer is over the edge (showing the resize cursor)
+    hovering: bool,
+    dragging: bool,
+}
+
+impl EdgeResize {
+    /// Show the resize cursor over the edge, and move the edge with the pointer while dragging
+    /// Returns true while dragging, when the move isn't for the terminal
+    pub(super) fn cursor_moved(&mut self, y: f64, window: &winit::window::Window, dropdown: &DropdownWindow) -> bool {
+        let Some((screen_height, scale)) = dropdown.dropdown_screen(window) else {
+            return false;
+        };
+        if self.dragging {
+            let height = (y / scale).clamp(screen_height * MIN_HEIGHT_PERCENTAGE, screen_height);
+            dropdown.set_height(window, height);
+            return true;
+        }
+        let hovering = y >= window.inner_size().height as f64 - EDGE_GRAB_HEIGHT * scale;
+        if hovering != self.hovering {
+            self.hovering = hovering;
+            window.set_cursor_icon(if hovering { CursorIcon::NsResize } else { CursorIcon::Default });
+        }
+        false
+    }
+
+    /// Start a drag on the edge, or finish one: the new height is saved and snapped to whole rows
+    /// Returns true if the click was for the edge
+    pub(super) fn mouse_input(
+        &mut self,
+        state: ElementState,
+        window: &winit::window::Window,
+        dropdown: &Arc<Mutex<DropdownWindow>>,
+        renderer: &Arc<Mutex<Renderer>>,
+        config: &mut Config,
+    ) -> bool {
+        match state {
+            ElementState::Pressed => {
+                self.dragging = self.hovering;
+                self.dragging
+            }
+            ElementState::Released if self.dragging => {
+                self.dragging = false;
+                let dropdown = dropdown.lock();
+                if let Some((screen_height, scale)) = dropdown.dropdown_screen(window) {
+                    config.window.set_dropdown_height(window.inner_size().height as f64 / scale, screen_height);
+                    info!("Dropdown resized to {:.0}% of the screen", config.window.height_percentage * 100.0);
+                    if let Err(e) = config.save(None) {
+                        log::warn!("Failed to save config: {}", e);
+                    }
+                    let (cell_height, padding) = super::window::row_metrics(renderer);
+                    super::window::fit_dropdown_height(window, cell_height, &padding, &dropdown, config);
+                }
+                true
+            }
+            ElementState::Released => false,
+        }
+    }
+}
@@ -1,12 +1,33 @@
+use super::window_manager::{is_new_window_shortcut, WindowManager};
 use super::App;
 use anyhow::Result;
 use log::info;
+use saternal_core::ipc::EventKind;
+use saternal_core::{CloseTarget, CommandHistory, Config, DropdownSurface, GlobalHotkeys, IpcServer};
+use crate::tab::TabManager;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use winit::{
-    event::{Event, WindowEvent},
+    event::{ElementState, Event, MouseButton, WindowEvent},
     event_loop::ControlFlow,
+    window::Window,
 };
 
+/// Poll interval while a pane waits for its shell prompt
+const STARTUP_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Poll interval while typed characters wait for their echo (stale ones get dropped)
+const ECHO_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Redraw interval while a visual bell flash fades out
+const BELL_FRAME_INTERVAL: Duration = Duration::from_millis(16);
+
+/// Redraw interval while momentum scrolling coasts
+const SCROLL_FRAME_INTERVAL: Duration = Duration::from_millis(16);
+
+/// Redraw interval of an animated post-process shader
+const POST_PROCESS_FRAME_INTERVAL: Duration = Duration::from_millis(16);
+
 impl App {
     /// Run the application event loop
     pub fn run(self) -> Result<()> {
@@ -19,10 +40,31 @@ impl App {
         let mut font_size = self.font_size;
         let mut config = self.config.clone();
         let mut modifiers_state = winit::event::Modifiers::default();
+        let mut edge_resize = super::edge_resize::EdgeResize::default();
+        // Low latency mode: a keypress was drawn and its echo should be as soon as it's parsed
+        let mut awaiting_echo = false;
+        // Keeps App Nap away while a window is on screen
+        #[cfg(target_os = "macos")]
+        let mut app_activity = saternal_macos::AppActivity::default();
 
         let mut selection_manager = self.selection_manager;
         let mut search_state = self.search_state;
+        let mut command_history = self.command_history;
+        let mut history_search = self.history_search;
+        let mut snippet_picker = self.snippet_picker;
+        let mut clipboard_history = self.clipboard_history;
         let mut mouse_state = self.mouse_state;
+        let privacy_lock = self.privacy_lock.clone();
+        #[cfg(target_os = "macos")]
+        let screen_lock_monitor = self.screen_lock_monitor;
+        #[cfg(target_os = "macos")]
+        let mut pin_status_item = self.pin_status_item;
+        let ipc_server = self.ipc_server;
+        let updater = self.updater;
+        #[cfg(feature = "shader-hot-reload")]
+        let shader_watcher = self.shader_watcher;
+
+        let mut windows = WindowManager::default();
 
         info!("Starting event loop");
 
@@ -31,13 +73,150 @@ impl App {
 
             hotkey_manager.process_events();
 
+            if let Some(server) = &ipc_server {
+                while let Some(call) = server.try_recv() {
+                    let reply = super::ipc::handle_request(
+                        &call.request,
+                        &mut config,
+                        &renderer,
+                        &tab_manager,
+                        &hotkey_manager,
+                    );
+                    call.reply(reply.map_err(|e| e.to_string()));
+                    window.request_redraw();
+                }
+            }
+
             match event {
+                // Windows opened with Cmd+N - same handlers, their own state
+                Event::WindowEvent { window_id, event } if window_id != window.id() => {
+                    if let WindowEvent::CloseRequested = event {
+                        windows.request_close(window_id);
+                        return;
+                    }
+                    if let WindowEvent::ModifiersChanged(new_modifiers) = event {
+                        modifiers_state = new_modifiers;
+                        return;
+                    }
+                    if let WindowEvent::KeyboardInput { event: key_event, .. } = &event {
+                        if is_new_window_shortcut(key_event, &modifiers_state) {
+                            if let Err(e) = windows.open(elwt, &config) {
+                                log::error!("Failed to open window: {}", e);
+                            }
+                            return;
+                        }
+                    }
+                    let Some(extra) = windows.get_mut(window_id) else {
+                        return;
+                    };
+                    let content_hidden = privacy_lock.lock().is_locked();
+                    match event {
+                        WindowEvent::Resized(size) => {
+                            super::window::handle_resize(size, &extra.renderer, &extra.tab_manager, &extra.window);
+                        }
+                        WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                            super::window::handle_scale_factor_changed(scale_factor, &extra.renderer, &extra.window, &dropdown, &config);
+                        }
+                        WindowEvent::Focused(focused) => {
+                            extra.renderer.lock().set_window_focused(focused);
+                            extra.window.request_redraw();
+                        }
+                        WindowEvent::KeyboardInput { event, .. } => {
+                            super::input::handle_keyboard_input(
+                                &event,
+                                event.state,
+                                &modifiers_state,
+                                &extra.renderer,
+                                &extra.tab_manager,
+                                &mut extra.selection_manager,
+                                &mut extra.search_state,
+                                &command_history,
+                                &mut history_search,
+                                &mut clipboard_history,
+                                &mut snippet_picker,
+                                &mut config,
+                                &mut font_size,
+                                &extra.window,
+                                &dropdown,
+                                &privacy_lock,
+                            );
+                        }
+                        #[cfg(target_os = "macos")]
+                        WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Right, .. }
+                            if !content_hidden =>
+                        {
+                            let restart = super::context_menu::handle_right_click(
+                                &extra.tab_manager,
+                                &mut extra.selection_manager,
+                                &mut clipboard_history,
+                                &extra.renderer,
+                                &extra.window,
+                                updater.as_ref(),
+                            );
+                            if restart {
+                                super::context_menu::restart(&tab_manager, elwt);
+                            }
+                        }
+                        WindowEvent::MouseInput { state, button, .. } if !content_hidden => {
+                            super::mouse::handle_mouse_input(
+                                state,
+                                button,
+                                &mut extra.mouse_state,
+                                &mut extra.selection_manager,
+                                &extra.tab_manager,
+                                &extra.renderer,
+                                &extra.window,
+                                &extra.search_state,
+                            );
+                        }
+                        WindowEvent::CursorMoved { position, .. } => {
+                            super::mouse::handle_cursor_moved(
+                                position.x as f32,
+                                position.y as f32,
+                                &mut extra.mouse_state,
+                                &mut extra.selection_manager,
+                                &extra.renderer,
+                                &extra.tab_manager,
+                                &extra.window,
+                            );
+                        }
+                        WindowEvent::MouseWheel { delta, phase, .. } => {
+                            super::mouse::handle_mouse_wheel(delta, phase, &extra.renderer, &extra.window);
+                        }
+                        WindowEvent::RedrawRequested => {
+                            super::window::handle_redraw(
+                                &extra.renderer,
+                                &extra.tab_manager,
+                                &extra.window,
+                                &extra.search_state,
+                                &command_history,
+                                &history_search,
+                                &clipboard_history,
+                                &snippet_picker,
+                                content_hidden,
+                            );
+                            return;
+                        }
+                        _ => return,
+                    }
+                    extra.window.request_redraw();
+                }
+
                 Event::WindowEvent {
                     event: WindowEvent::CloseRequested,
                     ..
                 } => {
                     info!("Close requested");
-                    elwt.exit();
+                    let confirmed = {
+                        let mut tab_mgr = tab_manager.lock();
+                        let busy = tab_mgr.busy_processes();
+                        tab_mgr.confirm_close(CloseTarget::Window, &busy)
+                    };
+                    if confirmed {
+                        elwt.exit();
+                    } else {
+                        window.request_redraw();
+                    }
                 }
 
                 Event::WindowEvent {
@@ -59,7 +238,19 @@ impl App {
                     event: WindowEvent::ScaleFactorChanged { scale_factor, .. },
                     ..
                 } => {
-                    super::window::handle_scale_factor_changed(scale_factor, &renderer, &window);
+                    super::window::handle_scale_factor_changed(scale_factor, &renderer, &window, &dropdown, &config);
+                    window.request_redraw();
+                }
+
+                Event::WindowEvent {
+                    event: WindowEvent::Focused(focused),
+                    ..
+                } => {
+                    // Cursors turn hollow while the window is in the background
+                    renderer.lock().set_window_focused(focused);
+                    if !focused && config.window.hide_on_focus_loss {
+                        super::window::handle_focus_lost(&window, &dropdown);
+                    }
                     window.request_redraw();
                 }
 
@@ -67,6 +258,12 @@ impl App {
                     event: WindowEvent::KeyboardInput { event, .. },
                     ..
                 } => {
+                    if is_new_window_shortcut(&event, &modifiers_state) {
+                        if let Err(e) = windows.open(elwt, &config) {
+                            log::error!("Failed to open window: {}", e);
+                        }
+                        return;
+                    }
                     super::input::handle_keyboard_input(
                         &event,
                         event.state,
@@ -75,18 +272,64 @@ impl App {
                         &tab_manager,
                         &mut selection_manager,
                         &mut search_state,
+                        &command_history,
+                        &mut history_search,
+                        &mut clipboard_history,
+                        &mut snippet_picker,
                         &mut config,
                         &mut font_size,
                         &window,
                         &dropdown,
+                        &privacy_lock,
                     );
-                    window.request_redraw();
+                    // Low latency mode draws the keypress now instead of on the next redraw
+                    if event.state == ElementState::Pressed && renderer.lock().low_latency() {
+                        awaiting_echo = true;
+                        super::window::handle_redraw(
+                            &renderer,
+                            &tab_manager,
+                            &window,
+                            &search_state,
+                            &command_history,
+                            &history_search,
+                            &clipboard_history,
+                            &snippet_picker,
+                            privacy_lock.lock().is_locked(),
+                        );
+                    } else {
+                        window.request_redraw();
+                    }
                 }
 
                 Event::WindowEvent {
                     event: WindowEvent::MouseInput { state, button, .. },
                     ..
                 } => {
+                    if button == MouseButton::Left
+                        && edge_resize.mouse_input(state, &window, &dropdown, &renderer, &mut config)
+                    {
+                        return;
+                    }
+                    // No selecting or copying hidden content
+                    if privacy_lock.lock().is_locked() {
+                        return;
+                    }
+                    #[cfg(target_os = "macos")]
+                    if state == ElementState::Pressed && button == MouseButton::Right {
+                        let restart = super::context_menu::handle_right_click(
+                            &tab_manager,
+                            &mut selection_manager,
+                            &mut clipboard_history,
+                            &renderer,
+                            &window,
+                            updater.as_ref(),
+                        );
+                        if restart {
+                            super::context_menu::restart(&tab_manager, elwt);
+                        }
+                        window.request_redraw();
+                        return;
+                    }
                     super::mouse::handle_mouse_input(
                         state,
                         button,
@@ -95,6 +338,7 @@ impl App {
                         &tab_manager,
                         &renderer,
                         &window,
+                        &search_state,
                     );
                     window.request_redraw();
                 }
@@ -103,6 +347,9 @@ impl App {
                     event: WindowEvent::CursorMoved { position, .. },
                     ..
                 } => {
+                    if edge_resize.cursor_moved(position.y, &window, &dropdown.lock()) {
+                        return;
+                    }
                     super::mouse::handle_cursor_moved(
                         position.x as f32,
                         position.y as f32,
@@ -110,27 +357,188 @@ impl App {
                         &mut selection_manager,
                         &renderer,
                         &tab_manager,
+                        &window,
                     );
                     window.request_redraw();
                 }
 
                 Event::WindowEvent {
-                    event: WindowEvent::MouseWheel { delta, .. },
+                    event: WindowEvent::MouseWheel { delta, phase, .. },
                     ..
                 } => {
-                    super::mouse::handle_mouse_wheel(delta, &renderer, &window);
+                    super::mouse::handle_mouse_wheel(delta, phase, &renderer, &window);
                     window.request_redraw();
                 }
 
                 Event::AboutToWait => {
+                    #[cfg(target_os = "macos")]
+                    if let Some(monitor) = &screen_lock_monitor {
+                        if monitor.take_lock_request() {
+                            info!("Screen locked - hiding pane content");
+                            privacy_lock.lock().lock();
+                            window.request_redraw();
+                            windows.request_redraw();
+                        }
+                    }
+
+                    if let Some(updater) = &updater {
+                        while let Some(event) = updater.try_recv() {
+                            super::updates::show(&event);
+                        }
+                    }
+
+                    #[cfg(feature = "shader-hot-reload")]
+                    if shader_watcher.as_ref().is_some_and(|watcher| watcher.take_change()) {
+                        renderer.lock().reload_shaders();
+                        windows.reload_shaders();
+                        window.request_redraw();
+                        windows.request_redraw();
+                    }
+
+                    #[cfg(target_os = "macos")]
+                    if let Some(item) = &mut pin_status_item {
+                        let mut dropdown = dropdown.lock();
+                        if item.take_click() {
+                            dropdown.toggle_pin();
+                        }
+                        // Also catches Cmd+Shift+P
+                        unsafe { item.show_pinned(dropdown.is_pinned()) };
+                    }
+
+                    // Nothing on screen: no animations, and output is parsed a few times a second
+                    let hidden = !dropdown.lock().is_visible();
+                    if hidden {
+                        // An armed reveal only lasts while the window is on screen
+                        privacy_lock.lock().disarm();
+                    }
+                    let idle = hidden && windows.is_empty();
+                    saternal_core::output_parser::set_throttled(idle);
+                    #[cfg(target_os = "macos")]
+                    app_activity.set_active(!idle);
+
+                    // Redraw blinking text when its phase flips, and wake up for the next flip
+                    // (set first so shorter animation intervals below take over)
+                    let now = Instant::now();
+                    let mut next_blink = windows.redraw_blinking(now);
+                    match renderer.lock().next_blink() {
+                        _ if hidden => {}
+                        Some(at) if at <= now => window.request_redraw(),
+                        Some(at) => next_blink = Some(next_blink.map_or(at, |next| next.min(at))),
+                        None => {}
+                    }
+                    // Same for close warnings on the status line
+                    let mut next_notice = windows.expire_notices(now);
+                    {
+                        let mut tab_mgr = tab_manager.lock();
+                        if tab_mgr.expire_notice(now) {
+                            window.request_redraw();
+                        }
+                        if let Some(until) = tab_mgr.notice_until() {
+                            next_notice = Some(next_notice.map_or(until, |next| next.min(until)));
+                        }
+                    }
+                    if let Some(at) = next_blink.into_iter().chain(next_notice).min() {
+                        elwt.set_control_flow(ControlFlow::WaitUntil(at));
+                    }
+
+                    // Keep a post-process shader's `post.time` moving while the window is shown
+                    if !hidden && renderer.lock().animates_post_process() {
+                        window.request_redraw();
+                        elwt.set_control_flow(ControlFlow::WaitUntil(now + POST_PROCESS_FRAME_INTERVAL));
+                    }
+
+                    // Output parsed from here on wakes the loop again
+                    saternal_core::output_parser::clear_wakeup();
+                    let mut echo_arrived = false;
                     if let Some(mut tab_mgr) = tab_manager.try_lock() {
+                        drain_background_tabs(&mut tab_mgr, &window, &mut command_history, &config, ipc_server.as_ref());
+                        if tab_mgr.background_tabs_mut().any(|tab| tab.has_pending_startup()) {
+                            elwt.set_control_flow(ControlFlow::WaitUntil(Instant::now() + STARTUP_POLL_INTERVAL));
+                        }
                         if let Some(active_tab) = tab_mgr.active_tab_mut() {
-                            match active_tab.process_output() {
+                            let server = ipc_server.as_ref();
+                            active_tab.set_output_tap(server.is_some_and(|s| s.is_subscribed(EventKind::Output)));
+                            let predicting = active_tab.has_predictions();
+                            let result = active_tab.process_output();
+                            if let Some(server) = server {
+                                for event in active_tab.take_ipc_events() {
+                                    server.publish(&event);
+                                }
+                            }
+                            match result {
                                 Ok(bytes_processed) => {
                                     // Only request redraw if there was actual output
-                                    if bytes_processed > 0 {
+                                    // (or predicted characters went away without any)
+                                    let predictions_dropped = predicting && !active_tab.has_predictions();
+                                    if bytes_processed > 0 || predictions_dropped {
+                                        if let Some(mut renderer) = renderer.try_lock() {
+                                            if renderer.stats_hud_visible() {
+                                                renderer.record_pty_bytes(bytes_processed);
+                                            }
+                                        }
+                                        if awaiting_echo && bytes_processed > 0 {
+                                            echo_arrived = true;
+                                        } else {
+                                            window.request_redraw();
+                                        }
+                                    }
+                                    if super::mouse::follow_output_scroll(active_tab, &mut selection_manager, &renderer) {
                                         window.request_redraw();
                                     }
+                                    // Commands captured across panes feed one shared history
+                                    let mut history_changed = false;
+                                    for command in active_tab.take_captured_commands() {
+                                        history_changed |= command_history.push(&command);
+                                    }
+                                    if history_changed {
+                                        if let Err(e) = command_history.save() {
+                                            log::warn!("Failed to save command history: {}", e);
+                                        }
+                                    }
+                                    for pane_id in active_tab.take_bells() {
+                                        if config.bell.mode.is_visual() {
+                                            renderer.lock().ring_bell(pane_id);
+                                        }
+                                        if config.bell.mode.is_audible() {
+                                            crate::platform::play_bell();
+                                        }
+                                    }
+                                    for hit in active_tab.take_trigger_hits() {
+                                        super::triggers::fire(&hit);
+                                    }
+                                    for finished in active_tab.take_finished_commands() {
+                                        super::command_alerts::command_finished(&finished, &config.command_alerts, &window, hidden);
+                                    }
+                                    // Animate the visual bell and copy pulse until they have faded
+                                    let flashing = {
+                                        let renderer = renderer.lock();
+                                        renderer.is_bell_flashing() || renderer.is_copy_flashing()
+                                    };
+                                    if flashing && !hidden {
+                                        window.request_redraw();
+                                        elwt.set_control_flow(ControlFlow::WaitUntil(
+                                            Instant::now() + BELL_FRAME_INTERVAL,
+                                        ));
+                                    }
+                                    // Coast after a trackpad flick when momentum is enabled
+                                    if !hidden && renderer.lock().tick_scroll_momentum() {
+                                        window.request_redraw();
+                                        elwt.set_control_flow(ControlFlow::WaitUntil(
+                                            Instant::now() + SCROLL_FRAME_INTERVAL,
+                                        ));
+                                    }
+                                    // Drop predictions whose echo never comes
+                                    if active_tab.has_predictions() {
+                                        elwt.set_control_flow(ControlFlow::WaitUntil(
+                                            Instant::now() + ECHO_POLL_INTERVAL,
+                                        ));
+                                    }
+                                    // Keep polling until startup commands see a ready shell
+                                    if active_tab.has_pending_startup() {
+                                        elwt.set_control_flow(ControlFlow::WaitUntil(
+                                            Instant::now() + STARTUP_POLL_INTERVAL,
+                                        ));
+                                    }
                                 }
                                 Err(e) => {
                                     log::error!("Error processing output: {}", e);
@@ -140,13 +548,48 @@ impl App {
                             log::warn!("No active tab found");
                         }
                     }
+
+                    // Draw the echo of a keypress now (low latency mode), once the tabs are unlocked
+                    if echo_arrived {
+                        awaiting_echo = false;
+                        super::window::handle_redraw(
+                            &renderer,
+                            &tab_manager,
+                            &window,
+                            &search_state,
+                            &command_history,
+                            &history_search,
+                            &clipboard_history,
+                            &snippet_picker,
+                            privacy_lock.lock().is_locked(),
+                        );
+                    }
+
+                    if windows.process_output(&mut command_history, &config) {
+                        elwt.set_control_flow(ControlFlow::Poll);
+                    }
                 }
 
                 Event::WindowEvent {
                     event: WindowEvent::RedrawRequested,
                     ..
                 } => {
-                    super::window::handle_redraw(&renderer, &tab_manager, &window);
+                    // Showing the window asks for a frame again
+                    if !dropdown.lock().is_visible() {
+                        return;
+                    }
+                    let content_hidden = privacy_lock.lock().is_locked();
+                    super::window::handle_redraw(
+                        &renderer,
+                        &tab_manager,
+                        &window,
+                        &search_state,
+                        &command_history,
+                        &history_search,
+                        &clipboard_history,
+                        &snippet_picker,
+                        content_hidden,
+                    );
                 }
 
                 _ => {}
@@ -158,3 +601,47 @@ impl App {
         Ok(())
     }
 }
+
+/// Handle what the tabs out of view queued since the last poll
+///
+/// Their output isn't drawn, but captured commands, triggers, command alerts
+/// and remote-control events shouldn't pile up until the tab is shown again.
+pub(super) fn drain_background_tabs(
+    tab_mgr: &mut TabManager,
+    window: &Window,
+    command_history: &mut CommandHistory,
+    config: &Config,
+    server: Option<&IpcServer>,
+) {
+    let tap_output = server.is_some_and(|server| server.is_subscribed(EventKind::Output));
+    let mut history_changed = false;
+    for tab in tab_mgr.background_tabs_mut() {
+        tab.set_output_tap(tap_output);
+        if let Err(e) = tab.process_output() {
+            log::error!("Error processing output: {}", e);
+        }
+        if let Some(server) = server {
+            for event in tab.take_ipc_events() {
+                server.publish(&event);
+            }
+        }
+        for command in tab.take_captured_commands() {
+            history_changed |= command_history.push(&command);
+        }
+        for hit in tab.take_trigger_hits() {
+            super::triggers::fire(&hit);
+        }
+        for finished in tab.take_finished_commands() {
+            super::command_alerts::command_finished(&finished, &config.command_alerts, window, true);
+        }
+        // Nothing to flash in a tab out of view
+        if !tab.take_bells().is_empty() && config.bell.mode.is_audible() {
+            crate::platform::play_bell();
+        }
+    }
+    if history_changed {
+        if let Err(e) = command_history.save() {
+            log::warn!("Failed to save command history: {}", e);
+        }
+    }
+}
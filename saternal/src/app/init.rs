@@ -1,24 +1,66 @@
 use super::App;
+use crate::platform::{Clipboard, DropdownWindow, HotkeyManager};
 use anyhow::Result;
-use cocoa::base::id;
 use log::info;
-use objc::{msg_send, sel, sel_impl};
 use parking_lot::Mutex;
-use saternal_core::{Clipboard, Renderer, SearchState, SelectionManager, MouseState};
-use saternal_macos::{DropdownWindow, HotkeyManager};
+use saternal_core::{ClipboardHistory, CommandHistory, DropdownSurface, HistorySearch, IpcServer, SearchState, SelectionManager, SnippetPicker, MouseState, PrivacyLock, WindowMode};
+use saternal_core::update::Updater;
+#[cfg(target_os = "macos")]
+use saternal_macos::{PinStatusItem, ScreenLockMonitor};
+use saternal_renderer::Renderer;
 use std::sync::Arc;
-use winit::{
-    event_loop::EventLoop,
-    raw_window_handle::{HasWindowHandle, RawWindowHandle},
-    window::WindowBuilder,
-};
+use winit::{dpi::PhysicalSize, window::WindowBuilder};
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+use winit::event_loop::EventLoop;
+
+/// Apply the config settings that Renderer::new doesn't take
+pub(super) fn apply_renderer_settings(renderer: &mut Renderer, config: &saternal_core::Config) -> Result<()> {
+    renderer.set_blur_strength(config.appearance.blur_strength);
+    renderer.set_wallpaper_mode(config.appearance.wallpaper_mode)?;
+    renderer.set_antialias(config.appearance.antialias);
+    renderer.set_inactive_pane_dim(config.appearance.inactive_pane_dim);
+    renderer.set_blinking(config.appearance.blinking);
+    renderer.set_pane_headers(config.appearance.pane_headers);
+    renderer.set_padding(config.window.effective_padding());
+    renderer.set_window_frame(super::window::window_frame(&config.window));
+    renderer.set_default_badge(config.appearance.badge.as_deref());
+    renderer.set_ssh_badge(config.appearance.ssh_badge);
+    renderer.set_command_durations(config.appearance.command_durations);
+    renderer.set_autosuggestions(config.terminal.autosuggestions);
+    renderer.set_selection_style(config.selection.style);
+    renderer.set_pane_borders(config.panes.border);
+    let scrolling = &config.scrolling;
+    renderer.set_scrolling(scrolling.multiplier, scrolling.momentum, scrolling.deceleration);
+    renderer.set_spacing(config.appearance.line_height, config.appearance.letter_spacing);
+    renderer.set_pixel_snap(config.appearance.pixel_snap);
+    renderer.set_low_latency(config.appearance.low_latency);
+    renderer.set_color_space(config.appearance.output_color_space());
+    // A broken shader shouldn't keep the window from opening; `config reload` reports it
+    let post_process = config.appearance.post_process_source().and_then(|source| {
+        renderer.set_post_process(source.as_deref(), config.appearance.post_process_animate)
+    });
+    if let Err(e) = post_process {
+        log::error!("Post-process shader not loaded: {:#}", e);
+    }
+    Ok(())
+}
 
 impl App {
     /// Create a new application
     pub async fn new(config: saternal_core::Config) -> Result<Self> {
         info!("Initializing application");
 
+        #[cfg(any(target_os = "macos", target_os = "windows"))]
         let event_loop = EventLoop::new()?;
+        #[cfg(target_os = "linux")]
+        let event_loop = saternal_linux::event_loop()?;
+
+        // Parser threads wake the event loop with an empty user event when
+        // output arrives (the proxy is Send but not Sync, hence the lock)
+        let output_proxy = Mutex::new(event_loop.create_proxy());
+        saternal_core::output_parser::set_waker(move || {
+            let _ = output_proxy.lock().send_event(());
+        });
 
         #[cfg(target_os = "macos")]
         unsafe {
@@ -34,20 +76,15 @@ impl App {
 
         let window = Arc::new(window);
 
-        let dropdown = DropdownWindow::new();
-        let (window_width, window_height, window_scale_factor) = unsafe {
-            if let Ok(handle) = window.window_handle() {
-                if let RawWindowHandle::AppKit(appkit_handle) = handle.as_raw() {
-                    let ns_view = appkit_handle.ns_view.as_ptr() as id;
-                    let ns_window: id = msg_send![ns_view, window];
-                    dropdown.configure_window(ns_window, ns_view, config.window.height_percentage)?
-                } else {
-                    return Err(anyhow::anyhow!("Failed to get AppKit window handle"));
-                }
-            } else {
-                return Err(anyhow::anyhow!("Failed to get window handle"));
-            }
-        };
+        let mut dropdown = DropdownWindow::new();
+        dropdown.set_opacity(config.window.opacity, config.window.glass_opacity);
+        dropdown.set_spaces(config.window.can_join_all_spaces, config.window.appear_over_fullscreen);
+        let (window_width, window_height, window_scale_factor) = dropdown.configure(
+            &window,
+            config.window.mode,
+            config.window.width_percentage,
+            config.window.height_percentage,
+        )?;
         let dropdown = Arc::new(Mutex::new(dropdown));
 
         let mut renderer = Renderer::new(
@@ -59,11 +96,11 @@ impl App {
             config.appearance.wallpaper_path.as_deref(),
             config.appearance.wallpaper_opacity,
             config.appearance.opacity,
+            &config.renderer,
         )
         .await?;
 
-        // Apply blur strength from config
-        renderer.set_blur_strength(config.appearance.blur_strength);
+        apply_renderer_settings(&mut renderer, &config)?;
 
         // Apply DPI scale from the window's screen (or override if configured)
         let effective_scale = config.appearance.dpi_scale_override.unwrap_or(window_scale_factor);
@@ -72,103 +109,206 @@ impl App {
                   effective_scale, window.scale_factor());
             renderer.handle_scale_factor_changed(effective_scale)?;
         }
-        
+        renderer.set_display_scale_factors(super::window::display_scale_factors(&window, &config));
+        let (_, cell_height, _) = renderer.font_manager().cell_metrics();
+        super::window::snap_dropdown_height(&window, cell_height, &renderer.padding(), &dropdown.lock(), &config);
+
         // Calculate terminal dimensions from the actual window dimensions (physical pixels)
         let physical_size = window.inner_size();
-        let effective_size = renderer.font_manager().effective_font_size();
-        let line_metrics = renderer.font_manager().font().horizontal_line_metrics(effective_size).unwrap();
-        let cell_width = renderer.font_manager().font().metrics('M', effective_size).advance_width;
-        let cell_height = (line_metrics.ascent - line_metrics.descent + line_metrics.line_gap).ceil();
+        let (cell_width, cell_height, _) = renderer.font_manager().cell_metrics();
         let (initial_cols, initial_rows) = Self::calculate_terminal_size(
             physical_size.width,
             physical_size.height,
             cell_width,
-            cell_height
+            cell_height,
+            &renderer.padding(),
         );
         info!("Calculated initial terminal size: {}x{} for window {}x{} (scale: {:.2}x)",
               initial_cols, initial_rows, physical_size.width, physical_size.height, effective_scale);
         
+        let font_warning = renderer.font_manager().fallback_warning().map(str::to_string);
         let renderer = Arc::new(Mutex::new(renderer));
 
-        info!("Configuring Metal layer for rendering and backdrop blur");
-        unsafe {
-            if let Ok(handle) = window.window_handle() {
-                if let RawWindowHandle::AppKit(appkit_handle) = handle.as_raw() {
-                    let ns_view = appkit_handle.ns_view.as_ptr() as id;
-                    dropdown.lock().enable_vibrancy_layer(ns_view)?;
+        // Transparency for the Metal layer on macOS
+        dropdown.lock().surface_created(&window);
+        dropdown.lock().set_color_space(&window, config.appearance.output_color_space());
 
-                }
-            }
-        }
-
-        let tab_manager = crate::tab::TabManager::new_with_size(
+        let mut tab_manager = crate::tab::TabManager::new_with_size(
             config.terminal.shell.clone(),
             initial_cols,
             initial_rows
         )?;
+
+        // Let the user know their configured font wasn't found
+        if let (Some(warning), Some(tab)) = (font_warning, tab_manager.active_tab_mut()) {
+            tab.show_warning(&warning);
+        }
+
+        tab_manager.set_pane_headers(config.appearance.pane_headers)?;
+        tab_manager.set_confirm_close(config.terminal.confirm_close);
+        tab_manager.set_confirm_password_paste(config.terminal.confirm_password_paste);
+        tab_manager.set_paste_cleanup(config.clipboard.paste_transforms(), config.clipboard.confirm_cleanup);
+        tab_manager.set_local_echo(config.terminal.local_echo);
+        tab_manager.set_triggers(&config.triggers);
+        tab_manager.set_palette(config.appearance.palette);
+        tab_manager.set_auto_log(&config.logging);
+
+        // Auto-launch the configured startup command in the first tab and every new one
+        tab_manager.set_startup_command(config.terminal.startup_command.clone());
+        if let (Some(command), Some(tab)) = (tab_manager.startup_command(), tab_manager.active_tab_mut()) {
+            tab.run_when_ready(&command);
+        }
         let tab_manager = Arc::new(Mutex::new(tab_manager));
 
+        let privacy_lock = Arc::new(Mutex::new(PrivacyLock::default()));
+
         let window_clone = window.clone();
+        let privacy_lock_clone = privacy_lock.clone();
         let dropdown_clone = dropdown.clone();
         let renderer_clone = renderer.clone();
         let tab_manager_clone = tab_manager.clone();
-        let hotkey_manager = HotkeyManager::new(move || {
+        let dpi_scale_override = config.appearance.dpi_scale_override;
+        let toggle = move || {
             info!("Hotkey triggered!");
-            let mut dropdown = dropdown_clone.lock();
-            unsafe {
-                if let Ok(handle) = window_clone.window_handle() {
-                    if let RawWindowHandle::AppKit(appkit_handle) = handle.as_raw() {
-                        let ns_view = appkit_handle.ns_view.as_ptr() as id;
-                        let ns_window: id = msg_send![ns_view, window];
-                        
-                        match dropdown.toggle(ns_window) {
-                            Ok(maybe_dimensions) => {
-                                // ALWAYS check actual window size when hotkey is pressed
-                                // The window size might have changed without toggle() detecting it
-                                let size = window_clone.inner_size();
-                                info!("Hotkey pressed - checking window size: {}x{}", size.width, size.height);
-
-                                if let Some(mut renderer_lock) = renderer_clone.try_lock() {
-                                    // CRITICAL: Update renderer dimensions first (like handle_resize)
-                                    // This ensures padding calculations use current window size
-                                    renderer_lock.resize(size.width, size.height);
-
-                                    let fm = renderer_lock.font_manager();
-                                    let effective_size = fm.effective_font_size();
-                                    let line_metrics = fm.font().horizontal_line_metrics(effective_size).unwrap();
-                                    let cell_width = fm.font().metrics('M', effective_size).advance_width;
-                                    let cell_height = (line_metrics.ascent - line_metrics.descent + line_metrics.line_gap).ceil();
-
-                                    let (cols, rows) = App::calculate_terminal_size(size.width, size.height, cell_width, cell_height);
-                                    info!("Resizing terminal to {}x{} for window {}x{}", cols, rows, size.width, size.height);
-                                    drop(renderer_lock);
-
-                                    if let Some(mut tab_mgr) = tab_manager_clone.try_lock() {
-                                        if let Some(active_tab) = tab_mgr.active_tab_mut() {
-                                            if let Err(e) = active_tab.resize(cols, rows) {
-                                                log::error!("Failed to resize terminal: {}", e);
-                                            }
-                                        }
-                                    }
-                                }
-
-                                window_clone.request_redraw();
-                            }
-                            Err(e) => {
-                                log::error!("Failed to toggle window: {}", e);
-                            }
-                        }
-                    }
+            // Hotkey is the first half of the reveal gesture when privacy-locked;
+            // the dropdown stays open so the next keypress can unlock it
+            {
+                let mut privacy_lock = privacy_lock_clone.lock();
+                if privacy_lock.is_locked() && dropdown_clone.lock().is_visible() {
+                    privacy_lock.arm_reveal();
+                    window_clone.request_redraw();
+                    return;
                 }
             }
-        })?;
+            match dropdown_clone.lock().toggle(&window_clone) {
+                Ok(dimensions) => {
+                    // Refit when the dropdown moved to another screen; otherwise
+                    // check the window anyway, its size may have changed meanwhile
+                    let (size, scale_factor) = match dimensions {
+                        Some((width, height, scale_factor)) => (
+                            PhysicalSize::new(width, height),
+                            dpi_scale_override.unwrap_or(scale_factor),
+                        ),
+                        None => (
+                            window_clone.inner_size(),
+                            dpi_scale_override.unwrap_or(window_clone.scale_factor()),
+                        ),
+                    };
+                    super::window::resize_to(size, scale_factor, &renderer_clone, &tab_manager_clone);
+                    window_clone.request_redraw();
+                }
+                Err(e) => {
+                    log::error!("Failed to toggle window: {}", e);
+                }
+            }
+        };
+        // Presses arrive as messages on the event loop's own thread
+        #[cfg(any(target_os = "macos", target_os = "windows"))]
+        let mut hotkey_manager = HotkeyManager::new(toggle)?;
+        // Presses arrive on other threads there; wake the loop to handle them
+        #[cfg(target_os = "linux")]
+        let mut hotkey_manager = {
+            let proxy = Mutex::new(event_loop.create_proxy());
+            HotkeyManager::new(toggle, move || {
+                let _ = proxy.lock().send_event(());
+            })?
+        };
+
+        // Cmd+Shift+` - click-through glass mode
+        let window_clone = window.clone();
+        let dropdown_clone = dropdown.clone();
+        if let Err(e) = hotkey_manager.register_glass_toggle(move || {
+            dropdown_clone.lock().toggle_glass(&window_clone);
+            window_clone.request_redraw();
+        }) {
+            log::warn!("Glass mode unavailable: {}", e);
+        }
         let hotkey_manager = Arc::new(hotkey_manager);
 
+        super::window::settle_size(&renderer, &tab_manager, &window, &config);
+
+        // Regular windows open right away; the dropdown waits for the hotkey
+        if config.window.mode != WindowMode::Dropdown {
+            dropdown.lock().toggle(&window)?;
+        }
+
         let font_size = config.appearance.font_size;
-        let selection_manager = SelectionManager::new();
+        let mut selection_manager = SelectionManager::new();
+        selection_manager.set_word_chars(&config.selection.word_chars);
         let clipboard = Clipboard::new()?;
-        let search_state = SearchState::new();
+        let search_state = SearchState::with_options(config.search);
         let mouse_state = MouseState::new();
+        let command_history = if config.terminal.persist_history {
+            CommandHistory::default_path()
+                .map(CommandHistory::load)
+                .transpose()
+                .unwrap_or_else(|e| {
+                    log::warn!("Failed to load command history: {}", e);
+                    None
+                })
+                .unwrap_or_default()
+        } else {
+            CommandHistory::default()
+        };
+        let history_size = config.clipboard.history_size;
+        let clipboard_history = if config.clipboard.persist_history {
+            ClipboardHistory::default_path()
+                .map(|path| ClipboardHistory::load(path, history_size))
+                .transpose()
+                .unwrap_or_else(|e| {
+                    log::warn!("Failed to load clipboard history: {}", e);
+                    None
+                })
+                .unwrap_or_else(|| ClipboardHistory::new(history_size))
+        } else {
+            ClipboardHistory::new(history_size)
+        };
+        #[cfg(target_os = "macos")]
+        let screen_lock_monitor = if config.privacy.lock_on_screen_lock {
+            Some(unsafe { ScreenLockMonitor::start() })
+        } else {
+            None
+        };
+
+        #[cfg(target_os = "macos")]
+        let pin_status_item = if config.window.mode == WindowMode::Dropdown && config.window.hide_on_focus_loss {
+            let proxy = Mutex::new(event_loop.create_proxy());
+            Some(unsafe {
+                PinStatusItem::new(move || {
+                    let _ = proxy.lock().send_event(());
+                })
+            })
+        } else {
+            None
+        };
+
+        // Scripts drive the running instance through this socket; a
+        // request wakes the event loop with an empty user event
+        let proxy = event_loop.create_proxy();
+        let ipc_server = IpcServer::start(&saternal_core::ipc::socket_path(), move || {
+            let _ = proxy.send_event(());
+        })
+        .map_err(|e| log::warn!("Remote control unavailable: {}", e))
+        .ok();
+
+        // Finished checks wake the event loop to show what they found
+        let updater = saternal_core::update::cache_dir().map(|dir| {
+            let proxy = Mutex::new(event_loop.create_proxy());
+            Updater::start(&config.updates, dir, move || {
+                let _ = proxy.lock().send_event(());
+            })
+        });
+
+        // Edited shaders wake the event loop to rebuild the pipelines
+        #[cfg(feature = "shader-hot-reload")]
+        let shader_watcher = {
+            let proxy = Mutex::new(event_loop.create_proxy());
+            saternal_renderer::ShaderWatcher::start(move || {
+                let _ = proxy.lock().send_event(());
+            })
+            .map_err(|e| log::warn!("Shader hot reload unavailable: {}", e))
+            .ok()
+        };
 
         Ok(Self {
             config,
@@ -181,8 +321,21 @@ impl App {
             font_size,
             selection_manager,
             clipboard,
+            clipboard_history,
             search_state,
+            command_history,
+            history_search: HistorySearch::new(),
+            snippet_picker: SnippetPicker::new(),
             mouse_state,
+            privacy_lock,
+            #[cfg(target_os = "macos")]
+            screen_lock_monitor,
+            #[cfg(target_os = "macos")]
+            pin_status_item,
+            ipc_server,
+            updater,
+            #[cfg(feature = "shader-hot-reload")]
+            shader_watcher,
         })
     }
 }
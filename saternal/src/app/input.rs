@@ -1,12 +1,21 @@
 use alacritty_terminal::grid::Dimensions;
 use alacritty_terminal::index::Column;
+use alacritty_terminal::term::TermMode;
 use log::info;
+use crate::platform::DropdownWindow;
 use parking_lot::Mutex;
 use saternal_core::{
-    Config, InputModifiers, Renderer, SearchState, SelectionManager, SplitDirection,
-    is_jump_to_bottom, key_to_bytes,
+    ClipboardHistory, CloseTarget, CommandHistory, Config, DropdownSurface, FeedbackKind, HistorySearch, InputModifiers, PrivacyLock, SearchState, SelectionManager, SnippetInsert,
+    SnippetPicker, SplitDirection,
+    is_jump_to_bottom, key_to_bytes, scroll_action, ScrollAction,
 };
-use saternal_macos::DropdownWindow;
+use saternal_core::output_parser::TERM_LOCK_TIMEOUT;
+use saternal_core::pane::FONT_SCALE_STEP;
+use saternal_core::theme::THEME_NAMES;
+use saternal_core::wallpaper::WallpaperOverride;
+use saternal_core::ColorPalette;
+use saternal_renderer::screenshot::default_screenshot_path;
+use saternal_renderer::Renderer;
 use std::sync::Arc;
 use winit::{
     event::{ElementState, KeyEvent, Modifiers},
@@ -22,18 +31,83 @@ pub(super) fn handle_keyboard_input(
     tab_manager: &Arc<Mutex<crate::tab::TabManager>>,
     selection_manager: &mut SelectionManager,
     search_state: &mut SearchState,
+    command_history: &CommandHistory,
+    history_search: &mut HistorySearch,
+    clipboard_history: &mut ClipboardHistory,
+    snippet_picker: &mut SnippetPicker,
     config: &mut Config,
     font_size: &mut f32,
     window: &winit::window::Window,
     dropdown: &Arc<Mutex<DropdownWindow>>,
+    privacy_lock: &Arc<Mutex<PrivacyLock>>,
 ) -> bool {
     if state != ElementState::Pressed {
         return false;
     }
 
+    // While privacy-locked, keys never reach the terminal
+    if privacy_lock.lock().handle_keypress() {
+        window.request_redraw();
+        return true;
+    }
+
+    // Typing holds the cursor solid and restarts its idle timeout
+    if renderer.lock().restart_cursor_blink() {
+        window.request_redraw();
+    }
+
     let cmd = modifiers_state.state().super_key();
     let shift = modifiers_state.state().shift_key();
     let ctrl = modifiers_state.state().control_key();
+    let alt = modifiers_state.state().alt_key();
+
+    // Cmd+Shift+L - Hide pane content until hotkey + keypress
+    if cmd && shift && event.physical_key == PhysicalKey::Code(KeyCode::KeyL) {
+        info!("Privacy lock engaged (Cmd+Shift+L)");
+        privacy_lock.lock().lock();
+        window.request_redraw();
+        return true;
+    }
+
+    // While history search is open it owns the keyboard
+    if history_search.is_active() {
+        return handle_history_input(event, command_history, history_search, tab_manager);
+    }
+
+    // While the clipboard picker is open it owns the keyboard
+    if clipboard_history.is_picking() {
+        return handle_clipboard_picker_input(event, clipboard_history, tab_manager, renderer, window);
+    }
+
+    // While the snippet picker is open it owns the keyboard
+    if snippet_picker.is_active() {
+        return handle_snippet_input(event, shift, snippet_picker, tab_manager, renderer, window);
+    }
+
+    // Cmd+; - Pick a snippet to insert
+    if cmd && !shift && event.physical_key == PhysicalKey::Code(KeyCode::Semicolon) {
+        if snippet_picker.open(&config.snippets) {
+            info!("Snippet picker opened (Cmd+;), {} snippets", config.snippets.len());
+        } else if let Some(active_tab) = tab_manager.lock().active_tab_mut() {
+            active_tab.print_feedback(FeedbackKind::Info, "No snippets defined ([snippets] in config.toml)");
+        }
+        return true;
+    }
+
+    // Cmd+Shift+V - Pick an older copy to paste
+    if cmd && shift && event.physical_key == PhysicalKey::Code(KeyCode::KeyV) {
+        if clipboard_history.open_picker() {
+            info!("Clipboard picker opened (Cmd+Shift+V), {} entries", clipboard_history.len());
+        }
+        return true;
+    }
+
+    // Cmd+R - Fuzzy search over commands captured from every pane
+    if cmd && !shift && event.physical_key == PhysicalKey::Code(KeyCode::KeyR) {
+        info!("History search activated (Cmd+R), {} commands", command_history.len());
+        history_search.activate(command_history);
+        return true;
+    }
 
     // Handle Escape key for UI operations (search/selection)
     // Only intercept if search is active or selection exists
@@ -44,6 +118,20 @@ pub(super) fn handle_keyboard_input(
         // Otherwise, let it fall through to terminal input below
     }
 
+    // While the find field is open, editing keys go to the query (like a native find bar)
+    if search_state.is_active()
+        && !cmd
+        && !ctrl
+        && handle_search_input(event, shift, alt, search_state, selection_manager, tab_manager, renderer)
+    {
+        return true;
+    }
+
+    // Keyboard scrollback navigation (full-screen apps get these keys instead)
+    if handle_scroll_keys(event, modifiers_state, tab_manager, renderer, window) {
+        return true;
+    }
+
     // Pane navigation removed from Ctrl+Tab (conflicts with system shortcuts)
     // Now handled by Cmd+Shift+[ and Cmd+Shift+] below
 
@@ -52,13 +140,16 @@ pub(super) fn handle_keyboard_input(
         return handle_cmd_shortcuts(
             event,
             shift,
+            alt,
             tab_manager,
             selection_manager,
+            clipboard_history,
             search_state,
             config,
             font_size,
             renderer,
             window,
+            dropdown,
         );
     }
 
@@ -71,8 +162,15 @@ pub(super) fn handle_keyboard_input(
         }
     }
 
+    // Right Arrow at the end of the input takes the autosuggestion
+    if !shift && !ctrl && !alt && matches!(event.logical_key, Key::Named(winit::keyboard::NamedKey::ArrowRight))
+        && super::autosuggest::accept(tab_manager, command_history, renderer, window)
+    {
+        return true;
+    }
+
     // Handle terminal input
-    handle_terminal_input(event, modifiers_state, tab_manager, renderer, window, dropdown)
+    handle_terminal_input(event, modifiers_state, tab_manager, renderer, window, dropdown, snippet_picker, config, font_size)
 }
 
 fn handle_escape(
@@ -82,6 +180,10 @@ fn handle_escape(
     tab_manager: &Arc<Mutex<crate::tab::TabManager>>,
 ) -> bool {
     if search_state.is_active() {
+        // The scroll position counted filtered lines; go back to the live view
+        if search_state.is_filtering() {
+            renderer.lock().reset_scroll();
+        }
         search_state.deactivate();
         info!("Search deactivated");
         return true;
@@ -89,20 +191,140 @@ fn handle_escape(
     if selection_manager.range().is_some() {
         selection_manager.clear();
         let (grid_cols, grid_lines) = super::mouse::get_grid_dimensions(tab_manager);
-        renderer.lock().update_selection(None, grid_cols, grid_lines);
+        renderer.lock().update_selection(&[], grid_cols, grid_lines);
         info!("Selection cleared");
         return true;
     }
     false
 }
 
+/// Keys while history search is open: type to filter, arrows to pick,
+/// Enter inserts the pick at the prompt without running it, Escape cancels
+fn handle_history_input(
+    event: &KeyEvent,
+    command_history: &CommandHistory,
+    history_search: &mut HistorySearch,
+    tab_manager: &Arc<Mutex<crate::tab::TabManager>>,
+) -> bool {
+    use winit::keyboard::NamedKey;
+
+    match &event.logical_key {
+        Key::Named(NamedKey::Escape) => {
+            history_search.deactivate();
+            info!("History search cancelled");
+        }
+        Key::Named(NamedKey::Enter) => {
+            if let Some(command) = history_search.selected() {
+                let command = command.to_string();
+                if let Err(e) = with_active_tab(tab_manager, |tab| tab.write_input(command.as_bytes())) {
+                    log::error!("Failed to insert history command: {}", e);
+                }
+            }
+            history_search.deactivate();
+        }
+        Key::Named(NamedKey::ArrowUp) => history_search.select_prev(),
+        Key::Named(NamedKey::ArrowDown) => history_search.select_next(),
+        Key::Named(NamedKey::Backspace) => history_search.delete_backward(command_history),
+        _ => {
+            if let Some(text) = event.text.as_ref().filter(|t| !t.chars().any(char::is_control)) {
+                history_search.insert_text(text, command_history);
+            }
+        }
+    }
+    true
+}
+
+/// Keys while the clipboard picker is open: arrows to pick,
+/// Enter pastes the pick, Escape cancels
+fn handle_clipboard_picker_input(
+    event: &KeyEvent,
+    clipboard_history: &mut ClipboardHistory,
+    tab_manager: &Arc<Mutex<crate::tab::TabManager>>,
+    renderer: &Arc<Mutex<Renderer>>,
+    window: &winit::window::Window,
+) -> bool {
+    use winit::keyboard::NamedKey;
+
+    match &event.logical_key {
+        Key::Named(NamedKey::Escape) => {
+            clipboard_history.close_picker();
+            info!("Clipboard picker cancelled");
+        }
+        Key::Named(NamedKey::Enter) => {
+            if let Some((_, entry)) = clipboard_history.picked() {
+                let entry = entry.to_string();
+                super::clipboard::paste_text(&entry, tab_manager, renderer, window);
+            }
+            clipboard_history.close_picker();
+        }
+        Key::Named(NamedKey::ArrowUp) => clipboard_history.picker_prev(),
+        Key::Named(NamedKey::ArrowDown) => clipboard_history.picker_next(),
+        _ => {}
+    }
+    true
+}
+
+/// Keys while the snippet picker is open: type to filter, arrows to pick, Enter
+/// takes it; then type each placeholder, Tab or Enter moves on, Shift+Tab goes back
+fn handle_snippet_input(
+    event: &KeyEvent,
+    shift: bool,
+    snippet_picker: &mut SnippetPicker,
+    tab_manager: &Arc<Mutex<crate::tab::TabManager>>,
+    renderer: &Arc<Mutex<Renderer>>,
+    window: &winit::window::Window,
+) -> bool {
+    use winit::keyboard::NamedKey;
+
+    match &event.logical_key {
+        Key::Named(NamedKey::Escape) => {
+            snippet_picker.close();
+            info!("Snippet picker cancelled");
+        }
+        Key::Named(NamedKey::Tab) if shift => snippet_picker.back(),
+        Key::Named(NamedKey::Enter | NamedKey::Tab) => {
+            if let Some(insert) = snippet_picker.advance() {
+                insert_snippet(&insert, tab_manager, renderer, window);
+            }
+        }
+        Key::Named(NamedKey::ArrowUp) => snippet_picker.select_prev(),
+        Key::Named(NamedKey::ArrowDown) => snippet_picker.select_next(),
+        Key::Named(NamedKey::Backspace) => snippet_picker.delete_backward(),
+        _ => {
+            if let Some(text) = event.text.as_ref().filter(|t| !t.chars().any(char::is_control)) {
+                snippet_picker.insert_text(text);
+            }
+        }
+    }
+    true
+}
+
+/// Send a filled-in snippet to the focused pane as a paste, so it isn't run until Enter
+fn insert_snippet(
+    insert: &SnippetInsert,
+    tab_manager: &Arc<Mutex<crate::tab::TabManager>>,
+    renderer: &Arc<Mutex<Renderer>>,
+    window: &winit::window::Window,
+) {
+    // Ctrl+U takes the `snippet <name>` command back off the shell's line
+    if insert.replace_line {
+        if let Err(e) = with_active_tab(tab_manager, |tab| tab.write_input(b"\x15")) {
+            log::error!("Failed to clear the command line: {}", e);
+        }
+    }
+    info!("Inserting snippet ({} chars)", insert.text.len());
+    super::clipboard::paste_text(&insert.text, tab_manager, renderer, window);
+}
+
 fn handle_pane_navigation(
     previous: bool,
     tab_manager: &Arc<Mutex<crate::tab::TabManager>>,
     window: &winit::window::Window,
 ) -> bool {
     if let Some(active_tab) = tab_manager.lock().active_tab_mut() {
-        if previous {
+        if active_tab.pane_tree.focus_locked() {
+            info!("Input is locked to the focused pane (Cmd+Shift+I to unlock)");
+        } else if previous {
             active_tab.pane_tree.focus_prev();
             info!("Focus moved to previous pane (Cmd+Shift+[)");
         } else {
@@ -114,34 +336,51 @@ fn handle_pane_navigation(
     true
 }
 
+fn handle_pane_layout(
+    tab_manager: &Arc<Mutex<crate::tab::TabManager>>,
+    window: &winit::window::Window,
+    f: impl FnOnce(&mut crate::tab::Tab) -> anyhow::Result<()>,
+) -> bool {
+    if let Err(e) = with_active_tab(tab_manager, f) {
+        log::error!("Failed to update pane layout: {}", e);
+    }
+    window.request_redraw();
+    true
+}
+
 fn handle_cmd_shortcuts(
     event: &KeyEvent,
     shift: bool,
+    alt: bool,
     tab_manager: &Arc<Mutex<crate::tab::TabManager>>,
     selection_manager: &mut SelectionManager,
+    clipboard_history: &mut ClipboardHistory,
     search_state: &mut SearchState,
     config: &mut Config,
     font_size: &mut f32,
     renderer: &Arc<Mutex<Renderer>>,
     window: &winit::window::Window,
+    dropdown: &Arc<Mutex<DropdownWindow>>,
 ) -> bool {
     if let PhysicalKey::Code(keycode) = event.physical_key {
         match keycode {
             KeyCode::KeyC => {
-                super::clipboard::handle_copy(tab_manager, selection_manager);
+                super::clipboard::handle_copy(tab_manager, selection_manager, clipboard_history, renderer, window);
                 return true;
             }
             KeyCode::KeyV => {
-                super::clipboard::handle_paste(tab_manager, renderer, window);
+                super::clipboard::handle_paste(alt, tab_manager, renderer, window);
                 return true;
             }
             KeyCode::KeyF => {
                 info!("Search activated (Cmd+F)");
-                search_state.activate();
+                with_focused_grid(tab_manager, |pane_id, grid| {
+                    search_state.activate_for_pane(pane_id, grid);
+                });
                 return true;
             }
             KeyCode::KeyG => {
-                return handle_search_navigation(shift, search_state, tab_manager);
+                return handle_search_navigation(shift, search_state, tab_manager, renderer);
             }
             KeyCode::BracketLeft => {
                 // Cmd+Shift+[ - Navigate to previous pane
@@ -155,6 +394,49 @@ fn handle_cmd_shortcuts(
                     return handle_pane_navigation(false, tab_manager, window);
                 }
             }
+            KeyCode::KeyS if shift => {
+                info!("Swapping focused pane (Cmd+Shift+S)");
+                return handle_pane_layout(tab_manager, window, |tab| tab.swap_focused_pane());
+            }
+            KeyCode::KeyR if shift => {
+                info!("Rotating focused split (Cmd+Shift+R)");
+                return handle_pane_layout(tab_manager, window, |tab| tab.rotate_focused_split());
+            }
+            KeyCode::KeyE if shift => {
+                info!("Respawning focused pane (Cmd+Shift+E)");
+                let respawned = with_active_tab(tab_manager, |tab| {
+                    tab.respawn_focused_pane()?;
+                    tab.print_feedback(FeedbackKind::Success, "✓ Shell restarted");
+                    Ok(())
+                });
+                if let Err(e) = respawned {
+                    log::error!("Failed to respawn pane: {}", e);
+                }
+                renderer.lock().reset_scroll();
+                window.request_redraw();
+                return true;
+            }
+            KeyCode::KeyI if shift => {
+                if let Some(active_tab) = tab_manager.lock().active_tab_mut() {
+                    let locked = active_tab.toggle_focus_lock();
+                    info!("Input {} the focused pane (Cmd+Shift+I)", if locked { "locked to" } else { "unlocked from" });
+                }
+                window.request_redraw();
+                return true;
+            }
+            KeyCode::KeyP if shift => {
+                info!("Toggling dropdown pin (Cmd+Shift+P)");
+                dropdown.lock().toggle_pin();
+                return true;
+            }
+            KeyCode::KeyH if shift => {
+                super::window::cycle_height_preset(config, renderer, window, dropdown);
+                return true;
+            }
+            KeyCode::KeyB if shift => {
+                info!("Balancing panes (Cmd+Shift+B)");
+                return handle_pane_layout(tab_manager, window, |tab| tab.balance_panes());
+            }
             KeyCode::KeyD => {
                 info!("Splitting pane vertically (Cmd+D) - side by side");
                 if let Some(active_tab) = tab_manager.lock().active_tab_mut() {
@@ -172,14 +454,20 @@ fn handle_cmd_shortcuts(
         }
     }
 
+    // Cmd+Alt+= / - / 0 zoom only the focused pane
+    if alt && handle_pane_zoom(event, tab_manager, window) {
+        return true;
+    }
+
     // Font size adjustment
-    handle_font_size_shortcuts(event, config, font_size, renderer)
+    handle_font_size_shortcuts(event, config, font_size, renderer, tab_manager, window, dropdown)
 }
 
 fn handle_search_navigation(
     shift: bool,
     search_state: &mut SearchState,
     tab_manager: &Arc<Mutex<crate::tab::TabManager>>,
+    renderer: &Arc<Mutex<Renderer>>,
 ) -> bool {
     if !search_state.is_active() {
         return false;
@@ -187,7 +475,7 @@ fn handle_search_navigation(
 
     if let Some(tab_mgr) = tab_manager.try_lock() {
         if let Some(pane) = tab_mgr.active_tab().and_then(|tab| tab.pane_tree.focused_pane()) {
-            if let Some(term_lock) = pane.terminal.term().try_lock() {
+            if let Some(term_lock) = pane.terminal.term().try_lock_for(TERM_LOCK_TIMEOUT) {
                 let result = if shift {
                     search_state.prev_match(&term_lock.grid())
                 } else {
@@ -196,6 +484,17 @@ fn handle_search_navigation(
                 
                 if let Some(match_point) = result {
                     info!("Found match at {:?}", match_point);
+                    let grid = term_lock.grid();
+                    let mut renderer = renderer.lock();
+                    match search_state.line_filter(grid) {
+                        // Filtered: scroll the view of matching lines instead of the grid
+                        Some(filter) => {
+                            if let Some(line) = filter.view_line(match_point.line.0, grid.screen_lines()) {
+                                renderer.scroll_to_line(line, filter.max_offset(grid.screen_lines()), grid.screen_lines());
+                            }
+                        }
+                        None => renderer.scroll_to_line(match_point.line.0, grid.history_size(), grid.screen_lines()),
+                    }
                 }
             }
         }
@@ -203,11 +502,136 @@ fn handle_search_navigation(
     true
 }
 
+/// Handle keys typed into the find field
+/// Enter = next match, Shift+Enter = previous, Backspace edits, text extends the query
+/// Alt+R / Alt+C / Alt+W toggle regex, case sensitivity, and whole-word matching
+/// Alt+F toggles filter mode (only matching lines are shown)
+/// Alt+Enter selects the current match and Alt+A every match, closing the find field
+fn handle_search_input(
+    event: &KeyEvent,
+    shift: bool,
+    alt: bool,
+    search_state: &mut SearchState,
+    selection_manager: &mut SelectionManager,
+    tab_manager: &Arc<Mutex<crate::tab::TabManager>>,
+    renderer: &Arc<Mutex<Renderer>>,
+) -> bool {
+    use winit::keyboard::NamedKey;
+
+    // Match on the physical key: Option+letter produces symbols on macOS
+    if alt && matches!(event.physical_key, PhysicalKey::Code(KeyCode::Enter | KeyCode::KeyA)) {
+        let all = event.physical_key == PhysicalKey::Code(KeyCode::KeyA);
+        select_search_matches(all, search_state, selection_manager, tab_manager, renderer);
+        return true;
+    }
+    if alt && event.physical_key == PhysicalKey::Code(KeyCode::KeyF) {
+        search_state.toggle_filter();
+        info!("Search filter mode: {}", search_state.is_filtering());
+        // Both views start at their newest lines
+        renderer.lock().reset_scroll();
+        return true;
+    }
+    if alt {
+        let toggle: fn(&mut SearchState, &alacritty_terminal::grid::Grid<alacritty_terminal::term::cell::Cell>) =
+            match event.physical_key {
+                PhysicalKey::Code(KeyCode::KeyR) => SearchState::toggle_regex,
+                PhysicalKey::Code(KeyCode::KeyC) => SearchState::toggle_case_sensitive,
+                PhysicalKey::Code(KeyCode::KeyW) => SearchState::toggle_whole_word,
+                _ => return false,
+            };
+        with_focused_grid(tab_manager, |_, grid| toggle(search_state, grid));
+        info!("Search options: {:?}", search_state.options());
+        return true;
+    }
+
+    match &event.logical_key {
+        Key::Named(NamedKey::Enter) => handle_search_navigation(shift, search_state, tab_manager, renderer),
+        Key::Named(NamedKey::Backspace) => {
+            with_focused_grid(tab_manager, |_, grid| search_state.delete_backward(grid));
+            true
+        }
+        _ => match &event.text {
+            Some(text) if !text.chars().any(char::is_control) => {
+                with_focused_grid(tab_manager, |_, grid| search_state.insert_text(text, grid));
+                true
+            }
+            _ => false,
+        },
+    }
+}
+
+/// Turn the current search match (or all of them) into the selection, so Cmd+C copies it
+fn select_search_matches(
+    all: bool,
+    search_state: &mut SearchState,
+    selection_manager: &mut SelectionManager,
+    tab_manager: &Arc<Mutex<crate::tab::TabManager>>,
+    renderer: &Arc<Mutex<Renderer>>,
+) {
+    let mut dimensions = None;
+    with_focused_grid(tab_manager, |_, grid| {
+        let ranges = if all {
+            search_state.match_ranges(grid)
+        } else {
+            search_state.current_match_range(grid).into_iter().collect()
+        };
+        if !ranges.is_empty() {
+            info!("Selected {} search match(es)", ranges.len());
+            selection_manager.select_ranges(ranges);
+            dimensions = Some((grid.columns(), grid.screen_lines()));
+        }
+    });
+    if let Some((grid_cols, grid_lines)) = dimensions {
+        let mut renderer = renderer.lock();
+        if search_state.is_filtering() {
+            renderer.reset_scroll();
+        }
+        search_state.deactivate();
+        renderer.update_selection(&selection_manager.ranges(), grid_cols, grid_lines);
+    }
+}
+
+/// Run a closure against the focused pane's grid
+fn with_focused_grid(
+    tab_manager: &Arc<Mutex<crate::tab::TabManager>>,
+    f: impl FnOnce(usize, &alacritty_terminal::grid::Grid<alacritty_terminal::term::cell::Cell>),
+) {
+    let tab_mgr = tab_manager.lock();
+    if let Some(pane) = tab_mgr.active_tab().and_then(|tab| tab.pane_tree.focused_pane()) {
+        let term_arc = pane.terminal.term();
+        let term_lock = term_arc.lock();
+        f(pane.id, term_lock.grid());
+    }
+}
+
+/// Zoom the focused pane's text in, out, or back to the window's font size
+/// Matches physical keys: Option changes the characters they type on macOS
+fn handle_pane_zoom(
+    event: &KeyEvent,
+    tab_manager: &Arc<Mutex<crate::tab::TabManager>>,
+    window: &winit::window::Window,
+) -> bool {
+    let step = match event.physical_key {
+        PhysicalKey::Code(KeyCode::Equal) => Some(FONT_SCALE_STEP),
+        PhysicalKey::Code(KeyCode::Minus) => Some(-FONT_SCALE_STEP),
+        PhysicalKey::Code(KeyCode::Digit0) => None,
+        _ => return false,
+    };
+    if let Err(e) = with_active_tab(tab_manager, |tab| tab.zoom_focused_pane(step)) {
+        log::error!("Failed to zoom pane: {}", e);
+    }
+    window.request_redraw();
+    true
+}
+
 fn handle_font_size_shortcuts(
     event: &KeyEvent,
     config: &mut Config,
     font_size: &mut f32,
     renderer: &Arc<Mutex<Renderer>>,
+    tab_manager: &Arc<Mutex<crate::tab::TabManager>>,
+    window: &winit::window::Window,
+    dropdown: &Arc<Mutex<DropdownWindow>>,
 ) -> bool {
     let key_text = match &event.logical_key {
         Key::Character(s) => Some(s.as_str()),
@@ -228,20 +652,20 @@ fn handle_font_size_shortcuts(
     if should_increase_font {
         *font_size = (*font_size + 2.0).min(48.0);
         info!("Increased font size to {}", font_size);
-        update_font_size(config, *font_size, renderer);
+        update_font_size(config, *font_size, renderer, tab_manager, window, dropdown);
         return true;
     } else if let Some(key_text) = key_text {
         match key_text {
             "-" => {
                 *font_size = (*font_size - 2.0).max(8.0);
                 info!("Decreased font size to {}", font_size);
-                update_font_size(config, *font_size, renderer);
+                update_font_size(config, *font_size, renderer, tab_manager, window, dropdown);
                 return true;
             }
             "0" => {
                 *font_size = 14.0;
                 info!("Reset font size to default (14.0)");
-                update_font_size(config, *font_size, renderer);
+                update_font_size(config, *font_size, renderer, tab_manager, window, dropdown);
                 return true;
             }
             _ => {}
@@ -251,7 +675,15 @@ fn handle_font_size_shortcuts(
     true
 }
 
-fn update_font_size(config: &mut Config, font_size: f32, renderer: &Arc<Mutex<Renderer>>) {
+/// Apply and save a new font size, then refit the dropdown and panes to the new cells
+fn update_font_size(
+    config: &mut Config,
+    font_size: f32,
+    renderer: &Arc<Mutex<Renderer>>,
+    tab_manager: &Arc<Mutex<crate::tab::TabManager>>,
+    window: &winit::window::Window,
+    dropdown: &Arc<Mutex<DropdownWindow>>,
+) {
     config.appearance.font_size = font_size;
     let _ = config.save(None);
     if let Some(mut renderer) = renderer.try_lock() {
@@ -259,6 +691,7 @@ fn update_font_size(config: &mut Config, font_size: f32, renderer: &Arc<Mutex<Re
             log::error!("Failed to update font size: {}", e);
         }
     }
+    super::window::handle_cell_size_changed(renderer, tab_manager, window, dropdown, config);
 }
 
 fn handle_ctrl_shortcuts(
@@ -270,9 +703,22 @@ fn handle_ctrl_shortcuts(
     match keycode {
         KeyCode::KeyW => {
             info!("Closing focused pane");
-            if let Some(active_tab) = tab_manager.lock().active_tab_mut() {
-                if let Err(e) = active_tab.close_focused_pane() {
-                    log::error!("Failed to close pane: {}", e);
+            let mut tab_mgr = tab_manager.lock();
+            // The last pane never closes, so there is nothing to confirm
+            let target = tab_mgr
+                .active_tab()
+                .filter(|tab| tab.pane_tree.pane_ids().len() > 1)
+                .and_then(|tab| {
+                    let pane = tab.pane_tree.focused_pane()?;
+                    Some((CloseTarget::Pane { tab: tab.id, pane: pane.id }, pane.terminal.busy_process()))
+                });
+            if let Some((target, busy)) = target {
+                if tab_mgr.confirm_close(target, busy.as_slice()) {
+                    if let Some(active_tab) = tab_mgr.active_tab_mut() {
+                        if let Err(e) = active_tab.close_focused_pane() {
+                            log::error!("Failed to close pane: {}", e);
+                        }
+                    }
                 }
             }
             window.request_redraw();
@@ -291,9 +737,15 @@ fn read_current_line_from_grid(tab_manager: &Arc<Mutex<crate::tab::TabManager>>)
 
     // Extend lifetime by binding the Arc first
     let term_arc = pane.terminal.term();
-    let term_lock = term_arc.try_lock()?;
+    let term_lock = term_arc.try_lock_for(TERM_LOCK_TIMEOUT)?;
+
+    Some(cursor_line_text(term_lock.grid()))
+}
 
-    let grid = term_lock.grid();
+/// Text of the line the cursor is on (prompt included), trailing blanks trimmed
+pub(super) fn cursor_line_text(
+    grid: &alacritty_terminal::grid::Grid<alacritty_terminal::term::cell::Cell>,
+) -> String {
     let cursor_line = grid.cursor.point.line;
 
     // Pre-allocate with reasonable capacity (most commands < 256 chars)
@@ -313,7 +765,50 @@ fn read_current_line_from_grid(tab_manager: &Arc<Mutex<crate::tab::TabManager>>)
         line.push(ch);
     }
 
-    Some(line.trim_end().to_string())
+    line.trim_end().to_string()
+}
+
+/// Scroll the focused pane's history with Shift+PageUp/PageDown, Shift+Up/Down
+/// and Cmd+Home/End. Returns false (sending the key to the PTY) in the alt screen
+fn handle_scroll_keys(
+    event: &KeyEvent,
+    modifiers_state: &Modifiers,
+    tab_manager: &Arc<Mutex<crate::tab::TabManager>>,
+    renderer: &Arc<Mutex<Renderer>>,
+    window: &winit::window::Window,
+) -> bool {
+    let PhysicalKey::Code(keycode) = event.physical_key else {
+        return false;
+    };
+    let Some(action) = scroll_action(keycode, InputModifiers::from_winit(modifiers_state.state())) else {
+        return false;
+    };
+
+    let (history_size, screen_lines) = {
+        let tab_mgr = tab_manager.lock();
+        let Some(pane) = tab_mgr.active_tab().and_then(|tab| tab.pane_tree.focused_pane()) else {
+            return false;
+        };
+        let term = pane.terminal.term();
+        let term_lock = term.lock();
+        if term_lock.mode().contains(TermMode::ALT_SCREEN) {
+            return false;
+        }
+        (term_lock.grid().history_size(), term_lock.screen_lines())
+    };
+
+    let page = screen_lines.saturating_sub(1).max(1) as i32;
+    let mut renderer = renderer.lock();
+    match action {
+        ScrollAction::PageUp => renderer.scroll_lines(page, history_size),
+        ScrollAction::PageDown => renderer.scroll_lines(-page, history_size),
+        ScrollAction::LineUp => renderer.scroll_lines(1, history_size),
+        ScrollAction::LineDown => renderer.scroll_lines(-1, history_size),
+        ScrollAction::Top => renderer.set_scroll_offset(history_size),
+        ScrollAction::Bottom => renderer.reset_scroll(),
+    }
+    window.request_redraw();
+    true
 }
 
 fn handle_terminal_input(
@@ -323,6 +818,9 @@ fn handle_terminal_input(
     renderer: &Arc<Mutex<Renderer>>,
     window: &winit::window::Window,
     dropdown: &Arc<Mutex<DropdownWindow>>,
+    snippet_picker: &mut SnippetPicker,
+    config: &mut Config,
+    font_size: &mut f32,
 ) -> bool {
     let input_mods = InputModifiers::from_winit(modifiers_state.state());
 
@@ -351,7 +849,16 @@ fn handle_terminal_input(
                         log::info!("✓ Command detected: {}", cmd_name);
 
                         // Execute command
-                        let success = execute_command(cmd, renderer, window, dropdown);
+                        let success = execute_command(
+                            cmd,
+                            renderer,
+                            tab_manager,
+                            window,
+                            dropdown,
+                            snippet_picker,
+                            config,
+                            font_size,
+                        );
 
                         if success {
                             log::info!("✓ Command executed successfully");
@@ -380,9 +887,7 @@ fn handle_terminal_input(
     if !input_mods.ctrl && !input_mods.alt {
         if let Some(text) = &event.text {
             // Pass to terminal
-            if let Some(active_tab) = tab_manager.lock().active_tab_mut() {
-                let _ = active_tab.write_input(text.as_bytes());
-            }
+            let _ = tab_manager.lock().type_text(text);
             renderer.lock().reset_scroll();
             window.request_redraw();
         }
@@ -396,9 +901,31 @@ fn get_command_name(cmd: &crate::app::commands::TerminalCommand) -> &'static str
     use crate::app::commands::TerminalCommand;
     match cmd {
         TerminalCommand::Wallpaper { .. } => "Wallpaper",
+        TerminalCommand::TabWallpaper { .. } => "TabWallpaper",
         TerminalCommand::WallpaperOpacity { .. } => "WallpaperOpacity",
+        TerminalCommand::WallpaperMode { .. } => "WallpaperMode",
         TerminalCommand::BackgroundOpacity { .. } => "BackgroundOpacity",
         TerminalCommand::BlurStrength { .. } => "BlurStrength",
+        TerminalCommand::SwapPane => "SwapPane",
+        TerminalCommand::RotatePanes => "RotatePanes",
+        TerminalCommand::BalancePanes => "BalancePanes",
+        TerminalCommand::Theme { .. } => "Theme",
+        TerminalCommand::FontSize { .. } => "FontSize",
+        TerminalCommand::Font { .. } => "Font",
+        TerminalCommand::Split { .. } => "Split",
+        TerminalCommand::ClearScrollback => "ClearScrollback",
+        TerminalCommand::Respawn => "Respawn",
+        TerminalCommand::Badge { .. } => "Badge",
+        TerminalCommand::LogStart { .. } => "LogStart",
+        TerminalCommand::LogStop => "LogStop",
+        TerminalCommand::Screenshot { .. } => "Screenshot",
+        TerminalCommand::ConfigReload => "ConfigReload",
+        TerminalCommand::ConfigInit => "ConfigInit",
+        TerminalCommand::ToggleHud => "ToggleHud",
+        TerminalCommand::RendererInfo => "RendererInfo",
+        TerminalCommand::Layout { .. } => "Layout",
+        TerminalCommand::Snippet { .. } => "Snippet",
+        TerminalCommand::Help => "Help",
     }
 }
 
@@ -406,19 +933,41 @@ fn get_command_name(cmd: &crate::app::commands::TerminalCommand) -> &'static str
 fn execute_command(
     cmd: crate::app::commands::TerminalCommand,
     renderer: &Arc<Mutex<Renderer>>,
+    tab_manager: &Arc<Mutex<crate::tab::TabManager>>,
     window: &winit::window::Window,
     dropdown: &Arc<Mutex<DropdownWindow>>,
+    snippet_picker: &mut SnippetPicker,
+    config: &mut Config,
+    font_size: &mut f32,
 ) -> bool {
     use crate::app::commands::TerminalCommand;
 
+    // Name the default screenshot now so the confirmation can show where it went
+    let cmd = match cmd {
+        TerminalCommand::Screenshot { path: None, pane_only } => TerminalCommand::Screenshot {
+            path: Some(default_screenshot_path().to_string_lossy().into_owned()),
+            pane_only,
+        },
+        cmd => cmd,
+    };
+
     let result = match &cmd {
         TerminalCommand::Wallpaper { path } => {
             renderer.lock().set_wallpaper(path.as_deref())
         }
+        TerminalCommand::TabWallpaper { path } => match path {
+            Some(p) if !std::path::Path::new(p).is_file() => Err(anyhow::anyhow!("file not found")),
+            _ => with_active_tab(tab_manager, |tab| {
+                tab.wallpaper = path.clone().map(|path| WallpaperOverride { path, opacity: None });
+                renderer.lock().set_wallpaper_override(tab.wallpaper.as_ref());
+                Ok(())
+            }),
+        },
         TerminalCommand::WallpaperOpacity { opacity } => {
             renderer.lock().set_wallpaper_opacity(*opacity);
             Ok(())
         }
+        TerminalCommand::WallpaperMode { mode } => renderer.lock().set_wallpaper_mode(*mode),
         TerminalCommand::BackgroundOpacity { opacity } => {
             renderer.lock().set_background_opacity(*opacity);
             Ok(())
@@ -427,17 +976,229 @@ fn execute_command(
             renderer.lock().set_blur_strength(*strength);
             Ok(())
         }
+        TerminalCommand::SwapPane => with_active_tab(tab_manager, |tab| tab.swap_focused_pane()),
+        TerminalCommand::RotatePanes => {
+            with_active_tab(tab_manager, |tab| tab.rotate_focused_split())
+        }
+        TerminalCommand::BalancePanes => with_active_tab(tab_manager, |tab| tab.balance_panes()),
+        TerminalCommand::Theme { name } => match ColorPalette::by_name(name) {
+            Some(palette) => {
+                renderer.lock().set_palette(palette);
+                tab_manager.lock().set_palette(palette);
+                config.appearance.palette = palette;
+                config.save(None)
+            }
+            None => Err(anyhow::anyhow!("unknown theme (available: {})", THEME_NAMES.join(", "))),
+        },
+        TerminalCommand::FontSize { size } => {
+            *font_size = *size;
+            update_font_size(config, *size, renderer, tab_manager, window, dropdown);
+            Ok(())
+        }
+        TerminalCommand::Font { family } => renderer.lock().set_font_family(family).and_then(|_| {
+            config.appearance.font_family = family.clone();
+            config.save(None)
+        }),
+        TerminalCommand::Split { direction } => {
+            let shell = Some(config.terminal.shell.clone());
+            with_active_tab(tab_manager, |tab| tab.split(*direction, shell))
+        }
+        TerminalCommand::ClearScrollback => with_active_tab(tab_manager, |tab| {
+            tab.clear_scrollback();
+            renderer.lock().reset_scroll();
+            Ok(())
+        }),
+        TerminalCommand::Respawn => with_active_tab(tab_manager, |tab| {
+            tab.respawn_focused_pane()?;
+            renderer.lock().reset_scroll();
+            Ok(())
+        }),
+        TerminalCommand::Badge { text } => with_active_tab(tab_manager, |tab| {
+            // An empty badge hides the configured default too
+            tab.set_focused_badge(Some(text.clone().unwrap_or_default()));
+            Ok(())
+        }),
+        TerminalCommand::LogStart { path, format } => {
+            with_active_tab(tab_manager, |tab| tab.start_focused_log(std::path::Path::new(path), *format))
+        }
+        TerminalCommand::LogStop => with_active_tab(tab_manager, |tab| match tab.stop_focused_log() {
+            Some(path) => {
+                info!("Stopped logging to {}", path.display());
+                Ok(())
+            }
+            None => Err(anyhow::anyhow!("this pane is not being logged")),
+        }),
+        TerminalCommand::Screenshot { path, pane_only } => {
+            let path = path.as_deref().map(std::path::PathBuf::from).unwrap_or_else(default_screenshot_path);
+            with_active_tab(tab_manager, |tab| renderer.lock().screenshot(&tab.pane_tree, &path, *pane_only))
+        }
+        TerminalCommand::ConfigReload => reload_config(config, font_size, renderer, tab_manager, dropdown, window),
+        TerminalCommand::ConfigInit => Config::write_default(None).and_then(|backup| {
+            if let Some(backup) = backup {
+                info!("Previous config saved to {}", backup.display());
+            }
+            reload_config(config, font_size, renderer, tab_manager, dropdown, window)
+        }),
+        TerminalCommand::Layout { name } => match config.layouts.get(name) {
+            Some(layout) => tab_manager.lock().new_tab_from_layout(name, layout).map(|_| ()),
+            None if config.layouts.is_empty() => Err(anyhow::anyhow!("no layouts defined in config.toml")),
+            None => Err(anyhow::anyhow!(
+                "unknown layout (available: {})",
+                config.layouts.keys().cloned().collect::<Vec<_>>().join(", ")
+            )),
+        },
+        TerminalCommand::Snippet { name } => match config.snippets.get(name) {
+            Some(text) => {
+                if let Some(insert) = snippet_picker.start(name, text, true) {
+                    insert_snippet(&insert, tab_manager, renderer, window);
+                }
+                Ok(())
+            }
+            None if config.snippets.is_empty() => Err(anyhow::anyhow!("no snippets defined in config.toml")),
+            None => Err(anyhow::anyhow!(
+                "unknown snippet (available: {})",
+                config.snippets.keys().cloned().collect::<Vec<_>>().join(", ")
+            )),
+        },
+        TerminalCommand::ToggleHud => {
+            let visible = renderer.lock().toggle_stats_hud();
+            info!("Frame statistics HUD {}", if visible { "shown" } else { "hidden" });
+            Ok(())
+        }
+        TerminalCommand::RendererInfo => {
+            let gpu_info = renderer.lock().gpu_info();
+            if let Some(tab) = tab_manager.lock().active_tab_mut() {
+                tab.print_feedback(FeedbackKind::Info, &gpu_info);
+            }
+            Ok(())
+        }
+        TerminalCommand::Help => Ok(()),
     };
 
+    // Cell size changed - re-fit the panes to the window
+    let changes_cell_size = matches!(
+        cmd,
+        TerminalCommand::Font { .. }
+            | TerminalCommand::ConfigReload
+            | TerminalCommand::ConfigInit
+            | TerminalCommand::Layout { .. }
+    );
+    if result.is_ok() && changes_cell_size {
+        super::window::handle_cell_size_changed(renderer, tab_manager, window, dropdown, config);
+    }
+
     let success = result.is_ok();
-    let _message = match result {
-        Ok(_) => crate::app::commands::format_success_message(&cmd),
-        Err(e) => crate::app::commands::format_error_message(&cmd, &e.to_string()),
+    let (kind, message) = match result {
+        Ok(_) if matches!(cmd, TerminalCommand::Help) => {
+            (FeedbackKind::Info, crate::app::commands::format_success_message(&cmd))
+        }
+        Ok(_) => (FeedbackKind::Success, crate::app::commands::format_success_message(&cmd)),
+        Err(e) => (FeedbackKind::Error, crate::app::commands::format_error_message(&cmd, &e.to_string())),
     };
 
-    // Note: In a real implementation, we'd display the message in the terminal
-    // For now, the log messages in the renderer are sufficient
+    // Show the result inline, below the command that was typed
+    if let Some(active_tab) = tab_manager.lock().active_tab_mut() {
+        active_tab.print_feedback(kind, &message);
+    }
 
     window.request_redraw();
     success
 }
+
+/// Re-read config.toml and apply its appearance settings
+/// Settings that can fail (font, wallpaper, post-process shader) are checked
+/// first, so a bad one leaves everything as it was
+fn reload_config(
+    config: &mut Config,
+    font_size: &mut f32,
+    renderer: &Arc<Mutex<Renderer>>,
+    tab_manager: &Arc<Mutex<crate::tab::TabManager>>,
+    dropdown: &Arc<Mutex<DropdownWindow>>,
+    window: &winit::window::Window,
+) -> anyhow::Result<()> {
+    let new_config = Config::load(None)?;
+    let appearance = &new_config.appearance;
+    let font_changed = appearance.font_family != config.appearance.font_family;
+    let post_process = appearance.post_process_source()?;
+
+    {
+        let mut renderer = renderer.lock();
+        // Nothing below can fail once these pass
+        if font_changed {
+            renderer.check_font_family(&appearance.font_family)?;
+        }
+        if let Some(path) = &appearance.wallpaper_path {
+            renderer.preload_wallpaper(path)?;
+        }
+        if let Some(source) = &post_process {
+            renderer.check_post_process(source)?;
+        }
+
+        if font_changed {
+            renderer.set_font_family(&appearance.font_family)?;
+        }
+        renderer.set_wallpaper(appearance.wallpaper_path.as_deref())?;
+        renderer.set_post_process(post_process.as_deref(), appearance.post_process_animate)?;
+        renderer.set_palette(appearance.palette);
+        renderer.set_background_opacity(appearance.opacity);
+        renderer.set_wallpaper_opacity(appearance.wallpaper_opacity);
+        renderer.set_wallpaper_mode(appearance.wallpaper_mode)?;
+        renderer.set_blur_strength(appearance.blur_strength);
+        renderer.set_antialias(appearance.antialias);
+        renderer.set_inactive_pane_dim(appearance.inactive_pane_dim);
+        renderer.set_blinking(appearance.blinking);
+        renderer.set_pane_headers(appearance.pane_headers);
+        renderer.set_padding(new_config.window.effective_padding());
+        renderer.set_window_frame(super::window::window_frame(&new_config.window));
+        renderer.set_default_badge(appearance.badge.as_deref());
+        renderer.set_ssh_badge(appearance.ssh_badge);
+        renderer.set_command_durations(appearance.command_durations);
+        renderer.set_autosuggestions(new_config.terminal.autosuggestions);
+        renderer.set_selection_style(new_config.selection.style);
+        renderer.set_pane_borders(new_config.panes.border);
+        let scrolling = &new_config.scrolling;
+        renderer.set_scrolling(scrolling.multiplier, scrolling.momentum, scrolling.deceleration);
+        renderer.set_font_size(appearance.font_size)?;
+        renderer.set_spacing(appearance.line_height, appearance.letter_spacing);
+        renderer.set_pixel_snap(appearance.pixel_snap);
+        renderer.set_low_latency(appearance.low_latency);
+        renderer.set_color_space(appearance.output_color_space());
+        renderer.set_display_scale_factors(super::window::display_scale_factors(window, &new_config));
+    }
+
+    {
+        let mut tab_mgr = tab_manager.lock();
+        tab_mgr.set_confirm_close(new_config.terminal.confirm_close);
+        tab_mgr.set_confirm_password_paste(new_config.terminal.confirm_password_paste);
+        tab_mgr.set_paste_cleanup(new_config.clipboard.paste_transforms(), new_config.clipboard.confirm_cleanup);
+        tab_mgr.set_local_echo(new_config.terminal.local_echo);
+        tab_mgr.set_triggers(&new_config.triggers);
+        tab_mgr.set_palette(appearance.palette);
+        tab_mgr.set_auto_log(&new_config.logging);
+    }
+
+    {
+        let mut dropdown = dropdown.lock();
+        dropdown.set_opacity(new_config.window.opacity, new_config.window.glass_opacity);
+        dropdown.set_spaces(new_config.window.can_join_all_spaces, new_config.window.appear_over_fullscreen);
+        dropdown.apply_opacity(window);
+        dropdown.set_color_space(window, appearance.output_color_space());
+    }
+
+    // Resizing the panes for headers can only fail on the PTYs, after every setting is in place
+    let pane_headers = appearance.pane_headers;
+    *font_size = appearance.font_size;
+    *config = new_config;
+    tab_manager.lock().set_pane_headers(pane_headers)
+}
+
+/// Run a fallible operation against the active tab
+pub(super) fn with_active_tab(
+    tab_manager: &Arc<Mutex<crate::tab::TabManager>>,
+    f: impl FnOnce(&mut crate::tab::Tab) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    match tab_manager.lock().active_tab_mut() {
+        Some(tab) => f(tab),
+        None => Err(anyhow::anyhow!("No active tab")),
+    }
+}
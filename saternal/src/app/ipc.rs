@@ -0,0 +1,68 @@
+use super::input::with_active_tab;
+use anyhow::{anyhow, Result};
+use parking_lot::Mutex;
+use saternal_core::ipc::PaneTarget;
+use saternal_core::theme::THEME_NAMES;
+use saternal_core::{ColorPalette, Config, IpcRequest, Pane};
+use crate::platform::HotkeyManager;
+use saternal_core::GlobalHotkeys;
+use saternal_renderer::Renderer;
+use serde_json::Value;
+use std::sync::Arc;
+
+/// Carry out a remote-control request on the main window
+/// Returns the JSON result sent back to the client
+pub(super) fn handle_request(
+    request: &IpcRequest,
+    config: &mut Config,
+    renderer: &Arc<Mutex<Renderer>>,
+    tab_manager: &Arc<Mutex<crate::tab::TabManager>>,
+    hotkey_manager: &HotkeyManager,
+) -> Result<Value> {
+    log::info!("Remote control: {:?}", request);
+    match request {
+        IpcRequest::Toggle => hotkey_manager.trigger_toggle(),
+        IpcRequest::Run { command } => {
+            let line = format!("{}\r", command);
+            with_active_tab(tab_manager, |tab| tab.write_input(line.as_bytes()))?;
+            renderer.lock().reset_scroll();
+        }
+        IpcRequest::Split { direction } => {
+            let shell = Some(config.terminal.shell.clone());
+            with_active_tab(tab_manager, |tab| tab.split(*direction, shell))?;
+        }
+        IpcRequest::SetTheme { name } => {
+            let palette = ColorPalette::by_name(name)
+                .ok_or_else(|| anyhow!("unknown theme (available: {})", THEME_NAMES.join(", ")))?;
+            renderer.lock().set_palette(palette);
+            tab_manager.lock().set_palette(palette);
+            config.appearance.palette = palette;
+            config.save(None)?;
+        }
+        IpcRequest::ListTabs => return Ok(serde_json::to_value(tab_manager.lock().tab_infos())?),
+        IpcRequest::SendText { target, text } => {
+            with_pane(tab_manager, *target, |pane| pane.terminal.write_input(text.as_bytes()))?;
+        }
+        IpcRequest::GetText { target, scrollback } => {
+            let text = with_pane(tab_manager, *target, |pane| Ok(pane.terminal.screen_text(*scrollback)))?;
+            return Ok(Value::String(text));
+        }
+    }
+    Ok(Value::Null)
+}
+
+/// Run `f` on the addressed pane, failing if the tab or pane doesn't exist
+fn with_pane<T>(
+    tab_manager: &Arc<Mutex<crate::tab::TabManager>>,
+    target: PaneTarget,
+    f: impl FnOnce(&mut Pane) -> Result<T>,
+) -> Result<T> {
+    let mut tab_manager = tab_manager.lock();
+    let tab = tab_manager
+        .tab_mut(target.tab)
+        .ok_or_else(|| anyhow!("no tab {}", target.tab.map_or("active".to_string(), |id| id.to_string())))?;
+    let pane = tab
+        .pane_mut(target.pane)
+        .ok_or_else(|| anyhow!("no pane {}", target.pane.map_or("focused".to_string(), |id| id.to_string())))?;
+    f(pane)
+}
@@ -1,10 +1,20 @@
+mod autosuggest;
 mod clipboard;
+mod command_alerts;
 pub(crate) mod commands;
+// Native right-click menu (AppKit only for now)
+#[cfg(target_os = "macos")]
+mod context_menu;
+mod edge_resize;
 mod event_loop;
 mod init;
 mod input;
+mod ipc;
 mod mouse;
 mod state;
+mod triggers;
+mod updates;
 mod window;
+mod window_manager;
 
 pub use state::App;
@@ -1,9 +1,14 @@
 use alacritty_terminal::grid::Dimensions;
 use log::info;
 use parking_lot::Mutex;
-use saternal_core::{MouseButton, MouseState, Renderer, SelectionManager, SelectionMode, calculate_pane_viewports};
+use saternal_core::output_parser::TERM_LOCK_TIMEOUT;
+use saternal_core::terminal::SCROLLBACK_LINES;
+use alacritty_terminal::index::Point;
+use saternal_core::{MouseButton, MouseState, Padding, SearchState, SelectionManager, SelectionMode, calculate_pane_viewports, pane_at};
+use saternal_renderer::scrollbar::{self, ScrollbarState};
+use saternal_renderer::Renderer;
 use std::sync::Arc;
-use winit::event::{ElementState, MouseButton as WinitMouseButton, MouseScrollDelta};
+use winit::event::{ElementState, MouseButton as WinitMouseButton, MouseScrollDelta, TouchPhase};
 
 /// Handle mouse button events
 pub(super) fn handle_mouse_input(
@@ -14,6 +19,7 @@ pub(super) fn handle_mouse_input(
     tab_manager: &Arc<Mutex<crate::tab::TabManager>>,
     renderer: &Arc<Mutex<Renderer>>,
     window: &winit::window::Window,
+    search_state: &SearchState,
 ) {
     let mouse_button = match button {
         WinitMouseButton::Left => MouseButton::Left,
@@ -24,6 +30,11 @@ pub(super) fn handle_mouse_input(
 
     match state {
         ElementState::Pressed => {
+            if mouse_button == MouseButton::Left
+                && handle_scrollbar_click(mouse_state, search_state, tab_manager, renderer, window)
+            {
+                return;
+            }
             handle_mouse_press(mouse_button, mouse_state, selection_manager, tab_manager, renderer, window);
         }
         ElementState::Released => {
@@ -32,6 +43,67 @@ pub(super) fn handle_mouse_input(
     }
 }
 
+/// Jump to a search match when its scrollbar marker is clicked
+/// Returns true if the click hit a marker
+fn handle_scrollbar_click(
+    mouse_state: &MouseState,
+    search_state: &SearchState,
+    tab_manager: &Arc<Mutex<crate::tab::TabManager>>,
+    renderer: &Arc<Mutex<Renderer>>,
+    window: &winit::window::Window,
+) -> bool {
+    let markers = super::window::search_marker_lines(search_state);
+    if markers.is_empty() {
+        return false;
+    }
+
+    let Some(tab_mgr) = tab_manager.try_lock() else {
+        return false;
+    };
+    let Some(tab) = tab_mgr.active_tab() else {
+        return false;
+    };
+    let viewports = calculate_pane_viewports(
+        &tab.pane_tree,
+        window.inner_size().width,
+        window.inner_size().height,
+    );
+    let Some(mut renderer_lock) = renderer.try_lock() else {
+        return false;
+    };
+    let Some(viewport) = viewports.iter().find(|vp| vp.focused) else {
+        return false;
+    };
+    let viewport = &viewport.without_header(renderer_lock.pane_header_height());
+    let Some(pane) = tab.pane_tree.find_pane(viewport.pane_id) else {
+        return false;
+    };
+
+    let state = {
+        let term = pane.terminal.term();
+        let Some(term_lock) = term.try_lock_for(TERM_LOCK_TIMEOUT) else {
+            return false;
+        };
+        let history_size = term_lock.grid().history_size();
+        ScrollbarState {
+            history_size,
+            screen_lines: term_lock.screen_lines(),
+            scroll_offset: renderer_lock.scroll_offset().min(history_size),
+        }
+    };
+
+    let (x, y) = mouse_state.pixel_position;
+    match scrollbar::marker_at(viewport, state, &markers, x, y) {
+        Some(line) => {
+            info!("Jumping to search match on line {}", line);
+            renderer_lock.set_scroll_offset(state.offset_for_line(line));
+            window.request_redraw();
+            true
+        }
+        None => false,
+    }
+}
+
 fn handle_mouse_press(
     mouse_button: MouseButton,
     mouse_state: &mut MouseState,
@@ -40,53 +112,13 @@ fn handle_mouse_press(
     renderer: &Arc<Mutex<Renderer>>,
     window: &winit::window::Window,
 ) {
+    // Focus the pane under the cursor and make the click relative to its grid
+    let consumed = mouse_button == MouseButton::Left
+        && focus_pane_under_cursor(mouse_state, tab_manager, renderer, window);
     mouse_state.press_button(mouse_button);
-    
-    // Check if click is on a different pane and focus it
-    if mouse_button == MouseButton::Left {
-        let mouse_x = mouse_state.position.column.0 as f32;
-        let mouse_y = mouse_state.position.line.0 as f32;
-        
-        if let Some(mut renderer_lock) = renderer.try_lock() {
-            let fm = renderer_lock.font_manager();
-            let effective_size = fm.effective_font_size();
-            let line_metrics = fm.font().horizontal_line_metrics(effective_size).unwrap();
-            let cell_width = fm.font().metrics('M', effective_size).advance_width;
-            let cell_height = (line_metrics.ascent - line_metrics.descent + line_metrics.line_gap).ceil();
-            
-            // Convert cell position to pixel position
-            let pixel_x = (mouse_x * cell_width + 10.0) as u32; // PADDING_LEFT
-            let pixel_y = (mouse_y * cell_height + 5.0) as u32; // PADDING_TOP
-            
-            drop(renderer_lock);
-            
-            // Check which pane viewport contains this click
-            if let Some(mut tab_mgr) = tab_manager.try_lock() {
-                if let Some(active_tab) = tab_mgr.active_tab_mut() {
-                    let viewports = calculate_pane_viewports(
-                        &active_tab.pane_tree,
-                        window.inner_size().width,
-                        window.inner_size().height
-                    );
-                    
-                    // Find which viewport was clicked
-                    for viewport in viewports {
-                        if pixel_x >= viewport.x && pixel_x < viewport.x + viewport.width &&
-                           pixel_y >= viewport.y && pixel_y < viewport.y + viewport.height {
-                            if !viewport.focused {
-                                info!("Focusing pane {} via mouse click", viewport.pane_id);
-                                active_tab.pane_tree.set_focus(viewport.pane_id);
-                                window.request_redraw();
-                            }
-                            break;
-                        }
-                    }
-                }
-            }
-        }
-    }
-    
-    if mouse_button != MouseButton::Left {
+
+    // Header clicks only focus the pane (and clicks outside a locked pane do nothing)
+    if mouse_button != MouseButton::Left || consumed {
         return;
     }
 
@@ -106,6 +138,79 @@ fn handle_mouse_press(
     }
 }
 
+/// Hit-test the cursor against the active tab's panes, focus the one under it,
+/// and set the mouse grid position relative to that pane's viewport
+/// Returns true if the click stops here: it landed on the pane's header strip,
+/// or on another pane while input is locked to the focused one
+fn focus_pane_under_cursor(
+    mouse_state: &mut MouseState,
+    tab_manager: &Arc<Mutex<crate::tab::TabManager>>,
+    renderer: &Arc<Mutex<Renderer>>,
+    window: &winit::window::Window,
+) -> bool {
+    let Some(mut tab_mgr) = tab_manager.try_lock() else {
+        return false;
+    };
+    let Some(active_tab) = tab_mgr.active_tab_mut() else {
+        return false;
+    };
+    let viewports = calculate_pane_viewports(
+        &active_tab.pane_tree,
+        window.inner_size().width,
+        window.inner_size().height,
+    );
+
+    let (x, y) = mouse_state.pixel_position;
+    let Some(viewport) = pane_at(&viewports, x, y) else {
+        return false;
+    };
+    if !viewport.focused && active_tab.pane_tree.focus_locked() {
+        info!("Ignoring click on pane {}: input is locked to the focused pane", viewport.pane_id);
+        return true;
+    }
+    if !viewport.focused {
+        info!("Focusing pane {} via mouse click", viewport.pane_id);
+        active_tab.pane_tree.set_focus(viewport.pane_id);
+        window.request_redraw();
+    }
+    let Some((cell_width, cell_height, header_height, padding)) = cell_size(renderer, viewport.font_scale) else {
+        return false;
+    };
+    let content = viewport.without_header(header_height);
+    mouse_state.position = content.cell_at(x, y, cell_width, cell_height, &padding);
+    y < content.y as f32
+}
+
+/// Grid position of the cursor relative to the focused pane's viewport
+fn focused_pane_cell(
+    mouse_state: &MouseState,
+    tab_manager: &Arc<Mutex<crate::tab::TabManager>>,
+    renderer: &Arc<Mutex<Renderer>>,
+    window: &winit::window::Window,
+) -> Option<Point> {
+    let tab_mgr = tab_manager.try_lock()?;
+    let tab = tab_mgr.active_tab()?;
+    let viewports = calculate_pane_viewports(
+        &tab.pane_tree,
+        window.inner_size().width,
+        window.inner_size().height,
+    );
+    let viewport = viewports.iter().find(|vp| vp.focused)?;
+    let (cell_width, cell_height, header_height, padding) = cell_size(renderer, viewport.font_scale)?;
+    let (x, y) = mouse_state.pixel_position;
+    Some(viewport.without_header(header_height).cell_at(x, y, cell_width, cell_height, &padding))
+}
+
+/// Cell width/height of a pane zoomed to `font_scale`, the pane header height, and the
+/// padding around each grid, in pixels
+fn cell_size(renderer: &Arc<Mutex<Renderer>>, font_scale: f32) -> Option<(f32, f32, u32, Padding)> {
+    let renderer_lock = renderer.try_lock()?;
+    let header_height = renderer_lock.pane_header_height();
+    let padding = renderer_lock.padding();
+    let (cell_width, cell_height, _) = renderer_lock.pane_cell_metrics(font_scale);
+    Some((cell_width, cell_height, header_height, padding))
+}
+
 fn handle_double_click(
     selection_manager: &mut SelectionManager,
     mouse_state: &MouseState,
@@ -114,14 +219,14 @@ fn handle_double_click(
 ) {
     if let Some(tab_mgr) = tab_manager.try_lock() {
         if let Some(pane) = tab_mgr.active_tab().and_then(|tab| tab.pane_tree.focused_pane()) {
-            if let Some(term_lock) = pane.terminal.term().try_lock() {
+            if let Some(term_lock) = pane.terminal.term().try_lock_for(TERM_LOCK_TIMEOUT) {
                 let grid = term_lock.grid();
                 let grid_cols = grid.columns();
                 let grid_lines = grid.screen_lines();
                 selection_manager.expand_word(grid, mouse_state.position);
                 drop(term_lock);
                 if let Some(mut renderer_lock) = renderer.try_lock() {
-                    renderer_lock.update_selection(selection_manager.range(), grid_cols, grid_lines);
+                    renderer_lock.update_selection(&selection_manager.ranges(), grid_cols, grid_lines);
                 }
             }
         }
@@ -136,14 +241,14 @@ fn handle_triple_click(
 ) {
     if let Some(tab_mgr) = tab_manager.try_lock() {
         if let Some(pane) = tab_mgr.active_tab().and_then(|tab| tab.pane_tree.focused_pane()) {
-            if let Some(term_lock) = pane.terminal.term().try_lock() {
+            if let Some(term_lock) = pane.terminal.term().try_lock_for(TERM_LOCK_TIMEOUT) {
                 let grid = term_lock.grid();
                 let grid_cols = grid.columns();
                 let grid_lines = grid.screen_lines();
                 selection_manager.expand_line(grid, mouse_state.position);
                 drop(term_lock);
                 if let Some(mut renderer_lock) = renderer.try_lock() {
-                    renderer_lock.update_selection(selection_manager.range(), grid_cols, grid_lines);
+                    renderer_lock.update_selection(&selection_manager.ranges(), grid_cols, grid_lines);
                 }
             }
         }
@@ -159,7 +264,7 @@ fn handle_mouse_release(
     if mouse_button == MouseButton::Left && selection_manager.is_active() {
         if let Some(tab_mgr) = tab_manager.try_lock() {
             if let Some(pane) = tab_mgr.active_tab().and_then(|tab| tab.pane_tree.focused_pane()) {
-                if let Some(term_lock) = pane.terminal.term().try_lock() {
+                if let Some(term_lock) = pane.terminal.term().try_lock_for(TERM_LOCK_TIMEOUT) {
                     let _ = selection_manager.finalize(&term_lock.grid());
                 }
             }
@@ -176,32 +281,74 @@ pub(super) fn handle_cursor_moved(
     selection_manager: &mut SelectionManager,
     renderer: &Arc<Mutex<Renderer>>,
     tab_manager: &Arc<Mutex<crate::tab::TabManager>>,
+    window: &winit::window::Window,
+) {
+    let Some((cell_width, cell_height, _, _)) = cell_size(renderer, 1.0) else {
+        return;
+    };
+    mouse_state.update_position(x, y, cell_width, cell_height);
+
+    // Selections belong to the focused pane, so drags stay relative to it
+    if let Some(point) = focused_pane_cell(mouse_state, tab_manager, renderer, window) {
+        mouse_state.position = point;
+    }
+
+    if mouse_state.is_dragging() && selection_manager.is_active() {
+        extend_selection(selection_manager, mouse_state, tab_manager, renderer);
+    }
+}
+
+/// Drag the selection to the cursor (by words or lines after a double- or triple-click)
+fn extend_selection(
+    selection_manager: &mut SelectionManager,
+    mouse_state: &MouseState,
+    tab_manager: &Arc<Mutex<crate::tab::TabManager>>,
+    renderer: &Arc<Mutex<Renderer>>,
 ) {
-    if let Some(mut renderer_lock) = renderer.try_lock() {
-        let fm = renderer_lock.font_manager();
-        let effective_size = fm.effective_font_size();
-        let line_metrics = fm.font().horizontal_line_metrics(effective_size).unwrap();
-        let cell_width = fm.font().metrics('M', effective_size).advance_width;
-        let cell_height = (line_metrics.ascent - line_metrics.descent + line_metrics.line_gap).ceil();
-        
-        mouse_state.update_position(x, y, cell_width, cell_height);
-        
-        if mouse_state.is_dragging() && selection_manager.is_active() {
-            selection_manager.update(mouse_state.position);
-            drop(renderer_lock);
-            
-            let (grid_cols, grid_lines) = get_grid_dimensions(tab_manager);
-            if let Some(mut renderer_lock) = renderer.try_lock() {
-                renderer_lock.update_selection(selection_manager.range(), grid_cols, grid_lines);
+    if let Some(tab_mgr) = tab_manager.try_lock() {
+        if let Some(pane) = tab_mgr.active_tab().and_then(|tab| tab.pane_tree.focused_pane()) {
+            if let Some(term_lock) = pane.terminal.term().try_lock_for(TERM_LOCK_TIMEOUT) {
+                let grid = term_lock.grid();
+                let grid_cols = grid.columns();
+                let grid_lines = grid.screen_lines();
+                selection_manager.extend(grid, mouse_state.position);
+                drop(term_lock);
+                if let Some(mut renderer_lock) = renderer.try_lock() {
+                    renderer_lock.update_selection(&selection_manager.ranges(), grid_cols, grid_lines);
+                }
             }
         }
     }
 }
 
+/// Move the selection up with its text as output scrolls the focused pane
+/// Returns true if the selection changed and the window needs a redraw
+pub(super) fn follow_output_scroll(
+    tab: &crate::tab::Tab,
+    selection_manager: &mut SelectionManager,
+    renderer: &Arc<Mutex<Renderer>>,
+) -> bool {
+    let Some(pane) = tab.pane_tree.focused_pane() else {
+        return false;
+    };
+    let term = pane.terminal.term();
+    let Some(term_lock) = term.try_lock_for(TERM_LOCK_TIMEOUT) else {
+        return false;
+    };
+    let grid = term_lock.grid();
+    let (grid_cols, grid_lines) = (grid.columns(), grid.screen_lines());
+    if !selection_manager.follow_scroll(pane.id, grid, SCROLLBACK_LINES) {
+        return false;
+    }
+    drop(term_lock);
+    renderer.lock().update_selection(&selection_manager.ranges(), grid_cols, grid_lines);
+    true
+}
+
 pub(super) fn get_grid_dimensions(tab_manager: &Arc<Mutex<crate::tab::TabManager>>) -> (usize, usize) {
     if let Some(tab_mgr) = tab_manager.try_lock() {
         if let Some(pane) = tab_mgr.active_tab().and_then(|tab| tab.pane_tree.focused_pane()) {
-            if let Some(term_lock) = pane.terminal.term().try_lock() {
+            if let Some(term_lock) = pane.terminal.term().try_lock_for(TERM_LOCK_TIMEOUT) {
                 let grid = term_lock.grid();
                 return (grid.columns(), grid.screen_lines());
             }
@@ -211,20 +358,26 @@ pub(super) fn get_grid_dimensions(tab_manager: &Arc<Mutex<crate::tab::TabManager
 }
 
 /// Handle mouse wheel scrolling
+/// Wheel notches scroll `scrolling.multiplier` lines; trackpads scroll by pixels
 pub(super) fn handle_mouse_wheel(
     delta: MouseScrollDelta,
+    phase: TouchPhase,
     renderer: &Arc<Mutex<Renderer>>,
     window: &winit::window::Window,
 ) {
-    let scroll_delta = match delta {
-        MouseScrollDelta::LineDelta(_x, y) => y * 3.0,
-        MouseScrollDelta::PixelDelta(pos) => (pos.y / 18.0) as f32,
+    let Some(mut renderer_lock) = renderer.try_lock() else {
+        return;
     };
-
-    if scroll_delta.abs() > 0.001 {
-        if let Some(mut renderer_lock) = renderer.try_lock() {
-            renderer_lock.scroll(scroll_delta);
-            window.request_redraw();
+    match delta {
+        MouseScrollDelta::LineDelta(_x, y) => {
+            if y.abs() > 0.001 {
+                renderer_lock.scroll_notches(y);
+            }
+        }
+        MouseScrollDelta::PixelDelta(pos) => {
+            let gesture_ended = phase == TouchPhase::Ended;
+            renderer_lock.scroll_pixels(pos.y as f32, gesture_ended);
         }
     }
+    window.request_redraw();
 }
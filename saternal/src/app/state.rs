@@ -1,9 +1,13 @@
 use parking_lot::Mutex;
+use crate::platform::{Clipboard, DropdownWindow, HotkeyManager};
 use saternal_core::{
-    Clipboard, Config, Renderer, SearchState, SelectionManager, MouseState,
-    PADDING_LEFT, PADDING_TOP, PADDING_RIGHT, PADDING_BOTTOM, MIN_CELL_DIMENSION,
+    ClipboardHistory, CommandHistory, Config, HistorySearch, IpcServer, Padding, SearchState, SelectionManager, MouseState,
+    PrivacyLock, SnippetPicker,
 };
-use saternal_macos::{DropdownWindow, HotkeyManager};
+use saternal_core::update::Updater;
+#[cfg(target_os = "macos")]
+use saternal_macos::{PinStatusItem, ScreenLockMonitor};
+use saternal_renderer::Renderer;
 use std::sync::Arc;
 use winit::event_loop::EventLoop;
 
@@ -19,13 +23,30 @@ pub struct App {
     pub(super) font_size: f32,
     pub(super) selection_manager: SelectionManager,
     pub(super) clipboard: Clipboard,
+    pub(super) clipboard_history: ClipboardHistory,
     pub(super) search_state: SearchState,
+    pub(super) command_history: CommandHistory,
+    pub(super) history_search: HistorySearch,
+    pub(super) snippet_picker: SnippetPicker,
     pub(super) mouse_state: MouseState,
+    pub(super) privacy_lock: Arc<Mutex<PrivacyLock>>,
+    #[cfg(target_os = "macos")]
+    pub(super) screen_lock_monitor: Option<ScreenLockMonitor>,
+    /// Menu bar pin for the dropdown (when it hides on focus loss)
+    #[cfg(target_os = "macos")]
+    pub(super) pin_status_item: Option<PinStatusItem>,
+    /// Remote control socket (`saternal toggle`, `saternal run`, ...)
+    pub(super) ipc_server: Option<IpcServer>,
+    /// Background update checks (None without a home directory to stage them in)
+    pub(super) updater: Option<Updater>,
+    /// Rebuilds the render pipelines when a shader source is edited (development builds)
+    #[cfg(feature = "shader-hot-reload")]
+    pub(super) shader_watcher: Option<saternal_renderer::ShaderWatcher>,
 }
 
 impl App {
     /// Calculate terminal dimensions from window size
-    /// Returns (cols, rows) accounting for padding to prevent text cutoff
+    /// Returns (cols, rows) inside `padding` (the renderer's, so the grid fits what it draws)
     ///
     /// # Safety
     /// Guards against division by zero by clamping cell dimensions to minimum values.
@@ -35,6 +56,7 @@ impl App {
         window_height: u32,
         cell_width: f32,
         cell_height: f32,
+        padding: &Padding,
     ) -> (usize, usize) {
         // Guard against invalid cell dimensions
         if cell_width <= 0.0 || cell_height <= 0.0 {
@@ -46,19 +68,6 @@ impl App {
             return (1, 1);
         }
 
-        // Clamp cell dimensions to prevent division by very small values
-        let cell_width = cell_width.max(MIN_CELL_DIMENSION);
-        let cell_height = cell_height.max(MIN_CELL_DIMENSION);
-
-        // Calculate available space after padding
-        let available_width = (window_width as f32 - PADDING_LEFT - PADDING_RIGHT).max(0.0);
-        let available_height = (window_height as f32 - PADDING_TOP - PADDING_BOTTOM).max(0.0);
-
-        // Calculate terminal dimensions from available space
-        let cols = (available_width / cell_width).floor() as usize;
-        let rows = (available_height / cell_height).floor() as usize;
-
-        // Ensure minimum dimensions (just safety checks to prevent zero-size)
-        (cols.max(1), rows.max(1))
+        padding.grid_size(window_width, window_height, cell_width, cell_height)
     }
 }
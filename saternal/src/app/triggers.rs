@@ -0,0 +1,30 @@
+use saternal_core::trigger::{TriggerAction, TriggerHit};
+use std::process::{Command, Stdio};
+
+/// Carry out a notify or run trigger that matched a line of output
+pub(super) fn fire(hit: &TriggerHit) {
+    log::debug!("Trigger fired on '{}': {:?}", hit.matched, hit.action);
+    match hit.action {
+        TriggerAction::Notify => crate::platform::notify("Saternal", &hit.line),
+        TriggerAction::Run => {
+            let Some(command) = &hit.command else {
+                return;
+            };
+            let spawned = Command::new("sh")
+                .args(["-c", command])
+                .env("SATERNAL_MATCH", &hit.matched)
+                .env("SATERNAL_LINE", &hit.line)
+                .stdin(Stdio::null())
+                .spawn();
+            match spawned {
+                // Reap it off the main thread
+                Ok(mut child) => {
+                    std::thread::spawn(move || child.wait());
+                }
+                Err(e) => log::warn!("Failed to run trigger command '{}': {}", command, e),
+            }
+        }
+        // Applied to the grid while parsing
+        TriggerAction::Highlight => {}
+    }
+}
@@ -0,0 +1,16 @@
+use saternal_core::update::UpdateEvent;
+
+/// Tell the user what an update check found
+pub(super) fn show(event: &UpdateEvent) {
+    match event {
+        UpdateEvent::Ready { version } => crate::platform::notify(
+            &format!("Saternal {} is ready", version),
+            "Right-click > Restart to Update, or it installs the next time Saternal starts",
+        ),
+        UpdateEvent::UpToDate => crate::platform::notify(
+            "Saternal is up to date",
+            &format!("{} is the newest version", env!("CARGO_PKG_VERSION")),
+        ),
+        UpdateEvent::Failed(message) => crate::platform::notify("Couldn't update Saternal", message),
+    }
+}
@@ -1,10 +1,29 @@
 use alacritty_terminal::grid::Dimensions;
 use log::{debug, info};
 use parking_lot::Mutex;
-use saternal_core::Renderer;
+use saternal_core::output_parser::TERM_LOCK_TIMEOUT;
+use saternal_core::search::MAX_HISTORY_MATCHES;
+use saternal_core::{
+    ClipboardHistory, CommandHistory, Config, HistorySearch, Padding, RendererBackend, SearchState, SnippetPicker,
+};
+use crate::platform::DropdownWindow;
+use saternal_core::DropdownSurface;
+use saternal_renderer::{Renderer, WindowFrame};
 use std::sync::Arc;
 use winit::dpi::PhysicalSize;
 
+/// Corner rounding and outline for the window's `[window]` settings
+pub(super) fn window_frame(config: &saternal_core::config::WindowConfig) -> WindowFrame {
+    let (round_top, round_bottom) = config.rounded_corners();
+    WindowFrame {
+        corner_radius: config.corner_radius,
+        round_top,
+        round_bottom,
+        border_width: config.border_width,
+        border_color: config.border_color,
+    }
+}
+
 /// Handle window resize events
 pub(super) fn handle_resize(
     size: PhysicalSize<u32>,
@@ -17,44 +36,194 @@ pub(super) fn handle_resize(
     renderer.resize(size.width, size.height);
     
     let font_mgr = renderer.font_manager();
-    let effective_size = font_mgr.effective_font_size();
-    let line_metrics = font_mgr.font().horizontal_line_metrics(effective_size).unwrap();
-    let cell_width = font_mgr.font().metrics('M', effective_size).advance_width;
-    let cell_height = (line_metrics.ascent - line_metrics.descent + line_metrics.line_gap).ceil();
+    let (cell_width, cell_height, _) = font_mgr.cell_metrics();
     
     let (cols, rows) = super::App::calculate_terminal_size(
         size.width,
         size.height,
         cell_width,
-        cell_height
+        cell_height,
+        &renderer.padding(),
     );
     debug!("Resizing terminal to {}x{} ({}x{} window, {}x{} cells)",
            cols, rows, size.width, size.height, cell_width, cell_height);
-    drop(renderer);
-    
+
     if let Some(mut tab_mgr) = tab_manager.try_lock() {
         if let Some(active_tab) = tab_mgr.active_tab_mut() {
+            // Keep the top visible history line in place while the grid reflows
+            let anchor = focused_grid_size(active_tab)
+                .and_then(|(history_size, screen_lines)| renderer.scroll_anchor(history_size, screen_lines));
             if let Err(e) = active_tab.resize(cols, rows) {
                 log::error!("Failed to resize terminal: {}", e);
             }
+            if let Some((history_size, _)) = focused_grid_size(active_tab) {
+                renderer.restore_scroll_anchor(anchor, history_size);
+            }
         }
     }
+    drop(renderer);
     
     window.request_redraw();
 }
 
+/// History size and screen lines of the tab's focused pane
+fn focused_grid_size(tab: &crate::tab::Tab) -> Option<(usize, usize)> {
+    let pane = tab.pane_tree.focused_pane()?;
+    let term = pane.terminal.term();
+    let term_lock = term.try_lock_for(TERM_LOCK_TIMEOUT)?;
+    let grid = term_lock.grid();
+    Some((grid.history_size(), grid.screen_lines()))
+}
+
 /// Handle scale factor changed events
 pub(super) fn handle_scale_factor_changed(
     scale_factor: f64,
     renderer: &Arc<Mutex<Renderer>>,
     window: &winit::window::Window,
+    dropdown: &Arc<Mutex<DropdownWindow>>,
+    config: &Config,
 ) {
+    // The override wins over whatever screen the window lands on
+    let scale_factor = config.appearance.dpi_scale_override.unwrap_or(scale_factor);
     info!("Scale factor changed: {:.2}x", scale_factor);
+    let (cell_height, padding) = {
+        let mut renderer = renderer.lock();
+        if let Err(e) = renderer.handle_scale_factor_changed(scale_factor) {
+            log::error!("Failed to handle scale factor change: {}", e);
+        }
+        // Catch displays attached since the last move
+        renderer.set_display_scale_factors(display_scale_factors(window, config));
+        (renderer.font_manager().cell_metrics().1, renderer.padding())
+    };
+    snap_dropdown_height(window, cell_height, &padding, &dropdown.lock(), config);
+    window.request_redraw();
+}
+
+/// Scale factors of the connected displays, for the renderer to keep glyph
+/// atlases ready for; none with the DPI override, which applies on every display
+pub(super) fn display_scale_factors(window: &winit::window::Window, config: &Config) -> Vec<f64> {
+    if config.appearance.dpi_scale_override.is_some() {
+        return Vec::new();
+    }
+    window.available_monitors().map(|monitor| monitor.scale_factor()).collect()
+}
+
+/// Shrink the dropdown to a whole number of rows (`window.snap_to_cells`)
+///
+/// The height starts from the configured share of the screen each time, so it
+/// can grow back when the cells get smaller. Other windows are left alone.
+/// Don't hold the renderer lock: the resize is handled as it happens.
+pub(super) fn snap_dropdown_height(
+    window: &winit::window::Window,
+    cell_height: f32,
+    padding: &Padding,
+    dropdown: &DropdownWindow,
+    config: &Config,
+) {
+    if config.window.snap_to_cells {
+        fit_dropdown_height(window, cell_height, padding, dropdown, config);
+    }
+}
+
+/// Resize the dropdown to the configured share of its screen, in whole rows
+/// with `window.snap_to_cells`. Other windows are left alone
+pub(super) fn fit_dropdown_height(
+    window: &winit::window::Window,
+    cell_height: f32,
+    padding: &Padding,
+    dropdown: &DropdownWindow,
+    config: &Config,
+) {
+    let Some((screen_height, scale)) = dropdown.dropdown_screen(window) else {
+        return;
+    };
+    let target = (screen_height * config.window.height_percentage * scale).round() as u32;
+    let height = if config.window.snap_to_cells { padding.snapped_height(target, cell_height) } else { target };
+    dropdown.set_height(window, height as f64 / scale);
+}
+
+/// Resize the dropdown to its next height preset and save it (Cmd+Shift+H)
+pub(super) fn cycle_height_preset(
+    config: &mut Config,
+    renderer: &Arc<Mutex<Renderer>>,
+    window: &winit::window::Window,
+    dropdown: &Arc<Mutex<DropdownWindow>>,
+) {
+    let Some(share) = config.window.next_height_preset() else {
+        return;
+    };
+    info!("Dropdown height: {:.0}% of the screen", share * 100.0);
+    config.window.height_percentage = share;
+    if let Err(e) = config.save(None) {
+        log::warn!("Failed to save config: {}", e);
+    }
+    let (cell_height, padding) = row_metrics(renderer);
+    fit_dropdown_height(window, cell_height, &padding, &dropdown.lock(), config);
+}
+
+/// Hide the dropdown after another app took focus (`window.hide_on_focus_loss`)
+pub(super) fn handle_focus_lost(window: &winit::window::Window, dropdown: &Arc<Mutex<DropdownWindow>>) {
+    if let Err(e) = dropdown.lock().hide_on_focus_loss(window) {
+        log::error!("Failed to hide window: {}", e);
+    }
+}
+
+/// Row height and padding the dropdown height snaps to
+pub(super) fn row_metrics<R: RendererBackend>(renderer: &Arc<Mutex<R>>) -> (f32, Padding) {
+    let renderer = renderer.lock();
+    (renderer.cell_metrics().1, renderer.padding())
+}
+
+/// Size the renderer and every pane for the window as it's about to be shown
+///
+/// Window creation, the DPI override, and dropdown placement each move the
+/// size or scale, and their events arrive after the shells have started. Run
+/// once the window is final so the PTYs don't start at a stale size.
+pub(super) fn settle_size(
+    renderer: &Arc<Mutex<Renderer>>,
+    tab_manager: &Arc<Mutex<crate::tab::TabManager>>,
+    window: &winit::window::Window,
+    config: &Config,
+) {
+    let scale_factor = config.appearance.dpi_scale_override.unwrap_or(window.scale_factor());
+    resize_to(window.inner_size(), scale_factor, renderer, tab_manager);
+}
+
+/// Resize the renderer and every tab's panes to a physical size and scale factor
+pub(super) fn resize_to<R: RendererBackend>(
+    size: PhysicalSize<u32>,
+    scale_factor: f64,
+    renderer: &Arc<Mutex<R>>,
+    tab_manager: &Arc<Mutex<crate::tab::TabManager>>,
+) {
     let mut renderer = renderer.lock();
-    if let Err(e) = renderer.handle_scale_factor_changed(scale_factor) {
-        log::error!("Failed to handle scale factor change: {}", e);
+    if renderer.scale_factor() != scale_factor {
+        if let Err(e) = renderer.set_scale_factor(scale_factor) {
+            log::error!("Failed to handle scale factor change: {}", e);
+        }
     }
-    window.request_redraw();
+    renderer.resize(size.width, size.height);
+    let (cols, rows) = renderer.grid_size();
+    drop(renderer);
+
+    info!("Resizing terminals to {}x{} for window {}x{} (scale: {:.2}x)",
+          cols, rows, size.width, size.height, scale_factor);
+    if let Err(e) = tab_manager.lock().resize_all(cols, rows) {
+        log::error!("Failed to resize terminals: {}", e);
+    }
+}
+
+/// Refit the dropdown and the panes after the cell size changed (font, spacing, padding)
+pub(super) fn handle_cell_size_changed(
+    renderer: &Arc<Mutex<Renderer>>,
+    tab_manager: &Arc<Mutex<crate::tab::TabManager>>,
+    window: &winit::window::Window,
+    dropdown: &Arc<Mutex<DropdownWindow>>,
+    config: &Config,
+) {
+    let (cell_height, padding) = row_metrics(renderer);
+    snap_dropdown_height(window, cell_height, &padding, &dropdown.lock(), config);
+    handle_resize(window.inner_size(), renderer, tab_manager, window);
 }
 
 /// Handle redraw requests
@@ -62,12 +231,37 @@ pub(super) fn handle_redraw(
     renderer: &Arc<Mutex<Renderer>>,
     tab_manager: &Arc<Mutex<crate::tab::TabManager>>,
     window: &winit::window::Window,
+    search_state: &SearchState,
+    command_history: &CommandHistory,
+    history_search: &HistorySearch,
+    clipboard_history: &ClipboardHistory,
+    snippet_picker: &SnippetPicker,
+    content_hidden: bool,
 ) {
     if let (Some(mut renderer), Some(tab_mgr)) = (renderer.try_lock(), tab_manager.try_lock()) {
         if let Some(tab) = tab_mgr.active_tab() {
+            // Matches to highlight and the grid size, when the focused pane could be read
+            let mut search_highlights = None;
             let history_size = if let Some(pane) = tab.pane_tree.focused_pane() {
-                if let Some(term_lock) = pane.terminal.term().try_lock() {
-                    term_lock.grid().history_size()
+                if let Some(term_lock) = pane.terminal.term().try_lock_for(TERM_LOCK_TIMEOUT) {
+                    let grid = term_lock.grid();
+                    let (current, others) = if search_state.is_active() && search_state.pane_id() == Some(pane.id) {
+                        // Filter mode: rebuilt when new output arrives, so it is filtered too
+                        let filter = search_state.line_filter(grid);
+                        let scrollable = match &filter {
+                            Some(filter) => filter.max_offset(grid.screen_lines()),
+                            None => grid.history_size(),
+                        };
+                        let display_offset = renderer.scroll_offset().min(scrollable);
+                        let ranges = search_state.visible_match_ranges(grid, display_offset, filter.as_ref());
+                        renderer.set_line_filter(filter);
+                        ranges
+                    } else {
+                        renderer.set_line_filter(None);
+                        (None, Vec::new())
+                    };
+                    search_highlights = Some((current, others, grid.columns(), grid.screen_lines()));
+                    grid.history_size()
                 } else {
                     0
                 }
@@ -75,17 +269,128 @@ pub(super) fn handle_redraw(
                 0
             };
 
+            renderer.set_content_hidden(content_hidden);
+            renderer.set_wallpaper_override(tab.wallpaper.as_ref());
+            renderer.set_search_markers(search_marker_lines(search_state));
+            if let Some((current, others, grid_cols, grid_lines)) = search_highlights {
+                renderer.update_search_highlights(current, &others, grid_cols, grid_lines);
+            }
+
             let scroll_offset = renderer.scroll_offset();
-            if scroll_offset > 0 && history_size > 0 {
+            let status = if let Some(status) = snippet_picker.status() {
+                // The pick, or the field being filled
+                Some(status)
+            } else if let Some((index, entry)) = clipboard_history.picked() {
+                Some(format!(
+                    "Clipboard: {} ({}/{})",
+                    clipboard_preview(entry),
+                    index + 1,
+                    clipboard_history.len()
+                ))
+            } else if history_search.is_active() {
+                let pick = match history_search.selected() {
+                    Some(command) => format!(
+                        "{} ({}/{})",
+                        command,
+                        history_search.selected_index() + 1,
+                        history_search.result_count()
+                    ),
+                    None => "no matches".to_string(),
+                };
+                Some(format!("History: {} → {}", history_search.query(), pick))
+            } else if search_state.is_active() {
+                let position = match (search_state.error(), search_state.current_match_index()) {
+                    (Some(error), _) => error.to_string(),
+                    (None, Some(index)) => format!("{}/{}", index, search_state.match_count()),
+                    (None, None) => "no matches".to_string(),
+                };
+                let options = search_state.options();
+                let flags: String = [
+                    (options.regex, " .*"),
+                    (options.case_sensitive, " Aa"),
+                    (options.whole_word, " \\b"),
+                ]
+                    .iter()
+                    .filter(|(on, _)| *on)
+                    .map(|(_, flag)| *flag)
+                    .collect();
+                let mode = if search_state.is_filtering() { "Filter" } else { "Find" };
+                // The filter stopped at the match cap; say so rather than pass it off as complete
+                let partial = if search_state.is_filtering() && search_state.filter_truncated() {
+                    format!(", first {} matches only", MAX_HISTORY_MATCHES)
+                } else {
+                    String::new()
+                };
+                Some(format!("{}{}: {} ({}{})", mode, flags, search_state.pattern(), position, partial))
+            } else if let Some(notice) = tab_mgr.notice() {
+                Some(notice.to_string())
+            } else if scroll_offset > 0 && history_size > 0 {
                 let percentage = (scroll_offset * 100) / history_size.max(1);
-                window.set_title(&format!("Saternal [↑ {}%] - Press Shift+G to jump to bottom", percentage));
+                Some(format!("↑ {}% - Press Shift+G to jump to bottom", percentage))
             } else {
-                window.set_title("Saternal");
+                command_hints_status(tab)
+            };
+            // Drawn in the window (the dropdown has no title bar), and titled for the window switcher
+            match &status {
+                Some(status) => window.set_title(&format!("Saternal - {}", status)),
+                None => window.set_title("Saternal"),
             }
+            renderer.set_status_line(status);
             
+            // Only once the term lock above is released: this reads the parser, then the term
+            let suggestion = (renderer.autosuggestions() && !content_hidden)
+                .then(|| super::autosuggest::focused_suggestion(tab, command_history))
+                .flatten();
+            renderer.set_autosuggestion(suggestion);
+
             if let Err(e) = renderer.render_with_panes(&tab.pane_tree) {
                 log::error!("Render error: {}", e);
             }
+            // The surface was reconfigured but had no texture yet; draw again soon
+            if renderer.should_retry_frame() {
+                window.request_redraw();
+            }
         }
     }
 }
+
+/// Lines of every search match, used for the scrollbar markers
+pub(super) fn search_marker_lines(search_state: &SearchState) -> Vec<i32> {
+    if !search_state.is_active() {
+        return Vec::new();
+    }
+    let mut lines: Vec<i32> = search_state.history_matches().iter().map(|p| p.line.0).collect();
+    lines.dedup();
+    lines
+}
+
+/// Builtin command hints for what is typed at the focused pane's prompt
+/// Usage of the commands matching what's typed at the prompt, for the status line
+fn command_hints_status(tab: &crate::tab::Tab) -> Option<String> {
+    let pane = tab.pane_tree.focused_pane()?;
+    let term_arc = pane.terminal.term();
+    let term_lock = term_arc.try_lock_for(TERM_LOCK_TIMEOUT)?;
+    let line = super::input::cursor_line_text(term_lock.grid());
+
+    let hints = super::commands::command_hints(&line);
+    if hints.is_empty() {
+        return None;
+    }
+
+    let usage: Vec<String> = hints
+        .iter()
+        .map(|spec| format!("{} {}", spec.name, spec.args).trim_end().to_string())
+        .collect();
+    Some(usage.join("  ·  "))
+}
+
+/// One-line preview of a clipboard entry for the status line
+fn clipboard_preview(entry: &str) -> String {
+    const MAX_CHARS: usize = 60;
+    let line = entry.trim().lines().next().unwrap_or_default();
+    let mut preview: String = line.chars().take(MAX_CHARS).collect();
+    if preview.len() < entry.trim().len() {
+        preview.push('…');
+    }
+    preview
+}
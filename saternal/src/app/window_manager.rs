@@ -0,0 +1,258 @@
+use super::App;
+use anyhow::Result;
+use log::info;
+use parking_lot::Mutex;
+use saternal_core::{CloseTarget, CommandHistory, Config, MouseState, SearchState, SelectionManager};
+use saternal_renderer::Renderer;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+use winit::dpi::LogicalSize;
+use winit::event::{ElementState, KeyEvent, Modifiers};
+use winit::event_loop::EventLoopWindowTarget;
+use winit::keyboard::{KeyCode, PhysicalKey};
+use winit::window::{Window, WindowBuilder, WindowId};
+
+/// Size of a newly opened window in points
+const NEW_WINDOW_SIZE: LogicalSize<f64> = LogicalSize::new(960.0, 600.0);
+
+/// A regular terminal window opened with Cmd+N
+///
+/// Has its own renderer, tabs, and selection/search state. The dropdown
+/// behavior (hotkey toggle, slide animation, glass mode) stays with the
+/// primary window.
+pub(super) struct TerminalWindow {
+    pub(super) window: Arc<Window>,
+    pub(super) renderer: Arc<Mutex<Renderer>>,
+    pub(super) tab_manager: Arc<Mutex<crate::tab::TabManager>>,
+    pub(super) selection_manager: SelectionManager,
+    pub(super) search_state: SearchState,
+    pub(super) mouse_state: MouseState,
+}
+
+impl TerminalWindow {
+    fn new(elwt: &EventLoopWindowTarget<()>, config: &Config) -> Result<Self> {
+        let window = WindowBuilder::new()
+            .with_title("Saternal")
+            .with_transparent(true)
+            .with_inner_size(NEW_WINDOW_SIZE)
+            .build(elwt)?;
+        let window = Arc::new(window);
+
+        let appearance = &config.appearance;
+        let mut renderer = pollster::block_on(Renderer::new(
+            window.clone(),
+            &appearance.font_family,
+            appearance.font_size,
+            appearance.cursor,
+            appearance.palette,
+            appearance.wallpaper_path.as_deref(),
+            appearance.wallpaper_opacity,
+            appearance.opacity,
+            &config.renderer,
+        ))?;
+        super::init::apply_renderer_settings(&mut renderer, config)?;
+
+        #[cfg(target_os = "macos")]
+        unsafe {
+            if let Some((_, ns_view)) = saternal_macos::appkit_handles(&window) {
+                saternal_macos::configure_transparent_layer(ns_view);
+                saternal_macos::set_layer_color_space(ns_view, config.appearance.output_color_space());
+            }
+        }
+
+        let size = window.inner_size();
+        let (cell_width, cell_height, _) = renderer.font_manager().cell_metrics();
+        let (cols, rows) = App::calculate_terminal_size(size.width, size.height, cell_width, cell_height, &renderer.padding());
+        let mut tab_manager = crate::tab::TabManager::new_with_size(config.terminal.shell.clone(), cols, rows)?;
+        tab_manager.set_pane_headers(config.appearance.pane_headers)?;
+        tab_manager.set_confirm_close(config.terminal.confirm_close);
+        tab_manager.set_confirm_password_paste(config.terminal.confirm_password_paste);
+        tab_manager.set_paste_cleanup(config.clipboard.paste_transforms(), config.clipboard.confirm_cleanup);
+        tab_manager.set_local_echo(config.terminal.local_echo);
+        tab_manager.set_triggers(&config.triggers);
+        tab_manager.set_palette(config.appearance.palette);
+        tab_manager.set_auto_log(&config.logging);
+        tab_manager.set_startup_command(config.terminal.startup_command.clone());
+        if let (Some(command), Some(tab)) = (tab_manager.startup_command(), tab_manager.active_tab_mut()) {
+            tab.run_when_ready(&command);
+        }
+
+        let mut selection_manager = SelectionManager::new();
+        selection_manager.set_word_chars(&config.selection.word_chars);
+
+        Ok(Self {
+            window,
+            renderer: Arc::new(Mutex::new(renderer)),
+            tab_manager: Arc::new(Mutex::new(tab_manager)),
+            selection_manager,
+            search_state: SearchState::with_options(config.search),
+            mouse_state: MouseState::new(),
+        })
+    }
+}
+
+/// Windows opened alongside the dropdown, keyed by winit window id
+#[derive(Default)]
+pub(super) struct WindowManager {
+    windows: HashMap<WindowId, TerminalWindow>,
+}
+
+impl WindowManager {
+    /// Open a new terminal window and bring it to the front
+    pub(super) fn open(&mut self, elwt: &EventLoopWindowTarget<()>, config: &Config) -> Result<WindowId> {
+        let terminal_window = TerminalWindow::new(elwt, config)?;
+        let id = terminal_window.window.id();
+        terminal_window.window.focus_window();
+        terminal_window.window.request_redraw();
+        self.windows.insert(id, terminal_window);
+        info!("Opened window {:?} ({} extra windows)", id, self.windows.len());
+        Ok(id)
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.windows.is_empty()
+    }
+
+    pub(super) fn get_mut(&mut self, id: WindowId) -> Option<&mut TerminalWindow> {
+        self.windows.get_mut(&id)
+    }
+
+    /// Close a window, ending its shells
+    /// Windows running programs other than the shell need a second close to confirm
+    pub(super) fn request_close(&mut self, id: WindowId) {
+        let Some(terminal_window) = self.windows.get(&id) else {
+            return;
+        };
+        let confirmed = {
+            let mut tab_mgr = terminal_window.tab_manager.lock();
+            let busy = tab_mgr.busy_processes();
+            tab_mgr.confirm_close(CloseTarget::Window, &busy)
+        };
+        if confirmed {
+            self.close(id);
+        } else {
+            terminal_window.window.request_redraw();
+        }
+    }
+
+    fn close(&mut self, id: WindowId) {
+        if self.windows.remove(&id).is_some() {
+            info!("Closed window {:?}", id);
+        }
+    }
+
+    // For the screen lock and shader reloads
+    #[cfg(any(target_os = "macos", feature = "shader-hot-reload"))]
+    pub(super) fn request_redraw(&self) {
+        for terminal_window in self.windows.values() {
+            terminal_window.window.request_redraw();
+        }
+    }
+
+    /// Rebuild every window's render pipelines from the edited shaders
+    #[cfg(feature = "shader-hot-reload")]
+    pub(super) fn reload_shaders(&self) {
+        for terminal_window in self.windows.values() {
+            terminal_window.renderer.lock().reload_shaders();
+        }
+    }
+
+    /// Redraw windows whose blinking text changed phase
+    /// Returns when the next one is due, if any window shows blinking text
+    pub(super) fn redraw_blinking(&self, now: Instant) -> Option<Instant> {
+        let mut next = None;
+        for terminal_window in self.windows.values() {
+            match terminal_window.renderer.lock().next_blink() {
+                Some(at) if at <= now => terminal_window.window.request_redraw(),
+                Some(at) => next = Some(next.map_or(at, |next: Instant| next.min(at))),
+                None => {}
+            }
+        }
+        next
+    }
+
+    /// Redraw windows whose close warning expired
+    /// Returns when the next one expires, if any window shows one
+    pub(super) fn expire_notices(&self, now: Instant) -> Option<Instant> {
+        let mut next = None;
+        for terminal_window in self.windows.values() {
+            let mut tab_mgr = terminal_window.tab_manager.lock();
+            if tab_mgr.expire_notice(now) {
+                terminal_window.window.request_redraw();
+            }
+            if let Some(until) = tab_mgr.notice_until() {
+                next = Some(next.map_or(until, |next: Instant| next.min(until)));
+            }
+        }
+        next
+    }
+
+    /// Collect parsed PTY output for every window's tabs
+    /// Returns true if any window needs another frame right away (an animation)
+    pub(super) fn process_output(&mut self, command_history: &mut CommandHistory, config: &Config) -> bool {
+        let mut pending = false;
+        for terminal_window in self.windows.values_mut() {
+            let Some(mut tab_mgr) = terminal_window.tab_manager.try_lock() else {
+                continue;
+            };
+            super::event_loop::drain_background_tabs(&mut tab_mgr, &terminal_window.window, command_history, config, None);
+            let Some(active_tab) = tab_mgr.active_tab_mut() else {
+                continue;
+            };
+            match active_tab.process_output() {
+                Ok(bytes_processed) if bytes_processed > 0 => terminal_window.window.request_redraw(),
+                Ok(_) => {}
+                Err(e) => log::error!("Error processing output: {}", e),
+            }
+            if super::mouse::follow_output_scroll(active_tab, &mut terminal_window.selection_manager, &terminal_window.renderer) {
+                terminal_window.window.request_redraw();
+            }
+
+            let mut history_changed = false;
+            for command in active_tab.take_captured_commands() {
+                history_changed |= command_history.push(&command);
+            }
+            if history_changed {
+                if let Err(e) = command_history.save() {
+                    log::warn!("Failed to save command history: {}", e);
+                }
+            }
+            for pane_id in active_tab.take_bells() {
+                if config.bell.mode.is_visual() {
+                    terminal_window.renderer.lock().ring_bell(pane_id);
+                    terminal_window.window.request_redraw();
+                }
+                if config.bell.mode.is_audible() {
+                    crate::platform::play_bell();
+                }
+            }
+            for hit in active_tab.take_trigger_hits() {
+                super::triggers::fire(&hit);
+            }
+            // Extra windows aren't hidden by the hotkey; out of sight means another window has focus
+            let in_background = !terminal_window.window.has_focus();
+            for finished in active_tab.take_finished_commands() {
+                super::command_alerts::command_finished(&finished, &config.command_alerts, &terminal_window.window, in_background);
+            }
+
+            let mut renderer = terminal_window.renderer.lock();
+            if renderer.is_bell_flashing() | renderer.is_copy_flashing() | renderer.tick_scroll_momentum() {
+                terminal_window.window.request_redraw();
+                pending = true;
+            }
+        }
+        pending
+    }
+}
+
+/// Cmd+N opens a new window
+pub(super) fn is_new_window_shortcut(event: &KeyEvent, modifiers: &Modifiers) -> bool {
+    let mods = modifiers.state();
+    event.state == ElementState::Pressed
+        && mods.super_key()
+        && !mods.shift_key()
+        && !mods.control_key()
+        && !mods.alt_key()
+        && event.physical_key == PhysicalKey::Code(KeyCode::KeyN)
+}
@@ -1,23 +1,88 @@
 mod app;
+mod platform;
 mod tab;
 
 use anyhow::Result;
-use log::info;
+use log::{info, warn};
 
 fn main() -> Result<()> {
+    #[cfg(target_os = "windows")]
+    saternal_windows::set_home_from_profile();
+
     // Initialize logging
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
 
+    if std::env::args().skip(1).any(|arg| arg == "--print-default") {
+        print!("{}", saternal_core::Config::default_toml());
+        return Ok(());
+    }
+
+    // `saternal toggle`, `saternal run -- cmd`, ... drive the running instance
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(request) = saternal_core::IpcRequest::from_args(&args) {
+        let request = request.map_err(|e| anyhow::anyhow!("{}\n{}", e, saternal_core::ipc::CLI_USAGE))?;
+        match saternal_core::ipc::send(&saternal_core::ipc::socket_path(), &request)? {
+            Ok(output) => {
+                if !output.is_empty() {
+                    println!("{}", output);
+                }
+                return Ok(());
+            }
+            Err(message) => {
+                eprintln!("saternal: {}", message);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    // Hang up on the shells and leave a report if anything panics from here on
+    let crash_dir = saternal_core::crash::report_dir();
+    saternal_core::crash::install_panic_hook(crash_dir.clone());
+
+    // An update downloaded last time goes in before anything else starts
+    if let (Some(dir), Some(bundle)) = (saternal_core::update::cache_dir(), saternal_core::update::current_bundle()) {
+        match saternal_core::update::install_staged(&dir, &bundle, env!("CARGO_PKG_VERSION")) {
+            Ok(Some(version)) => {
+                info!("Installed Saternal {}, starting it", version);
+                return Err(saternal_core::update::relaunch().into());
+            }
+            Ok(None) => {}
+            Err(e) => warn!("Failed to install update: {:#}", e),
+        }
+    }
+
     info!("Starting Saternal - The blazing fast dropdown terminal");
-    info!("Press Cmd+` to toggle the terminal");
+    let modifier = if cfg!(target_os = "macos") {
+        "Cmd"
+    } else if cfg!(target_os = "windows") {
+        "Win"
+    } else {
+        "Super"
+    };
+    info!("Press {}+` to toggle the terminal", modifier);
 
     // Load configuration
     let config = saternal_core::Config::load(None)?;
+    if let Ok(path) = saternal_core::Config::default_path() {
+        info!("Config file: {}", path.display());
+    }
     info!("Loaded configuration: {:?}", config);
 
+    if let Some(report) = crash_dir.as_deref().and_then(saternal_core::crash::take_pending_report) {
+        warn!("Saternal crashed last time; report at {}", report.display());
+        if config.logging.crash_dialog {
+            platform::show_crash_report(&report);
+        }
+    }
+
     // Create and run the application using pollster to block on async initialization
     let app = pollster::block_on(app::App::new(config))?;
     app.run()?;
 
+    // "Restart to Update": the fresh start installs the staged bundle
+    if saternal_core::update::relaunch_requested() {
+        return Err(saternal_core::update::relaunch().into());
+    }
+
     Ok(())
 }
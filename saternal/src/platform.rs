@@ -0,0 +1,17 @@
+//! The desktop integration this build runs on
+//!
+//! The platform crates implement the `saternal_core::platform` traits with the same
+//! type names, so the app only names them through here.
+
+#[cfg(target_os = "macos")]
+pub use saternal_core::Clipboard;
+#[cfg(target_os = "macos")]
+pub use saternal_macos::{notify, play_bell, show_crash_report, DropdownWindow, HotkeyManager};
+
+#[cfg(target_os = "linux")]
+pub use saternal_linux::{notify, play_bell, show_crash_report, Clipboard, DropdownWindow, HotkeyManager};
+
+#[cfg(target_os = "windows")]
+pub use saternal_core::Clipboard;
+#[cfg(target_os = "windows")]
+pub use saternal_windows::{notify, play_bell, show_crash_report, DropdownWindow, HotkeyManager};
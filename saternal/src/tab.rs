@@ -1,13 +1,35 @@
 use anyhow::Result;
 use log::info;
-use saternal_core::{PaneNode, SplitDirection};
+use saternal_core::startup::DEFAULT_STARTUP_TIMEOUT;
+use saternal_core::theme::ColorPalette;
+use saternal_core::wallpaper::WallpaperOverride;
+use saternal_core::close_guard::{close_warning, CLOSE_CONFIRM_WINDOW};
+use saternal_core::config::LoggingConfig;
+use saternal_core::pane_log::{self, LogFormat};
+use saternal_core::ipc::{PaneInfo, TabInfo};
+use saternal_core::trigger::{TriggerConfig, TriggerHit, TriggerSet};
+use saternal_core::secure_input::{is_multiline, paste_warning};
+use saternal_core::clipboard::paste::{clean_up, cleanup_offer};
+use saternal_core::shell_integration::FinishedCommand;
+use saternal_core::{CloseGuard, CloseTarget, PasteGuard, FeedbackKind, IpcEvent, PasteTransform, LayoutNode, Pane, PaneNode, ProcessInfo, SplitDirection};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
 
 /// Represents a single tab containing a pane tree
 pub struct Tab {
     pub id: usize,
     pub title: String,
     pub pane_tree: PaneNode,
+    /// Shown instead of the global wallpaper while this tab is active
+    pub wallpaper: Option<WallpaperOverride>,
     next_pane_id: usize,
+    /// Output triggers given to every pane, including later splits
+    triggers: Option<Arc<TriggerSet>>,
+    /// Logging for panes without a log of their own, including later splits
+    auto_log: Option<LoggingConfig>,
+    /// Theme colors every pane starts from, including later splits
+    palette: ColorPalette,
 }
 
 impl Tab {
@@ -23,18 +45,58 @@ impl Tab {
             id,
             title: format!("Tab {}", id + 1),
             pane_tree,
+            wallpaper: None,
             next_pane_id: 1,
+            triggers: None,
+            auto_log: None,
+            palette: ColorPalette::default(),
+        })
+    }
+
+    /// Create a tab whose panes follow a configured layout
+    pub fn from_layout(id: usize, name: &str, layout: &LayoutNode, cols: usize, rows: usize, shell: Option<String>) -> Result<Self> {
+        let mut next_pane_id = 0;
+        let mut pane_tree = PaneNode::from_plan(&layout.plan(), cols, rows, shell, &mut next_pane_id)?;
+        pane_tree.set_focus(0);
+
+        Ok(Self {
+            id,
+            title: name.to_string(),
+            pane_tree,
+            wallpaper: layout.wallpaper.clone().map(|path| WallpaperOverride {
+                path,
+                opacity: layout.wallpaper_opacity,
+            }),
+            next_pane_id,
+            triggers: None,
+            auto_log: None,
+            palette: ColorPalette::default(),
         })
     }
 
     /// Split the focused pane
+    /// The new pane takes focus, unless focus is locked to the one split
     pub fn split(&mut self, direction: SplitDirection, shell: Option<String>) -> Result<()> {
         let pane_id = self.next_pane_id;
         self.next_pane_id += 1;
 
+        let locked = self.pane_tree.focused_pane().filter(|pane| pane.focus_locked).map(|pane| pane.id);
         if !self.pane_tree.split_focused(direction, pane_id, shell)? {
             log::warn!("No focused pane found to split");
         }
+        if let Some(locked) = locked {
+            self.pane_tree.set_focus(locked);
+        }
+        let triggers = self.triggers.clone();
+        let auto_log = self.auto_log.clone();
+        let palette = self.palette;
+        if let Some(pane) = self.pane_mut(Some(pane_id)) {
+            pane.terminal.set_triggers(triggers);
+            pane.terminal.set_palette(palette);
+            if let Some(logging) = &auto_log {
+                start_auto_log(pane, logging);
+            }
+        }
 
         Ok(())
     }
@@ -57,6 +119,71 @@ impl Tab {
         Ok(())
     }
 
+    /// Swap the focused pane with its sibling
+    pub fn swap_focused_pane(&mut self) -> Result<()> {
+        self.reshape(PaneNode::swap_focused_with_sibling)
+    }
+
+    /// Rotate the split containing the focused pane
+    pub fn rotate_focused_split(&mut self) -> Result<()> {
+        self.reshape(PaneNode::rotate_focused_split)
+    }
+
+    /// Reset all splits to equal ratios
+    pub fn balance_panes(&mut self) -> Result<()> {
+        self.reshape(|tree| {
+            tree.balance();
+            true
+        })
+    }
+
+    /// Reserve a header row above each pane (or give it back to the terminals)
+    pub fn set_pane_headers(&mut self, enabled: bool) -> Result<()> {
+        self.reshape(|tree| {
+            tree.set_header_rows(enabled as usize);
+            true
+        })
+    }
+
+    /// Run these triggers on the output of every pane
+    pub fn set_triggers(&mut self, triggers: Option<Arc<TriggerSet>>) {
+        for (_, pane) in self.pane_tree.all_panes_mut() {
+            pane.terminal.set_triggers(triggers.clone());
+        }
+        self.triggers = triggers;
+    }
+
+    /// Set the theme colors of every pane (colors a program changed stay on top)
+    pub fn set_palette(&mut self, palette: ColorPalette) {
+        for (_, pane) in self.pane_tree.all_panes_mut() {
+            pane.terminal.set_palette(palette);
+        }
+        self.palette = palette;
+    }
+
+    /// Log every pane that isn't logged yet to a new file, now and after splits (None: stop doing so)
+    pub fn set_auto_log(&mut self, logging: Option<LoggingConfig>) {
+        if let Some(logging) = &logging {
+            for (_, pane) in self.pane_tree.all_panes_mut() {
+                if pane.terminal.log_path().is_none() {
+                    start_auto_log(pane, logging);
+                }
+            }
+        }
+        self.auto_log = logging;
+    }
+
+    /// Change the tree's shape, then fit the panes back into the space they had
+    /// `change` returns whether it changed anything
+    fn reshape(&mut self, change: impl FnOnce(&mut PaneNode) -> bool) -> Result<()> {
+        // Measured first: a rotated split would add its panes up along the other axis
+        let (cols, rows) = self.pane_tree.dimensions();
+        if change(&mut self.pane_tree) {
+            self.pane_tree.resize(cols, rows)?;
+        }
+        Ok(())
+    }
+
     /// Write input to the focused pane
     pub fn write_input(&mut self, data: &[u8]) -> Result<()> {
         if let Some(pane) = self.pane_tree.focused_pane_mut() {
@@ -65,6 +192,159 @@ impl Tab {
         Ok(())
     }
 
+    /// Write typed text to the focused pane, predicting its echo when `predict` is set
+    pub fn write_typed(&mut self, text: &str, predict: bool) -> Result<()> {
+        if let Some(pane) = self.pane_tree.focused_pane_mut() {
+            pane.terminal.write_typed(text, predict)?;
+        }
+        Ok(())
+    }
+
+    /// The focused pane shows typed characters still waiting for their echo
+    pub fn has_predictions(&self) -> bool {
+        self.pane_tree.focused_pane().is_some_and(|pane| !pane.terminal.predictions().is_empty())
+    }
+
+    /// The pane with this id, or the focused pane when None
+    pub fn pane_mut(&mut self, id: Option<usize>) -> Option<&mut Pane> {
+        match id {
+            Some(id) => self
+                .pane_tree
+                .all_panes_mut()
+                .into_iter()
+                .find(|(pane_id, _)| *pane_id == id)
+                .map(|(_, pane)| pane),
+            None => self.pane_tree.focused_pane_mut(),
+        }
+    }
+
+    /// Description for remote-control clients
+    pub fn info(&self, active: bool) -> TabInfo {
+        let panes = self
+            .pane_tree
+            .all_panes()
+            .into_iter()
+            .map(|(id, pane)| {
+                let (cols, rows) = pane.terminal.dimensions();
+                let process = pane.terminal.foreground_process();
+                PaneInfo {
+                    id,
+                    focused: pane.focused,
+                    cols,
+                    rows,
+                    title: pane.terminal.title(),
+                    process: process.as_ref().map(|process| process.name.clone()),
+                    cwd: process.and_then(|process| process.cwd),
+                }
+            })
+            .collect();
+        TabInfo { id: self.id, title: self.title.clone(), active, panes }
+    }
+
+    /// Keep a copy of every pane's output for take_ipc_events()
+    pub fn set_output_tap(&mut self, enabled: bool) {
+        for (_, pane) in self.pane_tree.all_panes_mut() {
+            pane.terminal.set_output_tap(enabled);
+        }
+    }
+
+    /// Output and title changes since the last call, for remote-control subscribers
+    pub fn take_ipc_events(&mut self) -> Vec<IpcEvent> {
+        let tab = self.id;
+        let mut events = Vec::new();
+        for (pane_id, pane) in self.pane_tree.all_panes_mut() {
+            let data = pane.terminal.take_tapped_output();
+            if !data.is_empty() {
+                events.push(IpcEvent::Output { tab, pane: pane_id, data });
+            }
+            if pane.terminal.take_title_change() {
+                events.push(IpcEvent::Title { tab, pane: pane_id, title: pane.terminal.title() });
+            }
+        }
+        events
+    }
+
+    /// Send a command to the focused pane once its shell shows a prompt
+    pub fn run_when_ready(&mut self, command: &str) {
+        if let Some(pane) = self.pane_tree.focused_pane_mut() {
+            pane.terminal.run_when_ready(command, DEFAULT_STARTUP_TIMEOUT);
+        }
+    }
+
+    /// Drop the focused pane's scrollback history
+    pub fn clear_scrollback(&mut self) {
+        if let Some(pane) = self.pane_tree.focused_pane_mut() {
+            pane.terminal.clear_scrollback();
+        }
+    }
+
+    /// Set the focused pane's badge (None: back to the configured default)
+    pub fn set_focused_badge(&mut self, badge: Option<String>) {
+        if let Some(pane) = self.pane_tree.focused_pane_mut() {
+            pane.terminal.set_badge(badge);
+        }
+    }
+
+    /// Append the focused pane's output to `path`
+    pub fn start_focused_log(&mut self, path: &Path, format: LogFormat) -> Result<()> {
+        match self.pane_tree.focused_pane_mut() {
+            Some(pane) => pane.terminal.start_log(path, format),
+            None => Ok(()),
+        }
+    }
+
+    /// Stop logging the focused pane; returns the file it was logged to
+    pub fn stop_focused_log(&mut self) -> Option<PathBuf> {
+        self.pane_tree.focused_pane_mut()?.terminal.stop_log()
+    }
+
+    /// Lock input to the focused pane, or unlock it; returns whether it's locked now
+    pub fn toggle_focus_lock(&mut self) -> bool {
+        match self.pane_tree.focused_pane_mut() {
+            Some(pane) => {
+                pane.focus_locked = !pane.focus_locked;
+                pane.focus_locked
+            }
+            None => false,
+        }
+    }
+
+    /// Zoom the focused pane's text by `step`, or back to the window's font size with None
+    pub fn zoom_focused_pane(&mut self, step: Option<f32>) -> Result<()> {
+        if let Some(pane) = self.pane_tree.focused_pane_mut() {
+            let font_scale = pane.set_font_scale(step.map_or(1.0, |step| pane.font_scale() + step))?;
+            info!("Pane {} zoomed to {:.0}%", pane.id, font_scale * 100.0);
+        }
+        Ok(())
+    }
+
+    /// Restart the focused pane's shell in place, keeping its scrollback
+    pub fn respawn_focused_pane(&mut self) -> Result<()> {
+        match self.pane_tree.focused_pane_mut() {
+            Some(pane) => pane.terminal.respawn(),
+            None => Ok(()),
+        }
+    }
+
+    /// Print styled feedback into the focused pane (never sent to the shell)
+    pub fn print_feedback(&mut self, kind: FeedbackKind, text: &str) {
+        if let Some(pane) = self.pane_tree.focused_pane_mut() {
+            pane.terminal.print_feedback(kind, text);
+        }
+    }
+
+    /// Show a warning line in the focused pane (never sent to the shell)
+    pub fn show_warning(&mut self, message: &str) {
+        if let Some(pane) = self.pane_tree.focused_pane_mut() {
+            pane.terminal.show_warning(message);
+        }
+    }
+
+    /// The focused pane's program is reading a password
+    pub fn at_password_prompt(&self) -> bool {
+        self.pane_tree.focused_pane().is_some_and(|pane| pane.terminal.at_password_prompt())
+    }
+
     /// Process output from all panes
     /// Returns the total number of bytes processed across all panes
     pub fn process_output(&mut self) -> Result<usize> {
@@ -84,18 +364,104 @@ impl Tab {
         Ok(total_bytes)
     }
 
+    /// Commands captured via shell integration in any pane since the last call
+    pub fn take_captured_commands(&mut self) -> Vec<String> {
+        self.pane_tree
+            .all_panes_mut()
+            .into_iter()
+            .flat_map(|(_, pane)| pane.terminal.take_captured_commands())
+            .collect()
+    }
+
+    /// Commands that finished running in any pane since the last call
+    pub fn take_finished_commands(&mut self) -> Vec<FinishedCommand> {
+        self.pane_tree
+            .all_panes_mut()
+            .into_iter()
+            .flat_map(|(_, pane)| pane.terminal.take_finished_commands())
+            .collect()
+    }
+
+    /// Notify and run triggers that fired in any pane since the last call
+    pub fn take_trigger_hits(&mut self) -> Vec<TriggerHit> {
+        self.pane_tree
+            .all_panes_mut()
+            .into_iter()
+            .flat_map(|(_, pane)| pane.terminal.take_trigger_hits())
+            .collect()
+    }
+
+    /// Ids of panes that received BEL since the last call
+    pub fn take_bells(&self) -> Vec<usize> {
+        self.pane_tree
+            .all_panes()
+            .into_iter()
+            .filter(|(_, pane)| pane.terminal.take_bell())
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    /// Programs other than the shell running in this tab's panes
+    pub fn busy_processes(&self) -> Vec<ProcessInfo> {
+        self.pane_tree
+            .all_panes()
+            .iter()
+            .filter_map(|(_, pane)| pane.terminal.busy_process())
+            .collect()
+    }
+
+    /// Check if any pane is still waiting to send its startup command
+    pub fn has_pending_startup(&self) -> bool {
+        self.pane_tree
+            .all_panes()
+            .iter()
+            .any(|(_, pane)| pane.terminal.has_pending_startup())
+    }
+
     /// Resize the tab to fit new dimensions
     pub fn resize(&mut self, width: usize, height: usize) -> Result<()> {
         self.pane_tree.resize(width, height)
     }
 }
 
+/// Start logging a pane to a new file in the configured directory
+fn start_auto_log(pane: &mut Pane, logging: &LoggingConfig) {
+    let path = pane_log::auto_log_path(&logging.directory);
+    if let Err(e) = pane.terminal.start_log(&path, logging.format) {
+        log::warn!("Failed to log pane to {}: {}", path.display(), e);
+    }
+}
+
 /// Manages multiple tabs
 pub struct TabManager {
     tabs: Vec<Tab>,
     active_tab: usize,
     next_tab_id: usize,
     shell: String,
+    /// Sent to every new tab once its shell is ready
+    startup_command: Option<String>,
+    /// Panes leave room for a header strip
+    pane_headers: bool,
+    /// Warn before closing panes/windows that run something other than the shell
+    confirm_close: bool,
+    close_guard: CloseGuard,
+    /// Close warning for the status line, and when it goes away
+    notice: Option<(String, Instant)>,
+    /// Hold back multi-line pastes into password prompts until repeated
+    confirm_password_paste: bool,
+    paste_guard: PasteGuard,
+    /// Rewrites offered for pasted shell examples
+    paste_transforms: Vec<PasteTransform>,
+    /// Hold back a paste that could be cleaned up until repeated
+    confirm_paste_cleanup: bool,
+    cleanup_guard: PasteGuard,
+    /// Draw typed text ahead of its echo
+    local_echo: bool,
+    /// Compiled output triggers (None when none are configured)
+    triggers: Option<Arc<TriggerSet>>,
+    /// Log new panes automatically (None when auto-logging is off)
+    auto_log: Option<LoggingConfig>,
+    palette: ColorPalette,
 }
 
 impl TabManager {
@@ -115,6 +481,20 @@ impl TabManager {
             active_tab: 0,
             next_tab_id: 1,
             shell,
+            startup_command: None,
+            pane_headers: false,
+            confirm_close: true,
+            close_guard: CloseGuard::default(),
+            notice: None,
+            confirm_password_paste: true,
+            paste_guard: PasteGuard::default(),
+            paste_transforms: vec![PasteTransform::StripPrompts, PasteTransform::JoinContinuations],
+            confirm_paste_cleanup: true,
+            cleanup_guard: PasteGuard::default(),
+            local_echo: false,
+            triggers: None,
+            auto_log: None,
+            palette: ColorPalette::default(),
         })
     }
 
@@ -125,6 +505,13 @@ impl TabManager {
 
         let mut tab = Tab::new(id, Some(self.shell.clone()))?;
         tab.pane_tree.set_focus(0);
+        tab.set_pane_headers(self.pane_headers)?;
+        tab.set_triggers(self.triggers.clone());
+        tab.set_auto_log(self.auto_log.clone());
+        tab.set_palette(self.palette);
+        if let Some(command) = &self.startup_command {
+            tab.run_when_ready(command);
+        }
 
         self.tabs.push(tab);
         self.active_tab = self.tabs.len() - 1;
@@ -132,6 +519,189 @@ impl TabManager {
         Ok(id)
     }
 
+    /// Set the command sent to new tabs (None = plain shell)
+    pub fn set_startup_command(&mut self, command: Option<String>) {
+        self.startup_command = command.filter(|c| !c.trim().is_empty());
+    }
+
+    /// Show or hide pane headers in every tab, now and for new tabs
+    pub fn set_pane_headers(&mut self, enabled: bool) -> Result<()> {
+        self.pane_headers = enabled;
+        for tab in &mut self.tabs {
+            tab.set_pane_headers(enabled)?;
+        }
+        Ok(())
+    }
+
+    /// Compile output triggers and apply them to every tab, now and for new tabs
+    pub fn set_triggers(&mut self, configs: &[TriggerConfig]) {
+        let triggers = TriggerSet::new(configs);
+        self.triggers = (!triggers.is_empty()).then(|| Arc::new(triggers));
+        for tab in &mut self.tabs {
+            tab.set_triggers(self.triggers.clone());
+        }
+    }
+
+    /// Set the theme colors of every pane, now and in new tabs
+    pub fn set_palette(&mut self, palette: ColorPalette) {
+        self.palette = palette;
+        for tab in &mut self.tabs {
+            tab.set_palette(palette);
+        }
+    }
+
+    /// Apply `[logging]`: with `auto` on, every pane not already logged gets its own log file
+    pub fn set_auto_log(&mut self, logging: &LoggingConfig) {
+        self.auto_log = logging.auto.then(|| logging.clone());
+        for tab in &mut self.tabs {
+            tab.set_auto_log(self.auto_log.clone());
+        }
+    }
+
+    /// Turn confirm-on-close on or off
+    pub fn set_confirm_close(&mut self, enabled: bool) {
+        self.confirm_close = enabled;
+    }
+
+    /// Programs other than the shell running in any tab
+    pub fn busy_processes(&self) -> Vec<ProcessInfo> {
+        self.tabs.iter().flat_map(Tab::busy_processes).collect()
+    }
+
+    /// Whether a close may go ahead given the programs it would kill
+    /// The first close of something busy shows a warning on the status line and returns false;
+    /// repeating it shortly after confirms
+    pub fn confirm_close(&mut self, target: CloseTarget, busy: &[ProcessInfo]) -> bool {
+        if !self.confirm_close || self.close_guard.check(target, !busy.is_empty(), Instant::now()) {
+            return true;
+        }
+        info!("Close of {:?} needs confirmation ({} running)", target, busy.len());
+        // Not printed in the pane: a full-screen program there would draw over it
+        self.notice = Some((close_warning(target, busy), Instant::now() + CLOSE_CONFIRM_WINDOW));
+        false
+    }
+
+    /// Warning to show on the status line, until the close it asks to repeat expires
+    pub fn notice(&self) -> Option<&str> {
+        self.notice
+            .as_ref()
+            .filter(|(_, until)| Instant::now() < *until)
+            .map(|(notice, _)| notice.as_str())
+    }
+
+    /// When the notice goes away (None: no notice up)
+    pub fn notice_until(&self) -> Option<Instant> {
+        self.notice.as_ref().map(|(_, until)| *until)
+    }
+
+    /// Drop the notice once it has expired; true when one went away (redraw to clear it)
+    pub fn expire_notice(&mut self, now: Instant) -> bool {
+        let expired = self.notice_until().is_some_and(|until| until <= now);
+        if expired {
+            self.notice = None;
+        }
+        expired
+    }
+
+    /// Turn confirmation of multi-line pastes into password prompts on or off
+    pub fn set_confirm_password_paste(&mut self, enabled: bool) {
+        self.confirm_password_paste = enabled;
+    }
+
+    /// Set the paste cleanup transforms and whether a cleanup needs a second paste
+    pub fn set_paste_cleanup(&mut self, transforms: Vec<PasteTransform>, confirm: bool) {
+        self.paste_transforms = transforms;
+        self.confirm_paste_cleanup = confirm;
+    }
+
+    /// Turn predictive local echo of typed text on or off
+    pub fn set_local_echo(&mut self, enabled: bool) {
+        self.local_echo = enabled;
+    }
+
+    /// Send typed text to the active tab's focused pane
+    pub fn type_text(&mut self, text: &str) -> Result<()> {
+        let predict = self.local_echo;
+        match self.active_tab_mut() {
+            Some(tab) => tab.write_typed(text, predict),
+            None => Ok(()),
+        }
+    }
+
+    /// Text to paste for `text` copied from the clipboard, or None to hold it back
+    /// Text that looks like a shell example prints an offer to clean it up the first
+    /// time; pasting again shortly after sends the cleaned-up text
+    pub fn clean_up_paste(&mut self, text: &str) -> Option<String> {
+        let Some(cleanup) = clean_up(text, &self.paste_transforms) else {
+            return Some(text.to_string());
+        };
+        if !self.confirm_paste_cleanup || self.cleanup_guard.check(true, Instant::now()) {
+            info!("Cleaned up paste ({} prompts, {} continued lines)", cleanup.prompts, cleanup.joined);
+            return Some(cleanup.text);
+        }
+        if let Some(tab) = self.active_tab_mut() {
+            tab.show_warning(&cleanup_offer(&cleanup));
+        }
+        None
+    }
+
+    /// Whether `text` may be pasted into the focused pane
+    /// A multi-line paste into a password prompt prints a warning and returns false the
+    /// first time; pasting again shortly after confirms
+    pub fn confirm_paste(&mut self, text: &str) -> bool {
+        if !self.confirm_password_paste || !is_multiline(text) {
+            return true;
+        }
+        let Some(tab) = self.active_tab_mut() else {
+            return true;
+        };
+        let risky = tab.at_password_prompt();
+        if self.paste_guard.check(risky, Instant::now()) {
+            return true;
+        }
+        info!("Multi-line paste into a password prompt needs confirmation");
+        if let Some(tab) = self.active_tab_mut() {
+            tab.show_warning(&paste_warning(text));
+        }
+        false
+    }
+
+    /// Command sent to new tabs, if any
+    pub fn startup_command(&self) -> Option<String> {
+        self.startup_command.clone()
+    }
+
+    /// Open a configured layout in a new tab, sized like the active tab
+    pub fn new_tab_from_layout(&mut self, name: &str, layout: &LayoutNode) -> Result<usize> {
+        let (cols, rows) = self
+            .active_tab()
+            .map(|tab| tab.pane_tree.dimensions())
+            .unwrap_or((80, 24));
+
+        let id = self.next_tab_id;
+        self.next_tab_id += 1;
+
+        let mut tab = Tab::from_layout(id, name, layout, cols, rows, Some(self.shell.clone()))?;
+        tab.set_pane_headers(self.pane_headers)?;
+        tab.set_triggers(self.triggers.clone());
+        tab.set_auto_log(self.auto_log.clone());
+        tab.set_palette(self.palette);
+        info!("Opened layout '{}' in tab {}", name, id);
+
+        self.tabs.push(tab);
+        self.active_tab = self.tabs.len() - 1;
+
+        Ok(id)
+    }
+
+    /// Resize every tab, not just the active one
+    pub fn resize_all(&mut self, cols: usize, rows: usize) -> Result<()> {
+        for tab in &mut self.tabs {
+            tab.resize(cols, rows)?;
+        }
+        Ok(())
+    }
+
     /// Close a tab
     pub fn close_tab(&mut self, id: usize) {
         if self.tabs.len() > 1 {
@@ -149,6 +719,23 @@ impl TabManager {
         }
     }
 
+    /// Tab with this id, or the active tab when None
+    pub fn tab_mut(&mut self, id: Option<usize>) -> Option<&mut Tab> {
+        match id {
+            Some(id) => self.tabs.iter_mut().find(|tab| tab.id == id),
+            None => self.active_tab_mut(),
+        }
+    }
+
+    /// Tabs and their panes for remote-control clients
+    pub fn tab_infos(&self) -> Vec<TabInfo> {
+        self.tabs
+            .iter()
+            .enumerate()
+            .map(|(index, tab)| tab.info(index == self.active_tab))
+            .collect()
+    }
+
     /// Get the active tab
     pub fn active_tab(&self) -> Option<&Tab> {
         self.tabs.get(self.active_tab)
@@ -159,6 +746,16 @@ impl TabManager {
         self.tabs.get_mut(self.active_tab)
     }
 
+    /// Tabs other than the active one
+    pub fn background_tabs_mut(&mut self) -> impl Iterator<Item = &mut Tab> {
+        let active = self.active_tab;
+        self.tabs
+            .iter_mut()
+            .enumerate()
+            .filter(move |(index, _)| *index != active)
+            .map(|(_, tab)| tab)
+    }
+
     /// Get all tabs
     pub fn tabs(&self) -> &[Tab] {
         &self.tabs
@@ -169,3 +766,45 @@ impl TabManager {
         self.tabs.len()
     }
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn pane_sizes(tab: &Tab) -> Vec<(usize, usize)> {
+        tab.pane_tree.all_panes().iter().map(|(_, pane)| pane.dimensions()).collect()
+    }
+
+    #[test]
+    fn test_reshaping_keeps_the_tab_size() {
+        let mut tab = Tab::new_with_size(0, 80, 24, Some("/bin/sh".to_string())).unwrap();
+        tab.pane_tree.set_focus(0);
+        tab.split(SplitDirection::Vertical, Some("/bin/sh".to_string())).unwrap();
+        assert_eq!(pane_sizes(&tab), vec![(40, 24), (40, 24)]);
+
+        tab.rotate_focused_split().unwrap();
+        assert_eq!(tab.pane_tree.dimensions(), (80, 24));
+        assert_eq!(pane_sizes(&tab), vec![(80, 12), (80, 12)]);
+
+        tab.swap_focused_pane().unwrap();
+        tab.balance_panes().unwrap();
+        assert_eq!(pane_sizes(&tab), vec![(80, 12), (80, 12)]);
+
+        tab.set_pane_headers(true).unwrap();
+        assert_eq!(tab.pane_tree.dimensions(), (80, 24));
+    }
+
+    #[test]
+    fn test_close_warning_goes_to_the_status_line() {
+        let mut tab_mgr = TabManager::new_with_size("/bin/sh".to_string(), 80, 24).unwrap();
+        let vim = ProcessInfo { pid: 42, name: "vim".to_string(), cwd: None, remote_host: None };
+        assert!(!tab_mgr.confirm_close(CloseTarget::Window, &[vim]));
+        assert!(tab_mgr.notice().is_some_and(|notice| notice.contains("vim (pid 42)")));
+
+        let until = tab_mgr.notice_until().unwrap();
+        assert!(!tab_mgr.expire_notice(until - CLOSE_CONFIRM_WINDOW));
+        assert!(tab_mgr.expire_notice(until));
+        assert_eq!(tab_mgr.notice(), None);
+        assert!(tab_mgr.confirm_close(CloseTarget::Window, &[]));
+    }
+}